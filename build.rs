@@ -0,0 +1,38 @@
+//! ビルドスクリプト
+//! `ffi` フィーチャーが有効な場合、cbindgen で C ヘッダー（include/reversi.h）を生成する。
+//! `grpc` フィーチャーが有効な場合、proto/reversi.proto からgRPCのサーバーコードを生成する。
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "ffi")]
+    generate_ffi_header();
+
+    #[cfg(feature = "grpc")]
+    generate_grpc_service();
+}
+
+#[cfg(feature = "grpc")]
+fn generate_grpc_service() {
+    println!("cargo:rerun-if-changed=proto/reversi.proto");
+
+    let descriptors = protox::compile(["reversi.proto"], ["proto"]).expect("proto/reversi.proto のコンパイルに失敗");
+    tonic_build::configure()
+        .compile_fds(descriptors)
+        .expect("gRPCのコード生成に失敗");
+}
+
+#[cfg(feature = "ffi")]
+fn generate_ffi_header() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is set by cargo");
+    let config = cbindgen::Config::from_root_or_default(&crate_dir);
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("C ヘッダーの生成に失敗")
+        .write_to_file(std::path::Path::new(&crate_dir).join("include/reversi.h"));
+}
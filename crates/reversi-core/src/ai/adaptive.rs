@@ -0,0 +1,87 @@
+//! 適応難易度
+//! AIの手番ごとにAIから見た局面の評価を調べ、リードしすぎていればレベルを下げ、離されていればレベルを上げる。
+//! レベルは探索深度と評価値の揺らぎに対応するため、強さを1段階ずつ調整して接戦を保つ。
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::evaluation::{BoardEvaluator, EvalWeights};
+use super::levels::AiLevel;
+use crate::game::{Board, GameVariant, Player};
+
+/// AIから見た評価値がこれを超えればレベルを下げ、符号を反転した値を下回ればレベルを上げる（静的評価の単位）
+pub const ADAPTIVE_MARGIN: f32 = 10.0;
+
+/// 適応難易度の状態
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AdaptiveState {
+    /// 現在のAIのレベル
+    #[schema(value_type = u8)]
+    pub level: AiLevel,
+    /// 最後に調整したときのAIから見た石差
+    pub disc_margin: i32,
+    /// 最後に調整したときのAIから見た評価値
+    pub evaluation: f32,
+    /// レベルを上げた回数
+    pub raised: u32,
+    /// レベルを下げた回数
+    pub lowered: u32,
+}
+
+impl AdaptiveState {
+    pub fn new(level: AiLevel) -> Self {
+        Self { level, disc_margin: 0, evaluation: 0.0, raised: 0, lowered: 0 }
+    }
+
+    /// AIの手番の局面を評価してレベルを1段階調整する（レベルが変わった場合はtrue）
+    pub fn adjust(&mut self, board: &Board, ai: Player, variant: GameVariant) -> bool {
+        self.evaluation = BoardEvaluator::evaluate_for_variant(board, ai, &EvalWeights::default(), variant);
+        self.disc_margin = BoardEvaluator::evaluate_piece_count(board, ai) as i32 * variant.score_sign();
+
+        let level = self.level.value();
+        let next = if self.evaluation > ADAPTIVE_MARGIN {
+            AiLevel::new(level - 1)
+        } else if self.evaluation < -ADAPTIVE_MARGIN {
+            AiLevel::new(level + 1)
+        } else {
+            None
+        };
+        let Some(next) = next else {
+            return false;
+        };
+        if next < self.level {
+            self.lowered += 1;
+        } else {
+            self.raised += 1;
+        }
+        self.level = next;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Cell, Position};
+
+    #[test]
+    fn test_adjusts_level_toward_a_close_game() {
+        let mut state = AdaptiveState::new(AiLevel::new(5).unwrap());
+        assert!(!state.adjust(&Board::new(), Player::White, GameVariant::Standard));
+        assert_eq!(state.level.value(), 5);
+
+        // 白が隅を2つ持つ局面では白のAIは手を緩め、黒のAIは強くなる
+        let mut board = Board::new();
+        for (row, col) in [(0, 0), (7, 7)] {
+            board.set_cell(Position::new(row, col).unwrap(), Cell::White);
+        }
+        assert!(state.adjust(&board, Player::White, GameVariant::Standard));
+        assert_eq!((state.level.value(), state.lowered, state.disc_margin), (4, 1, 2));
+        assert!(state.adjust(&board, Player::Black, GameVariant::Standard));
+        assert_eq!((state.level.value(), state.raised), (5, 1));
+
+        let mut weakest = AdaptiveState::new(AiLevel::new(AiLevel::MIN).unwrap());
+        assert!(!weakest.adjust(&board, Player::White, GameVariant::Standard));
+        assert_eq!(weakest.level.value(), AiLevel::MIN);
+    }
+}
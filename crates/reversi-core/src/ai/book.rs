@@ -0,0 +1,152 @@
+//! 定石ブック
+//! 棋譜データベースの序盤の局面ごとに、指された手の回数と終局時の石差を集計する。
+//! 局面は正規化した局面ハッシュで引くため、盤の向きが違う同じ局面の棋譜もまとめて集計される。
+//! 8x8の盤で石の多い方が勝つ通常の対局のみを対象にする。
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::game::{Board, GameState, Player, Position, PositionHash, ReversiRules};
+use crate::wthor::{self, WthorError, WthorGame};
+
+/// ブックに登録する序盤の手数
+pub const BOOK_MAX_PLIES: usize = 20;
+
+/// ブックの手として選ぶのに必要な対局数
+pub const MIN_BOOK_GAMES: u32 = 2;
+
+/// ある局面から指された手の集計
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookMove {
+    /// 正規化後の盤面での位置
+    pub position: Position,
+    pub games: u32,
+    /// 手番側から見た終局時の石差の合計
+    pub total_margin: i64,
+}
+
+impl BookMove {
+    pub fn average_margin(&self) -> f64 {
+        self.total_margin as f64 / self.games as f64
+    }
+}
+
+/// 序盤の局面から、棋譜で指された手を引く定石ブック
+#[derive(Debug, Clone, Default)]
+pub struct OpeningBook {
+    entries: HashMap<PositionHash, Vec<BookMove>>,
+}
+
+impl OpeningBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// WTHOR形式の対局からブックを作る（ルールどおりに再生できない手以降は登録しない）
+    pub fn from_wthor(games: &[WthorGame]) -> Self {
+        let mut book = Self::new();
+        for game in games {
+            book.add_game(&game.moves, game.black_margin());
+        }
+        book
+    }
+
+    /// WTHOR形式のデータベースファイルからブックを作る
+    pub fn load_wthor(path: &Path) -> Result<Self, WthorError> {
+        let database = wthor::read_database(BufReader::new(File::open(path)?))?;
+        Ok(Self::from_wthor(&database.games))
+    }
+
+    /// 初期局面からの対局を序盤の手数まで登録する
+    /// `black_margin` は黒番から見た終局時の石差。全ての手を登録できた場合はtrue
+    pub fn add_game(&mut self, moves: &[Position], black_margin: i32) -> bool {
+        let mut state = GameState::new();
+        for &position in moves.iter().take(BOOK_MAX_PLIES) {
+            ReversiRules::handle_turn(&mut state);
+            if state.is_finished() {
+                return false;
+            }
+            let player = state.current_player;
+            let canonical = PositionHash::canonicalize(&state.board, player);
+            if ReversiRules::apply_move(&mut state, position).is_err() {
+                return false;
+            }
+            state.switch_player();
+
+            let margin = if player == Player::Black { black_margin } else { -black_margin };
+            let position = canonical.to_canonical(position);
+            let moves = self.entries.entry(canonical.hash).or_default();
+            match moves.iter_mut().find(|known| known.position == position) {
+                Some(known) => {
+                    known.games += 1;
+                    known.total_margin += margin as i64;
+                }
+                None => moves.push(BookMove { position, games: 1, total_margin: margin as i64 }),
+            }
+        }
+        true
+    }
+
+    /// 局面で指された手を対局数の多い順に返す（元の盤面の向きの位置）
+    pub fn moves(&self, board: &Board, to_move: Player) -> Vec<BookMove> {
+        let canonical = PositionHash::canonicalize(board, to_move);
+        let mut moves: Vec<BookMove> = self
+            .entries
+            .get(&canonical.hash)
+            .into_iter()
+            .flatten()
+            .map(|known| BookMove { position: canonical.from_canonical(known.position), ..known.clone() })
+            .collect();
+        moves.sort_by_key(|known| (std::cmp::Reverse(known.games), known.position.row, known.position.col));
+        moves
+    }
+
+    /// 十分な対局数のある手のうち、手番側の平均石差が最も大きい手を選ぶ
+    pub fn best_move(&self, board: &Board, to_move: Player) -> Option<Position> {
+        self.moves(board, to_move)
+            .into_iter()
+            .filter(|known| known.games >= MIN_BOOK_GAMES)
+            .max_by(|a, b| a.average_margin().total_cmp(&b.average_margin()).then(a.games.cmp(&b.games)))
+            .map(|known| known.position)
+    }
+
+    /// 登録した局面数
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::transcript::parse_transcript;
+
+    #[test]
+    fn test_book_prefers_the_best_scoring_move() {
+        let mut book = OpeningBook::new();
+        // c4とf5の後の局面は正規化すると同じ局面になるため、2手目以降はまとめて集計される
+        assert!(book.add_game(&parse_transcript("F5D6C3").unwrap(), 10));
+        assert!(book.add_game(&parse_transcript("C4E3F6").unwrap(), 20));
+        assert!(book.add_game(&parse_transcript("F5F6E6").unwrap(), -30));
+        assert!(!book.add_game(&parse_transcript("F5F5").unwrap(), 0));
+
+        let initial = Board::new();
+        let openings = book.moves(&initial, Player::Black);
+        assert_eq!((openings[0].position, openings[0].games), (Position::new(4, 5).unwrap(), 3));
+        assert_eq!(book.best_move(&initial, Player::Black), Some(Position::new(4, 5).unwrap()));
+
+        // f5の後の白番: d6は2局で黒が勝ち越し（白から見て-15）、f6は1局のみでブックの手にならない
+        let state = crate::game::transcript::import("F5", GameState::new()).unwrap();
+        let replies = book.moves(&state.board, Player::White);
+        assert_eq!(replies[0].position, Position::new(5, 3).unwrap());
+        assert_eq!((replies[0].games, replies[0].average_margin()), (2, -15.0));
+        assert_eq!(book.best_move(&state.board, Player::White), Some(Position::new(5, 3).unwrap()));
+        assert_eq!(book.best_move(&Board::with_size(6).unwrap(), Player::Black), None);
+    }
+}
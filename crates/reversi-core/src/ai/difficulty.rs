@@ -0,0 +1,143 @@
+//! AIの難易度
+//! APIのリクエスト・設定ファイル・AIサービスで共通に使う3段階の難易度。
+//! 数値レベル（`AiLevel`）と旧APIの難易度（`Difficulty`）の別名として扱う。
+
+use serde::{Deserialize, Deserializer, Serialize};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+use super::levels::AiLevel;
+use super::strategies::Difficulty as LegacyDifficulty;
+use crate::serde_util;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, ToSchema)]
+pub enum AiDifficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl AiDifficulty {
+    pub fn all() -> Vec<AiDifficulty> {
+        vec![AiDifficulty::Easy, AiDifficulty::Medium, AiDifficulty::Hard]
+    }
+    
+    pub fn description(&self) -> &'static str {
+        match self {
+            AiDifficulty::Easy => "初級 - ランダムな手を選択",
+            AiDifficulty::Medium => "中級 - 基本的な戦略を使用", 
+            AiDifficulty::Hard => "上級 - 高度な先読みを実行",
+        }
+    }
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            AiDifficulty::Easy => "Easy",
+            AiDifficulty::Medium => "Medium", 
+            AiDifficulty::Hard => "Hard",
+        }
+    }
+    
+    /// 難易度を別名とする数値レベル
+    pub fn level(self) -> AiLevel {
+        AiLevel::from_difficulty(self.into())
+    }
+}
+
+impl FromStr for AiDifficulty {
+    type Err = String;
+    
+    /// 大文字小文字を区別せずに解析する
+    /// 旧APIの難易度名（beginner / intermediate / advanced）も別名として受け付ける
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let aliases = [("beginner", "Easy"), ("intermediate", "Medium"), ("advanced", "Hard")];
+        match serde_util::resolve_variant(s, &["Easy", "Medium", "Hard"], &aliases) {
+            Some("Easy") => Ok(AiDifficulty::Easy),
+            Some("Medium") => Ok(AiDifficulty::Medium),
+            Some("Hard") => Ok(AiDifficulty::Hard),
+            _ => Err(format!("Invalid difficulty: {}. Valid options: easy, medium, hard", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AiDifficulty {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde_util::deserialize_from_str(deserializer)
+    }
+}
+
+impl From<AiDifficulty> for LegacyDifficulty {
+    fn from(difficulty: AiDifficulty) -> Self {
+        match difficulty {
+            AiDifficulty::Easy => LegacyDifficulty::Beginner,
+            AiDifficulty::Medium => LegacyDifficulty::Intermediate,
+            AiDifficulty::Hard => LegacyDifficulty::Advanced,
+        }
+    }
+}
+
+impl From<AiLevel> for AiDifficulty {
+    fn from(level: AiLevel) -> Self {
+        level.difficulty().into()
+    }
+}
+
+impl From<LegacyDifficulty> for AiDifficulty {
+    fn from(difficulty: LegacyDifficulty) -> Self {
+        match difficulty {
+            LegacyDifficulty::Beginner => AiDifficulty::Easy,
+            LegacyDifficulty::Intermediate => AiDifficulty::Medium,
+            LegacyDifficulty::Advanced => AiDifficulty::Hard,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_ai_difficulty_all() {
+        let all_difficulties = AiDifficulty::all();
+        assert_eq!(all_difficulties.len(), 3);
+        assert!(all_difficulties.contains(&AiDifficulty::Easy));
+        assert!(all_difficulties.contains(&AiDifficulty::Medium));
+        assert!(all_difficulties.contains(&AiDifficulty::Hard));
+    }
+    
+    #[test]
+    fn test_ai_difficulty_description() {
+        assert!(AiDifficulty::Easy.description().contains("初級"));
+        assert!(AiDifficulty::Medium.description().contains("中級"));
+        assert!(AiDifficulty::Hard.description().contains("上級"));
+    }
+    
+    #[test]
+    fn test_ai_difficulty_name() {
+        assert_eq!(AiDifficulty::Easy.name(), "Easy");
+        assert_eq!(AiDifficulty::Medium.name(), "Medium");
+        assert_eq!(AiDifficulty::Hard.name(), "Hard");
+    }
+    
+    #[test]
+    fn test_ai_difficulty_from_str() {
+        assert_eq!("easy".parse::<AiDifficulty>().unwrap(), AiDifficulty::Easy);
+        assert_eq!("MEDIUM".parse::<AiDifficulty>().unwrap(), AiDifficulty::Medium);
+        assert_eq!("Hard".parse::<AiDifficulty>().unwrap(), AiDifficulty::Hard);
+        assert!("invalid".parse::<AiDifficulty>().is_err());
+    }
+    
+    #[test]
+    fn test_ai_difficulty_conversion_to_legacy() {
+        assert_eq!(LegacyDifficulty::from(AiDifficulty::Easy), LegacyDifficulty::Beginner);
+        assert_eq!(LegacyDifficulty::from(AiDifficulty::Medium), LegacyDifficulty::Intermediate);
+        assert_eq!(LegacyDifficulty::from(AiDifficulty::Hard), LegacyDifficulty::Advanced);
+    }
+    
+    #[test]
+    fn test_ai_difficulty_conversion_from_legacy() {
+        assert_eq!(AiDifficulty::from(LegacyDifficulty::Beginner), AiDifficulty::Easy);
+        assert_eq!(AiDifficulty::from(LegacyDifficulty::Intermediate), AiDifficulty::Medium);
+        assert_eq!(AiDifficulty::from(LegacyDifficulty::Advanced), AiDifficulty::Hard);
+    }
+}
@@ -2,7 +2,10 @@
 //! リバーシのAIが盤面の優劣を判定するための評価関数を提供する。
 //! 石数、コーナー制御、エッジ制御などの要素で評価する。
 
-use crate::game::{Board, Player, Position};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::game::{Board, Cell, GameVariant, Player, Position};
 
 /// 評価関数の重み係数を管理する構造体
 /// 各評価要素の重要度を調整してAIの戦略を変更できる
@@ -31,6 +34,47 @@ impl Default for EvalWeights {
     }
 }
 
+/// 名前付きの重み係数のプリセット
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EvalPreset {
+    /// デフォルトの重み係数
+    #[default]
+    Balanced,
+    /// コーナーとエッジをより重視し、石数をあまり気にしない
+    Positional,
+    /// 目先の石数を重視する
+    Greedy,
+    /// 石数とコーナーを取りにいき、エッジの安定はあまり気にしない
+    Aggressive,
+}
+
+impl EvalPreset {
+    pub fn weights(self) -> EvalWeights {
+        match self {
+            EvalPreset::Balanced => EvalWeights::default(),
+            EvalPreset::Positional => EvalWeights {
+                piece_count: 0.5,
+                corner_control: 20.0,
+                edge_control: 8.0,
+                mobility: 3.0,
+            },
+            EvalPreset::Greedy => EvalWeights {
+                piece_count: 5.0,
+                corner_control: 2.0,
+                edge_control: 1.0,
+                mobility: 0.0,
+            },
+            EvalPreset::Aggressive => EvalWeights {
+                piece_count: 2.5,
+                corner_control: 14.0,
+                edge_control: 1.5,
+                mobility: 3.0,
+            },
+        }
+    }
+}
+
 /// 盤面評価を行うスタティックメソッド集
 pub struct BoardEvaluator;
 
@@ -46,6 +90,12 @@ impl BoardEvaluator {
         piece_score + corner_score + edge_score
     }
     
+    /// ルールに沿った総合評価値を計算する
+    /// 石の少ない方が勝つルールでは、石数もコーナー・エッジの確定石も不利になるため評価を反転する
+    pub fn evaluate_for_variant(board: &Board, player: Player, weights: &EvalWeights, variant: GameVariant) -> f32 {
+        Self::evaluate_position(board, player, weights) * variant.score_sign() as f32
+    }
+    
     /// 石数に基づく評価
     /// 自分の石数 - 相手の石数で計算
     pub fn evaluate_piece_count(board: &Board, player: Player) -> f32 {
@@ -61,11 +111,12 @@ impl BoardEvaluator {
     /// コーナーは取られると絶対にひっくり返されないため極めて重要
     pub fn evaluate_corner_control(board: &Board, player: Player) -> f32 {
         // 4つのコーナー位置を定義
+        let last = board.size() - 1;
         let corners = [
-            Position::new(0, 0).unwrap(),   // 左上
-            Position::new(0, 7).unwrap(),   // 右上
-            Position::new(7, 0).unwrap(),   // 左下
-            Position::new(7, 7).unwrap(),   // 右下
+            Position { row: 0, col: 0 },       // 左上
+            Position { row: 0, col: last },    // 右上
+            Position { row: last, col: 0 },    // 左下
+            Position { row: last, col: last }, // 右下
         ];
         
         let player_cell = player.to_cell();
@@ -90,36 +141,80 @@ impl BoardEvaluator {
         let player_cell = player.to_cell();
         let opponent_cell = player.opposite().to_cell();
         
+        let last = board.size() - 1;
+        
         let mut score = 0.0;
         
         // 上下のエッジをチェック
-        for col in 0..8 {
-            for &row in &[0, 7] {
-                if let Some(position) = Position::new(row, col) {
-                    match board.get_cell(position) {
-                        Some(cell) if cell == player_cell => score += 0.5,
-                        Some(cell) if cell == opponent_cell => score -= 0.5,
-                        _ => {}
-                    }
+        for col in 0..=last {
+            for &row in &[0, last] {
+                match board.get_cell(Position { row, col }) {
+                    Some(cell) if cell == player_cell => score += 0.5,
+                    Some(cell) if cell == opponent_cell => score -= 0.5,
+                    _ => {}
                 }
             }
         }
         
         // 左右のエッジをチェック（コーナー除く）
-        for row in 1..7 {
-            for &col in &[0, 7] {
-                if let Some(position) = Position::new(row, col) {
-                    match board.get_cell(position) {
-                        Some(cell) if cell == player_cell => score += 0.5,
-                        Some(cell) if cell == opponent_cell => score -= 0.5,
-                        _ => {}
-                    }
+        for row in 1..last {
+            for &col in &[0, last] {
+                match board.get_cell(Position { row, col }) {
+                    Some(cell) if cell == player_cell => score += 0.5,
+                    Some(cell) if cell == opponent_cell => score -= 0.5,
+                    _ => {}
                 }
             }
         }
         
         score
     }
+
+    /// 確定石（以後どう打たれても返されない石）の数
+    /// 4方向の軸のそれぞれで、片側が盤外か自分の確定石であるか、軸上の列が埋まっている石を確定石とする
+    /// 隅から広がる確定石を繰り返し伝播させるため、実際の確定石より少なく数えることはあっても多くは数えない
+    pub fn count_stable_discs(board: &Board, player: Player) -> u32 {
+        const AXES: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+        let size = board.size();
+        let player_cell = player.to_cell();
+        let neighbor = |position: Position, (dr, dc): (isize, isize)| {
+            let row = position.row.checked_add_signed(dr)?;
+            let col = position.col.checked_add_signed(dc)?;
+            Position::within(row, col, size)
+        };
+        let line_is_full = |position: Position, axis: (isize, isize)| {
+            [axis, (-axis.0, -axis.1)].into_iter().all(|direction| {
+                std::iter::successors(neighbor(position, direction), |next| neighbor(*next, direction))
+                    .all(|next| board.get_cell(next).is_some_and(|cell| cell != Cell::Empty))
+            })
+        };
+
+        let mut stable = vec![false; size * size];
+        loop {
+            let mut changed = false;
+            for row in 0..size {
+                for col in 0..size {
+                    let position = Position { row, col };
+                    if stable[row * size + col] || board.get_cell(position) != Some(player_cell) {
+                        continue;
+                    }
+                    let anchored = |direction: (isize, isize)| {
+                        neighbor(position, direction).is_none_or(|next| stable[next.row * size + next.col])
+                    };
+                    let is_stable = AXES.into_iter().all(|axis| {
+                        anchored(axis) || anchored((-axis.0, -axis.1)) || line_is_full(position, axis)
+                    });
+                    if is_stable {
+                        stable[row * size + col] = true;
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                return stable.iter().filter(|&&stable| stable).count() as u32;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -134,6 +229,9 @@ mod tests {
         assert_eq!(weights.corner_control, 10.0);
         assert_eq!(weights.edge_control, 5.0);
         assert_eq!(weights.mobility, 3.0);
+        assert_eq!(EvalPreset::default().weights().corner_control, weights.corner_control);
+        assert!(EvalPreset::Greedy.weights().piece_count > EvalPreset::Positional.weights().piece_count);
+        assert_eq!(serde_json::from_str::<EvalPreset>(r#""positional""#).unwrap(), EvalPreset::Positional);
     }
 
     #[test]
@@ -219,4 +317,17 @@ mod tests {
         
         assert_eq!(score, expected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_count_stable_discs_spreads_from_corner() {
+        let mut board = Board::new();
+        assert_eq!(BoardEvaluator::count_stable_discs(&board, Player::Black), 0);
+
+        // 隅から辺に沿って並んだ石と、隅の斜め隣で3方向を固められた石は確定石
+        for (row, col) in [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1)] {
+            board.set_cell(Position::new(row, col).unwrap(), Cell::Black);
+        }
+        assert_eq!(BoardEvaluator::count_stable_discs(&board, Player::Black), 5);
+        assert_eq!(BoardEvaluator::count_stable_discs(&board, Player::White), 0);
+    }
+}
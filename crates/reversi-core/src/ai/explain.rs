@@ -0,0 +1,214 @@
+//! AIの着手の解説（教習モード）
+//! 着手前の局面の合法手を評価関数の要素ごとに評価し、AIが検討した手と選んだ手の理由を組み立てる。
+//! 解説は1手先の静的評価に基づくため、深い探索で選んだ手の理由をすべて説明するものではない。
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::ai::evaluation::{BoardEvaluator, EvalWeights};
+use crate::ai::strategies::ScoredMove;
+use crate::game::{Board, GameState, GameVariant, Player, Position, ReversiRules};
+
+/// 解説に載せる検討手の数の上限
+pub const MAX_CONSIDERED_MOVES: usize = 5;
+
+/// 評価関数の要素ごとの値（着手した側から見た、重みを掛けた後の値）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, ToSchema)]
+pub struct EvalComponents {
+    pub piece_count: f32,
+    pub corner_control: f32,
+    pub edge_control: f32,
+    /// 要素の合計（静的評価値）
+    pub total: f32,
+}
+
+impl EvalComponents {
+    pub fn of(board: &Board, player: Player, weights: &EvalWeights, variant: GameVariant) -> Self {
+        let sign = variant.score_sign() as f32;
+        let piece_count = BoardEvaluator::evaluate_piece_count(board, player) * weights.piece_count * sign;
+        let corner_control = BoardEvaluator::evaluate_corner_control(board, player) * weights.corner_control * sign;
+        let edge_control = BoardEvaluator::evaluate_edge_control(board, player) * weights.edge_control * sign;
+        Self { piece_count, corner_control, edge_control, total: piece_count + corner_control + edge_control }
+    }
+}
+
+/// AIが検討した手
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ConsideredMove {
+    pub position: Position,
+    /// 着手後の局面の評価
+    pub components: EvalComponents,
+    /// 返る石の数
+    pub flips: usize,
+}
+
+/// AIの着手の解説
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct MoveExplanation {
+    /// AIが選んだ手
+    pub position: Position,
+    /// 検討した手（1手先の静的評価の高い順、最大5手）
+    pub considered: Vec<ConsideredMove>,
+    /// 合法手だったが避けたX打ち（空いている隅の斜め隣）
+    pub avoided_x_squares: Vec<Position>,
+    /// この着手で増えたAIの確定石の数
+    pub stable_discs_gained: u32,
+    /// 返した石の数
+    pub flipped: usize,
+    /// 解説文
+    pub reasons: Vec<String>,
+    /// 探索で評価値を求めた候補手（評価値の高い順、候補手を求めなかった場合は空）
+    pub searched: Vec<ScoredMove>,
+}
+
+impl MoveExplanation {
+    /// 探索で求めた候補手（評価値の高い順）を解説に加える
+    pub fn with_searched(mut self, searched: Vec<ScoredMove>) -> Self {
+        match searched.as_slice() {
+            [best, ..] if best.position != self.position => {
+                self.reasons.push(format!("先読みした評価が最も高いのは{}でしたが、{}を選びました", best.position, self.position));
+            }
+            [best, second, ..] => {
+                self.reasons.push(format!("先読みした評価では、次点の{}より{:.1}高い手でした", second.position, best.score - second.score));
+            }
+            _ => {}
+        }
+        self.searched = searched;
+        self
+    }
+}
+
+/// 着手前の局面 `state` で手番側が `position` に打った理由を解説する（合法手でなければNone）
+pub fn explain_move(state: &GameState, position: Position, weights: &EvalWeights) -> Option<MoveExplanation> {
+    let board = &state.board;
+    let player = state.current_player;
+    let moves = ReversiRules::get_valid_moves(board, player);
+    if !moves.contains(&position) {
+        return None;
+    }
+
+    let mut considered: Vec<ConsideredMove> = moves
+        .iter()
+        .map(|&candidate| {
+            let (next, flips) = play(board, candidate, player);
+            ConsideredMove {
+                position: candidate,
+                components: EvalComponents::of(&next, player, weights, state.variant),
+                flips,
+            }
+        })
+        .collect();
+    considered.sort_by(|a, b| b.components.total.total_cmp(&a.components.total));
+
+    let (after, flipped) = play(board, position, player);
+    let stable_discs_gained = BoardEvaluator::count_stable_discs(&after, player)
+        .saturating_sub(BoardEvaluator::count_stable_discs(board, player));
+    let avoided_x_squares: Vec<Position> = moves
+        .iter()
+        .copied()
+        .filter(|&candidate| candidate != position && is_x_square(board, candidate))
+        .collect();
+
+    let mut reasons = Vec::new();
+    if is_corner(board, position) {
+        reasons.push(format!("隅の{}を取りました。隅の石は二度と返されません", position));
+    } else if is_x_square(board, position) {
+        reasons.push(format!("{}は空いている隅の斜め隣（X打ち）ですが、ほかの手より評価が高いと判断しました", position));
+    } else if is_edge(board, position) {
+        reasons.push(format!("辺の{}に打って、辺の石を増やしました", position));
+    }
+    if !avoided_x_squares.is_empty() {
+        let squares: Vec<String> = avoided_x_squares.iter().map(Position::to_string).collect();
+        reasons.push(format!("{}は空いている隅の斜め隣（X打ち）で、相手に隅を与えやすいため避けました", squares.join("・")));
+    }
+    if stable_discs_gained > 0 {
+        reasons.push(format!("確定石（二度と返されない石）が{}個増えました", stable_discs_gained));
+    }
+    reasons.push(format!("{}個の石を返しました", flipped));
+    if let Some(best) = considered.first().filter(|best| best.position != position) {
+        reasons.push(format!("1手先の評価が最も高いのは{}ですが、先の展開まで読んで{}を選びました", best.position, position));
+    }
+
+    considered.truncate(MAX_CONSIDERED_MOVES);
+    Some(MoveExplanation { position, considered, avoided_x_squares, stable_discs_gained, flipped, reasons, searched: Vec::new() })
+}
+
+/// 着手後の盤面と返った石の数
+fn play(board: &Board, position: Position, player: Player) -> (Board, usize) {
+    let flipped = ReversiRules::get_flipped_positions(board, position, player);
+    let mut next = board.clone();
+    for flip in &flipped {
+        next.set_cell(*flip, player.to_cell());
+    }
+    next.set_cell(position, player.to_cell());
+    (next, flipped.len())
+}
+
+fn is_corner(board: &Board, position: Position) -> bool {
+    let last = board.size() - 1;
+    (position.row == 0 || position.row == last) && (position.col == 0 || position.col == last)
+}
+
+fn is_edge(board: &Board, position: Position) -> bool {
+    let last = board.size() - 1;
+    position.row == 0 || position.row == last || position.col == 0 || position.col == last
+}
+
+/// 空いている隅の斜め隣か
+fn is_x_square(board: &Board, position: Position) -> bool {
+    let last = board.size() - 1;
+    let corner_index = |index: usize| match index {
+        1 => Some(0),
+        index if index + 1 == last => Some(last),
+        _ => None,
+    };
+    let (Some(row), Some(col)) = (corner_index(position.row), corner_index(position.col)) else {
+        return false;
+    };
+    board.is_empty(Position { row, col })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Cell;
+
+    #[test]
+    fn test_explains_corner_and_avoided_x_square() {
+        // 黒はh1の隅も、b2のX打ち（a1が空いている）も打てる
+        let mut state = GameState::new();
+        for (row, col, cell) in [(1, 6, Cell::White), (2, 5, Cell::Black), (2, 2, Cell::White), (5, 5, Cell::Black)] {
+            state.board.set_cell(Position::new(row, col).unwrap(), cell);
+        }
+        let corner = Position::new(0, 7).unwrap();
+        let x_square = Position::new(1, 1).unwrap();
+        assert!(ReversiRules::is_valid_move(&state.board, x_square, Player::Black));
+
+        let explanation = explain_move(&state, corner, &EvalWeights::default()).unwrap();
+        assert_eq!(explanation.considered[0].position, corner);
+        assert_eq!((explanation.flipped, explanation.stable_discs_gained), (1, 1));
+        assert_eq!(explanation.avoided_x_squares, vec![x_square]);
+        assert!(explanation.reasons[0].contains("隅のh1"));
+        assert!(explanation.reasons[1].contains("b2"));
+
+        assert!(explain_move(&state, Position::new(7, 7).unwrap(), &EvalWeights::default()).is_none());
+    }
+
+    #[test]
+    fn test_explains_searched_candidates() {
+        let state = GameState::new();
+        let [chosen, second, ..] = ReversiRules::get_valid_moves(&state.board, Player::Black)[..] else {
+            panic!("初期局面の合法手は4つ");
+        };
+        let explanation = explain_move(&state, chosen, &EvalWeights::default()).unwrap();
+        let reasons = explanation.reasons.len();
+        let searched = vec![ScoredMove { position: chosen, score: 1.5 }, ScoredMove { position: second, score: 0.5 }];
+
+        let explained = explanation.clone().with_searched(searched.clone());
+        assert_eq!(explained.searched, searched);
+        assert!(explained.reasons[reasons].contains("1.0高い"));
+        let reversed = explanation.clone().with_searched(searched.into_iter().rev().collect());
+        assert!(reversed.reasons[reasons].contains("最も高いのは"));
+        assert_eq!(explanation.with_searched(Vec::new()).reasons.len(), reasons);
+    }
+}
@@ -0,0 +1,257 @@
+//! AIの数値レベル（1〜10）
+//! 各レベルを探索深度・思考時間の上限・評価値の揺らぎに対応付ける。
+//! レベル1〜3は先読みをしない戦略（乱択・石数・マスの重み）を使う。
+//! 難易度（Easy / Medium / Hard）はレベル1・5・8の別名として扱う。
+//! 対局ごとに探索深度・思考時間の上限・評価の重みを上書きすることもできる。
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+use super::evaluation::EvalPreset;
+use super::strategies::{AIStrategy, AlphaBetaAI, CancelFlag, Difficulty, GreedyAI, PositionalAI, RandomAI};
+
+/// AIのレベル（1が最弱、10が最強の整数）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(try_from = "u8", into = "u8")]
+#[schema(value_type = u8)]
+pub struct AiLevel(u8);
+
+/// 探索深度0のレベルで使う、先読みをしない戦略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Heuristic {
+    /// 合法手からの乱択
+    #[default]
+    Random,
+    /// ひっくり返せる石が最も多い手
+    Greedy,
+    /// マスごとの重みの表で最も良い手
+    Positional,
+}
+
+/// レベルごとの探索の設定（上書きを適用した後の実際の設定としても使う）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub struct LevelParams {
+    /// αβ探索の深さ（0は探索せず `heuristic` で選ぶ）
+    pub depth: u8,
+    /// 反復深化で深さを増やしていく時間の上限（ミリ秒、0は上限なし）
+    pub time_limit_ms: u64,
+    /// ルートの各手の評価値に加える揺らぎの幅（評価値の100倍の単位）
+    pub noise: i32,
+    /// 静的評価の重み係数
+    #[serde(default)]
+    pub weights: EvalPreset,
+    /// 探索深度が0のときに使う戦略
+    #[serde(default)]
+    pub heuristic: Heuristic,
+    /// 評価値の高い順に返す候補手の数（0なら候補手を求めない、探索深度が0なら使わない）
+    #[serde(default)]
+    pub multipv: u8,
+}
+
+impl LevelParams {
+    /// 一度に求められる候補手の数の上限
+    pub const MAX_MULTIPV: u8 = 10;
+
+    /// 設定に対応するAI戦略を生成する（シードは乱択と揺らぎに使う）
+    pub fn create_strategy(self, seed: Option<u64>, search_threads: usize) -> Box<dyn AIStrategy> {
+        self.create_cancellable_strategy(seed, search_threads, None)
+    }
+
+    /// 中断フラグを指定してAI戦略を生成する
+    /// 探索する設定では、フラグが立つと探索を打ち切る（探索しない設定はすぐに終わるため使わない）
+    pub fn create_cancellable_strategy(self, seed: Option<u64>, search_threads: usize, cancel: Option<CancelFlag>) -> Box<dyn AIStrategy> {
+        if self.depth == 0 {
+            return match self.heuristic {
+                Heuristic::Random => Box::new(RandomAI { seed }),
+                Heuristic::Greedy => Box::new(GreedyAI { seed }),
+                Heuristic::Positional => Box::new(PositionalAI { seed }),
+            };
+        }
+
+        let mut strategy = AlphaBetaAI::new(self.depth)
+            .with_threads(search_threads)
+            .with_noise(self.noise, seed)
+            .with_weights(self.weights.weights())
+            .with_multipv(self.multipv as usize);
+        if self.time_limit_ms > 0 {
+            strategy = strategy.with_time_limit(Duration::from_millis(self.time_limit_ms));
+        }
+        if let Some(cancel) = cancel {
+            strategy = strategy.with_cancel(cancel);
+        }
+        Box::new(strategy)
+    }
+}
+
+/// 対局ごとのAIの設定の上書き（指定した項目だけレベルの設定を置き換える）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AiConfigOverrides {
+    /// αβ探索の深さ（1〜12）
+    #[serde(default)]
+    pub depth: Option<u8>,
+    /// 思考時間の上限（ミリ秒、0は上限なし）
+    #[serde(default)]
+    pub time_limit_ms: Option<u64>,
+    /// 静的評価の重み係数のプリセット
+    #[serde(default)]
+    pub weights: Option<EvalPreset>,
+}
+
+impl AiConfigOverrides {
+    /// 上書きできる探索深度の上限
+    pub const MAX_DEPTH: u8 = 12;
+
+    /// レベルの設定に上書きを適用する（探索深度が範囲外ならエラー）
+    pub fn apply(&self, params: LevelParams) -> Result<LevelParams, String> {
+        if let Some(depth) = self.depth.filter(|depth| !(1..=Self::MAX_DEPTH).contains(depth)) {
+            return Err(format!("探索深度は1〜{}で指定してください: {}", Self::MAX_DEPTH, depth));
+        }
+        Ok(LevelParams {
+            depth: self.depth.unwrap_or(params.depth),
+            time_limit_ms: self.time_limit_ms.unwrap_or(params.time_limit_ms),
+            noise: params.noise,
+            weights: self.weights.unwrap_or(params.weights),
+            heuristic: params.heuristic,
+            multipv: params.multipv,
+        })
+    }
+}
+
+impl AiLevel {
+    pub const MIN: u8 = 1;
+    pub const MAX: u8 = 10;
+
+    /// 1〜10の範囲外ならNone
+    pub fn new(level: u8) -> Option<Self> {
+        (Self::MIN..=Self::MAX).contains(&level).then_some(AiLevel(level))
+    }
+
+    pub fn value(self) -> u8 {
+        self.0
+    }
+
+    pub fn all() -> impl Iterator<Item = AiLevel> {
+        (Self::MIN..=Self::MAX).map(AiLevel)
+    }
+
+    /// 難易度の別名に対応するレベル
+    pub fn from_difficulty(difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Beginner => AiLevel(1),
+            Difficulty::Intermediate => AiLevel(5),
+            Difficulty::Advanced => AiLevel(8),
+        }
+    }
+
+    /// レベルが属する難易度（統計・レーティングの区分に使う）
+    pub fn difficulty(self) -> Difficulty {
+        match self.0 {
+            1..=3 => Difficulty::Beginner,
+            4..=7 => Difficulty::Intermediate,
+            _ => Difficulty::Advanced,
+        }
+    }
+
+    pub fn params(self) -> LevelParams {
+        let (depth, time_limit_ms, noise) = match self.0 {
+            1..=3 => (0, 0, 0),
+            4 => (2, 200, 600),
+            5 => (3, 300, 300),
+            6 => (4, 500, 100),
+            7 => (5, 1000, 0),
+            8 => (6, 2000, 0),
+            9 => (8, 3000, 0),
+            _ => (10, 5000, 0),
+        };
+        let heuristic = match self.0 {
+            2 => Heuristic::Greedy,
+            3 => Heuristic::Positional,
+            _ => Heuristic::Random,
+        };
+        LevelParams { depth, time_limit_ms, noise, weights: EvalPreset::Balanced, heuristic, multipv: 0 }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self.0 {
+            1 => "合法手からランダムに選ぶ",
+            2 => "ひっくり返せる石が最も多い手を選ぶ",
+            3 => "隅を重視するマスの重みの表で選ぶ",
+            4 => "2手先まで読むが、読み違えが多い",
+            5 => "3手先まで読み、ときどき読み違える",
+            6 => "4手先まで読み、まれに読み違える",
+            7 => "5手先まで正確に読む",
+            8 => "6手先まで正確に読む",
+            9 => "最大8手先まで、3秒以内で読む",
+            _ => "最大10手先まで、5秒以内で読む",
+        }
+    }
+
+    /// レベルに対応するAI戦略を生成する（シードは乱択と揺らぎに使う）
+    pub fn create_strategy(self, seed: Option<u64>, search_threads: usize) -> Box<dyn AIStrategy> {
+        self.params().create_strategy(seed, search_threads)
+    }
+}
+
+impl TryFrom<u8> for AiLevel {
+    type Error = String;
+
+    fn try_from(level: u8) -> Result<Self, Self::Error> {
+        AiLevel::new(level).ok_or_else(|| format!("レベルは{}〜{}で指定してください: {}", Self::MIN, Self::MAX, level))
+    }
+}
+
+impl From<AiLevel> for u8 {
+    fn from(level: AiLevel) -> Self {
+        level.0
+    }
+}
+
+impl fmt::Display for AiLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{GameState, ReversiRules};
+
+    #[test]
+    fn test_levels_map_to_strategies_and_aliases() {
+        assert_eq!(AiLevel::new(0), None);
+        assert_eq!(AiLevel::new(11), None);
+        assert_eq!(AiLevel::all().count(), 10);
+        assert!(serde_json::from_str::<AiLevel>("11").is_err());
+        assert_eq!(serde_json::from_str::<AiLevel>("7").unwrap().value(), 7);
+
+        for difficulty in [Difficulty::Beginner, Difficulty::Intermediate, Difficulty::Advanced] {
+            assert_eq!(AiLevel::from_difficulty(difficulty.clone()).difficulty(), difficulty);
+        }
+        let names: Vec<_> = AiLevel::all().take(4).map(|level| level.create_strategy(None, 1).get_name()).collect();
+        assert_eq!(names, ["RandomAI", "GreedyAI", "PositionalAI", "AlphaBetaAI"]);
+        // 記録済みの設定に戦略がなければ乱択として読む
+        let params: LevelParams = serde_json::from_str(r#"{"depth": 0, "time_limit_ms": 0, "noise": 0}"#).unwrap();
+        assert_eq!(params, AiLevel::new(1).unwrap().params());
+
+        let depths: Vec<u8> = AiLevel::all().map(|level| level.params().depth).collect();
+        assert!(depths.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        // 上書きは指定した項目だけを置き換える
+        let level = AiLevel::new(1).unwrap();
+        let overrides = AiConfigOverrides { depth: Some(3), weights: Some(EvalPreset::Greedy), ..Default::default() };
+        let params = overrides.apply(level.params()).unwrap();
+        assert_eq!((params.depth, params.time_limit_ms, params.weights), (3, 0, EvalPreset::Greedy));
+        assert_eq!(params.create_strategy(None, 1).get_name(), "AlphaBetaAI");
+        assert!(AiConfigOverrides { depth: Some(13), ..Default::default() }.apply(level.params()).is_err());
+
+        let state = GameState::new();
+        for level in AiLevel::all().take(6) {
+            let position = level.create_strategy(Some(3), 1).calculate_move(&state).unwrap();
+            assert!(ReversiRules::is_valid_move(&state.board, position, state.current_player));
+        }
+    }
+}
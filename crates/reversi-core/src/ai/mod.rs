@@ -0,0 +1,14 @@
+pub mod strategies;
+pub mod evaluation;
+pub mod levels;
+pub mod personality;
+pub mod solver;
+pub mod explain;
+pub mod adaptive;
+pub mod book;
+pub mod difficulty;
+
+pub use strategies::*;
+pub use levels::*;
+pub use personality::*;
+pub use difficulty::AiDifficulty;
@@ -0,0 +1,92 @@
+//! AIの打ち筋（パーソナリティ）
+//! 評価の重み係数のプリセットと手選びのランダムさ（温度）の組み合わせで、同じレベルでも打ち筋を変える。
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::evaluation::EvalPreset;
+use super::levels::LevelParams;
+
+/// AIのパーソナリティ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AiPersonality {
+    /// 石数とコーナーを積極的に取りにいく
+    Aggressive,
+    /// コーナーとエッジを固め、じっくり打つ
+    Positional,
+    /// 目先の石数を欲張る
+    Greedy,
+    /// 評価は標準だが、気まぐれに手を選ぶ
+    Wild,
+}
+
+impl AiPersonality {
+    pub fn all() -> [AiPersonality; 4] {
+        [AiPersonality::Aggressive, AiPersonality::Positional, AiPersonality::Greedy, AiPersonality::Wild]
+    }
+
+    /// 画面に表示する名前
+    pub fn name(self) -> &'static str {
+        match self {
+            AiPersonality::Aggressive => "攻め好き",
+            AiPersonality::Positional => "堅実派",
+            AiPersonality::Greedy => "欲張り",
+            AiPersonality::Wild => "気まぐれ",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            AiPersonality::Aggressive => "石数とコーナーを積極的に取りにいく",
+            AiPersonality::Positional => "コーナーとエッジを固め、じっくり打つ",
+            AiPersonality::Greedy => "目先の石数を欲張る",
+            AiPersonality::Wild => "読みは標準だが、気まぐれに手を選ぶ",
+        }
+    }
+
+    /// 評価の重み係数のプリセット
+    pub fn preset(self) -> EvalPreset {
+        match self {
+            AiPersonality::Aggressive => EvalPreset::Aggressive,
+            AiPersonality::Positional => EvalPreset::Positional,
+            AiPersonality::Greedy => EvalPreset::Greedy,
+            AiPersonality::Wild => EvalPreset::Balanced,
+        }
+    }
+
+    /// ルートの各手の評価値に加える揺らぎの幅（レベルの揺らぎに上乗せする）
+    pub fn temperature(self) -> i32 {
+        match self {
+            AiPersonality::Aggressive => 100,
+            AiPersonality::Positional => 0,
+            AiPersonality::Greedy => 50,
+            AiPersonality::Wild => 800,
+        }
+    }
+
+    /// レベルの設定にパーソナリティを適用する
+    pub fn apply(self, params: LevelParams) -> LevelParams {
+        LevelParams {
+            noise: params.noise + self.temperature(),
+            weights: self.preset(),
+            ..params
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::levels::AiLevel;
+
+    #[test]
+    fn test_personality_changes_weights_and_temperature() {
+        let params = AiLevel::new(8).unwrap().params();
+        let wild = AiPersonality::Wild.apply(params);
+        assert_eq!((wild.depth, wild.time_limit_ms), (params.depth, params.time_limit_ms));
+        assert!(wild.noise > params.noise);
+        assert_eq!(AiPersonality::Positional.apply(params).weights, EvalPreset::Positional);
+        assert_eq!(serde_json::from_str::<AiPersonality>(r#""greedy""#).unwrap(), AiPersonality::Greedy);
+    }
+}
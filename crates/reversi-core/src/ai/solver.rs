@@ -0,0 +1,119 @@
+//! 終盤の完全読み
+//! 空きマスの少ない局面を終局まで読み切り、双方が最善を尽くした場合の最終的な石差を求める。
+//! 石差は手番側から見た値で、石の少ない方が勝つルールでは符号を反転し、常に大きいほど手番側に良い向きにそろえる。
+
+use crate::game::{Board, GameVariant, Player, Position, ReversiRules};
+
+/// 完全読みする局面の空きマス数の上限
+pub const MAX_SOLVER_EMPTIES: usize = 12;
+
+/// 完全読みで求めた合法手の結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolvedMove {
+    pub position: Position,
+    /// この手を打った後、双方が最善を尽くした場合の最終的な石差（手番側から見た値）
+    pub margin: i32,
+}
+
+impl SolvedMove {
+    pub fn wins(&self) -> bool {
+        self.margin > 0
+    }
+}
+
+/// 盤面の空きマス数
+pub fn empties(board: &Board) -> usize {
+    board.positions().filter(|&position| board.is_empty(position)).count()
+}
+
+/// 局面を読み切り、手番側から見た最終的な石差を返す（空きマスが多すぎる場合はNone）
+pub fn solve(board: &Board, player: Player, variant: GameVariant) -> Option<i32> {
+    if empties(board) > MAX_SOLVER_EMPTIES {
+        return None;
+    }
+    let bound = (board.size() * board.size()) as i32 + 1;
+    Some(negamax(board, player, variant, -bound, bound))
+}
+
+/// 指定した手を打った後を読み切り、手番側から見た最終的な石差を返す
+/// 合法手でない場合や、空きマスが多すぎる場合はNone
+pub fn solve_move(board: &Board, player: Player, position: Position, variant: GameVariant) -> Option<i32> {
+    if !ReversiRules::is_valid_move(board, position, player) {
+        return None;
+    }
+    solve(&play(board, position, player), player.opposite(), variant).map(|margin| -margin)
+}
+
+/// 全ての合法手を読み切り、石差の大きい順に返す（空きマスが多すぎる場合はNone）
+pub fn solve_moves(board: &Board, player: Player, variant: GameVariant) -> Option<Vec<SolvedMove>> {
+    let mut moves = ReversiRules::get_valid_moves(board, player)
+        .into_iter()
+        .map(|position| solve_move(board, player, position, variant).map(|margin| SolvedMove { position, margin }))
+        .collect::<Option<Vec<_>>>()?;
+    moves.sort_by_key(|solved| std::cmp::Reverse(solved.margin));
+    Some(moves)
+}
+
+/// 着手後の盤面を返す
+fn play(board: &Board, position: Position, player: Player) -> Board {
+    let mut next = board.clone();
+    for flipped in ReversiRules::get_flipped_positions(board, position, player) {
+        next.set_cell(flipped, player.to_cell());
+    }
+    next.set_cell(position, player.to_cell());
+    next
+}
+
+/// ネガマックス形式のαβ探索（終局まで読み切る）
+fn negamax(board: &Board, player: Player, variant: GameVariant, mut alpha: i32, beta: i32) -> i32 {
+    let moves = ReversiRules::get_valid_moves(board, player);
+    if moves.is_empty() {
+        if !ReversiRules::has_valid_moves(board, player.opposite()) {
+            let (black, white) = board.count_pieces();
+            let margin = black as i32 - white as i32;
+            let margin = if player == Player::Black { margin } else { -margin };
+            return margin * variant.score_sign();
+        }
+        return -negamax(board, player.opposite(), variant, -beta, -alpha);
+    }
+
+    for position in moves {
+        let score = -negamax(&play(board, position, player), player.opposite(), variant, -beta, -alpha);
+        if score >= beta {
+            return beta;
+        }
+        alpha = alpha.max(score);
+    }
+    alpha
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::setup::parse_board;
+
+    #[test]
+    fn test_solver_reads_the_endgame_to_the_end() {
+        // 黒番: (6,7)から打てば最後に(7,7)も打てて全滅させるが、(7,7)から打つと(6,7)が残って終局する
+        let board = parse_board(
+            "XXXXXXXX/XXXXXXXX/XXXXXXXX/XXXXXXXX/XXXXXXXX/XXXXXXXX/XXXXXXO-/XXXXXXO-",
+        )
+        .unwrap();
+        assert_eq!(empties(&board), 2);
+
+        let moves = solve_moves(&board, Player::Black, GameVariant::Standard).unwrap();
+        let expected = [
+            SolvedMove { position: Position::new(6, 7).unwrap(), margin: 64 },
+            SolvedMove { position: Position::new(7, 7).unwrap(), margin: 63 },
+        ];
+        assert_eq!(moves, expected);
+        assert_eq!(solve(&board, Player::Black, GameVariant::Standard), Some(64));
+        // 白は打てる手がなくパスする
+        assert_eq!(solve(&board, Player::White, GameVariant::Standard), Some(-64));
+        assert_eq!(solve_move(&board, Player::White, Position::new(6, 7).unwrap(), GameVariant::Standard), None);
+
+        // 石の少ない方が勝つルールでは、返す石の少ない手を選んでも負ける
+        assert_eq!(solve(&board, Player::Black, GameVariant::AntiReversi), Some(-63));
+        assert_eq!(solve(&Board::new(), Player::Black, GameVariant::Standard), None);
+    }
+}
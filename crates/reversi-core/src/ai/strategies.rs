@@ -0,0 +1,1247 @@
+//! AI戦略の実装モジュール
+//! 異なるAI戦略（ランダム、ミニマックス、αβ法など）を定義し、
+//! 統一されたインターフェースで提供する。
+
+use crate::ai::evaluation::{BoardEvaluator, EvalWeights};
+use crate::game::{Board, GameState, GameVariant, Position, Player, ReversiRules, MAX_BOARD_SIZE};
+use crate::error::{AIError, Result as GameResult};
+use crate::serde_util;
+use rand::Rng;
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::ToSchema;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// AIの難易度を表すenum
+/// 異なる戦略や探索深度に対応する
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub enum Difficulty {
+    /// 初心者レベル（ランダム戦略）
+    Beginner,
+    /// 中級者レベル（ミニマックス法）
+    Intermediate,
+    /// 上級者レベル（αβ法）
+    Advanced,
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    /// 大文字小文字を区別せずに解析する
+    /// AI対戦APIの難易度名（easy / medium / hard）も別名として受け付ける
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let aliases = [("easy", "Beginner"), ("medium", "Intermediate"), ("hard", "Advanced")];
+        match serde_util::resolve_variant(s, &["Beginner", "Intermediate", "Advanced"], &aliases) {
+            Some("Beginner") => Ok(Difficulty::Beginner),
+            Some("Intermediate") => Ok(Difficulty::Intermediate),
+            Some("Advanced") => Ok(Difficulty::Advanced),
+            _ => Err(format!("Invalid difficulty: {}. Valid options: beginner, intermediate, advanced", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Difficulty {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde_util::deserialize_from_str(deserializer)
+    }
+}
+
+/// AI戦略の共通インターフェース
+/// 異なるAI実装を統一して扱うためのtrait
+pub trait AIStrategy: Send + Sync {
+    /// ゲーム状態から最適な手を計算する
+    fn calculate_move(&self, game_state: &GameState) -> Result<Position, AIError>;
+    /// このAIの難易度を返す
+    fn get_difficulty(&self) -> Difficulty;
+    /// AIの名前を返す
+    fn get_name(&self) -> &'static str;
+    /// 手と探索の統計を計算する（探索しない戦略では統計はNone）
+    fn calculate_move_with_stats(&self, game_state: &GameState) -> Result<(Position, Option<SearchStats>), AIError> {
+        self.calculate_move(game_state).map(|position| (position, None))
+    }
+    /// 手と探索の統計に加え、評価値の高い順の候補手を計算する（候補手を求めない戦略では候補手は空）
+    fn search(&self, game_state: &GameState) -> Result<SearchOutcome, AIError> {
+        let (position, stats) = self.calculate_move_with_stats(game_state)?;
+        Ok(SearchOutcome { position, stats, candidates: Vec::new(), principal_variation: Vec::new() })
+    }
+}
+
+/// 候補手とその評価値（手番側から見た値、静的評価と同じ単位）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ScoredMove {
+    pub position: Position,
+    pub score: f64,
+}
+
+/// 着手の計算結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchOutcome {
+    /// 選んだ手
+    pub position: Position,
+    /// 探索の統計（探索しない戦略ではNone）
+    pub stats: Option<SearchStats>,
+    /// 評価値の高い順の候補手（揺らぎを加える場合、先頭が選んだ手とは限らない）
+    pub candidates: Vec<ScoredMove>,
+    /// 選んだ手から始まる読み筋（双方が最善を尽くした場合に予想される手順、探索しない戦略では空）
+    /// パスは自動で行われるため含まない
+    pub principal_variation: Vec<Position>,
+}
+
+/// 探索の統計（手の並べ替えによる枝刈りの効果を測るために使う）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchStats {
+    /// 最後まで完了した反復深化の深さ
+    pub depth_reached: u8,
+    /// 探索した局面の数（反復深化の各深さの合計）
+    pub nodes: u64,
+    /// βカットの回数
+    pub cutoffs: u64,
+    /// 最初に調べた手でβカットした回数（並べ替えが良いほど `cutoffs` に近づく）
+    pub first_move_cutoffs: u64,
+}
+
+/// ランダムに手を選択するAI実装
+/// 初心者レベルで、合法手の中からランダムに選ぶ
+#[derive(Debug, Clone)]
+pub struct RandomAI {
+    /// 指定されている場合は手の選択をこのシードで決める（未指定ならOSの乱数源で選ぶ）
+    pub seed: Option<u64>,
+}
+
+impl RandomAI {
+    /// 新しいRandomAIインスタンスを作成する
+    pub fn new() -> Self {
+        RandomAI { seed: None }
+    }
+    
+    /// シード付きのRandomAIを作成する（同じシード・同じ局面なら同じ手を返す）
+    pub fn with_seed(seed: u64) -> Self {
+        RandomAI { seed: Some(seed) }
+    }
+}
+
+/// SplitMix64による64bit値の攪拌
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// `len` 個の候補から1つを選ぶ添字
+/// シードが指定されていれば、同じシード・同じ局面では同じ添字を返す
+fn choose_index(seed: Option<u64>, game_state: &GameState, len: usize) -> usize {
+    match seed {
+        Some(seed) => {
+            let ply = (game_state.get_move_count() as u64) << 1 | game_state.current_player as u64;
+            (splitmix64(seed ^ splitmix64(ply)) % len as u64) as usize
+        }
+        None => rand::thread_rng().gen_range(0..len),
+    }
+}
+
+impl Default for RandomAI {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AIStrategy for RandomAI {
+    /// 合法手の中からランダムに選択する
+    /// シードが指定されていれば、同じシード・同じ局面では同じ手を選ぶ
+    fn calculate_move(&self, game_state: &GameState) -> Result<Position, AIError> {
+        if game_state.is_finished() {
+            return Err(AIError::StrategyError {
+                message: "Cannot calculate move for finished game".to_string(),
+            });
+        }
+        
+        let valid_moves = ReversiRules::get_valid_moves(&game_state.board, game_state.current_player);
+        
+        if valid_moves.is_empty() {
+            return Err(AIError::NoValidMoves);
+        }
+        
+        Ok(valid_moves[choose_index(self.seed, game_state, valid_moves.len())])
+    }
+    
+    fn get_difficulty(&self) -> Difficulty {
+        Difficulty::Beginner
+    }
+    
+    fn get_name(&self) -> &'static str {
+        "RandomAI"
+    }
+}
+
+/// 合法手を1手だけ見た点数で選ぶ（最高点の手が複数あればその中から乱択する）
+/// 石の少ない方が勝つルールでは点数が最も低い手を選ぶ
+fn best_scored_move(
+    game_state: &GameState,
+    seed: Option<u64>,
+    score: impl Fn(Position) -> i32,
+) -> Result<Position, AIError> {
+    if game_state.is_finished() {
+        return Err(AIError::StrategyError {
+            message: "Cannot calculate move for finished game".to_string(),
+        });
+    }
+
+    let sign = if game_state.variant.fewer_discs_win() { -1 } else { 1 };
+    let scored: Vec<(Position, i32)> = ReversiRules::get_valid_moves(&game_state.board, game_state.current_player)
+        .into_iter()
+        .map(|position| (position, sign * score(position)))
+        .collect();
+    let Some(best) = scored.iter().map(|&(_, score)| score).max() else {
+        return Err(AIError::NoValidMoves);
+    };
+    let candidates: Vec<Position> = scored
+        .into_iter()
+        .filter_map(|(position, score)| (score == best).then_some(position))
+        .collect();
+    Ok(candidates[choose_index(seed, game_state, candidates.len())])
+}
+
+/// ひっくり返せる石が最も多い手を選ぶAI実装
+/// 先読みをしない分、ランダムより強くαβ法よりずっと弱い
+#[derive(Debug, Clone, Default)]
+pub struct GreedyAI {
+    /// 同点の手からの選択に使うシード（未指定ならOSの乱数源で選ぶ）
+    pub seed: Option<u64>,
+}
+
+impl GreedyAI {
+    pub fn new() -> Self {
+        GreedyAI { seed: None }
+    }
+
+    /// シード付きのGreedyAIを作成する（同じシード・同じ局面なら同じ手を返す）
+    pub fn with_seed(seed: u64) -> Self {
+        GreedyAI { seed: Some(seed) }
+    }
+}
+
+impl AIStrategy for GreedyAI {
+    fn calculate_move(&self, game_state: &GameState) -> Result<Position, AIError> {
+        let board = &game_state.board;
+        let player = game_state.current_player;
+        best_scored_move(game_state, self.seed, |position| {
+            ReversiRules::get_flipped_positions(board, position, player).len() as i32
+        })
+    }
+
+    fn get_difficulty(&self) -> Difficulty {
+        Difficulty::Beginner
+    }
+
+    fn get_name(&self) -> &'static str {
+        "GreedyAI"
+    }
+}
+
+/// 8×8の盤の左上4×4の各マスの重み（隅が最も高く、隅に隣接するマスは低い）
+/// 盤の4隅それぞれから数えた位置で引くため、他の大きさの盤でも隅・辺・内側の区別はそのまま使える
+const POSITION_WEIGHTS: [[i32; 4]; 4] = [
+    [100, -20, 10, 5],
+    [-20, -50, -2, -2],
+    [10, -2, -1, -1],
+    [5, -2, -1, -1],
+];
+
+/// マスの位置による重み
+pub fn position_weight(position: Position, size: usize) -> i32 {
+    let from_edge = |index: usize| index.min(size - 1 - index).min(3);
+    POSITION_WEIGHTS[from_edge(position.row)][from_edge(position.col)]
+}
+
+/// マスごとの静的な重みの表で、着手後の盤面が最も良くなる手を選ぶAI実装
+/// 置いた石と返した石の重みだけで差分を計算するため、先読みをしない
+#[derive(Debug, Clone, Default)]
+pub struct PositionalAI {
+    /// 同点の手からの選択に使うシード（未指定ならOSの乱数源で選ぶ）
+    pub seed: Option<u64>,
+}
+
+impl PositionalAI {
+    pub fn new() -> Self {
+        PositionalAI { seed: None }
+    }
+
+    /// シード付きのPositionalAIを作成する（同じシード・同じ局面なら同じ手を返す）
+    pub fn with_seed(seed: u64) -> Self {
+        PositionalAI { seed: Some(seed) }
+    }
+}
+
+impl AIStrategy for PositionalAI {
+    fn calculate_move(&self, game_state: &GameState) -> Result<Position, AIError> {
+        let board = &game_state.board;
+        let player = game_state.current_player;
+        let size = board.size();
+        best_scored_move(game_state, self.seed, |position| {
+            // 返した石は相手の重みが減って自分の重みが増えるため2倍で数える
+            let flipped: i32 = ReversiRules::get_flipped_positions(board, position, player)
+                .into_iter()
+                .map(|flipped| position_weight(flipped, size))
+                .sum();
+            position_weight(position, size) + 2 * flipped
+        })
+    }
+
+    fn get_difficulty(&self) -> Difficulty {
+        Difficulty::Beginner
+    }
+
+    fn get_name(&self) -> &'static str {
+        "PositionalAI"
+    }
+}
+
+/// ミニマックス法を使用するAI実装（未実装）
+/// 指定した深度までゲームツリーを探索して最適手を見つける
+#[derive(Debug, Clone)]
+pub struct MinimaxAI {
+    /// 探索深度（手数）
+    pub depth: u8,
+}
+
+impl MinimaxAI {
+    /// 指定した探索深度で新しいMinimaxAIを作成する
+    pub fn new(depth: u8) -> Self {
+        MinimaxAI { depth }
+    }
+}
+
+impl AIStrategy for MinimaxAI {
+    /// ミニマックス法で最適手を計算する（未実装）
+    fn calculate_move(&self, _game_state: &GameState) -> Result<Position, AIError> {
+        Err(AIError::StrategyError {
+            message: "MinimaxAI not yet implemented".to_string(),
+        })
+    }
+    
+    fn get_difficulty(&self) -> Difficulty {
+        Difficulty::Intermediate
+    }
+    
+    fn get_name(&self) -> &'static str {
+        "MinimaxAI"
+    }
+}
+
+/// αβ法（アルファベータ法）を使用するAI実装
+/// ミニマックス法に枝刈りを追加して高速化したAI
+/// ルートでは最初の手を逐次探索して下限を得てから、残りの手を並列に探索する（Young Brothers Wait）
+#[derive(Debug, Clone)]
+pub struct AlphaBetaAI {
+    /// 探索深度（手数）
+    pub depth: u8,
+    /// ルートの並列探索に使うスレッド数（0はCPU数、1は逐次探索）
+    pub threads: usize,
+    /// ルートの各手の評価値に加える揺らぎの幅（評価値の100倍の単位、0なら最善手を選ぶ）
+    pub noise: i32,
+    /// 揺らぎのシード（未指定なら着手ごとに乱数で決める）
+    pub seed: Option<u64>,
+    /// 反復深化で深さを増やしていく時間の上限（未指定なら `depth` まで一度に探索する）
+    pub time_limit: Option<Duration>,
+    /// 静的評価の重み係数
+    pub weights: EvalWeights,
+    /// 評価値を正確に求めて返す候補手の数（multi-PV、0なら候補手を求めない）
+    pub multipv: usize,
+    /// 探索の中断フラグ（立つと探索を打ち切り `AIError::Cancelled` を返す）
+    pub cancel: Option<CancelFlag>,
+    /// 探索している対局のルール（着手の計算ごとに局面から設定する）
+    variant: GameVariant,
+}
+
+/// 探索の中断フラグ
+/// 複製したフラグ同士は状態を共有し、別のスレッドから `cancel` すると実行中の探索を打ち切れる
+#[derive(Debug, Clone, Default)]
+pub struct CancelFlag(Arc<AtomicBool>);
+
+impl CancelFlag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// 探索を始める局面（合法手は有望な順に並べたもの、`salt` は揺らぎに使う局面ごとの値）
+#[derive(Clone, Copy)]
+struct RootPosition<'a> {
+    board: &'a Board,
+    player: Player,
+    moves: &'a [Position],
+    salt: u64,
+}
+
+/// ルートの探索結果
+struct RootResult {
+    /// 揺らぎを加えた評価が最も高い手
+    position: Position,
+    /// 揺らぎを加える前の最高の評価値
+    score: i32,
+    /// 評価値が正確に求まった手のうち上位 `multipv` 個（評価値の高い順、同点なら合法手の並びで先の手）
+    candidates: Vec<(Position, i32)>,
+    /// 選んだ手から始まる読み筋
+    principal_variation: Vec<Position>,
+}
+
+/// 1回の着手の計算で共有する探索の状態（並列探索のスレッド間で共有する）
+/// 反復深化の時間切れの判定、手の並べ替えに使うキラー手と履歴の表、探索の統計を持つ
+/// 表は並べ替えにしか使わないため、スレッド間の更新の競合は評価値に影響しない
+struct SearchContext {
+    deadline: Option<Instant>,
+    cancel: Option<CancelFlag>,
+    expired: AtomicBool,
+    /// 残りの深さごとに、直近でβカットを起こした手を2つ（マスの番号 + 1、0は未登録）
+    killers: Vec<[AtomicU8; 2]>,
+    /// 手番・マスごとの、その手でβカットしたときの残りの深さの2乗の合計
+    history: Vec<AtomicU32>,
+    nodes: AtomicU64,
+    cutoffs: AtomicU64,
+    first_move_cutoffs: AtomicU64,
+}
+
+/// キラー手・履歴の表でのマスの番号
+fn square_index(position: Position) -> usize {
+    position.row * MAX_BOARD_SIZE + position.col
+}
+
+impl SearchContext {
+    fn new(deadline: Option<Instant>, cancel: Option<CancelFlag>, max_depth: u8) -> Self {
+        SearchContext {
+            deadline,
+            cancel,
+            expired: AtomicBool::new(false),
+            killers: (0..=max_depth).map(|_| [AtomicU8::new(0), AtomicU8::new(0)]).collect(),
+            history: (0..2 * MAX_BOARD_SIZE * MAX_BOARD_SIZE).map(|_| AtomicU32::new(0)).collect(),
+            nodes: AtomicU64::new(0),
+            cutoffs: AtomicU64::new(0),
+            first_move_cutoffs: AtomicU64::new(0),
+        }
+    }
+
+    fn expired(&self) -> bool {
+        if self.expired.load(Ordering::Relaxed) {
+            return true;
+        }
+        let expired = self.deadline.is_some_and(|deadline| Instant::now() >= deadline) || self.cancelled();
+        if expired {
+            self.expired.store(true, Ordering::Relaxed);
+        }
+        expired
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(CancelFlag::is_cancelled)
+    }
+
+    fn history_slot(&self, player: Player, position: Position) -> &AtomicU32 {
+        &self.history[player as usize * MAX_BOARD_SIZE * MAX_BOARD_SIZE + square_index(position)]
+    }
+
+    /// キラー手、履歴の点数、マスの重み（隅が先）の順に有望な手から並べる
+    fn order_moves(&self, moves: &mut [Position], player: Player, depth: u8, size: usize) {
+        let killers = self.killers.get(depth as usize).map(|slots| slots.each_ref().map(|slot| slot.load(Ordering::Relaxed)));
+        moves.sort_by_cached_key(|&position| {
+            let code = square_index(position) as u8 + 1;
+            let killer_rank = match killers {
+                Some([first, _]) if first == code => 2,
+                Some([_, second]) if second == code => 1,
+                _ => 0,
+            };
+            let history = self.history_slot(player, position).load(Ordering::Relaxed);
+            std::cmp::Reverse((killer_rank, history, position_weight(position, size)))
+        });
+    }
+
+    /// βカットを起こした手をキラー手と履歴に記録する
+    fn record_cutoff(&self, player: Player, position: Position, depth: u8, move_index: usize) {
+        self.cutoffs.fetch_add(1, Ordering::Relaxed);
+        if move_index == 0 {
+            self.first_move_cutoffs.fetch_add(1, Ordering::Relaxed);
+        }
+        self.history_slot(player, position).fetch_add(depth as u32 * depth as u32, Ordering::Relaxed);
+        if let Some([first, second]) = self.killers.get(depth as usize) {
+            let code = square_index(position) as u8 + 1;
+            let previous = first.swap(code, Ordering::Relaxed);
+            if previous != code {
+                second.store(previous, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn stats(&self, depth_reached: u8) -> SearchStats {
+        SearchStats {
+            depth_reached,
+            nodes: self.nodes.load(Ordering::Relaxed),
+            cutoffs: self.cutoffs.load(Ordering::Relaxed),
+            first_move_cutoffs: self.first_move_cutoffs.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl RootResult {
+    fn into_outcome(self, stats: SearchStats) -> SearchOutcome {
+        SearchOutcome {
+            position: self.position,
+            stats: Some(stats),
+            candidates: self
+                .candidates
+                .into_iter()
+                .map(|(position, score)| ScoredMove { position, score: score as f64 / 100.0 })
+                .collect(),
+            principal_variation: self.principal_variation,
+        }
+    }
+}
+
+/// 評価値の上限（終局時の評価はこれより小さい）
+const SCORE_INFINITY: i32 = 1_000_000;
+
+/// 終局時の評価値の基準（石差を加えて静的評価より必ず大きくする）
+const SCORE_WIN: i32 = 100_000;
+
+/// 反復深化で前の深さの評価値の周りに置く探索窓の半分の幅（評価値の100倍の単位）
+const ASPIRATION_WINDOW: i32 = 500;
+
+/// 探索窓を絞らない全幅の窓
+const FULL_WINDOW: (i32, i32) = (-SCORE_INFINITY, SCORE_INFINITY);
+
+impl AlphaBetaAI {
+    /// 指定した探索深度で新しいAlphaBetaAIを作成する
+    pub fn new(depth: u8) -> Self {
+        AlphaBetaAI {
+            depth,
+            threads: 0,
+            noise: 0,
+            seed: None,
+            time_limit: None,
+            weights: EvalWeights::default(),
+            multipv: 0,
+            cancel: None,
+            variant: GameVariant::Standard,
+        }
+    }
+
+    /// ルートの並列探索に使うスレッド数を指定する
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// ルートの各手の評価値に `-noise..=noise` の揺らぎを加え、弱く・手が散らばるようにする
+    pub fn with_noise(mut self, noise: i32, seed: Option<u64>) -> Self {
+        self.noise = noise.max(0);
+        self.seed = seed;
+        self
+    }
+
+    /// 深さ1から反復深化し、時間の上限を過ぎたら完了した最も深い探索の結果を使う
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    /// 静的評価の重み係数を指定する
+    pub fn with_weights(mut self, weights: EvalWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// 中断フラグが立ったら探索を打ち切る（反復深化では深さの区切りでも確かめる）
+    pub fn with_cancel(mut self, cancel: CancelFlag) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// 評価値の高い順に `multipv` 個の候補手を求める
+    /// 最善以外の手も正確な評価値が要るため、ルートの枝刈りが減って探索は遅くなる
+    pub fn with_multipv(mut self, multipv: usize) -> Self {
+        self.multipv = multipv;
+        self
+    }
+
+    /// 手番側から見た局面の評価値（`depth` 手先までのαβ探索、静的評価と同じ単位）
+    /// 終局まで読み切れた場合は勝敗に応じて ±1000 に石差を加えた値になる
+    pub fn evaluate_state(&self, game_state: &GameState) -> f32 {
+        let searcher = AlphaBetaAI { variant: game_state.variant, ..self.clone() };
+        let context = SearchContext::new(None, None, self.depth);
+        let score = searcher.negamax(&game_state.board, game_state.current_player, self.depth, FULL_WINDOW, &context, &mut Vec::new());
+        score as f32 / 100.0
+    }
+
+    /// 着手後の盤面を返す
+    fn play(board: &Board, position: Position, player: Player) -> Board {
+        let mut next = board.clone();
+        for flipped in ReversiRules::get_flipped_positions(board, position, player) {
+            next.set_cell(flipped, player.to_cell());
+        }
+        next.set_cell(position, player.to_cell());
+        next
+    }
+
+    /// 手番側から見た静的評価値（評価関数の値を100倍して整数にする）
+    fn evaluate(&self, board: &Board, player: Player) -> i32 {
+        (BoardEvaluator::evaluate_for_variant(board, player, &self.weights, self.variant) * 100.0).round() as i32
+    }
+
+    /// ネガマックス形式のαβ探索（手番側から見た評価値を返す）
+    /// 2手目以降はnull window（幅1の窓）で最初の手より良いかだけを調べ、良い場合のみ窓を広げて探索し直す（PVS）
+    /// 窓の外の評価値もそのまま返す（fail-soft）ため、窓を外れた側の境界として使える
+    /// `line` にはこの局面からの読み筋を入れる（評価値が窓の中に収まった場合のみ正しい）
+    /// 時間切れの場合は途中で0を返す（その深さの結果は使わない）
+    fn negamax(&self, board: &Board, player: Player, depth: u8, (mut alpha, beta): (i32, i32), context: &SearchContext, line: &mut Vec<Position>) -> i32 {
+        line.clear();
+        if depth >= 2 && context.expired() {
+            return 0;
+        }
+        context.nodes.fetch_add(1, Ordering::Relaxed);
+        let mut moves = ReversiRules::get_valid_moves(board, player);
+        if moves.is_empty() {
+            if !ReversiRules::has_valid_moves(board, player.opposite()) {
+                let margin = BoardEvaluator::evaluate_piece_count(board, player) as i32 * self.variant.score_sign();
+                return match margin.signum() {
+                    1 => SCORE_WIN + margin,
+                    -1 => -SCORE_WIN + margin,
+                    _ => 0,
+                };
+            }
+            // パスは手数に数えない（読み筋にも含めない）
+            return -self.negamax(board, player.opposite(), depth, (-beta, -alpha), context, line);
+        }
+        if depth == 0 {
+            return self.evaluate(board, player);
+        }
+
+        context.order_moves(&mut moves, player, depth, board.size());
+        let mut best = -SCORE_INFINITY;
+        let mut child_line = Vec::new();
+        for (index, position) in moves.into_iter().enumerate() {
+            let child = Self::play(board, position, player);
+            let opponent = player.opposite();
+            let score = if index == 0 {
+                -self.negamax(&child, opponent, depth - 1, (-beta, -alpha), context, &mut child_line)
+            } else {
+                let score = -self.negamax(&child, opponent, depth - 1, (-alpha - 1, -alpha), context, &mut child_line);
+                if score > alpha && score < beta {
+                    // 真の評価値はscore以上のため、下限をscoreの1つ下にして探索し直す
+                    -self.negamax(&child, opponent, depth - 1, (-beta, -score + 1), context, &mut child_line)
+                } else {
+                    score
+                }
+            };
+            best = best.max(score);
+            if score >= beta {
+                context.record_cutoff(player, position, depth, index);
+                return best;
+            }
+            if score > alpha {
+                alpha = score;
+                line.clear();
+                line.push(position);
+                line.extend_from_slice(&child_line);
+            }
+        }
+        best
+    }
+
+    /// 手ごとの揺らぎ（`salt` は局面ごとの値）
+    fn noise_for(&self, salt: u64, position: Position) -> i32 {
+        if self.noise == 0 {
+            return 0;
+        }
+        let span = 2 * self.noise as u64 + 1;
+        (splitmix64(salt ^ (position.row * 8 + position.col) as u64) % span) as i32 - self.noise
+    }
+
+    /// ルートの各手を探索し、揺らぎを加えた評価が最も高い手と評価値、上位の候補手を返す
+    /// 評価値が窓 `(low, high)` の外になった場合、評価値は窓の外の境界になり手は正しくない（窓を広げて探索し直す）
+    fn search_root(&self, root: &RootPosition, depth: u8, context: &SearchContext, (low, high): (i32, i32)) -> RootResult {
+        let RootPosition { board, player, moves, salt } = *root;
+        let child_depth = depth.saturating_sub(1);
+        // 手の評価値と、その手から始まる読み筋
+        let search = |position: Position, alpha: i32| {
+            let mut line = Vec::new();
+            let score = -self.negamax(&Self::play(board, position, player), player.opposite(), child_depth, (-high, -alpha), context, &mut line);
+            line.insert(0, position);
+            (score, line)
+        };
+
+        let (first, first_line) = search(moves[0], low);
+        // これまでの上位 `multipv` 個（最低1個）の評価値（降順）
+        let count = self.multipv.max(1);
+        let top = Mutex::new(vec![first]);
+        // 下限を上位で最も低い評価値の1つ下にして探索し、同点の手も正確な評価値を得る（並列でも結果が変わらないようにする）
+        // 揺らぎを加える場合は最善でない手の評価値も要るため、全ての手を窓を狭めずに探索する
+        let search_sibling = |(index, position): (usize, &Position)| {
+            let alpha = if self.noise > 0 {
+                -SCORE_INFINITY
+            } else {
+                top.lock().unwrap().get(count - 1).map_or(low, |lowest| (lowest - 1).max(low))
+            };
+            let (score, line) = search(*position, alpha);
+            (score > alpha).then(|| {
+                let mut top = top.lock().unwrap();
+                top.push(score);
+                top.sort_unstable_by(|a, b| b.cmp(a));
+                top.truncate(count);
+                (index + 1, score, line)
+            })
+        };
+        let siblings: Vec<Option<(usize, i32, Vec<Position>)>> = self.run_siblings(&moves[1..], &search_sibling);
+
+        let mut scored: Vec<(usize, i32, Vec<Position>)> =
+            std::iter::once((0, first, first_line)).chain(siblings.into_iter().flatten()).collect();
+        let chosen = scored
+            .iter()
+            .enumerate()
+            .map(|(entry, &(index, score, _))| (entry, index, score + self.noise_for(salt, moves[index])))
+            .max_by(|a, b| a.2.cmp(&b.2).then(b.1.cmp(&a.1)))
+            .map(|(entry, ..)| entry)
+            .expect("first move is always searched");
+        let mut candidates: Vec<(usize, i32)> = scored.iter().map(|&(index, score, _)| (index, score)).collect();
+        candidates.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        candidates.truncate(self.multipv);
+        let (index, _, principal_variation) = scored.swap_remove(chosen);
+        RootResult {
+            position: moves[index],
+            score: top.into_inner().unwrap()[0],
+            candidates: candidates.into_iter().map(|(index, score)| (moves[index], score)).collect(),
+            principal_variation,
+        }
+    }
+
+    /// 前の評価値の周りの窓でルートを探索し、評価値が窓の外なら外れた側だけ窓を広げて探索し直す
+    /// 揺らぎを加える場合や複数の候補手を求める場合は、最善でない手の正確な評価値も要るため窓を絞らない
+    fn search_root_aspirated(&self, root: &RootPosition, depth: u8, context: &SearchContext, previous: i32) -> RootResult {
+        if self.noise > 0 || self.multipv > 1 {
+            return self.search_root(root, depth, context, FULL_WINDOW);
+        }
+        let (low, high) = (previous - ASPIRATION_WINDOW, previous + ASPIRATION_WINDOW);
+        let result = self.search_root(root, depth, context, (low, high));
+        if result.score <= low {
+            // 真の評価値はscore以下
+            return self.search_root(root, depth, context, (-SCORE_INFINITY, result.score + 1));
+        }
+        if result.score >= high {
+            // 真の評価値はscore以上
+            return self.search_root(root, depth, context, (result.score - 1, SCORE_INFINITY));
+        }
+        result
+    }
+
+    #[cfg(feature = "parallel")]
+    fn run_siblings<T, F>(&self, moves: &[Position], search: &F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn((usize, &Position)) -> T + Sync,
+    {
+        use rayon::prelude::*;
+
+        if self.threads == 1 {
+            return moves.iter().enumerate().map(search).collect();
+        }
+        search_pool(self.threads).install(|| moves.par_iter().enumerate().map(search).collect())
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn run_siblings<T, F>(&self, moves: &[Position], search: &F) -> Vec<T>
+    where
+        F: Fn((usize, &Position)) -> T,
+    {
+        moves.iter().enumerate().map(search).collect()
+    }
+}
+
+/// スレッド数ごとの探索用スレッドプール（対局ごとに作り直さないよう共有する）
+#[cfg(feature = "parallel")]
+fn search_pool(threads: usize) -> std::sync::Arc<rayon::ThreadPool> {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex, OnceLock};
+
+    static POOLS: OnceLock<Mutex<HashMap<usize, Arc<rayon::ThreadPool>>>> = OnceLock::new();
+    let mut pools = POOLS.get_or_init(Default::default).lock().unwrap();
+    Arc::clone(pools.entry(threads).or_insert_with(|| {
+        Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name(|index| format!("alphabeta-{}", index))
+                .build()
+                .expect("failed to build search thread pool"),
+        )
+    }))
+}
+
+impl AIStrategy for AlphaBetaAI {
+    /// αβ法で最適手を計算する
+    fn calculate_move(&self, game_state: &GameState) -> Result<Position, AIError> {
+        self.search(game_state).map(|outcome| outcome.position)
+    }
+
+    fn calculate_move_with_stats(&self, game_state: &GameState) -> Result<(Position, Option<SearchStats>), AIError> {
+        self.search(game_state).map(|outcome| (outcome.position, outcome.stats))
+    }
+
+    fn search(&self, game_state: &GameState) -> Result<SearchOutcome, AIError> {
+        if game_state.is_finished() {
+            return Err(AIError::StrategyError {
+                message: "Cannot calculate move for finished game".to_string(),
+            });
+        }
+
+        if game_state.variant != self.variant {
+            return AlphaBetaAI { variant: game_state.variant, ..self.clone() }.search(game_state);
+        }
+
+        let player = game_state.current_player;
+        let mut moves = ReversiRules::get_valid_moves(&game_state.board, player);
+        if moves.is_empty() {
+            return Err(AIError::NoValidMoves);
+        }
+        // 1手先の静的評価で並べ替え、有望な手から探索して枝刈りを増やす
+        moves.sort_by_cached_key(|position| {
+            std::cmp::Reverse(self.evaluate(&Self::play(&game_state.board, *position, player), player))
+        });
+
+        let ply = (game_state.get_move_count() as u64) << 1 | player as u64;
+        // シードがなければ揺らぎは毎回変える
+        let salt = self.seed.map_or_else(rand::random, |seed| splitmix64(seed ^ splitmix64(ply)));
+        let root = RootPosition { board: &game_state.board, player, moves: &moves, salt };
+        let Some(time_limit) = self.time_limit else {
+            let context = SearchContext::new(None, self.cancel.clone(), self.depth);
+            let result = self.search_root(&root, self.depth, &context, FULL_WINDOW);
+            if context.cancelled() {
+                return Err(AIError::Cancelled);
+            }
+            return Ok(result.into_outcome(context.stats(self.depth)));
+        };
+
+        // 深さ1は時間に関係なく探索し（葉は時間切れを判定しない）、以降は時間内に完了した最も深い探索の結果を使う
+        // キラー手と履歴の表は深さを増やしても引き継ぎ、前の深さで枝刈りできた手から調べる
+        // 深さ2以降は前の評価値の周りに窓を絞って探索する（aspiration window）
+        // 中断された場合は途中までの結果を使わない
+        let context = SearchContext::new(Some(Instant::now() + time_limit), self.cancel.clone(), self.depth);
+        let mut best = self.search_root(&root, 1, &context, FULL_WINDOW);
+        let mut scores = vec![best.score];
+        let mut depth_reached = 1;
+        for depth in 2..=self.depth {
+            if context.cancelled() {
+                return Err(AIError::Cancelled);
+            }
+            // 手番の偶奇で評価値が大きく揺れるため、2つ前の深さ（同じ偶奇）の評価値を窓の中心にする
+            let previous = scores[scores.len().saturating_sub(2)];
+            let result = self.search_root_aspirated(&root, depth, &context, previous);
+            if context.cancelled() {
+                return Err(AIError::Cancelled);
+            }
+            if context.expired() {
+                break;
+            }
+            scores.push(result.score);
+            best = result;
+            depth_reached = depth;
+        }
+        Ok(best.into_outcome(context.stats(depth_reached)))
+    }
+    
+    fn get_difficulty(&self) -> Difficulty {
+        Difficulty::Advanced
+    }
+    
+    fn get_name(&self) -> &'static str {
+        "AlphaBetaAI"
+    }
+}
+
+/// 難易度に応じたAI戦略を生成するファクトリ関数
+/// 難易度に応じて適切なAI実装を選択して返す
+pub fn create_ai_strategy(difficulty: Difficulty) -> Box<dyn AIStrategy> {
+    create_seeded_ai_strategy(difficulty, None)
+}
+
+/// シードを指定してAI戦略を生成する
+/// 乱択を行う戦略のみシードを使用し、それ以外は `create_ai_strategy` と同じ
+pub fn create_seeded_ai_strategy(difficulty: Difficulty, seed: Option<u64>) -> Box<dyn AIStrategy> {
+    create_configured_ai_strategy(difficulty, seed, 0)
+}
+
+/// シードと探索スレッド数（0はCPU数）を指定してAI戦略を生成する
+pub fn create_configured_ai_strategy(difficulty: Difficulty, seed: Option<u64>, search_threads: usize) -> Box<dyn AIStrategy> {
+    match difficulty {
+        Difficulty::Beginner => Box::new(RandomAI { seed }),
+        Difficulty::Intermediate => Box::new(MinimaxAI::new(3)),  // 深度3手
+        Difficulty::Advanced => Box::new(AlphaBetaAI::new(6).with_threads(search_threads)),  // 深度6手
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Cell, GameState};
+
+    #[test]
+    fn test_difficulty_deserialize_case_insensitive() {
+        assert_eq!(serde_json::from_str::<Difficulty>(r#""Beginner""#).unwrap(), Difficulty::Beginner);
+        assert_eq!(serde_json::from_str::<Difficulty>(r#""intermediate""#).unwrap(), Difficulty::Intermediate);
+        assert_eq!(serde_json::from_str::<Difficulty>(r#""HARD""#).unwrap(), Difficulty::Advanced);
+        assert!(serde_json::from_str::<Difficulty>(r#""expert""#).is_err());
+    }
+
+    #[test]
+    fn test_random_ai_creation() {
+        let ai = RandomAI::new();
+        assert_eq!(ai.get_name(), "RandomAI");
+        assert!(matches!(ai.get_difficulty(), Difficulty::Beginner));
+    }
+
+    #[test]
+    fn test_random_ai_calculate_move() {
+        let game_state = GameState::new();
+        let ai = RandomAI::new();
+        
+        let result = ai.calculate_move(&game_state);
+        assert!(result.is_ok());
+        
+        let position = result.unwrap();
+        assert!(position.is_valid());
+        
+        assert!(ReversiRules::is_valid_move(&game_state.board, position, game_state.current_player));
+        
+        // シードがなければ同じ局面でも毎回同じ手になるとは限らない
+        let choices: std::collections::HashSet<_> = (0..64)
+            .map(|_| RandomAI::new().calculate_move(&game_state).unwrap())
+            .collect();
+        assert!(choices.len() > 1);
+    }
+
+    #[test]
+    fn test_seeded_random_ai_is_reproducible() {
+        let game_state = GameState::new();
+        
+        for seed in 0..16 {
+            let first = RandomAI::with_seed(seed).calculate_move(&game_state).unwrap();
+            let second = RandomAI::with_seed(seed).calculate_move(&game_state).unwrap();
+            assert_eq!(first, second);
+            assert!(ReversiRules::is_valid_move(&game_state.board, first, game_state.current_player));
+        }
+        
+        let choices: std::collections::HashSet<_> = (0..16)
+            .map(|seed| RandomAI::with_seed(seed).calculate_move(&game_state).unwrap())
+            .collect();
+        assert!(choices.len() > 1);
+    }
+
+    #[test]
+    fn test_random_ai_finished_game() {
+        let mut game_state = GameState::new();
+        game_state.finish(Some(Player::Black));
+        
+        let ai = RandomAI::new();
+        let result = ai.calculate_move(&game_state);
+        
+        assert!(result.is_err());
+        if let Err(AIError::StrategyError { message }) = result {
+            assert!(message.contains("finished game"));
+        } else {
+            panic!("Expected StrategyError for finished game");
+        }
+    }
+
+    #[test]
+    fn test_greedy_ai_maximizes_flips() {
+        // 黒がc1に置くとb1とc2の2石、他の手は1石しか返せない局面
+        let mut game_state = GameState::new();
+        for position in game_state.board.positions().collect::<Vec<_>>() {
+            game_state.board.set_cell(position, Cell::Empty);
+        }
+        for (row, col, cell) in [(0, 0, Cell::Black), (0, 1, Cell::White), (1, 2, Cell::White), (2, 2, Cell::Black), (5, 5, Cell::White), (6, 6, Cell::Black)] {
+            game_state.board.set_cell(Position::new(row, col).unwrap(), cell);
+        }
+        let ai = GreedyAI::new();
+        assert_eq!(ai.get_name(), "GreedyAI");
+        assert!(matches!(ai.get_difficulty(), Difficulty::Beginner));
+        assert_eq!(ai.calculate_move(&game_state).unwrap(), Position::new(0, 2).unwrap());
+
+        // 同じ枚数を返す手が複数ある初期局面でも、シードがあれば再現できる
+        let start = GameState::new();
+        assert_eq!(GreedyAI::with_seed(3).calculate_move(&start).unwrap(), GreedyAI::with_seed(3).calculate_move(&start).unwrap());
+    }
+
+    #[test]
+    fn test_positional_ai_prefers_corner_and_avoids_x_square() {
+        assert_eq!(position_weight(Position::new(0, 0).unwrap(), 8), 100);
+        assert_eq!(position_weight(Position::new(6, 6).unwrap(), 8), -50);
+        assert_eq!(position_weight(Position::new(4, 3).unwrap(), 8), -1);
+        assert_eq!(position_weight(Position::new(5, 5).unwrap(), 6), 100);
+
+        // 黒がa1（隅）とb2の隣を取れる局面（a1で返すのはb2の1石）
+        let mut game_state = GameState::new();
+        for (row, col, cell) in [(1, 1, Cell::White), (2, 2, Cell::Black)] {
+            game_state.board.set_cell(Position::new(row, col).unwrap(), cell);
+        }
+        let corner = Position::new(0, 0).unwrap();
+        assert!(ReversiRules::is_valid_move(&game_state.board, corner, Player::Black));
+        let ai = PositionalAI::with_seed(1);
+        assert_eq!(ai.get_name(), "PositionalAI");
+        assert_eq!(ai.calculate_move(&game_state).unwrap(), corner);
+
+        let mut finished = GameState::new();
+        finished.finish(None);
+        assert!(PositionalAI::new().calculate_move(&finished).is_err());
+    }
+
+    #[test]
+    fn test_minimax_ai_creation() {
+        let ai = MinimaxAI::new(5);
+        assert_eq!(ai.depth, 5);
+        assert_eq!(ai.get_name(), "MinimaxAI");
+        assert!(matches!(ai.get_difficulty(), Difficulty::Intermediate));
+    }
+
+    #[test]
+    fn test_minimax_ai_not_implemented() {
+        let game_state = GameState::new();
+        let ai = MinimaxAI::new(3);
+        
+        let result = ai.calculate_move(&game_state);
+        assert!(result.is_err());
+        
+        if let Err(AIError::StrategyError { message }) = result {
+            assert!(message.contains("not yet implemented"));
+        } else {
+            panic!("Expected StrategyError for unimplemented MinimaxAI");
+        }
+    }
+
+    #[test]
+    fn test_alphabeta_ai_creation() {
+        let ai = AlphaBetaAI::new(7);
+        assert_eq!(ai.depth, 7);
+        assert_eq!(ai.get_name(), "AlphaBetaAI");
+        assert!(matches!(ai.get_difficulty(), Difficulty::Advanced));
+    }
+
+    #[test]
+    fn test_alphabeta_ai_takes_corner_and_is_thread_independent() {
+        // 黒がa1を取れる局面（白のb2を挟む）
+        let mut game_state = GameState::new();
+        for (row, col) in [(3, 3), (3, 4), (4, 3), (4, 4)] {
+            game_state.board.set_cell(Position::new(row, col).unwrap(), Cell::Empty);
+        }
+        for (row, col, cell) in [(1, 1, Cell::White), (2, 2, Cell::Black), (3, 3, Cell::White), (4, 4, Cell::Black), (3, 4, Cell::White)] {
+            game_state.board.set_cell(Position::new(row, col).unwrap(), cell);
+        }
+        let corner = Position::new(0, 0).unwrap();
+        assert!(ReversiRules::is_valid_move(&game_state.board, corner, Player::Black));
+        assert_eq!(AlphaBetaAI::new(3).with_threads(1).calculate_move(&game_state).unwrap(), corner);
+
+        let mut midgame = GameState::new();
+        for _ in 0..12 {
+            let position = RandomAI::with_seed(9).calculate_move(&midgame).unwrap();
+            ReversiRules::apply_move(&mut midgame, position).unwrap();
+            midgame.switch_player();
+            ReversiRules::handle_turn(&mut midgame);
+        }
+        let sequential = AlphaBetaAI::new(4).with_threads(1).calculate_move(&midgame).unwrap();
+        for threads in [2, 4] {
+            assert_eq!(AlphaBetaAI::new(4).with_threads(threads).calculate_move(&midgame).unwrap(), sequential);
+        }
+    }
+
+    #[test]
+    fn test_alphabeta_reports_search_stats() {
+        let mut midgame = GameState::new();
+        for _ in 0..16 {
+            let position = RandomAI::with_seed(4).calculate_move(&midgame).unwrap();
+            ReversiRules::apply_move(&mut midgame, position).unwrap();
+            midgame.switch_player();
+            ReversiRules::handle_turn(&mut midgame);
+        }
+        let (position, stats) = AlphaBetaAI::new(5).with_threads(1).calculate_move_with_stats(&midgame).unwrap();
+        let stats = stats.unwrap();
+        assert!(ReversiRules::is_valid_move(&midgame.board, position, midgame.current_player));
+        assert_eq!(stats.depth_reached, 5);
+        assert!(stats.cutoffs > 0 && stats.cutoffs < stats.nodes);
+        // キラー手・履歴・隅を優先する並べ替えで、βカットの大半は最初に調べた手で起きる
+        assert!(stats.first_move_cutoffs * 2 > stats.cutoffs, "{:?}", stats);
+
+        let (_, timed) = AlphaBetaAI::new(5).with_threads(1).with_time_limit(Duration::from_secs(60)).calculate_move_with_stats(&midgame).unwrap();
+        assert_eq!(timed.unwrap().depth_reached, 5);
+        assert_eq!(RandomAI::with_seed(1).calculate_move_with_stats(&midgame).unwrap().1, None);
+    }
+
+    /// 枝刈りをしないネガマックス（探索の打ち切り方を変えても評価値が変わらないことの確認用）
+    fn plain_negamax(ai: &AlphaBetaAI, board: &Board, player: Player, depth: u8) -> i32 {
+        let moves = ReversiRules::get_valid_moves(board, player);
+        if moves.is_empty() {
+            if !ReversiRules::has_valid_moves(board, player.opposite()) {
+                let margin = BoardEvaluator::evaluate_piece_count(board, player) as i32;
+                return if margin == 0 { 0 } else { margin.signum() * SCORE_WIN + margin };
+            }
+            return -plain_negamax(ai, board, player.opposite(), depth);
+        }
+        if depth == 0 {
+            return ai.evaluate(board, player);
+        }
+        moves
+            .into_iter()
+            .map(|position| -plain_negamax(ai, &AlphaBetaAI::play(board, position, player), player.opposite(), depth - 1))
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_principal_variation_search_and_aspiration_keep_results() {
+        let mut midgame = GameState::new();
+        for _ in 0..20 {
+            let position = RandomAI::with_seed(11).calculate_move(&midgame).unwrap();
+            ReversiRules::apply_move(&mut midgame, position).unwrap();
+            midgame.switch_player();
+            ReversiRules::handle_turn(&mut midgame);
+        }
+        let ai = AlphaBetaAI::new(4);
+        for depth in 1..=4 {
+            let context = SearchContext::new(None, None, depth);
+            let score = ai.negamax(&midgame.board, midgame.current_player, depth, FULL_WINDOW, &context, &mut Vec::new());
+            assert_eq!(score, plain_negamax(&ai, &midgame.board, midgame.current_player, depth), "depth {}", depth);
+        }
+
+        // 窓を絞った反復深化でも、最後の深さを全幅で一度に探索した場合と同じ手を選ぶ
+        for depth in [3, 5] {
+            let direct = AlphaBetaAI::new(depth).with_threads(1).calculate_move(&midgame).unwrap();
+            let iterative = AlphaBetaAI::new(depth).with_threads(1).with_time_limit(Duration::from_secs(60)).calculate_move(&midgame).unwrap();
+            assert_eq!(iterative, direct, "depth {}", depth);
+        }
+    }
+
+    #[test]
+    fn test_principal_variation_leads_to_the_searched_score() {
+        let mut midgame = GameState::new();
+        for _ in 0..12 {
+            let position = RandomAI::with_seed(3).calculate_move(&midgame).unwrap();
+            ReversiRules::apply_move(&mut midgame, position).unwrap();
+            midgame.switch_player();
+            ReversiRules::handle_turn(&mut midgame);
+        }
+        let root_player = midgame.current_player;
+        for ai in [AlphaBetaAI::new(4).with_threads(1), AlphaBetaAI::new(5).with_time_limit(Duration::from_secs(60))] {
+            let outcome = ai.clone().with_multipv(1).search(&midgame).unwrap();
+            let line = &outcome.principal_variation;
+            assert_eq!(line.len(), ai.depth as usize);
+            assert_eq!(line[0], outcome.position);
+
+            // 読み筋を最後まで指した局面の静的評価が、探索の評価値になる
+            let mut leaf = midgame.clone();
+            for &position in line {
+                ReversiRules::apply_move(&mut leaf, position).unwrap();
+                leaf.switch_player();
+                ReversiRules::handle_turn(&mut leaf);
+            }
+            let leaf_score = ai.evaluate(&leaf.board, leaf.current_player);
+            let expected = if leaf.current_player == root_player { leaf_score } else { -leaf_score };
+            assert_eq!(expected as f64 / 100.0, outcome.candidates[0].score);
+        }
+        assert!(RandomAI::with_seed(1).search(&midgame).unwrap().principal_variation.is_empty());
+    }
+
+    #[test]
+    fn test_multipv_returns_exact_scores_of_best_moves() {
+        let mut midgame = GameState::new();
+        for _ in 0..16 {
+            let position = RandomAI::with_seed(5).calculate_move(&midgame).unwrap();
+            ReversiRules::apply_move(&mut midgame, position).unwrap();
+            midgame.switch_player();
+            ReversiRules::handle_turn(&mut midgame);
+        }
+        let (board, player) = (&midgame.board, midgame.current_player);
+        let ai = AlphaBetaAI::new(3).with_threads(1);
+        let mut expected: Vec<(Position, i32)> = ReversiRules::get_valid_moves(board, player)
+            .into_iter()
+            .map(|position| (position, -plain_negamax(&ai, &AlphaBetaAI::play(board, position, player), player.opposite(), 2)))
+            .collect();
+        expected.sort_by(|a, b| b.1.cmp(&a.1));
+        assert!(expected.len() > 3);
+
+        let outcome = ai.clone().with_multipv(3).search(&midgame).unwrap();
+        let scores: Vec<f64> = outcome.candidates.iter().map(|candidate| candidate.score).collect();
+        let expected_scores: Vec<f64> = expected[..3].iter().map(|&(_, score)| score as f64 / 100.0).collect();
+        assert_eq!(scores, expected_scores);
+        assert_eq!(outcome.candidates[0].position, outcome.position);
+        assert_eq!(outcome.position, ai.calculate_move(&midgame).unwrap());
+
+        // 候補手を求めなければ空、反復深化でも同じ候補手になる
+        assert!(ai.search(&midgame).unwrap().candidates.is_empty());
+        let timed = ai.clone().with_multipv(3).with_time_limit(Duration::from_secs(60)).search(&midgame).unwrap();
+        assert_eq!(timed.candidates, outcome.candidates);
+        assert!(RandomAI::with_seed(1).search(&midgame).unwrap().candidates.is_empty());
+    }
+
+    #[test]
+    fn test_alphabeta_evaluate_state_is_from_side_to_move() {
+        // 黒がa1の隅を持つ局面
+        let mut black_to_move = GameState::new();
+        black_to_move.board.set_cell(Position::new(0, 0).unwrap(), Cell::Black);
+        let mut white_to_move = black_to_move.clone();
+        white_to_move.switch_player();
+
+        let ai = AlphaBetaAI::new(1);
+        assert!(ai.evaluate_state(&black_to_move) > 0.0);
+        assert!(ai.evaluate_state(&white_to_move) < 0.0);
+    }
+
+    #[test]
+    fn test_cancelled_search_stops_without_a_result() {
+        // 深さ12は打ち切らなければ終わらない
+        for timed in [false, true] {
+            let cancel = CancelFlag::new();
+            let mut ai = AlphaBetaAI::new(12).with_threads(1).with_cancel(cancel.clone());
+            if timed {
+                ai = ai.with_time_limit(Duration::from_secs(600));
+            }
+            let started = Instant::now();
+            let search = std::thread::spawn(move || ai.search(&GameState::new()));
+            std::thread::sleep(Duration::from_millis(50));
+            cancel.cancel();
+            assert!(matches!(search.join().unwrap(), Err(AIError::Cancelled)));
+            assert!(started.elapsed() < Duration::from_secs(5));
+        }
+
+        // 立っていなければ結果は変わらない
+        let game_state = GameState::new();
+        let plain = AlphaBetaAI::new(3).with_threads(1).calculate_move(&game_state).unwrap();
+        let cancellable = AlphaBetaAI::new(3).with_threads(1).with_cancel(CancelFlag::new()).calculate_move(&game_state).unwrap();
+        assert_eq!(cancellable, plain);
+    }
+
+    #[test]
+    fn test_create_ai_strategy_factory() {
+        let beginner = create_ai_strategy(Difficulty::Beginner);
+        assert_eq!(beginner.get_name(), "RandomAI");
+        
+        let intermediate = create_ai_strategy(Difficulty::Intermediate);
+        assert_eq!(intermediate.get_name(), "MinimaxAI");
+        
+        let advanced = create_ai_strategy(Difficulty::Advanced);
+        assert_eq!(advanced.get_name(), "AlphaBetaAI");
+    }
+    
+    #[test]
+    fn test_ai_strategy_trait_object() {
+        let ai: Box<dyn AIStrategy> = Box::new(RandomAI::new());
+        let game_state = GameState::new();
+        
+        let result = ai.calculate_move(&game_state);
+        assert!(result.is_ok());
+    }
+}
\ No newline at end of file
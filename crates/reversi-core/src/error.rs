@@ -2,14 +2,18 @@
 //! ゲームロジック、AIサービス、永続化などのエラーを統一管理。
 
 use thiserror::Error;
+#[cfg(feature = "std")]
 use uuid::Uuid;
 
+use crate::prelude::*;
+
 /// ゲームロジックに関連するエラー
 #[derive(Debug, Error)]
 pub enum GameError {
     #[error("Invalid move: {reason}")]
     InvalidMove { reason: String },
     
+    #[cfg(feature = "std")]
     #[error("Game not found: {game_id}")]
     GameNotFound { game_id: Uuid },
     
@@ -22,6 +26,7 @@ pub enum GameError {
         source: AIError 
     },
     
+    #[cfg(feature = "std")]
     #[error("Persistence error: {source}")]
     PersistenceError { 
         #[from]
@@ -52,9 +57,18 @@ pub enum AIError {
     
     #[error("AI service configuration error: {message}")]
     ConfigurationError { message: String },
+    
+    /// 計算待ちのキューが満杯
+    #[error("AI service overloaded, retry after {retry_after_secs}s")]
+    Overloaded { retry_after_secs: u64 },
+    
+    /// 中断フラグが立ち、探索を打ち切った
+    #[error("AI calculation cancelled")]
+    Cancelled,
 }
 
 /// データ永続化に関連するエラー
+#[cfg(feature = "std")]
 #[derive(Debug, Error)]
 pub enum PersistenceError {
     #[error("Database error: {message}")]
@@ -71,4 +85,4 @@ pub enum PersistenceError {
 }
 
 /// ゲームエラーをベースとした結果型
-pub type Result<T> = std::result::Result<T, GameError>;
\ No newline at end of file
+pub type Result<T> = core::result::Result<T, GameError>;
\ No newline at end of file
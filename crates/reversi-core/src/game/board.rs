@@ -0,0 +1,369 @@
+//! リバーシゲームの盤面状態を管理するモジュール
+//! 盤面（標準は8x8、6x6と10x10も扱える）と石の配置、操作を担当する。
+
+use super::types::{Cell, Position};
+use serde::{Deserialize, Serialize};
+use crate::prelude::*;
+
+/// 標準の盤面の一辺のマス数
+pub const DEFAULT_BOARD_SIZE: usize = 8;
+
+/// 扱える最大の盤面の一辺のマス数
+pub const MAX_BOARD_SIZE: usize = 10;
+
+/// 対局に使える盤面の一辺のマス数
+pub const SUPPORTED_BOARD_SIZES: [usize; 3] = [6, DEFAULT_BOARD_SIZE, MAX_BOARD_SIZE];
+
+/// リバーシ盤面を表現する構造体
+/// 各マスのCell状態を保持し、盤面操作を提供する
+/// 石に隣接する空きマス（フロンティア）を石の配置と同時に更新し、合法手の候補の列挙に使う
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "BoardCells", into = "BoardCells")]
+pub struct Board {
+    /// 盤面の外側のマスは常に空
+    cells: [[Cell; MAX_BOARD_SIZE]; MAX_BOARD_SIZE],
+    size: usize,
+    /// フロンティアのマスのビット集合（`row * MAX_BOARD_SIZE + col` 番目のビット）
+    frontier: u128,
+}
+
+/// 盤面のシリアライズ形式（一辺のマス数は行数から、フロンティアは読み込み時に計算し直す）
+#[derive(Clone, Serialize, Deserialize)]
+struct BoardCells {
+    cells: Vec<Vec<Cell>>,
+}
+
+impl TryFrom<BoardCells> for Board {
+    type Error = String;
+
+    fn try_from(data: BoardCells) -> Result<Self, Self::Error> {
+        let size = data.cells.len();
+        if !Board::is_supported_size(size) || data.cells.iter().any(|row| row.len() != size) {
+            return Err(format!("盤面の大きさが不正です: {}行", size));
+        }
+        let mut cells = [[Cell::Empty; MAX_BOARD_SIZE]; MAX_BOARD_SIZE];
+        for (row, values) in data.cells.iter().enumerate() {
+            cells[row][..size].copy_from_slice(values);
+        }
+        Ok(Board::from_cells(cells, size))
+    }
+}
+
+impl From<Board> for BoardCells {
+    fn from(board: Board) -> Self {
+        BoardCells {
+            cells: board.cells[..board.size].iter().map(|row| row[..board.size].to_vec()).collect(),
+        }
+    }
+}
+
+impl Board {
+    /// 新しい8x8のリバーシ盤面を作成する
+    /// 中央の4マスに初期配置（白黒交互）を設定する
+    pub fn new() -> Self {
+        Self::with_size(DEFAULT_BOARD_SIZE).expect("標準の盤面の大きさは対応している")
+    }
+    
+    /// 一辺のマス数を指定して初期配置の盤面を作成する（対応していない大きさの場合はNone）
+    pub fn with_size(size: usize) -> Option<Self> {
+        if !Self::is_supported_size(size) {
+            return None;
+        }
+        Self::from_layout(size, Self::initial_layout(size))
+    }
+    
+    /// リバーシの標準初期配置（中央の4マスに白黒を交互に置く）
+    /// `size` は2以上であること
+    pub fn initial_layout(size: usize) -> Vec<(Position, Cell)> {
+        let center = size / 2;
+        vec![
+            (Position { row: center - 1, col: center - 1 }, Cell::White),
+            (Position { row: center - 1, col: center }, Cell::Black),
+            (Position { row: center, col: center - 1 }, Cell::Black),
+            (Position { row: center, col: center }, Cell::White),
+        ]
+    }
+    
+    /// 一辺のマス数と石の配置を指定して盤面を作成する（配置のないマスは空）
+    /// 対応していない大きさの場合や、盤面の外側に石がある場合はNone
+    pub fn from_layout(size: usize, stones: impl IntoIterator<Item = (Position, Cell)>) -> Option<Self> {
+        if !Self::is_supported_size(size) {
+            return None;
+        }
+        let mut cells = [[Cell::Empty; MAX_BOARD_SIZE]; MAX_BOARD_SIZE];
+        for (position, cell) in stones {
+            if position.row >= size || position.col >= size {
+                return None;
+            }
+            cells[position.row][position.col] = cell;
+        }
+        Some(Self::from_cells(cells, size))
+    }
+    
+    /// 対局に使える一辺のマス数か
+    pub fn is_supported_size(size: usize) -> bool {
+        SUPPORTED_BOARD_SIZES.contains(&size)
+    }
+    
+    /// 盤面の一辺のマス数
+    pub fn size(&self) -> usize {
+        self.size
+    }
+    
+    /// 指定した位置が盤面の内側か
+    pub fn contains(&self, position: Position) -> bool {
+        position.row < self.size && position.col < self.size
+    }
+    
+    /// 盤面の全てのマスを行優先の順に返す
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let size = self.size;
+        (0..size).flat_map(move |row| (0..size).map(move |col| Position { row, col }))
+    }
+    
+    /// 各マスの状態から盤面を作り、フロンティアを計算する
+    fn from_cells(cells: [[Cell; MAX_BOARD_SIZE]; MAX_BOARD_SIZE], size: usize) -> Self {
+        let mut board = Board { cells, size, frontier: 0 };
+        for row in 0..size {
+            for col in 0..size {
+                board.update_frontier_at(row, col);
+            }
+        }
+        board
+    }
+    
+    /// 1マスがフロンティアに含まれるかを計算し直す
+    fn update_frontier_at(&mut self, row: usize, col: usize) {
+        let last = self.size - 1;
+        let bit = 1u128 << (row * MAX_BOARD_SIZE + col);
+        let neighbors_occupied = (row.saturating_sub(1)..=(row + 1).min(last))
+            .flat_map(|r| (col.saturating_sub(1)..=(col + 1).min(last)).map(move |c| (r, c)))
+            .any(|(r, c)| (r, c) != (row, col) && self.cells[r][c] != Cell::Empty);
+        if self.cells[row][col] == Cell::Empty && neighbors_occupied {
+            self.frontier |= bit;
+        } else {
+            self.frontier &= !bit;
+        }
+    }
+    
+    /// 石に隣接する空きマスを行優先の順に返す（合法手は必ずこの中にある）
+    pub fn frontier(&self) -> impl Iterator<Item = Position> {
+        let mut bits = self.frontier;
+        core::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let index = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+            Some(Position { row: index / MAX_BOARD_SIZE, col: index % MAX_BOARD_SIZE })
+        })
+    }
+    
+    /// 指定した位置がフロンティア（石に隣接する空きマス）か
+    pub fn is_frontier(&self, position: Position) -> bool {
+        self.contains(position) && self.frontier & (1u128 << (position.row * MAX_BOARD_SIZE + position.col)) != 0
+    }
+    
+    /// 指定した位置のセル状態を取得する
+    /// 範囲外の場合はNoneを返す
+    pub fn get_cell(&self, position: Position) -> Option<Cell> {
+        if self.contains(position) {
+            Some(self.cells[position.row][position.col])
+        } else {
+            None
+        }
+    }
+    
+    /// 指定した位置にセル状態を設定する
+    /// 範囲外の場合はfalseを返す
+    pub fn set_cell(&mut self, position: Position, cell: Cell) -> bool {
+        if self.contains(position) {
+            self.cells[position.row][position.col] = cell;
+            // フロンティアが変わりうるのは置いたマスとその周囲だけ
+            let last = self.size - 1;
+            for row in position.row.saturating_sub(1)..=(position.row + 1).min(last) {
+                for col in position.col.saturating_sub(1)..=(position.col + 1).min(last) {
+                    self.update_frontier_at(row, col);
+                }
+            }
+            true
+        } else {
+            false
+        }
+    }
+    
+    /// 指定した位置が空かチェックする
+    pub fn is_empty(&self, position: Position) -> bool {
+        matches!(self.get_cell(position), Some(Cell::Empty))
+    }
+    
+    /// 盤面上の黒石と白石の数を数える
+    /// 戻り値: (黒石数, 白石数)
+    pub fn count_pieces(&self) -> (u8, u8) {
+        let mut black_count = 0;
+        let mut white_count = 0;
+        
+        for row in &self.cells {
+            for &cell in row {
+                match cell {
+                    Cell::Black => black_count += 1,
+                    Cell::White => white_count += 1,
+                    Cell::Empty => {}
+                }
+            }
+        }
+        
+        (black_count, white_count)
+    }
+    
+    /// デバッグ用の盤面表示文字列を生成する
+    /// •で黒、○で白、.で空マスを表現
+    pub fn display(&self) -> String {
+        let mut result = String::new();
+        result.push(' ');
+        for col in 0..self.size {
+            result.push_str(&format!(" {}", col));
+        }
+        result.push('\n');
+        
+        // 各行を処理して表示文字列を構築
+        for (row_idx, row) in self.cells[..self.size].iter().enumerate() {
+            result.push_str(&format!("{} ", row_idx));
+            // 各セルをシンボルに変換
+            for &cell in &row[..self.size] {
+                let symbol = match cell {
+                    Cell::Empty => ".",
+                    Cell::Black => "●",
+                    Cell::White => "○",
+                };
+                result.push_str(&format!("{} ", symbol));
+            }
+            result.push('\n');
+        }
+        
+        result
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board_new_initial_state() {
+        let board = Board::new();
+        
+        assert_eq!(board.get_cell(Position::new(3, 3).unwrap()), Some(Cell::White));
+        assert_eq!(board.get_cell(Position::new(3, 4).unwrap()), Some(Cell::Black));
+        assert_eq!(board.get_cell(Position::new(4, 3).unwrap()), Some(Cell::Black));
+        assert_eq!(board.get_cell(Position::new(4, 4).unwrap()), Some(Cell::White));
+        
+        assert_eq!(board.get_cell(Position::new(0, 0).unwrap()), Some(Cell::Empty));
+        assert_eq!(board.get_cell(Position::new(7, 7).unwrap()), Some(Cell::Empty));
+    }
+
+    #[test]
+    fn test_board_get_cell_invalid_position() {
+        let board = Board::new();
+        assert_eq!(board.get_cell(Position { row: 8, col: 0 }), None);
+        assert_eq!(board.get_cell(Position { row: 0, col: 8 }), None);
+    }
+
+    #[test]
+    fn test_board_set_cell() {
+        let mut board = Board::new();
+        let pos = Position::new(0, 0).unwrap();
+        
+        assert!(board.set_cell(pos, Cell::Black));
+        assert_eq!(board.get_cell(pos), Some(Cell::Black));
+    }
+
+    #[test]
+    fn test_board_set_cell_invalid_position() {
+        let mut board = Board::new();
+        assert!(!board.set_cell(Position { row: 8, col: 0 }, Cell::Black));
+    }
+
+    #[test]
+    fn test_board_is_empty() {
+        let board = Board::new();
+        
+        assert!(board.is_empty(Position::new(0, 0).unwrap()));
+        assert!(!board.is_empty(Position::new(3, 3).unwrap()));
+    }
+
+    #[test]
+    fn test_board_count_pieces_initial() {
+        let board = Board::new();
+        let (black_count, white_count) = board.count_pieces();
+        
+        assert_eq!(black_count, 2);
+        assert_eq!(white_count, 2);
+    }
+
+    #[test]
+    fn test_board_frontier_tracks_stones() {
+        let mut board = Board::new();
+        assert_eq!(board.frontier().count(), 12);
+        assert!(board.is_frontier(Position::new(2, 2).unwrap()));
+        assert!(!board.is_frontier(Position::new(3, 3).unwrap()));
+        assert!(!board.is_frontier(Position::new(0, 0).unwrap()));
+        
+        let corner = Position::new(0, 0).unwrap();
+        board.set_cell(corner, Cell::Black);
+        let frontier: Vec<Position> = board.frontier().collect();
+        assert!(frontier.contains(&Position::new(1, 1).unwrap()));
+        assert!(!frontier.contains(&corner));
+        assert!(frontier.windows(2).all(|pair| (pair[0].row, pair[0].col) < (pair[1].row, pair[1].col)));
+        
+        board.set_cell(corner, Cell::Empty);
+        assert_eq!(board, Board::new());
+        
+        // シリアライズ形式は石の配置だけで、読み込み時にフロンティアを計算し直す
+        board.set_cell(corner, Cell::White);
+        let json = serde_json::to_value(&board).unwrap();
+        assert_eq!(json.as_object().unwrap().len(), 1);
+        assert_eq!(serde_json::from_value::<Board>(json).unwrap(), board);
+    }
+
+    #[test]
+    fn test_board_sizes() {
+        let small = Board::with_size(6).unwrap();
+        assert_eq!(small.size(), 6);
+        assert_eq!(small.get_cell(Position::new(2, 2).unwrap()), Some(Cell::White));
+        assert_eq!(small.get_cell(Position::new(2, 3).unwrap()), Some(Cell::Black));
+        assert_eq!(small.get_cell(Position::new(6, 0).unwrap()), None);
+        assert_eq!(small.positions().count(), 36);
+        assert!(small.frontier().all(|position| small.contains(position)));
+        
+        let large = Board::with_size(10).unwrap();
+        assert_eq!(large.get_cell(Position { row: 5, col: 5 }), Some(Cell::White));
+        assert_eq!(large.count_pieces(), (2, 2));
+        assert_eq!(large.frontier().count(), 12);
+        assert!(Board::with_size(7).is_none());
+        assert!(Board::with_size(12).is_none());
+        
+        // 一辺のマス数は行数から読み込む
+        let json = serde_json::to_value(&small).unwrap();
+        assert_eq!(json["cells"].as_array().unwrap().len(), 6);
+        assert_eq!(serde_json::from_value::<Board>(json).unwrap(), small);
+        assert!(serde_json::from_value::<Board>(serde_json::json!({ "cells": [[ "Empty" ]] })).is_err());
+    }
+
+    #[test]
+    fn test_board_display() {
+        let board = Board::new();
+        let display = board.display();
+        
+        assert!(display.contains("0 1 2 3 4 5 6 7"));
+        assert!(display.contains("●"));
+        assert!(display.contains("○"));
+        assert!(display.contains("."));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,109 @@
+//! 1行の盤面表記（FEN風）
+//! 行優先に並べた全マス（X: 黒, O: 白, -: 空。8x8なら64文字）と、空白を挟んで手番の色（X / O）を書く。
+//! 例: 初期局面は `---------------------------OX------XO--------------------------- X`。
+//! 盤面の文字は `setup::parse_board` と同じく B / W / * / . も受け付ける。
+
+use thiserror::Error;
+
+use super::board::Board;
+use super::setup::{self, SetupError};
+use super::state::GameState;
+use super::types::{Cell, Player};
+use super::variant::GameVariant;
+use crate::prelude::*;
+
+/// 盤面表記の解析のエラー
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum FenError {
+    #[error("盤面と手番を空白で区切って書いてください（例: \"---...OX... X\"）")]
+    Format,
+
+    #[error("手番の色が不正です: {0:?}（X: 黒, O: 白）")]
+    InvalidSideToMove(String),
+
+    #[error(transparent)]
+    Board(#[from] SetupError),
+}
+
+fn side_symbol(player: Player) -> char {
+    match player {
+        Player::Black => 'X',
+        Player::White => 'O',
+    }
+}
+
+impl Board {
+    /// 盤面と手番を1行の表記にする
+    pub fn to_fen(&self, to_move: Player) -> String {
+        let cells: String = self
+            .positions()
+            .map(|position| match self.get_cell(position) {
+                Some(Cell::Black) => 'X',
+                Some(Cell::White) => 'O',
+                _ => '-',
+            })
+            .collect();
+        format!("{} {}", cells, side_symbol(to_move))
+    }
+
+    /// 1行の表記から盤面と手番を読む（局面が到達できるかは検証しない）
+    pub fn from_fen(text: &str) -> Result<(Board, Player), FenError> {
+        let mut fields = text.split_whitespace();
+        let (Some(cells), Some(side), None) = (fields.next(), fields.next(), fields.next()) else {
+            return Err(FenError::Format);
+        };
+        if cells.contains('/') {
+            return Err(FenError::Format);
+        }
+        let to_move = match side.to_ascii_uppercase().as_str() {
+            "X" | "B" => Player::Black,
+            "O" | "W" => Player::White,
+            _ => return Err(FenError::InvalidSideToMove(side.to_string())),
+        };
+        Ok((setup::parse_board(cells)?, to_move))
+    }
+}
+
+impl GameState {
+    /// 現在の盤面と手番を1行の表記にする
+    pub fn to_fen(&self) -> String {
+        self.board.to_fen(self.current_player)
+    }
+
+    /// 1行の表記の局面から始まるゲーム状態を作る（局面が到達できるかは検証しない）
+    pub fn from_fen(text: &str, variant: GameVariant) -> Result<GameState, FenError> {
+        let (board, to_move) = Board::from_fen(text)?;
+        let mut state = GameState::from_board(board, variant);
+        state.current_player = to_move;
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fen_round_trip() {
+        let initial = GameState::new();
+        let fen = initial.to_fen();
+        assert_eq!(fen, "---------------------------OX------XO--------------------------- X");
+        assert_eq!(fen.len(), 64 + 2);
+
+        let state = GameState::from_fen(&fen, GameVariant::Standard).unwrap();
+        assert_eq!((state.board, state.current_player), (initial.board.clone(), Player::Black));
+        let (board, to_move) = Board::from_fen(&fen.replace('X', "b").replace('O', "w")).unwrap();
+        assert_eq!((board, to_move), (initial.board.clone(), Player::Black));
+
+        let small = Board::with_size(6).unwrap();
+        assert_eq!(Board::from_fen(&small.to_fen(Player::White)), Ok((small, Player::White)));
+
+        assert_eq!(Board::from_fen(&fen[..64]), Err(FenError::Format));
+        assert_eq!(Board::from_fen(&format!("{} extra", fen)), Err(FenError::Format));
+        assert_eq!(
+            Board::from_fen(&format!("{} Z", &fen[..64])),
+            Err(FenError::InvalidSideToMove("Z".to_string()))
+        );
+        assert_eq!(Board::from_fen(&fen[1..]), Err(FenError::Board(SetupError::InvalidLength(63))));
+    }
+}
@@ -0,0 +1,215 @@
+//! 局面ハッシュモジュール
+//! 盤面と手番から、盤の回転・反転に依存しない正規化済みのハッシュを計算する。
+//! ハッシュはAPIで公開する識別子として使うため、Rustのバージョンや実行ごとに変わらない
+//! FNV-1a (64bit) で計算する。
+
+use core::fmt;
+use core::str::FromStr;
+
+use super::board::Board;
+use super::types::{Cell, Player, Position};
+use crate::prelude::*;
+
+/// 盤の対称変換（回転4種と反転4種）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipHorizontal,
+    FlipVertical,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    /// 一辺が `size` マスの盤で、座標を変換後の盤上の座標に写す
+    pub fn apply(self, position: Position, size: usize) -> Position {
+        let (r, c) = (position.row, position.col);
+        let last = size - 1;
+        let (row, col) = match self {
+            Symmetry::Identity => (r, c),
+            Symmetry::Rotate90 => (c, last - r),
+            Symmetry::Rotate180 => (last - r, last - c),
+            Symmetry::Rotate270 => (last - c, r),
+            Symmetry::FlipHorizontal => (r, last - c),
+            Symmetry::FlipVertical => (last - r, c),
+            Symmetry::Transpose => (c, r),
+            Symmetry::AntiTranspose => (last - c, last - r),
+        };
+        Position { row, col }
+    }
+
+    /// 逆変換（変換後の盤上の座標を元の盤上の座標に戻す）
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => other,
+        }
+    }
+
+    /// 盤面全体を変換する
+    pub fn transform(self, board: &Board) -> Board {
+        // 全マスを上書きするため、初期値は元の盤面でよい
+        let mut transformed = board.clone();
+        for position in board.positions() {
+            if let Some(cell) = board.get_cell(position) {
+                transformed.set_cell(self.apply(position, board.size()), cell);
+            }
+        }
+        transformed
+    }
+}
+
+/// 正規化済みの局面ハッシュ
+/// 16桁の小文字16進数で表記する
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PositionHash(u64);
+
+/// 正規化した局面
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanonicalPosition {
+    pub hash: PositionHash,
+    /// 正規化後の向きの盤面
+    pub board: Board,
+    pub to_move: Player,
+    /// 元の盤面から正規化後の盤面への変換
+    pub symmetry: Symmetry,
+}
+
+impl PositionHash {
+    /// 8通りの対称変換のうち符号化が辞書順で最小になる向きを正規形とし、そのハッシュを返す
+    pub fn canonicalize(board: &Board, to_move: Player) -> CanonicalPosition {
+        let (symmetry, encoded) = Symmetry::ALL
+            .iter()
+            .map(|&symmetry| (symmetry, encode(&symmetry.transform(board), to_move)))
+            .min_by(|(_, a), (_, b)| a.cmp(b))
+            .expect("対称変換は8通りある");
+
+        CanonicalPosition {
+            hash: PositionHash(fnv1a(&encoded)),
+            board: symmetry.transform(board),
+            to_move,
+            symmetry,
+        }
+    }
+
+    pub fn of(board: &Board, to_move: Player) -> Self {
+        Self::canonicalize(board, to_move).hash
+    }
+}
+
+impl CanonicalPosition {
+    /// 元の盤面の座標を正規化後の盤面の座標に写す
+    pub fn to_canonical(&self, position: Position) -> Position {
+        self.symmetry.apply(position, self.board.size())
+    }
+
+    /// 正規化後の盤面の座標を元の盤面の座標に戻す
+    pub fn from_canonical(&self, position: Position) -> Position {
+        self.symmetry.inverse().apply(position, self.board.size())
+    }
+}
+
+/// 手番と全マスを行優先に並べる（盤の大きさが違えば長さも違うため、別の局面として区別される）
+fn encode(board: &Board, to_move: Player) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(1 + board.size() * board.size());
+    bytes.push(match to_move {
+        Player::Black => 1,
+        Player::White => 2,
+    });
+    bytes.extend(board.positions().map(|position| match board.get_cell(position) {
+        Some(Cell::Black) => 1,
+        Some(Cell::White) => 2,
+        _ => 0,
+    }));
+    bytes
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+}
+
+impl fmt::Display for PositionHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl FromStr for PositionHash {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 16 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(format!("Invalid position hash: {}. Expected 16 hex digits", s));
+        }
+        u64::from_str_radix(s, 16)
+            .map(PositionHash)
+            .map_err(|e| format!("Invalid position hash: {}: {}", s, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{GameState, ReversiRules};
+
+    #[test]
+    fn test_hash_is_stable_and_round_trips() {
+        let hash = PositionHash::of(&Board::new(), Player::Black);
+        // 公開識別子のため、初期局面のハッシュ値を固定する
+        assert_eq!(hash.to_string(), "e3bcd9d6a0781bf6");
+        assert_eq!(hash.to_string().parse::<PositionHash>().unwrap(), hash);
+        assert!("xyz".parse::<PositionHash>().is_err());
+        assert!("e3bcd9d6a0781bf".parse::<PositionHash>().is_err());
+    }
+
+    #[test]
+    fn test_symmetric_openings_share_a_hash() {
+        // 初手4通りはいずれも対称な局面になる
+        let hashes: Vec<PositionHash> = ReversiRules::get_valid_moves(&Board::new(), Player::Black)
+            .into_iter()
+            .map(|position| {
+                let mut state = GameState::new();
+                ReversiRules::apply_move(&mut state, position).unwrap();
+                PositionHash::of(&state.board, Player::White)
+            })
+            .collect();
+        assert_eq!(hashes.len(), 4);
+        assert!(hashes.iter().all(|&hash| hash == hashes[0]));
+
+        // 手番が異なれば別の局面
+        assert_ne!(PositionHash::of(&Board::new(), Player::Black), PositionHash::of(&Board::new(), Player::White));
+        // 盤の大きさが異なれば別の局面
+        assert_ne!(PositionHash::of(&Board::with_size(6).unwrap(), Player::Black), PositionHash::of(&Board::new(), Player::Black));
+    }
+
+    #[test]
+    fn test_canonical_board_maps_moves_consistently() {
+        let mut state = GameState::new();
+        ReversiRules::apply_move(&mut state, Position::new(2, 3).unwrap()).unwrap();
+        let canonical = PositionHash::canonicalize(&state.board, Player::White);
+
+        for position in ReversiRules::get_valid_moves(&state.board, Player::White) {
+            let mapped = canonical.to_canonical(position);
+            assert_eq!(canonical.from_canonical(mapped), position);
+            assert!(ReversiRules::is_valid_move(&canonical.board, mapped, Player::White));
+        }
+        assert_eq!(canonical.board.count_pieces(), state.board.count_pieces());
+    }
+}
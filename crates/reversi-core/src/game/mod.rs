@@ -0,0 +1,19 @@
+pub mod types;
+pub mod board;
+pub mod rules;
+pub mod state;
+pub mod hash;
+pub mod variant;
+pub mod setup;
+pub mod fen;
+pub mod transcript;
+pub mod replay;
+pub mod perft;
+
+pub use types::*;
+pub use board::*;
+pub use rules::*;
+pub use state::*;
+pub use variant::GameVariant;
+pub use hash::{CanonicalPosition, PositionHash, Symmetry};
+pub use perft::{perft, perft_divide};
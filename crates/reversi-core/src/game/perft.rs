@@ -0,0 +1,118 @@
+//! 着手生成の検証（perft）モジュール
+//! 局面から指定した手数までのゲーム木の末端の数を数え、既知の値と比べて合法手の生成と石の反転を検証する。
+//! 盤面の実装を差し替えたときに、置き換え前と同じ木を生成していることを確かめるために使う。
+
+use super::board::Board;
+use super::rules::ReversiRules;
+use super::types::{Player, Position};
+use crate::prelude::*;
+
+/// 標準の8×8の初期局面（黒番）からの深さ1〜11のperftの値
+/// パスも1手と数え、指定した深さより前に終局した局面は末端として1つと数える
+pub const STANDARD_PERFT: [u64; 11] = [
+    4,
+    12,
+    56,
+    244,
+    1_396,
+    8_200,
+    55_092,
+    390_216,
+    3_005_288,
+    24_571_284,
+    212_258_800,
+];
+
+/// 標準の初期局面から深さ `depth` のperftの既知の値（表にない深さはNone）
+pub fn standard_perft(depth: u32) -> Option<u64> {
+    depth.checked_sub(1).and_then(|index| STANDARD_PERFT.get(index as usize)).copied()
+}
+
+/// `player` の手番の局面から深さ `depth` までのゲーム木の末端の数を数える
+pub fn perft(board: &Board, player: Player, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let moves = ReversiRules::get_valid_moves(board, player);
+    if moves.is_empty() {
+        if ReversiRules::has_valid_moves(board, player.opposite()) {
+            return perft(board, player.opposite(), depth - 1);
+        }
+        // 両者とも置けなければ終局（末端）
+        return 1;
+    }
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    moves
+        .into_iter()
+        .map(|position| perft(&play(board, position, player), player.opposite(), depth - 1))
+        .sum()
+}
+
+/// 最初の1手ごとに末端の数を数える（パスはNone）
+/// 既知の値と合わないときに、どの手の部分木で食い違うかを絞り込むために使う
+pub fn perft_divide(board: &Board, player: Player, depth: u32) -> Vec<(Option<Position>, u64)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+    let moves = ReversiRules::get_valid_moves(board, player);
+    if moves.is_empty() {
+        if ReversiRules::has_valid_moves(board, player.opposite()) {
+            return vec![(None, perft(board, player.opposite(), depth - 1))];
+        }
+        return Vec::new();
+    }
+    moves
+        .into_iter()
+        .map(|position| (Some(position), perft(&play(board, position, player), player.opposite(), depth - 1)))
+        .collect()
+}
+
+/// 着手後の盤面
+fn play(board: &Board, position: Position, player: Player) -> Board {
+    let mut next = board.clone();
+    for flipped in ReversiRules::get_flipped_positions(board, position, player) {
+        next.set_cell(flipped, player.to_cell());
+    }
+    next.set_cell(position, player.to_cell());
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perft_matches_reference_values() {
+        let board = Board::new();
+        for depth in 1..=7 {
+            assert_eq!(Some(perft(&board, Player::Black, depth)), standard_perft(depth), "depth {}", depth);
+        }
+        assert_eq!(perft(&board, Player::Black, 0), 1);
+        assert_eq!(standard_perft(0), None);
+    }
+
+    #[test]
+    fn test_perft_divide_sums_to_perft() {
+        let board = Board::new();
+        let divided = perft_divide(&board, Player::Black, 4);
+        assert_eq!(divided.len(), 4);
+        assert_eq!(divided.iter().map(|(_, nodes)| nodes).sum::<u64>(), perft(&board, Player::Black, 4));
+    }
+
+    #[test]
+    fn test_perft_counts_pass_and_game_end() {
+        // 黒は置けず（パス）、白だけがc1に置ける。白の着手後は両者とも置けないため終局
+        let stones = [
+            (Position::new(0, 0).unwrap(), Player::White.to_cell()),
+            (Position::new(0, 1).unwrap(), Player::Black.to_cell()),
+        ];
+        let board = Board::from_layout(8, stones).unwrap();
+        assert_eq!(perft(&board, Player::Black, 1), 1);
+        assert_eq!(perft(&board, Player::Black, 2), 1);
+        assert_eq!(perft(&board, Player::Black, 5), 1);
+        assert_eq!(perft_divide(&board, Player::Black, 2), vec![(None, 1)]);
+        assert!(perft_divide(&board, Player::White, 0).is_empty());
+    }
+}
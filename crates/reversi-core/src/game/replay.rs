@@ -0,0 +1,119 @@
+//! 着手履歴からの局面の再構築
+//! 開始局面に着手を1手ずつ適用し直し、各手の後の盤面を再現する。
+//! 着手した色が手番と異なる場合は、手番側に合法手がないときに限りパスとして手番を渡す。
+
+use thiserror::Error;
+
+use super::board::Board;
+use super::rules::ReversiRules;
+use super::state::GameState;
+use super::types::{Move, Player, Position};
+use crate::prelude::*;
+
+/// 再生のエラー（`ply` はパスを含まない1始まりの手数）
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ReplayError {
+    #[error("{ply}手目は手番ではない色の着手です")]
+    WrongPlayer { ply: usize },
+
+    #[error("{ply}手目の{position}は合法手ではありません")]
+    IllegalMove { ply: usize, position: Position },
+}
+
+/// ある手の後の局面
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayFrame {
+    /// 打たれた石の数（開始局面は0）
+    pub ply: usize,
+    pub board: Board,
+    /// この局面の手番（終局していれば最後に打った色の相手）
+    pub to_move: Player,
+    /// この局面に至った着手（開始局面ではNone）
+    pub last_move: Option<Move>,
+}
+
+/// 開始局面に着手を適用し直して各手の後の局面を記録する
+#[derive(Debug, Clone)]
+pub struct ReplayBuilder {
+    state: GameState,
+    frames: Vec<ReplayFrame>,
+}
+
+impl ReplayBuilder {
+    pub fn new(start: GameState) -> Self {
+        let frame = ReplayFrame { ply: 0, board: start.board.clone(), to_move: start.current_player, last_move: None };
+        Self { state: start, frames: vec![frame] }
+    }
+
+    /// 開始局面から着手履歴を全て適用し、開始局面を含む各手の後の局面を返す
+    pub fn replay(start: GameState, moves: &[Move]) -> Result<Vec<ReplayFrame>, ReplayError> {
+        let mut builder = Self::new(start);
+        for game_move in moves {
+            builder.play(game_move.player, game_move.position)?;
+            // 着手の時刻は再生した時刻ではなく記録の時刻にそろえる
+            if let Some(frame) = builder.frames.last_mut() {
+                frame.last_move = Some(game_move.clone());
+            }
+        }
+        Ok(builder.finish())
+    }
+
+    /// 1手適用する（手番側に合法手がなければ先にパスする）
+    pub fn play(&mut self, player: Player, position: Position) -> Result<&ReplayFrame, ReplayError> {
+        let ply = self.frames.len();
+        if player != self.state.current_player {
+            if self.state.has_valid_moves() {
+                return Err(ReplayError::WrongPlayer { ply });
+            }
+            self.state.switch_player();
+        }
+        ReversiRules::apply_move(&mut self.state, position).map_err(|_| ReplayError::IllegalMove { ply, position })?;
+        self.state.switch_player();
+
+        self.frames.push(ReplayFrame {
+            ply,
+            board: self.state.board.clone(),
+            to_move: self.state.current_player,
+            last_move: self.state.move_history.last().cloned(),
+        });
+        Ok(self.frames.last().expect("直前に追加した"))
+    }
+
+    /// 現在の局面
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    pub fn finish(self) -> Vec<ReplayFrame> {
+        self.frames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::transcript;
+
+    #[test]
+    fn test_replay_reproduces_each_position() {
+        let played = transcript::import("F5D6C3D3C4", GameState::new()).unwrap();
+        let frames = ReplayBuilder::replay(GameState::new(), &played.move_history).unwrap();
+
+        assert_eq!(frames.len(), 6);
+        assert_eq!((frames[0].board.clone(), frames[0].last_move.clone()), (Board::new(), None));
+        let after_f5 = &frames[1];
+        assert_eq!((after_f5.ply, after_f5.to_move), (1, Player::White));
+        assert_eq!(after_f5.last_move.as_ref().unwrap().flipped, vec![Position::new(4, 4).unwrap()]);
+        assert_eq!(frames[5].board, played.board);
+        assert_eq!(frames[5].last_move.as_ref(), played.move_history.last());
+
+        // 白に合法手があるのに黒が続けて打つことはできない
+        let mut builder = ReplayBuilder::new(GameState::new());
+        builder.play(Player::Black, Position::new(4, 5).unwrap()).unwrap();
+        assert_eq!(builder.play(Player::Black, Position::new(5, 5).unwrap()), Err(ReplayError::WrongPlayer { ply: 2 }));
+        assert_eq!(
+            builder.play(Player::White, Position::new(0, 0).unwrap()),
+            Err(ReplayError::IllegalMove { ply: 2, position: Position::new(0, 0).unwrap() })
+        );
+    }
+}
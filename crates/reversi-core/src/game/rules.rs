@@ -1,9 +1,15 @@
 //! リバーシのルールとゲームロジック実装モジュール
 //! 合法手の判定、石のフリップ処理、ゲーム終了判定などを担当する。
 
+use serde::Serialize;
+use crate::prelude::*;
+#[cfg(feature = "std")]
+use utoipa::ToSchema;
+
 use super::types::{Cell, Player, Position, Move};
 use super::board::Board;
 use super::state::GameState;
+use super::variant::GameVariant;
 use crate::error::{GameError, Result};
 
 /// 盤面上の8方向への移動ベクトル
@@ -14,6 +20,32 @@ const DIRECTIONS: [(i8, i8); 8] = [
     (1, -1),  (1, 0),  (1, 1),   // 左下、下、右下
 ];
 
+/// 着手できない理由の分類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "std", derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum InvalidMoveReason {
+    /// 盤面の外のマス
+    OffBoard,
+    /// 着手した色の手番ではない
+    NotYourTurn,
+    /// 既に石が置かれているマス
+    SquareOccupied,
+    /// どの方向にも相手の石を挟めない
+    NoBracketingLine,
+}
+
+impl InvalidMoveReason {
+    pub fn description(self) -> &'static str {
+        match self {
+            InvalidMoveReason::OffBoard => "盤面の外のマスです",
+            InvalidMoveReason::NotYourTurn => "手番ではありません",
+            InvalidMoveReason::SquareOccupied => "既に石が置かれています",
+            InvalidMoveReason::NoBracketingLine => "どの方向にも相手の石を挟めません",
+        }
+    }
+}
+
 /// リバーシのルールを実装する構造体
 /// スタティックメソッドのみを提供する
 pub struct ReversiRules;
@@ -22,7 +54,8 @@ impl ReversiRules {
     /// 指定した位置に現在のプレイヤーが置けるかチェックする
     /// 空のマスで、かつ相手の石を少なくとも1個フリップできる必要がある
     pub fn is_valid_move(board: &Board, position: Position, player: Player) -> bool {
-        if !board.is_empty(position) {
+        // 石に隣接していない空きマスには置けない
+        if !board.is_frontier(position) {
             return false;
         }
         
@@ -36,6 +69,7 @@ impl ReversiRules {
         let mut flipped = Vec::new();
         let player_cell = player.to_cell();
         let opponent_cell = player.opposite().to_cell();
+        let size = board.size() as i8;
         
         // 8方向に向かって探索し、フリップ可能な石を探す
         for &(dr, dc) in &DIRECTIONS {
@@ -44,7 +78,7 @@ impl ReversiRules {
             let mut current_col = position.col as i8 + dc;
             
             // この方向に盤面の端まで探索
-            while current_row >= 0 && current_row < 8 && current_col >= 0 && current_col < 8 {
+            while current_row >= 0 && current_row < size && current_col >= 0 && current_col < size {
                 let current_pos = Position {
                     row: current_row as usize,
                     col: current_col as usize,
@@ -74,23 +108,35 @@ impl ReversiRules {
         flipped
     }
     
-    /// 指定したプレイヤーの合法手を全て取得する
-    /// 盤面全体をスキャンして合法手を探索する
-    pub fn get_valid_moves(board: &Board, player: Player) -> Vec<Position> {
-        let mut valid_moves = Vec::new();
-        
-        // 盤面全体をスキャンして合法手を探索
-        for row in 0..8 {
-            for col in 0..8 {
-                if let Some(position) = Position::new(row, col) {
-                    if Self::is_valid_move(board, position, player) {
-                        valid_moves.push(position);
-                    }
-                }
+    /// `player` が `position` に打てない理由を返す（打てる場合はNone）
+    pub fn invalid_move_reason(game_state: &GameState, position: Position, player: Player) -> Option<InvalidMoveReason> {
+        match game_state.board.get_cell(position) {
+            None => Some(InvalidMoveReason::OffBoard),
+            Some(_) if player != game_state.current_player => Some(InvalidMoveReason::NotYourTurn),
+            Some(Cell::Black | Cell::White) => Some(InvalidMoveReason::SquareOccupied),
+            Some(Cell::Empty) if !Self::is_valid_move(&game_state.board, position, player) => {
+                Some(InvalidMoveReason::NoBracketingLine)
             }
+            Some(Cell::Empty) => None,
         }
-        
-        valid_moves
+    }
+    
+    /// `position` に近い順に最大 `limit` 個の合法手を返す
+    /// 距離は縦横斜めの移動回数（チェビシェフ距離）で測り、同じ距離なら行優先の順に並べる
+    pub fn nearest_valid_moves(board: &Board, position: Position, player: Player, limit: usize) -> Vec<Position> {
+        let mut moves = Self::get_valid_moves(board, player);
+        moves.sort_by_key(|candidate| candidate.row.abs_diff(position.row).max(candidate.col.abs_diff(position.col)));
+        moves.truncate(limit);
+        moves
+    }
+    
+    /// 指定したプレイヤーの合法手を全て取得する（行優先の順）
+    /// 石に隣接する空きマス（フロンティア）だけを候補として調べる
+    pub fn get_valid_moves(board: &Board, player: Player) -> Vec<Position> {
+        board
+            .frontier()
+            .filter(|&position| Self::is_valid_move(board, position, player))
+            .collect()
     }
     
     /// 指定した位置に手を適用し、盤面を更新する
@@ -127,7 +173,7 @@ impl ReversiRules {
     /// 指定したプレイヤーに合法手があるかチェックする
     /// パス判定に使用される
     pub fn has_valid_moves(board: &Board, player: Player) -> bool {
-        Self::get_valid_moves(board, player).len() > 0
+        board.frontier().any(|position| Self::is_valid_move(board, position, player))
     }
     
     /// ゲーム終了判定（両プレイヤーとも合法手がない）
@@ -135,24 +181,16 @@ impl ReversiRules {
         !Self::has_valid_moves(board, Player::Black) && !Self::has_valid_moves(board, Player::White)
     }
     
-    /// 最終スコアに基づいて勝者を決定する
+    /// 最終スコアとルールに基づいて勝者を決定する
     /// 同数の場合はNone（引き分け）を返す
-    pub fn determine_winner(board: &Board) -> Option<Player> {
-        let (black_count, white_count) = board.count_pieces();
-        
-        if black_count > white_count {
-            Some(Player::Black)
-        } else if white_count > black_count {
-            Some(Player::White)
-        } else {
-            None
-        }
+    pub fn determine_winner(board: &Board, variant: GameVariant) -> Option<Player> {
+        variant.winner(board.count_pieces())
     }
     
     /// ターン処理とパス判定を管理する
     /// 戻り値: ターンが切り替わったかまたはゲームが終了したか
     pub fn handle_turn(game_state: &mut GameState) -> bool {
-        if Self::has_valid_moves(&game_state.board, game_state.current_player) {
+        if game_state.has_valid_moves() {
             // 現在のプレイヤーに合法手があるのでターン継続
             return false;
         }
@@ -160,13 +198,13 @@ impl ReversiRules {
         // 現在のプレイヤーはパス、相手にターンを渡す
         game_state.switch_player();
         
-        if Self::has_valid_moves(&game_state.board, game_state.current_player) {
+        if game_state.has_valid_moves() {
             // 相手に合法手があるのでゲーム継続
             return true;
         }
         
         // 両プレイヤーとも合法手がないのでゲーム終了
-        let winner = Self::determine_winner(&game_state.board);
+        let winner = Self::determine_winner(&game_state.board, game_state.variant);
         game_state.finish(winner);
         true
     }
@@ -189,6 +227,21 @@ mod tests {
         assert!(!ReversiRules::is_valid_move(&board, Position::new(3, 3).unwrap(), Player::Black));
     }
 
+    #[test]
+    fn test_invalid_move_reason_and_nearest_valid_moves() {
+        let state = GameState::new();
+        let reason = |row, col, player| ReversiRules::invalid_move_reason(&state, Position { row, col }, player);
+        
+        assert_eq!(reason(2, 3, Player::Black), None);
+        assert_eq!(reason(8, 0, Player::Black), Some(InvalidMoveReason::OffBoard));
+        assert_eq!(reason(2, 3, Player::White), Some(InvalidMoveReason::NotYourTurn));
+        assert_eq!(reason(3, 3, Player::Black), Some(InvalidMoveReason::SquareOccupied));
+        assert_eq!(reason(0, 0, Player::Black), Some(InvalidMoveReason::NoBracketingLine));
+        
+        let nearest = ReversiRules::nearest_valid_moves(&state.board, Position::new(0, 0).unwrap(), Player::Black, 2);
+        assert_eq!(nearest, vec![Position::new(2, 3).unwrap(), Position::new(3, 2).unwrap()]);
+    }
+
     #[test]
     fn test_get_flipped_positions() {
         let board = Board::new();
@@ -270,14 +323,15 @@ mod tests {
     fn test_determine_winner() {
         let mut board = Board::new();
         
-        assert_eq!(ReversiRules::determine_winner(&board), None);
+        assert_eq!(ReversiRules::determine_winner(&board, GameVariant::Standard), None);
         
         board.set_cell(Position::new(0, 0).unwrap(), Cell::Black);
-        assert_eq!(ReversiRules::determine_winner(&board), Some(Player::Black));
+        assert_eq!(ReversiRules::determine_winner(&board, GameVariant::Standard), Some(Player::Black));
         
         board.set_cell(Position::new(0, 1).unwrap(), Cell::White);
         board.set_cell(Position::new(0, 2).unwrap(), Cell::White);
-        assert_eq!(ReversiRules::determine_winner(&board), Some(Player::White));
+        assert_eq!(ReversiRules::determine_winner(&board, GameVariant::Standard), Some(Player::White));
+        assert_eq!(ReversiRules::determine_winner(&board, GameVariant::AntiReversi), Some(Player::Black));
     }
 
     #[test]
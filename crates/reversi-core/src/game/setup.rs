@@ -0,0 +1,178 @@
+//! 任意の開始局面の解析と検証
+//! 盤面の文字列表現（X: 黒, O: 白, -: 空）を解析し、対局の開始局面として使えるかを確かめる。
+//! 石は取り除かれないため、中央の4マスが埋まっていない局面や、中央の石とつながっていない石がある局面は
+//! 通常の対局では到達できないものとして受け付けない。
+
+use thiserror::Error;
+
+use super::board::{Board, MAX_BOARD_SIZE};
+use super::rules::ReversiRules;
+use super::state::GameState;
+use super::types::{Cell, Player, Position};
+use super::variant::GameVariant;
+use crate::prelude::*;
+
+/// 開始局面の解析・検証のエラー
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SetupError {
+    #[error("盤面のマス数が不正です: {0}マス（6x6 / 8x8 / 10x10 のいずれかにしてください）")]
+    InvalidLength(usize),
+
+    #[error("盤面に使えない文字があります: {0:?}（X: 黒, O: 白, -: 空）")]
+    InvalidChar(char),
+
+    #[error("中央の4マスに空きがあり、到達できない局面です")]
+    EmptyCenter,
+
+    #[error("({row}, {col})の石が中央の石とつながっておらず、到達できない局面です")]
+    Disconnected { row: usize, col: usize },
+
+    #[error("終局している局面からは対局を始められません")]
+    GameOver,
+
+    #[error("手番の{0}に合法手がありません")]
+    NoValidMoves(&'static str),
+}
+
+fn color(player: Player) -> &'static str {
+    match player {
+        Player::Black => "黒",
+        Player::White => "白",
+    }
+}
+
+/// 盤面の文字列を解析する
+/// 行優先で1マス1文字（X / B / * は黒、O / W は白、- / . は空）とし、空白と `/` は読み飛ばす
+pub fn parse_board(text: &str) -> Result<Board, SetupError> {
+    let cells = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '/')
+        .map(|c| match c.to_ascii_uppercase() {
+            'X' | 'B' | '*' => Ok(Cell::Black),
+            'O' | 'W' => Ok(Cell::White),
+            '-' | '.' => Ok(Cell::Empty),
+            _ => Err(SetupError::InvalidChar(c)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let size = (1..=MAX_BOARD_SIZE)
+        .find(|size| size * size == cells.len())
+        .filter(|&size| Board::is_supported_size(size))
+        .ok_or(SetupError::InvalidLength(cells.len()))?;
+    let layout = cells
+        .into_iter()
+        .enumerate()
+        .map(|(index, cell)| (Position { row: index / size, col: index % size }, cell));
+    Board::from_layout(size, layout).ok_or(SetupError::InvalidLength(size * size))
+}
+
+/// 盤面を文字列にする（X: 黒, O: 白, -: 空、行を `/` で区切る）
+pub fn board_to_string(board: &Board) -> String {
+    let size = board.size();
+    board
+        .positions()
+        .map(|position| {
+            let symbol = match board.get_cell(position) {
+                Some(Cell::Black) => 'X',
+                Some(Cell::White) => 'O',
+                _ => '-',
+            };
+            if position.col == size - 1 && position.row < size - 1 {
+                format!("{}/", symbol)
+            } else {
+                symbol.to_string()
+            }
+        })
+        .collect()
+}
+
+/// 任意の局面を対局の開始局面として検証する
+/// 到達できない石の配置、終局した局面、手番に合法手のない局面はエラーにする
+pub fn validate_start(board: &Board, to_move: Player) -> Result<(), SetupError> {
+    let size = board.size();
+    let center: Vec<Position> = Board::initial_layout(size).into_iter().map(|(position, _)| position).collect();
+    if center.iter().any(|&position| board.get_cell(position) == Some(Cell::Empty)) {
+        return Err(SetupError::EmptyCenter);
+    }
+
+    // 石は既にある石に隣接するマスにしか置けないため、全ての石は中央の石から8方向にたどれる
+    let mut reached = [[false; MAX_BOARD_SIZE]; MAX_BOARD_SIZE];
+    let mut stack = center;
+    while let Some(position) = stack.pop() {
+        if reached[position.row][position.col] {
+            continue;
+        }
+        reached[position.row][position.col] = true;
+        for row in position.row.saturating_sub(1)..=(position.row + 1).min(size - 1) {
+            for col in position.col.saturating_sub(1)..=(position.col + 1).min(size - 1) {
+                let neighbor = Position { row, col };
+                if board.get_cell(neighbor) != Some(Cell::Empty) {
+                    stack.push(neighbor);
+                }
+            }
+        }
+    }
+    if let Some(position) = board
+        .positions()
+        .find(|&position| board.get_cell(position) != Some(Cell::Empty) && !reached[position.row][position.col])
+    {
+        return Err(SetupError::Disconnected { row: position.row, col: position.col });
+    }
+
+    if ReversiRules::is_game_over(board) {
+        return Err(SetupError::GameOver);
+    }
+    if !ReversiRules::has_valid_moves(board, to_move) {
+        return Err(SetupError::NoValidMoves(color(to_move)));
+    }
+    Ok(())
+}
+
+/// 盤面の文字列と手番を解析・検証し、その局面から始まるゲーム状態を作る
+pub fn start_state(text: &str, to_move: Player, variant: GameVariant) -> Result<GameState, SetupError> {
+    let board = parse_board(text)?;
+    validate_start(&board, to_move)?;
+    let mut state = GameState::from_board(board, variant);
+    state.current_player = to_move;
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_validate_start_position() {
+        let text = board_to_string(&Board::new());
+        assert_eq!(text, "--------/--------/--------/---OX---/---XO---/--------/--------/--------");
+        assert_eq!(parse_board(&text).unwrap(), Board::new());
+        assert_eq!(parse_board(&text.replace('/', "\n").to_lowercase()).unwrap(), Board::new());
+
+        // 白番から始める中盤の局面
+        let midgame = "\
+            --------\
+            --------\
+            ---X----\
+            ---XX---\
+            ---XO---\
+            --------\
+            --------\
+            --------";
+        let state = start_state(midgame, Player::White, GameVariant::Standard).unwrap();
+        assert_eq!((state.current_player, state.get_score()), (Player::White, (4, 1)));
+        assert!(!state.valid_moves().is_empty());
+
+        assert_eq!(parse_board("XO-"), Err(SetupError::InvalidLength(3)));
+        assert_eq!(parse_board(&text.replace('X', "Z")), Err(SetupError::InvalidChar('Z')));
+        let empty_center = text.replacen('O', "-", 1);
+        assert_eq!(validate_start(&parse_board(&empty_center).unwrap(), Player::Black), Err(SetupError::EmptyCenter));
+        let corner = format!("X{}", &text[1..]);
+        assert_eq!(
+            validate_start(&parse_board(&corner).unwrap(), Player::Black),
+            Err(SetupError::Disconnected { row: 0, col: 0 })
+        );
+        // 黒だけの局面は終局している
+        let finished = text.replace('O', "X");
+        assert_eq!(validate_start(&parse_board(&finished).unwrap(), Player::Black), Err(SetupError::GameOver));
+    }
+}
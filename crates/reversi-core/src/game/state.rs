@@ -0,0 +1,408 @@
+//! ゲーム状態管理モジュール
+//! リバーシゲームの全体的な状態（盤面、プレイヤー、進行状態など）を管理する。
+
+use super::types::{Cell, Move, Player, Position};
+use super::board::Board;
+use super::rules::ReversiRules;
+use super::variant::GameVariant;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use uuid::Uuid;
+#[cfg(feature = "std")]
+use chrono::{DateTime, Utc};
+
+use crate::prelude::*;
+
+/// 合法手キャッシュのセル（no_stdではスレッド間で共有しない前提で `OnceCell` を使う）
+#[cfg(feature = "std")]
+type OnceLock<T> = std::sync::OnceLock<T>;
+#[cfg(not(feature = "std"))]
+type OnceLock<T> = core::cell::OnceCell<T>;
+
+/// ゲームの進行状態を表すenum
+/// ゲームの状態遷移と終了時の情報を管理する
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameStatus {
+    /// ゲーム進行中
+    InProgress,
+    /// ゲーム終了（勝者と最終スコアを記録）
+    Finished { 
+        winner: Option<Player>, 
+        score: (u8, u8) 
+    },
+    /// ゲーム一時停止
+    Paused,
+}
+
+/// リバーシゲームの全体状態を保持する構造体
+/// 盤面、現在のプレイヤー、手の履歴などを全て含む
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameState {
+    #[cfg(feature = "std")]
+    pub id: Uuid,
+    pub board: Board,
+    pub current_player: Player,
+    pub game_status: GameStatus,
+    pub move_history: Vec<Move>,
+    #[cfg(feature = "std")]
+    pub created_at: DateTime<Utc>,
+    #[cfg(feature = "std")]
+    pub last_updated: DateTime<Utc>,
+    /// 対局のルール
+    #[serde(default)]
+    pub variant: GameVariant,
+    /// 現在の手番の合法手と石数のキャッシュ
+    /// 着手・手番の交代・取り消しで破棄し、次に参照したときに計算し直す
+    #[serde(skip)]
+    turn_cache: OnceLock<TurnCache>,
+}
+
+/// ある盤面と手番について計算した合法手と石数
+#[derive(Debug, Clone)]
+struct TurnCache {
+    board: Board,
+    player: Player,
+    valid_moves: Vec<Position>,
+    score: (u8, u8),
+}
+
+impl TurnCache {
+    fn compute(board: &Board, player: Player) -> Self {
+        Self {
+            board: board.clone(),
+            player,
+            valid_moves: ReversiRules::get_valid_moves(board, player),
+            score: board.count_pieces(),
+        }
+    }
+}
+
+impl GameState {
+    /// 新しいゲーム状態を作成する
+    /// 初期状態：黒の番でゲーム開始
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            id: Uuid::new_v4(),
+            board: Board::new(),
+            current_player: Player::Black,
+            game_status: GameStatus::InProgress,
+            move_history: Vec::new(),
+            #[cfg(feature = "std")]
+            created_at: Utc::now(),
+            #[cfg(feature = "std")]
+            last_updated: Utc::now(),
+            variant: GameVariant::Standard,
+            turn_cache: OnceLock::new(),
+        }
+    }
+    
+    /// 一辺のマス数を指定して新しいゲーム状態を作成する（対応していない大きさの場合はNone）
+    pub fn with_board_size(size: usize) -> Option<Self> {
+        Self::with_variant(size, GameVariant::Standard, 0)
+    }
+    
+    /// 一辺のマス数とルールを指定して新しいゲーム状態を作成する（対応していない大きさの場合はNone）
+    /// `seed` は初期配置を乱択するルールで使う
+    pub fn with_variant(size: usize, variant: GameVariant, seed: u64) -> Option<Self> {
+        Some(Self::from_board(variant.initial_board(size, seed)?, variant))
+    }
+    
+    /// 指定した盤面とルールで、黒番から始まる新しいゲーム状態を作成する
+    pub fn from_board(board: Board, variant: GameVariant) -> Self {
+        Self {
+            board,
+            variant,
+            ..Self::new()
+        }
+    }
+    
+    /// 盤面の一辺のマス数
+    pub fn board_size(&self) -> usize {
+        self.board.size()
+    }
+    
+    /// 指定IDで新しいゲーム状態を作成する
+    /// テストや特定のIDが必要な場合に使用
+    #[cfg(feature = "std")]
+    pub fn new_with_id(id: Uuid) -> Self {
+        Self { id, ..Self::new() }
+    }
+    
+    /// 最終更新時刻を現在時刻にする（no_stdでは時刻を記録しない）
+    fn touch(&mut self) {
+        #[cfg(feature = "std")]
+        {
+            self.last_updated = Utc::now();
+        }
+    }
+    
+    /// ゲームが終了しているかチェックする
+    pub fn is_finished(&self) -> bool {
+        matches!(self.game_status, GameStatus::Finished { .. })
+    }
+    
+    /// ゲームが一時停止中かチェックする
+    pub fn is_paused(&self) -> bool {
+        matches!(self.game_status, GameStatus::Paused)
+    }
+    
+    /// 現在のプレイヤーを交代する
+    /// 手の実行後やパス時に呼び出される
+    pub fn switch_player(&mut self) {
+        self.current_player = self.current_player.opposite();
+        self.turn_cache.take();
+        self.touch();
+    }
+    
+    /// 手の履歴に新しい手を追加する
+    /// 最終更新時刻も同時に更新する（着手で盤面が変わるため合法手のキャッシュも破棄する）
+    pub fn add_move(&mut self, game_move: Move) {
+        self.move_history.push(game_move);
+        self.turn_cache.take();
+        self.touch();
+    }
+    
+    /// キャッシュした現在の盤面・手番の計算結果を参照する
+    /// `board` や `current_player` を直接書き換えた後でも古い結果は使わず、その場で計算する
+    fn with_turn_cache<T>(&self, f: impl FnOnce(&TurnCache) -> T) -> T {
+        let cached = self.turn_cache.get_or_init(|| TurnCache::compute(&self.board, self.current_player));
+        if cached.player == self.current_player && cached.board == self.board {
+            f(cached)
+        } else {
+            f(&TurnCache::compute(&self.board, self.current_player))
+        }
+    }
+    
+    /// 現在の手番のプレイヤーの合法手（盤面・手番が変わるまでキャッシュする）
+    pub fn valid_moves(&self) -> Vec<Position> {
+        self.with_turn_cache(|cached| cached.valid_moves.clone())
+    }
+    
+    /// 現在の手番のプレイヤーに合法手があるか
+    pub fn has_valid_moves(&self) -> bool {
+        self.with_turn_cache(|cached| !cached.valid_moves.is_empty())
+    }
+    
+    /// 最後の手を取り消し、その手を打つ直前の状態に戻す
+    /// 記録された反転位置を元の色に戻し、手番も着手したプレイヤーに戻す
+    pub fn undo_last_move(&mut self) -> Option<Move> {
+        let game_move = self.move_history.pop()?;
+        
+        self.board.set_cell(game_move.position, Cell::Empty);
+        for flipped in &game_move.flipped {
+            self.board.set_cell(*flipped, game_move.player.opposite().to_cell());
+        }
+        
+        self.current_player = game_move.player;
+        self.turn_cache.take();
+        self.game_status = GameStatus::InProgress;
+        self.touch();
+        Some(game_move)
+    }
+    
+    /// ゲームを一時停止する
+    /// 進行中のゲームのみ停止可能
+    pub fn pause(&mut self) {
+        if matches!(self.game_status, GameStatus::InProgress) {
+            self.game_status = GameStatus::Paused;
+            self.touch();
+        }
+    }
+    
+    /// 一時停止中のゲームを再開する
+    pub fn resume(&mut self) {
+        if matches!(self.game_status, GameStatus::Paused) {
+            self.game_status = GameStatus::InProgress;
+            self.touch();
+        }
+    }
+    
+    /// ゲームを終了させる
+    /// 勝者と最終スコアを記録する
+    pub fn finish(&mut self, winner: Option<Player>) {
+        let (black_count, white_count) = self.board.count_pieces();
+        self.game_status = GameStatus::Finished {
+            winner,
+            score: (black_count, white_count),
+        };
+        self.touch();
+    }
+    
+    /// 現在のスコアを取得する（盤面が変わるまでキャッシュする）
+    /// 戻り値: (黒石数, 白石数)
+    pub fn get_score(&self) -> (u8, u8) {
+        self.with_turn_cache(|cached| cached.score)
+    }
+    
+    /// これまでの手数を取得する
+    pub fn get_move_count(&self) -> usize {
+        self.move_history.len()
+    }
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_state_new() {
+        let game = GameState::new();
+        
+        assert_eq!(game.current_player, Player::Black);
+        assert!(matches!(game.game_status, GameStatus::InProgress));
+        assert_eq!(game.move_history.len(), 0);
+        assert_eq!(game.get_score(), (2, 2));
+    }
+
+    #[test]
+    fn test_game_state_new_with_id() {
+        let custom_id = Uuid::new_v4();
+        let game = GameState::new_with_id(custom_id);
+        
+        assert_eq!(game.id, custom_id);
+        assert_eq!(game.current_player, Player::Black);
+    }
+
+    #[test]
+    fn test_game_state_status_checks() {
+        let mut game = GameState::new();
+        
+        assert!(!game.is_finished());
+        assert!(!game.is_paused());
+        
+        game.pause();
+        assert!(game.is_paused());
+        assert!(!game.is_finished());
+        
+        game.resume();
+        assert!(!game.is_paused());
+        
+        game.finish(Some(Player::Black));
+        assert!(game.is_finished());
+    }
+
+    #[test]
+    fn test_game_state_switch_player() {
+        let mut game = GameState::new();
+        
+        assert_eq!(game.current_player, Player::Black);
+        
+        game.switch_player();
+        assert_eq!(game.current_player, Player::White);
+        
+        game.switch_player();
+        assert_eq!(game.current_player, Player::Black);
+    }
+
+    #[test]
+    fn test_game_state_add_move() {
+        let mut game = GameState::new();
+        let pos = Position::new(2, 3).unwrap();
+        let game_move = Move::new(Player::Black, pos, vec![]);
+        
+        assert_eq!(game.get_move_count(), 0);
+        
+        game.add_move(game_move.clone());
+        assert_eq!(game.get_move_count(), 1);
+        assert_eq!(game.move_history[0].position, pos);
+    }
+
+    #[test]
+    fn test_game_state_undo_last_move() {
+        use crate::game::ReversiRules;
+        
+        let mut game = GameState::new();
+        let initial_board = game.board.clone();
+        
+        let first = ReversiRules::get_valid_moves(&game.board, Player::Black)[0];
+        ReversiRules::apply_move(&mut game, first).unwrap();
+        game.switch_player();
+        let after_first = game.board.clone();
+        
+        let reply = ReversiRules::get_valid_moves(&game.board, Player::White)[0];
+        ReversiRules::apply_move(&mut game, reply).unwrap();
+        game.switch_player();
+        game.finish(None);
+        
+        let undone = game.undo_last_move().unwrap();
+        assert_eq!(undone.position, reply);
+        assert_eq!(game.board, after_first);
+        assert_eq!(game.current_player, Player::White);
+        assert!(matches!(game.game_status, GameStatus::InProgress));
+        
+        game.undo_last_move().unwrap();
+        assert_eq!(game.board, initial_board);
+        assert_eq!(game.current_player, Player::Black);
+        assert!(game.undo_last_move().is_none());
+    }
+
+    #[test]
+    fn test_valid_moves_cache_follows_board_and_turn() {
+        use crate::game::ReversiRules;
+        
+        let mut game = GameState::new();
+        assert_eq!(game.valid_moves(), ReversiRules::get_valid_moves(&game.board, Player::Black));
+        
+        let first = game.valid_moves()[0];
+        ReversiRules::apply_move(&mut game, first).unwrap();
+        assert_eq!(game.get_score(), (4, 1));
+        game.switch_player();
+        assert_eq!(game.valid_moves(), ReversiRules::get_valid_moves(&game.board, Player::White));
+        
+        // 盤面を直接書き換えても古いキャッシュは使わない
+        let mut edited = game.clone();
+        edited.board = Board::new();
+        assert_eq!(edited.valid_moves(), ReversiRules::get_valid_moves(&Board::new(), Player::White));
+        assert_eq!(edited.get_score(), (2, 2));
+        
+        game.undo_last_move().unwrap();
+        assert_eq!(game.valid_moves(), ReversiRules::get_valid_moves(&Board::new(), Player::Black));
+        
+        // キャッシュはシリアライズしない
+        let json = serde_json::to_value(&game).unwrap();
+        assert!(json.get("turn_cache").is_none());
+        let restored: GameState = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.valid_moves(), game.valid_moves());
+    }
+
+    #[test]
+    fn test_game_state_finish() {
+        let mut game = GameState::new();
+        
+        game.finish(Some(Player::Black));
+        
+        assert!(game.is_finished());
+        if let GameStatus::Finished { winner, score } = &game.game_status {
+            assert_eq!(*winner, Some(Player::Black));
+            assert_eq!(*score, (2, 2)); // Initial board state
+        } else {
+            panic!("Game should be finished");
+        }
+    }
+
+    #[test]
+    fn test_game_state_pause_resume() {
+        let mut game = GameState::new();
+        
+        // Can only pause in progress games
+        game.pause();
+        assert!(game.is_paused());
+        
+        // Can only resume paused games
+        game.resume();
+        assert!(!game.is_paused());
+        assert!(matches!(game.game_status, GameStatus::InProgress));
+        
+        // Cannot pause finished games
+        game.finish(None);
+        game.pause();
+        assert!(game.is_finished()); // Should still be finished
+    }
+}
\ No newline at end of file
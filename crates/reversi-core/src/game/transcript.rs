@@ -0,0 +1,113 @@
+//! 棋譜文字列の書き出しと読み込み
+//! オセロの標準的な棋譜形式（"F5D6C3..."）で、着手を列の英字と行の数字の組で続けて書く。
+//! パスは書かず、合法手のない手番は読み込み時に自動でパスする。
+
+use thiserror::Error;
+
+use super::rules::ReversiRules;
+use super::state::GameState;
+use super::types::Position;
+use crate::prelude::*;
+
+/// 棋譜の解析・再生のエラー（`ply` はパスを含まない1始まりの手数）
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TranscriptError {
+    #[error("棋譜を解析できません: {offset}文字目の {found:?}（\"F5D6C3\" のように英字と数字の組で書いてください）")]
+    Syntax { offset: usize, found: String },
+
+    #[error("{ply}手目の{square}は盤面の範囲外です")]
+    OutOfBoard { ply: usize, square: String },
+
+    #[error("{ply}手目の{square}は合法手ではありません")]
+    IllegalMove { ply: usize, square: String },
+
+    #[error("{ply}手目の{square}は終局後の着手です")]
+    MoveAfterGameOver { ply: usize, square: String },
+}
+
+/// 着手を棋譜文字列にする（列の英字は大文字）
+pub fn to_transcript(moves: impl IntoIterator<Item = Position>) -> String {
+    moves.into_iter().map(|position| position.to_string().to_ascii_uppercase()).collect()
+}
+
+/// 棋譜文字列を着手の列に解析する（大文字小文字は区別せず、空白は読み飛ばす）
+pub fn parse_transcript(text: &str) -> Result<Vec<Position>, TranscriptError> {
+    let chars: Vec<(usize, char)> = text.char_indices().filter(|(_, c)| !c.is_whitespace()).collect();
+    let mut moves = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let (offset, letter) = chars[index];
+        let digits: String = chars[index + 1..].iter().map(|&(_, c)| c).take_while(char::is_ascii_digit).collect();
+        let token = format!("{}{}", letter, digits);
+        let position = token
+            .parse::<Position>()
+            .map_err(|_| TranscriptError::Syntax { offset: offset + 1, found: token.clone() })?;
+        moves.push(position);
+        index += 1 + digits.len();
+    }
+    Ok(moves)
+}
+
+/// 局面から着手の列をルールどおりに再生する
+/// 合法手のない手番は自動でパスし、最後まで再生して終局していれば勝敗を記録する
+pub fn replay(mut state: GameState, moves: &[Position]) -> Result<GameState, TranscriptError> {
+    for (index, &position) in moves.iter().enumerate() {
+        let (ply, square) = (index + 1, position.to_string().to_ascii_uppercase());
+        if !state.board.contains(position) {
+            return Err(TranscriptError::OutOfBoard { ply, square });
+        }
+        ReversiRules::handle_turn(&mut state);
+        if state.is_finished() {
+            return Err(TranscriptError::MoveAfterGameOver { ply, square });
+        }
+        ReversiRules::apply_move(&mut state, position).map_err(|_| TranscriptError::IllegalMove { ply, square })?;
+        state.switch_player();
+    }
+    ReversiRules::handle_turn(&mut state);
+    Ok(state)
+}
+
+/// 棋譜文字列を解析し、局面から再生する
+pub fn import(text: &str, start: GameState) -> Result<GameState, TranscriptError> {
+    replay(start, &parse_transcript(text)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{GameStatus, Player};
+
+    #[test]
+    fn test_transcript_round_trip_and_validation() {
+        // 9手で白の石がなくなる対局
+        let wipeout = "D3C3B3D2E1D6D7E3F4";
+        let moves = parse_transcript(wipeout).unwrap();
+        assert_eq!(moves[0], Position { row: 2, col: 3 });
+        assert_eq!(to_transcript(moves.iter().copied()), wipeout);
+        assert_eq!(parse_transcript("d3 c3\nb3").unwrap(), moves[..3]);
+
+        let state = import(wipeout, GameState::new()).unwrap();
+        assert_eq!(state.get_move_count(), 9);
+        assert!(matches!(state.game_status, GameStatus::Finished { winner: Some(Player::Black), .. }));
+
+        let opening = import("D3C3", GameState::new()).unwrap();
+        assert_eq!((opening.current_player, opening.get_move_count()), (Player::Black, 2));
+
+        assert_eq!(
+            parse_transcript("D3C3X3"),
+            Err(TranscriptError::Syntax { offset: 5, found: "X3".to_string() })
+        );
+        assert_eq!(
+            import("D3D3", GameState::new()).unwrap_err(),
+            TranscriptError::IllegalMove { ply: 2, square: "D3".to_string() }
+        );
+        assert_eq!(
+            import(&format!("{}A1", wipeout), GameState::new()).unwrap_err(),
+            TranscriptError::MoveAfterGameOver { ply: 10, square: "A1".to_string() }
+        );
+        assert_eq!(
+            import("J10", GameState::new()).unwrap_err(),
+            TranscriptError::OutOfBoard { ply: 1, square: "J10".to_string() }
+        );
+    }
+}
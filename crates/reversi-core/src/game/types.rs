@@ -0,0 +1,224 @@
+//! ゲームの基本型定義モジュール
+//! リバーシゲームで使用される基本的な型とenum、構造体を定義する。
+
+use serde::{Deserialize, Deserializer, Serialize};
+use core::fmt;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use utoipa::ToSchema;
+
+use super::board::MAX_BOARD_SIZE;
+use crate::prelude::*;
+use crate::serde_util;
+
+/// 盤面の各マスの状態を表現するenum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Cell {
+    Empty,
+    Black,
+    White,
+}
+
+/// ゲームのプレイヤーを表すenum
+/// 先手は黒、後手は白
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "std", derive(ToSchema))]
+pub enum Player {
+    Black,
+    White,
+}
+
+impl Player {
+    /// 相手プレイヤーを返す
+    pub fn opposite(self) -> Player {
+        match self {
+            Player::Black => Player::White,
+            Player::White => Player::Black,
+        }
+    }
+    
+    /// プレイヤーを対応するセル状態に変換する
+    pub fn to_cell(self) -> Cell {
+        match self {
+            Player::Black => Cell::Black,
+            Player::White => Cell::White,
+        }
+    }
+}
+
+impl FromStr for Player {
+    type Err = String;
+
+    /// 大文字小文字を区別せずに解析する（"b" / "w" の略記も受け付ける）
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match serde_util::resolve_variant(s, &["Black", "White"], &[("b", "Black"), ("w", "White")]) {
+            Some("Black") => Ok(Player::Black),
+            Some("White") => Ok(Player::White),
+            _ => Err(format!("Invalid player: {}. Valid options: black, white", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Player {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde_util::deserialize_from_str(deserializer)
+    }
+}
+
+/// リバーシ盤面上の座標を表す構造体
+/// 標準の8x8盤面ではrow, colともに0-7の範囲で有効
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "std", derive(ToSchema))]
+pub struct Position {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Position {
+    /// 範囲チェック付きのコンストラクタ
+    /// 8x8盤面の範囲外の座標の場合はNoneを返す
+    pub fn new(row: usize, col: usize) -> Option<Position> {
+        Self::within(row, col, 8)
+    }
+    
+    /// 一辺が `size` マスの盤面の範囲外の座標の場合はNoneを返す
+    pub fn within(row: usize, col: usize, size: usize) -> Option<Position> {
+        if row < size && col < size {
+            Some(Position { row, col })
+        } else {
+            None
+        }
+    }
+    
+    /// 座標が8x8盤面の有効範囲内かチェックする
+    pub fn is_valid(&self) -> bool {
+        self.row < 8 && self.col < 8
+    }
+}
+
+/// オセロの標準表記（列をaから始まる英字、行を1から始まる数字で表す。例: "d3" は (2, 3)）
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", (b'a' + self.col as u8) as char, self.row + 1)
+    }
+}
+
+impl FromStr for Position {
+    type Err = String;
+
+    /// 大文字小文字を区別せずに解析する（10x10盤面の "j10" まで）
+    /// 盤面の大きさによる範囲チェックは呼び出し側で行う
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid square: {}. Use a letter and a number such as d3", s);
+        let mut chars = s.trim().chars();
+        let col = chars
+            .next()
+            .map(|c| c.to_ascii_lowercase())
+            .filter(|c| ('a'..='j').contains(c))
+            .ok_or_else(invalid)?;
+        let row = chars
+            .as_str()
+            .parse::<usize>()
+            .ok()
+            .filter(|row| (1..=MAX_BOARD_SIZE).contains(row))
+            .ok_or_else(invalid)?;
+        Ok(Position { row: row - 1, col: col as usize - 'a' as usize })
+    }
+}
+
+/// ゲームの1手を表現する構造体
+/// 手の情報とひっくり返された石の位置、タイムスタンプ（stdのみ）を保持する
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Move {
+    pub player: Player,
+    pub position: Position,
+    pub flipped: Vec<Position>,
+    #[cfg(feature = "std")]
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+impl Move {
+    /// 新しい手を作成する
+    /// タイムスタンプは現在時刻で自動設定される
+    pub fn new(player: Player, position: Position, flipped: Vec<Position>) -> Self {
+        Self {
+            player,
+            position,
+            flipped,
+            #[cfg(feature = "std")]
+            timestamp: chrono::Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_player_opposite() {
+        assert_eq!(Player::Black.opposite(), Player::White);
+        assert_eq!(Player::White.opposite(), Player::Black);
+    }
+
+    #[test]
+    fn test_player_to_cell() {
+        assert_eq!(Player::Black.to_cell(), Cell::Black);
+        assert_eq!(Player::White.to_cell(), Cell::White);
+    }
+
+    #[test]
+    fn test_player_deserialize_case_insensitive() {
+        for input in [r#""Black""#, r#""black""#, r#""BLACK""#, r#""b""#] {
+            assert_eq!(serde_json::from_str::<Player>(input).unwrap(), Player::Black);
+        }
+        assert_eq!(serde_json::from_str::<Player>(r#""white""#).unwrap(), Player::White);
+        assert!(serde_json::from_str::<Player>(r#""red""#).is_err());
+        assert_eq!(serde_json::to_string(&Player::White).unwrap(), r#""White""#);
+    }
+
+    #[test]
+    fn test_position_new_valid() {
+        let pos = Position::new(3, 4);
+        assert!(pos.is_some());
+        assert_eq!(pos.unwrap(), Position { row: 3, col: 4 });
+    }
+
+    #[test]
+    fn test_position_new_invalid() {
+        assert!(Position::new(8, 4).is_none());
+        assert!(Position::new(3, 8).is_none());
+        assert!(Position::new(10, 10).is_none());
+    }
+
+    #[test]
+    fn test_position_algebraic_notation() {
+        assert_eq!(Position::new(2, 3).unwrap().to_string(), "d3");
+        assert_eq!(Position { row: 9, col: 9 }.to_string(), "j10");
+        assert_eq!("d3".parse::<Position>(), Ok(Position { row: 2, col: 3 }));
+        assert_eq!(" H8 ".parse::<Position>(), Ok(Position { row: 7, col: 7 }));
+        assert_eq!("j10".parse::<Position>(), Ok(Position { row: 9, col: 9 }));
+        for invalid in ["", "d", "3d", "k1", "a0", "a11", "d3x"] {
+            assert!(invalid.parse::<Position>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_position_is_valid() {
+        assert!(Position { row: 0, col: 0 }.is_valid());
+        assert!(Position { row: 7, col: 7 }.is_valid());
+        assert!(!Position { row: 8, col: 0 }.is_valid());
+        assert!(!Position { row: 0, col: 8 }.is_valid());
+    }
+    
+    #[test]
+    fn test_move_creation() {
+        let pos = Position::new(3, 4).unwrap();
+        let flipped = vec![Position::new(3, 3).unwrap()];
+        let move_obj = Move::new(Player::Black, pos, flipped.clone());
+        
+        assert_eq!(move_obj.player, Player::Black);
+        assert_eq!(move_obj.position, pos);
+        assert_eq!(move_obj.flipped, flipped);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,98 @@
+//! 対局のルールの種類
+//! 通常のオセロのほか、石の少ない方が勝つアンチリバーシと、
+//! 中央の4石の配置を原始リバーシのようにプレイヤーが置いた場合の配置から選ぶ変種を扱う。
+
+use serde::{Deserialize, Serialize};
+use crate::prelude::*;
+#[cfg(feature = "std")]
+use utoipa::ToSchema;
+
+use super::board::Board;
+use super::types::{Cell, Player, Position};
+
+/// 対局のルールの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "std", derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum GameVariant {
+    /// 通常のオセロ（石の多い方が勝ち）
+    #[default]
+    Standard,
+    /// アンチリバーシ（石の少ない方が勝ち）
+    AntiReversi,
+    /// 原始リバーシの開始（最初の4石をプレイヤーが中央に置いた配置から始める）
+    /// 置き方でありうる6通りの配置から、対局のシードで1つを選ぶ
+    RandomStart,
+}
+
+impl GameVariant {
+    /// 石の少ない方が勝つルールか
+    pub fn fewer_discs_win(self) -> bool {
+        matches!(self, GameVariant::AntiReversi)
+    }
+
+    /// 石差を勝敗の向きにそろえる係数（石の少ない方が勝つルールでは-1）
+    pub fn score_sign(self) -> i32 {
+        if self.fewer_discs_win() { -1 } else { 1 }
+    }
+
+    /// 石数から勝者を決める（同数の場合はNone）
+    pub fn winner(self, (black_count, white_count): (u8, u8)) -> Option<Player> {
+        let (black, white) = (black_count as i32 * self.score_sign(), white_count as i32 * self.score_sign());
+        match black.cmp(&white) {
+            core::cmp::Ordering::Greater => Some(Player::Black),
+            core::cmp::Ordering::Less => Some(Player::White),
+            core::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// このルールの初期配置（`seed` は中央の4石の配置を選ぶルールでのみ使う）
+    /// `size` は2以上であること
+    pub fn initial_layout(self, size: usize, seed: u64) -> Vec<(Position, Cell)> {
+        let mut layout = Board::initial_layout(size);
+        if self == GameVariant::RandomStart {
+            // 黒白2石ずつを交互に置いてできる配置は、黒の2マスの選び方の6通り
+            let (first, second) = CENTER_ARRANGEMENTS[(seed % CENTER_ARRANGEMENTS.len() as u64) as usize];
+            for (index, (_, cell)) in layout.iter_mut().enumerate() {
+                *cell = if index == first || index == second { Cell::Black } else { Cell::White };
+            }
+        }
+        layout
+    }
+
+    /// このルールの初期配置の盤面（対応していない大きさの場合はNone）
+    pub fn initial_board(self, size: usize, seed: u64) -> Option<Board> {
+        if !Board::is_supported_size(size) {
+            return None;
+        }
+        Board::from_layout(size, self.initial_layout(size, seed))
+    }
+}
+
+/// 中央の4マス（左上・右上・左下・右下の順）のうち黒を置くマスの組
+const CENTER_ARRANGEMENTS: [(usize, usize); 6] = [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ReversiRules;
+
+    #[test]
+    fn test_variant_winner_and_initial_board() {
+        assert_eq!(GameVariant::Standard.winner((40, 24)), Some(Player::Black));
+        assert_eq!(GameVariant::AntiReversi.winner((40, 24)), Some(Player::White));
+        assert_eq!(GameVariant::AntiReversi.winner((32, 32)), None);
+
+        assert_eq!(GameVariant::AntiReversi.initial_board(8, 3), Some(Board::new()));
+        let boards: Vec<Board> = (0..6).map(|seed| GameVariant::RandomStart.initial_board(8, seed).unwrap()).collect();
+        assert!(boards.contains(&Board::new()));
+        for (index, board) in boards.iter().enumerate() {
+            assert!(!boards[..index].contains(board));
+        }
+        for seed in 0..6 {
+            let board = GameVariant::RandomStart.initial_board(6, seed).unwrap();
+            assert_eq!(board.count_pieces(), (2, 2));
+            assert!(ReversiRules::has_valid_moves(&board, Player::Black));
+        }
+    }
+}
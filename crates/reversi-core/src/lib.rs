@@ -0,0 +1,28 @@
+//! リバーシのルールエンジンとAI戦略
+//! 盤面・合法手・棋譜の読み書きと、αβ探索などのAI戦略・評価関数を提供する。
+//! tokioやaxumには依存しないため、APIサーバーを使わないアプリケーションやWebAssemblyからも利用できる。
+//! `std` フィーチャーを無効にすると、ルールエンジン（`game`）だけをno_stdでビルドする。
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod game;
+#[cfg(feature = "std")]
+pub mod ai;
+pub mod error;
+pub mod serde_util;
+#[cfg(feature = "std")]
+pub mod wthor;
+
+#[cfg(feature = "std")]
+pub use error::PersistenceError;
+pub use error::{GameError, AIError, Result};
+
+/// stdのプレリュードのうち、no_stdではallocから取り込む型とマクロ
+pub(crate) mod prelude {
+    pub use alloc::format;
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+}
@@ -0,0 +1,103 @@
+//! serde補助モジュール
+//! APIで受け付ける列挙型を大文字小文字・区切り文字を問わずデシリアライズするための
+//! 共通処理を提供する。シリアライズ形式（"Easy" など）は変更しない。
+
+use serde::{de::Error as _, Deserialize, Deserializer};
+use serde_json::Value;
+use core::{fmt::Display, str::FromStr};
+use crate::prelude::*;
+
+/// 列挙値の表記を比較用に正規化する
+/// 小文字化し、`_`・`-`・空白を取り除く（"In_Progress" → "inprogress"）
+pub fn normalize_variant(input: &str) -> String {
+    input
+        .chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 入力を正規のバリアント名に解決する
+/// `aliases` は（別名, 正規名）の組で、別名も正規化して比較する
+pub fn resolve_variant(
+    input: &str,
+    variants: &[&'static str],
+    aliases: &[(&str, &'static str)],
+) -> Option<&'static str> {
+    let normalized = normalize_variant(input);
+
+    variants
+        .iter()
+        .copied()
+        .find(|variant| normalize_variant(variant) == normalized)
+        .or_else(|| {
+            aliases
+                .iter()
+                .find(|(alias, _)| normalize_variant(alias) == normalized)
+                .map(|&(_, variant)| variant)
+        })
+}
+
+/// `FromStr` 実装を経由してデシリアライズする
+/// 大文字小文字を区別しない単純な列挙型の `Deserialize` 実装で使用する
+pub fn deserialize_from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(D::Error::custom)
+}
+
+/// 外部タグ形式の列挙値のタグを正規のバリアント名に書き換える
+/// 文字列（ユニットバリアント）と単一キーのオブジェクト（データ付きバリアント）に対応する
+pub fn canonicalize_enum_tag(
+    value: Value,
+    variants: &[&'static str],
+    aliases: &[(&str, &'static str)],
+) -> Value {
+    match value {
+        Value::String(tag) => match resolve_variant(&tag, variants, aliases) {
+            Some(variant) => Value::String(variant.to_string()),
+            None => Value::String(tag),
+        },
+        Value::Object(map) if map.len() == 1 => {
+            let (tag, content) = map.into_iter().next().unwrap();
+            let tag = resolve_variant(&tag, variants, aliases)
+                .map(str::to_string)
+                .unwrap_or(tag);
+            Value::Object([(tag, content)].into_iter().collect())
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const VARIANTS: &[&str] = &["InProgress", "Finished"];
+    const ALIASES: &[(&str, &str)] = &[("done", "Finished")];
+
+    #[test]
+    fn test_resolve_variant_ignores_case_and_separators() {
+        for input in ["InProgress", "inprogress", "IN_PROGRESS", "in-progress", "In Progress"] {
+            assert_eq!(resolve_variant(input, VARIANTS, ALIASES), Some("InProgress"));
+        }
+        assert_eq!(resolve_variant("DONE", VARIANTS, ALIASES), Some("Finished"));
+        assert_eq!(resolve_variant("paused", VARIANTS, ALIASES), None);
+    }
+
+    #[test]
+    fn test_canonicalize_enum_tag() {
+        assert_eq!(canonicalize_enum_tag(json!("in_progress"), VARIANTS, ALIASES), json!("InProgress"));
+        assert_eq!(
+            canonicalize_enum_tag(json!({"finished": {"winner": null}}), VARIANTS, ALIASES),
+            json!({"Finished": {"winner": null}})
+        );
+        assert_eq!(canonicalize_enum_tag(json!("unknown"), VARIANTS, ALIASES), json!("unknown"));
+        assert_eq!(canonicalize_enum_tag(json!(42), VARIANTS, ALIASES), json!(42));
+    }
+}
@@ -0,0 +1,263 @@
+//! WTHOR形式（.wtb）の棋譜データベース
+//! 16バイトのヘッダーと、1局あたり68バイトの対局レコードからなる。
+//! 対局レコードは大会番号・黒番/白番のプレイヤー番号（各2バイト、リトルエンディアン）、
+//! 終局時の黒石数、理論スコア、60手分の着手（`10 * 行 + 列`、行・列は1始まり、未使用は0）を並べる。
+//! パスは記録されず、次に着手した色から手番を判断する。
+//! 8x8の盤の対局データベースのみを扱う。
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use std::io::{self, Read, Write};
+use thiserror::Error;
+
+use crate::game::transcript::{self, TranscriptError};
+use crate::game::{GameState, Position};
+
+/// ヘッダーのバイト数
+pub const HEADER_SIZE: usize = 16;
+
+/// 対局レコードのバイト数
+pub const RECORD_SIZE: usize = 68;
+
+/// 1局に記録できる着手数
+pub const MAX_MOVES: usize = 60;
+
+/// WTHOR形式の読み込みのエラー
+#[derive(Debug, Error)]
+pub enum WthorError {
+    #[error("ファイルを読み込めません: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("ヘッダーが短すぎます: {0}バイト")]
+    TruncatedHeader(usize),
+
+    #[error("対応していない盤面サイズです: {0}（8x8のみ対応）")]
+    UnsupportedBoardSize(u8),
+
+    #[error("対局レコードが不足しています: ヘッダーでは{expected}局、実際は{actual}局")]
+    TruncatedGames { expected: u32, actual: usize },
+
+    #[error("{game}局目の{ply}手目の着手 {byte} を解釈できません")]
+    InvalidMove { game: usize, ply: usize, byte: u8 },
+}
+
+/// 読み込んだWTHOR形式のデータベース
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WthorDatabase {
+    /// ファイルの作成日（ヘッダーの日付が不正な場合はNone）
+    pub created: Option<NaiveDate>,
+    /// 対局の年
+    pub games_year: u16,
+    /// 理論スコアの計算深さ
+    pub depth: u8,
+    pub games: Vec<WthorGame>,
+}
+
+/// WTHORの対局レコード
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WthorGame {
+    /// 大会番号（大会ファイル .trn の通し番号、不明なら0）
+    pub tournament: u16,
+    /// 黒番のプレイヤー番号（プレイヤーファイル .jou の通し番号、不明なら0）
+    pub black_player: u16,
+    pub white_player: u16,
+    /// 終局時の黒石数
+    pub black_discs: u8,
+    /// 両者が最善を尽くした場合の黒石数（未計算の場合は実際の石数を入れる）
+    pub theoretical_black_discs: u8,
+    /// パスを除く着手順
+    pub moves: Vec<Position>,
+}
+
+impl WthorGame {
+    /// 対局の盤面と着手履歴から作る（プレイヤー・大会は不明として0にする）
+    pub fn from_state(state: &GameState) -> Self {
+        let (black_discs, _) = state.board.count_pieces();
+        Self {
+            tournament: 0,
+            black_player: 0,
+            white_player: 0,
+            black_discs,
+            theoretical_black_discs: black_discs,
+            moves: state.move_history.iter().map(|game_move| game_move.position).collect(),
+        }
+    }
+
+    /// 初期局面から着手をルールどおりに再生する（合法手のない手番は自動でパスする）
+    pub fn replay(&self) -> Result<GameState, TranscriptError> {
+        transcript::replay(GameState::new(), &self.moves)
+    }
+
+    /// 黒番から見た終局時の石差（空きマスは勝った側に数える）
+    pub fn black_margin(&self) -> i32 {
+        2 * self.black_discs as i32 - 64
+    }
+
+    fn encode(&self) -> [u8; RECORD_SIZE] {
+        let mut record = [0u8; RECORD_SIZE];
+        record[0..2].copy_from_slice(&self.tournament.to_le_bytes());
+        record[2..4].copy_from_slice(&self.black_player.to_le_bytes());
+        record[4..6].copy_from_slice(&self.white_player.to_le_bytes());
+        record[6] = self.black_discs;
+        record[7] = self.theoretical_black_discs;
+        for (slot, position) in record[8..].iter_mut().zip(&self.moves) {
+            *slot = encode_move(*position);
+        }
+        record
+    }
+}
+
+/// 着手を `10 * 行 + 列`（行・列は1始まり）に符号化する（f5 は56）
+pub fn encode_move(position: Position) -> u8 {
+    (10 * (position.row + 1) + position.col + 1) as u8
+}
+
+/// `10 * 行 + 列` の符号を着手に戻す（盤外の符号はNone）
+pub fn decode_move(byte: u8) -> Option<Position> {
+    let (row, col) = ((byte / 10) as usize, (byte % 10) as usize);
+    Position::new(row.checked_sub(1)?, col.checked_sub(1)?)
+}
+
+fn decode_game(index: usize, record: &[u8]) -> Result<WthorGame, WthorError> {
+    let word = |offset: usize| u16::from_le_bytes([record[offset], record[offset + 1]]);
+    let moves = record[8..]
+        .iter()
+        .take_while(|&&byte| byte != 0)
+        .enumerate()
+        .map(|(ply, &byte)| decode_move(byte).ok_or(WthorError::InvalidMove { game: index + 1, ply: ply + 1, byte }))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(WthorGame {
+        tournament: word(0),
+        black_player: word(2),
+        white_player: word(4),
+        black_discs: record[6],
+        theoretical_black_discs: record[7],
+        moves,
+    })
+}
+
+/// WTHOR形式のデータベースを読み込む
+/// 着手がルールどおりかは検証しない（`WthorGame::replay` で確かめる）
+pub fn read_database<R: Read>(mut reader: R) -> Result<WthorDatabase, WthorError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    if bytes.len() < HEADER_SIZE {
+        return Err(WthorError::TruncatedHeader(bytes.len()));
+    }
+    let (header, body) = bytes.split_at(HEADER_SIZE);
+    if header[12] != 0 && header[12] != 8 {
+        return Err(WthorError::UnsupportedBoardSize(header[12]));
+    }
+
+    let expected = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    let records: Vec<&[u8]> = body.chunks_exact(RECORD_SIZE).take(expected as usize).collect();
+    if records.len() < expected as usize {
+        return Err(WthorError::TruncatedGames { expected, actual: records.len() });
+    }
+    let games = records
+        .into_iter()
+        .enumerate()
+        .map(|(index, record)| decode_game(index, record))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let year = header[0] as i32 * 100 + header[1] as i32;
+    Ok(WthorDatabase {
+        created: NaiveDate::from_ymd_opt(year, header[2] as u32, header[3] as u32),
+        games_year: u16::from_le_bytes([header[10], header[11]]),
+        depth: header[14],
+        games,
+    })
+}
+
+/// 対局をWTHOR形式で書き出す
+/// `games_year` はヘッダーに記録する対局の年、`created` はファイルの作成日
+pub fn write_database<W: Write>(
+    mut writer: W,
+    games: &[WthorGame],
+    games_year: u16,
+    created: DateTime<Utc>,
+) -> io::Result<()> {
+    let count = u32::try_from(games.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "対局数が多すぎます"))?;
+    if let Some(game) = games.iter().find(|game| game.moves.len() > MAX_MOVES) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("着手数が{}手を超えています: {}手", MAX_MOVES, game.moves.len()),
+        ));
+    }
+
+    let mut header = [0u8; HEADER_SIZE];
+    header[0] = (created.year() / 100) as u8;
+    header[1] = (created.year() % 100) as u8;
+    header[2] = created.month() as u8;
+    header[3] = created.day() as u8;
+    header[4..8].copy_from_slice(&count.to_le_bytes());
+    // 8..10 はプレイヤー・大会ファイル用のレコード数（.wtb では0）
+    header[10..12].copy_from_slice(&games_year.to_le_bytes());
+    // 12: 盤面サイズ（0は8x8）、13: 対局の種類（0は通常）、14: 理論スコアの計算深さ、15: 予約
+    writer.write_all(&header)?;
+
+    for game in games {
+        writer.write_all(&game.encode())?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ReversiRules;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_write_database_layout() {
+        let mut state = GameState::new();
+        for (row, col) in [(4, 5), (5, 3)] {
+            ReversiRules::apply_move(&mut state, Position::new(row, col).unwrap()).unwrap();
+            state.switch_player();
+        }
+        let game = WthorGame::from_state(&state);
+        let created = Utc.with_ymd_and_hms(2026, 10, 16, 0, 0, 0).unwrap();
+
+        let mut bytes = Vec::new();
+        write_database(&mut bytes, &[game.clone(), game], 2026, created).unwrap();
+
+        assert_eq!(bytes.len(), HEADER_SIZE + 2 * RECORD_SIZE);
+        assert_eq!(&bytes[0..4], &[20, 26, 10, 16]);
+        assert_eq!(u32::from_le_bytes(bytes[4..8].try_into().unwrap()), 2);
+        assert_eq!(u16::from_le_bytes(bytes[10..12].try_into().unwrap()), 2026);
+
+        let record = &bytes[HEADER_SIZE..HEADER_SIZE + RECORD_SIZE];
+        assert_eq!(record[6], 3);
+        // f5 → 56, d6 → 64、以降は0で埋める
+        assert_eq!(&record[8..11], &[56, 64, 0]);
+    }
+
+    #[test]
+    fn test_read_database_round_trip() {
+        let state = transcript::import("F5D6C3D3C4", GameState::new()).unwrap();
+        let game = WthorGame { tournament: 7, black_player: 12, white_player: 345, ..WthorGame::from_state(&state) };
+        let created = Utc.with_ymd_and_hms(2026, 10, 16, 0, 0, 0).unwrap();
+        let mut bytes = Vec::new();
+        write_database(&mut bytes, std::slice::from_ref(&game), 2025, created).unwrap();
+
+        let database = read_database(bytes.as_slice()).unwrap();
+        assert_eq!(database.created, NaiveDate::from_ymd_opt(2026, 10, 16));
+        assert_eq!(database.games_year, 2025);
+        assert_eq!(database.games, vec![game.clone()]);
+        assert_eq!(database.games[0].replay().unwrap().board, state.board);
+        assert_eq!(decode_move(encode_move(Position::new(7, 0).unwrap())), Some(Position::new(7, 0).unwrap()));
+
+        assert!(matches!(
+            read_database(&bytes[..HEADER_SIZE + 10]),
+            Err(WthorError::TruncatedGames { expected: 1, actual: 0 })
+        ));
+        let mut corrupted = bytes.clone();
+        corrupted[HEADER_SIZE + 9] = 99;
+        assert!(matches!(
+            read_database(corrupted.as_slice()),
+            Err(WthorError::InvalidMove { game: 1, ply: 2, byte: 99 })
+        ));
+        corrupted[12] = 10;
+        assert!(matches!(read_database(corrupted.as_slice()), Err(WthorError::UnsupportedBoardSize(10))));
+    }
+}
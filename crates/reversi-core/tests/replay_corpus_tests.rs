@@ -0,0 +1,145 @@
+//! 棋譜コーパスによるルールエンジンの回帰テストモジュール
+//! `tests/data/replays` の全対局を `ReversiRules` で再生し、
+//! 終局時の盤面と石数が記録と一致することを確認する。
+//!
+//! 棋譜ファイルの形式:
+//! - `#` で始まる行はコメント
+//! - `moves:` 行に着手を空白区切りで並べる（列 a-h、行 1-8、パスは `pass`）。複数行に分けてよい
+//! - `black:` / `white:` 行に終局時の石数
+//! - `board:` 行の後に終局時の盤面を上の行から8行（`X` 黒、`O` 白、`.` 空き）
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use reversi_core::game::{Board, Cell, GameState, Player, Position, ReversiRules};
+
+/// 棋譜ファイル1件分の記録
+struct Replay {
+    name: String,
+    moves: Vec<Option<Position>>,
+    black: u8,
+    white: u8,
+    board: Board,
+}
+
+fn parse_move(token: &str) -> Option<Position> {
+    if token == "pass" {
+        return None;
+    }
+    let mut chars = token.chars();
+    let col = chars.next().and_then(|c| "abcdefgh".find(c));
+    let row = chars.as_str().parse::<usize>().ok().and_then(|row| row.checked_sub(1));
+    match (row, col) {
+        (Some(row), Some(col)) => Some(Position::new(row, col).unwrap_or_else(|| panic!("盤外の着手: {}", token))),
+        _ => panic!("着手を解釈できません: {}", token),
+    }
+}
+
+fn parse_replay(path: &Path) -> Replay {
+    let text = fs::read_to_string(path).unwrap();
+    let mut moves = Vec::new();
+    let mut black = None;
+    let mut white = None;
+    let mut rows = Vec::new();
+    let mut in_board = false;
+
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        if in_board {
+            rows.push(line.to_string());
+        } else if let Some(rest) = line.strip_prefix("moves:") {
+            moves.extend(rest.split_whitespace().map(parse_move));
+        } else if let Some(rest) = line.strip_prefix("black:") {
+            black = rest.trim().parse().ok();
+        } else if let Some(rest) = line.strip_prefix("white:") {
+            white = rest.trim().parse().ok();
+        } else if line == "board:" {
+            in_board = true;
+        } else {
+            panic!("{}: 不明な行: {}", path.display(), line);
+        }
+    }
+
+    assert_eq!(rows.len(), 8, "{}: 盤面は8行必要です", path.display());
+    let mut board = Board::new();
+    for (row, line) in rows.iter().enumerate() {
+        assert_eq!(line.len(), 8, "{}: 盤面の{}行目は8列必要です", path.display(), row + 1);
+        for (col, symbol) in line.chars().enumerate() {
+            let cell = match symbol {
+                'X' => Cell::Black,
+                'O' => Cell::White,
+                '.' => Cell::Empty,
+                other => panic!("{}: 不明な記号: {}", path.display(), other),
+            };
+            board.set_cell(Position::new(row, col).unwrap(), cell);
+        }
+    }
+
+    Replay {
+        name: path.file_stem().unwrap().to_string_lossy().into_owned(),
+        moves,
+        black: black.unwrap_or_else(|| panic!("{}: black: 行がありません", path.display())),
+        white: white.unwrap_or_else(|| panic!("{}: white: 行がありません", path.display())),
+        board,
+    }
+}
+
+fn load_corpus() -> Vec<Replay> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/replays");
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    paths.sort();
+    paths.iter().map(|path| parse_replay(path)).collect()
+}
+
+/// 棋譜を最初から再生し、終局した状態を返す
+fn replay(game: &Replay) -> GameState {
+    let mut state = GameState::new();
+    for (ply, game_move) in game.moves.iter().enumerate() {
+        let player = state.current_player;
+        match game_move {
+            Some(position) => {
+                ReversiRules::apply_move(&mut state, *position)
+                    .unwrap_or_else(|e| panic!("{}: {}手目 {:?} の着手が不正: {}", game.name, ply + 1, player, e));
+            }
+            None => assert!(
+                !ReversiRules::has_valid_moves(&state.board, player),
+                "{}: {}手目 {:?} は合法手があるのにパスしている",
+                game.name,
+                ply + 1,
+                player,
+            ),
+        }
+        state.switch_player();
+    }
+    state
+}
+
+#[test]
+fn test_corpus_is_not_empty() {
+    let corpus = load_corpus();
+    assert!(corpus.len() >= 5);
+    // パスを含む対局と、盤が埋まる前に終わる対局を必ず含める
+    assert!(corpus.iter().any(|game| game.moves.iter().any(Option::is_none)));
+    assert!(corpus.iter().any(|game| (game.black + game.white) < 64));
+}
+
+#[test]
+fn test_replays_reach_recorded_final_positions() {
+    for game in load_corpus() {
+        let state = replay(&game);
+
+        assert!(ReversiRules::is_game_over(&state.board), "{}: 棋譜の最後で終局していない", game.name);
+        assert_eq!(state.board, game.board, "{}: 終局時の盤面が一致しない\n{}", game.name, state.board.display());
+        assert_eq!(state.get_score(), (game.black, game.white), "{}: 石数が一致しない", game.name);
+
+        let expected_winner = match game.black.cmp(&game.white) {
+            std::cmp::Ordering::Greater => Some(Player::Black),
+            std::cmp::Ordering::Less => Some(Player::White),
+            std::cmp::Ordering::Equal => None,
+        };
+        assert_eq!(ReversiRules::determine_winner(&state.board, state.variant), expected_winner, "{}: 勝者が一致しない", game.name);
+    }
+}
@@ -0,0 +1,133 @@
+#ifndef REVERSI_H
+#define REVERSI_H
+
+/* build.rs（cbindgen）が src/ffi.rs から生成するファイルのため、直接編集しないこと */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * 関数の結果
+ */
+typedef enum ReversiStatus {
+  REVERSI_STATUS_OK = 0,
+  /**
+   * NULLポインタが渡された
+   */
+  REVERSI_STATUS_NULL_POINTER = -1,
+  /**
+   * マス番号・難易度が範囲外
+   */
+  REVERSI_STATUS_INVALID_ARGUMENT = -2,
+  /**
+   * 合法手ではない
+   */
+  REVERSI_STATUS_INVALID_MOVE = -3,
+  /**
+   * 終局している
+   */
+  REVERSI_STATUS_GAME_FINISHED = -4,
+  /**
+   * AIが着手を計算できなかった
+   */
+  REVERSI_STATUS_AI_FAILED = -5,
+} ReversiStatus;
+
+/**
+ * 対局（中身はC側からは見えない）
+ */
+typedef struct ReversiGame ReversiGame;
+
+/**
+ * FFIのバージョン
+ */
+uint32_t reversi_ffi_version(void);
+
+/**
+ * 新しい対局（初期配置・黒番）を作る。使い終わったら `reversi_free_game` で解放する
+ */
+struct ReversiGame *reversi_new_game(void);
+
+/**
+ * 対局を解放する（NULLの場合は何もしない）
+ *
+ * # Safety
+ * `game` は `reversi_new_game` が返したポインタで、まだ解放していないこと
+ */
+void reversi_free_game(struct ReversiGame *game);
+
+/**
+ * 64マス分の石の色を `out` に書き込む（a1からh8へ行ごと）
+ *
+ * # Safety
+ * `game` は有効な対局、`out` は64バイト以上書き込めること
+ */
+enum ReversiStatus reversi_board(const struct ReversiGame *game, uint8_t *out);
+
+/**
+ * 手番の色（NULLの場合は0）
+ *
+ * # Safety
+ * `game` は有効な対局かNULLであること
+ */
+uint8_t reversi_current_player(const struct ReversiGame *game);
+
+/**
+ * 手番の合法手を最大 `capacity` 個 `out` に書き込み、合法手の総数を返す
+ * `out` にNULL、`capacity` に0を渡すと数だけを返す
+ *
+ * # Safety
+ * `game` は有効な対局かNULL、`out` は `capacity` バイト以上書き込めるかNULLであること
+ */
+size_t reversi_valid_moves(const struct ReversiGame *game,
+                           uint8_t *out,
+                           size_t capacity);
+
+/**
+ * 手番の色で着手し、パス判定・終局判定まで行う
+ *
+ * # Safety
+ * `game` は有効な対局かNULLであること
+ */
+enum ReversiStatus reversi_apply_move(struct ReversiGame *game, uint8_t index);
+
+/**
+ * 手番のAIが選ぶ手のマス番号を `out_index` に書き込む
+ * `difficulty` は 0=Easy・1=Medium・2=Hard、`seed` は乱択のシード
+ *
+ * # Safety
+ * `game` は有効な対局かNULL、`out_index` は書き込めるかNULLであること
+ */
+enum ReversiStatus reversi_ai_move(const struct ReversiGame *game,
+                                   int32_t difficulty,
+                                   uint64_t seed,
+                                   uint8_t *out_index);
+
+/**
+ * 終局しているか（NULLの場合はfalse）
+ *
+ * # Safety
+ * `game` は有効な対局かNULLであること
+ */
+bool reversi_is_finished(const struct ReversiGame *game);
+
+/**
+ * 黒・白の石数を書き込む
+ *
+ * # Safety
+ * `game` は有効な対局、`black`・`white` は書き込めること
+ */
+enum ReversiStatus reversi_score(const struct ReversiGame *game, uint8_t *black, uint8_t *white);
+
+/**
+ * 勝者の色（進行中・引き分け・NULLの場合は0）
+ *
+ * # Safety
+ * `game` は有効な対局かNULLであること
+ */
+uint8_t reversi_winner(const struct ReversiGame *game);
+
+#endif  /* REVERSI_H */
@@ -0,0 +1,409 @@
+//! プレイヤーアカウントモジュール
+//! ユーザー名とパスワードでアカウントを登録・ログインし、署名付きのJWTを発行する。
+//! パスワードはArgon2でハッシュ化して保存し、JWTはHS256で署名する。
+//! アカウントの保存先はセッションストアと同じく `DatabaseConfig.session_store` で選択する。
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::ai_battle::{AiBattleError, AiBattleResult};
+use crate::config::{AuthConfig, DatabaseConfig};
+use crate::error::PersistenceError;
+use crate::persistence::{database_error, SqliteSessionStore};
+use crate::session::SessionStoreBackend;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS accounts (
+    id TEXT PRIMARY KEY,
+    username TEXT NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+"#;
+
+/// パスワードの最小文字数
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// 登録済みのアカウント
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+    pub id: Uuid,
+    /// 小文字に正規化したユーザー名
+    pub username: String,
+    /// Argon2のPHC文字列
+    pub password_hash: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// JWTで認証されたアカウント
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountIdentity {
+    pub account_id: Uuid,
+    pub username: String,
+}
+
+/// アカウントの登録・ログインのリクエスト
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CredentialsRequest {
+    /// 3〜32文字の英数字・`_`・`-`（大文字小文字は区別しない）
+    pub username: String,
+    pub password: String,
+}
+
+/// 発行したアクセストークン
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccessTokenResponse {
+    pub account_id: Uuid,
+    pub username: String,
+    /// `Authorization: Bearer` ヘッダーに指定するJWT
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// ログイン中のアカウントの情報
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccountProfile {
+    pub account_id: Uuid,
+    pub username: String,
+}
+
+/// JWTのクレーム
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    /// アカウントID
+    sub: Uuid,
+    name: String,
+    iat: i64,
+    exp: i64,
+}
+
+/// アカウントの保存先の統一インターフェース
+#[async_trait]
+pub trait AccountStore: Send + Sync + fmt::Debug {
+    fn backend_name(&self) -> &'static str;
+
+    /// アカウントを登録する（同じユーザー名が既にあればfalseを返す）
+    async fn insert(&self, account: &Account) -> Result<bool, PersistenceError>;
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<Account>, PersistenceError>;
+}
+
+/// メモリ上のアカウント（再起動で失われる）
+#[derive(Debug, Default)]
+pub struct MemoryAccountStore {
+    accounts: DashMap<String, Account>,
+}
+
+#[async_trait]
+impl AccountStore for MemoryAccountStore {
+    fn backend_name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn insert(&self, account: &Account) -> Result<bool, PersistenceError> {
+        match self.accounts.entry(account.username.clone()) {
+            dashmap::mapref::entry::Entry::Occupied(_) => Ok(false),
+            dashmap::mapref::entry::Entry::Vacant(entry) => {
+                entry.insert(account.clone());
+                Ok(true)
+            }
+        }
+    }
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<Account>, PersistenceError> {
+        Ok(self.accounts.get(username).map(|entry| entry.value().clone()))
+    }
+}
+
+/// SQLiteのアカウント
+#[derive(Debug, Clone)]
+pub struct SqliteAccountStore {
+    pool: SqlitePool,
+}
+
+impl SqliteAccountStore {
+    /// セッションストアと同じデータベースにアカウントのテーブルを作成する
+    pub async fn open(store: &SqliteSessionStore) -> Result<Self, PersistenceError> {
+        let pool = store.pool().clone();
+        sqlx::raw_sql(SCHEMA).execute(&pool).await.map_err(database_error)?;
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl AccountStore for SqliteAccountStore {
+    fn backend_name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn insert(&self, account: &Account) -> Result<bool, PersistenceError> {
+        let result = sqlx::query(
+            "INSERT INTO accounts (id, username, password_hash, created_at) VALUES (?, ?, ?, ?)
+             ON CONFLICT(username) DO NOTHING",
+        )
+        .bind(account.id.to_string())
+        .bind(&account.username)
+        .bind(&account.password_hash)
+        .bind(account.created_at)
+        .execute(&self.pool)
+        .await.map_err(database_error)?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<Account>, PersistenceError> {
+        let row = sqlx::query("SELECT id, username, password_hash, created_at FROM accounts WHERE username = ?")
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await.map_err(database_error)?;
+        row.map(|row| {
+            let id: String = row.get("id");
+            Ok(Account {
+                id: Uuid::parse_str(&id).map_err(|e| PersistenceError::SerializationError { message: e.to_string() })?,
+                username: row.get("username"),
+                password_hash: row.get("password_hash"),
+                created_at: row.get("created_at"),
+            })
+        })
+        .transpose()
+    }
+}
+
+/// 設定で選択された保存先のアカウントストアを開く
+pub async fn open_account_store(config: &DatabaseConfig) -> Result<Arc<dyn AccountStore>, PersistenceError> {
+    match config.session_store {
+        SessionStoreBackend::Memory => Ok(Arc::new(MemoryAccountStore::default())),
+        SessionStoreBackend::Sqlite => {
+            let store = SqliteSessionStore::connect(config).await?;
+            Ok(Arc::new(SqliteAccountStore::open(&store).await?))
+        }
+    }
+}
+
+/// JWTの形式（ピリオド区切りの3要素）か
+/// APIキーと同じ `Authorization: Bearer` ヘッダーを使うため、両者をこの形式で区別する
+pub fn is_jwt(token: &str) -> bool {
+    token.split('.').count() == 3 && token.split('.').all(|part| !part.is_empty())
+}
+
+/// アカウントの登録・ログインとJWTの発行・検証を行う
+pub struct Accounts {
+    store: RwLock<Arc<dyn AccountStore>>,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    token_ttl: Duration,
+}
+
+impl fmt::Debug for Accounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Accounts")
+            .field("store", &self.store().backend_name())
+            .field("token_ttl", &self.token_ttl)
+            .finish()
+    }
+}
+
+impl Default for Accounts {
+    fn default() -> Self {
+        Self::new(&AuthConfig::default())
+    }
+}
+
+impl Accounts {
+    /// 署名鍵が設定されていない場合は起動ごとにランダムな鍵を生成する（再起動で発行済みのトークンは無効になる）
+    pub fn new(config: &AuthConfig) -> Self {
+        let secret = match &config.jwt_secret {
+            Some(secret) => secret.as_bytes().to_vec(),
+            None => {
+                let mut secret = vec![0u8; 32];
+                OsRng.fill_bytes(&mut secret);
+                secret
+            }
+        };
+
+        Self {
+            store: RwLock::new(Arc::new(MemoryAccountStore::default())),
+            encoding_key: EncodingKey::from_secret(&secret),
+            decoding_key: DecodingKey::from_secret(&secret),
+            token_ttl: Duration::minutes(config.token_ttl_minutes as i64),
+        }
+    }
+
+    /// アカウントの保存先を差し替える（既定はメモリ）
+    pub fn attach_store(&self, store: Arc<dyn AccountStore>) {
+        *self.store.write().unwrap() = store;
+    }
+
+    pub fn store(&self) -> Arc<dyn AccountStore> {
+        Arc::clone(&self.store.read().unwrap())
+    }
+
+    /// アカウントを登録し、そのアカウントのトークンを発行する
+    pub async fn register(&self, request: &CredentialsRequest) -> AiBattleResult<AccessTokenResponse> {
+        let username = normalize_username(&request.username)?;
+        if request.password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(AiBattleError::BadRequest {
+                details: format!("パスワードは{}文字以上にしてください", MIN_PASSWORD_LENGTH),
+            });
+        }
+
+        let password = request.password.clone();
+        let password_hash = tokio::task::spawn_blocking(move || {
+            let salt = SaltString::generate(&mut OsRng);
+            Argon2::default().hash_password(password.as_bytes(), &salt).map(|hash| hash.to_string())
+        })
+        .await
+        .map_err(|e| AiBattleError::InternalError { details: e.to_string() })?
+        .map_err(|e| AiBattleError::InternalError { details: e.to_string() })?;
+
+        let account = Account { id: Uuid::new_v4(), username, password_hash, created_at: Utc::now() };
+        let inserted = self.store().insert(&account).await.map_err(|e| AiBattleError::InternalError { details: e.to_string() })?;
+        if !inserted {
+            return Err(AiBattleError::UsernameTaken { username: account.username });
+        }
+        self.issue_token(&account)
+    }
+
+    /// ユーザー名とパスワードを確認し、トークンを発行する
+    pub async fn login(&self, request: &CredentialsRequest) -> AiBattleResult<AccessTokenResponse> {
+        let username = request.username.trim().to_ascii_lowercase();
+        let account = self
+            .store()
+            .find_by_username(&username)
+            .await
+            .map_err(|e| AiBattleError::InternalError { details: e.to_string() })?
+            .ok_or(AiBattleError::InvalidCredentials)?;
+
+        let password = request.password.clone();
+        let password_hash = account.password_hash.clone();
+        let verified = tokio::task::spawn_blocking(move || {
+            PasswordHash::new(&password_hash)
+                .map(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+                .unwrap_or(false)
+        })
+        .await
+        .map_err(|e| AiBattleError::InternalError { details: e.to_string() })?;
+
+        if !verified {
+            return Err(AiBattleError::InvalidCredentials);
+        }
+        self.issue_token(&account)
+    }
+
+    fn issue_token(&self, account: &Account) -> AiBattleResult<AccessTokenResponse> {
+        let issued_at = Utc::now();
+        let expires_at = issued_at + self.token_ttl;
+        let claims = Claims {
+            sub: account.id,
+            name: account.username.clone(),
+            iat: issued_at.timestamp(),
+            exp: expires_at.timestamp(),
+        };
+        let access_token = jsonwebtoken::encode(&Header::default(), &claims, &self.encoding_key)
+            .map_err(|e| AiBattleError::InternalError { details: e.to_string() })?;
+
+        Ok(AccessTokenResponse {
+            account_id: account.id,
+            username: account.username.clone(),
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_at,
+        })
+    }
+
+    /// トークンの署名と有効期限を検証する
+    pub fn verify(&self, token: &str) -> AiBattleResult<AccountIdentity> {
+        jsonwebtoken::decode::<Claims>(token, &self.decoding_key, &Validation::default())
+            .map(|data| AccountIdentity { account_id: data.claims.sub, username: data.claims.name })
+            .map_err(|_| AiBattleError::InvalidAccessToken)
+    }
+}
+
+/// ユーザー名を検証し、小文字に正規化する
+fn normalize_username(username: &str) -> AiBattleResult<String> {
+    let username = username.trim().to_ascii_lowercase();
+    let valid_chars = username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !(3..=32).contains(&username.len()) || !valid_chars {
+        return Err(AiBattleError::BadRequest {
+            details: "ユーザー名は3〜32文字の英数字・'_'・'-'で指定してください".to_string(),
+        });
+    }
+    Ok(username)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credentials(username: &str, password: &str) -> CredentialsRequest {
+        CredentialsRequest { username: username.to_string(), password: password.to_string() }
+    }
+
+    async fn assert_register_and_login(accounts: &Accounts) {
+        let registered = accounts.register(&credentials("Alice", "correct horse")).await.unwrap();
+        assert_eq!(registered.username, "alice");
+        assert_eq!(accounts.verify(&registered.access_token).unwrap().account_id, registered.account_id);
+
+        assert!(matches!(
+            accounts.register(&credentials("alice", "another password")).await,
+            Err(AiBattleError::UsernameTaken { .. })
+        ));
+        assert!(matches!(accounts.register(&credentials("bob", "short")).await, Err(AiBattleError::BadRequest { .. })));
+        assert!(matches!(accounts.register(&credentials("b b", "long enough")).await, Err(AiBattleError::BadRequest { .. })));
+
+        let logged_in = accounts.login(&credentials("ALICE", "correct horse")).await.unwrap();
+        assert_eq!(logged_in.account_id, registered.account_id);
+        assert!(matches!(accounts.login(&credentials("alice", "wrong password")).await, Err(AiBattleError::InvalidCredentials)));
+        assert!(matches!(accounts.login(&credentials("nobody", "correct horse")).await, Err(AiBattleError::InvalidCredentials)));
+    }
+
+    #[tokio::test]
+    async fn test_memory_accounts_register_and_login() {
+        assert_register_and_login(&Accounts::default()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_accounts_register_and_login() {
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 1,
+            ..DatabaseConfig::default()
+        };
+        let store = SqliteSessionStore::connect(&config).await.unwrap();
+        let accounts = Accounts::default();
+        accounts.attach_store(Arc::new(SqliteAccountStore::open(&store).await.unwrap()));
+        assert_register_and_login(&accounts).await;
+    }
+
+    #[test]
+    fn test_tokens_from_another_key_or_expired_are_rejected() {
+        let accounts = Accounts::default();
+        let account = Account {
+            id: Uuid::new_v4(),
+            username: "alice".to_string(),
+            password_hash: String::new(),
+            created_at: Utc::now(),
+        };
+        let token = Accounts::default().issue_token(&account).unwrap().access_token;
+        assert!(is_jwt(&token));
+        assert!(matches!(accounts.verify(&token), Err(AiBattleError::InvalidAccessToken)));
+
+        let claims = Claims { sub: account.id, name: account.username.clone(), iat: 0, exp: 1 };
+        let expired = jsonwebtoken::encode(&Header::default(), &claims, &accounts.encoding_key).unwrap();
+        assert!(matches!(accounts.verify(&expired), Err(AiBattleError::InvalidAccessToken)));
+
+        assert!(!is_jwt("plain-api-key"));
+    }
+}
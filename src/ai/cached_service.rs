@@ -0,0 +1,346 @@
+//! AIの着手計算のキャッシュ
+//! 正規化した局面の1行の盤面表記と難易度・レベル・探索の設定（シードを指定した場合はシードも）をキーに、
+//! 計算済みの着手をLRUで保持する `AIService` のラッパー。
+//! 回転・反転で一致する局面では、キャッシュした着手を元の盤の向きに戻して返す。
+//! シードを指定しない乱択・揺らぎのある計算は毎回異なる手を選ぶべきなので、キャッシュしない。
+
+use async_trait::async_trait;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use utoipa::ToSchema;
+
+use crate::ai::AiDifficulty;
+use crate::error::AIError;
+use crate::game::{GameState, GameVariant, Position, PositionHash, ReversiRules};
+
+use super::levels::{AiLevel, LevelParams};
+use super::strategies::CancelFlag;
+use super::queued_service::AiQueueStats;
+use super::service::{AIMoveResult, AIService, AIServiceStatus, AIServiceType, MoveAnalysis};
+use super::timed_service::AiLatencyStats;
+
+/// キャッシュの統計情報
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AiCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// 保持している着手の数
+    pub entries: usize,
+    pub capacity: usize,
+    /// ヒット率（0.0〜1.0、まだ参照がなければ0）
+    pub hit_rate: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    /// 正規化後の盤面の表記（ハッシュと違い、異なる局面が同じキーになることはない）
+    fen: String,
+    /// 同じ局面でもルールが異なれば最善手は変わる
+    variant: GameVariant,
+    strength: Strength,
+    seed: Option<u64>,
+}
+
+/// 計算を依頼されたAIの強さ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Strength {
+    Difficulty(AiDifficulty),
+    Level(AiLevel),
+    /// 探索の設定を上書きしたレベル
+    Custom(AiLevel, LevelParams),
+}
+
+impl Strength {
+    /// シードがなければ呼び出すたびに結果が変わる強さか
+    fn is_randomized(self) -> bool {
+        let params = match self {
+            Strength::Difficulty(difficulty) => difficulty.level().params(),
+            Strength::Level(level) => level.params(),
+            Strength::Custom(_, params) => params,
+        };
+        params.depth == 0 || params.noise > 0
+    }
+}
+
+/// 着手計算の結果をキャッシュするAIサービス
+pub struct CachedAIService {
+    inner: Arc<dyn AIService>,
+    /// 正規化後の盤の向きでの着手
+    cache: Mutex<LruCache<CacheKey, AIMoveResult>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl std::fmt::Debug for CachedAIService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedAIService")
+            .field("inner", &self.inner.get_name())
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl CachedAIService {
+    /// `capacity` 件までの着手を保持する（0の場合は1件）
+    pub fn new(inner: Arc<dyn AIService>, capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            inner,
+            cache: Mutex::new(LruCache::new(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn stats(&self) -> AiCacheStats {
+        let cache = self.cache.lock().unwrap();
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let lookups = hits + misses;
+        AiCacheStats {
+            hits,
+            misses,
+            entries: cache.len(),
+            capacity: cache.cap().get(),
+            hit_rate: if lookups == 0 { 0.0 } else { hits as f64 / lookups as f64 },
+        }
+    }
+
+    async fn cached_move(
+        &self,
+        game_state: &GameState,
+        strength: Strength,
+        seed: Option<u64>,
+    ) -> Result<AIMoveResult, AIError> {
+        // 終局・合法手なしのエラーはラップ先に判定させる
+        if game_state.is_finished() || !ReversiRules::has_valid_moves(&game_state.board, game_state.current_player) {
+            return self.compute(game_state, strength, seed).await;
+        }
+        if seed.is_none() && strength.is_randomized() {
+            return self.compute(game_state, strength, seed).await;
+        }
+
+        let start_time = Instant::now();
+        let canonical = PositionHash::canonicalize(&game_state.board, game_state.current_player);
+        // 乱択・揺らぎのない計算はシードによらず同じ手になるため、シードの異なる対局でもエントリを共有する
+        let key = CacheKey {
+            fen: canonical.board.to_fen(game_state.current_player),
+            variant: game_state.variant,
+            strength,
+            seed: seed.filter(|_| strength.is_randomized()),
+        };
+
+        let cached = self.cache.lock().unwrap().get(&key).cloned();
+        if let Some(cached) = cached {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(AIMoveResult {
+                position: canonical.from_canonical(cached.position),
+                thinking_time_ms: start_time.elapsed().as_millis() as u64,
+                alternatives: map_alternatives(&cached.alternatives, |position| canonical.from_canonical(position)),
+                principal_variation: map_line(&cached.principal_variation, |position| canonical.from_canonical(position)),
+                ..cached
+            });
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let result = self.compute(game_state, strength, seed).await?;
+        let entry = AIMoveResult {
+            position: canonical.to_canonical(result.position),
+            alternatives: map_alternatives(&result.alternatives, |position| canonical.to_canonical(position)),
+            principal_variation: map_line(&result.principal_variation, |position| canonical.to_canonical(position)),
+            ..result.clone()
+        };
+        self.cache.lock().unwrap().put(key, entry);
+        Ok(result)
+    }
+
+    async fn compute(
+        &self,
+        game_state: &GameState,
+        strength: Strength,
+        seed: Option<u64>,
+    ) -> Result<AIMoveResult, AIError> {
+        match (strength, seed) {
+            (Strength::Level(level), seed) => self.inner.calculate_move_at_level(game_state, level, seed).await,
+            (Strength::Custom(level, params), seed) => {
+                self.inner.calculate_move_with_params(game_state, level, params, seed).await
+            }
+            (Strength::Difficulty(difficulty), Some(seed)) => self.inner.calculate_move_seeded(game_state, difficulty, seed).await,
+            (Strength::Difficulty(difficulty), None) => self.inner.calculate_move(game_state, difficulty).await,
+        }
+    }
+}
+
+/// 候補手の座標を写す（正規化後の盤面との相互変換に使う）
+fn map_alternatives(alternatives: &Option<Vec<MoveAnalysis>>, map: impl Fn(Position) -> Position) -> Option<Vec<MoveAnalysis>> {
+    alternatives.as_ref().map(|alternatives| {
+        alternatives.iter().map(|alternative| MoveAnalysis { position: map(alternative.position), ..alternative.clone() }).collect()
+    })
+}
+
+/// 読み筋の座標を写す
+fn map_line(line: &Option<Vec<Position>>, map: impl Fn(Position) -> Position) -> Option<Vec<Position>> {
+    line.as_ref().map(|line| line.iter().copied().map(map).collect())
+}
+
+#[async_trait]
+impl AIService for CachedAIService {
+    async fn calculate_move(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+    ) -> Result<AIMoveResult, AIError> {
+        self.cached_move(game_state, Strength::Difficulty(difficulty), None).await
+    }
+
+    async fn calculate_move_seeded(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+        seed: u64,
+    ) -> Result<AIMoveResult, AIError> {
+        self.cached_move(game_state, Strength::Difficulty(difficulty), Some(seed)).await
+    }
+
+    async fn calculate_move_at_level(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        seed: Option<u64>,
+    ) -> Result<AIMoveResult, AIError> {
+        self.cached_move(game_state, Strength::Level(level), seed).await
+    }
+    
+    async fn calculate_move_with_params(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        params: LevelParams,
+        seed: Option<u64>,
+    ) -> Result<AIMoveResult, AIError> {
+        // 上書きのない設定はレベル指定の計算と同じエントリを使う
+        let strength = if params == level.params() { Strength::Level(level) } else { Strength::Custom(level, params) };
+        self.cached_move(game_state, strength, seed).await
+    }
+
+    async fn calculate_move_when_idle(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        params: LevelParams,
+        seed: Option<u64>,
+        cancel: &CancelFlag,
+    ) -> Result<AIMoveResult, AIError> {
+        self.inner.calculate_move_when_idle(game_state, level, params, seed, cancel).await
+    }
+
+    fn ensure_capacity(&self) -> Result<(), AIError> {
+        self.inner.ensure_capacity()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+
+    fn get_supported_difficulties(&self) -> Vec<AiDifficulty> {
+        self.inner.get_supported_difficulties()
+    }
+
+    fn get_name(&self) -> &'static str {
+        self.inner.get_name()
+    }
+
+    fn get_service_type(&self) -> AIServiceType {
+        self.inner.get_service_type()
+    }
+
+    async fn analyze_moves(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+    ) -> Result<Vec<MoveAnalysis>, AIError> {
+        self.inner.analyze_moves(game_state, difficulty).await
+    }
+
+    fn cache_stats(&self) -> Option<AiCacheStats> {
+        Some(self.stats())
+    }
+
+    fn latency_stats(&self) -> Option<AiLatencyStats> {
+        self.inner.latency_stats()
+    }
+
+    fn queue_stats(&self) -> Option<AiQueueStats> {
+        self.inner.queue_stats()
+    }
+
+    async fn get_status(&self) -> AIServiceStatus {
+        AIServiceStatus {
+            cache: Some(self.stats()),
+            ..self.inner.get_status().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::local_service::LocalAIService;
+    use crate::ai::mock_service::MockAIService;
+    use crate::game::{Player, Position};
+
+    #[tokio::test]
+    async fn test_symmetric_positions_hit_the_cache() {
+        let service = CachedAIService::new(Arc::new(MockAIService::new_fast()), 2);
+
+        // 初手4通りの後の局面は互いに対称になる
+        let after = |row, col| {
+            let mut state = GameState::new();
+            ReversiRules::apply_move(&mut state, Position::new(row, col).unwrap()).unwrap();
+            state.switch_player();
+            state
+        };
+        let first = after(2, 3);
+        let mirrored = after(5, 4);
+
+        let computed = service.calculate_move(&first, AiDifficulty::Hard).await.unwrap();
+        let cached = service.calculate_move(&mirrored, AiDifficulty::Hard).await.unwrap();
+        assert!(ReversiRules::is_valid_move(&mirrored.board, cached.position, Player::White));
+        assert_ne!(cached.position, computed.position);
+        assert_eq!((service.stats().hits, service.stats().misses), (1, 1));
+
+        // シードのない乱択はキャッシュしない
+        service.calculate_move(&first, AiDifficulty::Easy).await.unwrap();
+        assert_eq!((service.stats().hits, service.stats().misses), (1, 1));
+
+        // レベル・シードが異なれば別のエントリになり、容量を超えると古いものから捨てる
+        service.calculate_move_at_level(&first, AiLevel::new(7).unwrap(), None).await.unwrap();
+        service.calculate_move_seeded(&first, AiDifficulty::Easy, 7).await.unwrap();
+        let stats = service.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries, stats.capacity), (1, 3, 2, 2));
+        assert_eq!(service.cache_stats(), Some(stats));
+    }
+
+    #[tokio::test]
+    async fn test_cached_alternatives_follow_the_symmetry() {
+        let service = CachedAIService::new(Arc::new(LocalAIService::new_fast().with_search_threads(1)), 4);
+        let first = crate::game::transcript::import("F5", GameState::new()).unwrap();
+        let mirrored = crate::game::transcript::import("C4", GameState::new()).unwrap();
+        let level = AiLevel::new(7).unwrap();
+        let params = LevelParams { multipv: 3, ..level.params() };
+
+        let computed = service.calculate_move_with_params(&first, level, params, None).await.unwrap();
+        let cached = service.calculate_move_with_params(&mirrored, level, params, None).await.unwrap();
+        assert_eq!(service.stats().hits, 1);
+        let alternatives = cached.alternatives.unwrap();
+        assert_eq!(alternatives.len(), computed.alternatives.unwrap().len());
+        assert_eq!(alternatives[0].position, cached.position);
+        assert!(alternatives
+            .iter()
+            .all(|alternative| ReversiRules::is_valid_move(&mirrored.board, alternative.position, Player::White)));
+    }
+}
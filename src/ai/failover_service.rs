@@ -0,0 +1,268 @@
+//! AIサービスの自動切り替え
+//! プライマリAIの死活を定期的に確認し、利用できない間は新しい着手をフォールバックAIで計算する
+//! `AIService` のラッパー。プライマリが復旧したら元に戻す。
+//! 切り替えは死活確認の結果だけで行い、計算中の着手は開始したサービスのまま完了させる。
+
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::ai::AiDifficulty;
+use crate::error::AIError;
+use crate::game::GameState;
+
+use super::cached_service::AiCacheStats;
+use super::levels::{AiLevel, LevelParams};
+use super::strategies::CancelFlag;
+use super::queued_service::AiQueueStats;
+use super::service::{AIMoveResult, AIService, AIServiceStatus, AIServiceType, MoveAnalysis};
+use super::timed_service::AiLatencyStats;
+
+/// プライマリが利用できない間、フォールバックに切り替えるAIサービス
+pub struct FailoverAIService {
+    primary: Arc<dyn AIService>,
+    fallback: Arc<dyn AIService>,
+    using_fallback: AtomicBool,
+}
+
+impl std::fmt::Debug for FailoverAIService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FailoverAIService")
+            .field("primary", &self.primary.get_name())
+            .field("fallback", &self.fallback.get_name())
+            .field("using_fallback", &self.is_using_fallback())
+            .finish()
+    }
+}
+
+impl FailoverAIService {
+    /// プライマリが利用できる前提で開始する（最初の死活確認で判定し直す）
+    pub fn new(primary: Arc<dyn AIService>, fallback: Arc<dyn AIService>) -> Self {
+        Self {
+            primary,
+            fallback,
+            using_fallback: AtomicBool::new(false),
+        }
+    }
+
+    /// 新しい着手をフォールバックで計算しているか
+    pub fn is_using_fallback(&self) -> bool {
+        self.using_fallback.load(Ordering::Relaxed)
+    }
+
+    /// 新しい着手を計算するサービス
+    pub fn active(&self) -> &Arc<dyn AIService> {
+        if self.is_using_fallback() {
+            &self.fallback
+        } else {
+            &self.primary
+        }
+    }
+
+    /// プライマリの死活を確認し、計算先を切り替える
+    /// 切り替えた場合はtrueを返す
+    pub async fn probe(&self) -> bool {
+        let primary_down = !self.primary.is_available().await;
+        let switched = self.using_fallback.swap(primary_down, Ordering::Relaxed) != primary_down;
+        if switched && primary_down {
+            println!(
+                "Primary AI service {} is unavailable, routing moves to fallback: {}",
+                self.primary.get_name(),
+                self.fallback.get_name(),
+            );
+        } else if switched {
+            println!("Primary AI service {} recovered, switching back from fallback", self.primary.get_name());
+        }
+        switched
+    }
+
+    /// 一定間隔でプライマリの死活を確認するタスクを起動する
+    pub fn spawn_monitor(self: Arc<Self>, interval: Duration, beat: impl Fn() + Send + 'static) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.probe().await;
+                beat();
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl AIService for FailoverAIService {
+    async fn calculate_move(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+    ) -> Result<AIMoveResult, AIError> {
+        self.active().calculate_move(game_state, difficulty).await
+    }
+
+    async fn calculate_move_seeded(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+        seed: u64,
+    ) -> Result<AIMoveResult, AIError> {
+        self.active().calculate_move_seeded(game_state, difficulty, seed).await
+    }
+
+    async fn calculate_move_at_level(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        seed: Option<u64>,
+    ) -> Result<AIMoveResult, AIError> {
+        self.active().calculate_move_at_level(game_state, level, seed).await
+    }
+
+    async fn calculate_move_with_params(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        params: LevelParams,
+        seed: Option<u64>,
+    ) -> Result<AIMoveResult, AIError> {
+        self.active().calculate_move_with_params(game_state, level, params, seed).await
+    }
+
+    async fn calculate_move_when_idle(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        params: LevelParams,
+        seed: Option<u64>,
+        cancel: &CancelFlag,
+    ) -> Result<AIMoveResult, AIError> {
+        self.active().calculate_move_when_idle(game_state, level, params, seed, cancel).await
+    }
+
+    fn ensure_capacity(&self) -> Result<(), AIError> {
+        self.active().ensure_capacity()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.primary.is_available().await || self.fallback.is_available().await
+    }
+
+    fn get_supported_difficulties(&self) -> Vec<AiDifficulty> {
+        self.active().get_supported_difficulties()
+    }
+
+    fn get_name(&self) -> &'static str {
+        self.active().get_name()
+    }
+
+    fn get_service_type(&self) -> AIServiceType {
+        self.active().get_service_type()
+    }
+
+    async fn analyze_moves(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+    ) -> Result<Vec<MoveAnalysis>, AIError> {
+        self.active().analyze_moves(game_state, difficulty).await
+    }
+
+    fn cache_stats(&self) -> Option<AiCacheStats> {
+        self.active().cache_stats()
+    }
+
+    fn latency_stats(&self) -> Option<AiLatencyStats> {
+        self.active().latency_stats()
+    }
+
+    fn queue_stats(&self) -> Option<AiQueueStats> {
+        self.active().queue_stats()
+    }
+
+    async fn get_status(&self) -> AIServiceStatus {
+        self.active().get_status().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::mock_service::MockAIService;
+    use crate::game::Position;
+
+    /// 利用可否を外から切り替えられるプライマリ
+    struct SwitchableService {
+        inner: MockAIService,
+        available: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl AIService for SwitchableService {
+        async fn calculate_move(&self, game_state: &GameState, difficulty: AiDifficulty) -> Result<AIMoveResult, AIError> {
+            self.inner.calculate_move(game_state, difficulty).await
+        }
+
+        async fn is_available(&self) -> bool {
+            self.available.load(Ordering::Relaxed)
+        }
+
+        fn get_supported_difficulties(&self) -> Vec<AiDifficulty> {
+            self.inner.get_supported_difficulties()
+        }
+
+        fn get_name(&self) -> &'static str {
+            "SwitchableService"
+        }
+
+        fn get_service_type(&self) -> AIServiceType {
+            AIServiceType::Mock
+        }
+    }
+
+    #[tokio::test]
+    async fn test_moves_follow_the_primary_health() {
+        let primary_move = Position::new(2, 3).unwrap();
+        let fallback_move = Position::new(5, 4).unwrap();
+        let available = Arc::new(AtomicBool::new(true));
+        let primary = SwitchableService {
+            inner: MockAIService::new_with_fixed_move(primary_move),
+            available: Arc::clone(&available),
+        };
+        let service = FailoverAIService::new(Arc::new(primary), Arc::new(MockAIService::new_with_fixed_move(fallback_move)));
+        let game_state = GameState::new();
+
+        assert!(!service.probe().await);
+        assert_eq!(service.calculate_move(&game_state, AiDifficulty::Easy).await.unwrap().position, primary_move);
+
+        // プライマリが落ちている間はフォールバックで計算する
+        available.store(false, Ordering::Relaxed);
+        assert!(service.probe().await);
+        assert!(service.is_using_fallback());
+        assert_eq!(service.calculate_move(&game_state, AiDifficulty::Easy).await.unwrap().position, fallback_move);
+        assert_eq!(service.get_name(), "MockAIService");
+        assert!(service.is_available().await);
+
+        // 復旧したら戻す
+        available.store(true, Ordering::Relaxed);
+        assert!(service.probe().await);
+        assert!(!service.probe().await);
+        assert_eq!(service.calculate_move(&game_state, AiDifficulty::Easy).await.unwrap().position, primary_move);
+    }
+
+    #[tokio::test]
+    async fn test_monitor_switches_in_the_background() {
+        let available = Arc::new(AtomicBool::new(false));
+        let primary = SwitchableService { inner: MockAIService::new_fast(), available: Arc::clone(&available) };
+        let service = Arc::new(FailoverAIService::new(Arc::new(primary), Arc::new(MockAIService::new_fast())));
+
+        let task = Arc::clone(&service).spawn_monitor(Duration::from_millis(5), || {});
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(service.is_using_fallback());
+
+        available.store(true, Ordering::Relaxed);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!service.is_using_fallback());
+        task.abort();
+    }
+}
@@ -1,20 +1,27 @@
 
 use async_trait::async_trait;
+use std::sync::Arc;
 use std::time::Instant;
 use tokio::time::{sleep, Duration};
 
-use crate::api::ai_battle::dto::AiDifficulty;
+use crate::ai::{AiDifficulty, SearchOutcome};
 use crate::error::AIError;
-use crate::game::{GameState, ReversiRules};
+use crate::game::{GameState, Position, ReversiRules};
 
-use super::service::{AIService, AIMoveResult, AIServiceType};
-use super::strategies::{AIStrategy, create_ai_strategy, Difficulty as LegacyDifficulty};
+use super::book::OpeningBook;
+use super::service::{static_move_analysis, AIService, AIMoveResult, AIServiceType, MoveAnalysis};
+use super::levels::{AiLevel, LevelParams};
+use super::strategies::CancelFlag;
 
 #[derive(Debug, Clone)]
 pub struct LocalAIService {
     pub simulate_thinking_time: bool,
     pub min_thinking_time_ms: u64,
     pub max_thinking_time_ms: u64,
+    /// αβ探索に使うスレッド数（0はCPU数）
+    pub search_threads: usize,
+    /// 序盤に使う定石ブック（揺らぎのない探索レベルでのみ使う）
+    pub opening_book: Option<Arc<OpeningBook>>,
 }
 
 impl LocalAIService {
@@ -23,6 +30,8 @@ impl LocalAIService {
             simulate_thinking_time: true,
             min_thinking_time_ms: 300,
             max_thinking_time_ms: 3000,
+            search_threads: 0,
+            opening_book: None,
         }
     }
     
@@ -31,9 +40,32 @@ impl LocalAIService {
             simulate_thinking_time: false,
             min_thinking_time_ms: 0,
             max_thinking_time_ms: 0,
+            search_threads: 0,
+            opening_book: None,
         }
     }
     
+    /// αβ探索に使うスレッド数を指定する
+    pub fn with_search_threads(mut self, search_threads: usize) -> Self {
+        self.search_threads = search_threads;
+        self
+    }
+    
+    /// 序盤に使う定石ブックを指定する
+    pub fn with_opening_book(mut self, book: Arc<OpeningBook>) -> Self {
+        self.opening_book = Some(book);
+        self
+    }
+    
+    /// 定石ブックにある局面ならブックの手を返す
+    /// 乱択・揺らぎのあるレベルや、石の少ない方が勝つルールでは使わない
+    fn book_move(&self, game_state: &GameState, params: &LevelParams) -> Option<Position> {
+        if params.depth == 0 || params.noise > 0 || game_state.variant.fewer_discs_win() {
+            return None;
+        }
+        self.opening_book.as_ref()?.best_move(&game_state.board, game_state.current_player)
+    }
+    
     fn get_thinking_time(&self, difficulty: AiDifficulty) -> u64 {
         if !self.simulate_thinking_time {
             return 0;
@@ -46,29 +78,36 @@ impl LocalAIService {
         }
     }
     
-    fn convert_difficulty(difficulty: AiDifficulty) -> LegacyDifficulty {
-        match difficulty {
-            AiDifficulty::Easy => LegacyDifficulty::Beginner,
-            AiDifficulty::Medium => LegacyDifficulty::Intermediate,
-            AiDifficulty::Hard => LegacyDifficulty::Advanced,
-        }
-    }
-}
-
-impl Default for LocalAIService {
-    fn default() -> Self {
-        Self::new()
+    /// 設定に対応する戦略で手と候補手を計算する
+    /// `cancel` が立つと探索を打ち切り、探索のスレッドをすぐに空ける
+    async fn search(
+        &self,
+        game_state: &GameState,
+        params: LevelParams,
+        seed: Option<u64>,
+        cancel: Option<&CancelFlag>,
+    ) -> Result<SearchOutcome, AIError> {
+        let ai_strategy = params.create_cancellable_strategy(seed, self.search_threads, cancel.cloned());
+        
+        // 探索はCPUを占有するため、非同期ランタイムのワーカーを塞がないよう別スレッドで行う
+        let search_state = game_state.clone();
+        tokio::task::spawn_blocking(move || ai_strategy.search(&search_state))
+            .await
+            .map_err(|e| AIError::StrategyError { message: format!("AI search task failed: {}", e) })?
     }
-}
-
-#[async_trait]
-impl AIService for LocalAIService {
-    async fn calculate_move(
-        &self, 
-        game_state: &GameState, 
-        difficulty: AiDifficulty
+    
+    async fn compute_move(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        params: LevelParams,
+        seed: Option<u64>,
+        cancel: Option<&CancelFlag>,
     ) -> Result<AIMoveResult, AIError> {
         let start_time = Instant::now();
+        if cancel.is_some_and(CancelFlag::is_cancelled) {
+            return Err(AIError::Cancelled);
+        }
         
         if game_state.is_finished() {
             return Err(AIError::StrategyError {
@@ -81,33 +120,126 @@ impl AIService for LocalAIService {
             return Err(AIError::NoValidMoves);
         }
         
-        let thinking_time_ms = self.get_thinking_time(difficulty);
+        // 中断できる計算（先読み）は人に見せないため、思考時間を演出しない
+        let thinking_time_ms = if cancel.is_some() { 0 } else { self.get_thinking_time(level.difficulty().into()) };
         if thinking_time_ms > 0 {
             sleep(Duration::from_millis(thinking_time_ms)).await;
         }
         
-        let legacy_difficulty = Self::convert_difficulty(difficulty);
-        let ai_strategy = create_ai_strategy(legacy_difficulty);
-        
-        let position = ai_strategy.calculate_move(game_state)?;
+        if let Some(position) = self.book_move(game_state, &params) {
+            return Ok(AIMoveResult {
+                position,
+                thinking_time_ms: start_time.elapsed().as_millis() as u64,
+                evaluation_score: None,
+                depth_reached: None,
+                nodes_evaluated: None,
+                cutoffs: None,
+                first_move_cutoffs: None,
+                alternatives: None,
+                principal_variation: None,
+            });
+        }
         
+        let outcome = self.search(game_state, params, seed, cancel).await?;
         let actual_thinking_time = start_time.elapsed().as_millis() as u64;
+        let stats = outcome.stats;
+        // 時間の上限で打ち切った場合は設定より浅い
+        let depth_reached = stats.map_or(params.depth, |stats| stats.depth_reached) as u32;
+        let alternatives = (!outcome.candidates.is_empty()).then(|| MoveAnalysis::from_candidates(&outcome.candidates, depth_reached));
         
         Ok(AIMoveResult {
-            position,
+            position: outcome.position,
             thinking_time_ms: actual_thinking_time,
             evaluation_score: None,
-            depth_reached: None,
-            nodes_evaluated: None,
+            depth_reached: Some(depth_reached),
+            nodes_evaluated: stats.map(|stats| stats.nodes),
+            cutoffs: stats.map(|stats| stats.cutoffs),
+            first_move_cutoffs: stats.map(|stats| stats.first_move_cutoffs),
+            alternatives,
+            principal_variation: (!outcome.principal_variation.is_empty()).then_some(outcome.principal_variation),
         })
     }
+}
+
+impl Default for LocalAIService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AIService for LocalAIService {
+    async fn calculate_move(
+        &self, 
+        game_state: &GameState, 
+        difficulty: AiDifficulty
+    ) -> Result<AIMoveResult, AIError> {
+        self.compute_move(game_state, difficulty.level(), difficulty.level().params(), None, None).await
+    }
+    
+    async fn calculate_move_seeded(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+        seed: u64,
+    ) -> Result<AIMoveResult, AIError> {
+        self.compute_move(game_state, difficulty.level(), difficulty.level().params(), Some(seed), None).await
+    }
+    
+    async fn calculate_move_at_level(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        seed: Option<u64>,
+    ) -> Result<AIMoveResult, AIError> {
+        self.compute_move(game_state, level, level.params(), seed, None).await
+    }
+    
+    async fn calculate_move_with_params(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        params: LevelParams,
+        seed: Option<u64>,
+    ) -> Result<AIMoveResult, AIError> {
+        self.compute_move(game_state, level, params, seed, None).await
+    }
+    
+    async fn calculate_move_when_idle(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        params: LevelParams,
+        seed: Option<u64>,
+        cancel: &CancelFlag,
+    ) -> Result<AIMoveResult, AIError> {
+        self.compute_move(game_state, level, params, seed, Some(cancel)).await
+    }
+    
+    /// 難易度のレベルの深さで全ての合法手の評価値を探索する（揺らぎは加えない）
+    /// 先読みをしないレベルでは着手後の静的評価を返す
+    async fn analyze_moves(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+    ) -> Result<Vec<MoveAnalysis>, AIError> {
+        let level = difficulty.level();
+        let move_count = ReversiRules::get_valid_moves(&game_state.board, game_state.current_player).len();
+        if level.params().depth == 0 || game_state.is_finished() || move_count == 0 {
+            return static_move_analysis(game_state);
+        }
+        let params = LevelParams { noise: 0, multipv: move_count as u8, ..level.params() };
+        let outcome = self.search(game_state, params, None, None).await?;
+        let depth = outcome.stats.map_or(params.depth, |stats| stats.depth_reached) as u32;
+        Ok(MoveAnalysis::from_candidates(&outcome.candidates, depth))
+    }
     
     async fn is_available(&self) -> bool {
         true
     }
     
     fn get_supported_difficulties(&self) -> Vec<AiDifficulty> {
-        vec![AiDifficulty::Easy]
+        AiDifficulty::all()
     }
     
     fn get_name(&self) -> &'static str {
@@ -145,6 +277,65 @@ mod tests {
         let service = LocalAIService::new();
         let difficulties = service.get_supported_difficulties();
         assert!(difficulties.contains(&AiDifficulty::Easy));
+        assert!(difficulties.contains(&AiDifficulty::Hard));
+    }
+    
+    #[tokio::test]
+    async fn test_calculate_move_hard_uses_alphabeta() {
+        let service = LocalAIService::new_fast().with_search_threads(2);
+        let game_state = GameState::new();
+        
+        let result = service.calculate_move(&game_state, AiDifficulty::Hard).await.unwrap();
+        let valid_moves = ReversiRules::get_valid_moves(&game_state.board, game_state.current_player);
+        assert!(valid_moves.contains(&result.position));
+    }
+    
+    #[tokio::test]
+    async fn test_opening_book_move_for_precise_levels() {
+        let mut book = OpeningBook::new();
+        for _ in 0..2 {
+            book.add_game(&crate::game::transcript::parse_transcript("F5F4").unwrap(), -10);
+        }
+        let service = LocalAIService::new_fast().with_opening_book(Arc::new(book));
+        let game_state = crate::game::transcript::import("F5", GameState::new()).unwrap();
+        let f4 = Position::new(3, 5).unwrap();
+        
+        let result = service.calculate_move_at_level(&game_state, AiLevel::try_from(8).unwrap(), None).await.unwrap();
+        assert_eq!((result.position, result.depth_reached), (f4, None));
+        
+        // 揺らぎのあるレベルではブックを使わず探索する
+        let result = service.calculate_move_at_level(&game_state, AiLevel::try_from(4).unwrap(), Some(1)).await.unwrap();
+        assert_eq!(result.depth_reached, Some(2));
+        assert!(result.nodes_evaluated.unwrap() > 0);
+        assert!(result.first_move_cutoffs <= result.cutoffs);
+        
+        // 探索しないレベルは統計を返さない
+        let result = service.calculate_move_at_level(&game_state, AiLevel::try_from(1).unwrap(), Some(1)).await.unwrap();
+        assert_eq!((result.depth_reached, result.nodes_evaluated, result.cutoffs), (Some(0), None, None));
+    }
+    
+    #[tokio::test]
+    async fn test_multipv_alternatives_and_searched_analysis() {
+        let service = LocalAIService::new_fast().with_search_threads(1);
+        let game_state = crate::game::transcript::import("F5F6", GameState::new()).unwrap();
+        let level = AiLevel::try_from(7).unwrap();
+        
+        let result = service.calculate_move_with_params(&game_state, level, LevelParams { multipv: 2, ..level.params() }, None).await.unwrap();
+        let alternatives = result.alternatives.unwrap();
+        assert_eq!(alternatives.len(), 2);
+        assert_eq!(alternatives[0].position, result.position);
+        assert!(alternatives[0].score >= alternatives[1].score);
+        assert!(alternatives.iter().all(|alternative| alternative.depth == 5));
+        assert!(service.calculate_move_at_level(&game_state, level, None).await.unwrap().alternatives.is_none());
+        
+        // 解析は難易度の深さで全ての合法手を探索し、先読みをしない難易度は静的評価に戻る
+        let valid_moves = ReversiRules::get_valid_moves(&game_state.board, game_state.current_player);
+        let analysis = service.analyze_moves(&game_state, AiDifficulty::Medium).await.unwrap();
+        assert_eq!(analysis.len(), valid_moves.len());
+        assert!(analysis.iter().all(|entry| entry.depth == 3));
+        assert!(analysis.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        let analysis = service.analyze_moves(&game_state, AiDifficulty::Easy).await.unwrap();
+        assert!(analysis.iter().all(|entry| entry.depth == 1));
     }
     
     #[tokio::test]
@@ -169,7 +360,7 @@ mod tests {
         
         game_state.game_status = crate::game::GameStatus::Finished { 
             winner: None, 
-            final_score: (32, 32) 
+            score: (32, 32) 
         };
         
         let result = service.calculate_move(&game_state, AiDifficulty::Easy).await;
@@ -179,9 +370,10 @@ mod tests {
     
     #[test]
     fn test_difficulty_conversion() {
-        assert_eq!(LocalAIService::convert_difficulty(AiDifficulty::Easy), LegacyDifficulty::Beginner);
-        assert_eq!(LocalAIService::convert_difficulty(AiDifficulty::Medium), LegacyDifficulty::Intermediate);
-        assert_eq!(LocalAIService::convert_difficulty(AiDifficulty::Hard), LegacyDifficulty::Advanced);
+        assert_eq!(AiDifficulty::Easy.level().value(), 1);
+        assert_eq!(AiDifficulty::Medium.level().value(), 5);
+        assert_eq!(AiDifficulty::Hard.level().value(), 8);
+        assert!(AiDifficulty::all().into_iter().all(|difficulty| AiDifficulty::from(difficulty.level()) == difficulty));
     }
     
     #[test]
@@ -3,7 +3,7 @@ use async_trait::async_trait;
 use std::time::Instant;
 use tokio::time::{sleep, Duration};
 
-use crate::api::ai_battle::dto::AiDifficulty;
+use crate::ai::AiDifficulty;
 use crate::error::AIError;
 use crate::game::{GameState, ReversiRules, Position};
 
@@ -166,6 +166,10 @@ impl AIService for MockAIService {
             evaluation_score,
             depth_reached,
             nodes_evaluated,
+            cutoffs: None,
+            first_move_cutoffs: None,
+            alternatives: None,
+            principal_variation: None,
         })
     }
     
@@ -1,10 +1,33 @@
-pub mod strategies;
-pub mod evaluation;
+//! AI戦略（reversi-core）と、APIサーバーから使うAIサービス
+
+pub use reversi_core::ai::*;
+
+#[cfg(feature = "server")]
 pub mod service;
+#[cfg(feature = "server")]
 pub mod local_service;
+#[cfg(feature = "server")]
 pub mod mock_service;
+#[cfg(feature = "server")]
+pub mod cached_service;
+#[cfg(feature = "server")]
+pub mod timed_service;
+#[cfg(feature = "server")]
+pub mod queued_service;
+#[cfg(feature = "server")]
+pub mod failover_service;
 
-pub use strategies::*;
+#[cfg(feature = "server")]
 pub use service::*;
+#[cfg(feature = "server")]
 pub use local_service::*;
-pub use mock_service::*;
\ No newline at end of file
+#[cfg(feature = "server")]
+pub use mock_service::*;
+#[cfg(feature = "server")]
+pub use cached_service::*;
+#[cfg(feature = "server")]
+pub use timed_service::*;
+#[cfg(feature = "server")]
+pub use queued_service::*;
+#[cfg(feature = "server")]
+pub use failover_service::*;
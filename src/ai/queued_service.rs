@@ -0,0 +1,340 @@
+//! AIの計算待ちキュー
+//! 着手計算と候補手の解析を上限付きのキューに積み、決まった数のワーカータスクで順に処理する
+//! `AIService` のラッパー。キューが満杯のときは待たせずに `AIError::Overloaded` を返し、
+//! 負荷が高いときに応答時間が際限なく伸びるのを防ぐ。
+//! 先読みなどの低優先度の計算は、空いているワーカーがあるときだけ受け付け、対局の着手の枠を使わない。
+//! ワーカーは最初の計算を受け付けたときにTokioのタスクとして起動する（作成はランタイムの外でもよい）。
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, oneshot};
+use utoipa::ToSchema;
+
+use crate::ai::AiDifficulty;
+use crate::error::AIError;
+use crate::game::GameState;
+
+use super::cached_service::AiCacheStats;
+use super::levels::{AiLevel, LevelParams};
+use super::strategies::CancelFlag;
+use super::service::{AIMoveResult, AIService, AIServiceType, MoveAnalysis};
+use super::timed_service::AiLatencyStats;
+
+/// キューが満杯のときに再試行を促すまでの秒数
+pub const RETRY_AFTER_SECS: u64 = 1;
+
+/// 計算待ちキューの統計
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AiQueueStats {
+    /// ワーカーが取り出すのを待っている計算の数
+    pub depth: usize,
+    pub capacity: usize,
+    pub workers: usize,
+    /// 計算中のワーカーの数
+    pub active: usize,
+    /// キューが満杯で断った計算の総数
+    pub rejected: u64,
+}
+
+type Job = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// 上限付きのキューとワーカーで計算するAIサービス
+pub struct QueuedAIService {
+    inner: Arc<dyn AIService>,
+    sender: mpsc::Sender<Job>,
+    /// ワーカーを起動するまで保持する受信側
+    receiver: std::sync::Mutex<Option<mpsc::Receiver<Job>>>,
+    workers: usize,
+    active: Arc<AtomicUsize>,
+    rejected: AtomicU64,
+}
+
+impl std::fmt::Debug for QueuedAIService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueuedAIService")
+            .field("inner", &self.inner.get_name())
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl QueuedAIService {
+    /// `workers` 個のワーカーで、`capacity` 件まで計算を待たせる（いずれも0の場合は1）
+    pub fn new(inner: Arc<dyn AIService>, workers: usize, capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>(capacity.max(1));
+        Self {
+            inner,
+            sender,
+            receiver: std::sync::Mutex::new(Some(receiver)),
+            workers: workers.max(1),
+            active: Arc::new(AtomicUsize::new(0)),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    /// まだ起動していなければワーカーを起動する
+    fn start_workers(&self) {
+        let Some(receiver) = self.receiver.lock().unwrap().take() else { return };
+        let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
+        for _ in 0..self.workers {
+            let receiver = Arc::clone(&receiver);
+            let active = Arc::clone(&self.active);
+            tokio::spawn(async move {
+                // 送信側（このサービス）が破棄されるとキューが閉じ、ワーカーも終了する
+                loop {
+                    let job = receiver.lock().await.recv().await;
+                    let Some(job) = job else { break };
+                    active.fetch_add(1, Ordering::Relaxed);
+                    job.await;
+                    active.fetch_sub(1, Ordering::Relaxed);
+                }
+            });
+        }
+    }
+
+    fn overloaded(&self) -> AIError {
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+        AIError::Overloaded { retry_after_secs: RETRY_AFTER_SECS }
+    }
+
+    pub fn stats(&self) -> AiQueueStats {
+        AiQueueStats {
+            depth: self.sender.max_capacity() - self.sender.capacity(),
+            capacity: self.sender.max_capacity(),
+            workers: self.workers,
+            active: self.active.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 計算をキューに積み、ワーカーが処理し終えるのを待つ
+    async fn enqueue<T, F, Fut>(&self, work: F) -> Result<T, AIError>
+    where
+        T: Send + 'static,
+        F: FnOnce(Arc<dyn AIService>) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, AIError>> + Send + 'static,
+    {
+        self.start_workers();
+        let (reply, response) = oneshot::channel();
+        let inner = Arc::clone(&self.inner);
+        let job: Job = Box::pin(async move {
+            // 呼び出し側がタイムアウトなどで待つのをやめていれば、結果は捨てる
+            let _ = reply.send(work(inner).await);
+        });
+
+        match self.sender.try_send(job) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => return Err(self.overloaded()),
+            Err(TrySendError::Closed(_)) => return Err(self.stopped()),
+        }
+        response.await.map_err(|_| self.stopped())?
+    }
+
+    fn stopped(&self) -> AIError {
+        AIError::ServiceUnavailable {
+            service_name: self.inner.get_name().to_string(),
+            reason: "AI worker pool has stopped".to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl AIService for QueuedAIService {
+    async fn calculate_move(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+    ) -> Result<AIMoveResult, AIError> {
+        let game_state = game_state.clone();
+        self.enqueue(move |inner| async move { inner.calculate_move(&game_state, difficulty).await }).await
+    }
+
+    async fn calculate_move_seeded(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+        seed: u64,
+    ) -> Result<AIMoveResult, AIError> {
+        let game_state = game_state.clone();
+        self.enqueue(move |inner| async move { inner.calculate_move_seeded(&game_state, difficulty, seed).await }).await
+    }
+
+    async fn calculate_move_at_level(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        seed: Option<u64>,
+    ) -> Result<AIMoveResult, AIError> {
+        let game_state = game_state.clone();
+        self.enqueue(move |inner| async move { inner.calculate_move_at_level(&game_state, level, seed).await }).await
+    }
+
+    async fn calculate_move_with_params(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        params: LevelParams,
+        seed: Option<u64>,
+    ) -> Result<AIMoveResult, AIError> {
+        let game_state = game_state.clone();
+        self.enqueue(move |inner| async move { inner.calculate_move_with_params(&game_state, level, params, seed).await })
+            .await
+    }
+
+    async fn calculate_move_when_idle(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        params: LevelParams,
+        seed: Option<u64>,
+        cancel: &CancelFlag,
+    ) -> Result<AIMoveResult, AIError> {
+        // 待っている計算があるか全ワーカーが計算中なら、対局の着手を優先して断る（断った数には数えない）
+        let stats = self.stats();
+        if stats.depth > 0 || stats.active >= stats.workers {
+            return Err(AIError::Overloaded { retry_after_secs: RETRY_AFTER_SECS });
+        }
+        // 中断されると探索を打ち切り、すぐにワーカーを空ける（呼び出し側が待つのをやめても計算は止まる）
+        let (game_state, cancel) = (game_state.clone(), cancel.clone());
+        self.enqueue(move |inner| async move { inner.calculate_move_when_idle(&game_state, level, params, seed, &cancel).await })
+            .await
+    }
+
+    fn ensure_capacity(&self) -> Result<(), AIError> {
+        if self.sender.capacity() == 0 {
+            return Err(self.overloaded());
+        }
+        Ok(())
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+
+    fn get_supported_difficulties(&self) -> Vec<AiDifficulty> {
+        self.inner.get_supported_difficulties()
+    }
+
+    fn get_name(&self) -> &'static str {
+        self.inner.get_name()
+    }
+
+    fn get_service_type(&self) -> AIServiceType {
+        self.inner.get_service_type()
+    }
+
+    async fn analyze_moves(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+    ) -> Result<Vec<MoveAnalysis>, AIError> {
+        let game_state = game_state.clone();
+        self.enqueue(move |inner| async move { inner.analyze_moves(&game_state, difficulty).await }).await
+    }
+
+    fn cache_stats(&self) -> Option<AiCacheStats> {
+        self.inner.cache_stats()
+    }
+
+    fn latency_stats(&self) -> Option<AiLatencyStats> {
+        self.inner.latency_stats()
+    }
+
+    fn queue_stats(&self) -> Option<AiQueueStats> {
+        Some(self.stats())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::mock_service::{MockAIConfig, MockAIService};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_full_queue_rejects_instead_of_waiting() {
+        let slow = MockAIConfig { response_time_ms: 200, ..MockAIConfig::default() };
+        let service = Arc::new(QueuedAIService::new(Arc::new(MockAIService::new(slow)), 1, 1));
+        let game_state = GameState::new();
+
+        // 1件目はワーカーが計算中、2件目はキューで待機
+        let running = tokio::spawn({
+            let (service, game_state) = (Arc::clone(&service), game_state.clone());
+            async move { service.calculate_move(&game_state, AiDifficulty::Easy).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let waiting = tokio::spawn({
+            let (service, game_state) = (Arc::clone(&service), game_state.clone());
+            async move { service.calculate_move(&game_state, AiDifficulty::Easy).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(service.stats(), AiQueueStats { depth: 1, capacity: 1, workers: 1, active: 1, rejected: 0 });
+
+        let rejected = service.calculate_move(&game_state, AiDifficulty::Easy).await;
+        assert!(matches!(rejected, Err(AIError::Overloaded { retry_after_secs: RETRY_AFTER_SECS })));
+        assert!(service.ensure_capacity().is_err());
+        assert_eq!(service.stats().rejected, 2);
+
+        // 先読みは空いていなければ断り、断った数には数えない
+        let level = AiLevel::new(1).unwrap();
+        let pondered = service.calculate_move_when_idle(&game_state, level, level.params(), None, &CancelFlag::new()).await;
+        assert!(matches!(pondered, Err(AIError::Overloaded { .. })));
+        assert_eq!(service.stats().rejected, 2);
+
+        assert!(running.await.unwrap().is_ok());
+        assert!(waiting.await.unwrap().is_ok());
+        assert_eq!((service.stats().depth, service.stats().active), (0, 0));
+        assert!(service.ensure_capacity().is_ok());
+        assert!(service.calculate_move_when_idle(&game_state, level, level.params(), None, &CancelFlag::new()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_idle_search_frees_the_worker() {
+        use crate::ai::local_service::LocalAIService;
+
+        let inner = LocalAIService::new_fast().with_search_threads(1);
+        let service = Arc::new(QueuedAIService::new(Arc::new(inner), 1, 1));
+        let game_state = GameState::new();
+        // 深さ12を時間の上限なしで読む先読みは、打ち切らなければワーカーを長く占有する
+        let level = AiLevel::new(10).unwrap();
+        let params = LevelParams { depth: 12, time_limit_ms: 0, noise: 0, multipv: 0, ..level.params() };
+        let cancel = CancelFlag::new();
+        let pondering = tokio::spawn({
+            let (service, game_state, cancel) = (Arc::clone(&service), game_state.clone(), cancel.clone());
+            async move { service.calculate_move_when_idle(&game_state, level, params, None, &cancel).await }
+        });
+        while service.stats().active == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        // 中断すれば探索の完了を待たずにワーカーが空き、次の着手を計算できる
+        cancel.cancel();
+        let next = tokio::time::timeout(Duration::from_secs(5), service.calculate_move(&game_state, AiDifficulty::Easy)).await;
+        assert!(next.expect("the worker should be freed by cancelling").is_ok());
+        assert!(matches!(pondering.await.unwrap(), Err(AIError::Cancelled)));
+        assert_eq!(service.stats().rejected, 0);
+    }
+
+    #[test]
+    fn test_workers_start_with_the_first_calculation() {
+        // ランタイムの外でも作成できる
+        let service = QueuedAIService::new(Arc::new(MockAIService::new_fast()), 2, 4);
+        assert_eq!(service.stats(), AiQueueStats { depth: 0, capacity: 4, workers: 2, active: 0, rejected: 0 });
+        assert!(service.receiver.lock().unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_workers_return_errors_of_the_inner_service() {
+        let service = QueuedAIService::new(Arc::new(MockAIService::new_error("boom")), 2, 4);
+        let result = service.analyze_moves(&GameState::new(), AiDifficulty::Easy).await;
+        assert!(result.is_ok());
+        let error = service.calculate_move(&GameState::new(), AiDifficulty::Easy).await.unwrap_err();
+        assert!(error.to_string().contains("boom"));
+        assert_eq!(service.queue_stats().map(|stats| stats.workers), Some(2));
+    }
+}
@@ -5,10 +5,20 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
+use utoipa::ToSchema;
 
-use crate::game::{GameState, Position};
-use crate::api::ai_battle::dto::AiDifficulty;
+use crate::game::{GameState, Position, ReversiRules};
+use crate::ai::book::OpeningBook;
+use crate::ai::evaluation::{BoardEvaluator, EvalWeights};
+use crate::ai::cached_service::AiCacheStats;
+use crate::ai::timed_service::AiLatencyStats;
+use crate::ai::queued_service::AiQueueStats;
+use crate::ai::levels::{AiLevel, LevelParams};
+use crate::ai::strategies::CancelFlag;
+use crate::ai::{AiDifficulty, ScoredMove};
 use crate::error::AIError;
 
 /// AIの手の計算結果を表す構造体
@@ -25,6 +35,67 @@ pub struct AIMoveResult {
     pub depth_reached: Option<u32>,
     /// 評価したノード数（実装によっては省略）
     pub nodes_evaluated: Option<u64>,
+    /// βカットの回数（探索しない実装では省略）
+    #[serde(default)]
+    pub cutoffs: Option<u64>,
+    /// 最初に調べた手でβカットした回数（`cutoffs` に近いほど手の並べ替えが効いている）
+    #[serde(default)]
+    pub first_move_cutoffs: Option<u64>,
+    /// 評価値の高い順の候補手（multi-PV、候補手を求めた場合のみ）
+    #[serde(default)]
+    pub alternatives: Option<Vec<MoveAnalysis>>,
+    /// 選んだ手から始まる読み筋（探索で求めた場合のみ、パスは含まない）
+    #[serde(default)]
+    pub principal_variation: Option<Vec<Position>>,
+}
+
+/// 合法手1つ分の解析結果を表す構造体
+/// 手番側から見た評価値と、その評価に用いた探索深度を保持する
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MoveAnalysis {
+    /// 解析対象の手の位置
+    pub position: Position,
+    /// 手番側から見た評価値（大きいほど有利）
+    pub score: f64,
+    /// 評価に用いた探索深度
+    pub depth: u32,
+}
+
+/// 手番側の全合法手を着手後の盤面の静的評価（深度1）で評価し、有利な順に並べる
+pub fn static_move_analysis(game_state: &GameState) -> Result<Vec<MoveAnalysis>, AIError> {
+    let player = game_state.current_player;
+    let weights = EvalWeights::default();
+
+    let mut analysis = Vec::new();
+    for position in ReversiRules::get_valid_moves(&game_state.board, player) {
+        let mut preview = game_state.clone();
+        ReversiRules::apply_move(&mut preview, position)
+            .map_err(|e| AIError::StrategyError { message: e.to_string() })?;
+        analysis.push(MoveAnalysis {
+            position,
+            score: BoardEvaluator::evaluate_for_variant(&preview.board, player, &weights, preview.variant) as f64,
+            depth: 1,
+        });
+    }
+
+    analysis.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(analysis)
+}
+
+impl MoveAnalysis {
+    /// 探索で求めた候補手を、探索した深度とともに解析結果にする
+    pub fn from_candidates(candidates: &[ScoredMove], depth: u32) -> Vec<Self> {
+        candidates
+            .iter()
+            .map(|candidate| MoveAnalysis { position: candidate.position, score: candidate.score, depth })
+            .collect()
+    }
+}
+
+impl From<MoveAnalysis> for ScoredMove {
+    fn from(analysis: MoveAnalysis) -> Self {
+        ScoredMove { position: analysis.position, score: analysis.score }
+    }
 }
 
 /// AIサービスの種類を表すenum
@@ -49,6 +120,15 @@ pub struct AIServiceStatus {
     pub supported_difficulties: Vec<AiDifficulty>,
     pub last_check: DateTime<Utc>,
     pub average_response_time_ms: Option<u64>,
+    /// 着手計算のキャッシュの統計（キャッシュが無効ならnull）
+    #[serde(default)]
+    pub cache: Option<AiCacheStats>,
+    /// 直近の着手計算の応答時間の統計（計測していなければnull）
+    #[serde(default)]
+    pub latency: Option<AiLatencyStats>,
+    /// 計算待ちキューの統計（キューを使わなければnull）
+    #[serde(default)]
+    pub queue: Option<AiQueueStats>,
 }
 
 /// AIサービスの統一インターフェース
@@ -62,6 +142,67 @@ pub trait AIService: Send + Sync {
         difficulty: AiDifficulty
     ) -> Result<AIMoveResult, AIError>;
     
+    /// シードを指定してAIの手を計算する
+    /// 乱択を行わない実装は `calculate_move` をそのまま使う
+    async fn calculate_move_seeded(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+        _seed: u64,
+    ) -> Result<AIMoveResult, AIError> {
+        self.calculate_move(game_state, difficulty).await
+    }
+    
+    /// 数値レベル（1〜10）を指定してAIの手を計算する
+    /// レベルに対応しない実装は、レベルが属する難易度で計算する
+    async fn calculate_move_at_level(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        seed: Option<u64>,
+    ) -> Result<AIMoveResult, AIError> {
+        let difficulty = level.difficulty().into();
+        match seed {
+            Some(seed) => self.calculate_move_seeded(game_state, difficulty, seed).await,
+            None => self.calculate_move(game_state, difficulty).await,
+        }
+    }
+    
+    /// 対局ごとに上書きした探索の設定でAIの手を計算する
+    /// 設定の上書きに対応しない実装は、`level` のレベルで計算する
+    async fn calculate_move_with_params(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        _params: LevelParams,
+        seed: Option<u64>,
+    ) -> Result<AIMoveResult, AIError> {
+        self.calculate_move_at_level(game_state, level, seed).await
+    }
+    
+    /// 先読みなど、空いているときだけ行う低優先度の着手計算
+    /// 計算待ちキューを使う実装は、空いているワーカーがなければ待たせずに `AIError::Overloaded` を返す
+    /// `cancel` が立つと `AIError::Cancelled` を返す。探索を途中で打ち切れない実装は、計算を始める前にだけ確かめる
+    async fn calculate_move_when_idle(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        params: LevelParams,
+        seed: Option<u64>,
+        cancel: &CancelFlag,
+    ) -> Result<AIMoveResult, AIError> {
+        if cancel.is_cancelled() {
+            return Err(AIError::Cancelled);
+        }
+        self.calculate_move_with_params(game_state, level, params, seed).await
+    }
+    
+    /// 着手計算を今受け付けられるかを確認する
+    /// 計算待ちキューが満杯であれば `AIError::Overloaded` を返す（キューを使わない実装は常にOk）
+    fn ensure_capacity(&self) -> Result<(), AIError> {
+        Ok(())
+    }
+    
     /// サービスが利用可能かチェックする
     async fn is_available(&self) -> bool;
     
@@ -74,6 +215,31 @@ pub trait AIService: Send + Sync {
     /// サービスの種類を返す
     fn get_service_type(&self) -> AIServiceType;
     
+    /// 手番側の全合法手を評価し、有利な順に並べて返す
+    /// デフォルト実装では各手を着手した後の盤面を静的評価する（深度1）
+    async fn analyze_moves(
+        &self,
+        game_state: &GameState,
+        _difficulty: AiDifficulty,
+    ) -> Result<Vec<MoveAnalysis>, AIError> {
+        static_move_analysis(game_state)
+    }
+    
+    /// 着手計算のキャッシュの統計（キャッシュしない実装はNone）
+    fn cache_stats(&self) -> Option<AiCacheStats> {
+        None
+    }
+    
+    /// 着手計算の応答時間の統計（計測しない実装はNone）
+    fn latency_stats(&self) -> Option<AiLatencyStats> {
+        None
+    }
+    
+    /// 計算待ちキューの統計（キューを使わない実装はNone）
+    fn queue_stats(&self) -> Option<AiQueueStats> {
+        None
+    }
+    
     /// サービスの現在の状態を取得する
     /// デフォルト実装では基本情報のみ提供
     async fn get_status(&self) -> AIServiceStatus {
@@ -83,7 +249,12 @@ pub trait AIService: Send + Sync {
             available: self.is_available().await,
             supported_difficulties: self.get_supported_difficulties(),
             last_check: Utc::now(),
-            average_response_time_ms: None,
+            average_response_time_ms: self.latency_stats()
+                .filter(|latency| latency.window > 0)
+                .map(|latency| latency.mean_ms.round() as u64),
+            cache: self.cache_stats(),
+            latency: self.latency_stats(),
+            queue: self.queue_stats(),
         }
     }
     
@@ -101,6 +272,9 @@ pub trait AIService: Send + Sync {
                 supported_difficulties: self.get_supported_difficulties(),
                 last_check: Utc::now(),
                 average_response_time_ms: Some(response_time),
+                cache: self.cache_stats(),
+                latency: self.latency_stats(),
+                queue: self.queue_stats(),
             })
         } else {
             Err(AIError::ServiceUnavailable {
@@ -120,7 +294,23 @@ pub struct AIServiceConfig {
     pub timeout_ms: u64,
     pub max_retries: u32,
     pub default_difficulty: AiDifficulty,
+    /// 着手計算の結果を局面ごとにキャッシュするか
     pub enable_caching: bool,
+    /// キャッシュする着手の最大数
+    #[serde(default = "default_cache_capacity")]
+    pub cache_capacity: usize,
+    /// 計算待ちキューを処理するワーカーの数（0はキューを使わず、リクエストごとに計算する）
+    #[serde(default = "default_queue_workers")]
+    pub queue_workers: usize,
+    /// 計算待ちキューの上限（満杯のときは503を返す）
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+    /// ローカルAIのαβ探索に使うスレッド数（0はCPU数、1は逐次探索）
+    #[serde(default)]
+    pub search_threads: usize,
+    /// ローカルAIが序盤に使う定石ブックのWTHOR形式（.wtb）ファイル
+    #[serde(default)]
+    pub opening_book: Option<PathBuf>,
 }
 
 impl Default for AIServiceConfig {
@@ -132,10 +322,27 @@ impl Default for AIServiceConfig {
             max_retries: 3,
             default_difficulty: AiDifficulty::Easy,
             enable_caching: false,
+            cache_capacity: default_cache_capacity(),
+            queue_workers: default_queue_workers(),
+            queue_capacity: default_queue_capacity(),
+            search_threads: 0,
+            opening_book: None,
         }
     }
 }
 
+fn default_cache_capacity() -> usize {
+    4096
+}
+
+fn default_queue_workers() -> usize {
+    4
+}
+
+fn default_queue_capacity() -> usize {
+    64
+}
+
 /// AIサービスを生成するファクトリクラス
 /// 設定に基づいて適切なAIサービス実装を選択して生成する
 pub struct AIServiceFactory;
@@ -143,12 +350,43 @@ pub struct AIServiceFactory;
 impl AIServiceFactory {
     /// 設定に基づいてAIサービスを生成する
     /// サービスタイプに応じて適切な実装を選択
+    /// `queue_workers` が1以上の場合は計算待ちキューで、`enable_caching` が有効な場合は着手計算のキャッシュで包む
+    /// キャッシュにヒットした着手はキューに積まない
+    /// 応答時間はキャッシュのヒットとキューでの待ち時間も含めて、呼び出し側から見た時間を計測する
+    /// キューはワーカーを別に持つため、対局の着手を計算するサービス以外（フォールバックなど）では `queue_workers` を0にする
     pub fn create_service(config: &AIServiceConfig) -> Result<Box<dyn AIService>, AIError> {
+        let mut service = Self::create_uncached_service(config)?;
+        if config.queue_workers > 0 {
+            use crate::ai::queued_service::QueuedAIService;
+            service = Box::new(QueuedAIService::new(service.into(), config.queue_workers, config.queue_capacity));
+        }
+        if config.enable_caching {
+            use crate::ai::cached_service::CachedAIService;
+            service = Box::new(CachedAIService::new(service.into(), config.cache_capacity));
+        }
+        
+        Ok(Self::timed(service))
+    }
+    
+    /// 着手計算の応答時間を計測するラッパーで包む
+    fn timed(service: Box<dyn AIService>) -> Box<dyn AIService> {
+        use crate::ai::timed_service::{TimedAIService, DEFAULT_LATENCY_WINDOW};
+        Box::new(TimedAIService::new(service.into(), DEFAULT_LATENCY_WINDOW))
+    }
+    
+    fn create_uncached_service(config: &AIServiceConfig) -> Result<Box<dyn AIService>, AIError> {
         match config.service_type {
             AIServiceType::Local => {
                 // ローカルAIサービスを生成
                 use crate::ai::local_service::LocalAIService;
-                Ok(Box::new(LocalAIService::new()))
+                let service = LocalAIService::new().with_search_threads(config.search_threads);
+                let Some(path) = &config.opening_book else {
+                    return Ok(Box::new(service));
+                };
+                let book = OpeningBook::load_wthor(path).map_err(|e| AIError::ConfigurationError {
+                    message: format!("定石ブック {} を読み込めません: {}", path.display(), e),
+                })?;
+                Ok(Box::new(service.with_opening_book(Arc::new(book))))
             }
             AIServiceType::Mock => {
                 // テスト用モックAIサービスを生成
@@ -166,10 +404,10 @@ impl AIServiceFactory {
         }
     }
     
-    /// デフォルト設定のローカルAIサービスを生成する
+    /// デフォルト設定のローカルAIサービスを、応答時間を計測するラッパーで包んで生成する
     pub fn create_default_local() -> Result<Box<dyn AIService>, AIError> {
         use crate::ai::local_service::LocalAIService;
-        Ok(Box::new(LocalAIService::new()))
+        Ok(Self::timed(Box::new(LocalAIService::new())))
     }
     
     /// 高速モードのローカルAIサービスを生成する
@@ -209,4 +447,18 @@ mod tests {
         let deserialized: AIServiceType = serde_json::from_str(&serialized).unwrap();
         assert_eq!(service_type, deserialized);
     }
+    
+    #[tokio::test]
+    async fn test_default_analyze_moves() {
+        let service = AIServiceFactory::create_mock(None).unwrap();
+        let game_state = GameState::new();
+        
+        let analysis = service.analyze_moves(&game_state, AiDifficulty::Easy).await.unwrap();
+        let valid_moves = ReversiRules::get_valid_moves(&game_state.board, game_state.current_player);
+        
+        assert_eq!(analysis.len(), valid_moves.len());
+        assert!(analysis.iter().all(|entry| valid_moves.contains(&entry.position)));
+        assert!(analysis.iter().all(|entry| entry.depth == 1));
+        assert!(analysis.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,259 @@
+//! AIの着手計算の応答時間の計測
+//! ラップ先の `AIService` の着手計算にかかった時間を直近 `window` 件分保持し、
+//! 件数・平均・95パーセンタイルを `get_status` と `/metrics` で公開する。
+//! エラーになった計算（終局・合法手なしなど）は応答時間の傾向を歪めるため計測しない。
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::ai::AiDifficulty;
+use crate::error::AIError;
+use crate::game::GameState;
+
+use super::cached_service::AiCacheStats;
+use super::levels::{AiLevel, LevelParams};
+use super::strategies::CancelFlag;
+use super::queued_service::AiQueueStats;
+use super::service::{AIMoveResult, AIService, AIServiceStatus, AIServiceType, MoveAnalysis};
+
+/// 保持する計測値の既定の件数
+pub const DEFAULT_LATENCY_WINDOW: usize = 256;
+
+/// 着手計算の応答時間の統計
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AiLatencyStats {
+    /// 起動から計測した着手計算の総数
+    pub count: u64,
+    /// 平均・パーセンタイルの集計に使った直近の計測数
+    pub window: usize,
+    /// 直近の計測の平均（ミリ秒、まだ計測がなければ0）
+    pub mean_ms: f64,
+    /// 直近の計測の95パーセンタイル（ミリ秒、まだ計測がなければ0）
+    pub p95_ms: f64,
+}
+
+/// 着手計算の応答時間を計測するAIサービス
+pub struct TimedAIService {
+    inner: Arc<dyn AIService>,
+    /// 直近の応答時間（古い順）
+    samples: Mutex<VecDeque<Duration>>,
+    window: usize,
+    count: AtomicU64,
+}
+
+impl std::fmt::Debug for TimedAIService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TimedAIService")
+            .field("inner", &self.inner.get_name())
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl TimedAIService {
+    /// 直近 `window` 件の応答時間を保持する（0の場合は1件）
+    pub fn new(inner: Arc<dyn AIService>, window: usize) -> Self {
+        let window = window.max(1);
+        Self {
+            inner,
+            samples: Mutex::new(VecDeque::with_capacity(window)),
+            window,
+            count: AtomicU64::new(0),
+        }
+    }
+
+    pub fn stats(&self) -> AiLatencyStats {
+        let mut samples: Vec<f64> = self.samples
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|sample| sample.as_secs_f64() * 1000.0)
+            .collect();
+        samples.sort_by(f64::total_cmp);
+
+        let mean_ms = if samples.is_empty() { 0.0 } else { samples.iter().sum::<f64>() / samples.len() as f64 };
+        // 最近傍順位法: 小さい方から ceil(0.95 * n) 番目
+        let p95_ms = match samples.len() {
+            0 => 0.0,
+            len => samples[(len * 95).div_ceil(100) - 1],
+        };
+        AiLatencyStats {
+            count: self.count.load(Ordering::Relaxed),
+            window: samples.len(),
+            mean_ms,
+            p95_ms,
+        }
+    }
+
+    fn record(&self, elapsed: Duration) {
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() == self.window {
+            samples.pop_front();
+        }
+        samples.push_back(elapsed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    async fn timed<F>(&self, calculation: F) -> Result<AIMoveResult, AIError>
+    where
+        F: Future<Output = Result<AIMoveResult, AIError>>,
+    {
+        let start_time = Instant::now();
+        let result = calculation.await;
+        if result.is_ok() {
+            self.record(start_time.elapsed());
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl AIService for TimedAIService {
+    async fn calculate_move(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+    ) -> Result<AIMoveResult, AIError> {
+        self.timed(self.inner.calculate_move(game_state, difficulty)).await
+    }
+
+    async fn calculate_move_seeded(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+        seed: u64,
+    ) -> Result<AIMoveResult, AIError> {
+        self.timed(self.inner.calculate_move_seeded(game_state, difficulty, seed)).await
+    }
+
+    async fn calculate_move_at_level(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        seed: Option<u64>,
+    ) -> Result<AIMoveResult, AIError> {
+        self.timed(self.inner.calculate_move_at_level(game_state, level, seed)).await
+    }
+
+    async fn calculate_move_with_params(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        params: LevelParams,
+        seed: Option<u64>,
+    ) -> Result<AIMoveResult, AIError> {
+        self.timed(self.inner.calculate_move_with_params(game_state, level, params, seed)).await
+    }
+
+    async fn calculate_move_when_idle(
+        &self,
+        game_state: &GameState,
+        level: AiLevel,
+        params: LevelParams,
+        seed: Option<u64>,
+        cancel: &CancelFlag,
+    ) -> Result<AIMoveResult, AIError> {
+        // 先読みは対局の応答時間ではないため計測しない
+        self.inner.calculate_move_when_idle(game_state, level, params, seed, cancel).await
+    }
+
+    fn ensure_capacity(&self) -> Result<(), AIError> {
+        self.inner.ensure_capacity()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+
+    fn get_supported_difficulties(&self) -> Vec<AiDifficulty> {
+        self.inner.get_supported_difficulties()
+    }
+
+    fn get_name(&self) -> &'static str {
+        self.inner.get_name()
+    }
+
+    fn get_service_type(&self) -> AIServiceType {
+        self.inner.get_service_type()
+    }
+
+    async fn analyze_moves(
+        &self,
+        game_state: &GameState,
+        difficulty: AiDifficulty,
+    ) -> Result<Vec<MoveAnalysis>, AIError> {
+        self.inner.analyze_moves(game_state, difficulty).await
+    }
+
+    fn cache_stats(&self) -> Option<AiCacheStats> {
+        self.inner.cache_stats()
+    }
+
+    fn latency_stats(&self) -> Option<AiLatencyStats> {
+        Some(self.stats())
+    }
+
+    fn queue_stats(&self) -> Option<AiQueueStats> {
+        self.inner.queue_stats()
+    }
+
+    async fn get_status(&self) -> AIServiceStatus {
+        let stats = self.stats();
+        AIServiceStatus {
+            average_response_time_ms: (stats.window > 0).then(|| stats.mean_ms.round() as u64),
+            latency: Some(stats),
+            ..self.inner.get_status().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::mock_service::{MockAIConfig, MockAIService};
+
+    #[test]
+    fn test_stats_keep_a_rolling_window() {
+        let service = TimedAIService::new(Arc::new(MockAIService::new_fast()), 20);
+        assert_eq!(service.stats(), AiLatencyStats { count: 0, window: 0, mean_ms: 0.0, p95_ms: 0.0 });
+
+        // 1〜25ms を計測すると、直近20件（6〜25ms）だけが集計に残る
+        for ms in 1..=25 {
+            service.record(Duration::from_millis(ms));
+        }
+        let stats = service.stats();
+        assert_eq!((stats.count, stats.window), (25, 20));
+        assert_eq!(stats.mean_ms, 15.5);
+        assert_eq!(stats.p95_ms, 24.0);
+    }
+
+    #[tokio::test]
+    async fn test_successful_moves_are_timed_and_reported_in_status() {
+        let mock = MockAIConfig { response_time_ms: 5, ..MockAIConfig::default() };
+        let service = TimedAIService::new(Arc::new(MockAIService::new(mock)), DEFAULT_LATENCY_WINDOW);
+        let status = service.get_status().await;
+        assert_eq!(status.average_response_time_ms, None);
+
+        let game_state = GameState::new();
+        service.calculate_move(&game_state, AiDifficulty::Easy).await.unwrap();
+        service.calculate_move_at_level(&game_state, AiLevel::new(3).unwrap(), Some(1)).await.unwrap();
+
+        // 終局した局面のエラーは計測しない
+        let mut finished = GameState::new();
+        finished.finish(None);
+        assert!(service.calculate_move(&finished, AiDifficulty::Easy).await.is_err());
+
+        let status = service.get_status().await;
+        let latency = status.latency.unwrap();
+        assert_eq!((latency.count, latency.window), (2, 2));
+        assert!(latency.mean_ms >= 5.0);
+        assert!(status.average_response_time_ms.unwrap() >= 5);
+        assert_eq!(service.latency_stats(), Some(service.stats()));
+    }
+}
@@ -0,0 +1,120 @@
+//! アカウントAPIハンドラー
+//! アカウントの登録・ログインでJWTを発行し、`Authorization: Bearer` ヘッダーで
+//! 提示されたJWTからログイン中のアカウントを取り出すエクストラクターを提供する。
+
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts, State},
+    http::{header, request::Parts, HeaderMap, StatusCode},
+    response::Json,
+};
+use std::sync::Arc;
+
+use crate::accounts::{is_jwt, AccessTokenResponse, AccountIdentity, AccountProfile, CredentialsRequest};
+
+use super::ai_battle::dto::{AiBattleError, AiBattleResult};
+use super::json::JsonBody;
+use super::ai_battle::service::AiBattleService;
+use super::handlers::AppState;
+
+/// リクエストに含まれるJWT形式のBearerトークン
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| is_jwt(token))
+}
+
+/// ログイン中のアカウント（JWTが提示されていなければNone）
+/// 提示されたJWTが無効・期限切れの場合は未ログインとして扱わず401を返す
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrentAccount(pub Option<AccountIdentity>);
+
+impl CurrentAccount {
+    pub fn as_ref(&self) -> Option<&AccountIdentity> {
+        self.0.as_ref()
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for CurrentAccount
+where
+    Arc<AiBattleService>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AiBattleError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Some(token) = bearer_token(&parts.headers) else {
+            return Ok(CurrentAccount(None));
+        };
+        let service = Arc::<AiBattleService>::from_ref(state);
+        Ok(CurrentAccount(Some(service.accounts().verify(token)?)))
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AccountIdentity
+where
+    Arc<AiBattleService>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AiBattleError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let CurrentAccount(account) = CurrentAccount::from_request_parts(parts, state).await?;
+        account.ok_or(AiBattleError::LoginRequired)
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/register",
+    tag = "accounts",
+    request_body = CredentialsRequest,
+    responses(
+        (status = 201, description = "アカウントを登録し、アクセストークンを発行", body = AccessTokenResponse),
+        (status = 400, description = "ユーザー名またはパスワードが条件を満たさない", body = ErrorResponse),
+        (status = 409, description = "ユーザー名は既に使われている", body = ErrorResponse),
+    )
+)]
+pub async fn register(
+    State(state): State<AppState>,
+    JsonBody(request): JsonBody<CredentialsRequest>,
+) -> AiBattleResult<(StatusCode, Json<AccessTokenResponse>)> {
+    let response = state.ai_battle_service.accounts().register(&request).await?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/login",
+    tag = "accounts",
+    request_body = CredentialsRequest,
+    responses(
+        (status = 200, description = "アクセストークンを発行", body = AccessTokenResponse),
+        (status = 401, description = "ユーザー名またはパスワードが正しくない", body = ErrorResponse),
+    )
+)]
+pub async fn login(
+    State(state): State<AppState>,
+    JsonBody(request): JsonBody<CredentialsRequest>,
+) -> AiBattleResult<Json<AccessTokenResponse>> {
+    Ok(Json(state.ai_battle_service.accounts().login(&request).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/accounts/me",
+    tag = "accounts",
+    params(("Authorization" = String, Header, description = "`Bearer <アクセストークン>`")),
+    responses(
+        (status = 200, description = "ログイン中のアカウント", body = AccountProfile),
+        (status = 401, description = "未ログイン、またはトークンが無効", body = ErrorResponse),
+    )
+)]
+pub async fn get_me(account: AccountIdentity) -> Json<AccountProfile> {
+    Json(AccountProfile { account_id: account.account_id, username: account.username })
+}
@@ -0,0 +1,50 @@
+//! 管理者向けセッション操作モジュール
+//! セッションのピン留めなど、運用中のセッションを管理者が操作するAPIと、
+//! AIサービスとセッションの状態を確認するAPIを提供する。
+
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use uuid::Uuid;
+
+use super::ai_battle::config_service::ServiceStatus;
+use super::ai_battle::dto::{AiBattleResult, PinSessionRequest, SessionSummary};
+use super::json::JsonBody;
+use super::handlers::AppState;
+
+#[utoipa::path(
+    put,
+    path = "/api/admin/sessions/{game_id}/pin",
+    tag = "system",
+    params(("game_id" = Uuid, Path, description = "ゲームID")),
+    request_body = PinSessionRequest,
+    responses(
+        (status = 200, description = "ピン留めを変更したセッション", body = SessionSummary),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+    )
+)]
+pub async fn pin_session(
+    State(state): State<AppState>,
+    Path(game_id): Path<Uuid>,
+    JsonBody(request): JsonBody<PinSessionRequest>,
+) -> AiBattleResult<Json<SessionSummary>> {
+    Ok(Json(state.ai_battle_service.set_pinned(game_id, request.pinned)?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/status",
+    tag = "system",
+    responses(
+        (status = 200, description = "プライマリ・フォールバックのAIサービスとセッションの状態", body = ServiceStatus),
+        (status = 401, description = "APIキーが指定されていない、または無効", body = ErrorResponse),
+    )
+)]
+pub async fn get_service_status(State(state): State<AppState>) -> Json<ServiceStatus> {
+    let status = match &state.configurable_service {
+        Some(configurable_service) => configurable_service.get_service_status().await,
+        None => ServiceStatus::from_service(&state.ai_battle_service).await,
+    };
+    Json(status)
+}
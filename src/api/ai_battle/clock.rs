@@ -0,0 +1,371 @@
+//! 持ち時間モジュール
+//! 対局ごとの持ち時間（初期時間＋1手ごとの加算、または通信対局の1手あたりの制限）を管理する。
+//! 手番側の経過時間を着手時に差し引き、時間切れと通信対局の催促の時期を判定する。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+use super::dto::{AiBattleError, AiBattleResult};
+use super::service::AiBattleService;
+use crate::api::encoding::api_player;
+use crate::api::health::HealthRegistry;
+use crate::game::Player;
+
+const DAY_SECONDS: u32 = 24 * 60 * 60;
+
+/// 催促の時期が設定されていない場合、残り時間がこの割合を下回ったら通信対局の手番側に催促する
+const REMINDER_FRACTION: i64 = 4;
+
+/// 時間切れと催促を確認する間隔
+pub const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 持ち時間の設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TimeControl {
+    /// 初期の持ち時間（秒）
+    pub initial_seconds: u32,
+    /// 1手ごとの加算時間（秒）
+    #[serde(default)]
+    pub increment_seconds: u32,
+    /// 通信対局の1手あたりの制限（秒）。指定した場合は着手ごとに持ち時間がこの値に戻る
+    #[serde(default)]
+    pub per_move_seconds: Option<u32>,
+}
+
+impl TimeControl {
+    pub fn is_correspondence(&self) -> bool {
+        self.per_move_seconds.is_some()
+    }
+
+    pub fn validate(&self) -> AiBattleResult<()> {
+        if self.initial_seconds == 0 {
+            return Err(AiBattleError::BadRequest { details: "initial_secondsは1以上を指定してください".to_string() });
+        }
+        if self.per_move_seconds == Some(0) {
+            return Err(AiBattleError::BadRequest { details: "per_move_secondsは1以上を指定してください".to_string() });
+        }
+        Ok(())
+    }
+}
+
+/// 名前付きの持ち時間プリセット
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum TimeControlPreset {
+    /// 1分切れ負け
+    #[serde(rename = "blitz_1_0")]
+    Blitz1,
+    /// 5分＋1手3秒
+    #[serde(rename = "blitz_5_3")]
+    Blitz5Plus3,
+    /// 15分＋1手10秒
+    #[serde(rename = "rapid_15_10")]
+    Rapid15Plus10,
+    /// 通信対局（1手1日）
+    #[serde(rename = "correspondence_1d")]
+    Correspondence1Day,
+    /// 通信対局（1手3日）
+    #[serde(rename = "correspondence_3d")]
+    Correspondence3Days,
+}
+
+impl TimeControlPreset {
+    pub fn all() -> Vec<TimeControlPreset> {
+        vec![
+            TimeControlPreset::Blitz1,
+            TimeControlPreset::Blitz5Plus3,
+            TimeControlPreset::Rapid15Plus10,
+            TimeControlPreset::Correspondence1Day,
+            TimeControlPreset::Correspondence3Days,
+        ]
+    }
+
+    pub fn time_control(&self) -> TimeControl {
+        let (initial_seconds, increment_seconds, per_move_seconds) = match self {
+            TimeControlPreset::Blitz1 => (60, 0, None),
+            TimeControlPreset::Blitz5Plus3 => (5 * 60, 3, None),
+            TimeControlPreset::Rapid15Plus10 => (15 * 60, 10, None),
+            TimeControlPreset::Correspondence1Day => (DAY_SECONDS, 0, Some(DAY_SECONDS)),
+            TimeControlPreset::Correspondence3Days => (3 * DAY_SECONDS, 0, Some(3 * DAY_SECONDS)),
+        };
+        TimeControl { initial_seconds, increment_seconds, per_move_seconds }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeControlPreset::Blitz1 => "1+0",
+            TimeControlPreset::Blitz5Plus3 => "5+3",
+            TimeControlPreset::Rapid15Plus10 => "15+10",
+            TimeControlPreset::Correspondence1Day => "通信対局 1手1日",
+            TimeControlPreset::Correspondence3Days => "通信対局 1手3日",
+        }
+    }
+}
+
+/// リクエストでの持ち時間の指定
+/// プリセット名（`"blitz_5_3"` など）か、`TimeControl` のオブジェクトで指定する
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum TimeControlSetting {
+    Preset(TimeControlPreset),
+    Custom(TimeControl),
+}
+
+impl TimeControlSetting {
+    /// 検証済みの持ち時間に変換する
+    pub fn resolve(self) -> AiBattleResult<TimeControl> {
+        let time_control = match self {
+            TimeControlSetting::Preset(preset) => preset.time_control(),
+            TimeControlSetting::Custom(time_control) => time_control,
+        };
+        time_control.validate()?;
+        Ok(time_control)
+    }
+}
+
+/// プリセット1件の情報
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TimeControlPresetInfo {
+    pub id: TimeControlPreset,
+    pub name: &'static str,
+    pub time_control: TimeControl,
+}
+
+/// プリセット一覧
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TimeControlPresetsResponse {
+    pub presets: Vec<TimeControlPresetInfo>,
+}
+
+impl TimeControlPresetsResponse {
+    pub fn new() -> Self {
+        let presets = TimeControlPreset::all()
+            .into_iter()
+            .map(|preset| TimeControlPresetInfo { id: preset, name: preset.name(), time_control: preset.time_control() })
+            .collect();
+        Self { presets }
+    }
+}
+
+impl Default for TimeControlPresetsResponse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 対局中の両者の持ち時間
+/// 手番側の残り時間は `turn_started_at` からの経過時間を差し引いて求める
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameClock {
+    pub time_control: TimeControl,
+    black_remaining_ms: i64,
+    white_remaining_ms: i64,
+    turn_started_at: DateTime<Utc>,
+    /// 現在の手番で催促を送ったか
+    reminder_sent: bool,
+}
+
+impl GameClock {
+    pub fn new(time_control: TimeControl, now: DateTime<Utc>) -> Self {
+        let initial_ms = i64::from(time_control.initial_seconds) * 1000;
+        Self {
+            time_control,
+            black_remaining_ms: initial_ms,
+            white_remaining_ms: initial_ms,
+            turn_started_at: now,
+            reminder_sent: false,
+        }
+    }
+
+    fn stored_ms(&self, player: Player) -> i64 {
+        match player {
+            Player::Black => self.black_remaining_ms,
+            Player::White => self.white_remaining_ms,
+        }
+    }
+
+    fn set_stored_ms(&mut self, player: Player, remaining_ms: i64) {
+        match player {
+            Player::Black => self.black_remaining_ms = remaining_ms,
+            Player::White => self.white_remaining_ms = remaining_ms,
+        }
+    }
+
+    /// `to_move` の手番中における `player` の残り時間（ミリ秒）
+    pub fn remaining_ms(&self, player: Player, to_move: Player, now: DateTime<Utc>) -> i64 {
+        let stored = self.stored_ms(player);
+        if player != to_move {
+            return stored;
+        }
+        let elapsed = (now - self.turn_started_at).num_milliseconds().max(0);
+        (stored - elapsed).max(0)
+    }
+
+    /// 手番側の持ち時間が尽きているか
+    pub fn is_flagged(&self, to_move: Player, now: DateTime<Utc>) -> bool {
+        self.remaining_ms(to_move, to_move, now) == 0
+    }
+
+    /// 着手（またはパス）した側の経過時間を差し引き、相手の手番を開始する
+    /// 持ち時間が尽きていた場合はfalseを返す
+    pub fn record_move(&mut self, mover: Player, now: DateTime<Utc>) -> bool {
+        let remaining = self.remaining_ms(mover, mover, now);
+        self.turn_started_at = now;
+        self.reminder_sent = false;
+
+        if remaining == 0 {
+            self.set_stored_ms(mover, 0);
+            return false;
+        }
+
+        let next = match self.time_control.per_move_seconds {
+            Some(per_move) => i64::from(per_move) * 1000,
+            None => remaining + i64::from(self.time_control.increment_seconds) * 1000,
+        };
+        self.set_stored_ms(mover, next);
+        true
+    }
+
+    /// 通信対局で手番側に催促すべき時期か（1手につき1回）
+    /// `remind_after` を指定した場合は手番になってからその時間が経過した時点、
+    /// 指定しない場合は残り時間が1手の制限の1/4になった時点
+    pub fn reminder_due(&self, to_move: Player, now: DateTime<Utc>, remind_after: Option<chrono::Duration>) -> bool {
+        let Some(per_move) = self.time_control.per_move_seconds else {
+            return false;
+        };
+        if self.reminder_sent || self.is_flagged(to_move, now) {
+            return false;
+        }
+        match remind_after {
+            Some(remind_after) => now - self.turn_started_at >= remind_after,
+            None => self.remaining_ms(to_move, to_move, now) <= i64::from(per_move) * 1000 / REMINDER_FRACTION,
+        }
+    }
+
+    pub fn mark_reminded(&mut self) {
+        self.reminder_sent = true;
+    }
+
+    /// 時間切れになった側の持ち時間を0にする
+    pub fn flag(&mut self, player: Player) {
+        self.set_stored_ms(player, 0);
+    }
+
+    /// 時間切れになったプレイヤー
+    pub fn flagged_player(&self) -> Option<Player> {
+        [Player::Black, Player::White].into_iter().find(|&player| self.stored_ms(player) == 0)
+    }
+}
+
+/// レスポンスに含める持ち時間の状態
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ClockView {
+    pub time_control: TimeControl,
+    pub black_remaining_ms: u64,
+    pub white_remaining_ms: u64,
+    /// 時計が動いている側（終局後はnull）
+    #[serde(with = "api_player::option")]
+    #[schema(value_type = Option<ApiPlayer>)]
+    pub running: Option<Player>,
+}
+
+impl ClockView {
+    pub fn new(clock: &GameClock, running: Option<Player>, now: DateTime<Utc>) -> Self {
+        let to_move = running.unwrap_or(Player::Black);
+        let remaining = |player| match running {
+            Some(_) => clock.remaining_ms(player, to_move, now),
+            None => clock.stored_ms(player),
+        };
+        Self {
+            time_control: clock.time_control,
+            black_remaining_ms: remaining(Player::Black).max(0) as u64,
+            white_remaining_ms: remaining(Player::White).max(0) as u64,
+            running,
+        }
+    }
+}
+
+/// 一定間隔で時間切れの判定と通信対局の催促を行うタスクを起動する
+pub fn spawn_clock_sweeper(service: Arc<AiBattleService>, health: &HealthRegistry) -> tokio::task::JoinHandle<()> {
+    let heartbeat = health.register_task("clock-sweeper", SWEEP_INTERVAL);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            service.sweep_clocks(Utc::now());
+            heartbeat.beat();
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap()
+    }
+
+    #[test]
+    fn test_increment_is_added_after_move() {
+        let mut clock = GameClock::new(TimeControlPreset::Blitz5Plus3.time_control(), at(0));
+
+        assert!(clock.record_move(Player::Black, at(10)));
+        assert_eq!(clock.remaining_ms(Player::Black, Player::White, at(10)), (300 - 10 + 3) * 1000);
+        assert_eq!(clock.remaining_ms(Player::White, Player::White, at(15)), 295 * 1000);
+    }
+
+    #[test]
+    fn test_flag_falls_when_time_runs_out() {
+        let mut clock = GameClock::new(TimeControlPreset::Blitz1.time_control(), at(0));
+
+        assert!(!clock.is_flagged(Player::Black, at(59)));
+        assert!(clock.is_flagged(Player::Black, at(60)));
+        assert!(!clock.record_move(Player::Black, at(61)));
+    }
+
+    #[test]
+    fn test_correspondence_clock_resets_and_reminds_once() {
+        let day = i64::from(DAY_SECONDS);
+        let mut clock = GameClock::new(TimeControlPreset::Correspondence1Day.time_control(), at(0));
+
+        assert!(!clock.reminder_due(Player::Black, at(day / 2), None));
+        assert!(clock.reminder_due(Player::Black, at(day * 3 / 4), None));
+        clock.mark_reminded();
+        assert!(!clock.reminder_due(Player::Black, at(day * 3 / 4 + 1), None));
+
+        // 着手すると1手あたりの制限まで戻る
+        assert!(clock.record_move(Player::Black, at(day - 1)));
+        assert_eq!(clock.remaining_ms(Player::Black, Player::White, at(day)), day * 1000);
+        assert!(!clock.reminder_due(Player::White, at(day), None));
+    }
+
+    #[test]
+    fn test_configured_reminder_period() {
+        let day = i64::from(DAY_SECONDS);
+        let clock = GameClock::new(TimeControlPreset::Correspondence1Day.time_control(), at(0));
+        let remind_after = Some(chrono::Duration::hours(2));
+
+        assert!(!clock.reminder_due(Player::Black, at(3600), remind_after));
+        assert!(clock.reminder_due(Player::Black, at(2 * 3600), remind_after));
+        // 時間切れ後は催促せず、時間切れとして扱う
+        assert!(!clock.reminder_due(Player::Black, at(day), remind_after));
+
+        let blitz = GameClock::new(TimeControlPreset::Blitz1.time_control(), at(0));
+        assert!(!blitz.reminder_due(Player::Black, at(30), remind_after));
+    }
+
+    #[test]
+    fn test_time_control_setting_accepts_preset_or_custom() {
+        let preset: TimeControlSetting = serde_json::from_str("\"rapid_15_10\"").unwrap();
+        assert_eq!(preset.resolve().unwrap().increment_seconds, 10);
+
+        let custom: TimeControlSetting = serde_json::from_str(r#"{"initial_seconds": 180, "increment_seconds": 2}"#).unwrap();
+        assert_eq!(custom.resolve().unwrap(), TimeControl { initial_seconds: 180, increment_seconds: 2, per_move_seconds: None });
+
+        let invalid: TimeControlSetting = serde_json::from_str(r#"{"initial_seconds": 0}"#).unwrap();
+        assert!(invalid.resolve().is_err());
+    }
+}
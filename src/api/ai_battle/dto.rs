@@ -1,130 +1,463 @@
 //! AI対戦API データ転送オブジェクト (DTO)
 
-use axum::{http::StatusCode, response::Json};
+use axum::{
+    extract::rejection::JsonRejection,
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use crate::game::{GameState, Position, Player, Move};
-use crate::ai::Difficulty as LegacyDifficulty;
+use crate::game::{setup, transcript, replay::ReplayFrame, Board, Cell, GameState, GameVariant, InvalidMoveReason, Position, PositionHash, Player, Move, ReversiRules, SUPPORTED_BOARD_SIZES};
+use super::clock::{ClockView, GameClock, TimeControl, TimeControlSetting};
+use super::ponder::PonderState;
+use crate::api::encoding::{self, api_player, ApiPlayer};
+use crate::ai::levels::{AiConfigOverrides, AiLevel, LevelParams};
+use crate::ai::personality::AiPersonality;
+use crate::ai::adaptive::AdaptiveState;
+use crate::ai::explain::MoveExplanation;
+use crate::ai::service::MoveAnalysis;
+use crate::ai::strategies::AlphaBetaAI;
+use crate::error::GameError;
+use crate::serde_util;
+use crate::ratings::RatingChange;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum AiDifficulty {
-    Easy,
-    Medium,
-    Hard,
+pub use crate::ai::AiDifficulty;
+
+/// 一辺が `board_size` マスの盤面の座標として検証する
+pub fn validate_position(row: u8, col: u8, board_size: usize) -> Result<Position, String> {
+    Position::within(row as usize, col as usize, board_size).ok_or_else(|| {
+        format!("座標が範囲外です: ({}, {}). 有効範囲: 0-{}", row, col, board_size.saturating_sub(1))
+    })
 }
 
-impl AiDifficulty {
-    pub fn all() -> Vec<AiDifficulty> {
-        vec![AiDifficulty::Easy, AiDifficulty::Medium, AiDifficulty::Hard]
+/// 対局の盤面の一辺のマス数を検証する
+pub fn validate_board_size(board_size: usize) -> Result<usize, String> {
+    if Board::is_supported_size(board_size) {
+        Ok(board_size)
+    } else {
+        Err(format!("盤面の大きさが不正です: {}. 指定できる値: {:?}", board_size, SUPPORTED_BOARD_SIZES))
     }
+}
+
+/// 置き石のハンデ（指定した色の石を隅に置いた状態から対局を始める）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Handicap {
+    /// 置き石を受ける色
+    #[serde(with = "api_player")]
+    #[schema(value_type = ApiPlayer)]
+    pub player: Player,
+    /// 置き石の数（1〜4）。左上・右下・右上・左下の隅の順に置く
+    pub corners: u8,
+}
+
+impl Handicap {
+    /// 置き石の数の上限（4隅）
+    pub const MAX_CORNERS: u8 = 4;
     
-    pub fn description(&self) -> &'static str {
-        match self {
-            AiDifficulty::Easy => "初級 - ランダムな手を選択",
-            AiDifficulty::Medium => "中級 - 基本的な戦略を使用", 
-            AiDifficulty::Hard => "上級 - 高度な先読みを実行",
+    pub fn validate(self) -> Result<Self, String> {
+        if (1..=Self::MAX_CORNERS).contains(&self.corners) {
+            Ok(self)
+        } else {
+            Err(format!("置き石の数が不正です: {}. 指定できる値: 1〜{}", self.corners, Self::MAX_CORNERS))
         }
     }
     
-    pub fn name(&self) -> &'static str {
-        match self {
-            AiDifficulty::Easy => "Easy",
-            AiDifficulty::Medium => "Medium", 
-            AiDifficulty::Hard => "Hard",
-        }
+    /// 一辺が `size` マスの盤面での置き石の配置
+    pub fn stones(self, size: usize) -> impl Iterator<Item = (Position, Cell)> {
+        let last = size - 1;
+        [(0, 0), (last, last), (0, last), (last, 0)]
+            .into_iter()
+            .take(self.corners as usize)
+            .map(move |(row, col)| (Position { row, col }, self.player.to_cell()))
     }
 }
 
-impl FromStr for AiDifficulty {
-    type Err = String;
-    
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "easy" => Ok(AiDifficulty::Easy),
-            "medium" => Ok(AiDifficulty::Medium),
-            "hard" => Ok(AiDifficulty::Hard),
-            _ => Err(format!("Invalid difficulty: {}. Valid options: easy, medium, hard", s)),
-        }
-    }
+/// 任意の局面から始める対局の開始局面
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct StartPosition {
+    /// 盤面の文字列（行優先で1マス1文字、X: 黒, O: 白, -: 空。行は `/` や改行で区切ってもよい）
+    #[schema(example = "--------/--------/---X----/---XX---/---XO---/--------/--------/--------")]
+    pub board: String,
+    /// 手番の色
+    #[serde(with = "api_player")]
+    #[schema(value_type = ApiPlayer)]
+    pub to_move: Player,
 }
 
-impl From<AiDifficulty> for LegacyDifficulty {
-    fn from(difficulty: AiDifficulty) -> Self {
-        match difficulty {
-            AiDifficulty::Easy => LegacyDifficulty::Beginner,
-            AiDifficulty::Medium => LegacyDifficulty::Intermediate,
-            AiDifficulty::Hard => LegacyDifficulty::Advanced,
-        }
+impl StartPosition {
+    /// 盤面を解析し、到達できる局面で手番に合法手があるかを検証した状態を返す
+    pub fn game_state(&self, variant: GameVariant) -> Result<GameState, String> {
+        setup::start_state(&self.board, self.to_move, variant).map_err(|err| err.to_string())
+    }
+    
+    /// 1行の盤面表記（`Board::to_fen`）から開始局面を読む（到達できるかは `game_state` で検証する）
+    pub fn from_fen(text: &str) -> Result<Self, String> {
+        let (board, to_move) = Board::from_fen(text).map_err(|err| err.to_string())?;
+        Ok(Self { board: setup::board_to_string(&board), to_move })
+    }
+    
+    /// 盤面の文字列を `/` 区切りの正規の形に直す（解析できない場合はエラー）
+    pub fn normalized(&self) -> Result<Self, String> {
+        let board = setup::parse_board(&self.board).map_err(|err| err.to_string())?;
+        Ok(Self { board: setup::board_to_string(&board), to_move: self.to_move })
     }
 }
 
-impl From<LegacyDifficulty> for AiDifficulty {
-    fn from(difficulty: LegacyDifficulty) -> Self {
-        match difficulty {
-            LegacyDifficulty::Beginner => AiDifficulty::Easy,
-            LegacyDifficulty::Intermediate => AiDifficulty::Medium,
-            LegacyDifficulty::Advanced => AiDifficulty::Hard,
-        }
+/// 盤面の大きさ・ルール・置き石、または任意の開始局面から対局開始時の状態を作る
+/// 対応していない大きさや、開始局面が不正な場合はNone
+pub fn initial_game_state(
+    size: usize,
+    variant: GameVariant,
+    handicap: Option<Handicap>,
+    position: Option<&StartPosition>,
+    seed: u64,
+) -> Option<GameState> {
+    if let Some(position) = position {
+        return position.game_state(variant).ok();
     }
+    let Some(handicap) = handicap else {
+        return GameState::with_variant(size, variant, seed);
+    };
+    if !Board::is_supported_size(size) {
+        return None;
+    }
+    let layout = variant.initial_layout(size, seed).into_iter().chain(handicap.stones(size));
+    Some(GameState::from_board(Board::from_layout(size, layout)?, variant))
 }
 
-pub fn validate_position(row: u8, col: u8) -> Result<Position, String> {
-    if row >= 8 || col >= 8 {
-        return Err(format!("座標が範囲外です: ({}, {}). 有効範囲: 0-7", row, col));
-    }
-    
-    Position::new(row as usize, col as usize)
-        .ok_or_else(|| format!("無効な座標です: ({}, {})", row, col))
+/// パスの着手の表記
+pub const PASS_NOTATION: &str = "pass";
+
+/// 着手の表記（"d3" 形式、パスは "pass"）
+pub fn move_notation(position: Option<Position>) -> String {
+    position.map_or_else(|| PASS_NOTATION.to_string(), |position| position.to_string())
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(from = "MoveRecordRepr")]
 pub struct MoveRecord {
+    /// セッション内で単調増加する着手の通し番号（待ったで取り消されても再利用しない）
+    /// 0は未採番を表す
+    #[serde(default)]
+    pub seq: u64,
+    #[serde(with = "api_player")]
+    #[schema(value_type = ApiPlayer)]
     pub player: Player,
-    pub position: Position,
+    /// パスの場合はnull
+    pub position: Option<Position>,
+    /// "d3" 形式の着手の表記（パスは "pass"）
+    #[schema(example = "d3")]
+    pub notation: String,
     pub timestamp: DateTime<Utc>,
     pub thinking_time_ms: Option<u64>,
+    /// AIの着手の場合、計算に使った探索の設定（対局の再生で同じ手になるかの検証に使う）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_params: Option<LevelParams>,
+}
+
+/// `MoveRecord` のデシリアライズ用表現（表記は座標から作り直すため、表記のない古い記録も読める）
+#[derive(Deserialize)]
+struct MoveRecordRepr {
+    #[serde(default)]
+    seq: u64,
+    #[serde(with = "api_player")]
+    player: Player,
+    position: Option<Position>,
+    timestamp: DateTime<Utc>,
+    thinking_time_ms: Option<u64>,
+    #[serde(default)]
+    ai_params: Option<LevelParams>,
+}
+
+impl From<MoveRecordRepr> for MoveRecord {
+    fn from(repr: MoveRecordRepr) -> Self {
+        Self {
+            seq: repr.seq,
+            player: repr.player,
+            position: repr.position,
+            notation: move_notation(repr.position),
+            timestamp: repr.timestamp,
+            thinking_time_ms: repr.thinking_time_ms,
+            ai_params: repr.ai_params,
+        }
+    }
 }
 
 impl MoveRecord {
     pub fn new(player: Player, position: Position, thinking_time_ms: Option<u64>) -> Self {
         Self {
+            seq: 0,
             player,
-            position,
+            position: Some(position),
+            notation: position.to_string(),
             timestamp: Utc::now(),
             thinking_time_ms,
+            ai_params: None,
+        }
+    }
+    
+    /// 合法手がなくパスしたことを表す記録
+    pub fn pass(player: Player) -> Self {
+        Self {
+            seq: 0,
+            player,
+            position: None,
+            notation: PASS_NOTATION.to_string(),
+            timestamp: Utc::now(),
+            thinking_time_ms: None,
+            ai_params: None,
         }
     }
     
     pub fn from_move(game_move: &Move, thinking_time_ms: Option<u64>) -> Self {
         Self {
+            seq: 0,
             player: game_move.player,
-            position: game_move.position,
+            position: Some(game_move.position),
+            notation: game_move.position.to_string(),
             timestamp: game_move.timestamp,
             thinking_time_ms,
+            ai_params: None,
         }
     }
+    
+    pub fn is_pass(&self) -> bool {
+        self.position.is_none()
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
 pub enum GameStatus {
     InProgress,
-    Finished { winner: Option<Player> },
+    Finished {
+        #[serde(with = "api_player::option")]
+        #[schema(value_type = Option<ApiPlayer>)]
+        winner: Option<Player>,
+    },
+}
+
+/// タグ正規化後の `GameStatus` のデシリアライズ用表現
+#[derive(Deserialize)]
+enum GameStatusRepr {
+    InProgress,
+    Finished { winner: Option<ApiPlayer> },
+}
+
+impl<'de> Deserialize<'de> for GameStatus {
+    /// "in_progress" や {"finished": {...}} のような表記揺れを受け付ける
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_util::canonicalize_enum_tag(
+            serde_json::Value::deserialize(deserializer)?,
+            &["InProgress", "Finished"],
+            &[("playing", "InProgress")],
+        );
+        match serde_json::from_value(value).map_err(serde::de::Error::custom)? {
+            GameStatusRepr::InProgress => Ok(GameStatus::InProgress),
+            GameStatusRepr::Finished { winner } => Ok(GameStatus::Finished { winner: winner.map(Player::from) }),
+        }
+    }
+}
+
+/// 各色の石を誰が操作するか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum PlayerController {
+    Human,
+    Ai { difficulty: AiDifficulty },
+}
+
+impl PlayerController {
+    pub fn is_ai(&self) -> bool {
+        matches!(self, PlayerController::Ai { .. })
+    }
+    
+    pub fn difficulty(&self) -> Option<AiDifficulty> {
+        match self {
+            PlayerController::Human => None,
+            PlayerController::Ai { difficulty } => Some(*difficulty),
+        }
+    }
+}
+
+/// セッションの種類（各色の操作主体から決まる）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum SessionKind {
+    HumanVsAi,
+    AiVsAi,
+    HumanVsHuman,
+}
+
+/// 各色を担当するプレイヤーのID（通知の宛先）
+/// `X-Player-Id` ヘッダー付きで作成・参加した場合のみ記録される
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeatOwners {
+    pub black: Option<Uuid>,
+    pub white: Option<Uuid>,
+}
+
+impl SeatOwners {
+    pub fn get(&self, player: Player) -> Option<Uuid> {
+        match player {
+            Player::Black => self.black,
+            Player::White => self.white,
+        }
+    }
+    
+    pub fn set(&mut self, player: Player, owner: Uuid) {
+        match player {
+            Player::Black => self.black = Some(owner),
+            Player::White => self.white = Some(owner),
+        }
+    }
+    
+    /// 記録されている全プレイヤー（同一プレイヤーは1回のみ）
+    pub fn all(&self) -> Vec<Uuid> {
+        let mut owners: Vec<Uuid> = self.black.into_iter().chain(self.white).collect();
+        owners.dedup();
+        owners
+    }
+}
+
+/// 対人戦で各色に割り当てられたトークン
+/// 白番は参加トークンで参加するまで空席となる
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeatTokens {
+    pub black: Uuid,
+    pub white: Option<Uuid>,
+    pub join_token: Option<Uuid>,
+}
+
+impl SeatTokens {
+    pub fn new() -> Self {
+        Self {
+            black: Uuid::new_v4(),
+            white: None,
+            join_token: Some(Uuid::new_v4()),
+        }
+    }
+    
+    /// 両方の席が埋まった状態で作成する（ロビーで成立した対局用）
+    pub fn seated(black: Uuid, white: Uuid) -> Self {
+        Self {
+            black,
+            white: Some(white),
+            join_token: None,
+        }
+    }
+    
+    /// プレイヤートークンに対応する色を返す
+    pub fn color_of(&self, player_token: Uuid) -> Option<Player> {
+        if player_token == self.black {
+            Some(Player::Black)
+        } else if Some(player_token) == self.white {
+            Some(Player::White)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for SeatTokens {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AiBattleSession {
     pub id: Uuid,
     pub game_state: GameState,
+    /// セッションの代表難易度（AI同士の対戦では黒番の難易度）
     pub ai_difficulty: AiDifficulty,
+    /// 数値レベルで作成した対局のAIのレベル（難易度で作成した場合はnull）
+    #[serde(default)]
+    pub ai_level: Option<AiLevel>,
+    /// この対局だけ上書きしたAIの探索の設定（上書きを適用した後の値、上書きがなければnull）
+    #[serde(default)]
+    pub ai_config: Option<LevelParams>,
+    /// AIのパーソナリティ（指定がなければnull）
+    #[serde(default)]
+    pub personality: Option<AiPersonality>,
+    pub black: PlayerController,
+    pub white: PlayerController,
+    /// 対人戦の場合のみ設定される
+    pub seat_tokens: Option<SeatTokens>,
+    #[serde(default)]
+    pub owners: SeatOwners,
+    /// 持ち時間（未設定なら無制限）
+    #[serde(default)]
+    pub clock: Option<GameClock>,
+    /// 置き石のハンデ（なければnull）
+    #[serde(default)]
+    pub handicap: Option<Handicap>,
+    /// 任意の局面から始めた対局の開始局面（初期配置から始めた場合はnull）
+    #[serde(default)]
+    pub start_position: Option<StartPosition>,
+    /// レーティング対象の対局か
+    #[serde(default)]
+    pub rated: bool,
+    /// 終局時のレーティングの変動（レーティングは対局ごとに1回だけ更新する）
+    #[serde(default)]
+    pub rating_changes: Vec<RatingChange>,
+    /// 管理者がピン留めしたセッション（クリーンアップの対象外にできる）
+    #[serde(default)]
+    pub pinned: bool,
+    /// 大会の対局の場合、その大会のID
+    #[serde(default)]
+    pub tournament_id: Option<Uuid>,
+    /// 観戦用の共有トークン（発行されていなければnull）
+    #[serde(default)]
+    pub spectator_token: Option<Uuid>,
+    /// セッションを作成したAPIキーの利用者名（認証が無効な場合はnull）
+    #[serde(default)]
+    pub api_key_name: Option<String>,
+    /// セッションを作成したアカウントのID
+    /// 設定されている場合、着手・難易度変更・削除はこのアカウントに限られる（未ログインで作成した場合はnull）
+    #[serde(default)]
+    pub owner_id: Option<Uuid>,
+    /// 各色を担当するログイン中のアカウント（対人戦で作成・参加した場合のみ記録される）
+    #[serde(default)]
+    pub accounts: SeatOwners,
     pub current_player: Player,
     pub ai_thinking: bool,
     pub created_at: DateTime<Utc>,
     pub last_move_at: DateTime<Utc>,
+    /// クライアントが現局面を最初に取得した時刻（人間の思考時間の起点、局面が変わるとリセットする）
+    #[serde(default)]
+    pub position_seen_at: Option<DateTime<Utc>>,
     pub move_history: Vec<MoveRecord>,
+    /// 最後に採番した着手の通し番号
+    #[serde(default)]
+    pub move_seq: u64,
+    /// 盤面の着手履歴（`game_state.move_history`）の各手の通し番号
+    #[serde(default)]
+    pub ply_seqs: Vec<u64>,
+    /// 最後に待ったで履歴を巻き戻したときの通し番号（0は巻き戻していない）
+    /// この版以前からは差分を作れない
+    #[serde(default)]
+    pub rewound_at: u64,
     pub status: GameStatus,
+    /// AIの乱択・揺らぎのシード（作成時に指定がなければ乱数で決める）
+    /// 記録しておくことで、終局後に対局を同じ手順で再生して検証できる
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// 人間の手番のあいだにAIが応手を先読みするか
+    #[serde(default)]
+    pub ponder: bool,
+    /// 進行中の先読み（保存・復元の対象外）
+    #[serde(skip)]
+    pub ponder_state: Option<PonderState>,
+    /// 応答に現局面の評価値（評価バー）を含めるか
+    #[serde(default)]
+    pub eval_bar: bool,
+    /// 教習モード（AIの着手の応答に解説を含める）
+    #[serde(default)]
+    pub teaching: bool,
+    /// 適応難易度の状態（接戦になるようにAIのレベルを上げ下げする、無効ならnull）
+    #[serde(default)]
+    pub adaptive: Option<AdaptiveState>,
 }
 
 impl AiBattleSession {
@@ -136,157 +469,1602 @@ impl AiBattleSession {
             id: Uuid::new_v4(),
             game_state: game_state.clone(),
             ai_difficulty,
+            ai_level: None,
+            ai_config: None,
+            personality: None,
+            black: PlayerController::Human,
+            white: PlayerController::Ai { difficulty: ai_difficulty },
+            seat_tokens: None,
+            owners: SeatOwners::default(),
+            clock: None,
+            handicap: None,
+            start_position: None,
+            rated: false,
+            rating_changes: Vec::new(),
+            pinned: false,
+            tournament_id: None,
+            spectator_token: None,
+            api_key_name: None,
+            owner_id: None,
+            accounts: SeatOwners::default(),
             current_player: game_state.current_player,
             ai_thinking: false,
             created_at: now,
             last_move_at: now,
+            position_seen_at: None,
             move_history: Vec::new(),
+            move_seq: 0,
+            ply_seqs: Vec::new(),
+            rewound_at: 0,
             status: GameStatus::InProgress,
+            seed: Some(rand::random()),
+            ponder: false,
+            ponder_state: None,
+            eval_bar: false,
+            teaching: false,
+            adaptive: None,
+        }
+    }
+    
+    /// 人間の担当色を指定してAI対戦を作成する（もう一方の色はAIが操作する）
+    pub fn new_with_color(ai_difficulty: AiDifficulty, player_color: Player) -> Self {
+        let ai = PlayerController::Ai { difficulty: ai_difficulty };
+        let (black, white) = match player_color {
+            Player::Black => (PlayerController::Human, ai),
+            Player::White => (ai, PlayerController::Human),
+        };
+        
+        Self {
+            black,
+            white,
+            ..Self::new(ai_difficulty)
+        }
+    }
+    
+    /// 両色ともAIが操作するエキシビション対局を作成する
+    pub fn new_ai_vs_ai(black_difficulty: AiDifficulty, white_difficulty: AiDifficulty) -> Self {
+        Self {
+            black: PlayerController::Ai { difficulty: black_difficulty },
+            white: PlayerController::Ai { difficulty: white_difficulty },
+            ..Self::new(black_difficulty)
+        }
+    }
+    
+    /// 対人戦を作成する（ヒント・解析の既定難易度はEasy）
+    pub fn new_pvp() -> Self {
+        Self {
+            black: PlayerController::Human,
+            white: PlayerController::Human,
+            seat_tokens: Some(SeatTokens::new()),
+            ..Self::new(AiDifficulty::Easy)
+        }
+    }
+    
+    /// 両者の席が決まった対人戦を作成する
+    pub fn new_pvp_seated(seats: SeatTokens) -> Self {
+        Self {
+            seat_tokens: Some(seats),
+            ..Self::new_pvp()
+        }
+    }
+    
+    pub fn controller(&self, player: Player) -> PlayerController {
+        match player {
+            Player::Black => self.black,
+            Player::White => self.white,
+        }
+    }
+    
+    /// 人間対AIの対局で人間が担当する色
+    pub fn player_color(&self) -> Option<Player> {
+        match (self.black.is_ai(), self.white.is_ai()) {
+            (false, true) => Some(Player::Black),
+            (true, false) => Some(Player::White),
+            _ => None,
+        }
+    }
+    
+    /// 人間が担当する色のプレイヤーID（AIの色、または分からない場合はNone）
+    /// 担当プレイヤーが記録されていない人間対AIの対局では、セッションを作成したアカウントとみなす
+    pub fn player_id(&self, player: Player) -> Option<Uuid> {
+        if self.controller(player).is_ai() {
+            return None;
+        }
+        self.owners
+            .get(player)
+            .or_else(|| self.owner_id.filter(|_| self.kind() == SessionKind::HumanVsAi))
+    }
+    
+    /// 人間が担当する色のアカウントID（AIの色、またはログインせずに担当している場合はNone）
+    /// `player_id` と異なり、認証されていない `X-Player-Id` は使わない。人間対AIの対局ではセッションを作成したアカウントとみなす
+    pub fn account_id(&self, player: Player) -> Option<Uuid> {
+        if self.controller(player).is_ai() {
+            return None;
+        }
+        self.accounts
+            .get(player)
+            .or_else(|| self.owner_id.filter(|_| self.kind() == SessionKind::HumanVsAi))
+    }
+    
+    pub fn is_ai_vs_ai(&self) -> bool {
+        self.black.is_ai() && self.white.is_ai()
+    }
+    
+    pub fn kind(&self) -> SessionKind {
+        match (self.black.is_ai(), self.white.is_ai()) {
+            (true, true) => SessionKind::AiVsAi,
+            (false, false) => SessionKind::HumanVsHuman,
+            _ => SessionKind::HumanVsAi,
+        }
+    }
+    
+    /// AI難易度を変更する（AIが操作する全ての色に適用し、数値レベルと探索の設定の上書きは解除する）
+    /// パーソナリティは引き継ぎ、新しい難易度の設定に適用し直す
+    pub fn set_ai_difficulty(&mut self, difficulty: AiDifficulty) {
+        if let Some(adaptive) = self.adaptive.as_mut() {
+            *adaptive = AdaptiveState::new(difficulty.level());
+        }
+        self.ai_level = None;
+        self.ai_config = self.personality.map(|personality| personality.apply(difficulty.level().params()));
+        self.ai_difficulty = difficulty;
+        for controller in [&mut self.black, &mut self.white] {
+            if controller.is_ai() {
+                *controller = PlayerController::Ai { difficulty };
+            }
+        }
+    }
+    
+    /// 盤面の一辺のマス数
+    pub fn board_size(&self) -> usize {
+        self.game_state.board_size()
+    }
+    
+    /// 対局のルール
+    pub fn variant(&self) -> GameVariant {
+        self.game_state.variant
+    }
+    
+    /// 着手前の対局の盤面の大きさ・ルール・置き石・開始局面を変える（対応していない大きさや不正な局面ならfalse）
+    /// 初期配置を乱択するルールでは対局のシードで配置を選ぶ
+    pub fn set_start(
+        &mut self,
+        size: usize,
+        variant: GameVariant,
+        handicap: Option<Handicap>,
+        position: Option<StartPosition>,
+    ) -> bool {
+        if !self.move_history.is_empty() {
+            return false;
+        }
+        match initial_game_state(size, variant, handicap, position.as_ref(), self.seed.unwrap_or_default()) {
+            Some(game_state) => {
+                self.current_player = game_state.current_player;
+                self.game_state = game_state;
+                self.handicap = handicap;
+                self.start_position = position;
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// AIを数値レベルで指定する（難易度はレベルが属する区分になる）
+    pub fn set_ai_level(&mut self, level: AiLevel) {
+        self.set_ai_difficulty(level.into());
+        if let Some(adaptive) = self.adaptive.as_mut() {
+            adaptive.level = level;
+        }
+        self.ai_level = Some(level);
+        self.ai_config = self.personality.map(|personality| personality.apply(level.params()));
+    }
+    
+    /// 指定した色のAIが使うレベルと探索の設定
+    /// 適応難易度では、その時点のレベルの設定にパーソナリティだけを適用する
+    pub fn ai_params(&self, player: Player) -> (AiLevel, LevelParams) {
+        if let Some(adaptive) = self.adaptive {
+            let params = adaptive.level.params();
+            return (adaptive.level, self.personality.map_or(params, |personality| personality.apply(params)));
+        }
+        let difficulty = self.controller(player).difficulty().unwrap_or(self.ai_difficulty);
+        let level = self.ai_level.unwrap_or(difficulty.level());
+        (level, self.ai_config.unwrap_or(level.params()))
+    }
+    
+    pub fn is_ai_turn(&self) -> bool {
+        self.controller(self.current_player).is_ai() && !self.ai_thinking
+    }
+    
+    pub fn is_player_turn(&self) -> bool {
+        !self.controller(self.current_player).is_ai()
+    }
+    
+    pub fn update_last_move(&mut self) {
+        self.last_move_at = Utc::now();
+        self.position_seen_at = None;
+    }
+    
+    /// クライアントが現局面を取得したことを記録する（同じ局面では最初に取得した時刻を残す）
+    pub fn mark_position_seen(&mut self, now: DateTime<Utc>) {
+        self.position_seen_at.get_or_insert(now);
+    }
+    
+    /// 手番側が現局面を見てから経過した時間（取得されていなければ局面が変わってからの時間）
+    pub fn thinking_time_ms(&self, now: DateTime<Utc>) -> u64 {
+        (now - self.position_seen_at.unwrap_or(self.last_move_at)).num_milliseconds().max(0) as u64
+    }
+    
+    /// 未採番の記録には通し番号を振ってから追加する
+    pub fn add_move_record(&mut self, mut move_record: MoveRecord) {
+        if move_record.seq == 0 {
+            move_record.seq = self.next_move_seq();
+        }
+        self.move_history.push(move_record);
+        self.update_last_move();
+    }
+    
+    /// 盤面に適用した直後の着手に通し番号を振る
+    pub fn record_placement(&mut self) -> u64 {
+        let seq = self.next_move_seq();
+        self.ply_seqs.push(seq);
+        seq
+    }
+    
+    fn next_move_seq(&mut self) -> u64 {
+        self.move_seq += 1;
+        self.move_seq
+    }
+    
+    /// 初手を指す色（任意の局面から始めた対局ではその局面の手番）
+    pub fn first_player(&self) -> Player {
+        self.start_position.as_ref().map_or(Player::Black, |position| position.to_move)
+    }
+    
+    /// 対局開始時の状態（不正な開始局面の場合はNone）
+    pub fn initial_state(&self) -> Option<GameState> {
+        let seed = self.seed.unwrap_or_default();
+        initial_game_state(self.board_size(), self.variant(), self.handicap, self.start_position.as_ref(), seed)
+    }
+    
+    /// 対局開始時の盤面（標準の初期配置から始めた対局ではNone）
+    pub fn custom_start(&self) -> Option<StartPosition> {
+        let size = self.board_size();
+        let initial = self.initial_state()?;
+        let standard = Board::with_size(size).is_some_and(|board| board == initial.board);
+        (!standard || initial.current_player != Player::Black)
+            .then(|| StartPosition { board: setup::board_to_string(&initial.board), to_move: initial.current_player })
+    }
+    
+    /// 人間の着手とパスを含む、着手順の全記録
+    /// 同じプレイヤーが続けて着手している箇所には相手のパスを挟む
+    pub fn transcript_records(&self) -> Vec<MoveRecord> {
+        let mut passes = self.move_history.iter().filter(|record| record.is_pass());
+        let mut records = Vec::new();
+        let mut expected = self.first_player();
+        for (index, game_move) in self.game_state.move_history.iter().enumerate() {
+            if game_move.player != expected {
+                records.push(passes.next().cloned().unwrap_or_else(|| MoveRecord::pass(expected)));
+            }
+            let mut record = MoveRecord::from_move(game_move, None);
+            record.seq = self.ply_seqs.get(index).copied().unwrap_or(0);
+            // 思考時間とAIの探索の設定は同じ番号の着手の記録から引き継ぐ
+            if let Some(played) = self.move_history.iter().find(|played| record.seq != 0 && played.seq == record.seq) {
+                record.thinking_time_ms = played.thinking_time_ms;
+                record.ai_params = played.ai_params;
+            }
+            records.push(record);
+            expected = game_move.player.opposite();
+        }
+        records.extend(passes.cloned());
+        records
+    }
+    
+    /// 通し番号のないセッション（採番導入前に保存されたもの）に着手順で番号を振り直す
+    pub fn ensure_move_seqs(&mut self) {
+        let plies = self.game_state.move_history.len();
+        let consistent = self.ply_seqs.len() == plies
+            && self.move_history.iter().all(|record| record.seq != 0);
+        if consistent {
+            return;
+        }
+        
+        let pass_indices: Vec<usize> = (0..self.move_history.len())
+            .filter(|&index| self.move_history[index].is_pass())
+            .collect();
+        let mut passes = pass_indices.into_iter();
+        let mut seq = 0;
+        let mut ply_seqs = Vec::with_capacity(plies);
+        let mut missing_passes = Vec::new();
+        let mut expected = self.first_player();
+        for game_move in &self.game_state.move_history {
+            if game_move.player != expected {
+                seq += 1;
+                match passes.next() {
+                    Some(index) => self.move_history[index].seq = seq,
+                    // 記録の欠けたパスは補う（以降のパスも全て欠けているため末尾に追加すれば順序が保たれる）
+                    None => missing_passes.push(MoveRecord { seq, ..MoveRecord::pass(expected) }),
+                }
+            }
+            seq += 1;
+            ply_seqs.push(seq);
+            expected = game_move.player.opposite();
+        }
+        for index in passes {
+            seq += 1;
+            self.move_history[index].seq = seq;
+        }
+        self.move_history.extend(missing_passes);
+        
+        // AIの着手の記録は対応する盤面の着手と同じ番号にする
+        let mut placements = self.game_state.move_history.iter().zip(&ply_seqs);
+        for record in self.move_history.iter_mut().filter(|record| !record.is_pass()) {
+            if let Some((_, &ply_seq)) = placements
+                .by_ref()
+                .find(|(game_move, _)| game_move.player == record.player && Some(game_move.position) == record.position)
+            {
+                record.seq = ply_seq;
+            }
+        }
+        
+        self.ply_seqs = ply_seqs;
+        self.move_seq = self.move_seq.max(seq);
+    }
+    
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status, GameStatus::Finished { .. })
+    }
+    
+    /// 1手ごとの制限がある通信対局か
+    pub fn is_correspondence(&self) -> bool {
+        self.clock.as_ref().is_some_and(|clock| clock.time_control.is_correspondence())
+    }
+    
+    /// 時間切れで負けたプレイヤー
+    pub fn lost_on_time(&self) -> Option<Player> {
+        if !self.is_finished() {
+            return None;
+        }
+        self.clock.as_ref().and_then(GameClock::flagged_player)
+    }
+    
+    /// 手番側に合法手がなく、パスするしかない状態か
+    pub fn must_pass(&self) -> bool {
+        !self.is_finished()
+            && !crate::game::ReversiRules::has_valid_moves(&self.game_state.board, self.current_player)
+    }
+    
+    /// 着手後に手番を相手に渡す
+    /// 次の手番がAIで合法手がなければ自動でパスを記録し、両者とも打てなければ終局とする。
+    /// 人間の手番で合法手がない場合は `pass_turn` による明示的なパスを待つ。
+    /// 自動でパスしたプレイヤーを返す
+    pub fn advance_turn(&mut self) -> Option<Player> {
+        use crate::game::ReversiRules;
+        
+        let mover = self.game_state.current_player;
+        let now = Utc::now();
+        if !self.punch_clock(mover, now) {
+            self.finish_on_time(mover);
+            return None;
+        }
+        
+        self.game_state.switch_player();
+        let next = self.game_state.current_player;
+        let board = &self.game_state.board;
+        
+        let mut passed = None;
+        if !ReversiRules::has_valid_moves(board, next) {
+            if !ReversiRules::has_valid_moves(board, next.opposite()) {
+                let winner = ReversiRules::determine_winner(board, self.game_state.variant);
+                self.game_state.finish(winner);
+                self.status = GameStatus::Finished { winner };
+            } else if self.controller(next).is_ai() {
+                self.add_move_record(MoveRecord::pass(next));
+                self.punch_clock(next, now);
+                self.game_state.switch_player();
+                passed = Some(next);
+            }
+        }
+        
+        self.current_player = self.game_state.current_player;
+        self.update_last_move();
+        passed
+    }
+    
+    /// 合法手のない手番側がパスする
+    pub fn pass_turn(&mut self) -> AiBattleResult<()> {
+        if !self.must_pass() {
+            return Err(AiBattleError::InvalidMove {
+                reason: "合法手があるためパスできません".to_string(),
+                rejection: None,
+            });
+        }
+        
+        let player = self.current_player;
+        if !self.punch_clock(player, Utc::now()) {
+            self.finish_on_time(player);
+            return Err(AiBattleError::TimeExpired);
+        }
+        
+        self.add_move_record(MoveRecord::pass(player));
+        self.game_state.switch_player();
+        self.current_player = self.game_state.current_player;
+        Ok(())
+    }
+    
+    /// 手番側の時間切れを確認し、時間切れなら相手の勝ちで終局させる
+    /// 時間切れで終局した場合はtrueを返す
+    pub fn settle_clock(&mut self, now: DateTime<Utc>) -> bool {
+        let flagged = !self.is_finished()
+            && self.clock.as_ref().is_some_and(|clock| clock.is_flagged(self.current_player, now));
+        if flagged {
+            self.finish_on_time(self.current_player);
+        }
+        flagged
+    }
+    
+    /// 着手した側の時計を止めて相手の時計を動かす（持ち時間がなければ何もしない）
+    fn punch_clock(&mut self, mover: Player, now: DateTime<Utc>) -> bool {
+        self.clock.as_mut().is_none_or(|clock| clock.record_move(mover, now))
+    }
+    
+    fn finish_on_time(&mut self, loser: Player) {
+        if let Some(clock) = self.clock.as_mut() {
+            clock.flag(loser);
+        }
+        let winner = Some(loser.opposite());
+        self.game_state.finish(winner);
+        self.status = GameStatus::Finished { winner };
+        self.update_last_move();
+    }
+    
+    /// 人間の直前の着手と、それ以降のAIの応手をまとめて取り消す
+    /// 取り消した手を着手順に返す
+    pub fn undo_last_turn(&mut self) -> AiBattleResult<Vec<TranscriptMove>> {
+        if self.seat_tokens.is_some() {
+            return Err(AiBattleError::CannotUndo { reason: "対人戦では待ったはできません".to_string() });
+        }
+        
+        let human_index = self.game_state.move_history
+            .iter()
+            .rposition(|game_move| !self.controller(game_move.player).is_ai())
+            .ok_or_else(|| AiBattleError::CannotUndo { reason: "取り消せる着手がありません".to_string() })?;
+        
+        let mut undone = Vec::new();
+        while self.game_state.move_history.len() > human_index {
+            let ply = self.game_state.move_history.len() as u32;
+            let Some(game_move) = self.game_state.undo_last_move() else { break };
+            self.ply_seqs.pop();
+            
+            // 取り消す着手より後のパスの記録も合わせて取り除く
+            while self.move_history.last().is_some_and(MoveRecord::is_pass) {
+                self.move_history.pop();
+            }
+            
+            // 記録のない着手（記録を始める前に保存された人間の着手）は履歴から取り除かない
+            let recorded = self.move_history
+                .last()
+                .is_some_and(|record| record.player == game_move.player && record.position == Some(game_move.position));
+            if recorded {
+                self.move_history.pop();
+            }
+            
+            undone.push(TranscriptMove { ply, player: game_move.player, position: game_move.position });
+        }
+        undone.reverse();
+        self.rewound_at = self.move_seq;
+        
+        self.current_player = self.game_state.current_player;
+        self.status = GameStatus::InProgress;
+        self.update_last_move();
+        Ok(undone)
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateAiBattleRequest {
+    /// 難易度（`level` を指定しない場合は必須）。Easy / Medium / Hard はレベル1・5・8の別名
+    #[serde(default)]
+    pub difficulty: Option<AiDifficulty>,
+    /// AIの数値レベル（1〜10）。指定した場合は `difficulty` より優先する
+    #[serde(default)]
+    #[schema(value_type = Option<u8>)]
+    pub level: Option<AiLevel>,
+    /// この対局だけAIの探索深度・思考時間の上限・評価の重みを上書きする（省略した項目はレベルの設定を使う）
+    #[serde(default)]
+    pub ai_config: Option<AiConfigOverrides>,
+    /// AIのパーソナリティ（評価の重みと手選びのランダムさ）。`ai_config` の重みの指定はこれより優先する
+    #[serde(default)]
+    pub personality: Option<AiPersonality>,
+    /// AIの乱択のシード。指定すると同じ着手に対して毎回同じ手を返す（テスト用、省略時は対局ごとに変わる）
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// 人間が担当する色（省略時は黒番）。白番の場合はAIが初手を打つ
+    #[serde(default = "default_player_color")]
+    #[schema(value_type = Option<ApiPlayer>)]
+    pub player_color: Player,
+    /// 持ち時間（プリセット名またはカスタム設定）。省略時は無制限
+    #[serde(default)]
+    pub time_control: Option<TimeControlSetting>,
+    /// trueの場合、終局時にAIの難易度の固定レーティングを相手としてプレイヤーのレーティングを更新する
+    /// レーティングはログイン中のアカウントに記録する（未ログインの対局は更新しない）
+    #[serde(default)]
+    pub rated: bool,
+    /// trueの場合、人間の手番のあいだにAIが応手を先読みし、先読みした手が指されればすぐに応手する
+    #[serde(default)]
+    pub ponder: bool,
+    /// trueの場合、対局の応答に現局面の評価値（評価バー用の浅い探索の結果）を含める
+    #[serde(default)]
+    pub eval_bar: bool,
+    /// trueの場合、着手の応答にAIが応手を選んだ理由の解説を含める（教習モード）
+    #[serde(default)]
+    pub teaching: bool,
+    /// trueの場合、接戦になるようにAIが局面の評価に応じてレベルを上げ下げする（`ai_config` とは同時に指定できない）
+    #[serde(default)]
+    pub adaptive: bool,
+    /// 盤面の一辺のマス数（6 / 8 / 10、省略時は8）
+    #[serde(default)]
+    pub board_size: Option<usize>,
+    /// 対局のルール（省略時は通常のオセロ）
+    #[serde(default)]
+    pub variant: Option<GameVariant>,
+    /// 置き石のハンデ（例: 黒が4隅に石を置いて始める）
+    #[serde(default)]
+    pub handicap: Option<Handicap>,
+    /// 初期配置の代わりに始める任意の局面（中盤・終盤の練習用）。`handicap` とは同時に指定できない
+    #[serde(default)]
+    pub start_position: Option<StartPosition>,
+    /// `start_position` の代わりに1行の盤面表記（全マスと手番）で指定する開始局面
+    #[serde(default)]
+    #[schema(example = "---------------------------OX------XO--------------------------- X")]
+    pub start_fen: Option<String>,
+}
+
+impl CreateAiBattleRequest {
+    /// AIと盤面の設定（開始局面を両方の形式で指定した場合や、盤面表記が不正な場合はエラー）
+    pub fn ai_setup(&self) -> AiBattleResult<AiSetup> {
+        let start_position = match (&self.start_position, &self.start_fen) {
+            (Some(_), Some(_)) => {
+                return Err(AiBattleError::BadRequest {
+                    details: "start_positionとstart_fenは同時に指定できません".to_string(),
+                });
+            }
+            (None, Some(fen)) => {
+                Some(StartPosition::from_fen(fen).map_err(|details| AiBattleError::BadRequest { details })?)
+            }
+            (position, None) => position.clone(),
+        };
+        Ok(AiSetup {
+            level: self.level,
+            overrides: self.ai_config,
+            personality: self.personality,
+            seed: self.seed,
+            board_size: self.board_size,
+            variant: self.variant,
+            handicap: self.handicap,
+            start_position,
+        })
+    }
+}
+
+fn default_player_color() -> Player {
+    Player::Black
+}
+
+/// 人間対AIの対局を作成するときのAIと盤面の設定
+#[derive(Debug, Clone, Default)]
+pub struct AiSetup {
+    /// 数値レベル（指定した場合は難易度より優先する）
+    pub level: Option<AiLevel>,
+    /// この対局だけのAIの設定の上書き
+    pub overrides: Option<AiConfigOverrides>,
+    pub personality: Option<AiPersonality>,
+    /// AIの乱択のシード（未指定なら対局ごとに異なる手を選ぶ）
+    pub seed: Option<u64>,
+    /// 盤面の一辺のマス数（未指定なら8）
+    pub board_size: Option<usize>,
+    /// 対局のルール（未指定なら通常のオセロ）
+    pub variant: Option<GameVariant>,
+    /// 置き石のハンデ
+    pub handicap: Option<Handicap>,
+    /// 初期配置の代わりに始める局面
+    pub start_position: Option<StartPosition>,
+}
+
+/// AI同士の対戦の作成リクエスト
+/// `play_out` がtrueの場合はサーバー側で終局まで進めてから返す
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateAiVsAiRequest {
+    pub black_difficulty: AiDifficulty,
+    pub white_difficulty: AiDifficulty,
+    #[serde(default)]
+    pub play_out: bool,
+}
+
+/// 着手のリクエスト（座標は `row` / `col` と "d3" 形式の `square` のどちらかで指定する）
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct PlayerMoveRequest {
+    #[serde(default)]
+    pub row: Option<u8>,
+    #[serde(default)]
+    pub col: Option<u8>,
+    /// "d3" 形式の座標（列をaから始まる英字、行を1から始まる数字で表す）
+    #[serde(default)]
+    #[schema(example = "d3")]
+    pub square: Option<String>,
+    /// 対人戦では着手する色のプレイヤートークンが必須
+    #[serde(default)]
+    pub player_token: Option<Uuid>,
+    /// trueならAIの応手の読み筋（予想される手順）も返す
+    #[serde(default)]
+    pub principal_variation: bool,
+}
+
+impl PlayerMoveRequest {
+    /// 指定された座標を盤面の大きさで検証する
+    pub fn position(&self, board_size: usize) -> AiBattleResult<Position> {
+        let (row, col) = match (self.row, self.col, self.square.as_deref()) {
+            (Some(row), Some(col), None) => (row, col),
+            (None, None, Some(square)) => {
+                let position: Position = square.parse().map_err(|reason| AiBattleError::InvalidPosition { reason })?;
+                (position.row as u8, position.col as u8)
+            }
+            _ => {
+                return Err(AiBattleError::BadRequest {
+                    details: "座標は row と col の組、または square（例: \"d3\"）のどちらか一方で指定してください".to_string(),
+                })
+            }
+        };
+        validate_position(row, col, board_size).map_err(|reason| AiBattleError::InvalidPosition { reason })
+    }
+}
+
+/// 対人戦への参加リクエスト
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct JoinPvpRequest {
+    pub join_token: Uuid,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ChangeDifficultyRequest {
+    pub difficulty: AiDifficulty,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AiBattleResponse {
+    pub game_id: Uuid,
+    #[serde(with = "api_player::board")]
+    #[schema(value_type = Vec<Vec<Option<ApiPlayer>>>)]
+    pub board: encoding::CanonicalBoard,
+    /// 盤面の一辺のマス数
+    pub board_size: usize,
+    /// 対局のルール
+    pub variant: GameVariant,
+    /// 置き石のハンデ（なければnull）
+    pub handicap: Option<Handicap>,
+    /// 任意の局面から始めた対局の開始局面（なければnull）
+    pub start_position: Option<StartPosition>,
+    #[serde(with = "api_player")]
+    #[schema(value_type = ApiPlayer)]
+    pub current_player: Player,
+    pub black_count: u8,
+    pub white_count: u8,
+    pub ai_difficulty: AiDifficulty,
+    /// 数値レベルで作成した対局のAIのレベル（難易度で作成した場合はnull）
+    #[schema(value_type = Option<u8>)]
+    pub ai_level: Option<AiLevel>,
+    /// この対局だけ上書きしたAIの探索の設定（上書きがなければnull）
+    pub ai_config: Option<LevelParams>,
+    /// 対戦相手のAIのパーソナリティ（指定がなければnull）
+    pub personality: Option<AiPersonality>,
+    pub kind: SessionKind,
+    pub black: PlayerController,
+    pub white: PlayerController,
+    pub ai_thinking: bool,
+    pub status: GameStatus,
+    pub valid_moves: Vec<Position>,
+    /// 手番側に合法手がなく、パスが必要な場合にtrue
+    pub must_pass: bool,
+    pub move_count: u32,
+    /// 局面の版（着手・パス・待ったのたびに増える）。`?since=` に渡すと以降の差分だけを取得できる
+    pub version: u64,
+    /// 持ち時間のない対局ではnull
+    pub clock: Option<ClockView>,
+    /// 現局面の正規化済みハッシュ（`/api/positions/{hash}` で局面の情報を引ける）
+    pub position_hash: String,
+    /// レーティング対象の対局が終局した場合の各プレイヤーのレーティングの変動
+    pub rating_changes: Vec<RatingChange>,
+    /// AIが人間の手番のあいだに応手を先読みする対局か
+    pub ponder: bool,
+    /// 応答に評価値を含める対局か
+    pub eval_bar: bool,
+    /// 現局面の評価値（評価バーが無効な対局、または終局した対局ではnull）
+    pub evaluation: Option<PositionEvaluation>,
+    /// 教習モードの対局か
+    pub teaching: bool,
+    /// 適応難易度の状態（無効な対局ではnull）
+    pub adaptive: Option<AdaptiveState>,
+}
+
+/// 評価バー用の探索深度（応答を返すたびに探索するため、対局のAIより浅くする）
+pub const EVAL_BAR_DEPTH: u8 = 3;
+
+/// 評価バーに表示する現局面の評価値
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, ToSchema)]
+pub struct PositionEvaluation {
+    /// 黒から見た評価値（正なら黒が有利、負なら白が有利）
+    /// 終局まで読み切れた場合は勝敗に応じて ±1000 に石差を加えた値になる
+    pub score: f32,
+    /// 探索した深さ
+    pub depth: u8,
+}
+
+impl PositionEvaluation {
+    /// 現局面を `EVAL_BAR_DEPTH` 手先まで探索して評価する
+    pub fn of(state: &GameState) -> Self {
+        let score = AlphaBetaAI::new(EVAL_BAR_DEPTH).evaluate_state(state);
+        let score = match state.current_player {
+            Player::Black => score,
+            Player::White => -score,
+        };
+        Self { score, depth: EVAL_BAR_DEPTH }
+    }
+}
+
+impl AiBattleResponse {
+    pub fn from_session(session: &AiBattleSession) -> Self {
+        let board = encoding::encode_board(&session.game_state.board);
+        
+        let valid_moves = if session.is_finished() {
+            Vec::new()
+        } else {
+            session.game_state.valid_moves()
+        };
+        
+        let (black_count, white_count) = session.game_state.get_score();
+        
+        Self {
+            game_id: session.id,
+            board,
+            board_size: session.board_size(),
+            variant: session.variant(),
+            handicap: session.handicap,
+            start_position: session.start_position.clone(),
+            current_player: session.current_player,
+            black_count,
+            white_count,
+            ai_difficulty: session.ai_difficulty,
+            ai_level: session.ai_level,
+            ai_config: session.ai_config,
+            personality: session.personality,
+            kind: session.kind(),
+            black: session.black,
+            white: session.white,
+            ai_thinking: session.ai_thinking,
+            status: session.status,
+            valid_moves,
+            must_pass: session.must_pass(),
+            move_count: session.game_state.move_history.len() as u32,
+            version: session.move_seq,
+            clock: session.clock.as_ref().map(|clock| {
+                let running = (!session.is_finished()).then_some(session.current_player);
+                ClockView::new(clock, running, Utc::now())
+            }),
+            position_hash: PositionHash::of(&session.game_state.board, session.current_player).to_string(),
+            rating_changes: session.rating_changes.clone(),
+            ponder: session.ponder,
+            eval_bar: session.eval_bar,
+            evaluation: (session.eval_bar && !session.is_finished()).then(|| PositionEvaluation::of(&session.game_state)),
+            teaching: session.teaching,
+            adaptive: session.adaptive,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MoveResponse {
+    pub success: bool,
+    pub game_state: AiBattleResponse,
+    pub player_move: Position,
+    /// プレイヤーの着手で返った石
+    pub flipped_positions: Vec<Position>,
+    pub ai_move: Option<Position>,
+    /// AIの応手で返った石（AIが応手しなかった場合はnull）
+    pub ai_flipped_positions: Option<Vec<Position>>,
+    /// この着手の結果、合法手がなく自動でパスしたプレイヤー
+    #[serde(with = "api_player::option")]
+    #[schema(value_type = Option<ApiPlayer>)]
+    pub passed: Option<Player>,
+    pub message: Option<String>,
+    /// AIが応手を選んだ理由（教習モードでAIが応手した場合のみ）
+    pub explanation: Option<MoveExplanation>,
+    /// AIの応手から始まる読み筋（`principal_variation` を指定し、AIが先読みして応手した場合のみ）
+    /// パスは自動で行われるため含まない
+    pub ai_principal_variation: Option<Vec<Position>>,
+}
+
+/// パスした結果
+/// 相手がAIの場合は `ai_move` にその応手が入る
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PassResponse {
+    pub success: bool,
+    pub game_state: AiBattleResponse,
+    #[serde(with = "api_player")]
+    #[schema(value_type = ApiPlayer)]
+    pub player: Player,
+    pub ai_move: Option<Position>,
+    pub message: Option<String>,
+}
+
+/// パスのリクエスト（対人戦ではプレイヤートークンが必須）
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct PassRequest {
+    #[serde(default)]
+    pub player_token: Option<Uuid>,
+}
+
+/// 対人戦の作成・参加時のレスポンス
+/// `player_token` は以降の着手で使用し、`join_token` は対戦相手に渡す
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PvpSeatResponse {
+    pub game_state: AiBattleResponse,
+    #[serde(with = "api_player")]
+    #[schema(value_type = ApiPlayer)]
+    pub color: Player,
+    pub player_token: Uuid,
+    pub join_token: Option<Uuid>,
+}
+
+/// 観戦用の共有リンク
+/// `share_token` を知っていれば、着手はできないが対局の状態とイベントを参照できる
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShareResponse {
+    pub game_id: Uuid,
+    pub share_token: Uuid,
+    /// 対局の状態を取得するURL
+    pub spectate_url: String,
+    /// 対局イベントのストリームのURL
+    pub events_url: String,
+}
+
+impl ShareResponse {
+    pub fn new(game_id: Uuid, share_token: Uuid) -> Self {
+        Self {
+            game_id,
+            share_token,
+            spectate_url: format!("/api/spectate/{}", share_token),
+            events_url: format!("/api/spectate/{}/events", share_token),
+        }
+    }
+}
+
+/// AI同士の対戦を1手進めた結果
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StepResponse {
+    pub success: bool,
+    pub game_state: AiBattleResponse,
+    #[serde(with = "api_player")]
+    #[schema(value_type = ApiPlayer)]
+    pub player: Player,
+    pub ai_move: Position,
+    pub message: Option<String>,
+}
+
+/// シード付き対局シミュレーションのリクエスト
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SimulateGameRequest {
+    pub black_difficulty: AiDifficulty,
+    pub white_difficulty: AiDifficulty,
+    pub seed: u64,
+}
+
+/// 棋譜の1手分
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TranscriptMove {
+    /// 1から始まる手数（パスは含まない）
+    pub ply: u32,
+    #[serde(with = "api_player")]
+    #[schema(value_type = ApiPlayer)]
+    pub player: Player,
+    pub position: Position,
+}
+
+/// シード付き対局シミュレーションの結果
+/// 同じ難易度・シードであれば `transcript` は常に同一になる
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SimulateGameResponse {
+    pub seed: u64,
+    pub black_difficulty: AiDifficulty,
+    pub white_difficulty: AiDifficulty,
+    pub transcript: Vec<TranscriptMove>,
+    pub final_state: AiBattleResponse,
+}
+
+/// 待った（直前の着手とAIの応手の取り消し）の結果
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UndoResponse {
+    pub success: bool,
+    pub game_state: AiBattleResponse,
+    /// 取り消した手（着手順）
+    pub undone_moves: Vec<TranscriptMove>,
+}
+
+/// ヒント取得時のクエリパラメータ
+/// 難易度を省略した場合はセッションの難易度を使用する
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HintQuery {
+    pub difficulty: Option<String>,
+    /// 評価値の高い順に返す候補手の数（1〜10、省略時は候補手を返さない）
+    pub multipv: Option<u8>,
+}
+
+/// プレイヤーへのヒント（推奨手）レスポンス
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HintResponse {
+    pub game_id: Uuid,
+    pub suggested_move: Position,
+    pub evaluation_score: Option<f64>,
+    pub depth_reached: Option<u32>,
+    pub difficulty: AiDifficulty,
+    pub thinking_time_ms: u64,
+    /// 評価値の高い順の候補手（`multipv` を指定し、AIが先読みする難易度の場合のみ）
+    pub alternatives: Option<Vec<MoveAnalysis>>,
+}
+
+/// 全合法手解析のリクエスト（省略時はセッションの難易度を使用）
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct AnalyzeRequest {
+    pub difficulty: Option<AiDifficulty>,
+    /// 現在の局面の代わりに解析する局面の1行の盤面表記（対局のルールで解析する）
+    #[serde(default)]
+    #[schema(example = "---------------------------OX------XO--------------------------- X")]
+    pub fen: Option<String>,
+}
+
+/// 手番側の全合法手の解析結果レスポンス（評価値の高い順）
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AnalyzeResponse {
+    pub game_id: Uuid,
+    #[serde(with = "api_player")]
+    #[schema(value_type = ApiPlayer)]
+    pub player: Player,
+    pub difficulty: AiDifficulty,
+    /// 解析した局面の1行の盤面表記
+    pub fen: String,
+    pub moves: Vec<MoveAnalysis>,
+    pub thinking_time_ms: u64,
+}
+
+/// セッション一覧の1ページの件数の既定値
+pub const DEFAULT_SESSIONS_PER_PAGE: usize = 20;
+
+/// セッション一覧の1ページの件数の上限
+pub const MAX_SESSIONS_PER_PAGE: usize = 100;
+
+/// セッション一覧の並び替えの基準
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionSortKey {
+    #[default]
+    CreatedAt,
+    LastMoveAt,
+}
+
+/// 並び順
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// セッション一覧のクエリパラメータ
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct SessionListQuery {
+    /// 1始まりのページ番号（既定: 1）
+    pub page: Option<usize>,
+    /// 1ページの件数（既定: 20、最大: 100）
+    pub per_page: Option<usize>,
+    /// `created_at` / `last_move_at`（既定: created_at）
+    pub sort_by: Option<String>,
+    /// `asc` / `desc`（既定: desc）
+    pub order: Option<String>,
+    /// `in_progress` / `finished`
+    pub status: Option<String>,
+    /// AIの難易度（AIが担当するいずれかの色の難易度と一致するセッション）
+    pub difficulty: Option<String>,
+}
+
+/// セッション一覧の絞り込みに使う対局状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionStatusFilter {
+    InProgress,
+    Finished,
+}
+
+/// セッション一覧の絞り込み条件（未指定の条件は全てのセッションに一致する）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionFilter {
+    pub status: Option<SessionStatusFilter>,
+    pub difficulty: Option<AiDifficulty>,
+}
+
+impl SessionFilter {
+    /// 難易度はAIが担当する色の難易度と比較するため、対人戦は難易度の指定に一致しない
+    pub fn matches(&self, session: &AiBattleSession) -> bool {
+        let status_matches = self.status.is_none_or(|status| match status {
+            SessionStatusFilter::InProgress => !session.is_finished(),
+            SessionStatusFilter::Finished => session.is_finished(),
+        });
+        let difficulty_matches = self.difficulty.is_none_or(|difficulty| {
+            [session.black, session.white]
+                .iter()
+                .any(|controller| controller.difficulty() == Some(difficulty))
+        });
+        status_matches && difficulty_matches
+    }
+}
+
+/// 解釈済みのページ指定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionPageRequest {
+    pub page: usize,
+    pub per_page: usize,
+    pub sort_by: SessionSortKey,
+    pub order: SortOrder,
+}
+
+impl Default for SessionPageRequest {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            per_page: DEFAULT_SESSIONS_PER_PAGE,
+            sort_by: SessionSortKey::default(),
+            order: SortOrder::default(),
+        }
+    }
+}
+
+impl SessionListQuery {
+    pub fn parse(&self) -> AiBattleResult<SessionPageRequest> {
+        let bad_request = |details: String| AiBattleError::BadRequest { details };
+        let defaults = SessionPageRequest::default();
+
+        let page = self.page.unwrap_or(defaults.page);
+        if page == 0 {
+            return Err(bad_request("page は1以上を指定してください".to_string()));
+        }
+        let per_page = self.per_page.unwrap_or(defaults.per_page);
+        if !(1..=MAX_SESSIONS_PER_PAGE).contains(&per_page) {
+            return Err(bad_request(format!("per_page は1〜{}で指定してください", MAX_SESSIONS_PER_PAGE)));
+        }
+        let sort_by = match self.sort_by.as_deref() {
+            None => defaults.sort_by,
+            Some("created_at") => SessionSortKey::CreatedAt,
+            Some("last_move_at") => SessionSortKey::LastMoveAt,
+            Some(other) => return Err(bad_request(format!("不明な並び替えの基準です: {}", other))),
+        };
+        let order = match self.order.as_deref().map(str::to_ascii_lowercase).as_deref() {
+            None => defaults.order,
+            Some("asc") => SortOrder::Asc,
+            Some("desc") => SortOrder::Desc,
+            Some(other) => return Err(bad_request(format!("不明な並び順です: {}", other))),
+        };
+
+        Ok(SessionPageRequest { page, per_page, sort_by, order })
+    }
+    
+    pub fn filter(&self) -> AiBattleResult<SessionFilter> {
+        let status = match self.status.as_deref().map(str::to_ascii_lowercase).as_deref() {
+            None => None,
+            Some("in_progress") => Some(SessionStatusFilter::InProgress),
+            Some("finished") => Some(SessionStatusFilter::Finished),
+            Some(other) => return Err(AiBattleError::BadRequest { details: format!("不明な対局状態です: {}", other) }),
+        };
+        let difficulty = self.difficulty
+            .as_deref()
+            .map(|value| value.parse::<AiDifficulty>().map_err(|details| AiBattleError::InvalidDifficulty { difficulty: details }))
+            .transpose()?;
+        
+        Ok(SessionFilter { status, difficulty })
+    }
+}
+
+impl SessionPageRequest {
+    /// セッションを並び替え、指定したページの分を返す
+    /// 同じ日時のセッションはIDの順に並べ、ページをまたいでも順序が変わらないようにする
+    pub fn apply(&self, mut sessions: Vec<AiBattleSession>) -> Vec<AiBattleSession> {
+        sessions.sort_by(|a, b| {
+            let ordering = match self.sort_by {
+                SessionSortKey::CreatedAt => a.created_at.cmp(&b.created_at),
+                SessionSortKey::LastMoveAt => a.last_move_at.cmp(&b.last_move_at),
+            }
+            .then_with(|| a.id.cmp(&b.id));
+            match self.order {
+                SortOrder::Asc => ordering,
+                SortOrder::Desc => ordering.reverse(),
+            }
+        });
+        sessions
+            .into_iter()
+            .skip((self.page - 1).saturating_mul(self.per_page))
+            .take(self.per_page)
+            .collect()
+    }
+
+    pub fn total_pages(&self, total_count: usize) -> usize {
+        total_count.div_ceil(self.per_page)
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionListResponse {
+    pub sessions: Vec<SessionSummary>,
+    /// 全ページのセッション数
+    pub total_count: usize,
+    pub page: usize,
+    pub per_page: usize,
+    pub total_pages: usize,
+    pub sort_by: SessionSortKey,
+    pub order: SortOrder,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionSummary {
+    pub game_id: Uuid,
+    pub kind: SessionKind,
+    pub ai_difficulty: AiDifficulty,
+    pub status: GameStatus,
+    pub created_at: DateTime<Utc>,
+    pub last_move_at: DateTime<Utc>,
+    pub move_count: u32,
+    pub pinned: bool,
+    /// セッションを作成したAPIキーの利用者名
+    pub api_key_name: Option<String>,
+    /// セッションを作成したアカウントのID
+    pub owner_id: Option<Uuid>,
+}
+
+impl SessionSummary {
+    pub fn from_session(session: &AiBattleSession) -> Self {
+        Self {
+            game_id: session.id,
+            kind: session.kind(),
+            ai_difficulty: session.ai_difficulty,
+            status: session.status,
+            created_at: session.created_at,
+            last_move_at: session.last_move_at,
+            move_count: session.game_state.move_history.len() as u32,
+            pinned: session.pinned,
+            api_key_name: session.api_key_name.clone(),
+            owner_id: session.owner_id,
+        }
+    }
+}
+
+/// `Prefer: return=representation` を指定した削除に返す受領書
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeletionReceipt {
+    pub game_id: Uuid,
+    /// 削除前に対局の記録をアーカイブしたか
+    pub archived: bool,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// セッションのピン留めを変更するリクエスト
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PinSessionRequest {
+    pub pinned: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MoveHistoryResponse {
+    pub game_id: Uuid,
+    pub moves: Vec<MoveRecord>,
+    pub total_moves: usize,
+}
+
+/// 標準の棋譜形式で書き出した対局
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TranscriptResponse {
+    pub game_id: Uuid,
+    /// 着手を列の英字と行の数字の組で続けた棋譜（パスは書かない）
+    #[schema(example = "F5D6C3D3C4")]
+    pub transcript: String,
+    pub board_size: usize,
+    pub variant: GameVariant,
+    /// 標準の初期配置以外から始めた対局の開始局面
+    pub start_position: Option<StartPosition>,
+    pub total_moves: usize,
+}
+
+impl TranscriptResponse {
+    pub fn from_session(session: &AiBattleSession) -> Self {
+        let moves = &session.game_state.move_history;
+        Self {
+            game_id: session.id,
+            transcript: transcript::to_transcript(moves.iter().map(|game_move| game_move.position)),
+            board_size: session.board_size(),
+            variant: session.variant(),
+            start_position: session.custom_start(),
+            total_moves: moves.len(),
+        }
+    }
+}
+
+/// 書き出した対局の形式の版（互換性のない変更をしたら上げる）
+pub const SESSION_EXPORT_VERSION: u32 = 1;
+
+/// 書き出した対局の設定
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SessionExportConfig {
+    pub ai_difficulty: AiDifficulty,
+    #[serde(default)]
+    #[schema(value_type = Option<u8>)]
+    pub ai_level: Option<AiLevel>,
+    #[serde(default)]
+    pub ai_config: Option<LevelParams>,
+    #[serde(default)]
+    pub personality: Option<AiPersonality>,
+    pub black: PlayerController,
+    pub white: PlayerController,
+    pub board_size: usize,
+    #[serde(default)]
+    pub variant: GameVariant,
+    #[serde(default)]
+    pub handicap: Option<Handicap>,
+    #[serde(default)]
+    pub start_position: Option<StartPosition>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// 持ち時間の設定（残り時間は書き出さず、読み込んだ対局は初期の持ち時間から始める）
+    #[serde(default)]
+    pub time_control: Option<TimeControl>,
+    #[serde(default)]
+    pub ponder: bool,
+    #[serde(default)]
+    pub eval_bar: bool,
+    #[serde(default)]
+    pub teaching: bool,
+    #[serde(default)]
+    pub adaptive: Option<AdaptiveState>,
+}
+
+/// 環境をまたいで持ち運べる対局の書き出し（`POST /api/ai-battle/import` でそのまま読み込める）
+/// 席のトークン・所有者・APIキーなど、その環境でしか意味を持たない情報は含めない
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SessionExport {
+    pub format_version: u32,
+    /// 書き出し元のセッションID（読み込むと新しいIDで作成する）
+    pub source_game_id: Uuid,
+    pub exported_at: DateTime<Utc>,
+    pub config: SessionExportConfig,
+    /// 現局面の盤面（`StartPosition` と同じ形式）。読み込み時は履歴を再生した結果と照合する
+    #[schema(example = "--------/--------/---X----/---XX---/---XO---/--------/--------/--------")]
+    pub board: String,
+    #[serde(with = "api_player")]
+    #[schema(value_type = ApiPlayer)]
+    pub current_player: Player,
+    pub status: GameStatus,
+    /// パスを含む着手順の全記録
+    pub history: Vec<MoveRecord>,
+    pub created_at: DateTime<Utc>,
+    pub last_move_at: DateTime<Utc>,
+}
+
+impl SessionExport {
+    pub fn from_session(session: &AiBattleSession, now: DateTime<Utc>) -> Self {
+        Self {
+            format_version: SESSION_EXPORT_VERSION,
+            source_game_id: session.id,
+            exported_at: now,
+            config: SessionExportConfig {
+                ai_difficulty: session.ai_difficulty,
+                ai_level: session.ai_level,
+                ai_config: session.ai_config,
+                personality: session.personality,
+                black: session.black,
+                white: session.white,
+                board_size: session.board_size(),
+                variant: session.variant(),
+                handicap: session.handicap,
+                start_position: session.start_position.clone(),
+                seed: session.seed,
+                time_control: session.clock.as_ref().map(|clock| clock.time_control),
+                ponder: session.ponder,
+                eval_bar: session.eval_bar,
+                teaching: session.teaching,
+                adaptive: session.adaptive,
+            },
+            board: setup::board_to_string(&session.game_state.board),
+            current_player: session.current_player,
+            status: session.status,
+            history: session.transcript_records(),
+            created_at: session.created_at,
+            last_move_at: session.last_move_at,
         }
     }
     
-    pub fn is_ai_turn(&self) -> bool {
-        self.current_player == Player::White && !self.ai_thinking
-    }
-    
-    pub fn is_player_turn(&self) -> bool {
-        self.current_player == Player::Black
-    }
-    
-    pub fn update_last_move(&mut self) {
-        self.last_move_at = Utc::now();
-    }
-    
-    pub fn add_move_record(&mut self, move_record: MoveRecord) {
-        self.move_history.push(move_record);
-        self.update_last_move();
-    }
-    
-    pub fn is_finished(&self) -> bool {
-        matches!(self.status, GameStatus::Finished { .. })
+    /// 開始局面から履歴をルールどおりに再生し、新しいIDのセッションを作る
+    /// 開始局面は検証・正規化済みであること。履歴・盤面・手番・終局の状態が食い違う場合はエラー
+    pub fn into_session(self, now: DateTime<Utc>) -> Result<AiBattleSession, String> {
+        if self.format_version != SESSION_EXPORT_VERSION {
+            return Err(format!(
+                "対応していない書き出し形式の版です: {}. 対応している版: {}",
+                self.format_version, SESSION_EXPORT_VERSION
+            ));
+        }
+        let config = self.config;
+        let mut session = AiBattleSession {
+            black: config.black,
+            white: config.white,
+            seed: config.seed,
+            ..AiBattleSession::new(config.ai_difficulty)
+        };
+        if !session.set_start(config.board_size, config.variant, config.handicap, config.start_position) {
+            return Err("開始局面を再現できません".to_string());
+        }
+        session.ai_level = config.ai_level;
+        session.ai_config = config.ai_config;
+        session.personality = config.personality;
+        session.ponder = config.ponder;
+        session.eval_bar = config.eval_bar;
+        session.teaching = config.teaching;
+        session.adaptive = config.adaptive;
+        session.created_at = self.created_at;
+        
+        for (index, record) in self.history.into_iter().enumerate() {
+            let ply = index + 1;
+            let board = &session.game_state.board;
+            if !ReversiRules::has_valid_moves(board, session.current_player)
+                && !ReversiRules::has_valid_moves(board, session.current_player.opposite())
+            {
+                return Err(format!("{}手目: 終局した後の着手です", ply));
+            }
+            if record.player != session.current_player {
+                return Err(format!("{}手目: {:?}の手番ではありません", ply, record.player));
+            }
+            match record.position {
+                None => {
+                    if ReversiRules::has_valid_moves(board, session.current_player) {
+                        return Err(format!("{}手目: 合法手があるためパスできません", ply));
+                    }
+                    session.add_move_record(MoveRecord { seq: 0, ..record });
+                }
+                Some(position) => {
+                    if let Some(rejection) = MoveRejection::check(&session.game_state, position, session.current_player) {
+                        return Err(format!("{}手目: {}: {}", ply, position, rejection.reason.description()));
+                    }
+                    ReversiRules::apply_move(&mut session.game_state, position).map_err(|err| err.to_string())?;
+                    let seq = session.record_placement();
+                    session.add_move_record(MoveRecord { seq, ..record });
+                }
+            }
+            session.game_state.switch_player();
+            session.current_player = session.game_state.current_player;
+        }
+        
+        let board = setup::parse_board(&self.board).map_err(|err| err.to_string())?;
+        if session.game_state.board != board {
+            return Err("盤面が履歴を再生した結果と一致しません".to_string());
+        }
+        if session.current_player != self.current_player {
+            return Err("手番が履歴を再生した結果と一致しません".to_string());
+        }
+        
+        let board = &session.game_state.board;
+        let over = !ReversiRules::has_valid_moves(board, Player::Black) && !ReversiRules::has_valid_moves(board, Player::White);
+        let winner = match self.status {
+            _ if over => {
+                let winner = ReversiRules::determine_winner(board, session.game_state.variant);
+                if self.status != (GameStatus::Finished { winner }) {
+                    return Err("終局の状態が履歴を再生した結果と一致しません".to_string());
+                }
+                Some(winner)
+            }
+            // 時間切れなど、盤面の外の理由で終局した対局はその結果を引き継ぐ
+            GameStatus::Finished { winner } => Some(winner),
+            GameStatus::InProgress => None,
+        };
+        if let Some(winner) = winner {
+            session.game_state.finish(winner);
+            session.status = GameStatus::Finished { winner };
+        }
+        session.clock = config.time_control.map(|time_control| GameClock::new(time_control, now));
+        session.update_last_move();
+        Ok(session)
     }
 }
 
-#[derive(Debug, Deserialize)]
-pub struct CreateAiBattleRequest {
-    pub difficulty: AiDifficulty,
+/// 対局の状態取得のクエリパラメータ
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct GameStateQuery {
+    /// 前回取得した `version`。指定するとそれ以降の着手と変化したマスだけを返す
+    #[serde(default)]
+    pub since: Option<u64>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct PlayerMoveRequest {
-    pub row: u8,
-    pub col: u8,
+/// 長いポーリングの待ち時間の既定値（秒）
+pub const DEFAULT_WAIT_SECONDS: u64 = 30;
+/// 長いポーリングの待ち時間の上限（秒）
+pub const MAX_WAIT_SECONDS: u64 = 60;
+
+/// 状態の変化を待つ長いポーリングのクエリパラメータ
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct WaitQuery {
+    /// 変化がなければこの秒数で応答する（1〜60、省略時は30）
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    /// 手元の状態の `version`。現在の版と異なればすぐに応答する
+    #[serde(default)]
+    pub version: Option<u64>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ChangeDifficultyRequest {
-    pub difficulty: AiDifficulty,
+/// 長いポーリングの結果
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WaitResponse {
+    /// 待っているあいだ（または指定した版から）状態が変化した場合はtrue、時間切れの場合はfalse
+    pub changed: bool,
+    pub game_state: AiBattleResponse,
 }
 
-#[derive(Debug, Serialize)]
-pub struct AiBattleResponse {
+/// 差分で変化したマスとその後の状態
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct CellChange {
+    pub position: Position,
+    /// 変化後の石の色（空きマスになった場合はnull）
+    #[serde(with = "api_player::option")]
+    #[schema(value_type = Option<ApiPlayer>)]
+    pub cell: Option<Player>,
+}
+
+/// `since` で指定した版から現在までの差分
+/// 盤面の代わりに、その後の着手と変化したマスだけを返す
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AiBattleDelta {
     pub game_id: Uuid,
-    pub board: Vec<Vec<Option<Player>>>,
+    /// 差分の起点の版
+    pub since: u64,
+    /// 現在の版
+    pub version: u64,
+    /// 起点より後の着手とパス（着手順）
+    pub moves: Vec<MoveRecord>,
+    /// 起点の局面から変化したマス
+    pub changes: Vec<CellChange>,
+    #[serde(with = "api_player")]
+    #[schema(value_type = ApiPlayer)]
     pub current_player: Player,
     pub black_count: u8,
     pub white_count: u8,
-    pub ai_difficulty: AiDifficulty,
     pub ai_thinking: bool,
     pub status: GameStatus,
     pub valid_moves: Vec<Position>,
+    pub must_pass: bool,
     pub move_count: u32,
+    pub clock: Option<ClockView>,
 }
 
-impl AiBattleResponse {
-    pub fn from_session(session: &AiBattleSession) -> Self {
-        let mut board = vec![vec![None; 8]; 8];
-        for row in 0..8 {
-            for col in 0..8 {
-                if let Some(position) = Position::new(row, col) {
-                    if let Some(cell) = session.game_state.board.get_cell(position) {
-                        board[row][col] = match cell {
-                            crate::game::Cell::Empty => None,
-                            crate::game::Cell::Black => Some(Player::Black),
-                            crate::game::Cell::White => Some(Player::White),
-                        };
-                    }
-                }
-            }
+impl AiBattleDelta {
+    /// `since` の版からの差分を作る
+    /// 待ったで巻き戻した履歴をまたぐ版や、まだない版からは作れないためNoneを返す
+    /// 巻き戻した時点の版は巻き戻す前と後で同じ値になるため、その版からも作らない
+    pub fn from_session(session: &AiBattleSession, since: u64) -> Option<Self> {
+        let plies = session.game_state.move_history.len();
+        let rewound = session.rewound_at > 0 && since <= session.rewound_at;
+        if rewound || since > session.move_seq || session.ply_seqs.len() != plies {
+            return None;
         }
         
-        let valid_moves = if session.is_finished() {
-            Vec::new()
-        } else {
-            crate::game::ReversiRules::get_valid_moves(&session.game_state.board, session.current_player)
-        };
-        
-        let (black_count, white_count) = session.game_state.get_score();
-        
-        Self {
-            game_id: session.id,
-            board,
-            current_player: session.current_player,
-            black_count,
-            white_count,
-            ai_difficulty: session.ai_difficulty,
-            ai_thinking: session.ai_thinking,
-            status: session.status,
-            valid_moves,
-            move_count: session.game_state.move_history.len() as u32,
+        // 起点より後の着手を取り消して、起点の盤面を復元する
+        let kept = session.ply_seqs.partition_point(|&seq| seq <= since);
+        let mut base = session.game_state.clone();
+        while base.move_history.len() > kept {
+            base.undo_last_move()?;
         }
+        let full = AiBattleResponse::from_session(session);
+        let base = encoding::encode_board(&base.board);
+        let changes = full.board
+            .iter()
+            .zip(&base)
+            .enumerate()
+            .flat_map(|(row, (cells, before))| {
+                cells.iter().zip(before).enumerate().filter(|(_, (cell, before))| cell != before).map(
+                    move |(col, (&cell, _))| CellChange { position: Position { row, col }, cell },
+                )
+            })
+            .collect();
+        let moves = session.transcript_records().into_iter().filter(|record| record.seq > since).collect();
+        
+        Some(Self {
+            game_id: full.game_id,
+            since,
+            version: full.version,
+            moves,
+            changes,
+            current_player: full.current_player,
+            black_count: full.black_count,
+            white_count: full.white_count,
+            ai_thinking: full.ai_thinking,
+            status: full.status,
+            valid_moves: full.valid_moves,
+            must_pass: full.must_pass,
+            move_count: full.move_count,
+            clock: full.clock,
+        })
     }
 }
 
-#[derive(Debug, Serialize)]
-pub struct MoveResponse {
-    pub success: bool,
-    pub game_state: AiBattleResponse,
-    pub player_move: Position,
-    pub ai_move: Option<Position>,
-    pub message: Option<String>,
+/// 対局の状態（`since` を指定して差分を作れた場合は差分、それ以外は全体）
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum GameStateView {
+    Full(Box<AiBattleResponse>),
+    Delta(AiBattleDelta),
 }
 
-#[derive(Debug, Serialize)]
-pub struct SessionListResponse {
-    pub sessions: Vec<SessionSummary>,
-    pub total_count: usize,
+/// 対局の再生のクエリパラメータ
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct ReplayQuery {
+    /// この手数（打たれた石の数、0は開始局面）の後の局面だけを返す。省略時は全ての局面を返す
+    #[serde(default, rename = "move")]
+    #[param(rename = "move")]
+    pub move_number: Option<usize>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct SessionSummary {
-    pub game_id: Uuid,
-    pub ai_difficulty: AiDifficulty,
-    pub status: GameStatus,
-    pub created_at: DateTime<Utc>,
-    pub last_move_at: DateTime<Utc>,
-    pub move_count: u32,
+/// 再生したある手の後の局面
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplaySnapshot {
+    /// 打たれた石の数（開始局面は0）
+    pub move_number: usize,
+    #[serde(with = "api_player::board")]
+    #[schema(value_type = Vec<Vec<Option<ApiPlayer>>>)]
+    pub board: encoding::CanonicalBoard,
+    /// この局面の手番
+    #[serde(with = "api_player")]
+    #[schema(value_type = ApiPlayer)]
+    pub to_move: Player,
+    pub black_count: u8,
+    pub white_count: u8,
+    /// この局面に至った着手（開始局面ではnull）
+    pub last_move: Option<Position>,
+    /// この局面に至った着手で返った石
+    pub flipped: Vec<Position>,
+    /// 1行の盤面表記
+    pub fen: String,
 }
 
-impl SessionSummary {
-    pub fn from_session(session: &AiBattleSession) -> Self {
+impl ReplaySnapshot {
+    pub fn from_frame(frame: &ReplayFrame) -> Self {
+        let (black_count, white_count) = frame.board.count_pieces();
         Self {
-            game_id: session.id,
-            ai_difficulty: session.ai_difficulty,
-            status: session.status,
-            created_at: session.created_at,
-            last_move_at: session.last_move_at,
-            move_count: session.game_state.move_history.len() as u32,
+            move_number: frame.ply,
+            board: encoding::encode_board(&frame.board),
+            to_move: frame.to_move,
+            black_count,
+            white_count,
+            last_move: frame.last_move.as_ref().map(|game_move| game_move.position),
+            flipped: frame.last_move.as_ref().map(|game_move| game_move.flipped.clone()).unwrap_or_default(),
+            fen: frame.board.to_fen(frame.to_move),
         }
     }
 }
 
-#[derive(Debug, Serialize)]
-pub struct MoveHistoryResponse {
+/// 着手履歴から再構築した局面
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayResponse {
     pub game_id: Uuid,
-    pub moves: Vec<MoveRecord>,
     pub total_moves: usize,
+    /// 手数の順の局面（`move` を指定した場合はその1局面のみ）
+    pub snapshots: Vec<ReplaySnapshot>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct DifficultyInfo {
     pub id: AiDifficulty,
     pub name: &'static str,
     pub description: &'static str,
+    /// この難易度を別名とする数値レベル
+    #[schema(value_type = u8)]
+    pub level: AiLevel,
 }
 
 impl From<AiDifficulty> for DifficultyInfo {
@@ -295,13 +2073,58 @@ impl From<AiDifficulty> for DifficultyInfo {
             id: difficulty,
             name: difficulty.name(),
             description: difficulty.description(),
+            level: difficulty.level(),
+        }
+    }
+}
+
+/// 数値レベル1つ分の説明
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LevelInfo {
+    #[schema(value_type = u8)]
+    pub level: AiLevel,
+    /// レベルが属する難易度（統計・レーティングの区分）
+    pub difficulty: AiDifficulty,
+    pub description: &'static str,
+    pub params: LevelParams,
+}
+
+impl From<AiLevel> for LevelInfo {
+    fn from(level: AiLevel) -> Self {
+        Self {
+            level,
+            difficulty: level.into(),
+            description: level.description(),
+            params: level.params(),
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+/// パーソナリティ1つ分の説明
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PersonalityInfo {
+    pub id: AiPersonality,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+impl From<AiPersonality> for PersonalityInfo {
+    fn from(personality: AiPersonality) -> Self {
+        Self {
+            id: personality,
+            name: personality.name(),
+            description: personality.description(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct DifficultiesResponse {
     pub difficulties: Vec<DifficultyInfo>,
+    /// 数値レベル（1〜10）の一覧
+    pub levels: Vec<LevelInfo>,
+    /// 対局の作成時に選べるパーソナリティの一覧
+    pub personalities: Vec<PersonalityInfo>,
 }
 
 impl DifficultiesResponse {
@@ -311,16 +2134,24 @@ impl DifficultiesResponse {
                 .into_iter()
                 .map(DifficultyInfo::from)
                 .collect(),
+            levels: AiLevel::all().map(LevelInfo::from).collect(),
+            personalities: AiPersonality::all().into_iter().map(PersonalityInfo::from).collect(),
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ErrorResponse {
     pub error: String,
     pub message: String,
     pub timestamp: DateTime<Utc>,
     pub error_code: Option<String>,
+    /// 着手できない理由の分類（不正な着手の場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason_code: Option<InvalidMoveReason>,
+    /// 指定したマスに近い順の合法手（不正な着手の場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nearest_valid_moves: Option<Vec<Position>>,
 }
 
 impl ErrorResponse {
@@ -330,6 +2161,8 @@ impl ErrorResponse {
             message: message.into(),
             timestamp: Utc::now(),
             error_code: None,
+            reason_code: None,
+            nearest_valid_moves: None,
         }
     }
     
@@ -339,6 +2172,37 @@ impl ErrorResponse {
             message: message.into(),
             timestamp: Utc::now(),
             error_code: Some(code.into()),
+            reason_code: None,
+            nearest_valid_moves: None,
+        }
+    }
+}
+
+/// エラー応答に添える合法手の候補の数
+pub const NEAREST_VALID_MOVES: usize = 3;
+
+/// 不正な着手の理由と、代わりに打てる合法手
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveRejection {
+    pub reason: InvalidMoveReason,
+    pub nearest_valid_moves: Vec<Position>,
+}
+
+impl MoveRejection {
+    /// `player` が `position` に打てない理由を調べる（打てる場合はNone）
+    pub fn check(game_state: &GameState, position: Position, player: Player) -> Option<Self> {
+        let reason = ReversiRules::invalid_move_reason(game_state, position, player)?;
+        Some(Self {
+            reason,
+            nearest_valid_moves: ReversiRules::nearest_valid_moves(&game_state.board, position, player, NEAREST_VALID_MOVES),
+        })
+    }
+    
+    /// `position` への着手を拒否するエラー
+    pub fn into_error(self, position: Position) -> AiBattleError {
+        AiBattleError::InvalidMove {
+            reason: format!("{}: {}", position, self.reason.description()),
+            rejection: Some(self),
         }
     }
 }
@@ -349,11 +2213,44 @@ pub enum AiBattleError {
     GameNotFound { game_id: Uuid },
     
     #[error("無効な着手です: {reason}")]
-    InvalidMove { reason: String },
+    InvalidMove { reason: String, rejection: Option<MoveRejection> },
     
     #[error("プレイヤーの手番ではありません")]
     NotPlayerTurn,
     
+    #[error("AIの手番ではありません")]
+    NotAiTurn,
+    
+    #[error("プレイヤートークンが無効です")]
+    InvalidPlayerToken,
+    
+    #[error("参加トークンが無効です")]
+    InvalidJoinToken,
+    
+    #[error("対戦相手は既に参加しています")]
+    SeatAlreadyTaken,
+    
+    #[error("待ったできません: {reason}")]
+    CannotUndo { reason: String },
+    
+    #[error("持ち時間が切れています")]
+    TimeExpired,
+    
+    #[error("この対局では別の着手を処理中です")]
+    MoveInProgress,
+    
+    #[error("対局の募集が見つかりません: {challenge_id}")]
+    ChallengeNotFound { challenge_id: Uuid },
+    
+    #[error("自分の募集には参加できません")]
+    OwnChallenge,
+    
+    #[error("この募集を取り消す権限がありません")]
+    NotChallengeOwner,
+    
+    #[error("この招待は別のプレイヤー宛てです")]
+    NotChallengeRecipient,
+    
     #[error("無効なAI難易度です: {difficulty}")]
     InvalidDifficulty { difficulty: String },
     
@@ -363,6 +2260,9 @@ pub enum AiBattleError {
     #[error("AI思考エラー: {details}")]
     AiThinkingError { details: String },
     
+    #[error("AIの計算待ちが混み合っています。{retry_after_secs}秒後に再試行してください")]
+    AiOverloaded { retry_after_secs: u64 },
+    
     #[error("ゲームは既に終了しています")]
     GameAlreadyFinished,
     
@@ -372,6 +2272,42 @@ pub enum AiBattleError {
     #[error("サーバー内部エラー: {details}")]
     InternalError { details: String },
     
+    #[error("局面が見つかりません: {hash}")]
+    PositionNotFound { hash: String },
+    
+    #[error("大会が見つかりません: {tournament_id}")]
+    TournamentNotFound { tournament_id: Uuid },
+    
+    #[error("出題できる問題がまだありません")]
+    NoPuzzleAvailable,
+    
+    #[error("観戦リンクが無効です（対局が削除されたか、トークンが誤っています）")]
+    ShareLinkNotFound,
+    
+    #[error("処理が制限時間 ({budget_ms}ms) 内に完了しませんでした")]
+    RequestTimeout { budget_ms: u64 },
+    
+    #[error("ユーザー名は既に使われています: {username}")]
+    UsernameTaken { username: String },
+    
+    #[error("ユーザー名またはパスワードが正しくありません")]
+    InvalidCredentials,
+    
+    #[error("アクセストークンが無効、または有効期限が切れています")]
+    InvalidAccessToken,
+    
+    #[error("ログインが必要です")]
+    LoginRequired,
+    
+    #[error("このセッションを操作する権限がありません")]
+    NotSessionOwner,
+    
+    #[error("{reason}")]
+    InvalidPosition { reason: String },
+    
+    #[error("リクエストボディが不正です: {details}")]
+    InvalidRequestBody { status: StatusCode, details: String },
+    
     #[error("ゲームエラー: {0}")]
     GameError(#[from] crate::error::GameError),
     
@@ -380,17 +2316,49 @@ pub enum AiBattleError {
 }
 
 impl AiBattleError {
+    /// AIサービスのエラーを変換する（計算待ちが満杯の場合は再試行を促す503にする）
+    pub fn from_ai_service(error: crate::error::AIError) -> Self {
+        match error {
+            crate::error::AIError::Overloaded { retry_after_secs } => AiBattleError::AiOverloaded { retry_after_secs },
+            error => AiBattleError::AiThinkingError { details: format!("AI service error: {}", error) },
+        }
+    }
+    
     pub fn error_code(&self) -> &'static str {
         match self {
             AiBattleError::GameNotFound { .. } => "GAME_NOT_FOUND",
             AiBattleError::InvalidMove { .. } => "INVALID_MOVE",
             AiBattleError::NotPlayerTurn => "NOT_PLAYER_TURN",
+            AiBattleError::NotAiTurn => "NOT_AI_TURN",
+            AiBattleError::InvalidPlayerToken => "INVALID_PLAYER_TOKEN",
+            AiBattleError::InvalidJoinToken => "INVALID_JOIN_TOKEN",
+            AiBattleError::SeatAlreadyTaken => "SEAT_ALREADY_TAKEN",
+            AiBattleError::CannotUndo { .. } => "CANNOT_UNDO",
+            AiBattleError::TimeExpired => "TIME_EXPIRED",
+            AiBattleError::MoveInProgress => "MOVE_IN_PROGRESS",
+            AiBattleError::ChallengeNotFound { .. } => "CHALLENGE_NOT_FOUND",
+            AiBattleError::OwnChallenge => "OWN_CHALLENGE",
+            AiBattleError::NotChallengeOwner => "NOT_CHALLENGE_OWNER",
+            AiBattleError::NotChallengeRecipient => "NOT_CHALLENGE_RECIPIENT",
             AiBattleError::InvalidDifficulty { .. } => "INVALID_DIFFICULTY",
             AiBattleError::MaxSessionsReached { .. } => "MAX_SESSIONS_REACHED",
             AiBattleError::AiThinkingError { .. } => "AI_THINKING_ERROR",
+            AiBattleError::AiOverloaded { .. } => "AI_OVERLOADED",
             AiBattleError::GameAlreadyFinished => "GAME_ALREADY_FINISHED",
             AiBattleError::BadRequest { .. } => "BAD_REQUEST",
             AiBattleError::InternalError { .. } => "INTERNAL_ERROR",
+            AiBattleError::PositionNotFound { .. } => "POSITION_NOT_FOUND",
+            AiBattleError::TournamentNotFound { .. } => "TOURNAMENT_NOT_FOUND",
+            AiBattleError::NoPuzzleAvailable => "NO_PUZZLE_AVAILABLE",
+            AiBattleError::ShareLinkNotFound => "SHARE_LINK_NOT_FOUND",
+            AiBattleError::RequestTimeout { .. } => "REQUEST_TIMEOUT",
+            AiBattleError::UsernameTaken { .. } => "USERNAME_TAKEN",
+            AiBattleError::InvalidCredentials => "INVALID_CREDENTIALS",
+            AiBattleError::InvalidAccessToken => "INVALID_ACCESS_TOKEN",
+            AiBattleError::LoginRequired => "LOGIN_REQUIRED",
+            AiBattleError::NotSessionOwner => "NOT_SESSION_OWNER",
+            AiBattleError::InvalidPosition { .. } => "INVALID_POSITION",
+            AiBattleError::InvalidRequestBody { .. } => "INVALID_REQUEST_BODY",
             AiBattleError::GameError(_) => "GAME_ERROR",
             AiBattleError::AIError(_) => "AI_ERROR",
         }
@@ -401,13 +2369,42 @@ impl AiBattleError {
             AiBattleError::GameNotFound { .. } => StatusCode::NOT_FOUND,
             AiBattleError::InvalidMove { .. } => StatusCode::BAD_REQUEST,
             AiBattleError::NotPlayerTurn => StatusCode::FORBIDDEN,
+            AiBattleError::NotAiTurn => StatusCode::FORBIDDEN,
+            AiBattleError::InvalidPlayerToken => StatusCode::FORBIDDEN,
+            AiBattleError::InvalidJoinToken => StatusCode::FORBIDDEN,
+            AiBattleError::SeatAlreadyTaken => StatusCode::CONFLICT,
+            AiBattleError::CannotUndo { .. } => StatusCode::CONFLICT,
+            AiBattleError::TimeExpired => StatusCode::CONFLICT,
+            AiBattleError::MoveInProgress => StatusCode::CONFLICT,
+            AiBattleError::ChallengeNotFound { .. } => StatusCode::NOT_FOUND,
+            AiBattleError::OwnChallenge => StatusCode::CONFLICT,
+            AiBattleError::NotChallengeOwner => StatusCode::FORBIDDEN,
+            AiBattleError::NotChallengeRecipient => StatusCode::FORBIDDEN,
             AiBattleError::InvalidDifficulty { .. } => StatusCode::BAD_REQUEST,
             AiBattleError::MaxSessionsReached { .. } => StatusCode::TOO_MANY_REQUESTS,
             AiBattleError::AiThinkingError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            AiBattleError::AiOverloaded { .. } => StatusCode::SERVICE_UNAVAILABLE,
             AiBattleError::GameAlreadyFinished => StatusCode::BAD_REQUEST,
             AiBattleError::BadRequest { .. } => StatusCode::BAD_REQUEST,
             AiBattleError::InternalError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
-            AiBattleError::GameError(_) => StatusCode::BAD_REQUEST,
+            AiBattleError::PositionNotFound { .. } => StatusCode::NOT_FOUND,
+            AiBattleError::TournamentNotFound { .. } => StatusCode::NOT_FOUND,
+            AiBattleError::NoPuzzleAvailable => StatusCode::NOT_FOUND,
+            AiBattleError::ShareLinkNotFound => StatusCode::NOT_FOUND,
+            AiBattleError::RequestTimeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+            AiBattleError::UsernameTaken { .. } => StatusCode::CONFLICT,
+            AiBattleError::InvalidCredentials => StatusCode::UNAUTHORIZED,
+            AiBattleError::InvalidAccessToken => StatusCode::UNAUTHORIZED,
+            AiBattleError::LoginRequired => StatusCode::UNAUTHORIZED,
+            AiBattleError::NotSessionOwner => StatusCode::FORBIDDEN,
+            AiBattleError::InvalidPosition { .. } => StatusCode::BAD_REQUEST,
+            AiBattleError::InvalidRequestBody { status, .. } => *status,
+            AiBattleError::GameError(err) => match err {
+                GameError::GameNotFound { .. } => StatusCode::NOT_FOUND,
+                GameError::SessionLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
+                GameError::AIError { .. } | GameError::PersistenceError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+                GameError::InvalidMove { .. } | GameError::GameFinished => StatusCode::BAD_REQUEST,
+            },
             AiBattleError::AIError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -416,16 +2413,41 @@ impl AiBattleError {
 impl From<AiBattleError> for (StatusCode, Json<ErrorResponse>) {
     fn from(err: AiBattleError) -> Self {
         let status_code = err.status_code();
-        let error_response = ErrorResponse::with_code(
+        let mut error_response = ErrorResponse::with_code(
             err.error_code(),
             err.to_string(),
             err.error_code(),
         );
+        if let AiBattleError::InvalidMove { rejection: Some(rejection), .. } = err {
+            error_response.reason_code = Some(rejection.reason);
+            error_response.nearest_valid_moves = Some(rejection.nearest_valid_moves);
+        }
         
         (status_code, Json(error_response))
     }
 }
 
+/// ハンドラーから `?` でエラーを返せるよう、共通のエラー形式（`ErrorResponse`）で応答する
+impl IntoResponse for AiBattleError {
+    fn into_response(self) -> Response {
+        if let AiBattleError::AiOverloaded { retry_after_secs } = self {
+            let retry_after = [(header::RETRY_AFTER, retry_after_secs.to_string())];
+            return (retry_after, <(StatusCode, Json<ErrorResponse>)>::from(self)).into_response();
+        }
+        <(StatusCode, Json<ErrorResponse>)>::from(self).into_response()
+    }
+}
+
+/// JSONボディの解析エラー（構文エラー・型の不一致・Content-Typeの不足）もAPI共通のエラー形式で返す
+impl From<JsonRejection> for AiBattleError {
+    fn from(rejection: JsonRejection) -> Self {
+        AiBattleError::InvalidRequestBody {
+            status: rejection.status(),
+            details: rejection.body_text(),
+        }
+    }
+}
+
 pub type AiBattleResult<T> = Result<T, AiBattleError>;
 
 #[cfg(test)]
@@ -433,62 +2455,94 @@ mod tests {
     use super::*;
     
     #[test]
-    fn test_ai_difficulty_all() {
-        let all_difficulties = AiDifficulty::all();
-        assert_eq!(all_difficulties.len(), 3);
-        assert!(all_difficulties.contains(&AiDifficulty::Easy));
-        assert!(all_difficulties.contains(&AiDifficulty::Medium));
-        assert!(all_difficulties.contains(&AiDifficulty::Hard));
-    }
-    
-    #[test]
-    fn test_ai_difficulty_description() {
-        assert!(AiDifficulty::Easy.description().contains("初級"));
-        assert!(AiDifficulty::Medium.description().contains("中級"));
-        assert!(AiDifficulty::Hard.description().contains("上級"));
-    }
-    
-    #[test]
-    fn test_ai_difficulty_name() {
-        assert_eq!(AiDifficulty::Easy.name(), "Easy");
-        assert_eq!(AiDifficulty::Medium.name(), "Medium");
-        assert_eq!(AiDifficulty::Hard.name(), "Hard");
+    fn test_ai_difficulty_deserialize_case_insensitive() {
+        let request: CreateAiBattleRequest = serde_json::from_str(r#"{"difficulty": "easy"}"#).unwrap();
+        assert_eq!(request.difficulty, Some(AiDifficulty::Easy));
+        let request: CreateAiBattleRequest = serde_json::from_str(r#"{"level": 9}"#).unwrap();
+        assert_eq!((request.difficulty, request.level.map(AiLevel::value)), (None, Some(9)));
+        assert!(serde_json::from_str::<CreateAiBattleRequest>(r#"{"level": 0}"#).is_err());
+
+        let request: ChangeDifficultyRequest = serde_json::from_str(r#"{"difficulty": "ADVANCED"}"#).unwrap();
+        assert_eq!(request.difficulty, AiDifficulty::Hard);
+
+        assert!(serde_json::from_str::<CreateAiBattleRequest>(r#"{"difficulty": "impossible"}"#).is_err());
+        
+        let request: CreateAiBattleRequest = serde_json::from_str(r#"{"difficulty": "easy"}"#).unwrap();
+        assert_eq!(request.player_color, Player::Black);
+        let request: CreateAiBattleRequest =
+            serde_json::from_str(r#"{"difficulty": "easy", "player_color": "WHITE"}"#).unwrap();
+        assert_eq!(request.player_color, Player::White);
+        assert_eq!(serde_json::to_string(&AiDifficulty::Medium).unwrap(), r#""Medium""#);
     }
-    
+
     #[test]
-    fn test_ai_difficulty_from_str() {
-        assert_eq!("easy".parse::<AiDifficulty>().unwrap(), AiDifficulty::Easy);
-        assert_eq!("MEDIUM".parse::<AiDifficulty>().unwrap(), AiDifficulty::Medium);
-        assert_eq!("Hard".parse::<AiDifficulty>().unwrap(), AiDifficulty::Hard);
-        assert!("invalid".parse::<AiDifficulty>().is_err());
+    fn test_start_position_from_fen() {
+        let fen = "---------------------------OX------XXX-------------------------- O";
+        let request: CreateAiBattleRequest =
+            serde_json::from_value(serde_json::json!({"difficulty": "easy", "start_fen": fen})).unwrap();
+        let position = request.ai_setup().unwrap().start_position.unwrap();
+        assert_eq!(position.to_move, Player::White);
+        assert_eq!(position.board, "--------/--------/--------/---OX---/---XXX--/--------/--------/--------");
+
+        let both: CreateAiBattleRequest = serde_json::from_value(serde_json::json!({
+            "difficulty": "easy",
+            "start_fen": fen,
+            "start_position": {"board": position.board, "to_move": "white"},
+        }))
+        .unwrap();
+        assert!(matches!(both.ai_setup(), Err(AiBattleError::BadRequest { .. })));
+        let invalid: CreateAiBattleRequest =
+            serde_json::from_value(serde_json::json!({"difficulty": "easy", "start_fen": "XO"})).unwrap();
+        assert!(matches!(invalid.ai_setup(), Err(AiBattleError::BadRequest { .. })));
     }
     
     #[test]
-    fn test_ai_difficulty_conversion_to_legacy() {
-        assert_eq!(LegacyDifficulty::from(AiDifficulty::Easy), LegacyDifficulty::Beginner);
-        assert_eq!(LegacyDifficulty::from(AiDifficulty::Medium), LegacyDifficulty::Intermediate);
-        assert_eq!(LegacyDifficulty::from(AiDifficulty::Hard), LegacyDifficulty::Advanced);
+    fn test_game_status_deserialize_case_insensitive() {
+        assert_eq!(serde_json::from_str::<GameStatus>(r#""in_progress""#).unwrap(), GameStatus::InProgress);
+        assert_eq!(
+            serde_json::from_str::<GameStatus>(r#"{"finished": {"winner": "white"}}"#).unwrap(),
+            GameStatus::Finished { winner: Some(Player::White) }
+        );
+
+        let status = GameStatus::Finished { winner: None };
+        let json = serde_json::to_string(&status).unwrap();
+        assert_eq!(serde_json::from_str::<GameStatus>(&json).unwrap(), status);
+        assert!(serde_json::from_str::<GameStatus>(r#""paused""#).is_err());
     }
     
     #[test]
-    fn test_ai_difficulty_conversion_from_legacy() {
-        assert_eq!(AiDifficulty::from(LegacyDifficulty::Beginner), AiDifficulty::Easy);
-        assert_eq!(AiDifficulty::from(LegacyDifficulty::Intermediate), AiDifficulty::Medium);
-        assert_eq!(AiDifficulty::from(LegacyDifficulty::Advanced), AiDifficulty::Hard);
+    fn test_validate_position_valid() {
+        assert!(validate_position(0, 0, 8).is_ok());
+        assert!(validate_position(7, 7, 8).is_ok());
+        assert!(validate_position(3, 4, 8).is_ok());
+        assert!(validate_position(9, 9, 10).is_ok());
     }
     
     #[test]
-    fn test_validate_position_valid() {
-        assert!(validate_position(0, 0).is_ok());
-        assert!(validate_position(7, 7).is_ok());
-        assert!(validate_position(3, 4).is_ok());
+    fn test_validate_position_invalid() {
+        assert!(validate_position(8, 0, 8).is_err());
+        assert!(validate_position(0, 8, 8).is_err());
+        assert!(validate_position(10, 10, 8).is_err());
+        assert!(validate_position(6, 0, 6).is_err());
+        assert!(validate_board_size(10).is_ok());
+        assert!(validate_board_size(7).is_err());
     }
     
     #[test]
-    fn test_validate_position_invalid() {
-        assert!(validate_position(8, 0).is_err());
-        assert!(validate_position(0, 8).is_err());
-        assert!(validate_position(10, 10).is_err());
+    fn test_player_move_request_position() {
+        let request = |body: serde_json::Value| serde_json::from_value::<PlayerMoveRequest>(body).unwrap();
+        
+        let d3 = Position::new(2, 3).unwrap();
+        assert_eq!(request(serde_json::json!({"row": 2, "col": 3})).position(8).unwrap(), d3);
+        assert_eq!(request(serde_json::json!({"square": "D3"})).position(8).unwrap(), d3);
+        assert_eq!(request(serde_json::json!({"square": "j10"})).position(10).unwrap(), Position { row: 9, col: 9 });
+        assert!(matches!(request(serde_json::json!({"square": "j10"})).position(8), Err(AiBattleError::InvalidPosition { .. })));
+        assert!(matches!(request(serde_json::json!({"square": "z9"})).position(8), Err(AiBattleError::InvalidPosition { .. })));
+        assert!(matches!(request(serde_json::json!({"row": 2})).position(8), Err(AiBattleError::BadRequest { .. })));
+        assert!(matches!(
+            request(serde_json::json!({"row": 2, "col": 3, "square": "d3"})).position(8),
+            Err(AiBattleError::BadRequest { .. })
+        ));
     }
     
     #[test]
@@ -497,8 +2551,21 @@ mod tests {
         let move_record = MoveRecord::new(Player::Black, position, Some(1500));
         
         assert_eq!(move_record.player, Player::Black);
-        assert_eq!(move_record.position, position);
+        assert_eq!(move_record.position, Some(position));
+        assert!(!move_record.is_pass());
+        assert!(MoveRecord::pass(Player::White).is_pass());
         assert_eq!(move_record.thinking_time_ms, Some(1500));
+        assert_eq!(move_record.notation, "e4");
+        assert_eq!(MoveRecord::pass(Player::White).notation, "pass");
+        
+        // 表記のない古い記録は座標から表記を補う
+        let legacy = serde_json::json!({
+            "player": "black",
+            "position": {"row": 2, "col": 3},
+            "timestamp": "2024-01-01T00:00:00Z",
+            "thinking_time_ms": null,
+        });
+        assert_eq!(serde_json::from_value::<MoveRecord>(legacy).unwrap().notation, "d3");
     }
     
     #[test]
@@ -514,6 +2581,147 @@ mod tests {
         assert_eq!(session.move_history.len(), 0);
     }
     
+    #[test]
+    fn test_ai_vs_ai_session_controllers() {
+        let mut session = AiBattleSession::new_ai_vs_ai(AiDifficulty::Easy, AiDifficulty::Hard);
+        
+        assert!(session.is_ai_vs_ai());
+        assert!(session.is_ai_turn());
+        assert!(!session.is_player_turn());
+        assert_eq!(session.controller(Player::Black).difficulty(), Some(AiDifficulty::Easy));
+        assert_eq!(session.controller(Player::White).difficulty(), Some(AiDifficulty::Hard));
+        
+        session.set_ai_difficulty(AiDifficulty::Medium);
+        assert_eq!(session.black, PlayerController::Ai { difficulty: AiDifficulty::Medium });
+        assert_eq!(session.white, PlayerController::Ai { difficulty: AiDifficulty::Medium });
+        
+        let mut human_session = AiBattleSession::new(AiDifficulty::Easy);
+        human_session.set_ai_difficulty(AiDifficulty::Hard);
+        assert_eq!(human_session.black, PlayerController::Human);
+        assert_eq!(human_session.white, PlayerController::Ai { difficulty: AiDifficulty::Hard });
+    }
+    
+    #[test]
+    fn test_session_with_white_player() {
+        let session = AiBattleSession::new_with_color(AiDifficulty::Easy, Player::White);
+        assert_eq!(session.player_color(), Some(Player::White));
+        assert_eq!(session.kind(), SessionKind::HumanVsAi);
+        assert!(session.is_ai_turn());
+        assert!(!session.is_player_turn());
+        
+        let session = AiBattleSession::new(AiDifficulty::Easy);
+        assert_eq!(session.player_color(), Some(Player::Black));
+        assert!(session.is_player_turn());
+        assert_eq!(AiBattleSession::new_pvp().player_color(), None);
+    }
+    
+    #[test]
+    fn test_undo_last_turn() {
+        use crate::game::ReversiRules;
+        
+        let mut session = AiBattleSession::new(AiDifficulty::Easy);
+        assert!(matches!(session.undo_last_turn(), Err(AiBattleError::CannotUndo { .. })));
+        
+        for _ in 0..2 {
+            let player = session.game_state.current_player;
+            let position = ReversiRules::get_valid_moves(&session.game_state.board, player)[0];
+            ReversiRules::apply_move(&mut session.game_state, position).unwrap();
+            if session.controller(player).is_ai() {
+                session.add_move_record(MoveRecord::new(player, position, Some(0)));
+            }
+            session.game_state.switch_player();
+        }
+        session.current_player = session.game_state.current_player;
+        
+        let undone = session.undo_last_turn().unwrap();
+        assert_eq!(undone.len(), 2);
+        assert_eq!(undone[0].ply, 1);
+        assert_eq!(undone[0].player, Player::Black);
+        assert_eq!(session.current_player, Player::Black);
+        assert!(session.move_history.is_empty());
+        assert_eq!(session.game_state.board, crate::game::Board::new());
+        
+        let mut pvp = AiBattleSession::new_pvp();
+        assert!(matches!(pvp.undo_last_turn(), Err(AiBattleError::CannotUndo { .. })));
+    }
+    
+    #[test]
+    fn test_delta_since_version() {
+        use crate::game::ReversiRules;
+        
+        let mut session = AiBattleSession::new(AiDifficulty::Easy);
+        let mut played = Vec::new();
+        for _ in 0..2 {
+            let player = session.game_state.current_player;
+            let position = ReversiRules::get_valid_moves(&session.game_state.board, player)[0];
+            ReversiRules::apply_move(&mut session.game_state, position).unwrap();
+            let seq = session.record_placement();
+            session.add_move_record(MoveRecord { seq, ..MoveRecord::new(player, position, Some(0)) });
+            session.advance_turn();
+            played.push(position);
+        }
+        
+        let delta = AiBattleDelta::from_session(&session, 1).unwrap();
+        assert_eq!(delta.version, 2);
+        assert_eq!(delta.moves.iter().map(|record| record.position).collect::<Vec<_>>(), vec![Some(played[1])]);
+        // 白の着手で置いた石と返した石だけが変化している
+        let flipped = &session.game_state.move_history[1].flipped;
+        assert_eq!(delta.changes.len(), flipped.len() + 1);
+        assert!(delta.changes.iter().all(|change| change.cell == Some(Player::White)));
+        assert_eq!(AiBattleDelta::from_session(&session, 0).unwrap().moves.len(), 2);
+        assert!(AiBattleDelta::from_session(&session, 2).unwrap().changes.is_empty());
+        assert!(AiBattleDelta::from_session(&session, 3).is_none());
+        
+        // 待ったで巻き戻した履歴をまたぐ差分は作れない
+        session.undo_last_turn().unwrap();
+        assert_eq!(session.rewound_at, 2);
+        assert!(AiBattleDelta::from_session(&session, 2).is_none());
+        assert!(AiBattleDelta::from_session(&session, 1).is_none());
+    }
+    
+    #[test]
+    fn test_ensure_move_seqs_numbers_legacy_sessions() {
+        use crate::game::ReversiRules;
+        
+        // 採番導入前のセッション: 白のパスの後に黒が続けて打っている
+        let mut session = AiBattleSession::new(AiDifficulty::Easy);
+        let position = ReversiRules::get_valid_moves(&session.game_state.board, Player::Black)[0];
+        ReversiRules::apply_move(&mut session.game_state, position).unwrap();
+        session.game_state.switch_player();
+        let reply = ReversiRules::get_valid_moves(&session.game_state.board, Player::White)[0];
+        ReversiRules::apply_move(&mut session.game_state, reply).unwrap();
+        session.move_history.push(MoveRecord::new(Player::White, reply, Some(0)));
+        session.move_history.push(MoveRecord::pass(Player::Black));
+        
+        session.ensure_move_seqs();
+        let seqs: Vec<u64> = session.transcript_records().iter().map(|record| record.seq).collect();
+        assert_eq!(seqs, vec![1, 2, 3]);
+        assert_eq!(session.move_history[0].seq, 2);
+        assert_eq!(session.move_seq, 3);
+        
+        // 以降の着手は続きの番号になる
+        assert_eq!(session.record_placement(), 4);
+    }
+    
+    #[test]
+    fn test_pvp_session_seats() {
+        let session = AiBattleSession::new_pvp();
+        assert_eq!(session.kind(), SessionKind::HumanVsHuman);
+        assert!(session.is_player_turn());
+        assert!(!session.is_ai_turn());
+        
+        let mut seats = session.seat_tokens.clone().unwrap();
+        assert_eq!(seats.color_of(seats.black), Some(Player::Black));
+        assert_eq!(seats.color_of(Uuid::new_v4()), None);
+        
+        let white = Uuid::new_v4();
+        seats.white = Some(white);
+        assert_eq!(seats.color_of(white), Some(Player::White));
+        
+        assert_eq!(AiBattleSession::new(AiDifficulty::Easy).kind(), SessionKind::HumanVsAi);
+        assert_eq!(AiBattleSession::new_ai_vs_ai(AiDifficulty::Easy, AiDifficulty::Easy).kind(), SessionKind::AiVsAi);
+    }
+    
     #[test]
     fn test_game_status() {
         let in_progress = GameStatus::InProgress;
@@ -592,7 +2800,7 @@ mod tests {
         let error = AiBattleError::GameNotFound { game_id };
         assert_eq!(error.error_code(), "GAME_NOT_FOUND");
         
-        let error = AiBattleError::InvalidMove { reason: "test".to_string() };
+        let error = AiBattleError::InvalidMove { reason: "test".to_string(), rejection: None };
         assert_eq!(error.error_code(), "INVALID_MOVE");
         
         let error = AiBattleError::NotPlayerTurn;
@@ -608,7 +2816,7 @@ mod tests {
         let error = AiBattleError::GameNotFound { game_id };
         assert_eq!(error.status_code(), StatusCode::NOT_FOUND);
         
-        let error = AiBattleError::InvalidMove { reason: "test".to_string() };
+        let error = AiBattleError::InvalidMove { reason: "test".to_string(), rejection: None };
         assert_eq!(error.status_code(), StatusCode::BAD_REQUEST);
         
         let error = AiBattleError::NotPlayerTurn;
@@ -621,6 +2829,32 @@ mod tests {
         assert_eq!(error.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
     }
     
+    #[test]
+    fn test_errors_into_response() {
+        let response = AiBattleError::NotSessionOwner.into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        
+        // ゲームエラーは種類に応じたステータスで返す
+        let response = AiBattleError::from(GameError::GameNotFound { game_id: Uuid::new_v4() }).into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(AiBattleError::from(GameError::SessionLimitExceeded).into_response().status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(AiBattleError::from(GameError::GameFinished).into_response().status(), StatusCode::BAD_REQUEST);
+    }
+    
+    #[test]
+    fn test_overloaded_ai_asks_to_retry_later() {
+        let error = AiBattleError::from_ai_service(crate::error::AIError::Overloaded { retry_after_secs: 2 });
+        assert_eq!(error.error_code(), "AI_OVERLOADED");
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "2");
+        
+        // それ以外のAIのエラーは思考エラーとして返す
+        let error = AiBattleError::from_ai_service(crate::error::AIError::Timeout);
+        assert_eq!(error.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(error.into_response().headers().get(header::RETRY_AFTER).is_none());
+    }
+    
     #[test]
     fn test_ai_battle_error_http_conversion() {
         let game_id = Uuid::new_v4();
@@ -641,4 +2875,71 @@ mod tests {
         let error_result: AiBattleResult<i32> = Err(AiBattleError::NotPlayerTurn);
         assert!(error_result.is_err());
     }
+    
+    #[test]
+    fn test_session_list_query_validation() {
+        let query = |page, per_page, sort_by: Option<&str>, order: Option<&str>| SessionListQuery {
+            page,
+            per_page,
+            sort_by: sort_by.map(str::to_string),
+            order: order.map(str::to_string),
+            ..SessionListQuery::default()
+        };
+        
+        assert_eq!(SessionListQuery::default().parse().unwrap(), SessionPageRequest::default());
+        let parsed = query(Some(2), Some(5), Some("last_move_at"), Some("ASC")).parse().unwrap();
+        assert_eq!(parsed.sort_by, SessionSortKey::LastMoveAt);
+        assert_eq!(parsed.order, SortOrder::Asc);
+        
+        for invalid in [
+            query(Some(0), None, None, None),
+            query(None, Some(0), None, None),
+            query(None, Some(MAX_SESSIONS_PER_PAGE + 1), None, None),
+            query(None, None, Some("status"), None),
+            query(None, None, None, Some("up")),
+        ] {
+            assert!(matches!(invalid.parse(), Err(AiBattleError::BadRequest { .. })));
+        }
+    }
+    
+    #[test]
+    fn test_session_list_query_filter() {
+        let query = |status: Option<&str>, difficulty: Option<&str>| SessionListQuery {
+            status: status.map(str::to_string),
+            difficulty: difficulty.map(str::to_string),
+            ..SessionListQuery::default()
+        };
+        
+        assert_eq!(SessionListQuery::default().filter().unwrap(), SessionFilter::default());
+        let filter = query(Some("in_progress"), Some("hard")).filter().unwrap();
+        assert_eq!(filter.status, Some(SessionStatusFilter::InProgress));
+        assert_eq!(filter.difficulty, Some(AiDifficulty::Hard));
+        assert!(matches!(query(Some("paused"), None).filter(), Err(AiBattleError::BadRequest { .. })));
+        assert!(matches!(query(None, Some("impossible")).filter(), Err(AiBattleError::InvalidDifficulty { .. })));
+    }
+    
+    #[test]
+    fn test_session_page_sorts_and_slices() {
+        let base = Utc::now();
+        let sessions: Vec<AiBattleSession> = (0..5)
+            .map(|i| {
+                let mut session = AiBattleSession::new(AiDifficulty::Easy);
+                session.created_at = base + chrono::Duration::seconds(i);
+                session.last_move_at = base - chrono::Duration::seconds(i);
+                session
+            })
+            .collect();
+        let ids = |page: Vec<AiBattleSession>| page.into_iter().map(|session| session.id).collect::<Vec<_>>();
+        
+        let newest_first = SessionPageRequest { per_page: 2, ..SessionPageRequest::default() };
+        assert_eq!(ids(newest_first.apply(sessions.clone())), vec![sessions[4].id, sessions[3].id]);
+        assert_eq!(newest_first.total_pages(sessions.len()), 3);
+        
+        let last_page = SessionPageRequest { page: 3, ..newest_first };
+        assert_eq!(ids(last_page.apply(sessions.clone())), vec![sessions[0].id]);
+        assert!(SessionPageRequest { page: 4, ..newest_first }.apply(sessions.clone()).is_empty());
+        
+        let by_last_move = SessionPageRequest { sort_by: SessionSortKey::LastMoveAt, order: SortOrder::Asc, ..newest_first };
+        assert_eq!(ids(by_last_move.apply(sessions.clone())), vec![sessions[4].id, sessions[3].id]);
+    }
 }
\ No newline at end of file
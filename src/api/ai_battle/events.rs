@@ -0,0 +1,200 @@
+//! 対局イベントモジュール
+//! セッションごとのイベントバスを管理し、着手・AIの思考状態・終局を購読者に配信する。
+//! WebSocketを使えないクライアント向けに Server-Sent Events で公開する。
+
+use axum::response::sse::Event;
+use dashmap::DashMap;
+use futures::stream::{self, Stream, StreamExt};
+use serde::Serialize;
+use std::convert::Infallible;
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::dto::AiBattleSession;
+use crate::api::encoding::api_player;
+use crate::game::{Player, Position};
+
+/// セッションごとの送信バッファ
+const CHANNEL_CAPACITY: usize = 64;
+
+/// 対局で発生したイベント
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// 着手またはパス（`position` がnull）が行われた
+    MoveMade {
+        game_id: Uuid,
+        /// 着手の通し番号
+        seq: u64,
+        #[serde(with = "api_player")]
+        #[schema(value_type = ApiPlayer)]
+        player: Player,
+        position: Option<Position>,
+        #[serde(with = "api_player")]
+        #[schema(value_type = ApiPlayer)]
+        next_player: Player,
+        black_count: u8,
+        white_count: u8,
+        move_count: u32,
+    },
+    /// AIが思考を開始・終了した
+    AiThinking {
+        game_id: Uuid,
+        #[serde(with = "api_player")]
+        #[schema(value_type = ApiPlayer)]
+        player: Player,
+        thinking: bool,
+    },
+    /// 対局が終了した
+    GameFinished {
+        game_id: Uuid,
+        #[serde(with = "api_player::option")]
+        #[schema(value_type = Option<ApiPlayer>)]
+        winner: Option<Player>,
+    },
+}
+
+impl SessionEvent {
+    /// SSEの `event:` フィールドに使う名前
+    pub fn name(&self) -> &'static str {
+        match self {
+            SessionEvent::MoveMade { .. } => "move_made",
+            SessionEvent::AiThinking { .. } => "ai_thinking",
+            SessionEvent::GameFinished { .. } => "game_finished",
+        }
+    }
+
+    pub fn move_made(session: &AiBattleSession, player: Player, position: Option<Position>) -> Self {
+        let (black_count, white_count) = session.game_state.get_score();
+        let seq = match position {
+            Some(_) => session.ply_seqs.last().copied(),
+            None => session.move_history
+                .iter()
+                .rev()
+                .find(|record| record.is_pass() && record.player == player)
+                .map(|record| record.seq),
+        };
+        SessionEvent::MoveMade {
+            game_id: session.id,
+            seq: seq.unwrap_or(0),
+            player,
+            position,
+            next_player: session.current_player,
+            black_count,
+            white_count,
+            move_count: session.game_state.move_history.len() as u32,
+        }
+    }
+
+    pub fn is_final(&self) -> bool {
+        matches!(self, SessionEvent::GameFinished { .. })
+    }
+}
+
+/// セッションごとのイベントバス
+/// 購読者がいるセッションにのみチャネルを持つ
+#[derive(Debug, Default)]
+pub struct SessionEventBus {
+    channels: DashMap<Uuid, broadcast::Sender<SessionEvent>>,
+}
+
+impl SessionEventBus {
+    /// イベントを配信する
+    /// 購読者が全員切断していればチャネルを破棄する
+    pub fn publish(&self, game_id: Uuid, event: SessionEvent) {
+        let Some(sender) = self.channels.get(&game_id) else {
+            return;
+        };
+        if sender.send(event).is_err() {
+            drop(sender);
+            self.channels.remove_if(&game_id, |_, sender| sender.receiver_count() == 0);
+        }
+    }
+
+    pub fn subscribe(&self, game_id: Uuid) -> broadcast::Receiver<SessionEvent> {
+        self.channels
+            .entry(game_id)
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// セッション削除時にチャネルを閉じ、購読中のストリームを終了させる
+    pub fn close(&self, game_id: Uuid) {
+        self.channels.remove(&game_id);
+    }
+
+    pub fn subscriber_count(&self, game_id: Uuid) -> usize {
+        self.channels.get(&game_id).map_or(0, |sender| sender.receiver_count())
+    }
+}
+
+/// 購読したイベントを順に流すストリーム
+/// `initial` があれば最初に流し、終局イベントを流した時点で終了する
+pub fn session_events(
+    receiver: broadcast::Receiver<SessionEvent>,
+    initial: Option<SessionEvent>,
+) -> impl Stream<Item = SessionEvent> + Send {
+    stream::unfold((receiver, initial, false), |(mut receiver, pending, done)| async move {
+        if done {
+            return None;
+        }
+
+        let event = match pending {
+            Some(event) => event,
+            None => loop {
+                match receiver.recv().await {
+                    Ok(event) => break event,
+                    // 取りこぼした分は対局状態APIで取得できる
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            },
+        };
+
+        let done = event.is_final();
+        Some((event, (receiver, None, done)))
+    })
+}
+
+/// イベントのストリームをSSEのイベント列に変換する
+pub fn sse_stream(events: impl Stream<Item = SessionEvent>) -> impl Stream<Item = Result<Event, Infallible>> {
+    events.filter_map(|event| async move { Event::default().event(event.name()).json_data(&event).ok().map(Ok) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_are_delivered_per_session() {
+        let bus = SessionEventBus::default();
+        let game = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let mut receiver = bus.subscribe(game);
+
+        bus.publish(other, SessionEvent::GameFinished { game_id: other, winner: None });
+        bus.publish(game, SessionEvent::AiThinking { game_id: game, player: Player::White, thinking: true });
+
+        let event = receiver.try_recv().unwrap();
+        assert_eq!(event.name(), "ai_thinking");
+        assert!(receiver.try_recv().is_err());
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "ai_thinking");
+        assert_eq!(json["player"], "white");
+    }
+
+    #[test]
+    fn test_channel_is_dropped_without_subscribers() {
+        let bus = SessionEventBus::default();
+        let game = Uuid::new_v4();
+
+        let receiver = bus.subscribe(game);
+        assert_eq!(bus.subscriber_count(game), 1);
+        drop(receiver);
+
+        bus.publish(game, SessionEvent::GameFinished { game_id: game, winner: Some(Player::Black) });
+        assert!(bus.channels.is_empty());
+    }
+}
@@ -1,119 +1,749 @@
 //! AI対戦APIハンドラー
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    response::Json,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
 };
+use futures::stream::Stream;
+use std::convert::Infallible;
 use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 
 use super::dto::{
-    AiBattleError, AiBattleResponse, CreateAiBattleRequest, 
-    DifficultiesResponse, ErrorResponse, PlayerMoveRequest,
-    MoveResponse, ChangeDifficultyRequest, validate_position,
-    MoveHistoryResponse, SessionListResponse, SessionSummary
+    AiBattleError, AiBattleResult, AiBattleResponse, CreateAiBattleRequest, 
+    DifficultiesResponse, PlayerMoveRequest,
+    MoveResponse, ChangeDifficultyRequest,
+    MoveHistoryResponse, SessionListQuery, SessionListResponse, SessionSummary,
+    HintQuery, HintResponse, AiDifficulty, AnalyzeRequest, AnalyzeResponse,
+    CreateAiVsAiRequest, StepResponse, JoinPvpRequest, PvpSeatResponse, UndoResponse,
+    PassRequest, PassResponse, DeletionReceipt, ShareResponse, TranscriptResponse,
+    ReplayQuery, ReplayResponse, GameStateQuery, GameStateView, WaitQuery, WaitResponse,
+    SessionExport, DEFAULT_WAIT_SECONDS, MAX_WAIT_SECONDS
 };
+use super::clock::{TimeControlPresetsResponse, TimeControlSetting};
+use super::events::sse_stream;
 use super::service::AiBattleService;
+use crate::api::accounts::CurrentAccount;
+use crate::api::auth::ApiKeyIdentity;
+use crate::api::identity::PlayerIdentity;
+use crate::api::json::JsonBody;
+use crate::api::negotiation::{Negotiated, ResponseFormat};
+use crate::api::prefer::{PreferRepresentation, PREFERENCE_APPLIED};
 
+#[utoipa::path(
+    post,
+    path = "/api/ai-battle",
+    tag = "ai-battle",
+    params(("X-Player-Id" = Option<Uuid>, Header, description = "通知を受け取り、レーティングを更新するプレイヤーID")),
+    request_body = CreateAiBattleRequest,
+    responses(
+        (status = 201, description = "AI対戦を作成（ログイン中はそのアカウントが所有者になる）", body = AiBattleResponse),
+        (status = 400, description = "難易度・レベル・AIの設定・持ち時間の指定が不正", body = ErrorResponse),
+        (status = 401, description = "アクセストークンが無効", body = ErrorResponse),
+        (status = 429, description = "セッション上限", body = ErrorResponse),
+    )
+)]
 pub async fn create_ai_battle(
     State(service): State<Arc<AiBattleService>>,
-    Json(request): Json<CreateAiBattleRequest>,
-) -> Result<(StatusCode, Json<AiBattleResponse>), (StatusCode, Json<ErrorResponse>)> {
-    match service.create_ai_battle(request.difficulty).await {
-        Ok(response) => Ok((StatusCode::CREATED, Json(response))),
-        Err(err) => Err(err.into()),
+    identity: Option<PlayerIdentity>,
+    api_key: Option<ApiKeyIdentity>,
+    account: CurrentAccount,
+    JsonBody(request): JsonBody<CreateAiBattleRequest>,
+) -> AiBattleResult<(StatusCode, Json<AiBattleResponse>)> {
+    let time_control = request.time_control.map(TimeControlSetting::resolve).transpose()?;
+    if request.adaptive && request.ai_config.is_some() {
+        return Err(AiBattleError::BadRequest {
+            details: "adaptiveとai_configは同時に指定できません".to_string(),
+        });
     }
+    
+    let difficulty = match (request.difficulty, request.level) {
+        (Some(difficulty), _) => difficulty,
+        (None, Some(level)) => level.into(),
+        (None, None) => {
+            return Err(AiBattleError::BadRequest {
+                details: "difficultyかlevelのどちらかを指定してください".to_string(),
+            });
+        }
+    };
+    
+    let mut response = service
+        .create_ai_battle_with_config(difficulty, request.ai_setup()?, request.player_color, time_control)
+        .await?;
+    
+    if let Some(PlayerIdentity(owner)) = identity {
+        service.set_owner(response.game_id, request.player_color, owner)?;
+    }
+    service.attribute_to_api_key(response.game_id, api_key.as_ref())?;
+    service.set_account_owner(response.game_id, account.as_ref())?;
+    if request.rated {
+        service.set_rated(response.game_id, true)?;
+    }
+    if request.ponder {
+        response = service.set_pondering(response.game_id, true)?;
+    }
+    if request.eval_bar {
+        response = service.set_eval_bar(response.game_id, true)?;
+    }
+    if request.teaching {
+        response = service.set_teaching(response.game_id, true)?;
+    }
+    if request.adaptive {
+        response = service.set_adaptive(response.game_id, true)?;
+    }
+    Ok((StatusCode::CREATED, Json(response)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/ai-battle/{game_id}",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID"), GameStateQuery),
+    responses(
+        (
+            status = 200,
+            description = "ゲーム状態（`since` を指定した場合はその版からの差分。差分を作れない版では全体）。`Accept` でMessagePack・CBORを指定できる",
+            content(
+                ("application/json" = GameStateView),
+                ("application/msgpack" = GameStateView),
+                ("application/cbor" = GameStateView),
+            )
+        ),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+    )
+)]
 pub async fn get_game_state(
     State(service): State<Arc<AiBattleService>>,
     Path(game_id): Path<Uuid>,
-) -> Result<Json<AiBattleResponse>, (StatusCode, Json<ErrorResponse>)> {
-    match service.get_game_state(game_id) {
-        Ok(response) => Ok(Json(response)),
-        Err(err) => Err(err.into()),
-    }
+    Query(query): Query<GameStateQuery>,
+    format: ResponseFormat,
+) -> AiBattleResult<Negotiated<GameStateView>> {
+    let view = match query.since {
+        Some(since) => service.get_game_state_since(game_id, since)?,
+        None => GameStateView::Full(Box::new(service.get_game_state(game_id)?)),
+    };
+    Ok(Negotiated(format, view))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/ai-battle/difficulties",
+    tag = "ai-battle",
+    responses((status = 200, description = "難易度一覧", body = DifficultiesResponse))
+)]
 pub async fn get_difficulties() -> Json<DifficultiesResponse> {
     Json(DifficultiesResponse::new())
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/ai-battle/time-controls",
+    tag = "ai-battle",
+    responses((status = 200, description = "持ち時間のプリセット一覧", body = TimeControlPresetsResponse))
+)]
+pub async fn get_time_control_presets() -> Json<TimeControlPresetsResponse> {
+    Json(TimeControlPresetsResponse::new())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ai-battle/{game_id}/move",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID")),
+    request_body = PlayerMoveRequest,
+    responses(
+        (
+            status = 200,
+            description = "着手結果（`Accept` でMessagePack・CBORを指定できる）",
+            content(
+                ("application/json" = MoveResponse),
+                ("application/msgpack" = MoveResponse),
+                ("application/cbor" = MoveResponse),
+            )
+        ),
+        (status = 400, description = "無効な着手", body = ErrorResponse),
+        (status = 401, description = "所有者のいるセッションに未ログインで着手した", body = ErrorResponse),
+        (status = 403, description = "手番ではない、プレイヤートークンが無効、またはセッションの所有者ではない", body = ErrorResponse),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+        (status = 409, description = "同じ対局の別の着手を処理中、または持ち時間切れ", body = ErrorResponse),
+        (
+            status = 503,
+            description = "AIの計算待ちが混み合っている（着手は反映していないため、同じ着手を再試行できる）",
+            body = ErrorResponse,
+            headers(("Retry-After" = u64, description = "再試行までの秒数"))
+        ),
+    )
+)]
 pub async fn execute_move(
     State(service): State<Arc<AiBattleService>>,
     Path(game_id): Path<Uuid>,
-    Json(request): Json<PlayerMoveRequest>,
-) -> Result<Json<MoveResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let position = match validate_position(request.row, request.col) {
-        Ok(pos) => pos,
-        Err(error_msg) => {
-            let error = ErrorResponse::with_code(
-                "INVALID_POSITION",
-                error_msg,
-                "INVALID_POSITION"
-            );
-            return Err((StatusCode::BAD_REQUEST, Json(error)));
+    account: CurrentAccount,
+    format: ResponseFormat,
+    JsonBody(request): JsonBody<PlayerMoveRequest>,
+) -> AiBattleResult<Negotiated<MoveResponse>> {
+    service.authorize_play(game_id, account.as_ref())?;
+    
+    let position = request.position(service.board_size(game_id)?)?;
+    
+    let mut response = service.make_player_move_as(game_id, position, request.player_token).await?;
+    if !request.principal_variation {
+        response.ai_principal_variation = None;
+    }
+    Ok(Negotiated(format, response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ai-battle/{game_id}/pass",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID")),
+    request_body(content = Option<PassRequest>, description = "対人戦ではプレイヤートークンを指定する"),
+    responses(
+        (
+            status = 200,
+            description = "パスした結果（相手がAIの場合はその応手を含む。`Accept` でMessagePack・CBORを指定できる）",
+            content(
+                ("application/json" = PassResponse),
+                ("application/msgpack" = PassResponse),
+                ("application/cbor" = PassResponse),
+            )
+        ),
+        (status = 400, description = "合法手があるためパスできない", body = ErrorResponse),
+        (status = 401, description = "所有者のいるセッションに未ログインでパスした", body = ErrorResponse),
+        (status = 403, description = "手番ではない、プレイヤートークンが無効、またはセッションの所有者ではない", body = ErrorResponse),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+        (status = 409, description = "同じ対局の別の着手を処理中、または持ち時間切れ", body = ErrorResponse),
+        (
+            status = 503,
+            description = "AIの計算待ちが混み合っている（パスは反映していないため、再試行できる）",
+            body = ErrorResponse,
+            headers(("Retry-After" = u64, description = "再試行までの秒数"))
+        ),
+    )
+)]
+pub async fn pass_turn(
+    State(service): State<Arc<AiBattleService>>,
+    Path(game_id): Path<Uuid>,
+    account: CurrentAccount,
+    format: ResponseFormat,
+    payload: Option<Json<PassRequest>>,
+) -> AiBattleResult<Negotiated<PassResponse>> {
+    let Json(request) = payload.unwrap_or_default();
+    service.authorize_play(game_id, account.as_ref())?;
+    
+    Ok(Negotiated(format, service.pass_turn(game_id, request.player_token).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/ai-battle/{game_id}/hint",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID"), HintQuery),
+    responses(
+        (status = 200, description = "推奨手", body = HintResponse),
+        (status = 400, description = "無効な難易度", body = ErrorResponse),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+        (
+            status = 503,
+            description = "AIの計算待ちが混み合っている",
+            body = ErrorResponse,
+            headers(("Retry-After" = u64, description = "再試行までの秒数"))
+        ),
+    )
+)]
+pub async fn get_hint(
+    State(service): State<Arc<AiBattleService>>,
+    Path(game_id): Path<Uuid>,
+    Query(query): Query<HintQuery>,
+) -> AiBattleResult<Json<HintResponse>> {
+    let difficulty = match query.difficulty.as_deref().map(str::parse::<AiDifficulty>) {
+        None => None,
+        Some(Ok(difficulty)) => Some(difficulty),
+        Some(Err(details)) => {
+            return Err(AiBattleError::InvalidDifficulty { difficulty: details });
         }
     };
     
-    match service.make_player_move(game_id, position).await {
-        Ok(response) => Ok(Json(response)),
-        Err(err) => Err(err.into()),
+    Ok(Json(service.get_hint(game_id, difficulty, query.multipv).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ai-battle/ai-vs-ai",
+    tag = "ai-battle",
+    request_body = CreateAiVsAiRequest,
+    responses(
+        (status = 201, description = "AI同士の対戦を作成（ログイン中はそのアカウントが所有者になる）", body = AiBattleResponse),
+        (status = 401, description = "アクセストークンが無効", body = ErrorResponse),
+        (status = 429, description = "セッション数の上限に到達", body = ErrorResponse),
+    )
+)]
+pub async fn create_ai_vs_ai(
+    State(service): State<Arc<AiBattleService>>,
+    api_key: Option<ApiKeyIdentity>,
+    account: CurrentAccount,
+    JsonBody(payload): JsonBody<CreateAiVsAiRequest>,
+) -> AiBattleResult<(StatusCode, Json<AiBattleResponse>)> {
+    let response = service.create_ai_vs_ai(payload.black_difficulty, payload.white_difficulty, payload.play_out).await?;
+    
+    service.attribute_to_api_key(response.game_id, api_key.as_ref())?;
+    service.set_account_owner(response.game_id, account.as_ref())?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ai-battle/{game_id}/undo",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID")),
+    responses(
+        (status = 200, description = "直前の着手とAIの応手を取り消した結果", body = UndoResponse),
+        (status = 401, description = "所有者のいるセッションに未ログインで待ったした", body = ErrorResponse),
+        (status = 403, description = "セッションの所有者ではない", body = ErrorResponse),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+        (status = 409, description = "取り消せる着手がない、AIが思考中、または同じ対局の別の着手を処理中", body = ErrorResponse),
+    )
+)]
+pub async fn undo_move(
+    State(service): State<Arc<AiBattleService>>,
+    Path(game_id): Path<Uuid>,
+    account: CurrentAccount,
+) -> AiBattleResult<Json<UndoResponse>> {
+    service.authorize_play(game_id, account.as_ref())?;
+    
+    Ok(Json(service.undo_move(game_id)?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ai-battle/pvp",
+    tag = "ai-battle",
+    params(("X-Player-Id" = Option<Uuid>, Header, description = "通知を受け取るプレイヤーID")),
+    responses(
+        (status = 201, description = "対人戦を作成（作成者は黒番、ログイン中はそのアカウントが所有者になる）", body = PvpSeatResponse),
+        (status = 401, description = "アクセストークンが無効", body = ErrorResponse),
+        (status = 429, description = "セッション数の上限に到達", body = ErrorResponse),
+    )
+)]
+pub async fn create_pvp(
+    State(service): State<Arc<AiBattleService>>,
+    identity: Option<PlayerIdentity>,
+    api_key: Option<ApiKeyIdentity>,
+    account: CurrentAccount,
+) -> AiBattleResult<(StatusCode, Json<PvpSeatResponse>)> {
+    let response = service.create_pvp().await?;
+    
+    if let Some(PlayerIdentity(owner)) = identity {
+        service.set_owner(response.game_state.game_id, response.color, owner)?;
     }
+    service.attribute_to_api_key(response.game_state.game_id, api_key.as_ref())?;
+    service.set_account_owner(response.game_state.game_id, account.as_ref())?;
+    service.set_seat_account(response.game_state.game_id, response.color, account.as_ref())?;
+    Ok((StatusCode::CREATED, Json(response)))
 }
 
-pub async fn change_difficulty(
+#[utoipa::path(
+    post,
+    path = "/api/ai-battle/{game_id}/join",
+    tag = "ai-battle",
+    params(
+        ("game_id" = Uuid, Path, description = "ゲームID"),
+        ("X-Player-Id" = Option<Uuid>, Header, description = "通知を受け取るプレイヤーID"),
+    ),
+    request_body = JoinPvpRequest,
+    responses(
+        (status = 200, description = "白番として参加（ログイン中はそのアカウントを白番の担当として記録する）", body = PvpSeatResponse),
+        (status = 401, description = "アクセストークンが無効", body = ErrorResponse),
+        (status = 403, description = "参加トークンが無効", body = ErrorResponse),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+        (status = 409, description = "対戦相手は既に参加済み", body = ErrorResponse),
+    )
+)]
+pub async fn join_pvp(
     State(service): State<Arc<AiBattleService>>,
     Path(game_id): Path<Uuid>,
-    Json(request): Json<ChangeDifficultyRequest>,
-) -> Result<Json<AiBattleResponse>, (StatusCode, Json<ErrorResponse>)> {
-    match service.change_difficulty(game_id, request.difficulty) {
-        Ok(response) => Ok(Json(response)),
-        Err(err) => Err(err.into()),
+    identity: Option<PlayerIdentity>,
+    account: CurrentAccount,
+    JsonBody(request): JsonBody<JoinPvpRequest>,
+) -> AiBattleResult<Json<PvpSeatResponse>> {
+    let response = service.join_pvp(game_id, request.join_token)?;
+    
+    if let Some(PlayerIdentity(owner)) = identity {
+        service.set_owner(game_id, response.color, owner)?;
     }
+    service.set_seat_account(game_id, response.color, account.as_ref())?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ai-battle/{game_id}/step",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID")),
+    responses(
+        (status = 200, description = "AIの手番を1手進めた結果", body = StepResponse),
+        (status = 401, description = "所有者のいるセッションを未ログインで進めようとした", body = ErrorResponse),
+        (status = 403, description = "AIの手番ではない、またはセッションの所有者ではない", body = ErrorResponse),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+        (status = 409, description = "同じ対局の別の着手を処理中", body = ErrorResponse),
+        (
+            status = 503,
+            description = "AIの計算待ちが混み合っている",
+            body = ErrorResponse,
+            headers(("Retry-After" = u64, description = "再試行までの秒数"))
+        ),
+    )
+)]
+pub async fn step_game(
+    State(service): State<Arc<AiBattleService>>,
+    Path(game_id): Path<Uuid>,
+    account: CurrentAccount,
+) -> AiBattleResult<Json<StepResponse>> {
+    service.authorize_play(game_id, account.as_ref())?;
+    
+    Ok(Json(service.step_game(game_id).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ai-battle/{game_id}/analyze",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID")),
+    request_body = AnalyzeRequest,
+    responses(
+        (status = 200, description = "全合法手の評価（評価値の高い順）", body = AnalyzeResponse),
+        (status = 400, description = "ゲームが終了している、または盤面表記が不正・手番に合法手がない", body = ErrorResponse),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+        (
+            status = 503,
+            description = "AIの計算待ちが混み合っている",
+            body = ErrorResponse,
+            headers(("Retry-After" = u64, description = "再試行までの秒数"))
+        ),
+    )
+)]
+pub async fn analyze_position(
+    State(service): State<Arc<AiBattleService>>,
+    Path(game_id): Path<Uuid>,
+    JsonBody(payload): JsonBody<AnalyzeRequest>,
+) -> AiBattleResult<Json<AnalyzeResponse>> {
+    Ok(Json(service.analyze_position(game_id, payload.difficulty, payload.fen.as_deref()).await?))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/ai-battle/{game_id}/difficulty",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID")),
+    request_body = ChangeDifficultyRequest,
+    responses(
+        (status = 200, description = "難易度変更後のゲーム状態", body = AiBattleResponse),
+        (status = 401, description = "所有者のいるセッションを未ログインで変更しようとした", body = ErrorResponse),
+        (status = 403, description = "セッションの所有者ではない", body = ErrorResponse),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+        (status = 409, description = "同じ対局の着手を処理中", body = ErrorResponse),
+    )
+)]
+pub async fn change_difficulty(
+    State(service): State<Arc<AiBattleService>>,
+    Path(game_id): Path<Uuid>,
+    account: CurrentAccount,
+    JsonBody(request): JsonBody<ChangeDifficultyRequest>,
+) -> AiBattleResult<Json<AiBattleResponse>> {
+    service.authorize_manage(game_id, account.as_ref())?;
+    
+    Ok(Json(service.change_difficulty(game_id, request.difficulty)?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/ai-battle/{game_id}",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID")),
+    params(
+        ("game_id" = Uuid, Path, description = "ゲームID"),
+        ("Prefer" = Option<String>, Header, description = "`return=representation` を指定すると削除の受領書を返す"),
+    ),
+    responses(
+        (status = 200, description = "削除完了（`Prefer: return=representation` 指定時）", body = DeletionReceipt),
+        (status = 204, description = "削除完了"),
+        (status = 401, description = "所有者のいるセッションを未ログインで削除しようとした", body = ErrorResponse),
+        (status = 403, description = "セッションの所有者ではない", body = ErrorResponse),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+    )
+)]
 pub async fn delete_game(
     State(service): State<Arc<AiBattleService>>,
     Path(game_id): Path<Uuid>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    match service.delete_session(game_id) {
-        Ok(_) => Ok(StatusCode::NO_CONTENT),
-        Err(err) => Err(err.into()),
+    account: CurrentAccount,
+    PreferRepresentation(representation): PreferRepresentation,
+) -> AiBattleResult<Response> {
+    service.authorize_manage(game_id, account.as_ref())?;
+    
+    let receipt = service.delete_session(game_id)?;
+    if representation {
+        Ok(([(PREFERENCE_APPLIED, PreferRepresentation::applied())], Json::<DeletionReceipt>(receipt)).into_response())
+    } else {
+        Ok(StatusCode::NO_CONTENT.into_response())
     }
 }
 
-pub async fn get_history(
+#[utoipa::path(
+    get,
+    path = "/api/ai-battle/{game_id}/events",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID")),
+    responses(
+        (status = 200, description = "対局イベントのストリーム（各イベントのdataは `SessionEvent` のJSON）", content_type = "text/event-stream", body = String),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+    )
+)]
+pub async fn stream_events(
+    State(service): State<Arc<AiBattleService>>,
+    Path(game_id): Path<Uuid>,
+) -> AiBattleResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    event_stream(&service, game_id)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/ai-battle/{game_id}/wait",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID"), WaitQuery),
+    responses(
+        (status = 200, description = "状態が変化した時点、または待ち時間が経過した時点の対局状態", body = WaitResponse),
+        (status = 400, description = "待ち時間が範囲外", body = ErrorResponse),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+    )
+)]
+pub async fn wait_for_change(
     State(service): State<Arc<AiBattleService>>,
     Path(game_id): Path<Uuid>,
-) -> Result<Json<MoveHistoryResponse>, (StatusCode, Json<ErrorResponse>)> {
-    match service.get_move_history(game_id) {
-        Ok(moves) => {
-            let response = MoveHistoryResponse {
-                game_id,
-                moves: moves.clone(),
-                total_moves: moves.len(),
-            };
-            Ok(Json(response))
-        },
-        Err(err) => Err(err.into()),
+    Query(query): Query<WaitQuery>,
+) -> AiBattleResult<Json<WaitResponse>> {
+    let seconds = query.timeout.unwrap_or(DEFAULT_WAIT_SECONDS);
+    if !(1..=MAX_WAIT_SECONDS).contains(&seconds) {
+        return Err(AiBattleError::BadRequest {
+            details: format!("timeout は1〜{}秒の範囲で指定してください", MAX_WAIT_SECONDS),
+        });
     }
+    
+    let response = service.wait_for_change(game_id, query.version, Duration::from_secs(seconds)).await?;
+    Ok(Json(response))
+}
+
+fn event_stream(
+    service: &AiBattleService,
+    game_id: Uuid,
+) -> AiBattleResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let events = service.subscribe_events(game_id)?;
+    Ok(Sse::new(sse_stream(events)).keep_alive(KeepAlive::default()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ai-battle/{game_id}/share",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID")),
+    responses(
+        (status = 200, description = "観戦用の共有リンク（発行済みであれば同じリンク）", body = ShareResponse),
+        (status = 401, description = "所有者のいるセッションを未ログインで共有しようとした", body = ErrorResponse),
+        (status = 403, description = "セッションの所有者ではない", body = ErrorResponse),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+    )
+)]
+pub async fn share_game(
+    State(service): State<Arc<AiBattleService>>,
+    Path(game_id): Path<Uuid>,
+    account: CurrentAccount,
+) -> AiBattleResult<Json<ShareResponse>> {
+    service.authorize_manage(game_id, account.as_ref())?;
+    
+    Ok(Json(service.share_session(game_id)?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/spectate/{share_token}",
+    tag = "ai-battle",
+    params(("share_token" = Uuid, Path, description = "観戦用の共有トークン")),
+    responses(
+        (status = 200, description = "観戦中の対局の状態", body = AiBattleResponse),
+        (status = 404, description = "共有トークンが無効", body = ErrorResponse),
+    )
+)]
+pub async fn spectate_game(
+    State(service): State<Arc<AiBattleService>>,
+    Path(share_token): Path<Uuid>,
+) -> AiBattleResult<Json<AiBattleResponse>> {
+    let game_id = service.resolve_share_token(share_token)?;
+    Ok(Json(service.get_game_state(game_id)?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/spectate/{share_token}/events",
+    tag = "ai-battle",
+    params(("share_token" = Uuid, Path, description = "観戦用の共有トークン")),
+    responses(
+        (status = 200, description = "観戦中の対局イベントのストリーム（各イベントのdataは `SessionEvent` のJSON）", content_type = "text/event-stream", body = String),
+        (status = 404, description = "共有トークンが無効", body = ErrorResponse),
+    )
+)]
+pub async fn stream_spectator_events(
+    State(service): State<Arc<AiBattleService>>,
+    Path(share_token): Path<Uuid>,
+) -> AiBattleResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let game_id = service.resolve_share_token(share_token)?;
+    event_stream(&service, game_id)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/ai-battle/{game_id}/history",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID")),
+    responses(
+        (status = 200, description = "着手履歴", body = MoveHistoryResponse),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+    )
+)]
+pub async fn get_history(
+    State(service): State<Arc<AiBattleService>>,
+    Path(game_id): Path<Uuid>,
+) -> AiBattleResult<Json<MoveHistoryResponse>> {
+    let moves = service.get_move_history(game_id)?;
+    let response = MoveHistoryResponse {
+        game_id,
+        total_moves: moves.len(),
+        moves,
+    };
+    Ok(Json(response))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/ai-battle/{game_id}/replay",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID"), ReplayQuery),
+    responses(
+        (status = 200, description = "着手履歴から再構築した各手の後の局面", body = ReplayResponse),
+        (status = 400, description = "手数が範囲外", body = ErrorResponse),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+    )
+)]
+pub async fn get_replay(
+    State(service): State<Arc<AiBattleService>>,
+    Path(game_id): Path<Uuid>,
+    Query(query): Query<ReplayQuery>,
+) -> AiBattleResult<Json<ReplayResponse>> {
+    Ok(Json(service.replay(game_id, query.move_number)?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/ai-battle/{game_id}/transcript",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID")),
+    responses(
+        (status = 200, description = "標準の棋譜形式（\"F5D6C3...\"）で書き出した対局", body = TranscriptResponse),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+    )
+)]
+pub async fn get_transcript(
+    State(service): State<Arc<AiBattleService>>,
+    Path(game_id): Path<Uuid>,
+) -> AiBattleResult<Json<TranscriptResponse>> {
+    Ok(Json(service.export_transcript(game_id)?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/ai-battle/{game_id}/export",
+    tag = "ai-battle",
+    params(("game_id" = Uuid, Path, description = "ゲームID")),
+    responses(
+        (status = 200, description = "盤面・履歴・設定・日時をまとめた、別の環境で読み込める対局の書き出し", body = SessionExport),
+        (status = 404, description = "ゲームが存在しない", body = ErrorResponse),
+    )
+)]
+pub async fn export_game(
+    State(service): State<Arc<AiBattleService>>,
+    Path(game_id): Path<Uuid>,
+) -> AiBattleResult<Json<SessionExport>> {
+    Ok(Json(service.export_session(game_id)?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/ai-battle/import",
+    tag = "ai-battle",
+    request_body = SessionExport,
+    responses(
+        (status = 201, description = "書き出した対局を新しいIDで作成（ログイン中はそのアカウントが所有者になる）。人間対AIでAIの手番なら応手を指した局面を返す", body = AiBattleResponse),
+        (status = 400, description = "形式の版・設定が不正、または履歴がルールに合わない・盤面と一致しない", body = ErrorResponse),
+        (status = 401, description = "アクセストークンが無効", body = ErrorResponse),
+        (status = 429, description = "セッション上限", body = ErrorResponse),
+        (
+            status = 503,
+            description = "AIの計算待ちが混み合っている（対局は作成していないため、再試行できる）",
+            body = ErrorResponse,
+            headers(("Retry-After" = u64, description = "再試行までの秒数"))
+        ),
+    )
+)]
+pub async fn import_game(
+    State(service): State<Arc<AiBattleService>>,
+    api_key: Option<ApiKeyIdentity>,
+    account: CurrentAccount,
+    JsonBody(bundle): JsonBody<SessionExport>,
+) -> AiBattleResult<(StatusCode, Json<AiBattleResponse>)> {
+    let response = service.import_session(bundle).await?;
+    service.attribute_to_api_key(response.game_id, api_key.as_ref())?;
+    service.set_account_owner(response.game_id, account.as_ref())?;
+    
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/ai-battle/sessions",
+    tag = "ai-battle",
+    params(SessionListQuery),
+    responses(
+        (status = 200, description = "セッション一覧の指定ページ", body = SessionListResponse),
+        (status = 400, description = "ページ指定・並び替え・絞り込みの指定が不正", body = ErrorResponse),
+    )
+)]
 pub async fn get_sessions(
     State(service): State<Arc<AiBattleService>>,
-) -> Json<SessionListResponse> {
-    let sessions = service.list_sessions();
-    let session_summaries: Vec<SessionSummary> = sessions
+    Query(query): Query<SessionListQuery>,
+) -> AiBattleResult<Json<SessionListResponse>> {
+    let request = query.parse()?;
+    let filter = query.filter()?;
+    
+    let sessions = service.list_sessions_or_stored(&filter).await;
+    let total_count = sessions.len();
+    let session_summaries: Vec<SessionSummary> = request
+        .apply(sessions)
         .iter()
         .map(SessionSummary::from_session)
         .collect();
     
     let response = SessionListResponse {
         sessions: session_summaries,
-        total_count: sessions.len(),
+        total_count,
+        page: request.page,
+        per_page: request.per_page,
+        total_pages: request.total_pages(total_count),
+        sort_by: request.sort_by,
+        order: request.order,
     };
     
-    Json(response)
-}
\ No newline at end of file
+    Ok(Json(response))
+}
@@ -3,12 +3,18 @@
 //! 既存のリバーシシステムを拡張し、AI相手との対戦機能をWebAPI経由で提供する。
 
 pub mod dto;
+pub mod clock;
+pub mod events;
+pub mod ponder;
 pub mod service;
 pub mod handlers;
 pub mod routes;
 pub mod config_service;
 
 pub use dto::*;
+pub use clock::*;
+pub use events::*;
+pub use ponder::*;
 pub use service::*;
 pub use handlers::*;
 pub use routes::*;
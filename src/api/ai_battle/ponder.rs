@@ -0,0 +1,190 @@
+//! AIの先読み（ポンダー）
+//! AIが応手を指した後、人間の手番のあいだに人間の着手候補ごとのAIの応手をバックグラウンドで計算しておく。
+//! 人間が候補のどれかを指した場合（ポンダーヒット）は、計算済みの応手をほぼ待たずに返せる。
+
+use std::sync::{Arc, Mutex};
+use tokio::task::AbortHandle;
+
+use crate::ai::evaluation::{BoardEvaluator, EvalWeights};
+use crate::ai::levels::{AiLevel, LevelParams};
+use crate::ai::service::{AIMoveResult, AIService};
+use crate::ai::strategies::CancelFlag;
+use crate::error::AIError;
+use crate::game::{Board, GameState, Player, ReversiRules};
+
+/// 先読み済みの応手
+#[derive(Debug, Clone)]
+struct PonderedReply {
+    board: Board,
+    to_move: Player,
+    result: AIMoveResult,
+}
+
+/// セッションごとの先読みの状態
+/// セッションのコピー同士でタスクと結果を共有し、最後のコピーが破棄されるとタスクを中断する
+#[derive(Debug, Clone)]
+pub struct PonderState {
+    level: AiLevel,
+    params: LevelParams,
+    seed: Option<u64>,
+    replies: Arc<Mutex<Vec<PonderedReply>>>,
+    task: Arc<PonderTask>,
+}
+
+/// 先読みのタスクと、計算中の探索の中断フラグ
+/// タスクを中断しても計算待ちキューのワーカーで動いている探索は止まらないため、フラグも立てる
+#[derive(Debug)]
+struct PonderTask {
+    handle: AbortHandle,
+    cancel: CancelFlag,
+}
+
+impl PonderTask {
+    fn stop(&self) {
+        self.cancel.cancel();
+        self.handle.abort();
+    }
+}
+
+impl Drop for PonderTask {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl PonderState {
+    /// 人間の手番の局面から、人間の着手候補（有望な順）ごとのAIの応手の計算を始める
+    /// 人間に合法手がない場合はパスした後の局面を先読みする
+    pub fn start(
+        ai_service: Arc<dyn AIService>,
+        state: &GameState,
+        level: AiLevel,
+        params: LevelParams,
+        seed: Option<u64>,
+    ) -> Self {
+        let candidates = Self::candidate_states(state);
+        let replies = Arc::new(Mutex::new(Vec::new()));
+        let shared = Arc::clone(&replies);
+        let cancel = CancelFlag::new();
+        let searching = cancel.clone();
+
+        let handle = tokio::spawn(async move {
+            for candidate in candidates {
+                // 先読みは計算待ちキューが空いているときだけ行い、対局の着手の枠を使わない
+                let result = match ai_service.calculate_move_when_idle(&candidate, level, params, seed, &searching).await {
+                    Ok(result) => result,
+                    Err(AIError::Overloaded { .. } | AIError::Cancelled) => break,
+                    Err(_) => continue,
+                };
+                let reply = PonderedReply {
+                    board: candidate.board.clone(),
+                    to_move: candidate.current_player,
+                    result,
+                };
+                shared.lock().unwrap().push(reply);
+            }
+        });
+
+        Self {
+            level,
+            params,
+            seed,
+            replies,
+            task: Arc::new(PonderTask { handle: handle.abort_handle(), cancel }),
+        }
+    }
+
+    /// 人間の着手後にAIの手番になる局面を、人間から見て有望な順に並べる
+    fn candidate_states(state: &GameState) -> Vec<GameState> {
+        let human = state.current_player;
+        let moves = ReversiRules::get_valid_moves(&state.board, human);
+        if moves.is_empty() {
+            let mut passed = state.clone();
+            passed.switch_player();
+            return vec![passed];
+        }
+
+        let weights = EvalWeights::default();
+        let mut candidates: Vec<(f32, GameState)> = moves
+            .into_iter()
+            .filter_map(|position| {
+                let mut next = state.clone();
+                ReversiRules::apply_move(&mut next, position).ok()?;
+                next.switch_player();
+                // AIがパスする局面では応手を計算しない
+                if !ReversiRules::has_valid_moves(&next.board, next.current_player) {
+                    return None;
+                }
+                let score = BoardEvaluator::evaluate_for_variant(&next.board, human, &weights, next.variant);
+                Some((score, next))
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+        candidates.into_iter().map(|(_, next)| next).collect()
+    }
+
+    /// 先読み済みであれば、その局面でのAIの応手を返す
+    /// レベル・探索の設定・シードが先読みを始めたときと異なる場合は使わない
+    pub fn reply_for(
+        &self,
+        state: &GameState,
+        level: AiLevel,
+        params: LevelParams,
+        seed: Option<u64>,
+    ) -> Option<AIMoveResult> {
+        if self.level != level || self.params != params || self.seed != seed {
+            return None;
+        }
+        self.replies
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|reply| reply.to_move == state.current_player && reply.board == state.board)
+            .map(|reply| reply.result.clone())
+    }
+
+    /// 先読みを中断する（計算中の探索は打ち切り、結果は捨てる）
+    pub fn cancel(&self) {
+        self.task.stop();
+    }
+
+    /// 先読み済みの応手の数
+    pub fn pondered(&self) -> usize {
+        self.replies.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::mock_service::MockAIService;
+
+    #[tokio::test]
+    async fn test_ponders_human_replies_until_cancelled() {
+        let mut state = GameState::new();
+        let first = ReversiRules::get_valid_moves(&state.board, Player::Black)[0];
+        ReversiRules::apply_move(&mut state, first).unwrap();
+        state.switch_player();
+        let human_moves = ReversiRules::get_valid_moves(&state.board, Player::White);
+        let (easy, hard) = (AiLevel::new(1).unwrap(), AiLevel::new(8).unwrap());
+
+        let ponder = PonderState::start(Arc::new(MockAIService::new_fast()), &state, easy, easy.params(), Some(1));
+        while ponder.pondered() < human_moves.len() {
+            tokio::task::yield_now().await;
+        }
+
+        let mut next = state.clone();
+        ReversiRules::apply_move(&mut next, human_moves[0]).unwrap();
+        next.switch_player();
+        let reply = ponder.reply_for(&next, easy, easy.params(), Some(1)).unwrap();
+        assert!(ReversiRules::is_valid_move(&next.board, reply.position, Player::Black));
+        assert!(ponder.reply_for(&next, hard, hard.params(), Some(1)).is_none());
+        assert!(ponder.reply_for(&state, easy, easy.params(), Some(1)).is_none());
+
+        // 応答に時間のかかるAIの先読みを中断すると、結果は残らない
+        let slow = PonderState::start(Arc::new(MockAIService::new_default()), &state, easy, easy.params(), None);
+        slow.cancel();
+        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+        assert_eq!(slow.pondered(), 0);
+    }
+}
@@ -6,20 +6,55 @@ use axum::{
 };
 use std::sync::Arc;
 
+use crate::api::timeout::WithTimeout;
+use crate::config::{RouteClass, RouteTimeouts};
+
 use super::handlers;
 use super::service::AiBattleService;
 
-pub fn create_ai_battle_routes(service: Arc<AiBattleService>) -> Router {
-    Router::new()
-        .route("/api/ai-battle", post(handlers::create_ai_battle))
-        .route("/api/ai-battle/difficulties", get(handlers::get_difficulties))
-        .route("/api/ai-battle/sessions", get(handlers::get_sessions))
+/// AI対戦APIのルートを作成する
+/// イベントストリームと長いポーリング以外の各ルートには種類に応じた処理時間の上限を設定する
+pub fn create_ai_battle_routes(service: Arc<AiBattleService>, timeouts: &RouteTimeouts) -> Router {
+    let read = timeouts.budget(RouteClass::StateRead);
+    let moves = timeouts.budget(RouteClass::Move);
+    let analysis = timeouts.budget(RouteClass::Analysis);
+    let default = timeouts.budget(RouteClass::Default);
+    
+    let router = Router::new()
+        .route("/api/ai-battle", post(handlers::create_ai_battle).with_timeout(moves))
+        .route("/api/ai-battle/difficulties", get(handlers::get_difficulties).with_timeout(read))
+        .route("/api/ai-battle/time-controls", get(handlers::get_time_control_presets).with_timeout(read))
+        .route("/api/ai-battle/sessions", get(handlers::get_sessions).with_timeout(read))
+        .route("/api/ai-battle/ai-vs-ai", post(handlers::create_ai_vs_ai).with_timeout(moves))
+        .route("/api/ai-battle/pvp", post(handlers::create_pvp).with_timeout(default))
+        .route("/api/ai-battle/import", post(handlers::import_game).with_timeout(default))
         
-        .route("/api/ai-battle/:game_id", get(handlers::get_game_state))
-        .route("/api/ai-battle/:game_id", delete(handlers::delete_game))
-        .route("/api/ai-battle/:game_id/move", post(handlers::execute_move))
-        .route("/api/ai-battle/:game_id/difficulty", put(handlers::change_difficulty))
-        .route("/api/ai-battle/:game_id/history", get(handlers::get_history))
+        .route("/api/ai-battle/:game_id", get(handlers::get_game_state).with_timeout(read))
+        .route("/api/ai-battle/:game_id", delete(handlers::delete_game).with_timeout(default))
+        .route("/api/ai-battle/:game_id/move", post(handlers::execute_move).with_timeout(moves))
+        .route("/api/ai-battle/:game_id/pass", post(handlers::pass_turn).with_timeout(moves))
+        .route("/api/ai-battle/:game_id/difficulty", put(handlers::change_difficulty).with_timeout(default))
+        .route("/api/ai-battle/:game_id/history", get(handlers::get_history).with_timeout(read))
+        .route("/api/ai-battle/:game_id/transcript", get(handlers::get_transcript).with_timeout(read))
+        .route("/api/ai-battle/:game_id/replay", get(handlers::get_replay).with_timeout(read))
+        .route("/api/ai-battle/:game_id/export", get(handlers::export_game).with_timeout(read))
+        .route("/api/ai-battle/:game_id/events", get(handlers::stream_events))
+        .route("/api/ai-battle/:game_id/wait", get(handlers::wait_for_change))
+        .route("/api/ai-battle/:game_id/hint", get(handlers::get_hint).with_timeout(moves))
+        .route("/api/ai-battle/:game_id/analyze", post(handlers::analyze_position).with_timeout(analysis))
+        .route("/api/ai-battle/:game_id/step", post(handlers::step_game).with_timeout(moves))
+        .route("/api/ai-battle/:game_id/join", post(handlers::join_pvp).with_timeout(default))
+        .route("/api/ai-battle/:game_id/undo", post(handlers::undo_move).with_timeout(default))
+        .route("/api/ai-battle/:game_id/share", post(handlers::share_game).with_timeout(default))
         
-        .with_state(service)
-}
\ No newline at end of file
+        .route("/api/spectate/:share_token", get(handlers::spectate_game).with_timeout(read))
+        .route("/api/spectate/:share_token/events", get(handlers::stream_spectator_events));
+    
+    #[cfg(feature = "debug-api")]
+    let router = router.merge(crate::api::debug::create_debug_routes());
+    
+    #[cfg(feature = "graphql")]
+    let router = router.merge(crate::api::graphql::create_graphql_routes(Arc::clone(&service), default));
+    
+    router.with_state(service)
+}
@@ -1,21 +1,87 @@
 //! AI対戦サービス
 
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use tokio::time::{sleep, Duration};
 use chrono::Utc;
+use futures::Stream;
 
-use crate::game::{Position, Player, ReversiRules};
-use crate::ai::service::{AIService, AIServiceFactory};
+use crate::game::{GameState, GameVariant, Move, Player, Position, ReversiRules, DEFAULT_BOARD_SIZE};
+use crate::game::replay::ReplayBuilder;
+use crate::ai::service::{AIMoveResult, AIService, AIServiceFactory};
+use crate::error::AIError;
+use crate::ai::evaluation::{BoardEvaluator, EvalWeights};
+use crate::ai::adaptive::AdaptiveState;
+use crate::ai::explain::{explain_move, MAX_CONSIDERED_MOVES};
+use crate::ai::strategies::ScoredMove;
+use crate::ai::levels::{AiLevel, LevelParams};
 use crate::session::AiBattleSessionManager;
+use crate::api::notifications::{NotificationHub, NotificationKind};
+use crate::api::lobby::{Lobby, OpenChallenge};
+use crate::api::auth::ApiKeyIdentity;
+use crate::accounts::{AccountIdentity, Accounts};
+use crate::ratings::Ratings;
+use crate::tournament::Tournaments;
+use crate::stats::{DifficultyStatsAggregator, DifficultyStatsResponse};
+use crate::leaderboard::{build_leaderboard, LeaderboardPeriod, LeaderboardResponse};
+use crate::api::webhook::WebhookSink;
+use crate::config::CorrespondenceConfig;
+use crate::archive::{ArchiveFilter, ArchivedGame, GameArchive, MemoryGameArchive};
+use crate::game::PositionHash;
+use crate::positions::{PositionIndex, PositionReport};
+use crate::puzzles::{DailyPuzzleResponse, PuzzleAnswerRequest, PuzzleAnswerResponse, PuzzleStreak, Puzzles};
 
+use super::clock::{GameClock, TimeControl};
+use super::events::{session_events, SessionEvent, SessionEventBus};
+use super::ponder::PonderState;
 use super::dto::{
-    AiBattleSession, AiBattleError, AiBattleResult, AiDifficulty, 
-    MoveRecord, GameStatus, AiBattleResponse, MoveResponse
+    AiBattleSession, AiBattleError, AiBattleResult, AiDifficulty, AiSetup, Handicap, StartPosition,
+    MoveRecord, GameStatus, AiBattleResponse, MoveResponse, HintResponse, AnalyzeResponse,
+    StepResponse, PvpSeatResponse, SimulateGameResponse, TranscriptMove, UndoResponse, PassResponse,
+    SeatTokens, SessionSummary, SessionFilter, DeletionReceipt, SessionKind, ShareResponse, TranscriptResponse,
+    ReplayResponse, ReplaySnapshot, MoveRejection, AiBattleDelta, GameStateView, WaitResponse,
+    SessionExport, validate_board_size
 };
 
 pub struct AiBattleService {
     session_manager: Arc<AiBattleSessionManager>,
     ai_service: Arc<dyn AIService>,
+    notifications: Arc<NotificationHub>,
+    lobby: Arc<Lobby>,
+    events: Arc<SessionEventBus>,
+    /// 通信対局で手番になってから催促するまでの時間（未設定なら持ち時間の残りで判断する）
+    reminder_after: Option<chrono::Duration>,
+    /// 終局した対局の記録先
+    archive: RwLock<Arc<dyn GameArchive>>,
+    /// 局面ハッシュから局面の情報を引く索引
+    positions: Arc<PositionIndex>,
+    /// プレイヤーアカウントとJWTの発行・検証
+    accounts: Arc<Accounts>,
+    /// プレイヤーのレーティング
+    ratings: Arc<Ratings>,
+    /// AIエンジン同士の大会
+    tournaments: Arc<Tournaments>,
+    /// 難易度別の対局統計
+    difficulty_stats: Arc<DifficultyStatsAggregator>,
+    /// アーカイブの対局から集めた毎日の問題
+    puzzles: Arc<Puzzles>,
+}
+
+/// AIの応手の計算に必要な、セッションから取り出した設定と局面
+struct AiTurn {
+    player: Player,
+    difficulty: AiDifficulty,
+    level: AiLevel,
+    params: LevelParams,
+    /// 対局ごとにAIの設定を上書きしているか（適応難易度でレベルを調整している場合を含む）
+    custom: bool,
+    /// 数値レベルで指定した対局か
+    at_level: bool,
+    seed: Option<u64>,
+    /// 求める候補手の数（教習モードで解説に載せる、0は候補手を求めない）
+    multipv: u8,
+    /// 先読み済みの応手
+    pondered: Option<AIMoveResult>,
+    state: GameState,
 }
 
 impl std::fmt::Debug for AiBattleService {
@@ -31,10 +97,22 @@ impl AiBattleService {
     pub fn new(session_manager: Arc<AiBattleSessionManager>) -> Self {
         let ai_service = AIServiceFactory::create_default_local()
             .expect("Failed to create default local AI service");
+        let notifications = Arc::new(NotificationHub::default());
         
         Self {
             session_manager,
             ai_service: ai_service.into(),
+            lobby: Arc::new(Lobby::new(Arc::clone(&notifications))),
+            notifications,
+            events: Arc::new(SessionEventBus::default()),
+            reminder_after: None,
+            archive: RwLock::new(Arc::new(MemoryGameArchive::default())),
+            positions: Arc::new(PositionIndex::new()),
+            accounts: Arc::new(Accounts::default()),
+            ratings: Arc::new(Ratings::default()),
+            tournaments: Arc::new(Tournaments::default()),
+            difficulty_stats: Arc::new(DifficultyStatsAggregator::default()),
+            puzzles: Arc::new(Puzzles::default()),
         }
     }
     
@@ -42,10 +120,69 @@ impl AiBattleService {
         session_manager: Arc<AiBattleSessionManager>,
         ai_service: Arc<dyn AIService>
     ) -> Self {
+        let notifications = Arc::new(NotificationHub::default());
+        
         Self {
             session_manager,
             ai_service,
+            lobby: Arc::new(Lobby::new(Arc::clone(&notifications))),
+            notifications,
+            events: Arc::new(SessionEventBus::default()),
+            reminder_after: None,
+            archive: RwLock::new(Arc::new(MemoryGameArchive::default())),
+            positions: Arc::new(PositionIndex::new()),
+            accounts: Arc::new(Accounts::default()),
+            ratings: Arc::new(Ratings::default()),
+            tournaments: Arc::new(Tournaments::default()),
+            difficulty_stats: Arc::new(DifficultyStatsAggregator::default()),
+            puzzles: Arc::new(Puzzles::default()),
+        }
+    }
+    
+    /// 通信対局の催促の時期とWebhookによる外部配信を設定する
+    pub fn with_correspondence(mut self, config: &CorrespondenceConfig) -> Self {
+        self.reminder_after = config
+            .reminder_after_minutes
+            .map(|minutes| chrono::Duration::minutes(minutes as i64));
+        
+        if let Some(url) = &config.webhook_url {
+            let kinds = vec![NotificationKind::TurnReminder, NotificationKind::GameFinished];
+            match WebhookSink::new(url, kinds) {
+                Ok(sink) => self.notifications.add_sink(Arc::new(sink)),
+                Err(e) => eprintln!("Warning: Webhookを設定できません: {}", e),
+            }
         }
+        self
+    }
+    
+    /// アカウントの管理を差し替える（サービスを作り直しても発行済みのトークンを使えるよう共有する）
+    pub fn with_accounts(mut self, accounts: Arc<Accounts>) -> Self {
+        self.accounts = accounts;
+        self
+    }
+    
+    /// レーティングを差し替える（サービスを作り直してもレーティングを引き継ぐよう共有する）
+    pub fn with_ratings(mut self, ratings: Arc<Ratings>) -> Self {
+        self.ratings = ratings;
+        self
+    }
+    
+    /// 大会の一覧を差し替える（サービスを作り直しても進行中の大会を追えるよう共有する）
+    pub fn with_tournaments(mut self, tournaments: Arc<Tournaments>) -> Self {
+        self.tournaments = tournaments;
+        self
+    }
+    
+    /// 難易度別の統計を差し替える（サービスを作り直しても集計を引き継ぐよう共有する）
+    pub fn with_difficulty_stats(mut self, difficulty_stats: Arc<DifficultyStatsAggregator>) -> Self {
+        self.difficulty_stats = difficulty_stats;
+        self
+    }
+    
+    /// 毎日の問題を差し替える（サービスを作り直しても出題と連続正解の記録を引き継ぐよう共有する）
+    pub fn with_puzzles(mut self, puzzles: Arc<Puzzles>) -> Self {
+        self.puzzles = puzzles;
+        self
     }
     
     pub fn get_ai_service(&self) -> &Arc<dyn AIService> {
@@ -56,328 +193,1978 @@ impl AiBattleService {
         self.ai_service = ai_service;
     }
     
-    pub async fn create_ai_battle(&self, difficulty: AiDifficulty) -> AiBattleResult<AiBattleResponse> {
-        let session_id = self.session_manager.create_session(difficulty).await?;
-        let session = self.session_manager.get_session(&session_id)?;
-        
-        Ok(AiBattleResponse::from_session(&session))
+    pub fn notifications(&self) -> &Arc<NotificationHub> {
+        &self.notifications
     }
     
-    pub fn get_game_state(&self, session_id: uuid::Uuid) -> AiBattleResult<AiBattleResponse> {
-        let session = self.session_manager.get_session(&session_id)?;
-        Ok(AiBattleResponse::from_session(&session))
+    pub fn lobby(&self) -> &Arc<Lobby> {
+        &self.lobby
     }
     
-    pub async fn make_player_move(
-        &self, 
-        session_id: uuid::Uuid, 
-        position: Position
-    ) -> AiBattleResult<MoveResponse> {
-        let mut session = self.session_manager.get_session(&session_id)?;
-        
-        if session.is_finished() {
-            return Err(AiBattleError::GameAlreadyFinished);
-        }
-        
-        if !session.is_player_turn() {
-            return Err(AiBattleError::NotPlayerTurn);
+    pub fn events(&self) -> &Arc<SessionEventBus> {
+        &self.events
+    }
+    
+    /// 終局した対局の記録先を差し替える（既定はメモリ）
+    pub fn attach_archive(&self, archive: Arc<dyn GameArchive>) {
+        *self.archive.write().unwrap() = archive;
+    }
+    
+    pub fn archive(&self) -> Arc<dyn GameArchive> {
+        Arc::clone(&self.archive.read().unwrap())
+    }
+    
+    /// アーカイブを検索する（受け付け済みの記録を反映してから検索する）
+    pub async fn query_archive(&self, filter: &ArchiveFilter) -> AiBattleResult<Vec<ArchivedGame>> {
+        let archive = self.archive();
+        archive.flush().await;
+        archive.query(filter).await.map_err(|e| AiBattleError::InternalError { details: e.to_string() })
+    }
+    
+    /// 期間内に終局した対局をプレイヤーごとに集計し、レーティングの順位表を作る
+    pub async fn leaderboard(&self, period: LeaderboardPeriod, limit: usize) -> AiBattleResult<LeaderboardResponse> {
+        let since = period.since(Utc::now());
+        let archive = self.archive();
+        archive.flush().await;
+        let tallies = archive
+            .tally_players(since)
+            .await
+            .map_err(|e| AiBattleError::InternalError { details: e.to_string() })?;
+        Ok(build_leaderboard(tallies, &self.ratings, period, since, limit))
+    }
+    
+    pub fn accounts(&self) -> &Arc<Accounts> {
+        &self.accounts
+    }
+    
+    /// AIの難易度ごとの人間の勝率・平均手数・AIの平均思考時間
+    pub fn difficulty_stats(&self) -> DifficultyStatsResponse {
+        self.difficulty_stats.snapshot()
+    }
+    
+    pub fn tournaments(&self) -> &Arc<Tournaments> {
+        &self.tournaments
+    }
+    
+    pub fn ratings(&self) -> &Arc<Ratings> {
+        &self.ratings
+    }
+    
+    pub fn positions(&self) -> &Arc<PositionIndex> {
+        &self.positions
+    }
+    
+    pub fn puzzles(&self) -> &Arc<Puzzles> {
+        &self.puzzles
+    }
+    
+    /// アーカイブの全対局から局面の統計と毎日の問題を集め直す（起動時にアーカイブを接続した後に呼ぶ）
+    pub async fn rebuild_position_index(&self) -> AiBattleResult<usize> {
+        let games = self.query_archive(&ArchiveFilter::default()).await?;
+        for game in &games {
+            self.positions.record_game(game);
+            self.puzzles.mine(game, &self.positions);
         }
+        Ok(games.len())
+    }
+    
+    /// 今日の問題
+    pub fn daily_puzzle(&self) -> AiBattleResult<DailyPuzzleResponse> {
+        self.puzzles.today(Utc::now().date_naive())
+    }
+    
+    /// 今日の問題への解答を完全読みで検証し、プレイヤーの連続正解日数を更新する
+    pub fn answer_puzzle(&self, player_id: uuid::Uuid, request: PuzzleAnswerRequest) -> AiBattleResult<PuzzleAnswerResponse> {
+        self.puzzles.answer(player_id, Utc::now().date_naive(), request)
+    }
+    
+    pub fn puzzle_streak(&self, player_id: uuid::Uuid) -> PuzzleStreak {
+        self.puzzles.streak(player_id, Utc::now().date_naive())
+    }
+    
+    /// 局面ハッシュから局面の情報を引く
+    pub fn lookup_position(&self, hash: &str) -> AiBattleResult<PositionReport> {
+        let parsed: PositionHash = hash.parse().map_err(|details| AiBattleError::BadRequest { details })?;
+        self.positions.lookup(parsed).ok_or_else(|| AiBattleError::PositionNotFound { hash: hash.to_string() })
+    }
+    
+    /// ロビーの公開募集に参加して対人戦を成立させる
+    pub fn accept_challenge(&self, challenge_id: uuid::Uuid, acceptor: uuid::Uuid) -> AiBattleResult<PvpSeatResponse> {
+        let open = self.lobby.take(challenge_id, acceptor)?;
+        self.start_challenge(open, acceptor)
+    }
+    
+    /// 招待トークンで非公開の募集に参加して対人戦を成立させる
+    pub fn accept_invitation(&self, invite_token: uuid::Uuid, acceptor: uuid::Uuid) -> AiBattleResult<PvpSeatResponse> {
+        let open = self.lobby.take_invite(invite_token, acceptor)?;
+        self.start_challenge(open, acceptor)
+    }
+    
+    /// 取り出した募集から対人戦のセッションを作成する
+    /// 作成に失敗した場合は募集をロビーに戻す
+    fn start_challenge(&self, open: OpenChallenge, acceptor: uuid::Uuid) -> AiBattleResult<PvpSeatResponse> {
+        let owner_color = open.owner_color();
+        let acceptor_color = owner_color.opposite();
+        let acceptor_token = uuid::Uuid::new_v4();
         
-        if session.ai_thinking {
-            return Err(AiBattleError::AiThinkingError { 
-                details: "AI is currently thinking".to_string() 
-            });
+        let seats = match owner_color {
+            Player::Black => SeatTokens::seated(open.player_token, acceptor_token),
+            Player::White => SeatTokens::seated(acceptor_token, open.player_token),
+        };
+        let mut session = AiBattleSession::new_pvp_seated(seats);
+        session.owners.set(owner_color, open.owner);
+        session.owners.set(acceptor_color, acceptor);
+        if let Some(account) = open.owner_account {
+            session.accounts.set(owner_color, account);
         }
+        session.clock = open.challenge.time_control.map(|time_control| GameClock::new(time_control, Utc::now()));
+        session.rated = open.challenge.rated;
         
-        if !ReversiRules::is_valid_move(&session.game_state.board, position, session.current_player) {
-            return Err(AiBattleError::InvalidMove { 
-                reason: format!("Invalid move at position {:?}", position) 
-            });
+        if let Err(err) = self.session_manager.insert_session(session.clone()) {
+            self.lobby.restore(open);
+            return Err(err);
         }
         
-        let _flipped_positions = ReversiRules::apply_move(&mut session.game_state, position)
-            .map_err(|e| AiBattleError::GameError(e))?;
-        
-        session.game_state.switch_player();
-        
-        // ゲーム終了チェック（両プレイヤーが手を打てない場合）
-        if ReversiRules::is_game_over(&session.game_state.board) {
-            let winner = ReversiRules::determine_winner(&session.game_state.board);
-            session.game_state.finish(winner);
-        }
+        let message = match owner_color {
+            Player::Black => "募集に対戦相手が参加しました。あなたは黒番です",
+            Player::White => "募集に対戦相手が参加しました。あなたは白番です",
+        };
+        self.notifications.notify(open.owner, NotificationKind::ChallengeAccepted, Some(session.id), message);
         
-        if session.game_state.is_finished() {
-            let winner = if let crate::game::GameStatus::Finished { winner, .. } = &session.game_state.game_status {
-                *winner
+        Ok(PvpSeatResponse {
+            game_state: AiBattleResponse::from_session(&session),
+            color: acceptor_color,
+            player_token: acceptor_token,
+            join_token: None,
+        })
+    }
+    
+    /// 指定した色の担当プレイヤーを記録する（以降の通知の宛先になる）
+    pub fn set_owner(&self, session_id: uuid::Uuid, player: Player, owner: uuid::Uuid) -> AiBattleResult<()> {
+        self.session_manager.modify_session(&session_id, |session| {
+            session.owners.set(player, owner);
+            Ok(())
+        })
+    }
+    
+    /// セッションの作成元としてAPIキーの利用者名を記録する
+    pub fn attribute_to_api_key(&self, session_id: uuid::Uuid, identity: Option<&ApiKeyIdentity>) -> AiBattleResult<()> {
+        let Some(ApiKeyIdentity(name)) = identity else {
+            return Ok(());
+        };
+        self.session_manager.modify_session(&session_id, |session| {
+            session.api_key_name = Some(name.clone());
+            Ok(())
+        })
+    }
+    
+    /// 終局時にレーティングを更新する対局として記録する
+    pub fn set_rated(&self, session_id: uuid::Uuid, rated: bool) -> AiBattleResult<()> {
+        self.session_manager.modify_session(&session_id, |session| {
+            session.rated = rated;
+            Ok(())
+        })
+    }
+    
+    /// 人間の手番のあいだにAIが応手を先読みするかを設定する
+    /// 有効にした時点で人間の手番であれば、すぐに先読みを始める
+    pub fn set_pondering(&self, session_id: uuid::Uuid, ponder: bool) -> AiBattleResult<AiBattleResponse> {
+        self.session_manager.modify_session(&session_id, |session| {
+            session.ponder = ponder;
+            if ponder {
+                self.start_pondering(session);
             } else {
-                None
-            };
-            session.status = GameStatus::Finished { winner };
-            session.current_player = session.game_state.current_player;
-            self.session_manager.update_session(session.clone())?;
-            
-            return Ok(MoveResponse {
-                success: true,
-                game_state: AiBattleResponse::from_session(&session),
-                player_move: position,
-                ai_move: None,
-                message: Some("Game finished".to_string()),
+                Self::stop_pondering(session);
+            }
+            Ok(AiBattleResponse::from_session(session))
+        })
+    }
+    
+    /// 対局の応答に現局面の評価値（評価バー）を含めるかを設定する
+    pub fn set_eval_bar(&self, session_id: uuid::Uuid, eval_bar: bool) -> AiBattleResult<AiBattleResponse> {
+        self.session_manager.modify_session(&session_id, |session| {
+            session.eval_bar = eval_bar;
+            Ok(AiBattleResponse::from_session(session))
+        })
+    }
+    
+    /// 適応難易度を設定する（人間対AIの対局のみ）
+    /// 有効にした時点のAIのレベルから調整を始める
+    pub fn set_adaptive(&self, session_id: uuid::Uuid, adaptive: bool) -> AiBattleResult<AiBattleResponse> {
+        self.session_manager.modify_session(&session_id, |session| {
+            if adaptive && session.kind() != SessionKind::HumanVsAi {
+                return Err(AiBattleError::BadRequest {
+                    details: "適応難易度は人間対AIの対局でのみ使えます".to_string(),
+                });
+            }
+            session.adaptive = adaptive.then(|| {
+                AdaptiveState::new(session.ai_level.unwrap_or(session.ai_difficulty.level()))
             });
+            Ok(AiBattleResponse::from_session(session))
+        })
+    }
+    
+    /// 着手の応答にAIの応手の解説を含めるか（教習モード）を設定する
+    pub fn set_teaching(&self, session_id: uuid::Uuid, teaching: bool) -> AiBattleResult<AiBattleResponse> {
+        self.session_manager.modify_session(&session_id, |session| {
+            session.teaching = teaching;
+            Ok(AiBattleResponse::from_session(session))
+        })
+    }
+    
+    /// 人間対AIの対局で人間の手番であれば、AIの応手の先読みを始める
+    fn start_pondering(&self, session: &mut AiBattleSession) {
+        Self::stop_pondering(session);
+        if !session.ponder || session.is_finished() || session.kind() != SessionKind::HumanVsAi || !session.is_player_turn() {
+            return;
         }
-        
-        session.current_player = session.game_state.current_player;
-        
-        if !session.is_ai_turn() {
-            self.session_manager.update_session(session.clone())?;
-            
-            return Ok(MoveResponse {
-                success: true,
-                game_state: AiBattleResponse::from_session(&session),
-                player_move: position,
-                ai_move: None,
-                message: Some(format!("Player continues, current_player: {:?}", session.current_player)),
-            });
+        let Some(ai_player) = session.player_color().map(Player::opposite) else { return };
+        let (level, params) = session.ai_params(ai_player);
+        session.ponder_state = Some(PonderState::start(
+            Arc::clone(&self.ai_service),
+            &session.game_state,
+            level,
+            params,
+            session.seed,
+        ));
+    }
+    
+    /// 進行中の先読みを中断する
+    fn stop_pondering(session: &mut AiBattleSession) {
+        if let Some(ponder) = session.ponder_state.take() {
+            ponder.cancel();
         }
-        
-        session.ai_thinking = true;
-        self.session_manager.update_session(session.clone())?;
-        
-        match self.process_ai_move(&mut session).await {
-            Ok(ai_position) => {
-                session.ai_thinking = false;
-                self.session_manager.update_session(session.clone())?;
-                
-                Ok(MoveResponse {
-                    success: true,
-                    game_state: AiBattleResponse::from_session(&session),
-                    player_move: position,
-                    ai_move: Some(ai_position),
-                    message: None,
-                })
+    }
+    
+    /// 観戦用の共有トークンを発行する（発行済みであれば同じトークンを返す）
+    pub fn share_session(&self, session_id: uuid::Uuid) -> AiBattleResult<ShareResponse> {
+        let share_token = self.session_manager.modify_session(&session_id, |session| {
+            Ok(*session.spectator_token.get_or_insert_with(uuid::Uuid::new_v4))
+        })?;
+        Ok(ShareResponse::new(session_id, share_token))
+    }
+    
+    /// 共有トークンから観戦対象のセッションIDを引く
+    pub fn resolve_share_token(&self, share_token: uuid::Uuid) -> AiBattleResult<uuid::Uuid> {
+        self.session_manager
+            .find_session(|session| session.spectator_token == Some(share_token))
+            .map(|session| session.id)
+            .ok_or(AiBattleError::ShareLinkNotFound)
+    }
+    
+    /// ログイン中のアカウントをセッションの所有者として記録する
+    pub fn set_account_owner(&self, session_id: uuid::Uuid, account: Option<&AccountIdentity>) -> AiBattleResult<()> {
+        let Some(account) = account else {
+            return Ok(());
+        };
+        self.session_manager.modify_session(&session_id, |session| {
+            session.owner_id = Some(account.account_id);
+            Ok(())
+        })
+    }
+    
+    /// ログイン中のアカウントを、対人戦でその色を担当するプレイヤーとして記録する（レーティングはこのアカウントに記録する）
+    pub fn set_seat_account(&self, session_id: uuid::Uuid, player: Player, account: Option<&AccountIdentity>) -> AiBattleResult<()> {
+        let Some(account) = account else {
+            return Ok(());
+        };
+        self.session_manager.modify_session(&session_id, |session| {
+            session.accounts.set(player, account.account_id);
+            Ok(())
+        })
+    }
+    
+    /// 所有者のいるセッションの着手（パス・待った・AIの手番を進める操作を含む）を所有者に限定する
+    /// 対人戦の着手は各色のプレイヤートークンで認可するため、所有者以外も指せる
+    pub fn authorize_play(&self, session_id: uuid::Uuid, account: Option<&AccountIdentity>) -> AiBattleResult<()> {
+        self.session_manager.with_session(&session_id, |session| {
+            if session.kind() == SessionKind::HumanVsHuman {
+                return Ok(());
             }
-            Err(ai_error) => {
-                session.ai_thinking = false;
-                self.session_manager.update_session(session)?;
-                Err(ai_error)
+            Self::check_owner(session, account)
+        })?
+    }
+    
+    /// 所有者のいるセッションの難易度変更・削除を所有者に限定する
+    pub fn authorize_manage(&self, session_id: uuid::Uuid, account: Option<&AccountIdentity>) -> AiBattleResult<()> {
+        self.session_manager.with_session(&session_id, |session| Self::check_owner(session, account))?
+    }
+    
+    fn check_owner(session: &AiBattleSession, account: Option<&AccountIdentity>) -> AiBattleResult<()> {
+        match (session.owner_id, account) {
+            (None, _) => Ok(()),
+            (Some(_), None) => Err(AiBattleError::LoginRequired),
+            (Some(owner), Some(account)) if owner == account.account_id => Ok(()),
+            (Some(_), Some(_)) => Err(AiBattleError::NotSessionOwner),
+        }
+    }
+    
+    /// 終局を対局の参加者に通知する
+    /// レーティング対象の対局では両者のレーティングを更新し、その変動をセッションに記録する
+    /// 人間対AIの対局は難易度別の統計にも加える
+    /// セッションマネージャーが保持するセッションをロックしたまま呼び出し、変動をそのセッションに直接記録する
+    fn publish_finished(&self, session: &mut AiBattleSession) {
+        let GameStatus::Finished { winner } = session.status else {
+            return;
+        };
+        if session.rating_changes.is_empty() {
+            session.rating_changes = self.ratings.record_game(session);
+        }
+        self.difficulty_stats.record_game(session);
+        self.events.publish(session.id, SessionEvent::GameFinished { game_id: session.id, winner });
+        if let Some(game) = ArchivedGame::from_session(session) {
+            self.positions.record_game(&game);
+            // 終盤の完全読みは時間がかかるため、ランタイム上ではバックグラウンドで行う
+            let (puzzles, positions) = (Arc::clone(&self.puzzles), Arc::clone(&self.positions));
+            match tokio::runtime::Handle::try_current() {
+                Ok(runtime) => {
+                    let game = game.clone();
+                    runtime.spawn_blocking(move || puzzles.mine(&game, &positions));
+                }
+                Err(_) => {
+                    puzzles.mine(&game, &positions);
+                }
             }
+            self.archive().record(game);
+        }
+        
+        let message = match (winner, session.lost_on_time()) {
+            (Some(Player::Black), Some(_)) => "白の時間切れにより黒の勝ちで対局が終了しました",
+            (Some(Player::White), Some(_)) => "黒の時間切れにより白の勝ちで対局が終了しました",
+            (Some(Player::Black), None) => "黒の勝ちで対局が終了しました",
+            (Some(Player::White), None) => "白の勝ちで対局が終了しました",
+            (None, _) => "引き分けで対局が終了しました",
+        };
+        for owner in session.owners.all() {
+            self.notifications.notify(owner, NotificationKind::GameFinished, Some(session.id), message);
         }
     }
     
-    async fn process_ai_move(&self, session: &mut AiBattleSession) -> AiBattleResult<Position> {
-        let ai_result = self.ai_service.calculate_move(&session.game_state, session.ai_difficulty).await
-            .map_err(|e| AiBattleError::AiThinkingError { 
-                details: format!("AI service error: {}", e) 
-            })?;
+    /// 着手後の状態を対局の参加者に通知する
+    fn publish_move(&self, session: &mut AiBattleSession, mover: Player, position: Position) {
+        self.positions.observe(&session.game_state.board, session.current_player);
+        self.events.publish(session.id, SessionEvent::move_made(session, mover, Some(position)));
+        if session.is_finished() {
+            self.publish_finished(session);
+            return;
+        }
         
-        let ai_position = ai_result.position;
+        // AIの応手は着手リクエストのレスポンスで返るため通知しない
+        let next = session.current_player;
+        if next == mover || session.controller(mover).is_ai() || session.controller(next).is_ai() {
+            return;
+        }
         
-        let move_record = MoveRecord::new(
-            Player::White,
-            ai_position,
-            Some(ai_result.thinking_time_ms),
-        );
-        session.add_move_record(move_record);
+        if let Some(owner) = session.owners.get(next) {
+            if session.owners.get(mover) != Some(owner) {
+                let message = format!("相手が({}, {})に着手しました。あなたの手番です", position.row, position.col);
+                self.notifications.notify(owner, NotificationKind::OpponentMoved, Some(session.id), message);
+            }
+        }
+    }
+    
+    /// パスをイベントの購読者に配信する
+    fn publish_pass(&self, session: &AiBattleSession, player: Player) {
+        self.positions.observe(&session.game_state.board, session.current_player);
+        self.events.publish(session.id, SessionEvent::move_made(session, player, None));
+    }
+    
+    /// AIの思考状態の変化をイベントの購読者に配信する
+    fn publish_ai_thinking(&self, session: &AiBattleSession, player: Player) {
+        let event = SessionEvent::AiThinking { game_id: session.id, player, thinking: session.ai_thinking };
+        self.events.publish(session.id, event);
+    }
+    
+    pub async fn create_ai_battle(&self, difficulty: AiDifficulty) -> AiBattleResult<AiBattleResponse> {
+        self.create_ai_battle_with_color(difficulty, Player::Black).await
+    }
+    
+    /// 人間の担当色を指定してAI対戦を作成する
+    /// 人間が白番の場合はAIの初手を打った状態を返す
+    pub async fn create_ai_battle_with_color(
+        &self,
+        difficulty: AiDifficulty,
+        player_color: Player,
+    ) -> AiBattleResult<AiBattleResponse> {
+        self.create_ai_battle_with_options(difficulty, player_color, None).await
+    }
+    
+    /// 人間の担当色と持ち時間を指定してAI対戦を作成する
+    pub async fn create_ai_battle_with_options(
+        &self,
+        difficulty: AiDifficulty,
+        player_color: Player,
+        time_control: Option<TimeControl>,
+    ) -> AiBattleResult<AiBattleResponse> {
+        self.create_ai_battle_with_config(difficulty, AiSetup::default(), player_color, time_control).await
+    }
+    
+    /// AIを数値レベルで指定してAI対戦を作成する
+    pub async fn create_ai_battle_at_level(
+        &self,
+        level: AiLevel,
+        player_color: Player,
+        time_control: Option<TimeControl>,
+    ) -> AiBattleResult<AiBattleResponse> {
+        let setup = AiSetup { level: Some(level), ..AiSetup::default() };
+        self.create_ai_battle_with_config(level.into(), setup, player_color, time_control).await
+    }
+    
+    /// AIの強さ（レベルを指定した場合はレベルを優先）・パーソナリティ・乱択のシードと、
+    /// この対局だけのAIの設定の上書きを指定してAI対戦を作成する
+    pub async fn create_ai_battle_with_config(
+        &self,
+        difficulty: AiDifficulty,
+        setup: AiSetup,
+        player_color: Player,
+        time_control: Option<TimeControl>,
+    ) -> AiBattleResult<AiBattleResponse> {
+        let AiSetup { level, overrides, personality, seed, board_size, variant, handicap, start_position } = setup;
+        let board_size = board_size
+            .map(validate_board_size)
+            .transpose()
+            .map_err(|details| AiBattleError::BadRequest { details })?;
+        let handicap = handicap
+            .map(Handicap::validate)
+            .transpose()
+            .map_err(|details| AiBattleError::BadRequest { details })?;
+        let (start_position, board_size) = match start_position {
+            Some(position) => {
+                let (position, size) = Self::check_start_position(position, variant, board_size, handicap)?;
+                (Some(position), Some(size))
+            }
+            None => (None, board_size),
+        };
+        let start = (board_size.is_some() || variant.is_some() || handicap.is_some())
+            .then(|| (board_size.unwrap_or(DEFAULT_BOARD_SIZE), variant.unwrap_or_default()));
+        let base = level.unwrap_or(difficulty.level()).params();
+        let base = personality.map_or(base, |personality| personality.apply(base));
+        let ai_config = match overrides {
+            Some(overrides) => Some(overrides.apply(base).map_err(|details| AiBattleError::BadRequest { details })?),
+            None => personality.map(|_| base),
+        };
+        let difficulty = level.map_or(difficulty, AiDifficulty::from);
         
-        let _flipped_positions = ReversiRules::apply_move(&mut session.game_state, ai_position)
-            .map_err(|e| AiBattleError::GameError(e))?;
+        let session_id = self.session_manager
+            .create_session_with_color(difficulty, player_color)
+            .await?;
         
-        session.game_state.switch_player();
+        if level.is_some() || ai_config.is_some() || seed.is_some() || time_control.is_some() || start.is_some() {
+            self.session_manager.modify_session(&session_id, |session| {
+                if let Some(level) = level {
+                    session.set_ai_level(level);
+                }
+                session.seed = seed.or(session.seed);
+                if let Some((board_size, variant)) = start {
+                    session.set_start(board_size, variant, handicap, start_position);
+                }
+                session.personality = personality;
+                session.ai_config = ai_config;
+                session.clock = time_control.map(|time_control| GameClock::new(time_control, Utc::now()));
+                Ok(())
+            })?;
+        }
         
-        // ゲーム終了チェック（両プレイヤーが手を打てない場合）
-        if ReversiRules::is_game_over(&session.game_state.board) {
-            let winner = ReversiRules::determine_winner(&session.game_state.board);
-            session.game_state.finish(winner);
+        if self.session_manager.get_session(&session_id)?.is_ai_turn() {
+            if let Err(err) = self.step_game(session_id).await {
+                // 初手を打てないセッションは残さない
+                let _ = self.session_manager.remove_session(&session_id);
+                return Err(err);
+            }
         }
         
-        if session.game_state.is_finished() {
-            let winner = if let crate::game::GameStatus::Finished { winner, .. } = &session.game_state.game_status {
-                *winner
-            } else {
-                None
-            };
-            session.status = GameStatus::Finished { winner };
+        let session = self.session_manager.get_session(&session_id)?;
+        Ok(AiBattleResponse::from_session(&session))
+    }
+    
+    /// 開始局面を検証し、盤面の文字列を正規の形に直したものと盤面の大きさを返す
+    fn check_start_position(
+        position: StartPosition,
+        variant: Option<GameVariant>,
+        board_size: Option<usize>,
+        handicap: Option<Handicap>,
+    ) -> AiBattleResult<(StartPosition, usize)> {
+        let bad_request = |details: String| AiBattleError::BadRequest { details };
+        if handicap.is_some() {
+            return Err(bad_request("開始局面と置き石のハンデは同時に指定できません".to_string()));
+        }
+        let position = position.normalized().map_err(bad_request)?;
+        let size = position.game_state(variant.unwrap_or_default()).map_err(bad_request)?.board_size();
+        if board_size.is_some_and(|board_size| board_size != size) {
+            return Err(bad_request(format!("開始局面の盤面の大きさ（{}x{}）が board_size と異なります", size, size)));
         }
+        Ok((position, size))
+    }
+    
+    /// AI同士の対戦を作成する
+    /// `play_out` がtrueの場合は終局まで進めた状態を返す
+    pub async fn create_ai_vs_ai(
+        &self,
+        black_difficulty: AiDifficulty,
+        white_difficulty: AiDifficulty,
+        play_out: bool,
+    ) -> AiBattleResult<AiBattleResponse> {
+        let session_id = self.session_manager
+            .create_ai_vs_ai_session(black_difficulty, white_difficulty)
+            .await?;
         
-        session.current_player = session.game_state.current_player;
+        if play_out {
+            while !self.session_manager.get_session(&session_id)?.is_finished() {
+                self.step_game(session_id).await?;
+            }
+        }
         
-        Ok(ai_position)
+        self.get_game_state(session_id)
     }
     
-    pub fn get_move_history(&self, session_id: uuid::Uuid) -> AiBattleResult<Vec<MoveRecord>> {
-        let session = self.session_manager.get_session(&session_id)?;
+    /// シードを固定したAI同士の対局を終局まで進め、棋譜と最終状態を返す
+    /// 結果はフィクスチャとして使えるよう、対局後にセッションを破棄する
+    pub async fn simulate_game(
+        &self,
+        black_difficulty: AiDifficulty,
+        white_difficulty: AiDifficulty,
+        seed: u64,
+    ) -> AiBattleResult<SimulateGameResponse> {
+        let session_id = self.session_manager
+            .create_seeded_ai_vs_ai_session(black_difficulty, white_difficulty, seed)
+            .await?;
         
-        let move_records: Vec<MoveRecord> = session.game_state.move_history
+        let played = async {
+            while !self.session_manager.get_session(&session_id)?.is_finished() {
+                self.step_game(session_id).await?;
+            }
+            self.session_manager.get_session(&session_id)
+        }
+        .await;
+        let _ = self.session_manager.remove_session(&session_id);
+        let session = played?;
+        
+        let transcript = session.move_history
             .iter()
-            .map(|game_move| MoveRecord::from_move(game_move, None))
+            .filter_map(|record| record.position.map(|position| (record.player, position)))
+            .enumerate()
+            .map(|(index, (player, position))| TranscriptMove {
+                ply: index as u32 + 1,
+                player,
+                position,
+            })
             .collect();
         
-        Ok(move_records)
+        Ok(SimulateGameResponse {
+            seed,
+            black_difficulty,
+            white_difficulty,
+            transcript,
+            final_state: AiBattleResponse::from_session(&session),
+        })
     }
     
-    pub fn list_sessions(&self) -> Vec<AiBattleSession> {
-        self.session_manager.list_sessions()
+    /// 対人戦を作成し、作成者を黒番として着席させる
+    pub async fn create_pvp(&self) -> AiBattleResult<PvpSeatResponse> {
+        let session_id = self.session_manager.create_pvp_session().await?;
+        let session = self.session_manager.get_session(&session_id)?;
+        let seats = session.seat_tokens.clone()
+            .ok_or_else(|| AiBattleError::InternalError { details: "PvP session has no seats".to_string() })?;
+        
+        Ok(PvpSeatResponse {
+            game_state: AiBattleResponse::from_session(&session),
+            color: Player::Black,
+            player_token: seats.black,
+            join_token: seats.join_token,
+        })
     }
     
-    pub fn delete_session(&self, session_id: uuid::Uuid) -> AiBattleResult<()> {
-        self.session_manager.remove_session(&session_id)?;
-        Ok(())
+    /// 参加トークンを使って対人戦に白番として参加する
+    pub fn join_pvp(&self, session_id: uuid::Uuid, join_token: uuid::Uuid) -> AiBattleResult<PvpSeatResponse> {
+        // 席の確認と確保はロックしたまま行い、同時に参加した側や並行する着手の変更を上書きしない
+        self.session_manager.with_session_mut(&session_id, |session| {
+            let seats = session.seat_tokens.as_mut().ok_or(AiBattleError::InvalidJoinToken)?;
+            
+            if seats.white.is_some() {
+                return Err(AiBattleError::SeatAlreadyTaken);
+            }
+            if seats.join_token != Some(join_token) {
+                return Err(AiBattleError::InvalidJoinToken);
+            }
+            
+            let player_token = uuid::Uuid::new_v4();
+            seats.white = Some(player_token);
+            seats.join_token = None;
+            
+            Ok(PvpSeatResponse {
+                game_state: AiBattleResponse::from_session(session),
+                color: Player::White,
+                player_token,
+                join_token: None,
+            })
+        })
     }
     
-    pub fn change_difficulty(&self, session_id: uuid::Uuid, new_difficulty: AiDifficulty) -> AiBattleResult<AiBattleResponse> {
-        let mut session = self.session_manager.get_session(&session_id)?;
-        
-        if session.ai_thinking {
-            return Err(AiBattleError::AiThinkingError { 
-                details: "Cannot change difficulty while AI is thinking".to_string() 
-            });
-        }
+    /// AIの手番を1手だけ進める
+    pub async fn step_game(&self, session_id: uuid::Uuid) -> AiBattleResult<StepResponse> {
+        let _move_guard = self.session_manager.begin_move(&session_id)?;
+        let turn = self.session_manager.with_session_mut(&session_id, |session| {
+            if session.is_finished() {
+                return Err(AiBattleError::GameAlreadyFinished);
+            }
+            
+            if session.ai_thinking {
+                return Err(AiBattleError::AiThinkingError { 
+                    details: "AI is currently thinking".to_string() 
+                });
+            }
+            
+            if !session.is_ai_turn() {
+                return Err(AiBattleError::NotAiTurn);
+            }
+            self.ai_service.ensure_capacity().map_err(AiBattleError::from_ai_service)?;
+            
+            Ok(self.begin_ai_turn(session))
+        })?;
+        let player = turn.player;
+        let (ai_move, _, game_state) = self.run_ai_turn(session_id, turn).await?;
+        let finished = matches!(game_state.status, GameStatus::Finished { .. });
         
-        session.ai_difficulty = new_difficulty;
-        self.session_manager.update_session(session.clone())?;
+        Ok(StepResponse {
+            success: true,
+            game_state,
+            player,
+            ai_move: ai_move.position,
+            message: finished.then(|| "Game finished".to_string()),
+        })
+    }
+    
+    /// 復元直後の人間対AIの対局のうち、AIの手番で止まっているものの応手を指す
+    /// 思考中に再起動した対局は誰も手番を進められなくなるため、起動時に呼び出す。
+    /// AI同士の対局はクライアントが `step` で進めるため対象外。応手を指した対局数を返す
+    pub async fn resume_pending_ai_turns(&self) -> usize {
+        let pending = self.session_manager.session_ids(|session| {
+            session.kind() == SessionKind::HumanVsAi && !session.is_finished() && session.is_ai_turn()
+        });
         
-        Ok(AiBattleResponse::from_session(&session))
+        let mut resumed = 0;
+        for session_id in pending {
+            let Ok(_move_guard) = self.session_manager.begin_move(&session_id) else {
+                continue;
+            };
+            match self.play_ai_reply(session_id).await {
+                Ok(_) => resumed += 1,
+                Err(e) => eprintln!("警告: 対局 {} のAIの応手を再開できません: {}", session_id, e),
+            }
+        }
+        resumed
     }
     
-    pub fn is_ai_thinking(&self, session_id: uuid::Uuid) -> AiBattleResult<bool> {
-        self.session_manager.is_ai_thinking(&session_id)
+    pub fn get_game_state(&self, session_id: uuid::Uuid) -> AiBattleResult<AiBattleResponse> {
+        self.view_game_state(session_id, AiBattleResponse::from_session)
     }
     
-    pub async fn cleanup_inactive_sessions(&self) -> usize {
-        self.session_manager.cleanup_inactive_sessions().await
+    /// `since` の版から現在までの差分を返す（差分を作れない版であれば全体を返す）
+    pub fn get_game_state_since(&self, session_id: uuid::Uuid, since: u64) -> AiBattleResult<GameStateView> {
+        self.view_game_state(session_id, |session| match AiBattleDelta::from_session(session, since) {
+            Some(delta) => GameStateView::Delta(delta),
+            None => GameStateView::Full(Box::new(AiBattleResponse::from_session(session))),
+        })
     }
     
-    pub fn get_service_stats(&self) -> ServiceStats {
-        let session_stats = self.session_manager.get_stats();
+    /// 対局のイベントを購読する
+    /// 購読後に状態を確認し、終局済みであれば終局イベントだけを流して終わる
+    pub fn subscribe_events(&self, session_id: uuid::Uuid) -> AiBattleResult<impl Stream<Item = SessionEvent> + Send> {
+        self.get_game_state(session_id)?;
         
-        ServiceStats {
-            total_sessions: session_stats.total_sessions,
-            max_sessions: session_stats.max_sessions,
-            ai_thinking_count: session_stats.ai_thinking_count,
-            difficulty_distribution: session_stats.difficulty_counts,
+        let receiver = self.events.subscribe(session_id);
+        let initial = match self.get_game_state(session_id)?.status {
+            GameStatus::Finished { winner } => Some(SessionEvent::GameFinished { game_id: session_id, winner }),
+            GameStatus::InProgress => None,
+        };
+        Ok(session_events(receiver, initial))
+    }
+    
+    /// 対局の状態が変化する（着手・パス・AIの思考状態の変化・終局）か、`timeout` が経過するまで待つ
+    /// `version` が現在の版と異なる場合は待たずに応答する
+    pub async fn wait_for_change(
+        &self,
+        session_id: uuid::Uuid,
+        version: Option<u64>,
+        timeout: Duration,
+    ) -> AiBattleResult<WaitResponse> {
+        self.get_game_state(session_id)?;
+        
+        // 確認と購読のあいだの変化を取りこぼさないよう、先に購読してから現在の版を確かめる
+        let mut receiver = self.events.subscribe(session_id);
+        let current = self.get_game_state(session_id)?;
+        if version.is_some_and(|version| version != current.version) {
+            return Ok(WaitResponse { changed: true, game_state: current });
         }
+        if matches!(current.status, GameStatus::Finished { .. }) {
+            return Ok(WaitResponse { changed: false, game_state: current });
+        }
+        
+        // 取りこぼしがあっても変化があったことに変わりはない。チャネルが閉じた場合はセッションが削除されている
+        let changed = tokio::time::timeout(timeout, receiver.recv()).await.is_ok();
+        Ok(WaitResponse { changed, game_state: self.get_game_state(session_id)? })
+    }
+    
+    /// 現局面を返し、人間の思考時間の起点として最初に取得した時刻を記録する
+    fn view_game_state<T>(
+        &self,
+        session_id: uuid::Uuid,
+        view: impl FnOnce(&AiBattleSession) -> T,
+    ) -> AiBattleResult<T> {
+        let (seen, response) = self.session_manager.with_session(&session_id, |session| {
+            (session.position_seen_at.is_some(), view(session))
+        })?;
+        // 取得のたびに保存しないよう、局面が変わってから最初の取得だけを記録する
+        if !seen {
+            self.session_manager.with_session_mut(&session_id, |session| {
+                session.mark_position_seen(Utc::now());
+                Ok(())
+            })?;
+        }
+        Ok(response)
+    }
+    
+    /// 対局の盤面の一辺のマス数
+    pub fn board_size(&self, session_id: uuid::Uuid) -> AiBattleResult<usize> {
+        self.session_manager.with_session(&session_id, AiBattleSession::board_size)
+    }
+    
+    pub async fn make_player_move(
+        &self, 
+        session_id: uuid::Uuid, 
+        position: Position
+    ) -> AiBattleResult<MoveResponse> {
+        self.make_player_move_as(session_id, position, None).await
+    }
+    
+    /// プレイヤートークンを指定して着手する
+    /// 対人戦ではトークンに対応する色の手番でなければ着手できない
+    pub async fn make_player_move_as(
+        &self, 
+        session_id: uuid::Uuid, 
+        position: Position,
+        player_token: Option<uuid::Uuid>,
+    ) -> AiBattleResult<MoveResponse> {
+        // AIの応手を指し終えるまで、同じ対局の他の着手は受け付けない
+        let _move_guard = self.session_manager.begin_move(&session_id)?;
+        // セッションを複製せず、ロックしたまま着手を反映する
+        let (flipped, passed, ai_turn, teaching, game_state) = self.session_manager.with_session_mut(&session_id, |session| {
+            self.settle_clock(session)?;
+            Self::check_human_turn(session, player_token)?;
+            
+            if let Some(rejection) = MoveRejection::check(&session.game_state, position, session.current_player) {
+                return Err(rejection.into_error(position));
+            }
+            self.ensure_ai_reply_capacity(session)?;
+            
+            let mover = session.current_player;
+            let thinking_time_ms = session.thinking_time_ms(Utc::now());
+            let flipped = ReversiRules::apply_move(&mut session.game_state, position)
+                .map_err(AiBattleError::GameError)?;
+            let seq = session.record_placement();
+            session.add_move_record(MoveRecord { seq, ..MoveRecord::new(mover, position, Some(thinking_time_ms)) });
+            
+            let passed = session.advance_turn();
+            if !session.is_ai_turn() {
+                // AIがパスして人間の手番が続く場合は、新しい局面で先読みし直す
+                self.start_pondering(session);
+            }
+            self.publish_move(session, mover, position);
+            if let Some(player) = passed {
+                self.publish_pass(session, player);
+            }
+            let ai_turn = !session.is_finished() && session.is_ai_turn();
+            // 教習モードではAIの応手を解説するため、応手の前の局面と評価の重みを控えておく
+            let teaching = (session.teaching && ai_turn).then(|| {
+                let (_, params) = session.ai_params(session.current_player);
+                (session.game_state.clone(), params.weights.weights())
+            });
+            Ok((flipped, passed, ai_turn, teaching, AiBattleResponse::from_session(session)))
+        })?;
+        
+        if matches!(game_state.status, GameStatus::Finished { .. }) {
+            return Ok(MoveResponse {
+                success: true,
+                game_state,
+                player_move: position,
+                flipped_positions: flipped,
+                ai_move: None,
+                ai_flipped_positions: None,
+                passed,
+                message: Some("Game finished".to_string()),
+                explanation: None,
+                ai_principal_variation: None,
+            });
+        }
+        
+        if !ai_turn {
+            let message = match passed {
+                Some(player) => format!("{:?} has no valid moves and passed", player),
+                None => format!("Player continues, current_player: {:?}", game_state.current_player),
+            };
+            
+            return Ok(MoveResponse {
+                success: true,
+                game_state,
+                player_move: position,
+                flipped_positions: flipped,
+                ai_move: None,
+                ai_flipped_positions: None,
+                passed,
+                message: Some(message),
+                explanation: None,
+                ai_principal_variation: None,
+            });
+        }
+        
+        let (ai_move, ai_result, game_state) = self.play_ai_reply(session_id).await?;
+        let explanation = teaching.and_then(|(state, weights)| explain_move(&state, ai_move.position, &weights)).map(|explanation| {
+            explanation.with_searched(ai_result.alternatives.unwrap_or_default().into_iter().map(ScoredMove::from).collect())
+        });
+        
+        Ok(MoveResponse {
+            success: true,
+            game_state,
+            player_move: position,
+            flipped_positions: flipped,
+            ai_move: Some(ai_move.position),
+            ai_flipped_positions: Some(ai_move.flipped),
+            passed,
+            message: None,
+            explanation,
+            ai_principal_variation: ai_result.principal_variation,
+        })
+    }
+    
+    /// 合法手のない人間の手番でパスする
+    /// 相手がAIの場合は続けてAIの応手を進める
+    pub async fn pass_turn(
+        &self,
+        session_id: uuid::Uuid,
+        player_token: Option<uuid::Uuid>,
+    ) -> AiBattleResult<PassResponse> {
+        let _move_guard = self.session_manager.begin_move(&session_id)?;
+        let (player, ai_turn, game_state) = self.session_manager.with_session_mut(&session_id, |session| {
+            self.settle_clock(session)?;
+            Self::check_human_turn(session, player_token)?;
+            
+            self.ensure_ai_reply_capacity(session)?;
+            let player = session.current_player;
+            session.pass_turn()?;
+            self.publish_pass(session, player);
+            Ok((player, session.is_ai_turn(), AiBattleResponse::from_session(session)))
+        })?;
+        
+        let (ai_move, game_state) = if ai_turn {
+            let (ai_move, _, game_state) = self.play_ai_reply(session_id).await?;
+            (Some(ai_move.position), game_state)
+        } else {
+            (None, game_state)
+        };
+        
+        Ok(PassResponse {
+            success: true,
+            game_state,
+            player,
+            ai_move,
+            message: Some(format!("{:?} passed", player)),
+        })
+    }
+    
+    /// 手番側が時間切れであれば終局として通知し、`TimeExpired` を返す
+    /// セッションマネージャーが保持するセッションをロックしたまま呼び出す
+    fn settle_clock(&self, session: &mut AiBattleSession) -> AiBattleResult<()> {
+        if !session.settle_clock(Utc::now()) {
+            return Ok(());
+        }
+        self.publish_finished(session);
+        Err(AiBattleError::TimeExpired)
+    }
+    
+    /// 持ち時間のある対局の時間切れを判定し、通信対局の手番側に催促を送る
+    pub fn sweep_clocks(&self, now: chrono::DateTime<Utc>) -> ClockSweep {
+        let mut sweep = ClockSweep::default();
+        let clocked = self.session_manager
+            .session_ids(|session| session.clock.is_some() && !session.is_finished() && !session.ai_thinking);
+        
+        for session_id in clocked {
+            let _ = self.session_manager.with_session_mut(&session_id, |session| {
+                if session.settle_clock(now) {
+                    self.publish_finished(session);
+                    sweep.flagged += 1;
+                    return Ok(());
+                }
+                
+                let to_move = session.current_player;
+                let owner = session.owners.get(to_move);
+                let Some(clock) = session.clock.as_mut() else { return Ok(()) };
+                if clock.reminder_due(to_move, now, self.reminder_after) {
+                    clock.mark_reminded();
+                    if let Some(owner) = owner {
+                        self.notifications.notify(owner, NotificationKind::TurnReminder, Some(session.id), "持ち時間が残りわずかです。着手してください");
+                    }
+                    sweep.reminded += 1;
+                }
+                Ok(())
+            });
+        }
+        
+        sweep
+    }
+    
+    /// 人間が着手・パスできる状態かを確認する
+    /// 対人戦ではトークンに対応する色の手番でなければならない
+    fn check_human_turn(session: &AiBattleSession, player_token: Option<uuid::Uuid>) -> AiBattleResult<()> {
+        if session.is_finished() {
+            return Err(AiBattleError::GameAlreadyFinished);
+        }
+        
+        if let Some(seats) = &session.seat_tokens {
+            let color = player_token
+                .and_then(|token| seats.color_of(token))
+                .ok_or(AiBattleError::InvalidPlayerToken)?;
+            if color != session.current_player {
+                return Err(AiBattleError::NotPlayerTurn);
+            }
+        }
+        
+        if !session.is_player_turn() {
+            return Err(AiBattleError::NotPlayerTurn);
+        }
+        
+        if session.ai_thinking {
+            return Err(AiBattleError::AiThinkingError { 
+                details: "AI is currently thinking".to_string() 
+            });
+        }
+        
+        Ok(())
+    }
+    
+    /// 相手がAIの対局では、人間の着手やパスを反映する前にAIの応手を計算できるかを確認する
+    /// 計算待ちが満杯であれば何も変更せずに503で断り、再試行した着手がそのまま受け付けられるようにする
+    fn ensure_ai_reply_capacity(&self, session: &AiBattleSession) -> AiBattleResult<()> {
+        if !session.controller(session.current_player.opposite()).is_ai() {
+            return Ok(());
+        }
+        self.ai_service.ensure_capacity().map_err(AiBattleError::from_ai_service)
+    }
+    
+    /// AIの応手を指す
+    async fn play_ai_reply(&self, session_id: uuid::Uuid) -> AiBattleResult<(Move, AIMoveResult, AiBattleResponse)> {
+        let turn = self.session_manager.with_session_mut(&session_id, |session| Ok(self.begin_ai_turn(session)))?;
+        self.run_ai_turn(session_id, turn).await
+    }
+    
+    /// AIの手番を始める：思考中フラグを立て、応手の計算に必要な設定と局面を取り出す
+    /// セッションマネージャーが保持するセッションをロックしたまま呼び出す
+    fn begin_ai_turn(&self, session: &mut AiBattleSession) -> AiTurn {
+        let player = session.current_player;
+        if let Some(adaptive) = session.adaptive.as_mut() {
+            adaptive.adjust(&session.game_state.board, player, session.game_state.variant);
+        }
+        let (level, params) = session.ai_params(player);
+        session.ai_thinking = true;
+        self.publish_ai_thinking(session, player);
+        
+        // 人間が先読みした手を指していれば、計算済みの応手をそのまま使う
+        let pondered = session.ponder_state.take().and_then(|ponder| {
+            ponder.cancel();
+            ponder.reply_for(&session.game_state, level, params, session.seed)
+        });
+        AiTurn {
+            player,
+            difficulty: session.controller(player).difficulty().unwrap_or(session.ai_difficulty),
+            level,
+            params,
+            custom: session.ai_config.is_some() || session.adaptive.is_some(),
+            at_level: session.ai_level.is_some(),
+            seed: session.seed,
+            multipv: if session.teaching { MAX_CONSIDERED_MOVES as u8 } else { 0 },
+            pondered,
+            state: session.game_state.clone(),
+        }
+    }
+    
+    /// AIの手番の応手を計算する（先読み済みであればその結果を使う）
+    async fn calculate_ai_turn(&self, turn: &AiTurn) -> Result<AIMoveResult, AIError> {
+        let start_time = std::time::Instant::now();
+        let state = &turn.state;
+        // 候補手は着手の記録に残す設定には含めない
+        let params = LevelParams { multipv: turn.multipv, ..turn.params };
+        match (turn.pondered.clone(), turn.custom || turn.multipv > 0, turn.at_level, turn.seed) {
+            (Some(reply), ..) => Ok(AIMoveResult { thinking_time_ms: start_time.elapsed().as_millis() as u64, ..reply }),
+            (None, true, _, seed) => self.ai_service.calculate_move_with_params(state, turn.level, params, seed).await,
+            (None, false, true, seed) => self.ai_service.calculate_move_at_level(state, turn.level, seed).await,
+            (None, false, false, Some(seed)) => self.ai_service.calculate_move_seeded(state, turn.difficulty, seed).await,
+            (None, false, false, None) => self.ai_service.calculate_move(state, turn.difficulty).await,
+        }
+    }
+    
+    /// セッションのロックを外して応手を計算し、結果をセッションに反映する
+    /// 思考中は局面の複製だけを使うため、計算のあいだも他のリクエストはセッションを参照できる
+    /// 指した手とその着手で返った石、AIの計算結果（候補手・読み筋）を返す
+    async fn run_ai_turn(&self, session_id: uuid::Uuid, turn: AiTurn) -> AiBattleResult<(Move, AIMoveResult, AiBattleResponse)> {
+        let ai_result = loop {
+            match self.calculate_ai_turn(&turn).await {
+                // 手番を始めた後は断らず、計算待ちが空くまで待って計算し直す（受け付けた人間の着手を宙に浮かせない）
+                Err(AIError::Overloaded { retry_after_secs }) => sleep(Duration::from_secs(retry_after_secs)).await,
+                result => break result,
+            }
+        };
+        
+        self.session_manager.with_session_mut(&session_id, |session| {
+            session.ai_thinking = false;
+            self.publish_ai_thinking(session, turn.player);
+            let ai_result = ai_result
+                .map_err(AiBattleError::from_ai_service)?;
+            
+            let ai_position = ai_result.position;
+            let flipped = ReversiRules::apply_move(&mut session.game_state, ai_position)
+                .map_err(AiBattleError::GameError)?;
+            
+            let mut move_record = MoveRecord::new(
+                turn.player,
+                ai_position,
+                Some(ai_result.thinking_time_ms),
+            );
+            move_record.ai_params = Some(turn.params);
+            move_record.seq = session.record_placement();
+            session.add_move_record(move_record);
+            
+            session.advance_turn();
+            self.publish_move(session, turn.player, ai_position);
+            
+            if session.ponder && !session.is_finished() {
+                self.start_pondering(session);
+            }
+            Ok((Move::new(turn.player, ai_position, flipped), ai_result, AiBattleResponse::from_session(session)))
+        })
+    }
+    
+    /// プレイヤーの現局面に対する推奨手を計算する
+    /// `multipv` を指定すると評価値の高い順の候補手も返す（先読みをしない難易度では返さない）
+    /// セッションの状態は変更しない
+    pub async fn get_hint(
+        &self,
+        session_id: uuid::Uuid,
+        difficulty: Option<AiDifficulty>,
+        multipv: Option<u8>,
+    ) -> AiBattleResult<HintResponse> {
+        if let Some(multipv) = multipv.filter(|multipv| !(1..=LevelParams::MAX_MULTIPV).contains(multipv)) {
+            return Err(AiBattleError::BadRequest {
+                details: format!("multipv は1〜{}の範囲で指定してください: {}", LevelParams::MAX_MULTIPV, multipv),
+            });
+        }
+
+        let session = self.session_manager.get_session(&session_id)?;
+        
+        if session.is_finished() {
+            return Err(AiBattleError::GameAlreadyFinished);
+        }
+        
+        if !session.is_player_turn() {
+            return Err(AiBattleError::NotPlayerTurn);
+        }
+        
+        let difficulty = difficulty.unwrap_or(session.ai_difficulty);
+        let ai_result = match multipv {
+            Some(multipv) => {
+                let level = difficulty.level();
+                let params = LevelParams { multipv, ..level.params() };
+                self.ai_service.calculate_move_with_params(&session.game_state, level, params, None).await
+            }
+            None => self.ai_service.calculate_move(&session.game_state, difficulty).await,
+        }
+            .map_err(AiBattleError::from_ai_service)?;
+        
+        // AIが評価値を返さない場合は着手後の静的評価で補う
+        let evaluation_score = ai_result.evaluation_score.or_else(|| {
+            let mut preview = session.game_state.clone();
+            ReversiRules::apply_move(&mut preview, ai_result.position).ok()?;
+            let score = BoardEvaluator::evaluate_for_variant(
+                &preview.board,
+                session.current_player,
+                &EvalWeights::default(),
+                preview.variant,
+            );
+            Some(score as f64)
+        });
+        
+        Ok(HintResponse {
+            game_id: session_id,
+            suggested_move: ai_result.position,
+            evaluation_score,
+            depth_reached: ai_result.depth_reached,
+            difficulty,
+            thinking_time_ms: ai_result.thinking_time_ms,
+            alternatives: ai_result.alternatives,
+        })
+    }
+    
+    /// 直前の着手とAIの応手を取り消す
+    /// 盤面・手番・履歴の巻き戻しはセッションをロックしたまま一括で反映する
+    pub fn undo_move(&self, session_id: uuid::Uuid) -> AiBattleResult<UndoResponse> {
+        let _move_guard = self.session_manager.begin_move(&session_id)?;
+        self.session_manager.modify_session(&session_id, |session| {
+            if session.ai_thinking {
+                return Err(AiBattleError::CannotUndo { reason: "AIが思考中です".to_string() });
+            }
+            
+            let undone_moves = session.undo_last_turn()?;
+            self.start_pondering(session);
+            Ok(UndoResponse {
+                success: true,
+                game_state: AiBattleResponse::from_session(session),
+                undone_moves,
+            })
+        })
+    }
+    
+    /// 手番側の全合法手を評価して返す（セッションの状態は変更しない）
+    pub async fn analyze_position(
+        &self,
+        session_id: uuid::Uuid,
+        difficulty: Option<AiDifficulty>,
+        fen: Option<&str>,
+    ) -> AiBattleResult<AnalyzeResponse> {
+        let session = self.session_manager.get_session(&session_id)?;
+        
+        let game_state = match fen {
+            Some(fen) => {
+                let state = GameState::from_fen(fen, session.variant())
+                    .map_err(|e| AiBattleError::BadRequest { details: e.to_string() })?;
+                if !state.has_valid_moves() {
+                    return Err(AiBattleError::BadRequest { details: "解析する局面の手番に合法手がありません".to_string() });
+                }
+                state
+            }
+            None if session.is_finished() => return Err(AiBattleError::GameAlreadyFinished),
+            None => session.game_state.clone(),
+        };
+        
+        let difficulty = difficulty.unwrap_or(session.ai_difficulty);
+        let start_time = std::time::Instant::now();
+        let moves = self.ai_service.analyze_moves(&game_state, difficulty).await
+            .map_err(AiBattleError::from_ai_service)?;
+        self.positions.record_evaluation(&game_state.board, game_state.current_player, difficulty, &moves);
+        
+        Ok(AnalyzeResponse {
+            game_id: session_id,
+            player: game_state.current_player,
+            difficulty,
+            fen: game_state.to_fen(),
+            moves,
+            thinking_time_ms: start_time.elapsed().as_millis() as u64,
+        })
+    }
+    
+    /// セッションの現在の内容（局面を取得済みとしては記録しない）
+    pub fn get_session(&self, session_id: uuid::Uuid) -> AiBattleResult<AiBattleSession> {
+        self.session_manager.get_session(&session_id)
+    }
+    
+    pub fn get_move_history(&self, session_id: uuid::Uuid) -> AiBattleResult<Vec<MoveRecord>> {
+        let session = self.session_manager.get_session(&session_id)?;
+        Ok(session.transcript_records())
+    }
+    
+    /// 着手履歴を開始局面から適用し直し、各手の後の局面を返す（`move_number` を指定した場合はその局面のみ）
+    pub fn replay(&self, session_id: uuid::Uuid, move_number: Option<usize>) -> AiBattleResult<ReplayResponse> {
+        let session = self.session_manager.get_session(&session_id)?;
+        let start = session.initial_state().ok_or_else(|| AiBattleError::InternalError {
+            details: "対局の開始局面を再現できません".to_string(),
+        })?;
+        let frames = ReplayBuilder::replay(start, &session.game_state.move_history)
+            .map_err(|e| AiBattleError::InternalError { details: e.to_string() })?;
+        let total_moves = frames.len() - 1;
+        let frames = match move_number {
+            Some(move_number) => {
+                let frame = frames.get(move_number).ok_or_else(|| AiBattleError::BadRequest {
+                    details: format!("move は0〜{}の範囲で指定してください", total_moves),
+                })?;
+                std::slice::from_ref(frame)
+            }
+            None => frames.as_slice(),
+        };
+        Ok(ReplayResponse {
+            game_id: session_id,
+            total_moves,
+            snapshots: frames.iter().map(ReplaySnapshot::from_frame).collect(),
+        })
+    }
+    
+    /// 対局を標準の棋譜形式で書き出す
+    pub fn export_transcript(&self, session_id: uuid::Uuid) -> AiBattleResult<TranscriptResponse> {
+        let session = self.session_manager.get_session(&session_id)?;
+        Ok(TranscriptResponse::from_session(&session))
+    }
+    
+    /// 対局を別の環境へ持ち運べる形式で書き出す
+    pub fn export_session(&self, session_id: uuid::Uuid) -> AiBattleResult<SessionExport> {
+        let session = self.session_manager.get_session(&session_id)?;
+        Ok(SessionExport::from_session(&session, Utc::now()))
+    }
+    
+    /// 書き出した対局の設定と履歴をルールどおりに検証し、新しいIDのセッションとして作成する
+    /// 読み込んだ対人戦は席のトークンを持たず、どちらの手番もトークンなしで指せる
+    /// 人間対AIの対局がAIの手番で書き出されていれば、その応手まで指してから返す（AI同士は `step` で進める）
+    pub async fn import_session(&self, mut bundle: SessionExport) -> AiBattleResult<AiBattleResponse> {
+        let bad_request = |details: String| AiBattleError::BadRequest { details };
+        let config = &mut bundle.config;
+        let board_size = validate_board_size(config.board_size).map_err(bad_request)?;
+        config.handicap = config.handicap.map(Handicap::validate).transpose().map_err(bad_request)?;
+        if let Some(position) = config.start_position.take() {
+            let (position, _) = Self::check_start_position(position, Some(config.variant), Some(board_size), config.handicap)?;
+            config.start_position = Some(position);
+        }
+        
+        let session = bundle.into_session(Utc::now()).map_err(bad_request)?;
+        let ai_turn = session.kind() == SessionKind::HumanVsAi && !session.is_finished() && session.is_ai_turn();
+        if ai_turn {
+            self.ai_service.ensure_capacity().map_err(AiBattleError::from_ai_service)?;
+        }
+        let session_id = self.session_manager.insert_session(session)?;
+        
+        if ai_turn {
+            let _move_guard = self.session_manager.begin_move(&session_id)?;
+            if let Err(err) = self.play_ai_reply(session_id).await {
+                // 応手を指せないセッションは残さない
+                let _ = self.session_manager.remove_session(&session_id);
+                return Err(err);
+            }
+        }
+        self.get_game_state(session_id)
+    }
+    
+    pub fn list_sessions(&self) -> Vec<AiBattleSession> {
+        self.session_manager.list_sessions()
+    }
+    
+    /// 条件に合うセッション一覧（メモリが空の場合は保存済みのセッション）
+    pub async fn list_sessions_or_stored(&self, filter: &SessionFilter) -> Vec<AiBattleSession> {
+        self.session_manager.list_sessions_or_stored(filter).await
+    }
+    
+    /// 管理者によるピン留めを設定・解除する
+    pub fn set_pinned(&self, session_id: uuid::Uuid, pinned: bool) -> AiBattleResult<SessionSummary> {
+        self.session_manager.modify_session(&session_id, |session| {
+            session.pinned = pinned;
+            Ok(SessionSummary::from_session(session))
+        })
+    }
+    
+    pub fn delete_session(&self, session_id: uuid::Uuid) -> AiBattleResult<DeletionReceipt> {
+        let mut session = self.session_manager.remove_session(&session_id)?;
+        Self::stop_pondering(&mut session);
+        self.events.close(session_id);
+        // 終局した対局は終局時にアーカイブ済み
+        Ok(DeletionReceipt { game_id: session_id, archived: session.is_finished(), deleted_at: Utc::now() })
+    }
+    
+    pub fn change_difficulty(&self, session_id: uuid::Uuid, new_difficulty: AiDifficulty) -> AiBattleResult<AiBattleResponse> {
+        // 着手中の変更は、その着手を上書きしないよう受け付けない
+        let _move_guard = self.session_manager.begin_move(&session_id)?;
+        self.session_manager.with_session_mut(&session_id, |session| {
+            if session.ai_thinking {
+                return Err(AiBattleError::AiThinkingError { 
+                    details: "Cannot change difficulty while AI is thinking".to_string() 
+                });
+            }
+            
+            session.set_ai_difficulty(new_difficulty);
+            self.start_pondering(session);
+            Ok(AiBattleResponse::from_session(session))
+        })
+    }
+    
+    pub fn is_ai_thinking(&self, session_id: uuid::Uuid) -> AiBattleResult<bool> {
+        self.session_manager.is_ai_thinking(&session_id)
+    }
+    
+    pub async fn cleanup_inactive_sessions(&self) -> usize {
+        self.session_manager.cleanup_inactive_sessions().await
+    }
+    
+    pub fn get_service_stats(&self) -> ServiceStats {
+        let session_stats = self.session_manager.get_stats();
+        
+        ServiceStats {
+            total_sessions: session_stats.total_sessions,
+            max_sessions: session_stats.max_sessions,
+            ai_thinking_count: session_stats.ai_thinking_count,
+            difficulty_distribution: session_stats.difficulty_counts,
+        }
+    }
+}
+
+/// 持ち時間の定期確認の結果
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSweep {
+    /// 時間切れで終局させた対局数
+    pub flagged: usize,
+    /// 催促を送った対局数
+    pub reminded: usize,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct ServiceStats {
+    pub total_sessions: usize,
+    pub max_sessions: usize,
+    pub ai_thinking_count: usize,
+    pub difficulty_distribution: std::collections::HashMap<AiDifficulty, usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+    use crate::game::{Board, Cell, InvalidMoveReason};
+    use super::super::clock::TimeControlPreset;
+    use crate::game::setup;
+    use super::super::dto::{PositionEvaluation, EVAL_BAR_DEPTH};
+    
+    fn create_test_service() -> AiBattleService {
+        let session_manager = Arc::new(AiBattleSessionManager::new(10));
+        AiBattleService::new(session_manager)
+    }
+    
+    fn create_fast_test_service() -> AiBattleService {
+        let session_manager = Arc::new(AiBattleSessionManager::new(10));
+        let ai_service = AIServiceFactory::create_fast_local().unwrap();
+        AiBattleService::new_with_ai_service(session_manager, ai_service.into())
+    }
+    
+    #[tokio::test]
+    async fn test_create_ai_battle() {
+        let service = create_test_service();
+        
+        let result = service.create_ai_battle(AiDifficulty::Easy).await;
+        assert!(result.is_ok());
+        
+        let response = result.unwrap();
+        assert_eq!(response.ai_difficulty, AiDifficulty::Easy);
+        assert_eq!(response.current_player, Player::Black);
+        assert!(!response.ai_thinking);
+    }
+    
+    #[tokio::test]
+    async fn test_get_game_state() {
+        let service = create_test_service();
+        
+        let create_result = service.create_ai_battle(AiDifficulty::Medium).await.unwrap();
+        let session_id = create_result.game_id;
+        
+        let result = service.get_game_state(session_id);
+        assert!(result.is_ok());
+        
+        let response = result.unwrap();
+        assert_eq!(response.game_id, session_id);
+        assert_eq!(response.ai_difficulty, AiDifficulty::Medium);
+    }
+    
+    #[tokio::test]
+    async fn test_get_nonexistent_game_state() {
+        let service = create_test_service();
+        let nonexistent_id = Uuid::new_v4();
+        
+        let result = service.get_game_state(nonexistent_id);
+        assert!(matches!(result, Err(AiBattleError::GameNotFound { .. })));
+    }
+    
+    #[tokio::test]
+    async fn test_make_player_move_valid() {
+        let service = create_test_service();
+        
+        let create_result = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let session_id = create_result.game_id;
+        
+        // 有効な着手位置を取得
+        let valid_moves = create_result.valid_moves;
+        assert!(!valid_moves.is_empty());
+        
+        let first_valid_move = valid_moves[0];
+        let result = service.make_player_move(session_id, first_valid_move).await;
+        
+        assert!(result.is_ok());
+        let move_response = result.unwrap();
+        println!("Move response: success={}, ai_move={:?}, message={:?}", 
+                 move_response.success, move_response.ai_move, move_response.message);
+        assert!(move_response.success);
+        assert_eq!(move_response.player_move, first_valid_move);
+        assert!(move_response.ai_move.is_some());
+        
+        // 返った石は着手の記録と一致し、盤面ではそれぞれの色になっている
+        let session = service.session_manager.get_session(&session_id).unwrap();
+        let moves = &session.game_state.move_history;
+        assert_eq!(move_response.flipped_positions, moves[0].flipped);
+        assert_eq!(move_response.ai_flipped_positions.as_ref(), Some(&moves[1].flipped));
+        assert!(!move_response.flipped_positions.is_empty());
+        for flipped in move_response.ai_flipped_positions.unwrap() {
+            assert_eq!(session.game_state.board.get_cell(flipped), Some(Cell::White));
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_resume_pending_ai_turns_after_restart() {
+        let service = create_fast_test_service();
+        let session_id = service.create_ai_battle(AiDifficulty::Easy).await.unwrap().game_id;
+        
+        // 人間の着手を保存した直後、AIの応手を指す前に落ちた状態を再現する
+        let mut session = service.session_manager.get_session(&session_id).unwrap();
+        ReversiRules::apply_move(&mut session.game_state, Position::new(2, 3).unwrap()).unwrap();
+        session.record_placement();
+        session.advance_turn();
+        service.session_manager.update_session(session).unwrap();
+        let idle = service.create_ai_battle(AiDifficulty::Easy).await.unwrap().game_id;
+        
+        assert_eq!(service.resume_pending_ai_turns().await, 1);
+        let resumed = service.session_manager.get_session(&session_id).unwrap();
+        assert_eq!(resumed.current_player, Player::Black);
+        assert_eq!(resumed.move_history.len(), 1);
+        assert!(!resumed.ai_thinking);
+        assert_eq!(service.session_manager.get_session(&idle).unwrap().move_history.len(), 0);
+        
+        // 応手済みの対局は二度指さない
+        assert_eq!(service.resume_pending_ai_turns().await, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_make_player_move_invalid_position() {
+        let service = create_test_service();
+        
+        let create_result = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let session_id = create_result.game_id;
+        
+        // 無効な位置で着手を試行
+        let invalid_position = Position::new(0, 0).unwrap(); // 初期状態では通常無効
+        let result = service.make_player_move(session_id, invalid_position).await;
+        
+        let Err(AiBattleError::InvalidMove { rejection: Some(rejection), .. }) = result else {
+            panic!("expected an invalid move with its reason, got {:?}", result.map(|response| response.player_move));
+        };
+        assert_eq!(rejection.reason, InvalidMoveReason::NoBracketingLine);
+        assert_eq!(rejection.nearest_valid_moves, ReversiRules::nearest_valid_moves(&Board::new(), invalid_position, Player::Black, 3));
+    }
+    
+    #[tokio::test]
+    async fn test_make_player_move_nonexistent_session() {
+        let service = create_test_service();
+        let nonexistent_id = Uuid::new_v4();
+        let position = Position::new(2, 3).unwrap();
+        
+        let result = service.make_player_move(nonexistent_id, position).await;
+        assert!(matches!(result, Err(AiBattleError::GameNotFound { .. })));
+    }
+    
+    #[tokio::test]
+    async fn test_get_move_history() {
+        let service = create_test_service();
+        
+        let create_result = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let session_id = create_result.game_id;
+        
+        // 初期状態では履歴は空
+        let history = service.get_move_history(session_id).unwrap();
+        assert_eq!(history.len(), 0);
+        
+        // プレイヤー着手後
+        let valid_moves = create_result.valid_moves;
+        let first_valid_move = valid_moves[0];
+        let _move_result = service.make_player_move(session_id, first_valid_move).await.unwrap();
+        
+        let history = service.get_move_history(session_id).unwrap();
+        assert_eq!(history.len(), 2); // プレイヤー + AI
+    }
+    
+    #[tokio::test]
+    async fn test_replay_reconstructs_each_move() {
+        let service = create_test_service();
+        
+        let created = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let moved = service.make_player_move(created.game_id, created.valid_moves[0]).await.unwrap();
+        
+        let replay = service.replay(created.game_id, None).unwrap();
+        assert_eq!(replay.total_moves, 2);
+        assert_eq!(replay.snapshots.len(), 3);
+        assert_eq!(replay.snapshots[0].fen, GameState::new().to_fen());
+        assert_eq!(replay.snapshots[1].last_move, Some(created.valid_moves[0]));
+        assert_eq!(replay.snapshots[2].board, moved.game_state.board);
+        
+        let after_first = service.replay(created.game_id, Some(1)).unwrap();
+        assert_eq!(after_first.snapshots.len(), 1);
+        assert_eq!((after_first.snapshots[0].move_number, after_first.snapshots[0].to_move), (1, Player::White));
+        assert!(matches!(service.replay(created.game_id, Some(3)), Err(AiBattleError::BadRequest { .. })));
+    }
+    
+    #[tokio::test]
+    async fn test_exported_session_imports_with_a_new_id() {
+        let service = create_fast_test_service();
+        
+        let created = service.create_ai_vs_ai(AiDifficulty::Easy, AiDifficulty::Medium, true).await.unwrap();
+        let bundle = service.export_session(created.game_id).unwrap();
+        assert_eq!(bundle.source_game_id, created.game_id);
+        let bundle: SessionExport = serde_json::from_value(serde_json::to_value(&bundle).unwrap()).unwrap();
+        
+        let imported = service.import_session(bundle.clone()).await.unwrap();
+        assert_ne!(imported.game_id, created.game_id);
+        assert_eq!((imported.board, imported.status, imported.kind), (created.board, created.status, SessionKind::AiVsAi));
+        assert_eq!(
+            service.export_transcript(imported.game_id).unwrap().transcript,
+            service.export_transcript(created.game_id).unwrap().transcript,
+        );
+        assert_eq!(service.get_session(imported.game_id).unwrap().created_at, bundle.created_at);
+        
+        // 人間の手番で書き出した対局は、読み込んだ先で続きを指せる
+        let human = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let moved = service.make_player_move(human.game_id, human.valid_moves[0]).await.unwrap();
+        let resumed = service.import_session(service.export_session(human.game_id).unwrap()).await.unwrap();
+        assert_eq!((resumed.move_count, resumed.current_player), (moved.game_state.move_count, Player::Black));
+        assert!(service.make_player_move(resumed.game_id, resumed.valid_moves[0]).await.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_import_plays_the_ai_reply_when_the_ai_is_to_move() {
+        let service = create_fast_test_service();
+        let created = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let human_move = created.valid_moves[0];
+        service.make_player_move(created.game_id, human_move).await.unwrap();
+        
+        // 人間が指し、AIが応手を返す前の局面に戻して書き出す
+        let mut bundle = service.export_session(created.game_id).unwrap();
+        bundle.history.truncate(1);
+        let mut game_state = GameState::new();
+        ReversiRules::apply_move(&mut game_state, human_move).unwrap();
+        bundle.board = setup::board_to_string(&game_state.board);
+        bundle.current_player = Player::White;
+        
+        let imported = service.import_session(bundle).await.unwrap();
+        assert_eq!((imported.move_count, imported.current_player), (2, Player::Black));
+        assert!(!imported.ai_thinking);
+        assert!(service.make_player_move(imported.game_id, imported.valid_moves[0]).await.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_import_rejects_bundles_that_break_the_rules() {
+        let service = create_test_service();
+        let created = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        service.make_player_move(created.game_id, created.valid_moves[0]).await.unwrap();
+        let bundle = service.export_session(created.game_id).unwrap();
+        
+        let mut illegal = bundle.clone();
+        illegal.history[0].position = Some(Position::new(0, 0).unwrap());
+        let mut out_of_turn = bundle.clone();
+        out_of_turn.history[1].player = Player::Black;
+        let mut tampered = bundle.clone();
+        tampered.board = setup::board_to_string(&Board::new());
+        let mut future = bundle.clone();
+        future.format_version += 1;
+        let mut oversized = bundle;
+        oversized.config.board_size = 7;
+        
+        for bundle in [illegal, out_of_turn, tampered, future, oversized] {
+            assert!(matches!(service.import_session(bundle).await, Err(AiBattleError::BadRequest { .. })));
+        }
+        assert_eq!(service.list_sessions().len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_get_hint_does_not_mutate_session() {
+        let service = create_test_service();
+        
+        let create_result = service.create_ai_battle(AiDifficulty::Medium).await.unwrap();
+        let session_id = create_result.game_id;
+        
+        let hint = service.get_hint(session_id, Some(AiDifficulty::Easy), None).await.unwrap();
+        assert_eq!(hint.game_id, session_id);
+        assert_eq!(hint.difficulty, AiDifficulty::Easy);
+        assert!(create_result.valid_moves.contains(&hint.suggested_move));
+        assert!(hint.evaluation_score.is_some());
+        
+        let state = service.get_game_state(session_id).unwrap();
+        assert_eq!(state.move_count, 0);
+        assert_eq!(state.ai_difficulty, AiDifficulty::Medium);
+        assert_eq!(state.current_player, Player::Black);
+    }
+    
+    #[tokio::test]
+    async fn test_get_hint_nonexistent_session() {
+        let service = create_test_service();
+        
+        let result = service.get_hint(Uuid::new_v4(), None, None).await;
+        assert!(matches!(result, Err(AiBattleError::GameNotFound { .. })));
+    }
+    
+    #[tokio::test]
+    async fn test_get_hint_with_multipv() {
+        let service = create_fast_test_service();
+        let created = service.create_ai_battle(AiDifficulty::Medium).await.unwrap();
+        
+        let hint = service.get_hint(created.game_id, None, Some(3)).await.unwrap();
+        let alternatives = hint.alternatives.unwrap();
+        assert_eq!(alternatives.len(), 3);
+        assert!(alternatives.iter().all(|alternative| created.valid_moves.contains(&alternative.position)));
+        assert!(alternatives.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        
+        assert!(service.get_hint(created.game_id, None, None).await.unwrap().alternatives.is_none());
+        // 先読みをしない難易度では候補手を返さない
+        assert!(service.get_hint(created.game_id, Some(AiDifficulty::Easy), Some(3)).await.unwrap().alternatives.is_none());
+        for multipv in [0, LevelParams::MAX_MULTIPV + 1] {
+            let result = service.get_hint(created.game_id, None, Some(multipv)).await;
+            assert!(matches!(result, Err(AiBattleError::BadRequest { .. })));
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_step_ai_vs_ai() {
+        let service = create_fast_test_service();
+        
+        let created = service.create_ai_vs_ai(AiDifficulty::Easy, AiDifficulty::Easy, false).await.unwrap();
+        assert_eq!(created.move_count, 0);
+        
+        let first = service.step_game(created.game_id).await.unwrap();
+        assert_eq!(first.player, Player::Black);
+        assert_eq!(first.game_state.current_player, Player::White);
+        
+        let second = service.step_game(created.game_id).await.unwrap();
+        assert_eq!(second.player, Player::White);
+        
+        let history = service.get_move_history(created.game_id).unwrap();
+        assert_eq!(history.len(), 2);
+    }
+    
+    #[tokio::test]
+    async fn test_ai_vs_ai_play_out() {
+        let service = create_fast_test_service();
+        
+        let finished = service.create_ai_vs_ai(AiDifficulty::Easy, AiDifficulty::Easy, true).await.unwrap();
+        assert!(matches!(finished.status, GameStatus::Finished { .. }));
+        assert!(finished.valid_moves.is_empty());
+        
+        let result = service.step_game(finished.game_id).await;
+        assert!(matches!(result, Err(AiBattleError::GameAlreadyFinished)));
+    }
+    
+    #[tokio::test]
+    async fn test_step_rejects_human_turn() {
+        let service = create_fast_test_service();
+        
+        let created = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let result = service.step_game(created.game_id).await;
+        assert!(matches!(result, Err(AiBattleError::NotAiTurn)));
+    }
+    
+    #[tokio::test]
+    async fn test_create_ai_battle_as_white() {
+        let service = create_fast_test_service();
+        
+        let response = service.create_ai_battle_with_color(AiDifficulty::Easy, Player::White).await.unwrap();
+        assert_eq!(response.current_player, Player::White);
+        assert_eq!(response.move_count, 1);
+        assert!(response.black.is_ai());
+        assert!(!response.white.is_ai());
+        
+        let session = service.session_manager.get_session(&response.game_id).unwrap();
+        assert_eq!(session.move_history[0].player, Player::Black);
+        assert!(session.is_player_turn());
+        
+        service.get_game_state(response.game_id).unwrap();
+        let seen_at = service.session_manager.get_session(&response.game_id).unwrap().position_seen_at;
+        assert!(seen_at.is_some());
+        service.get_game_state(response.game_id).unwrap();
+        assert_eq!(service.session_manager.get_session(&response.game_id).unwrap().position_seen_at, seen_at);
+        
+        let move_response = service.make_player_move(response.game_id, response.valid_moves[0]).await.unwrap();
+        assert_eq!(move_response.game_state.current_player, Player::White);
+        assert!(move_response.ai_move.is_some());
+        let session = service.session_manager.get_session(&response.game_id).unwrap();
+        // 人間の着手も思考時間とともに記録される
+        let players: Vec<Player> = session.move_history.iter().map(|record| record.player).collect();
+        assert_eq!(players, vec![Player::Black, Player::White, Player::Black]);
+        assert_eq!(session.move_history[1].position, Some(response.valid_moves[0]));
+        assert!(session.move_history[1].thinking_time_ms.is_some());
+        assert_eq!(session.position_seen_at, None);
+    }
+    
+    #[tokio::test]
+    async fn test_simulate_game_is_reproducible() {
+        let service = create_fast_test_service();
+        
+        let first = service.simulate_game(AiDifficulty::Easy, AiDifficulty::Easy, 42).await.unwrap();
+        let second = service.simulate_game(AiDifficulty::Easy, AiDifficulty::Easy, 42).await.unwrap();
+        assert_eq!(first.transcript, second.transcript);
+        assert!(matches!(first.final_state.status, GameStatus::Finished { .. }));
+        assert_eq!(first.transcript.len() as u32, first.final_state.move_count);
+        assert_eq!(service.list_sessions().len(), 0);
+        
+        let other = service.simulate_game(AiDifficulty::Easy, AiDifficulty::Easy, 7).await.unwrap();
+        assert_ne!(first.transcript, other.transcript);
     }
-}
-
-#[derive(Debug)]
-pub struct ServiceStats {
-    pub total_sessions: usize,
-    pub max_sessions: usize,
-    pub ai_thinking_count: usize,
-    pub difficulty_distribution: std::collections::HashMap<AiDifficulty, usize>,
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use uuid::Uuid;
     
-    fn create_test_service() -> AiBattleService {
-        let session_manager = Arc::new(AiBattleSessionManager::new(10));
-        AiBattleService::new(session_manager)
+    #[tokio::test]
+    async fn test_undo_move() {
+        let service = create_fast_test_service();
+        let created = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let game_id = created.game_id;
+        
+        assert!(matches!(service.undo_move(game_id), Err(AiBattleError::CannotUndo { .. })));
+        
+        service.make_player_move(game_id, created.valid_moves[0]).await.unwrap();
+        let undo = service.undo_move(game_id).unwrap();
+        assert_eq!(undo.undone_moves.len(), 2);
+        assert_eq!(undo.game_state.board, created.board);
+        assert_eq!(undo.game_state.current_player, Player::Black);
+        assert_eq!(undo.game_state.move_count, 0);
+        assert!(service.get_move_history(game_id).unwrap().is_empty());
     }
     
     #[tokio::test]
-    async fn test_create_ai_battle() {
-        let service = create_test_service();
+    async fn test_wait_for_change() {
+        let service = create_fast_test_service();
+        let created = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let game_id = created.game_id;
         
-        let result = service.create_ai_battle(AiDifficulty::Easy).await;
-        assert!(result.is_ok());
+        let idle = service.wait_for_change(game_id, Some(created.version), Duration::from_millis(50)).await.unwrap();
+        assert!(!idle.changed);
         
-        let response = result.unwrap();
-        assert_eq!(response.ai_difficulty, AiDifficulty::Easy);
-        assert_eq!(response.current_player, Player::Black);
-        assert!(!response.ai_thinking);
+        // 待っているあいだに着手されると、その時点で応答する
+        let (waited, moved) = tokio::join!(
+            service.wait_for_change(game_id, None, Duration::from_secs(30)),
+            async {
+                sleep(Duration::from_millis(20)).await;
+                service.make_player_move(game_id, created.valid_moves[0]).await
+            },
+        );
+        assert!(waited.unwrap().changed);
+        assert!(moved.is_ok());
+        
+        let stale = service.wait_for_change(game_id, Some(created.version), Duration::from_secs(30)).await.unwrap();
+        assert!(stale.changed);
+        assert_eq!(stale.game_state.move_count, 2);
+        assert!(matches!(
+            service.wait_for_change(Uuid::new_v4(), None, Duration::from_millis(10)).await,
+            Err(AiBattleError::GameNotFound { .. })
+        ));
     }
     
     #[tokio::test]
-    async fn test_get_game_state() {
-        let service = create_test_service();
+    async fn test_move_seqs_are_not_reused_after_undo() {
+        let service = create_fast_test_service();
+        let created = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let game_id = created.game_id;
         
-        let create_result = service.create_ai_battle(AiDifficulty::Medium).await.unwrap();
-        let session_id = create_result.game_id;
+        service.make_player_move(game_id, created.valid_moves[0]).await.unwrap();
+        let seqs: Vec<u64> = service.get_move_history(game_id).unwrap().iter().map(|record| record.seq).collect();
+        assert_eq!(seqs, vec![1, 2]);
         
-        let result = service.get_game_state(session_id);
-        assert!(result.is_ok());
+        service.undo_move(game_id).unwrap();
+        service.make_player_move(game_id, created.valid_moves[0]).await.unwrap();
+        let seqs: Vec<u64> = service.get_move_history(game_id).unwrap().iter().map(|record| record.seq).collect();
+        assert_eq!(seqs, vec![3, 4]);
+    }
+    
+    /// 指定した石だけを置いた盤面に差し替え、黒番から再開させる
+    fn set_position(service: &AiBattleService, game_id: Uuid, stones: &[(usize, usize, Cell)]) {
+        service.session_manager.modify_session(&game_id, |session| {
+            let mut board = Board::new();
+            for row in 0..8 {
+                for col in 0..8 {
+                    board.set_cell(Position::new(row, col).unwrap(), Cell::Empty);
+                }
+            }
+            for &(row, col, cell) in stones {
+                board.set_cell(Position::new(row, col).unwrap(), cell);
+            }
+            session.game_state.board = board;
+            session.game_state.current_player = Player::Black;
+            session.current_player = Player::Black;
+            Ok(())
+        }).unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_ai_passes_automatically() {
+        let service = create_fast_test_service();
+        let game_id = service.create_ai_battle(AiDifficulty::Easy).await.unwrap().game_id;
+        set_position(&service, game_id, &[(0, 0, Cell::Black), (0, 1, Cell::White), (1, 1, Cell::White)]);
         
-        let response = result.unwrap();
-        assert_eq!(response.game_id, session_id);
-        assert_eq!(response.ai_difficulty, AiDifficulty::Medium);
+        // 黒の着手後、白（AI）には合法手がないため自動でパスされる
+        let response = service.make_player_move(game_id, Position::new(0, 2).unwrap()).await.unwrap();
+        assert_eq!(response.passed, Some(Player::White));
+        assert_eq!(response.ai_move, None);
+        assert_eq!(response.game_state.current_player, Player::Black);
+        assert!(!response.game_state.must_pass);
+        
+        let history = service.get_move_history(game_id).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].position, Position::new(0, 2));
+        assert!(history[1].is_pass());
+        assert_eq!(history[1].player, Player::White);
     }
     
     #[tokio::test]
-    async fn test_get_nonexistent_game_state() {
-        let service = create_test_service();
-        let nonexistent_id = Uuid::new_v4();
+    async fn test_human_pass() {
+        let service = create_fast_test_service();
+        let created = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let game_id = created.game_id;
         
-        let result = service.get_game_state(nonexistent_id);
-        assert!(matches!(result, Err(AiBattleError::GameNotFound { .. })));
+        // 合法手がある間はパスできない
+        assert!(matches!(service.pass_turn(game_id, None).await, Err(AiBattleError::InvalidMove { .. })));
+        
+        set_position(&service, game_id, &[(0, 0, Cell::White), (0, 1, Cell::Black)]);
+        assert!(service.get_game_state(game_id).unwrap().must_pass);
+        
+        let response = service.pass_turn(game_id, None).await.unwrap();
+        assert_eq!(response.player, Player::Black);
+        assert_eq!(response.ai_move, Position::new(0, 2));
+        assert!(matches!(response.game_state.status, GameStatus::Finished { winner: Some(Player::White) }));
+        
+        let history = service.get_move_history(game_id).unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history[0].is_pass());
+        assert_eq!(history[1].position, Position::new(0, 2));
     }
     
     #[tokio::test]
-    async fn test_make_player_move_valid() {
+    async fn test_accept_challenge_creates_seated_pvp() {
+        use crate::api::lobby::{ColorPreference, CreateChallengeRequest};
+        
         let service = create_test_service();
+        let owner = Uuid::new_v4();
+        let acceptor = Uuid::new_v4();
+        let request = CreateChallengeRequest { color: ColorPreference::White, rated: true, ..Default::default() };
+        let created = service.lobby().post(owner, None, request).unwrap();
         
-        let create_result = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
-        let session_id = create_result.game_id;
+        let seat = service.accept_challenge(created.challenge.id, acceptor).unwrap();
+        assert_eq!(seat.color, Player::Black);
+        assert!(seat.join_token.is_none());
+        assert!(service.lobby().list().is_empty());
         
-        // 有効な着手位置を取得
-        let valid_moves = create_result.valid_moves;
-        assert!(!valid_moves.is_empty());
+        let session = service.session_manager.get_session(&seat.game_state.game_id).unwrap();
+        let seats = session.seat_tokens.clone().unwrap();
+        assert_eq!(seats.color_of(created.player_token), Some(Player::White));
+        assert_eq!(seats.color_of(seat.player_token), Some(Player::Black));
+        assert_eq!(session.owners.get(Player::White), Some(owner));
+        assert!(session.rated);
         
-        let first_valid_move = valid_moves[0];
-        let result = service.make_player_move(session_id, first_valid_move).await;
+        let inbox = service.notifications().list(owner, true);
+        assert_eq!(inbox.notifications[0].kind, NotificationKind::ChallengeAccepted);
+        assert_eq!(inbox.notifications[0].game_id, Some(session.id));
         
-        assert!(result.is_ok());
-        let move_response = result.unwrap();
-        println!("Move response: success={}, ai_move={:?}, message={:?}", 
-                 move_response.success, move_response.ai_move, move_response.message);
-        assert!(move_response.success);
-        assert_eq!(move_response.player_move, first_valid_move);
-        assert!(move_response.ai_move.is_some());
+        // 非公開の募集も同じ経路で対局が成立する
+        let invited = service.lobby()
+            .post(owner, None, CreateChallengeRequest { opponent: Some(acceptor), ..Default::default() })
+            .unwrap();
+        let seat = service.accept_invitation(invited.invite_token.unwrap(), acceptor).unwrap();
+        let session = service.session_manager.get_session(&seat.game_state.game_id).unwrap();
+        assert_eq!(session.owners.get(seat.color), Some(acceptor));
+        
+        // 成立済みの募集には参加できない
+        assert!(matches!(
+            service.accept_challenge(created.challenge.id, Uuid::new_v4()),
+            Err(AiBattleError::ChallengeNotFound { .. })
+        ));
     }
     
     #[tokio::test]
-    async fn test_make_player_move_invalid_position() {
+    async fn test_pvp_turn_enforcement() {
         let service = create_test_service();
         
-        let create_result = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
-        let session_id = create_result.game_id;
+        let black = service.create_pvp().await.unwrap();
+        let game_id = black.game_state.game_id;
+        let join_token = black.join_token.unwrap();
         
-        // 無効な位置で着手を試行
-        let invalid_position = Position::new(0, 0).unwrap(); // 初期状態では通常無効
-        let result = service.make_player_move(session_id, invalid_position).await;
+        let white = service.join_pvp(game_id, join_token).unwrap();
+        assert_eq!(white.color, Player::White);
+        assert!(matches!(service.join_pvp(game_id, join_token), Err(AiBattleError::SeatAlreadyTaken)));
+        
+        let first_move = black.game_state.valid_moves[0];
+        let result = service.make_player_move_as(game_id, first_move, None).await;
+        assert!(matches!(result, Err(AiBattleError::InvalidPlayerToken)));
+        
+        let result = service.make_player_move_as(game_id, first_move, Some(white.player_token)).await;
+        assert!(matches!(result, Err(AiBattleError::NotPlayerTurn)));
+        
+        let response = service.make_player_move_as(game_id, first_move, Some(black.player_token)).await.unwrap();
+        assert!(response.ai_move.is_none());
+        assert_eq!(response.game_state.current_player, Player::White);
         
-        assert!(matches!(result, Err(AiBattleError::InvalidMove { .. })));
+        let reply = response.game_state.valid_moves[0];
+        let result = service.make_player_move_as(game_id, reply, Some(black.player_token)).await;
+        assert!(matches!(result, Err(AiBattleError::NotPlayerTurn)));
+        
+        let response = service.make_player_move_as(game_id, reply, Some(white.player_token)).await.unwrap();
+        assert_eq!(response.game_state.current_player, Player::Black);
     }
     
     #[tokio::test]
-    async fn test_make_player_move_nonexistent_session() {
+    async fn test_concurrent_joins_seat_only_one_player() {
         let service = create_test_service();
-        let nonexistent_id = Uuid::new_v4();
-        let position = Position::new(2, 3).unwrap();
         
-        let result = service.make_player_move(nonexistent_id, position).await;
-        assert!(matches!(result, Err(AiBattleError::GameNotFound { .. })));
+        for _ in 0..8 {
+            let black = service.create_pvp().await.unwrap();
+            let game_id = black.game_state.game_id;
+            let join_token = black.join_token.unwrap();
+            
+            let barrier = std::sync::Barrier::new(2);
+            let (first, second) = std::thread::scope(|scope| {
+                let join = || {
+                    barrier.wait();
+                    service.join_pvp(game_id, join_token)
+                };
+                let first = scope.spawn(join);
+                let second = scope.spawn(join);
+                (first.join().unwrap(), second.join().unwrap())
+            });
+            
+            // 同じトークンで同時に参加しても席を得るのは一方だけで、その席のトークンが残る
+            let seated = match (first, second) {
+                (Ok(seat), Err(AiBattleError::SeatAlreadyTaken | AiBattleError::InvalidJoinToken))
+                | (Err(AiBattleError::SeatAlreadyTaken | AiBattleError::InvalidJoinToken), Ok(seat)) => seat,
+                other => panic!("expected exactly one join to succeed: {:?}", other),
+            };
+            let seats = service.get_session(game_id).unwrap().seat_tokens.unwrap();
+            assert_eq!(seats.white, Some(seated.player_token));
+        }
     }
     
     #[tokio::test]
-    async fn test_get_move_history() {
+    async fn test_join_pvp_rejects_invalid_token() {
         let service = create_test_service();
         
-        let create_result = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let black = service.create_pvp().await.unwrap();
+        let result = service.join_pvp(black.game_state.game_id, Uuid::new_v4());
+        assert!(matches!(result, Err(AiBattleError::InvalidJoinToken)));
+        
+        let ai_game = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let result = service.join_pvp(ai_game.game_id, Uuid::new_v4());
+        assert!(matches!(result, Err(AiBattleError::InvalidJoinToken)));
+    }
+    
+    #[tokio::test]
+    async fn test_analyze_position() {
+        let service = create_test_service();
+        
+        let create_result = service.create_ai_battle(AiDifficulty::Medium).await.unwrap();
         let session_id = create_result.game_id;
         
-        // 初期状態では履歴は空
-        let history = service.get_move_history(session_id).unwrap();
-        assert_eq!(history.len(), 0);
+        let analysis = service.analyze_position(session_id, None, None).await.unwrap();
+        assert_eq!(analysis.player, Player::Black);
+        assert_eq!(analysis.difficulty, AiDifficulty::Medium);
+        assert_eq!(analysis.fen, GameState::new().to_fen());
+        assert_eq!(analysis.moves.len(), create_result.valid_moves.len());
+        assert!(analysis.moves.windows(2).all(|pair| pair[0].score >= pair[1].score));
         
-        // プレイヤー着手後
-        let valid_moves = create_result.valid_moves;
-        let first_valid_move = valid_moves[0];
-        let _move_result = service.make_player_move(session_id, first_valid_move).await.unwrap();
+        let state = service.get_game_state(session_id).unwrap();
+        assert_eq!(state.move_count, 0);
         
-        let history = service.get_move_history(session_id).unwrap();
-        assert_eq!(history.len(), 2); // プレイヤー + AI
+        // 盤面表記で指定した局面を解析しても対局は進まない
+        let fen = "---------------------------OX------XXX-------------------------- O";
+        let analysis = service.analyze_position(session_id, None, Some(fen)).await.unwrap();
+        assert_eq!((analysis.player, analysis.fen.as_str(), analysis.moves.len()), (Player::White, fen, 3));
+        assert_eq!(service.get_game_state(session_id).unwrap().move_count, 0);
+        let result = service.analyze_position(session_id, None, Some("XO X")).await;
+        assert!(matches!(result, Err(AiBattleError::BadRequest { .. })));
     }
     
     #[tokio::test]
@@ -431,6 +2218,405 @@ mod tests {
         assert_eq!(response.ai_difficulty, AiDifficulty::Hard);
     }
     
+    #[tokio::test]
+    async fn test_pondered_reply_is_played_without_searching() {
+        use crate::ai::mock_service::{MockAIConfig, MockAIService};
+        
+        let session_manager = Arc::new(AiBattleSessionManager::new(10));
+        let slow_ai = MockAIService::new(MockAIConfig { response_time_ms: 150, ..MockAIConfig::default() });
+        let service = AiBattleService::new_with_ai_service(session_manager, Arc::new(slow_ai));
+        
+        let game_id = service.create_ai_battle(AiDifficulty::Easy).await.unwrap().game_id;
+        assert!(service.set_pondering(game_id, true).unwrap().ponder);
+        
+        let session = service.session_manager.get_session(&game_id).unwrap();
+        let ponder = session.ponder_state.clone().unwrap();
+        let human_moves = ReversiRules::get_valid_moves(&session.game_state.board, Player::Black);
+        while ponder.pondered() < human_moves.len() {
+            sleep(Duration::from_millis(20)).await;
+        }
+        
+        let started = std::time::Instant::now();
+        let response = service.make_player_move(game_id, human_moves[0]).await.unwrap();
+        assert!(response.ai_move.is_some());
+        assert!(started.elapsed() < Duration::from_millis(150));
+        
+        // AIの応手の後は次の人間の手番の先読みが始まっており、待ったで局面が変わると先読みし直す
+        let session = service.session_manager.get_session(&game_id).unwrap();
+        assert!(session.ponder_state.is_some());
+        service.undo_move(game_id).unwrap();
+        let undone = service.session_manager.get_session(&game_id).unwrap();
+        assert!(undone.ponder_state.is_some());
+        assert!(!service.set_pondering(game_id, false).unwrap().ponder);
+        assert!(service.session_manager.get_session(&game_id).unwrap().ponder_state.is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_eval_bar_is_included_only_when_enabled() {
+        let service = create_test_service();
+        
+        let created = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        assert!(!created.eval_bar);
+        assert!(created.evaluation.is_none());
+        
+        let enabled = service.set_eval_bar(created.game_id, true).unwrap();
+        assert_eq!(enabled.evaluation.map(|evaluation| evaluation.depth), Some(EVAL_BAR_DEPTH));
+        let moved = service.make_player_move(created.game_id, created.valid_moves[0]).await.unwrap();
+        let session = service.session_manager.get_session(&created.game_id).unwrap();
+        assert_eq!(moved.game_state.evaluation, Some(PositionEvaluation::of(&session.game_state)));
+        
+        assert!(service.set_eval_bar(created.game_id, false).unwrap().evaluation.is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_teaching_mode_explains_ai_reply() {
+        let service = create_test_service();
+        
+        let created = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let plain = service.make_player_move(created.game_id, created.valid_moves[0]).await.unwrap();
+        assert!(plain.explanation.is_none());
+        
+        assert!(service.set_teaching(created.game_id, true).unwrap().teaching);
+        let next_move = plain.game_state.valid_moves[0];
+        let taught = service.make_player_move(created.game_id, next_move).await.unwrap();
+        let explanation = taught.explanation.unwrap();
+        assert_eq!(Some(explanation.position), taught.ai_move);
+        assert!(!explanation.considered.is_empty());
+        assert!(!explanation.reasons.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_ai_reply_reports_principal_variation() {
+        let service = create_fast_test_service();
+        
+        let created = service.create_ai_battle(AiDifficulty::Medium).await.unwrap();
+        let moved = service.make_player_move(created.game_id, created.valid_moves[0]).await.unwrap();
+        let line = moved.ai_principal_variation.unwrap();
+        assert_eq!(Some(line[0]), moved.ai_move);
+        assert!(line.len() <= AiDifficulty::Medium.level().params().depth as usize);
+        
+        // 先読みしない難易度では読み筋を返さない
+        let created = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let moved = service.make_player_move(created.game_id, created.valid_moves[0]).await.unwrap();
+        assert!(moved.ai_principal_variation.is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_teaching_mode_explains_searched_candidates() {
+        let service = create_fast_test_service();
+        
+        let created = service.create_ai_battle(AiDifficulty::Medium).await.unwrap();
+        service.set_teaching(created.game_id, true).unwrap();
+        let taught = service.make_player_move(created.game_id, created.valid_moves[0]).await.unwrap();
+        let explanation = taught.explanation.unwrap();
+        assert!(!explanation.searched.is_empty());
+        assert!(explanation.searched.len() <= MAX_CONSIDERED_MOVES);
+        assert!(explanation.reasons.iter().any(|reason| reason.contains("先読みした評価")));
+        // 候補手の数は着手の記録に残さない
+        let history = service.get_move_history(created.game_id).unwrap();
+        assert_eq!(history[1].ai_params.unwrap().multipv, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_adaptive_difficulty_tracks_the_position() {
+        let service = create_test_service();
+        
+        let created = service.create_ai_battle(AiDifficulty::Medium).await.unwrap();
+        assert!(created.adaptive.is_none());
+        let enabled = service.set_adaptive(created.game_id, true).unwrap();
+        assert_eq!(enabled.adaptive, Some(AdaptiveState::new(AiDifficulty::Medium.level())));
+        
+        let moved = service.make_player_move(created.game_id, created.valid_moves[0]).await.unwrap();
+        let adaptive = moved.game_state.adaptive.unwrap();
+        // 黒の初手の後、白のAIは3石差で負けている局面から調整する（差が小さいためレベルは変えない）
+        assert_eq!((adaptive.level, adaptive.disc_margin), (AiDifficulty::Medium.level(), -3));
+        let session = service.session_manager.get_session(&created.game_id).unwrap();
+        assert_eq!(session.ai_params(Player::White).0, adaptive.level);
+        
+        let exhibition = service.create_ai_vs_ai(AiDifficulty::Easy, AiDifficulty::Easy, false).await.unwrap();
+        assert!(matches!(service.set_adaptive(exhibition.game_id, true), Err(AiBattleError::BadRequest { .. })));
+    }
+    
+    #[tokio::test]
+    async fn test_concurrent_moves_on_one_game_conflict() {
+        use crate::ai::mock_service::{MockAIConfig, MockAIService};
+        
+        let session_manager = Arc::new(AiBattleSessionManager::new(10));
+        let slow_ai = MockAIService::new(MockAIConfig { response_time_ms: 100, ..MockAIConfig::default() });
+        let service = AiBattleService::new_with_ai_service(session_manager, Arc::new(slow_ai));
+        let game_id = service.create_ai_battle(AiDifficulty::Easy).await.unwrap().game_id;
+        
+        let moves = ReversiRules::get_valid_moves(&Board::new(), Player::Black);
+        let (first, second) = tokio::join!(
+            service.make_player_move(game_id, moves[0]),
+            service.make_player_move(game_id, moves[1]),
+        );
+        // 先に受け付けた着手だけがAIの応手まで進み、もう一方は409で失敗する
+        assert!(first.unwrap().ai_move.is_some());
+        let error = second.unwrap_err();
+        assert!(matches!(error, AiBattleError::MoveInProgress));
+        assert_eq!(error.status_code(), axum::http::StatusCode::CONFLICT);
+        assert_eq!(service.get_game_state(game_id).unwrap().move_count, 2);
+        
+        // 処理が終われば次の着手を受け付ける
+        let reply = ReversiRules::get_valid_moves(&service.session_manager.get_session(&game_id).unwrap().game_state.board, Player::Black)[0];
+        assert!(service.make_player_move(game_id, reply).await.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_difficulty_change_during_a_move_keeps_the_move() {
+        use crate::ai::mock_service::{MockAIConfig, MockAIService};
+        
+        let session_manager = Arc::new(AiBattleSessionManager::new(10));
+        let slow_ai = MockAIService::new(MockAIConfig { response_time_ms: 100, ..MockAIConfig::default() });
+        let service = AiBattleService::new_with_ai_service(session_manager, Arc::new(slow_ai));
+        let game_id = service.create_ai_battle(AiDifficulty::Easy).await.unwrap().game_id;
+        
+        let position = ReversiRules::get_valid_moves(&Board::new(), Player::Black)[0];
+        let (moved, changed) = tokio::join!(
+            service.make_player_move(game_id, position),
+            async { service.change_difficulty(game_id, AiDifficulty::Hard) },
+        );
+        // 着手の処理中の難易度変更は409で失敗し、着手とAIの応手は失われない
+        assert!(moved.unwrap().ai_move.is_some());
+        assert!(matches!(changed, Err(AiBattleError::MoveInProgress)));
+        let state = service.get_game_state(game_id).unwrap();
+        assert_eq!((state.move_count, state.ai_difficulty), (2, AiDifficulty::Easy));
+        
+        // 処理が終われば難易度を変更できる
+        assert_eq!(service.change_difficulty(game_id, AiDifficulty::Hard).unwrap().ai_difficulty, AiDifficulty::Hard);
+    }
+    
+    #[tokio::test]
+    async fn test_full_ai_queue_rejects_the_move_without_saving_it() {
+        use crate::ai::mock_service::{MockAIConfig, MockAIService};
+        use crate::ai::queued_service::QueuedAIService;
+        use std::time::Duration;
+        
+        let session_manager = Arc::new(AiBattleSessionManager::new(10));
+        let slow_ai = MockAIService::new(MockAIConfig { response_time_ms: 200, ..MockAIConfig::default() });
+        let queued: Arc<dyn AIService> = Arc::new(QueuedAIService::new(Arc::new(slow_ai), 1, 1));
+        let service = AiBattleService::new_with_ai_service(session_manager, Arc::clone(&queued));
+        let created = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let game_id = created.game_id;
+        
+        // ほかの対局の計算でワーカーとキューを埋める
+        let mut others = Vec::new();
+        for _ in 0..2 {
+            let queued = Arc::clone(&queued);
+            others.push(tokio::spawn(async move {
+                queued.calculate_move(&GameState::new(), AiDifficulty::Easy).await
+            }));
+            tokio::time::sleep(Duration::from_millis(30)).await;
+        }
+        
+        // 満杯の間は503を返し、人間の着手も保存しない
+        let error = service.make_player_move(game_id, created.valid_moves[0]).await.unwrap_err();
+        assert!(matches!(error, AiBattleError::AiOverloaded { .. }));
+        let state = service.get_game_state(game_id).unwrap();
+        assert_eq!(state.move_count, 0);
+        assert_eq!(state.current_player, Player::Black);
+        assert!(matches!(service.pass_turn(game_id, None).await, Err(AiBattleError::AiOverloaded { .. })));
+        
+        // キューが空けば同じ着手で対局を続けられる
+        for other in others {
+            assert!(other.await.unwrap().is_ok());
+        }
+        let moved = service.make_player_move(game_id, created.valid_moves[0]).await.unwrap();
+        assert!(moved.ai_move.is_some());
+        assert_eq!(moved.game_state.move_count, 2);
+    }
+    
+    #[tokio::test]
+    async fn test_create_ai_battle_at_level() {
+        let service = create_test_service();
+        let level = AiLevel::new(6).unwrap();
+
+        // 人間が白番ならAIはレベル指定の強さで初手を打つ
+        let response = service.create_ai_battle_at_level(level, Player::White, None).await.unwrap();
+        assert_eq!(response.ai_level, Some(level));
+        assert_eq!(response.ai_difficulty, AiDifficulty::Medium);
+        assert_eq!(response.move_count, 1);
+
+        // 難易度を変更するとレベルの指定は解除される
+        let changed = service.change_difficulty(response.game_id, AiDifficulty::Hard).unwrap();
+        assert_eq!((changed.ai_level, changed.ai_difficulty), (None, AiDifficulty::Hard));
+    }
+
+    #[tokio::test]
+    async fn test_seeded_sessions_replay_the_same_ai_moves() {
+        let service = AiBattleService::new_with_ai_service(
+            Arc::new(AiBattleSessionManager::new(10)),
+            Arc::new(crate::ai::LocalAIService::new_fast()),
+        );
+        let setup = AiSetup { seed: Some(5), ..AiSetup::default() };
+
+        let mut openings = Vec::new();
+        for _ in 0..2 {
+            let response = service.create_ai_battle_with_config(AiDifficulty::Easy, setup.clone(), Player::White, None).await.unwrap();
+            let session = service.session_manager.get_session(&response.game_id).unwrap();
+            assert_eq!(session.seed, Some(5));
+            openings.push(session.move_history[0].position);
+        }
+        assert_eq!(openings[0], openings[1]);
+    }
+
+    #[tokio::test]
+    async fn test_ai_config_overrides_and_personality_apply_to_session_only() {
+        use crate::ai::evaluation::EvalPreset;
+        use crate::ai::levels::AiConfigOverrides;
+        use crate::ai::personality::AiPersonality;
+
+        let service = create_test_service();
+        let overrides = AiConfigOverrides { depth: Some(2), weights: Some(EvalPreset::Greedy), ..Default::default() };
+
+        let response = service
+            .create_ai_battle_with_config(AiDifficulty::Hard, AiSetup { overrides: Some(overrides), ..AiSetup::default() }, Player::White, None)
+            .await
+            .unwrap();
+        let config = response.ai_config.unwrap();
+        assert_eq!((config.depth, config.weights), (2, EvalPreset::Greedy));
+        assert_eq!(config.time_limit_ms, AiDifficulty::Hard.level().params().time_limit_ms);
+        assert_eq!((response.ai_level, response.move_count), (None, 1));
+
+        // 他の対局は全体の設定のまま
+        let other = service.create_ai_battle(AiDifficulty::Hard).await.unwrap();
+        assert!(other.ai_config.is_none());
+
+        let invalid = AiConfigOverrides { depth: Some(0), ..Default::default() };
+        let result = service
+            .create_ai_battle_with_config(AiDifficulty::Easy, AiSetup { overrides: Some(invalid), ..AiSetup::default() }, Player::Black, None)
+            .await;
+        assert!(matches!(result, Err(AiBattleError::BadRequest { .. })));
+
+        // パーソナリティは難易度を変えても引き継ぐ
+        let response = service
+            .create_ai_battle_with_config(AiDifficulty::Easy, AiSetup { personality: Some(AiPersonality::Wild), ..AiSetup::default() }, Player::Black, None)
+            .await
+            .unwrap();
+        assert_eq!(response.personality, Some(AiPersonality::Wild));
+        let changed = service.change_difficulty(response.game_id, AiDifficulty::Hard).unwrap();
+        assert_eq!(changed.personality, Some(AiPersonality::Wild));
+        assert_eq!(changed.ai_config, Some(AiPersonality::Wild.apply(AiDifficulty::Hard.level().params())));
+    }
+
+    #[tokio::test]
+    async fn test_board_size_is_chosen_per_game() {
+        let service = create_fast_test_service();
+        let setup = AiSetup { board_size: Some(6), ..AiSetup::default() };
+        let mut state = service.create_ai_battle_with_config(AiDifficulty::Easy, setup, Player::Black, None).await.unwrap();
+        assert_eq!((state.board_size, state.board.len(), state.valid_moves.len()), (6, 6, 4));
+        assert_eq!((state.black_count, state.white_count), (2, 2));
+
+        // 6x6の盤面で終局まで打ち切れる
+        while state.status == GameStatus::InProgress {
+            state = if state.must_pass {
+                service.pass_turn(state.game_id, None).await.unwrap().game_state
+            } else {
+                service.make_player_move(state.game_id, state.valid_moves[0]).await.unwrap().game_state
+            };
+        }
+        assert!(state.black_count + state.white_count <= 36);
+        assert_eq!(service.board_size(state.game_id).unwrap(), 6);
+
+        let setup = AiSetup { board_size: Some(7), ..AiSetup::default() };
+        let result = service.create_ai_battle_with_config(AiDifficulty::Easy, setup, Player::Black, None).await;
+        assert!(matches!(result, Err(AiBattleError::BadRequest { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_variant_decides_start_and_winner() {
+        use crate::game::GameVariant;
+        
+        let service = create_fast_test_service();
+        let setup = AiSetup { board_size: Some(6), variant: Some(GameVariant::AntiReversi), ..AiSetup::default() };
+        let mut state = service.create_ai_battle_with_config(AiDifficulty::Easy, setup, Player::Black, None).await.unwrap();
+        assert_eq!(state.variant, GameVariant::AntiReversi);
+        while state.status == GameStatus::InProgress {
+            state = if state.must_pass {
+                service.pass_turn(state.game_id, None).await.unwrap().game_state
+            } else {
+                service.make_player_move(state.game_id, state.valid_moves[0]).await.unwrap().game_state
+            };
+        }
+        // 石の少ない方が勝ち
+        let expected = GameVariant::AntiReversi.winner((state.black_count, state.white_count));
+        assert_eq!(state.status, GameStatus::Finished { winner: expected });
+        let game = service.query_archive(&ArchiveFilter::default()).await.unwrap().remove(0);
+        assert_eq!(game.variant, GameVariant::AntiReversi);
+        assert!(crate::replay::verify(&game).is_consistent());
+        
+        // 中央の4石の配置は対局のシードで決まる
+        let setup = AiSetup { seed: Some(2), variant: Some(GameVariant::RandomStart), ..AiSetup::default() };
+        let state = service.create_ai_battle_with_config(AiDifficulty::Easy, setup, Player::Black, None).await.unwrap();
+        let expected = GameVariant::RandomStart.initial_board(8, 2).unwrap();
+        assert_eq!(state.board, crate::api::encoding::encode_board(&expected));
+    }
+
+    #[tokio::test]
+    async fn test_handicap_stones_are_placed_and_archived() {
+        let service = create_fast_test_service();
+        let handicap = Handicap { player: Player::Black, corners: 4 };
+        let setup = AiSetup { handicap: Some(handicap), ..AiSetup::default() };
+        let mut state = service.create_ai_battle_with_config(AiDifficulty::Hard, setup, Player::Black, None).await.unwrap();
+        assert_eq!((state.black_count, state.white_count), (6, 2));
+        assert_eq!(state.board[0][0], Some(Player::Black));
+        assert_eq!(state.board[7][0], Some(Player::Black));
+        assert_eq!(state.handicap, Some(handicap));
+        
+        while state.status == GameStatus::InProgress {
+            state = if state.must_pass {
+                service.pass_turn(state.game_id, None).await.unwrap().game_state
+            } else {
+                service.make_player_move(state.game_id, state.valid_moves[0]).await.unwrap().game_state
+            };
+        }
+        let game = service.query_archive(&ArchiveFilter::default()).await.unwrap().remove(0);
+        assert_eq!(game.handicap, Some(handicap));
+        assert!(crate::replay::verify(&game).is_consistent());
+        
+        let setup = AiSetup { handicap: Some(Handicap { player: Player::Black, corners: 5 }), ..AiSetup::default() };
+        let result = service.create_ai_battle_with_config(AiDifficulty::Hard, setup, Player::Black, None).await;
+        assert!(matches!(result, Err(AiBattleError::BadRequest { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_custom_start_position() {
+        let service = create_fast_test_service();
+        let midgame = "--------\n--------\n---X----\n---XX---\n---XO---\n--------\n--------\n--------";
+        let position = StartPosition { board: midgame.to_string(), to_move: Player::White };
+        let setup = AiSetup { start_position: Some(position), ..AiSetup::default() };
+        let mut state = service.create_ai_battle_with_config(AiDifficulty::Easy, setup, Player::Black, None).await.unwrap();
+        // 白番の局面なのでAIが先に指している
+        assert_eq!((state.move_count, state.current_player), (1, Player::Black));
+        let recorded = state.start_position.clone().unwrap();
+        assert_eq!(recorded.board, "--------/--------/---X----/---XX---/---XO---/--------/--------/--------");
+        
+        while state.status == GameStatus::InProgress {
+            state = if state.must_pass {
+                service.pass_turn(state.game_id, None).await.unwrap().game_state
+            } else {
+                service.make_player_move(state.game_id, state.valid_moves[0]).await.unwrap().game_state
+            };
+        }
+        let game = service.query_archive(&ArchiveFilter::default()).await.unwrap().remove(0);
+        assert_eq!(game.start_position, Some(recorded));
+        let report = crate::replay::verify(&game);
+        assert!(report.is_consistent(), "{:?}", report.errors);
+        
+        // 到達できない局面、置き石との併用は受け付けない
+        let unreachable = StartPosition { board: format!("X{}", &midgame[1..]), to_move: Player::White };
+        let handicap = Handicap { player: Player::Black, corners: 1 };
+        for setup in [
+            AiSetup { start_position: Some(unreachable), ..AiSetup::default() },
+            AiSetup { start_position: Some(state.start_position.clone().unwrap()), handicap: Some(handicap), ..AiSetup::default() },
+            AiSetup { start_position: Some(state.start_position.clone().unwrap()), board_size: Some(6), ..AiSetup::default() },
+        ] {
+            let result = service.create_ai_battle_with_config(AiDifficulty::Easy, setup, Player::Black, None).await;
+            assert!(matches!(result, Err(AiBattleError::BadRequest { .. })));
+        }
+    }
+
     #[tokio::test]
     async fn test_is_ai_thinking() {
         let service = create_test_service();
@@ -453,6 +2639,124 @@ mod tests {
         assert_eq!(stats.ai_thinking_count, 0);
     }
     
+    #[tokio::test]
+    async fn test_sweep_clocks_flags_player_out_of_time() {
+        let service = create_fast_test_service();
+        let blitz = TimeControlPreset::Blitz1.time_control();
+        let game_id = service
+            .create_ai_battle_with_options(AiDifficulty::Easy, Player::Black, Some(blitz))
+            .await
+            .unwrap()
+            .game_id;
+        
+        assert_eq!(service.sweep_clocks(Utc::now()).flagged, 0);
+        
+        let sweep = service.sweep_clocks(Utc::now() + chrono::Duration::seconds(61));
+        assert_eq!(sweep.flagged, 1);
+        
+        let state = service.get_game_state(game_id).unwrap();
+        assert!(matches!(state.status, GameStatus::Finished { winner: Some(Player::White) }));
+        
+        let result = service.make_player_move(game_id, Position::new(2, 3).unwrap()).await;
+        assert!(matches!(result, Err(AiBattleError::GameAlreadyFinished)));
+    }
+
+    #[tokio::test]
+    async fn test_share_token_resolves_until_the_session_is_deleted() {
+        let service = create_fast_test_service();
+        let game_id = service.create_ai_battle(AiDifficulty::Easy).await.unwrap().game_id;
+        
+        let share = service.share_session(game_id).unwrap();
+        assert_eq!(service.share_session(game_id).unwrap().share_token, share.share_token);
+        assert_eq!(service.resolve_share_token(share.share_token).unwrap(), game_id);
+        assert!(matches!(service.resolve_share_token(Uuid::new_v4()), Err(AiBattleError::ShareLinkNotFound)));
+        
+        service.delete_session(game_id).unwrap();
+        assert!(matches!(service.resolve_share_token(share.share_token), Err(AiBattleError::ShareLinkNotFound)));
+    }
+    
+    #[tokio::test]
+    async fn test_rated_game_updates_the_player_rating_once() {
+        let service = create_fast_test_service();
+        let player = Uuid::new_v4();
+        let blitz = TimeControlPreset::Blitz1.time_control();
+        let game_id = service
+            .create_ai_battle_with_options(AiDifficulty::Hard, Player::Black, Some(blitz))
+            .await
+            .unwrap()
+            .game_id;
+        let account = AccountIdentity { account_id: player, username: "player".to_string() };
+        service.set_account_owner(game_id, Some(&account)).unwrap();
+        service.set_rated(game_id, true).unwrap();
+
+        service.sweep_clocks(Utc::now() + chrono::Duration::seconds(61));
+        let state = service.get_game_state(game_id).unwrap();
+        assert_eq!(state.rating_changes.len(), 1);
+        assert_eq!((state.rating_changes[0].player_id, state.rating_changes[0].delta), (player, -1));
+        assert_eq!(service.ratings().get(player).rating, 1199);
+
+        // 同じ対局で再び終局を処理しても二重に更新しない
+        service.sweep_clocks(Utc::now() + chrono::Duration::seconds(120));
+        assert_eq!(service.ratings().get(player).games, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rated_game_without_an_account_leaves_ratings_unchanged() {
+        let service = create_fast_test_service();
+        let player = Uuid::new_v4();
+        let blitz = TimeControlPreset::Blitz1.time_control();
+        let game_id = service
+            .create_ai_battle_with_options(AiDifficulty::Easy, Player::Black, Some(blitz))
+            .await
+            .unwrap()
+            .game_id;
+        // `X-Player-Id` だけで名乗ったプレイヤーは認証されていないため、レーティングを更新しない
+        service.set_owner(game_id, Player::Black, player).unwrap();
+        service.set_rated(game_id, true).unwrap();
+        
+        service.sweep_clocks(Utc::now() + chrono::Duration::seconds(61));
+        let state = service.get_game_state(game_id).unwrap();
+        assert!(matches!(state.status, GameStatus::Finished { .. }));
+        assert!(state.rating_changes.is_empty());
+        assert_eq!(service.ratings().get(player), crate::ratings::PlayerRating::initial(player));
+        assert!(service.ratings().store().load_all().await.unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_correspondence_reminder_and_forfeit() {
+        let config = CorrespondenceConfig { reminder_after_minutes: Some(60), webhook_url: None };
+        let service = create_fast_test_service().with_correspondence(&config);
+        let owner = Uuid::new_v4();
+        let game_id = service
+            .create_ai_battle_with_options(
+                AiDifficulty::Easy,
+                Player::Black,
+                Some(TimeControlPreset::Correspondence1Day.time_control()),
+            )
+            .await
+            .unwrap()
+            .game_id;
+        service.set_owner(game_id, Player::Black, owner).unwrap();
+        let start = Utc::now();
+        
+        assert_eq!(service.sweep_clocks(start + chrono::Duration::minutes(30)), ClockSweep::default());
+        assert_eq!(service.sweep_clocks(start + chrono::Duration::minutes(61)).reminded, 1);
+        assert_eq!(service.sweep_clocks(start + chrono::Duration::minutes(62)).reminded, 0);
+        
+        let inbox = service.notifications().list(owner, true);
+        assert_eq!(inbox.notifications[0].kind, NotificationKind::TurnReminder);
+        
+        // 1手の制限を過ぎると時間切れ負けになる
+        let sweep = service.sweep_clocks(start + chrono::Duration::days(1) + chrono::Duration::seconds(1));
+        assert_eq!(sweep.flagged, 1);
+        let finished = &service.notifications().list(owner, true).notifications[0];
+        assert_eq!(finished.kind, NotificationKind::GameFinished);
+        assert!(finished.message.contains("時間切れ"));
+        
+        let state = service.get_game_state(game_id).unwrap();
+        assert_eq!(state.clock.unwrap().black_remaining_ms, 0);
+    }
+    
     #[tokio::test]
     async fn test_cleanup_inactive_sessions() {
         let service = create_test_service();
@@ -460,4 +2764,28 @@ mod tests {
         let removed_count = service.cleanup_inactive_sessions().await;
         assert_eq!(removed_count, 0); // 初期状態では削除されるセッションはない
     }
+    
+    #[tokio::test]
+    async fn test_owned_sessions_are_limited_to_the_owner() {
+        let service = create_test_service();
+        let owner = AccountIdentity { account_id: Uuid::new_v4(), username: "owner".to_string() };
+        let other = AccountIdentity { account_id: Uuid::new_v4(), username: "other".to_string() };
+        
+        // 未ログインで作成したセッションは誰でも操作できる
+        let anonymous = service.create_ai_battle(AiDifficulty::Easy).await.unwrap().game_id;
+        service.set_account_owner(anonymous, None).unwrap();
+        assert!(service.authorize_manage(anonymous, Some(&other)).is_ok());
+        
+        let owned = service.create_ai_battle(AiDifficulty::Easy).await.unwrap().game_id;
+        service.set_account_owner(owned, Some(&owner)).unwrap();
+        assert!(matches!(service.authorize_play(owned, None), Err(AiBattleError::LoginRequired)));
+        assert!(matches!(service.authorize_manage(owned, Some(&other)), Err(AiBattleError::NotSessionOwner)));
+        assert!(service.authorize_play(owned, Some(&owner)).is_ok());
+        
+        // 対人戦の着手はプレイヤートークンで認可し、削除は所有者に限る
+        let pvp = service.create_pvp().await.unwrap().game_state.game_id;
+        service.set_account_owner(pvp, Some(&owner)).unwrap();
+        assert!(service.authorize_play(pvp, Some(&other)).is_ok());
+        assert!(matches!(service.authorize_manage(pvp, Some(&other)), Err(AiBattleError::NotSessionOwner)));
+    }
 }
\ No newline at end of file
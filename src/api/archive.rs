@@ -0,0 +1,78 @@
+//! 対局アーカイブAPIモジュール
+//! 終局した対局の記録を結果・難易度・終局日時で検索する `/api/archive` と、
+//! 検索結果を外部の解析ツール向けにWTHOR形式で書き出す `/api/archive/wthor` を提供する。
+
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{Datelike, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::archive::{ArchiveQuery, ArchivedGame};
+use crate::wthor;
+
+use super::ai_battle::dto::{AiBattleError, AiBattleResult};
+use super::handlers::AppState;
+
+/// アーカイブの検索結果（終局の新しい順）
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ArchiveResponse {
+    pub games: Vec<ArchivedGame>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/archive",
+    tag = "archive",
+    params(ArchiveQuery),
+    responses(
+        (status = 200, description = "条件に合う対局の記録", body = ArchiveResponse),
+        (status = 400, description = "検索条件が不正", body = ErrorResponse),
+    )
+)]
+pub async fn get_archive(
+    State(state): State<AppState>,
+    Query(query): Query<ArchiveQuery>,
+) -> AiBattleResult<Json<ArchiveResponse>> {
+    let filter = query.parse()?;
+    let games = state.ai_battle_service.query_archive(&filter).await?;
+    Ok(Json(ArchiveResponse { games }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/archive/wthor",
+    tag = "archive",
+    params(ArchiveQuery),
+    responses(
+        (
+            status = 200,
+            description = "条件に合う対局のWTHOR形式（.wtb）のデータベース（8x8の盤で初期配置から始めた通常の対局のみ）",
+            content_type = "application/octet-stream",
+            body = String
+        ),
+        (status = 400, description = "検索条件が不正", body = ErrorResponse),
+    )
+)]
+pub async fn get_archive_wthor(
+    State(state): State<AppState>,
+    Query(query): Query<ArchiveQuery>,
+) -> AiBattleResult<Response> {
+    let filter = query.parse()?;
+    let archived = state.ai_battle_service.query_archive(&filter).await?;
+    let games: Vec<_> = archived.iter().filter_map(ArchivedGame::to_wthor).collect();
+    let now = Utc::now();
+    let games_year = archived.first().map_or(now.year(), |game| game.finished_at.year());
+
+    let mut bytes = Vec::new();
+    wthor::write_database(&mut bytes, &games, games_year as u16, now)
+        .map_err(|e| AiBattleError::InternalError { details: e.to_string() })?;
+    let headers = [
+        (header::CONTENT_TYPE, "application/octet-stream"),
+        (header::CONTENT_DISPOSITION, "attachment; filename=\"archive.wtb\""),
+    ];
+    Ok((headers, bytes).into_response())
+}
@@ -0,0 +1,205 @@
+//! APIキー認証モジュール
+//! 設定（`AuthConfig.api_keys`）に登録されたAPIキーを `/api` 以下の全ルートで検証する。
+//! キーは `X-API-Key` ヘッダー、`Authorization: Bearer` ヘッダー、ヘッダーを付けられない
+//! WebSocket・SSE向けの `api_key` クエリパラメータのいずれかで指定する。
+//! キーが登録されていない場合は認証を行わない。ヘルスチェックと難易度一覧は常に公開する。
+//! `Authorization: Bearer` のうちJWT形式のものはアカウントのトークンとして扱い、APIキーとはみなさない。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    async_trait,
+    body::Body,
+    extract::{FromRequestParts, State},
+    http::{header, request::Parts, Method, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Json, Response},
+    Router,
+};
+
+use crate::accounts::is_jwt;
+use crate::config::AuthConfig;
+
+use super::ai_battle::dto::ErrorResponse;
+
+/// APIキーを指定するヘッダー名
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// APIキーを指定するクエリパラメータ名
+const API_KEY_QUERY: &str = "api_key";
+
+/// 認証なしで利用できるルート
+const PUBLIC_PATHS: &[&str] = &[
+    "/health",
+    "/api/admin/health/full",
+    "/api/ai-battle/difficulties",
+    "/api/openapi.json",
+];
+
+/// 登録済みのAPIキー（キーから利用者名を引く）
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeyRegistry {
+    keys: HashMap<String, String>,
+}
+
+impl ApiKeyRegistry {
+    pub fn from_config(config: &AuthConfig) -> Self {
+        Self {
+            keys: config.api_keys.iter().map(|entry| (entry.key.clone(), entry.name.clone())).collect(),
+        }
+    }
+
+    /// キーが登録されていて認証が有効か
+    pub fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub(crate) fn resolve(&self, key: &str) -> Option<ApiKeyIdentity> {
+        self.keys.get(key).map(|name| ApiKeyIdentity(name.clone()))
+    }
+}
+
+/// 認証に使われたAPIキーの利用者名
+/// 認証が無効な場合やキーなしの公開ルートではリクエストに含まれない
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyIdentity(pub String);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ApiKeyIdentity {
+    type Rejection = (StatusCode, Json<ErrorResponse>);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<ApiKeyIdentity>()
+            .cloned()
+            .ok_or_else(|| unauthorized("MISSING_API_KEY", "APIキーを指定してください"))
+    }
+}
+
+/// ルーター全体にAPIキー認証を適用する
+pub fn protect(router: Router, registry: ApiKeyRegistry) -> Router {
+    router.layer(middleware::from_fn_with_state(Arc::new(registry), require_api_key))
+}
+
+/// `/api` 以下の非公開ルートでAPIキーを検証し、利用者名をリクエストに付与する
+pub async fn require_api_key(
+    State(registry): State<Arc<ApiKeyRegistry>>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    if !registry.is_enabled() || request.method() == Method::OPTIONS || is_public(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let Some(key) = presented_key(&request) else {
+        return unauthorized("MISSING_API_KEY", "APIキーを指定してください").into_response();
+    };
+    match registry.resolve(&key) {
+        Some(identity) => {
+            request.extensions_mut().insert(identity);
+            next.run(request).await
+        }
+        None => unauthorized("INVALID_API_KEY", "APIキーが無効です").into_response(),
+    }
+}
+
+fn is_public(path: &str) -> bool {
+    !path.starts_with("/api/") || PUBLIC_PATHS.contains(&path)
+}
+
+fn presented_key(request: &Request<Body>) -> Option<String> {
+    let headers = request.headers();
+    if let Some(key) = headers.get(API_KEY_HEADER).and_then(|value| value.to_str().ok()) {
+        return Some(key.trim().to_string());
+    }
+    if let Some(key) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .filter(|value| !is_jwt(value.trim()))
+    {
+        return Some(key.trim().to_string());
+    }
+    request
+        .uri()
+        .query()?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(name, _)| *name == API_KEY_QUERY)
+        .map(|(_, key)| key.to_string())
+}
+
+fn unauthorized(code: &str, message: &str) -> (StatusCode, Json<ErrorResponse>) {
+    (StatusCode::UNAUTHORIZED, Json(ErrorResponse::with_code(code, message, code)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ApiKeyEntry;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    fn app(keys: &[(&str, &str)]) -> Router {
+        let config = AuthConfig {
+            api_keys: keys
+                .iter()
+                .map(|(name, key)| ApiKeyEntry { name: name.to_string(), key: key.to_string() })
+                .collect(),
+            ..AuthConfig::default()
+        };
+        let router = Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/api/ai-battle/difficulties", get(|| async { "open" }))
+            .route("/api/ai-battle/sessions", get(|identity: Option<ApiKeyIdentity>| async move {
+                identity.map(|ApiKeyIdentity(name)| name).unwrap_or_default()
+            }));
+        protect(router, ApiKeyRegistry::from_config(&config))
+    }
+
+    async fn call(app: Router, uri: &str, headers: &[(&str, &str)]) -> (StatusCode, String) {
+        let mut builder = Request::get(uri);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        let response = app.oneshot(builder.body(Body::empty()).unwrap()).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_protected_routes_require_a_registered_key() {
+        let keys = [("frontend", "secret-1")];
+
+        assert_eq!(call(app(&keys), "/api/ai-battle/sessions", &[]).await.0, StatusCode::UNAUTHORIZED);
+        let (status, body) = call(app(&keys), "/api/ai-battle/sessions", &[("X-API-Key", "wrong")]).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains("INVALID_API_KEY"));
+
+        let attributed = (StatusCode::OK, "frontend".to_string());
+        assert_eq!(call(app(&keys), "/api/ai-battle/sessions", &[("X-API-Key", "secret-1")]).await, attributed);
+        assert_eq!(call(app(&keys), "/api/ai-battle/sessions", &[("Authorization", "Bearer secret-1")]).await, attributed);
+        assert_eq!(call(app(&keys), "/api/ai-battle/sessions?api_key=secret-1", &[]).await, attributed);
+    }
+
+    #[tokio::test]
+    async fn test_public_routes_and_disabled_auth_stay_open() {
+        let keys = [("frontend", "secret-1")];
+        assert_eq!(call(app(&keys), "/health", &[]).await.0, StatusCode::OK);
+        assert_eq!(call(app(&keys), "/api/ai-battle/difficulties", &[]).await.0, StatusCode::OK);
+
+        // キー未登録なら認証しない
+        assert_eq!(call(app(&[]), "/api/ai-battle/sessions", &[]).await, (StatusCode::OK, String::new()));
+    }
+}
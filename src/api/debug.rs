@@ -0,0 +1,45 @@
+//! QA向けデバッグAPIモジュール
+//! `debug-api` フィーチャー有効時のみルーティングとOpenAPI定義に追加される。
+//! 本番ビルドには含めないこと。
+
+use axum::{extract::State, response::Json, routing::post, Router};
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+use super::ai_battle::dto::{AiBattleResult, SimulateGameRequest, SimulateGameResponse};
+use super::json::JsonBody;
+use super::ai_battle::service::AiBattleService;
+
+#[utoipa::path(
+    post,
+    path = "/api/debug/simulate-game",
+    tag = "debug",
+    request_body = SimulateGameRequest,
+    responses(
+        (status = 200, description = "終局までの棋譜と最終状態", body = SimulateGameResponse),
+        (status = 429, description = "セッション上限", body = ErrorResponse),
+        (status = 500, description = "AIの着手に失敗", body = ErrorResponse),
+    )
+)]
+pub async fn simulate_game(
+    State(service): State<Arc<AiBattleService>>,
+    JsonBody(request): JsonBody<SimulateGameRequest>,
+) -> AiBattleResult<Json<SimulateGameResponse>> {
+    let response = service
+        .simulate_game(request.black_difficulty, request.white_difficulty, request.seed)
+        .await?;
+    Ok(Json(response))
+}
+
+/// デバッグAPIのOpenAPI定義（`ApiDoc` にマージして公開する）
+#[derive(OpenApi)]
+#[openapi(
+    paths(simulate_game),
+    components(schemas(SimulateGameRequest, SimulateGameResponse)),
+    tags((name = "debug", description = "QA向けデバッグAPI")),
+)]
+pub struct DebugApiDoc;
+
+pub fn create_debug_routes() -> Router<Arc<AiBattleService>> {
+    Router::new().route("/api/debug/simulate-game", post(simulate_game))
+}
@@ -0,0 +1,388 @@
+//! 盤面エンコーディングの共通モジュール
+//! 旧API（/api/games）の数値表現（0: 空, 1: 黒, 2: 白）と
+//! AI対戦APIの `Option<Player>` 表現の相互変換を一元管理する。
+//!
+//! 正規のエンコーディングは `Option<Player>` 形式であり、
+//! 数値表現は旧APIとの互換性のためにのみ維持される（非推奨）。
+//! AI対戦APIではプレイヤーを小文字（"black" / "white"）で表記する。
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use utoipa::ToSchema;
+
+use crate::game::{Board, Cell, Player, Position};
+
+/// 旧API（8x8のみ）の盤面の一辺のマス数
+pub const BOARD_SIZE: usize = 8;
+
+/// 旧APIの数値盤面表現
+pub type LegacyBoard = [[u8; BOARD_SIZE]; BOARD_SIZE];
+
+/// 正規の盤面表現（空マスはNone）
+pub type CanonicalBoard = Vec<Vec<Option<Player>>>;
+
+/// 旧API形式の空マスの値
+pub const LEGACY_EMPTY: u8 = 0;
+/// 旧API形式の黒の値
+pub const LEGACY_BLACK: u8 = 1;
+/// 旧API形式の白の値
+pub const LEGACY_WHITE: u8 = 2;
+
+/// AI対戦APIでのプレイヤー表記
+/// 出力は小文字に統一し、入力では旧来の大文字表記（"Black"）も受け付ける
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiPlayer {
+    Black,
+    White,
+}
+
+impl From<Player> for ApiPlayer {
+    fn from(player: Player) -> Self {
+        match player {
+            Player::Black => ApiPlayer::Black,
+            Player::White => ApiPlayer::White,
+        }
+    }
+}
+
+impl From<ApiPlayer> for Player {
+    fn from(player: ApiPlayer) -> Self {
+        match player {
+            ApiPlayer::Black => Player::Black,
+            ApiPlayer::White => Player::White,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ApiPlayer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Player::deserialize(deserializer).map(ApiPlayer::from)
+    }
+}
+
+/// DTOの `Player` フィールドを `ApiPlayer` 表記で入出力するためのserdeモジュール
+/// `#[serde(with = "crate::api::encoding::api_player")]` の形で指定する
+pub mod api_player {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(player: &Player, serializer: S) -> Result<S::Ok, S::Error> {
+        ApiPlayer::from(*player).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Player, D::Error> {
+        ApiPlayer::deserialize(deserializer).map(Player::from)
+    }
+
+    /// `Option<Player>` 用
+    pub mod option {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(player: &Option<Player>, serializer: S) -> Result<S::Ok, S::Error> {
+            player.map(ApiPlayer::from).serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Player>, D::Error> {
+            Option::<ApiPlayer>::deserialize(deserializer).map(|player| player.map(Player::from))
+        }
+    }
+
+    /// 正規形式の盤面（`CanonicalBoard`）用
+    pub mod board {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(board: &CanonicalBoard, serializer: S) -> Result<S::Ok, S::Error> {
+            let rows: Vec<Vec<Option<ApiPlayer>>> = board
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.map(ApiPlayer::from)).collect())
+                .collect();
+            rows.serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<CanonicalBoard, D::Error> {
+            let rows = Vec::<Vec<Option<ApiPlayer>>>::deserialize(deserializer)?;
+            Ok(rows
+                .into_iter()
+                .map(|row| row.into_iter().map(|cell| cell.map(Player::from)).collect())
+                .collect())
+        }
+    }
+}
+
+/// 盤面エンコーディングの変換エラー
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EncodingError {
+    #[error("無効なセル値です: {value} (位置: {row}, {col})")]
+    InvalidCellCode { row: usize, col: usize, value: u8 },
+
+    #[error("無効なプレイヤー値です: {value}")]
+    InvalidPlayerCode { value: u8 },
+
+    #[error("盤面サイズが不正です: {rows}x{cols}")]
+    InvalidDimensions { rows: usize, cols: usize },
+}
+
+/// セルを旧API形式の数値に変換する
+pub fn cell_to_legacy(cell: Cell) -> u8 {
+    match cell {
+        Cell::Empty => LEGACY_EMPTY,
+        Cell::Black => LEGACY_BLACK,
+        Cell::White => LEGACY_WHITE,
+    }
+}
+
+/// 旧API形式の数値をセルに変換する
+/// 範囲外の値の場合はNoneを返す
+pub fn legacy_to_cell(value: u8) -> Option<Cell> {
+    match value {
+        LEGACY_EMPTY => Some(Cell::Empty),
+        LEGACY_BLACK => Some(Cell::Black),
+        LEGACY_WHITE => Some(Cell::White),
+        _ => None,
+    }
+}
+
+/// セルを正規形式に変換する
+pub fn cell_to_canonical(cell: Cell) -> Option<Player> {
+    match cell {
+        Cell::Empty => None,
+        Cell::Black => Some(Player::Black),
+        Cell::White => Some(Player::White),
+    }
+}
+
+/// 正規形式をセルに変換する
+pub fn canonical_to_cell(value: Option<Player>) -> Cell {
+    match value {
+        None => Cell::Empty,
+        Some(player) => player.to_cell(),
+    }
+}
+
+/// プレイヤーを旧API形式の数値に変換する
+pub fn player_to_legacy(player: Player) -> u8 {
+    cell_to_legacy(player.to_cell())
+}
+
+/// 旧API形式の数値をプレイヤーに変換する
+pub fn legacy_to_player(value: u8) -> Result<Player, EncodingError> {
+    match value {
+        LEGACY_BLACK => Ok(Player::Black),
+        LEGACY_WHITE => Ok(Player::White),
+        _ => Err(EncodingError::InvalidPlayerCode { value }),
+    }
+}
+
+/// 盤面を旧API形式にエンコードする
+pub fn encode_legacy_board(board: &Board) -> LegacyBoard {
+    let mut encoded = [[LEGACY_EMPTY; BOARD_SIZE]; BOARD_SIZE];
+    for (row, cells) in encoded.iter_mut().enumerate() {
+        for (col, value) in cells.iter_mut().enumerate() {
+            if let Some(cell) = Position::new(row, col).and_then(|pos| board.get_cell(pos)) {
+                *value = cell_to_legacy(cell);
+            }
+        }
+    }
+    encoded
+}
+
+/// 旧API形式の盤面をデコードする
+pub fn decode_legacy_board(encoded: &LegacyBoard) -> Result<Board, EncodingError> {
+    let mut board = Board::new();
+    for (row, cells) in encoded.iter().enumerate() {
+        for (col, &value) in cells.iter().enumerate() {
+            let cell = legacy_to_cell(value)
+                .ok_or(EncodingError::InvalidCellCode { row, col, value })?;
+            if let Some(position) = Position::new(row, col) {
+                board.set_cell(position, cell);
+            }
+        }
+    }
+    Ok(board)
+}
+
+/// 盤面を正規形式にエンコードする（盤面の大きさの正方形になる）
+pub fn encode_board(board: &Board) -> CanonicalBoard {
+    let size = board.size();
+    (0..size)
+        .map(|row| {
+            (0..size)
+                .map(|col| {
+                    Position::within(row, col, size)
+                        .and_then(|pos| board.get_cell(pos))
+                        .and_then(cell_to_canonical)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// 正規形式の盤面をデコードする（対応している大きさの正方形のみ）
+pub fn decode_board(encoded: &CanonicalBoard) -> Result<Board, EncodingError> {
+    let size = encoded.len();
+    let cols = encoded.first().map_or(0, |row| row.len());
+    let board = encoded
+        .iter()
+        .all(|row| row.len() == size)
+        .then(|| Board::with_size(size))
+        .flatten();
+    let Some(mut board) = board else {
+        return Err(EncodingError::InvalidDimensions { rows: size, cols });
+    };
+
+    for (row, cells) in encoded.iter().enumerate() {
+        for (col, &value) in cells.iter().enumerate() {
+            if let Some(position) = Position::within(row, col, size) {
+                board.set_cell(position, canonical_to_cell(value));
+            }
+        }
+    }
+    Ok(board)
+}
+
+/// 旧API形式から正規形式へ変換する
+pub fn legacy_to_canonical(encoded: &LegacyBoard) -> Result<CanonicalBoard, EncodingError> {
+    decode_legacy_board(encoded).map(|board| encode_board(&board))
+}
+
+/// 正規形式から旧API形式へ変換する
+pub fn canonical_to_legacy(encoded: &CanonicalBoard) -> Result<LegacyBoard, EncodingError> {
+    decode_board(encoded).map(|board| encode_legacy_board(&board))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{GameState, ReversiRules};
+
+    const ALL_CELLS: [Cell; 3] = [Cell::Empty, Cell::Black, Cell::White];
+
+    /// 全セル値を網羅する盤面を生成する
+    fn patterned_board(seed: usize) -> Board {
+        let mut board = Board::new();
+        for row in 0..BOARD_SIZE {
+            for col in 0..BOARD_SIZE {
+                let cell = ALL_CELLS[(row * BOARD_SIZE + col + seed) % ALL_CELLS.len()];
+                board.set_cell(Position::new(row, col).unwrap(), cell);
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn test_cell_legacy_round_trip_exhaustive() {
+        for cell in ALL_CELLS {
+            assert_eq!(legacy_to_cell(cell_to_legacy(cell)), Some(cell));
+        }
+        for value in 0..=u8::MAX {
+            match legacy_to_cell(value) {
+                Some(cell) => assert_eq!(cell_to_legacy(cell), value),
+                None => assert!(value > LEGACY_WHITE),
+            }
+        }
+    }
+
+    #[test]
+    fn test_cell_canonical_round_trip_exhaustive() {
+        for cell in ALL_CELLS {
+            assert_eq!(canonical_to_cell(cell_to_canonical(cell)), cell);
+        }
+        for value in [None, Some(Player::Black), Some(Player::White)] {
+            assert_eq!(cell_to_canonical(canonical_to_cell(value)), value);
+        }
+    }
+
+    #[test]
+    fn test_player_legacy_round_trip() {
+        for player in [Player::Black, Player::White] {
+            assert_eq!(legacy_to_player(player_to_legacy(player)), Ok(player));
+        }
+        assert!(legacy_to_player(LEGACY_EMPTY).is_err());
+        assert!(legacy_to_player(3).is_err());
+    }
+
+    #[test]
+    fn test_board_round_trips() {
+        for seed in 0..ALL_CELLS.len() {
+            let board = patterned_board(seed);
+
+            assert_eq!(decode_legacy_board(&encode_legacy_board(&board)).unwrap(), board);
+            assert_eq!(decode_board(&encode_board(&board)).unwrap(), board);
+
+            let legacy = encode_legacy_board(&board);
+            let canonical = encode_board(&board);
+            assert_eq!(legacy_to_canonical(&legacy).unwrap(), canonical);
+            assert_eq!(canonical_to_legacy(&canonical).unwrap(), legacy);
+        }
+    }
+
+    #[test]
+    fn test_board_round_trip_during_game() {
+        let mut game_state = GameState::new();
+        while let Some(&position) = ReversiRules::get_valid_moves(&game_state.board, game_state.current_player).first() {
+            ReversiRules::apply_move(&mut game_state, position).unwrap();
+            game_state.switch_player();
+            ReversiRules::handle_turn(&mut game_state);
+
+            let legacy = encode_legacy_board(&game_state.board);
+            let canonical = encode_board(&game_state.board);
+            assert_eq!(legacy_to_canonical(&legacy).unwrap(), canonical);
+            assert_eq!(decode_board(&canonical).unwrap(), game_state.board);
+
+            if game_state.is_finished() {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn test_api_player_serialization() {
+        #[derive(Serialize, Deserialize)]
+        struct Dto {
+            #[serde(with = "api_player")]
+            player: Player,
+            #[serde(with = "api_player::option")]
+            winner: Option<Player>,
+            #[serde(with = "api_player::board")]
+            board: CanonicalBoard,
+        }
+
+        let dto = Dto { player: Player::Black, winner: None, board: encode_board(&Board::new()) };
+        let json = serde_json::to_value(&dto).unwrap();
+        assert_eq!(json["player"], "black");
+        assert!(json["winner"].is_null());
+        assert_eq!(json["board"][3][3], "white");
+        assert_eq!(json["board"][3][4], "black");
+
+        let legacy_input = r#"{"player": "White", "winner": "Black", "board": [["black", null]]}"#;
+        let parsed: Dto = serde_json::from_str(legacy_input).unwrap();
+        assert_eq!(parsed.player, Player::White);
+        assert_eq!(parsed.winner, Some(Player::Black));
+        assert_eq!(parsed.board, vec![vec![Some(Player::Black), None]]);
+    }
+
+    #[test]
+    fn test_decode_legacy_board_invalid_value() {
+        let mut legacy = encode_legacy_board(&Board::new());
+        legacy[2][5] = 7;
+
+        assert_eq!(
+            decode_legacy_board(&legacy),
+            Err(EncodingError::InvalidCellCode { row: 2, col: 5, value: 7 })
+        );
+    }
+
+    #[test]
+    fn test_decode_board_invalid_dimensions() {
+        let mut canonical = encode_board(&Board::new());
+        canonical[3].pop();
+        assert!(matches!(decode_board(&canonical), Err(EncodingError::InvalidDimensions { .. })));
+
+        canonical.truncate(4);
+        assert!(matches!(decode_board(&canonical), Err(EncodingError::InvalidDimensions { rows: 4, .. })));
+
+        // 対応している大きさの正方形ならそのまま往復できる
+        let small = Board::with_size(6).unwrap();
+        assert_eq!(encode_board(&small).len(), 6);
+        assert_eq!(decode_board(&encode_board(&small)).unwrap(), small);
+    }
+}
@@ -0,0 +1,490 @@
+//! GraphQL API
+//! ダッシュボード向けに、セッション一覧・局面・着手履歴・難易度別の統計を1回のクエリで取得できるようにする。
+//! `/api/graphql` でクエリを受け付け、`/api/graphql/ws`（graphql-wsプロトコル）で対局のイベントを購読できる。
+//! 読み取り専用で、局面を取得しても人間の思考時間の計測には影響しない。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_graphql::{
+    Context, EmptyMutation, Enum, ErrorExtensions, Object, Schema, SimpleObject, Subscription, Union,
+};
+use async_graphql_axum::{GraphQL, GraphQLSubscription};
+use axum::{routing::post_service, Router};
+use chrono::{DateTime, Utc};
+use futures::stream::{Stream, StreamExt};
+use uuid::Uuid;
+
+use super::ai_battle::{
+    AiBattleError, AiBattleResponse, AiBattleService, AiBattleSession, AiDifficulty, GameStatus, MoveRecord,
+    SessionEvent, SessionFilter, SessionKind, SessionStatusFilter, MAX_SESSIONS_PER_PAGE,
+};
+use super::timeout::WithTimeout;
+use crate::game::{Player, Position};
+
+pub type ReversiSchema = Schema<QueryRoot, EmptyMutation, SubscriptionRoot>;
+
+/// `AiBattleService` を参照するスキーマを作成する
+pub fn build_schema(service: Arc<AiBattleService>) -> ReversiSchema {
+    Schema::build(QueryRoot, EmptyMutation, SubscriptionRoot).data(service).finish()
+}
+
+/// GraphQL APIのルートを作成する
+/// 購読のWebSocketには処理時間の上限を設定しない
+pub fn create_graphql_routes<S>(service: Arc<AiBattleService>, budget: Duration) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let schema = build_schema(service);
+    Router::new()
+        .route("/api/graphql", post_service(GraphQL::new(schema.clone())).with_timeout(budget))
+        .route_service("/api/graphql/ws", GraphQLSubscription::new(schema))
+}
+
+fn service<'a>(ctx: &Context<'a>) -> &'a Arc<AiBattleService> {
+    ctx.data_unchecked::<Arc<AiBattleService>>()
+}
+
+/// エラーの種類（`error_code`）を `extensions.code` に入れる
+fn graphql_error(error: AiBattleError) -> async_graphql::Error {
+    let code = error.error_code();
+    async_graphql::Error::new(error.to_string()).extend_with(|_, extensions| extensions.set("code", code))
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// 条件に合うセッション（最後の着手が新しい順、最大 `limit` 件）
+    async fn sessions(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<SessionStatus>,
+        difficulty: Option<Difficulty>,
+        #[graphql(default = 20)] limit: usize,
+    ) -> async_graphql::Result<Vec<Session>> {
+        if !(1..=MAX_SESSIONS_PER_PAGE).contains(&limit) {
+            return Err(graphql_error(AiBattleError::BadRequest {
+                details: format!("limit は1〜{}で指定してください", MAX_SESSIONS_PER_PAGE),
+            }));
+        }
+        let filter = SessionFilter {
+            status: status.map(SessionStatusFilter::from),
+            difficulty: difficulty.map(AiDifficulty::from),
+        };
+        let mut sessions = service(ctx).list_sessions_or_stored(&filter).await;
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.last_move_at));
+        Ok(sessions.into_iter().take(limit).map(Session).collect())
+    }
+
+    /// IDを指定したセッション（存在しない場合はnull）
+    async fn session(&self, ctx: &Context<'_>, id: Uuid) -> async_graphql::Result<Option<Session>> {
+        match service(ctx).get_session(id) {
+            Ok(session) => Ok(Some(Session(session))),
+            Err(AiBattleError::GameNotFound { .. }) => Ok(None),
+            Err(error) => Err(graphql_error(error)),
+        }
+    }
+
+    /// 難易度別の対局統計（難易度の低い順）
+    async fn difficulty_stats(&self, ctx: &Context<'_>) -> Vec<DifficultyStats> {
+        service(ctx).difficulty_stats().difficulties.into_iter().map(DifficultyStats::from).collect()
+    }
+}
+
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// 対局のイベント（終局すると終了する）
+    async fn game_events(
+        &self,
+        ctx: &Context<'_>,
+        game_id: Uuid,
+    ) -> async_graphql::Result<impl Stream<Item = GameEvent>> {
+        let events = service(ctx).subscribe_events(game_id).map_err(graphql_error)?;
+        Ok(events.map(GameEvent::from))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum Color {
+    Black,
+    White,
+}
+
+impl From<Player> for Color {
+    fn from(player: Player) -> Self {
+        match player {
+            Player::Black => Color::Black,
+            Player::White => Color::White,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl From<Difficulty> for AiDifficulty {
+    fn from(difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Easy => AiDifficulty::Easy,
+            Difficulty::Medium => AiDifficulty::Medium,
+            Difficulty::Hard => AiDifficulty::Hard,
+        }
+    }
+}
+
+impl From<AiDifficulty> for Difficulty {
+    fn from(difficulty: AiDifficulty) -> Self {
+        match difficulty {
+            AiDifficulty::Easy => Difficulty::Easy,
+            AiDifficulty::Medium => Difficulty::Medium,
+            AiDifficulty::Hard => Difficulty::Hard,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum SessionStatus {
+    InProgress,
+    Finished,
+}
+
+impl From<SessionStatus> for SessionStatusFilter {
+    fn from(status: SessionStatus) -> Self {
+        match status {
+            SessionStatus::InProgress => SessionStatusFilter::InProgress,
+            SessionStatus::Finished => SessionStatusFilter::Finished,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+#[graphql(name = "SessionKind")]
+pub enum Kind {
+    HumanVsAi,
+    AiVsAi,
+    HumanVsHuman,
+}
+
+impl From<SessionKind> for Kind {
+    fn from(kind: SessionKind) -> Self {
+        match kind {
+            SessionKind::HumanVsAi => Kind::HumanVsAi,
+            SessionKind::AiVsAi => Kind::AiVsAi,
+            SessionKind::HumanVsHuman => Kind::HumanVsHuman,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, SimpleObject)]
+#[graphql(name = "Position")]
+pub struct Square {
+    pub row: usize,
+    pub col: usize,
+    /// "d3" 形式の表記
+    pub notation: String,
+}
+
+impl From<Position> for Square {
+    fn from(position: Position) -> Self {
+        Self { row: position.row, col: position.col, notation: position.to_string() }
+    }
+}
+
+/// セッション（局面と着手履歴は同じ時点のもの）
+pub struct Session(AiBattleSession);
+
+#[Object]
+impl Session {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn kind(&self) -> Kind {
+        self.0.kind().into()
+    }
+
+    async fn ai_difficulty(&self) -> Difficulty {
+        self.0.ai_difficulty.into()
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    async fn last_move_at(&self) -> DateTime<Utc> {
+        self.0.last_move_at
+    }
+
+    async fn pinned(&self) -> bool {
+        self.0.pinned
+    }
+
+    /// セッションを作成したAPIキーの利用者名
+    async fn api_key_name(&self) -> Option<&str> {
+        self.0.api_key_name.as_deref()
+    }
+
+    /// 現在の局面
+    async fn state(&self) -> GameState {
+        AiBattleResponse::from_session(&self.0).into()
+    }
+
+    /// 着手とパスの履歴（待ったで取り消した手を除く）
+    async fn history(&self) -> Vec<Move> {
+        self.0.transcript_records().into_iter().map(Move::from).collect()
+    }
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+pub struct GameState {
+    pub board_size: usize,
+    /// 行ごとのマス（石がなければnull）
+    pub board: Vec<Vec<Option<Color>>>,
+    pub current_player: Color,
+    pub black_count: u8,
+    pub white_count: u8,
+    pub finished: bool,
+    /// 終局して引き分けでない場合の勝者
+    pub winner: Option<Color>,
+    pub valid_moves: Vec<Square>,
+    pub must_pass: bool,
+    pub move_count: u32,
+    /// 着手・パス・待ったのたびに進む局面の版
+    pub version: u64,
+    pub ai_thinking: bool,
+    pub position_hash: String,
+}
+
+impl From<AiBattleResponse> for GameState {
+    fn from(response: AiBattleResponse) -> Self {
+        let winner = match response.status {
+            GameStatus::Finished { winner } => winner,
+            GameStatus::InProgress => None,
+        };
+        Self {
+            board_size: response.board_size,
+            board: response
+                .board
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.map(Color::from)).collect())
+                .collect(),
+            current_player: response.current_player.into(),
+            black_count: response.black_count,
+            white_count: response.white_count,
+            finished: matches!(response.status, GameStatus::Finished { .. }),
+            winner: winner.map(Color::from),
+            valid_moves: response.valid_moves.into_iter().map(Square::from).collect(),
+            must_pass: response.must_pass,
+            move_count: response.move_count,
+            version: response.version,
+            ai_thinking: response.ai_thinking,
+            position_hash: response.position_hash,
+        }
+    }
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+pub struct Move {
+    pub seq: u64,
+    pub player: Color,
+    /// パスの場合はnull
+    pub position: Option<Square>,
+    /// "d3" 形式の表記（パスは "pass"）
+    pub notation: String,
+    pub timestamp: DateTime<Utc>,
+    pub thinking_time_ms: Option<u64>,
+}
+
+impl From<MoveRecord> for Move {
+    fn from(record: MoveRecord) -> Self {
+        Self {
+            seq: record.seq,
+            player: record.player.into(),
+            position: record.position.map(Square::from),
+            notation: record.notation,
+            timestamp: record.timestamp,
+            thinking_time_ms: record.thinking_time_ms,
+        }
+    }
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+pub struct DifficultyStats {
+    pub difficulty: Difficulty,
+    pub games: u32,
+    pub human_wins: u32,
+    pub ai_wins: u32,
+    pub draws: u32,
+    pub human_win_rate: Option<f64>,
+    pub average_moves: Option<f64>,
+    pub average_duration_seconds: Option<f64>,
+    pub average_ai_thinking_ms: Option<f64>,
+    pub average_human_thinking_ms: Option<f64>,
+}
+
+impl From<crate::stats::DifficultyStats> for DifficultyStats {
+    fn from(stats: crate::stats::DifficultyStats) -> Self {
+        Self {
+            difficulty: stats.difficulty.into(),
+            games: stats.games,
+            human_wins: stats.human_wins,
+            ai_wins: stats.ai_wins,
+            draws: stats.draws,
+            human_win_rate: stats.human_win_rate,
+            average_moves: stats.average_moves,
+            average_duration_seconds: stats.average_duration_seconds,
+            average_ai_thinking_ms: stats.average_ai_thinking_ms,
+            average_human_thinking_ms: stats.average_human_thinking_ms,
+        }
+    }
+}
+
+/// 着手またはパス（`position` がnull）が行われた
+#[derive(Debug, Clone, SimpleObject)]
+pub struct MoveMade {
+    pub game_id: Uuid,
+    pub seq: u64,
+    pub player: Color,
+    pub position: Option<Square>,
+    pub next_player: Color,
+    pub black_count: u8,
+    pub white_count: u8,
+    pub move_count: u32,
+}
+
+/// AIが思考を開始・終了した
+#[derive(Debug, Clone, SimpleObject)]
+pub struct AiThinking {
+    pub game_id: Uuid,
+    pub player: Color,
+    pub thinking: bool,
+}
+
+/// 対局が終了した
+#[derive(Debug, Clone, SimpleObject)]
+pub struct GameFinished {
+    pub game_id: Uuid,
+    /// 引き分けの場合はnull
+    pub winner: Option<Color>,
+}
+
+#[derive(Debug, Clone, Union)]
+pub enum GameEvent {
+    MoveMade(MoveMade),
+    AiThinking(AiThinking),
+    GameFinished(GameFinished),
+}
+
+impl From<SessionEvent> for GameEvent {
+    fn from(event: SessionEvent) -> Self {
+        match event {
+            SessionEvent::MoveMade { game_id, seq, player, position, next_player, black_count, white_count, move_count } => {
+                GameEvent::MoveMade(MoveMade {
+                    game_id,
+                    seq,
+                    player: player.into(),
+                    position: position.map(Square::from),
+                    next_player: next_player.into(),
+                    black_count,
+                    white_count,
+                    move_count,
+                })
+            }
+            SessionEvent::AiThinking { game_id, player, thinking } => {
+                GameEvent::AiThinking(AiThinking { game_id, player: player.into(), thinking })
+            }
+            SessionEvent::GameFinished { game_id, winner } => {
+                GameEvent::GameFinished(GameFinished { game_id, winner: winner.map(Color::from) })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::service::AIServiceFactory;
+    use crate::session::AiBattleSessionManager;
+    use serde_json::json;
+
+    fn schema() -> (ReversiSchema, Arc<AiBattleService>) {
+        let session_manager = Arc::new(AiBattleSessionManager::new(10));
+        let ai_service = AIServiceFactory::create_fast_local().unwrap();
+        let service = Arc::new(AiBattleService::new_with_ai_service(session_manager, ai_service.into()));
+        (build_schema(Arc::clone(&service)), service)
+    }
+
+    #[tokio::test]
+    async fn test_sessions_states_histories_and_stats_in_one_query() {
+        let (schema, service) = schema();
+        let created = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let response = service.make_player_move(created.game_id, created.valid_moves[0]).await.unwrap();
+
+        let result = schema
+            .execute(
+                "{ sessions(status: IN_PROGRESS) { id kind state { currentPlayer blackCount whiteCount version } \
+                 history { seq player notation } } difficultyStats { difficulty games } }",
+            )
+            .await;
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let data = result.data.into_json().unwrap();
+
+        let session = &data["sessions"][0];
+        assert_eq!(session["id"], json!(created.game_id.to_string()));
+        assert_eq!(session["kind"], json!("HUMAN_VS_AI"));
+        assert_eq!(session["state"]["currentPlayer"], json!("BLACK"));
+        assert_eq!(session["state"]["blackCount"], json!(response.game_state.black_count));
+        assert_eq!(session["state"]["version"], json!(response.game_state.version));
+        let history = session["history"].as_array().unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0]["player"], json!("BLACK"));
+        assert_eq!(history[0]["notation"], json!(created.valid_moves[0].to_string()));
+        assert_eq!(data["difficultyStats"].as_array().unwrap().len(), AiDifficulty::all().len());
+    }
+
+    #[tokio::test]
+    async fn test_missing_session_is_null_and_invalid_limit_is_rejected() {
+        let (schema, _) = schema();
+        let result = schema.execute(format!("{{ session(id: \"{}\") {{ id }} }}", Uuid::new_v4())).await;
+        assert!(result.errors.is_empty());
+        assert_eq!(result.data.into_json().unwrap(), json!({ "session": null }));
+
+        let result = schema.execute("{ sessions(limit: 0) { id } }").await;
+        let extensions = serde_json::to_value(&result.errors[0].extensions).unwrap();
+        assert_eq!(extensions["code"], json!("BAD_REQUEST"));
+    }
+
+    #[tokio::test]
+    async fn test_game_events_subscription() {
+        let (schema, service) = schema();
+        let created = service.create_ai_battle(AiDifficulty::Easy).await.unwrap();
+        let mut events = schema.execute_stream(format!(
+            "subscription {{ gameEvents(gameId: \"{}\") {{ __typename ... on MoveMade {{ seq player position {{ notation }} }} }} }}",
+            created.game_id
+        ));
+        // 購読の開始はストリームを最初にポーリングした時点のため、着手と並行して待つ
+        let (first, _) = tokio::join!(events.next(), async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            service.make_player_move(created.game_id, created.valid_moves[0]).await.unwrap()
+        });
+        let first = first.unwrap().data.into_json().unwrap();
+        assert_eq!(first["gameEvents"]["__typename"], json!("MoveMade"));
+        assert_eq!(first["gameEvents"]["player"], json!("BLACK"));
+        assert_eq!(first["gameEvents"]["position"]["notation"], json!(created.valid_moves[0].to_string()));
+
+        let missing = schema
+            .execute_stream(format!("subscription {{ gameEvents(gameId: \"{}\") {{ __typename }} }}", Uuid::new_v4()))
+            .next()
+            .await
+            .unwrap();
+        let extensions = serde_json::to_value(&missing.errors[0].extensions).unwrap();
+        assert_eq!(extensions["code"], json!("GAME_NOT_FOUND"));
+    }
+}
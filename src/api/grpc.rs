@@ -0,0 +1,346 @@
+//! gRPC API
+//! proto/reversi.proto の定義に沿って、対局の作成・着手・状態の取得・イベントの購読をgRPCで公開する。
+//! REST APIのハンドラーと同じ `AiBattleService` を呼び出し、エラーはHTTPステータスに対応するgRPCのステータスに変換する。
+//! APIキー認証が有効な場合は `x-api-key` メタデータでキーを検証する。
+
+// tonicのインターセプターとサービスは `Result<_, Status>` を返す決まりのため、Statusの大きさは変えられない
+#![allow(clippy::result_large_err)]
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use futures::stream::{Stream, StreamExt};
+use tonic::metadata::MetadataValue;
+use tonic::{Code, Request, Response, Status};
+use uuid::Uuid;
+
+use super::ai_battle::{
+    validate_position, AiBattleError, AiBattleResponse, AiBattleService, AiDifficulty, GameStatus, MoveResponse,
+    SessionEvent,
+};
+use super::auth::{ApiKeyRegistry, API_KEY_HEADER};
+use crate::game::{Player, Position};
+
+/// proto/reversi.proto から生成したメッセージとサービス
+pub mod proto {
+    tonic::include_proto!("reversi.v1");
+}
+
+use proto::ai_battle_server::{AiBattle, AiBattleServer};
+use proto::game_event::Event;
+
+/// エラーの種類（`error_code`）を入れるメタデータ名
+pub const ERROR_CODE_METADATA: &str = "x-error-code";
+
+/// gRPC APIのサーバーを起動する（停止するまで戻らない）
+pub async fn serve(
+    service: Arc<AiBattleService>,
+    api_keys: ApiKeyRegistry,
+    address: SocketAddr,
+) -> Result<(), tonic::transport::Error> {
+    let api_keys = Arc::new(api_keys);
+    let service = AiBattleServer::with_interceptor(GrpcAiBattle::new(service), move |request| {
+        require_api_key(&api_keys, request)
+    });
+    tonic::transport::Server::builder().add_service(service).serve(address).await
+}
+
+/// 認証が有効であれば `x-api-key` のキーを検証し、利用者名をリクエストに付与する
+fn require_api_key(registry: &ApiKeyRegistry, mut request: Request<()>) -> Result<Request<()>, Status> {
+    if !registry.is_enabled() {
+        return Ok(request);
+    }
+    let Some(key) = request.metadata().get(API_KEY_HEADER) else {
+        return Err(Status::unauthenticated("APIキーを指定してください"));
+    };
+    let identity = key
+        .to_str()
+        .ok()
+        .and_then(|key| registry.resolve(key.trim()))
+        .ok_or_else(|| Status::unauthenticated("APIキーが無効です"))?;
+    request.extensions_mut().insert(identity);
+    Ok(request)
+}
+
+/// REST APIと同じサービス層を呼び出すgRPCサービス
+pub struct GrpcAiBattle {
+    service: Arc<AiBattleService>,
+}
+
+impl GrpcAiBattle {
+    pub fn new(service: Arc<AiBattleService>) -> Self {
+        Self { service }
+    }
+}
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<proto::GameEvent, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl AiBattle for GrpcAiBattle {
+    async fn create_game(
+        &self,
+        request: Request<proto::CreateGameRequest>,
+    ) -> Result<Response<proto::GameState>, Status> {
+        let request = request.into_inner();
+        let difficulty = match request.difficulty() {
+            proto::Difficulty::Unspecified | proto::Difficulty::Medium => AiDifficulty::Medium,
+            proto::Difficulty::Easy => AiDifficulty::Easy,
+            proto::Difficulty::Hard => AiDifficulty::Hard,
+        };
+        let color = player_from_color(request.player_color()).unwrap_or(Player::Black);
+        let response = self.service.create_ai_battle_with_color(difficulty, color).await?;
+        Ok(Response::new(response.into()))
+    }
+
+    async fn make_move(&self, request: Request<proto::MoveRequest>) -> Result<Response<proto::MoveReply>, Status> {
+        let request = request.into_inner();
+        let game_id = parse_game_id(&request.game_id)?;
+        let position = request
+            .position
+            .ok_or_else(|| Status::invalid_argument("position を指定してください"))?;
+        self.service.authorize_play(game_id, None)?;
+
+        let board_size = self.service.board_size(game_id)?;
+        let coordinate = |value: u32| u8::try_from(value).unwrap_or(u8::MAX);
+        let position = validate_position(coordinate(position.row), coordinate(position.col), board_size)
+            .map_err(|reason| AiBattleError::InvalidPosition { reason })?;
+        let response = self.service.make_player_move(game_id, position).await?;
+        Ok(Response::new(response.into()))
+    }
+
+    async fn get_state(&self, request: Request<proto::GetStateRequest>) -> Result<Response<proto::GameState>, Status> {
+        let game_id = parse_game_id(&request.into_inner().game_id)?;
+        Ok(Response::new(self.service.get_game_state(game_id)?.into()))
+    }
+
+    type StreamEventsStream = EventStream;
+
+    async fn stream_events(
+        &self,
+        request: Request<proto::StreamEventsRequest>,
+    ) -> Result<Response<Self::StreamEventsStream>, Status> {
+        let game_id = parse_game_id(&request.into_inner().game_id)?;
+        let events = self.service.subscribe_events(game_id)?;
+        Ok(Response::new(Box::pin(events.map(|event| Ok(event.into())))))
+    }
+}
+
+fn parse_game_id(game_id: &str) -> Result<Uuid, Status> {
+    game_id
+        .parse()
+        .map_err(|_| Status::invalid_argument(format!("ゲームIDが不正です: {}", game_id)))
+}
+
+impl From<AiBattleError> for Status {
+    fn from(error: AiBattleError) -> Self {
+        let code = match error.status_code() {
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => Code::InvalidArgument,
+            StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+            StatusCode::FORBIDDEN => Code::PermissionDenied,
+            StatusCode::NOT_FOUND => Code::NotFound,
+            StatusCode::CONFLICT => Code::FailedPrecondition,
+            StatusCode::TOO_MANY_REQUESTS => Code::ResourceExhausted,
+            StatusCode::GATEWAY_TIMEOUT => Code::DeadlineExceeded,
+            _ => Code::Internal,
+        };
+        let mut status = Status::new(code, error.to_string());
+        status
+            .metadata_mut()
+            .insert(ERROR_CODE_METADATA, MetadataValue::from_static(error.error_code()));
+        status
+    }
+}
+
+fn color(player: Option<Player>) -> proto::Color {
+    match player {
+        Some(Player::Black) => proto::Color::Black,
+        Some(Player::White) => proto::Color::White,
+        None => proto::Color::Unspecified,
+    }
+}
+
+fn player_from_color(color: proto::Color) -> Option<Player> {
+    match color {
+        proto::Color::Black => Some(Player::Black),
+        proto::Color::White => Some(Player::White),
+        proto::Color::Unspecified => None,
+    }
+}
+
+impl From<Position> for proto::Position {
+    fn from(position: Position) -> Self {
+        Self { row: position.row as u32, col: position.col as u32 }
+    }
+}
+
+fn positions(positions: Vec<Position>) -> Vec<proto::Position> {
+    positions.into_iter().map(proto::Position::from).collect()
+}
+
+impl From<AiBattleResponse> for proto::GameState {
+    fn from(response: AiBattleResponse) -> Self {
+        let winner = match response.status {
+            GameStatus::Finished { winner } => winner,
+            GameStatus::InProgress => None,
+        };
+        Self {
+            game_id: response.game_id.to_string(),
+            board_size: response.board_size as u32,
+            cells: response.board.iter().flatten().map(|&cell| color(cell) as i32).collect(),
+            current_player: color(Some(response.current_player)) as i32,
+            black_count: response.black_count as u32,
+            white_count: response.white_count as u32,
+            finished: matches!(response.status, GameStatus::Finished { .. }),
+            winner: color(winner) as i32,
+            valid_moves: positions(response.valid_moves),
+            must_pass: response.must_pass,
+            move_count: response.move_count,
+            version: response.version,
+            ai_thinking: response.ai_thinking,
+        }
+    }
+}
+
+impl From<MoveResponse> for proto::MoveReply {
+    fn from(response: MoveResponse) -> Self {
+        Self {
+            game_state: Some(response.game_state.into()),
+            player_move: Some(response.player_move.into()),
+            flipped_positions: positions(response.flipped_positions),
+            ai_move: response.ai_move.map(proto::Position::from),
+            ai_flipped_positions: positions(response.ai_flipped_positions.unwrap_or_default()),
+            passed: color(response.passed) as i32,
+        }
+    }
+}
+
+impl From<SessionEvent> for proto::GameEvent {
+    fn from(event: SessionEvent) -> Self {
+        let event = match event {
+            SessionEvent::MoveMade { game_id, seq, player, position, next_player, black_count, white_count, move_count } => {
+                Event::MoveMade(proto::MoveMade {
+                    game_id: game_id.to_string(),
+                    seq,
+                    player: color(Some(player)) as i32,
+                    position: position.map(proto::Position::from),
+                    next_player: color(Some(next_player)) as i32,
+                    black_count: black_count as u32,
+                    white_count: white_count as u32,
+                    move_count,
+                })
+            }
+            SessionEvent::AiThinking { game_id, player, thinking } => Event::AiThinking(proto::AiThinking {
+                game_id: game_id.to_string(),
+                player: color(Some(player)) as i32,
+                thinking,
+            }),
+            SessionEvent::GameFinished { game_id, winner } => Event::GameFinished(proto::GameFinished {
+                game_id: game_id.to_string(),
+                winner: color(winner) as i32,
+            }),
+        };
+        Self { event: Some(event) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::service::AIServiceFactory;
+    use crate::api::auth::ApiKeyIdentity;
+    use crate::config::{ApiKeyEntry, AuthConfig};
+    use crate::session::AiBattleSessionManager;
+
+    fn grpc_service() -> GrpcAiBattle {
+        let session_manager = Arc::new(AiBattleSessionManager::new(10));
+        let ai_service = AIServiceFactory::create_fast_local().unwrap();
+        GrpcAiBattle::new(Arc::new(AiBattleService::new_with_ai_service(session_manager, ai_service.into())))
+    }
+
+    #[tokio::test]
+    async fn test_game_can_be_played_over_grpc() {
+        let grpc = grpc_service();
+        let created = grpc
+            .create_game(Request::new(proto::CreateGameRequest {
+                difficulty: proto::Difficulty::Easy as i32,
+                player_color: proto::Color::Unspecified as i32,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(created.cells.len(), 64);
+        assert_eq!(created.current_player(), proto::Color::Black);
+
+        let mut events = grpc
+            .stream_events(Request::new(proto::StreamEventsRequest { game_id: created.game_id.clone() }))
+            .await
+            .unwrap()
+            .into_inner();
+        let reply = grpc
+            .make_move(Request::new(proto::MoveRequest {
+                game_id: created.game_id.clone(),
+                position: Some(created.valid_moves[0]),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(reply.ai_move.is_some());
+        assert!(!reply.flipped_positions.is_empty());
+
+        let Some(Event::MoveMade(first)) = events.next().await.unwrap().unwrap().event else {
+            panic!("expected the player's move first");
+        };
+        assert_eq!((first.player(), first.position), (proto::Color::Black, Some(created.valid_moves[0])));
+
+        let state = grpc
+            .get_state(Request::new(proto::GetStateRequest { game_id: created.game_id }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(Some(state), reply.game_state);
+    }
+
+    #[tokio::test]
+    async fn test_errors_map_to_grpc_status_codes() {
+        let grpc = grpc_service();
+        let missing = grpc
+            .get_state(Request::new(proto::GetStateRequest { game_id: Uuid::new_v4().to_string() }))
+            .await
+            .unwrap_err();
+        assert_eq!(missing.code(), Code::NotFound);
+        assert_eq!(missing.metadata().get(ERROR_CODE_METADATA).unwrap(), "GAME_NOT_FOUND");
+
+        let malformed = grpc
+            .get_state(Request::new(proto::GetStateRequest { game_id: "not-a-uuid".to_string() }))
+            .await
+            .unwrap_err();
+        assert_eq!(malformed.code(), Code::InvalidArgument);
+
+        let created = grpc.create_game(Request::new(proto::CreateGameRequest::default())).await.unwrap().into_inner();
+        let invalid = grpc
+            .make_move(Request::new(proto::MoveRequest {
+                game_id: created.game_id,
+                position: Some(proto::Position { row: 0, col: 0 }),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(invalid.code(), Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_api_key_is_checked_when_enabled() {
+        let registry = ApiKeyRegistry::from_config(&AuthConfig {
+            api_keys: vec![ApiKeyEntry { name: "bot".to_string(), key: "secret".to_string() }],
+            ..Default::default()
+        });
+        assert!(require_api_key(&ApiKeyRegistry::default(), Request::new(())).is_ok());
+        assert_eq!(require_api_key(&registry, Request::new(())).unwrap_err().code(), Code::Unauthenticated);
+
+        let mut request = Request::new(());
+        request.metadata_mut().insert(API_KEY_HEADER, MetadataValue::from_static("secret"));
+        let request = require_api_key(&registry, request).unwrap();
+        assert_eq!(request.extensions().get::<ApiKeyIdentity>(), Some(&ApiKeyIdentity("bot".to_string())));
+    }
+}
@@ -1,33 +1,71 @@
 use axum::{
-    extract::{Path, State},
+    extract::{FromRef, Path, State},
     http::StatusCode,
     response::Json,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::{
+    openapi::{Array, ArrayBuilder, ObjectBuilder, SchemaType},
+    ToSchema,
+};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::{
-    game::{GameState, Position, Player, ReversiRules},
+    game::{transcript, GameState, Position, Player, ReversiRules},
     ai::{Difficulty},
     error::GameError,
-    api::ai_battle::service::AiBattleService,
+    api::{
+        ai_battle::service::AiBattleService,
+        encoding,
+        health::{AiBackendRole, HealthRegistry},
+    },
     session::AiBattleSessionManager,
 };
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct GameResponse {
     pub id: Uuid,
-    pub board: [[u8; 8]; 8],        // 0: Empty, 1: Black, 2: White
+    #[schema(schema_with = legacy_board_schema)]
+    pub board: encoding::LegacyBoard,  // 0: Empty, 1: Black, 2: White（非推奨）
     pub current_player: u8,          // 1: Black, 2: White
     pub valid_moves: Vec<[usize; 2]>,
     pub game_status: String,
+    #[schema(schema_with = score_schema)]
     pub score: (u8, u8),
     pub move_count: u32,
 }
 
-#[derive(Debug, Serialize)]
+/// 0〜`max`の整数の固定長配列スキーマを生成する
+fn fixed_integer_array(len: usize, max: f64) -> ArrayBuilder {
+    ArrayBuilder::new()
+        .items(
+            ObjectBuilder::new()
+                .schema_type(SchemaType::Integer)
+                .minimum(Some(0.0))
+                .maximum(Some(max)),
+        )
+        .min_items(Some(len))
+        .max_items(Some(len))
+}
+
+/// 旧API盤面（8x8、0: 空, 1: 黒, 2: 白）のスキーマ
+fn legacy_board_schema() -> Array {
+    ArrayBuilder::new()
+        .items(fixed_integer_array(encoding::BOARD_SIZE, 2.0))
+        .min_items(Some(encoding::BOARD_SIZE))
+        .max_items(Some(encoding::BOARD_SIZE))
+        .build()
+}
+
+/// スコア（黒石数, 白石数）のスキーマ
+fn score_schema() -> Array {
+    fixed_integer_array(2, 64.0).build()
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = LegacyMoveResponse)]
 pub struct MoveResponse {
     pub success: bool,
     pub game_state: GameResponse,
@@ -35,34 +73,52 @@ pub struct MoveResponse {
     pub message: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = LegacyErrorResponse)]
 pub struct ErrorResponse {
     pub error: String,
     pub details: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateGameRequest {
     pub player1_type: PlayerTypeRequest,
     pub player2_type: PlayerTypeRequest,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub enum PlayerTypeRequest {
     Human { name: String },
     AI { difficulty: Difficulty },
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct MakeMoveRequest {
     pub row: usize,
     pub col: usize,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImportGameRequest {
+    /// 標準の棋譜形式の着手列（パスは書かない）
+    #[schema(example = "F5D6C3D3C4")]
+    pub transcript: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportGameResponse {
+    pub game_state: GameResponse,
+    /// 大文字・空白なしに正規化した棋譜
+    pub transcript: String,
+}
+
 #[derive(Debug)]
 pub struct AppState {
     pub games: Arc<RwLock<std::collections::HashMap<Uuid, GameState>>>,
     pub ai_battle_service: Arc<AiBattleService>,
+    pub health: Arc<HealthRegistry>,
+    /// 設定対応サービス（設定なしで起動した場合はNone）
+    pub configurable_service: Option<Arc<crate::api::ai_battle::ConfigurableAiBattleService>>,
 }
 
 impl Clone for AppState {
@@ -70,6 +126,8 @@ impl Clone for AppState {
         Self {
             games: Arc::clone(&self.games),
             ai_battle_service: Arc::clone(&self.ai_battle_service),
+            health: Arc::clone(&self.health),
+            configurable_service: self.configurable_service.clone(),
         }
     }
 }
@@ -77,18 +135,31 @@ impl Clone for AppState {
 impl AppState {
     pub fn new() -> Self {
         let session_manager = Arc::new(AiBattleSessionManager::new(100));
-        let ai_battle_service = Arc::new(AiBattleService::new(session_manager));
+        let ai_battle_service = Arc::new(AiBattleService::new(Arc::clone(&session_manager)));
+        
+        let health = HealthRegistry::new(session_manager);
+        health.register_ai_backend(AiBackendRole::Primary, Arc::clone(ai_battle_service.get_ai_service()));
         
         Self {
             games: Arc::new(RwLock::new(std::collections::HashMap::new())),
             ai_battle_service,
+            health: Arc::new(health),
+            configurable_service: None,
         }
     }
     
     pub fn new_with_configurable_service(configurable_service: Arc<crate::api::ai_battle::ConfigurableAiBattleService>) -> Self {
+        let health = HealthRegistry::new(Arc::clone(configurable_service.session_manager()));
+        health.register_ai_backend(AiBackendRole::Primary, Arc::clone(configurable_service.primary_ai_service()));
+        if let Some(fallback) = configurable_service.fallback_ai_service() {
+            health.register_ai_backend(AiBackendRole::Fallback, Arc::clone(fallback));
+        }
+        
         Self {
             games: Arc::new(RwLock::new(std::collections::HashMap::new())),
             ai_battle_service: Arc::clone(configurable_service.get_service()),
+            health: Arc::new(health),
+            configurable_service: Some(configurable_service),
         }
     }
 }
@@ -99,22 +170,16 @@ impl Default for AppState {
     }
 }
 
+/// AI対戦サービスを必要とするエクストラクター（ログイン中のアカウントなど）を両方のルーターで使えるようにする
+impl FromRef<AppState> for Arc<AiBattleService> {
+    fn from_ref(state: &AppState) -> Self {
+        Arc::clone(&state.ai_battle_service)
+    }
+}
+
 impl GameResponse {
     pub fn from_game_state(game_state: &GameState) -> Self {
-        let mut board = [[0u8; 8]; 8];
-        for row in 0..8 {
-            for col in 0..8 {
-                if let Some(position) = Position::new(row, col) {
-                    if let Some(cell) = game_state.board.get_cell(position) {
-                        board[row][col] = match cell {
-                            crate::game::Cell::Empty => 0,
-                            crate::game::Cell::Black => 1,
-                            crate::game::Cell::White => 2,
-                        };
-                    }
-                }
-            }
-        }
+        let board = encoding::encode_legacy_board(&game_state.board);
 
         let valid_moves = ReversiRules::get_valid_moves(&game_state.board, game_state.current_player)
             .into_iter()
@@ -138,10 +203,7 @@ impl GameResponse {
         Self {
             id: game_state.id,
             board,
-            current_player: match game_state.current_player {
-                Player::Black => 1,
-                Player::White => 2,
-            },
+            current_player: encoding::player_to_legacy(game_state.current_player),
             valid_moves,
             game_status,
             score,
@@ -150,6 +212,13 @@ impl GameResponse {
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/games",
+    tag = "legacy",
+    request_body = CreateGameRequest,
+    responses((status = 200, description = "ゲームを作成（非推奨）", body = GameResponse))
+)]
 pub async fn create_game(
     State(state): State<AppState>,
     Json(_payload): Json<CreateGameRequest>,
@@ -166,6 +235,52 @@ pub async fn create_game(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/games/import",
+    tag = "legacy",
+    request_body = ImportGameRequest,
+    responses(
+        (status = 200, description = "棋譜を初期局面から再生したゲーム（非推奨）", body = ImportGameResponse),
+        (status = 400, description = "棋譜を解析できない、または合法手でない着手を含む", body = LegacyErrorResponse),
+    )
+)]
+pub async fn import_game(
+    State(state): State<AppState>,
+    Json(payload): Json<ImportGameRequest>,
+) -> std::result::Result<Json<ImportGameResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let game_state = transcript::import(&payload.transcript, GameState::new()).map_err(|err| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid transcript".to_string(),
+                details: Some(err.to_string()),
+            }),
+        )
+    })?;
+    
+    {
+        let mut games = state.games.write().await;
+        games.insert(game_state.id, game_state.clone());
+    }
+
+    let response = ImportGameResponse {
+        game_state: GameResponse::from_game_state(&game_state),
+        transcript: transcript::to_transcript(game_state.move_history.iter().map(|game_move| game_move.position)),
+    };
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/games/{id}",
+    tag = "legacy",
+    params(("id" = Uuid, Path, description = "ゲームID")),
+    responses(
+        (status = 200, description = "ゲーム状態（非推奨）", body = GameResponse),
+        (status = 404, description = "ゲームが存在しない", body = LegacyErrorResponse),
+    )
+)]
 pub async fn get_game(
     State(state): State<AppState>,
     Path(game_id): Path<Uuid>,
@@ -187,6 +302,18 @@ pub async fn get_game(
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/games/{id}/move",
+    tag = "legacy",
+    params(("id" = Uuid, Path, description = "ゲームID")),
+    request_body = MakeMoveRequest,
+    responses(
+        (status = 200, description = "着手結果（非推奨）", body = LegacyMoveResponse),
+        (status = 400, description = "無効な着手", body = LegacyErrorResponse),
+        (status = 404, description = "ゲームが存在しない", body = LegacyErrorResponse),
+    )
+)]
 pub async fn make_move(
     State(state): State<AppState>,
     Path(game_id): Path<Uuid>,
@@ -256,6 +383,16 @@ pub async fn make_move(
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/games/{id}",
+    tag = "legacy",
+    params(("id" = Uuid, Path, description = "ゲームID")),
+    responses(
+        (status = 204, description = "削除完了"),
+        (status = 404, description = "ゲームが存在しない", body = LegacyErrorResponse),
+    )
+)]
 pub async fn delete_game(
     State(state): State<AppState>,
     Path(game_id): Path<Uuid>,
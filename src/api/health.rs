@@ -0,0 +1,482 @@
+//! 依存サービスの一括ヘルスチェックモジュール
+//! AIバックエンド、セッションストア、バックグラウンドタスク、キューの状態を
+//! 1つのドキュメントにまとめ、`/api/admin/health/full` で公開する。
+//! ロードバランサー・オーケストレーター向けに、プロセスの生存確認（`/health/live`）と
+//! リクエストを受け付けられるかの確認（`/health/ready`）も提供する。
+
+use axum::{extract::State, http::StatusCode, response::Json};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::Serialize;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::ai::cached_service::AiCacheStats;
+use crate::ai::service::AIService;
+use crate::ai::timed_service::AiLatencyStats;
+use crate::session::AiBattleSessionManager;
+
+use super::handlers::AppState;
+
+/// 全体の健全性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// 全ての依存サービスが正常
+    Ok,
+    /// 一部に異常があるがリクエストは処理可能
+    Degraded,
+    /// AIバックエンドが全て利用不可
+    Unhealthy,
+}
+
+/// AIバックエンドの役割
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AiBackendRole {
+    Primary,
+    Fallback,
+}
+
+impl AiBackendRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AiBackendRole::Primary => "primary",
+            AiBackendRole::Fallback => "fallback",
+        }
+    }
+}
+
+/// サーキットブレーカーの状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// AIバックエンド1件分の状態
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AiBackendHealth {
+    pub name: String,
+    pub role: AiBackendRole,
+    pub available: bool,
+    pub latency_ms: Option<u64>,
+    /// ブレーカーが構成されていない場合はnull
+    pub breaker_state: Option<BreakerState>,
+    /// 着手計算のキャッシュの統計（キャッシュが無効ならnull）
+    pub cache: Option<AiCacheStats>,
+    /// 直近の着手計算の応答時間の統計（計測していなければnull）
+    pub latency: Option<AiLatencyStats>,
+    pub error: Option<String>,
+}
+
+/// セッションストアの状態
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SessionStoreHealth {
+    pub backend: String,
+    /// 永続化先のデータベースに接続できるか（メモリのみの場合は常にtrue）
+    pub available: bool,
+    pub active_sessions: usize,
+    pub max_sessions: usize,
+    pub ai_thinking_sessions: usize,
+    /// 接続を確認できなかった理由
+    pub error: Option<String>,
+}
+
+/// バックグラウンドタスクの生存状態
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BackgroundTaskHealth {
+    pub name: String,
+    pub alive: bool,
+    pub last_heartbeat: DateTime<Utc>,
+    pub interval_secs: u64,
+}
+
+/// キューの滞留状況
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct QueueHealth {
+    pub name: String,
+    pub depth: usize,
+    pub capacity: Option<usize>,
+}
+
+/// `/api/admin/health/full` のレスポンス
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FullHealthResponse {
+    pub status: HealthStatus,
+    pub checked_at: DateTime<Utc>,
+    /// サーバーの起動からの経過秒数
+    pub uptime_secs: u64,
+    pub ai_backends: Vec<AiBackendHealth>,
+    pub session_store: SessionStoreHealth,
+    pub background_tasks: Vec<BackgroundTaskHealth>,
+    pub queues: Vec<QueueHealth>,
+}
+
+/// `/health/live` のレスポンス
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LivenessResponse {
+    pub status: HealthStatus,
+    pub started_at: DateTime<Utc>,
+    pub uptime_secs: u64,
+}
+
+/// `/health/ready` のレスポンス
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    /// AIバックエンドのいずれかとデータベースが利用でき、セッション数が上限未満か
+    pub ready: bool,
+    pub status: HealthStatus,
+    pub checked_at: DateTime<Utc>,
+    pub uptime_secs: u64,
+    /// 主系・予備系のAIバックエンドの状態
+    pub ai_backends: Vec<AiBackendHealth>,
+    /// データベースへの接続とセッション数
+    pub session_store: SessionStoreHealth,
+}
+
+impl From<FullHealthResponse> for ReadinessResponse {
+    fn from(report: FullHealthResponse) -> Self {
+        let session_store = report.session_store;
+        Self {
+            ready: report.status != HealthStatus::Unhealthy && session_store.active_sessions < session_store.max_sessions,
+            status: report.status,
+            checked_at: report.checked_at,
+            uptime_secs: report.uptime_secs,
+            ai_backends: report.ai_backends,
+            session_store,
+        }
+    }
+}
+
+/// キューの深さを取得する関数
+type QueueProbe = Arc<dyn Fn() -> usize + Send + Sync>;
+
+struct TaskHeartbeat {
+    interval: Duration,
+    last_heartbeat: DateTime<Utc>,
+}
+
+struct QueueEntry {
+    capacity: Option<usize>,
+    probe: QueueProbe,
+}
+
+/// バックグラウンドタスクが生存を通知するためのハンドル
+#[derive(Clone)]
+pub struct TaskHeartbeatHandle {
+    name: String,
+    tasks: Arc<DashMap<String, TaskHeartbeat>>,
+}
+
+impl TaskHeartbeatHandle {
+    /// タスクがまだ動いていることを記録する
+    pub fn beat(&self) {
+        if let Some(mut task) = self.tasks.get_mut(&self.name) {
+            task.last_heartbeat = Utc::now();
+        }
+    }
+}
+
+/// ヘルスチェック対象の登録簿
+/// 各サブシステムは起動時に自身を登録し、一括ヘルスチェックで参照される
+pub struct HealthRegistry {
+    ai_backends: RwLock<Vec<(AiBackendRole, Arc<dyn AIService>)>>,
+    session_manager: Arc<AiBattleSessionManager>,
+    tasks: Arc<DashMap<String, TaskHeartbeat>>,
+    queues: DashMap<String, QueueEntry>,
+    started_at: DateTime<Utc>,
+    started: Instant,
+}
+
+impl std::fmt::Debug for HealthRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HealthRegistry")
+            .field("ai_backends", &self.ai_backends.read().unwrap().len())
+            .field("tasks", &self.tasks.len())
+            .field("queues", &self.queues.len())
+            .finish()
+    }
+}
+
+impl HealthRegistry {
+    pub fn new(session_manager: Arc<AiBattleSessionManager>) -> Self {
+        Self {
+            ai_backends: RwLock::new(Vec::new()),
+            session_manager,
+            tasks: Arc::new(DashMap::new()),
+            queues: DashMap::new(),
+            started_at: Utc::now(),
+            started: Instant::now(),
+        }
+    }
+
+    /// プロセスの生存状態（依存サービスは確認しない）
+    pub fn liveness(&self) -> LivenessResponse {
+        LivenessResponse {
+            status: HealthStatus::Ok,
+            started_at: self.started_at,
+            uptime_secs: self.started.elapsed().as_secs(),
+        }
+    }
+
+    /// AIバックエンドを登録する
+    /// 計算待ちキューを使うバックエンドは、キューの深さも登録する
+    pub fn register_ai_backend(&self, role: AiBackendRole, service: Arc<dyn AIService>) {
+        if let Some(queue) = service.queue_stats() {
+            let probe = Arc::clone(&service);
+            self.register_queue(format!("{}-ai-queue", role.as_str()), Some(queue.capacity), move || probe.queue_stats().map_or(0, |queue| queue.depth));
+        }
+        self.ai_backends.write().unwrap().push((role, service));
+    }
+
+    /// 登録されたAIバックエンドの一覧
+    pub fn ai_backends(&self) -> Vec<(AiBackendRole, Arc<dyn AIService>)> {
+        self.ai_backends.read().unwrap().clone()
+    }
+
+    /// バックグラウンドタスクを登録する
+    /// `interval` の2倍を超えて通知がない場合は停止しているとみなす
+    pub fn register_task(&self, name: impl Into<String>, interval: Duration) -> TaskHeartbeatHandle {
+        let name = name.into();
+        self.tasks.insert(name.clone(), TaskHeartbeat { interval, last_heartbeat: Utc::now() });
+
+        TaskHeartbeatHandle { name, tasks: Arc::clone(&self.tasks) }
+    }
+
+    /// キューの深さを取得する関数を登録する
+    pub fn register_queue<F>(&self, name: impl Into<String>, capacity: Option<usize>, probe: F)
+    where
+        F: Fn() -> usize + Send + Sync + 'static,
+    {
+        self.queues.insert(name.into(), QueueEntry { capacity, probe: Arc::new(probe) });
+    }
+
+    /// 全ての登録対象を確認して状態をまとめる
+    pub async fn check(&self) -> FullHealthResponse {
+        let backends = self.ai_backends();
+        let mut ai_backends = Vec::with_capacity(backends.len());
+        for (role, service) in backends {
+            ai_backends.push(Self::check_ai_backend(role, service.as_ref()).await);
+        }
+
+        let stats = self.session_manager.get_stats();
+        let store = self.session_manager.store();
+        let ping = match store {
+            Some(store) => store.ping().await,
+            None => Ok(()),
+        };
+        let session_store = SessionStoreHealth {
+            backend: store.map_or("memory", |store| store.backend_name()).to_string(),
+            available: ping.is_ok(),
+            active_sessions: stats.total_sessions,
+            max_sessions: stats.max_sessions,
+            ai_thinking_sessions: stats.ai_thinking_count,
+            error: ping.err().map(|e| e.to_string()),
+        };
+
+        let now = Utc::now();
+        let mut background_tasks: Vec<BackgroundTaskHealth> = self.tasks
+            .iter()
+            .map(|entry| {
+                let task = entry.value();
+                let deadline = chrono::Duration::from_std(task.interval * 2).unwrap_or(chrono::Duration::MAX);
+                BackgroundTaskHealth {
+                    name: entry.key().clone(),
+                    alive: now - task.last_heartbeat <= deadline,
+                    last_heartbeat: task.last_heartbeat,
+                    interval_secs: task.interval.as_secs(),
+                }
+            })
+            .collect();
+        background_tasks.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut queues: Vec<QueueHealth> = self.queues
+            .iter()
+            .map(|entry| QueueHealth {
+                name: entry.key().clone(),
+                depth: (entry.value().probe)(),
+                capacity: entry.value().capacity,
+            })
+            .collect();
+        queues.sort_by(|a, b| a.name.cmp(&b.name));
+
+        FullHealthResponse {
+            status: Self::overall_status(&ai_backends, &session_store, &background_tasks),
+            checked_at: now,
+            uptime_secs: self.started.elapsed().as_secs(),
+            ai_backends,
+            session_store,
+            background_tasks,
+            queues,
+        }
+    }
+
+    async fn check_ai_backend(role: AiBackendRole, service: &dyn AIService) -> AiBackendHealth {
+        match service.health_check().await {
+            Ok(status) => AiBackendHealth {
+                name: status.name,
+                role,
+                available: status.available,
+                latency_ms: status.average_response_time_ms,
+                breaker_state: None,
+                cache: status.cache,
+                latency: status.latency,
+                error: None,
+            },
+            Err(e) => AiBackendHealth {
+                name: service.get_name().to_string(),
+                role,
+                available: false,
+                latency_ms: None,
+                breaker_state: None,
+                cache: service.cache_stats(),
+                latency: service.latency_stats(),
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
+    fn overall_status(
+        ai_backends: &[AiBackendHealth],
+        session_store: &SessionStoreHealth,
+        background_tasks: &[BackgroundTaskHealth],
+    ) -> HealthStatus {
+        if !ai_backends.iter().any(|backend| backend.available) || !session_store.available {
+            return HealthStatus::Unhealthy;
+        }
+
+        let degraded = ai_backends.iter().any(|backend| !backend.available)
+            || background_tasks.iter().any(|task| !task.alive)
+            || session_store.active_sessions >= session_store.max_sessions;
+
+        if degraded {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Ok
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/health/full",
+    tag = "system",
+    responses(
+        (status = 200, description = "依存サービスの状態（正常または一部異常）", body = FullHealthResponse),
+        (status = 503, description = "AIバックエンドが利用不可", body = FullHealthResponse),
+    )
+)]
+pub async fn full_health(State(state): State<AppState>) -> (StatusCode, Json<FullHealthResponse>) {
+    let report = state.health.check().await;
+    let status_code = match report.status {
+        HealthStatus::Unhealthy => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::OK,
+    };
+
+    (status_code, Json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "system",
+    responses((status = 200, description = "プロセスが稼働している", body = LivenessResponse))
+)]
+pub async fn liveness(State(state): State<AppState>) -> Json<LivenessResponse> {
+    Json(state.health.liveness())
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "system",
+    responses(
+        (status = 200, description = "リクエストを受け付けられる", body = ReadinessResponse),
+        (status = 503, description = "AIバックエンドかデータベースが利用不可、またはセッション数が上限", body = ReadinessResponse),
+    )
+)]
+pub async fn readiness(State(state): State<AppState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let report = ReadinessResponse::from(state.health.check().await);
+    let status_code = if report.ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+
+    (status_code, Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::mock_service::{MockAIConfig, MockAIService};
+
+    fn create_registry() -> HealthRegistry {
+        HealthRegistry::new(Arc::new(AiBattleSessionManager::new(10)))
+    }
+
+    #[tokio::test]
+    async fn test_check_reports_registered_components() {
+        let registry = create_registry();
+        registry.register_ai_backend(AiBackendRole::Primary, Arc::new(MockAIService::new(MockAIConfig::default())));
+        let heartbeat = registry.register_task("cleanup", Duration::from_secs(60));
+        registry.register_queue("ai_moves", Some(8), || 3);
+        heartbeat.beat();
+
+        let report = registry.check().await;
+        assert_eq!(report.status, HealthStatus::Ok);
+        assert_eq!(report.ai_backends.len(), 1);
+        assert!(report.ai_backends[0].available);
+        assert_eq!(report.session_store.max_sessions, 10);
+        assert!(report.background_tasks[0].alive);
+        assert_eq!(report.queues[0].depth, 3);
+    }
+
+    #[tokio::test]
+    async fn test_stalled_task_degrades_status() {
+        let registry = create_registry();
+        registry.register_ai_backend(AiBackendRole::Primary, Arc::new(MockAIService::new(MockAIConfig::default())));
+        registry.register_task("watchdog", Duration::ZERO);
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+
+        let report = registry.check().await;
+        assert!(!report.background_tasks[0].alive);
+        assert_eq!(report.status, HealthStatus::Degraded);
+    }
+
+    #[tokio::test]
+    async fn test_backend_availability_drives_status() {
+        let unavailable = MockAIConfig { available: false, ..MockAIConfig::default() };
+
+        let registry = create_registry();
+        registry.register_ai_backend(AiBackendRole::Primary, Arc::new(MockAIService::new(MockAIConfig::default())));
+        registry.register_ai_backend(AiBackendRole::Fallback, Arc::new(MockAIService::new(unavailable.clone())));
+        let report = registry.check().await;
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert!(report.ai_backends[1].error.is_some());
+
+        let registry = create_registry();
+        registry.register_ai_backend(AiBackendRole::Primary, Arc::new(MockAIService::new(unavailable)));
+        let report = registry.check().await;
+        assert_eq!(report.status, HealthStatus::Unhealthy);
+        assert!(!ReadinessResponse::from(report).ready);
+    }
+
+    #[tokio::test]
+    async fn test_readiness_requires_free_session_slots() {
+        let session_manager = Arc::new(AiBattleSessionManager::new(1));
+        let registry = HealthRegistry::new(Arc::clone(&session_manager));
+        registry.register_ai_backend(AiBackendRole::Primary, Arc::new(MockAIService::new(MockAIConfig::default())));
+        assert_eq!(registry.liveness().status, HealthStatus::Ok);
+
+        let ready = ReadinessResponse::from(registry.check().await);
+        assert!(ready.ready);
+        assert!(ready.session_store.available);
+        assert_eq!(ready.session_store.backend, "memory");
+
+        session_manager.create_session(crate::api::ai_battle::AiDifficulty::Easy).await.unwrap();
+        let full = ReadinessResponse::from(registry.check().await);
+        assert!(!full.ready);
+        assert_eq!(full.status, HealthStatus::Degraded);
+    }
+}
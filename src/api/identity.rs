@@ -0,0 +1,70 @@
+//! プレイヤー識別モジュール
+//! アカウント機能が導入されるまでの暫定として、`X-Player-Id` ヘッダーの
+//! UUIDでプレイヤーを識別する。通知の宛先やセッションの参加者の記録に使う。
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{request::Parts, StatusCode},
+    response::Json,
+};
+use uuid::Uuid;
+
+use super::ai_battle::dto::ErrorResponse;
+
+/// プレイヤーIDを指定するヘッダー名
+pub const PLAYER_ID_HEADER: &str = "x-player-id";
+
+/// リクエスト元のプレイヤー
+/// ヘッダーがない、またはUUIDとして解釈できない場合は401を返す
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerIdentity(pub Uuid);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for PlayerIdentity {
+    type Rejection = (StatusCode, Json<ErrorResponse>);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .headers
+            .get(PLAYER_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| Uuid::parse_str(value.trim()).ok())
+            .map(PlayerIdentity)
+            .ok_or_else(|| {
+                (
+                    StatusCode::UNAUTHORIZED,
+                    Json(ErrorResponse::with_code(
+                        "MISSING_PLAYER_ID",
+                        format!("{} ヘッダーにプレイヤーID（UUID）を指定してください", PLAYER_ID_HEADER),
+                        "MISSING_PLAYER_ID",
+                    )),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+
+    async fn extract(header: Option<&str>) -> Result<PlayerIdentity, StatusCode> {
+        let mut builder = Request::builder().uri("/");
+        if let Some(value) = header {
+            builder = builder.header(PLAYER_ID_HEADER, value);
+        }
+        let (mut parts, _) = builder.body(()).unwrap().into_parts();
+
+        PlayerIdentity::from_request_parts(&mut parts, &()).await.map_err(|(status, _)| status)
+    }
+
+    #[tokio::test]
+    async fn test_player_identity_from_header() {
+        let player_id = Uuid::new_v4();
+
+        assert_eq!(extract(Some(&player_id.to_string())).await, Ok(PlayerIdentity(player_id)));
+        assert_eq!(extract(Some("not-a-uuid")).await, Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(extract(None).await, Err(StatusCode::UNAUTHORIZED));
+    }
+}
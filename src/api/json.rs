@@ -0,0 +1,73 @@
+//! JSONボディのエクストラクター
+//! axumの `Json` は解析に失敗するとプレーンテキストの422などを返すため、
+//! `AiBattleError::InvalidRequestBody` を経由してAPI共通のエラー形式（`ErrorResponse`）で返す。
+
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    response::Json,
+};
+use serde::de::DeserializeOwned;
+
+use super::ai_battle::dto::AiBattleError;
+
+/// リクエストボディをJSONとして解析する（失敗時は `ErrorResponse` 形式で応答する）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonBody<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for JsonBody<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AiBattleError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state).await?;
+        Ok(JsonBody(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::{header, StatusCode}, routing::post, Router};
+    use serde::Deserialize;
+    use tower::ServiceExt;
+
+    #[derive(Deserialize)]
+    struct MoveBody {
+        row: usize,
+    }
+
+    async fn call(content_type: Option<&str>, body: &str) -> (StatusCode, serde_json::Value) {
+        let app = Router::new().route(
+            "/move",
+            post(|JsonBody(body): JsonBody<MoveBody>| async move { Json(serde_json::json!({ "row": body.row })) }),
+        );
+        let mut builder = Request::post("/move");
+        if let Some(content_type) = content_type {
+            builder = builder.header(header::CONTENT_TYPE, content_type);
+        }
+        let response = app.oneshot(builder.body(Body::from(body.to_string())).unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_json_rejections_use_the_error_envelope() {
+        assert_eq!(call(Some("application/json"), r#"{"row": 3}"#).await.1["row"], 3);
+
+        for (content_type, body, status) in [
+            (Some("application/json"), r#"{"row": "three"}"#, StatusCode::UNPROCESSABLE_ENTITY),
+            (Some("application/json"), r#"{"row": "#, StatusCode::BAD_REQUEST),
+            (None, r#"{"row": 3}"#, StatusCode::UNSUPPORTED_MEDIA_TYPE),
+        ] {
+            let (actual, body) = call(content_type, body).await;
+            assert_eq!(actual, status);
+            assert_eq!(body["error_code"], "INVALID_REQUEST_BODY");
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! リーダーボードAPIモジュール
+//! アーカイブの対局から集計したプレイヤーの順位表を返す `/api/leaderboard` を提供する。
+
+use axum::{
+    extract::{Query, State},
+    response::Json,
+};
+
+use crate::leaderboard::{LeaderboardQuery, LeaderboardResponse};
+
+use super::ai_battle::dto::AiBattleResult;
+use super::handlers::AppState;
+
+#[utoipa::path(
+    get,
+    path = "/api/leaderboard",
+    tag = "players",
+    params(LeaderboardQuery),
+    responses(
+        (status = 200, description = "期間内に対局したプレイヤーの順位表（レーティングの高い順）", body = LeaderboardResponse),
+        (status = 400, description = "集計期間または件数が不正", body = ErrorResponse),
+    )
+)]
+pub async fn get_leaderboard(
+    State(state): State<AppState>,
+    Query(query): Query<LeaderboardQuery>,
+) -> AiBattleResult<Json<LeaderboardResponse>> {
+    let (period, limit) = query.parse()?;
+    Ok(Json(state.ai_battle_service.leaderboard(period, limit).await?))
+}
@@ -0,0 +1,627 @@
+//! 対局ロビーモジュール
+//! プレイヤーが持ち時間・レーティング有無・手番の希望を添えて対人戦の募集を出し、
+//! 他のプレイヤーが `/api/lobby` から選んで参加する。参加と同時に対人戦のセッションを作成し、
+//! 募集はロビーから取り除く。一定時間参加者のいない募集は期限切れとして破棄する。
+//! 相手を指定した募集や招待リンク用の募集は一覧に載せず、招待トークンで参加・辞退する。
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::ai_battle::clock::{TimeControl, TimeControlSetting};
+use super::ai_battle::dto::{AiBattleError, AiBattleResult, PvpSeatResponse};
+use super::handlers::AppState;
+use super::json::JsonBody;
+use super::accounts::CurrentAccount;
+use super::auth::ApiKeyIdentity;
+use super::identity::PlayerIdentity;
+use super::notifications::{NotificationHub, NotificationKind};
+use crate::game::Player;
+
+/// 募集の既定の有効期間
+const DEFAULT_TTL: Duration = Duration::from_secs(10 * 60);
+/// 募集時に指定できる有効期間の上限
+const MAX_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// 募集者の手番の希望
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorPreference {
+    Black,
+    White,
+    /// 対局成立時にランダムに決める
+    #[default]
+    Random,
+}
+
+/// ロビーに掲示される募集
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Challenge {
+    pub id: Uuid,
+    pub time_control: Option<TimeControl>,
+    pub rated: bool,
+    pub color: ColorPreference,
+    /// 招待トークンでのみ参加できる非公開の募集か
+    pub private: bool,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 募集の作成リクエスト
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CreateChallengeRequest {
+    /// 持ち時間（プリセット名またはカスタム設定）。省略時は無制限
+    #[serde(default)]
+    pub time_control: Option<TimeControlSetting>,
+    #[serde(default)]
+    pub rated: bool,
+    #[serde(default)]
+    pub color: ColorPreference,
+    /// 募集の有効期間（秒）。省略時は600秒、上限は3600秒
+    #[serde(default)]
+    pub ttl_seconds: Option<u64>,
+    /// 対戦相手のプレイヤーID。指定した場合はその相手だけが参加できる非公開の募集になる
+    #[serde(default)]
+    pub opponent: Option<Uuid>,
+    /// trueの場合は一覧に載せず、招待リンクを知っている相手だけが参加できる
+    #[serde(default)]
+    pub private: bool,
+}
+
+/// 募集の作成結果
+/// `player_token` は対局成立後の着手に使う。非公開の募集では `invite_url` を相手に共有する
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChallengeCreatedResponse {
+    pub challenge: Challenge,
+    pub player_token: Uuid,
+    pub invite_token: Option<Uuid>,
+    pub invite_url: Option<String>,
+}
+
+/// 自分宛ての募集
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IncomingChallenge {
+    pub challenge: Challenge,
+    pub invite_token: Uuid,
+}
+
+/// 自分宛ての募集一覧（古い順）
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IncomingChallengesResponse {
+    pub challenges: Vec<IncomingChallenge>,
+}
+
+/// ロビーの募集一覧（古い順）
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LobbyResponse {
+    pub challenges: Vec<Challenge>,
+}
+
+/// 参加待ちの募集
+#[derive(Debug, Clone)]
+pub struct OpenChallenge {
+    pub challenge: Challenge,
+    /// 募集者のプレイヤーID
+    pub owner: Uuid,
+    /// 募集者のログイン中のアカウントID（未ログインで募集した場合はNone）
+    pub owner_account: Option<Uuid>,
+    /// 対局成立時に募集者の席に割り当てるトークン
+    pub player_token: Uuid,
+    /// 非公開の募集に参加・辞退するためのトークン
+    pub invite_token: Option<Uuid>,
+    /// 参加できる相手（指定がなければ招待トークンを持つ誰でも参加できる）
+    pub opponent: Option<Uuid>,
+}
+
+/// 招待URLのパス
+fn invite_url(invite_token: Uuid) -> String {
+    format!("/api/lobby/invites/{}", invite_token)
+}
+
+impl OpenChallenge {
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.challenge.expires_at <= now
+    }
+
+    /// 募集者が担当する色を決める（ランダム指定は募集IDから決める）
+    pub fn owner_color(&self) -> Player {
+        match self.challenge.color {
+            ColorPreference::Black => Player::Black,
+            ColorPreference::White => Player::White,
+            ColorPreference::Random if self.challenge.id.as_u128() & 1 == 0 => Player::Black,
+            ColorPreference::Random => Player::White,
+        }
+    }
+}
+
+/// 対人戦の募集を保持するロビー
+/// 相手指定の募集の受信・辞退と、募集の期限切れは当事者に通知する
+#[derive(Debug)]
+pub struct Lobby {
+    challenges: DashMap<Uuid, OpenChallenge>,
+    notifications: Arc<NotificationHub>,
+}
+
+impl Lobby {
+    pub fn new(notifications: Arc<NotificationHub>) -> Self {
+        Self { challenges: DashMap::new(), notifications }
+    }
+
+    /// 募集を掲示する
+    pub fn post(&self, owner: Uuid, owner_account: Option<Uuid>, request: CreateChallengeRequest) -> AiBattleResult<ChallengeCreatedResponse> {
+        let ttl = match request.ttl_seconds {
+            Some(seconds) if seconds == 0 || seconds > MAX_TTL.as_secs() => {
+                return Err(AiBattleError::BadRequest {
+                    details: format!("ttl_secondsは1〜{}の範囲で指定してください", MAX_TTL.as_secs()),
+                });
+            }
+            Some(seconds) => Duration::from_secs(seconds),
+            None => DEFAULT_TTL,
+        };
+        let time_control = request.time_control.map(TimeControlSetting::resolve).transpose()?;
+        if request.opponent == Some(owner) {
+            return Err(AiBattleError::OwnChallenge);
+        }
+
+        self.purge_expired();
+
+        let now = Utc::now();
+        let challenge = Challenge {
+            id: Uuid::new_v4(),
+            time_control,
+            rated: request.rated,
+            color: request.color,
+            private: request.private || request.opponent.is_some(),
+            created_at: now,
+            expires_at: now + chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX),
+        };
+        let player_token = Uuid::new_v4();
+        let invite_token = challenge.private.then(Uuid::new_v4);
+        self.challenges.insert(challenge.id, OpenChallenge {
+            challenge: challenge.clone(),
+            owner,
+            owner_account,
+            player_token,
+            invite_token,
+            opponent: request.opponent,
+        });
+
+        if let Some(opponent) = request.opponent {
+            self.notifications.notify(opponent, NotificationKind::ChallengeReceived, None, "対局の申し込みが届きました");
+        }
+
+        Ok(ChallengeCreatedResponse {
+            challenge,
+            player_token,
+            invite_token,
+            invite_url: invite_token.map(invite_url),
+        })
+    }
+
+    /// 期限内の公開募集を古い順に返す
+    pub fn list(&self) -> Vec<Challenge> {
+        self.purge_expired();
+
+        let mut challenges: Vec<Challenge> = self.challenges
+            .iter()
+            .filter(|entry| !entry.challenge.private)
+            .map(|entry| entry.challenge.clone())
+            .collect();
+        challenges.sort_by_key(|challenge| challenge.created_at);
+        challenges
+    }
+
+    /// 指定したプレイヤー宛ての募集を古い順に返す
+    pub fn incoming(&self, player_id: Uuid) -> Vec<IncomingChallenge> {
+        self.purge_expired();
+
+        let mut challenges: Vec<IncomingChallenge> = self.challenges
+            .iter()
+            .filter(|entry| entry.opponent == Some(player_id))
+            .filter_map(|entry| {
+                entry.invite_token.map(|invite_token| IncomingChallenge { challenge: entry.challenge.clone(), invite_token })
+            })
+            .collect();
+        challenges.sort_by_key(|incoming| incoming.challenge.created_at);
+        challenges
+    }
+
+    /// 招待トークンに対応する募集を返す
+    pub fn invitation(&self, invite_token: Uuid) -> AiBattleResult<Challenge> {
+        let challenge_id = self.find_invite(invite_token)?;
+        self.challenges
+            .get(&challenge_id)
+            .map(|open| open.challenge.clone())
+            .ok_or(AiBattleError::ChallengeNotFound { challenge_id: invite_token })
+    }
+
+    /// 参加者のために公開募集を取り出す
+    /// 取り出しは1回だけ成功するため、同じ募集に同時に参加しても対局は1つしか成立しない
+    pub fn take(&self, challenge_id: Uuid, acceptor: Uuid) -> AiBattleResult<OpenChallenge> {
+        let is_public = self.challenges.get(&challenge_id).is_some_and(|open| !open.challenge.private);
+        if !is_public {
+            return Err(AiBattleError::ChallengeNotFound { challenge_id });
+        }
+        self.take_open(challenge_id, acceptor)
+    }
+
+    /// 招待トークンで非公開の募集を取り出す
+    pub fn take_invite(&self, invite_token: Uuid, acceptor: Uuid) -> AiBattleResult<OpenChallenge> {
+        let challenge_id = self.find_invite(invite_token)?;
+        self.check_recipient(challenge_id, acceptor)?;
+        self.take_open(challenge_id, acceptor)
+    }
+
+    /// 招待された相手が非公開の募集を辞退する
+    pub fn decline(&self, invite_token: Uuid, player_id: Uuid) -> AiBattleResult<()> {
+        let challenge_id = self.find_invite(invite_token)?;
+        self.check_recipient(challenge_id, player_id)?;
+
+        let (_, open) = self.challenges
+            .remove_if(&challenge_id, |_, open| open.owner != player_id)
+            .ok_or(AiBattleError::OwnChallenge)?;
+        self.notifications.notify(open.owner, NotificationKind::ChallengeDeclined, None, "対局の申し込みが辞退されました");
+        Ok(())
+    }
+
+    fn take_open(&self, challenge_id: Uuid, acceptor: Uuid) -> AiBattleResult<OpenChallenge> {
+        let now = Utc::now();
+        if let Some((_, open)) = self.challenges.remove_if(&challenge_id, |_, open| open.owner != acceptor && !open.is_expired(now)) {
+            return Ok(open);
+        }
+
+        match self.challenges.get(&challenge_id) {
+            Some(open) if !open.is_expired(now) => Err(AiBattleError::OwnChallenge),
+            Some(open) => {
+                drop(open);
+                self.purge_expired();
+                Err(AiBattleError::ChallengeNotFound { challenge_id })
+            }
+            None => Err(AiBattleError::ChallengeNotFound { challenge_id }),
+        }
+    }
+
+    fn find_invite(&self, invite_token: Uuid) -> AiBattleResult<Uuid> {
+        let now = Utc::now();
+        self.challenges
+            .iter()
+            .find(|entry| entry.invite_token == Some(invite_token) && !entry.is_expired(now))
+            .map(|entry| entry.challenge.id)
+            .ok_or(AiBattleError::ChallengeNotFound { challenge_id: invite_token })
+    }
+
+    /// 相手が指定された募集では、その相手以外の参加・辞退を拒否する
+    fn check_recipient(&self, challenge_id: Uuid, player_id: Uuid) -> AiBattleResult<()> {
+        let addressed_to = self.challenges.get(&challenge_id).and_then(|open| open.opponent);
+        match addressed_to {
+            Some(opponent) if opponent != player_id => Err(AiBattleError::NotChallengeRecipient),
+            _ => Ok(()),
+        }
+    }
+
+    /// 対局を作成できなかった場合に募集をロビーへ戻す
+    pub fn restore(&self, open: OpenChallenge) {
+        self.challenges.insert(open.challenge.id, open);
+    }
+
+    /// 募集者が募集を取り消す
+    pub fn cancel(&self, challenge_id: Uuid, owner: Uuid) -> AiBattleResult<()> {
+        match self.challenges.remove_if(&challenge_id, |_, open| open.owner == owner) {
+            Some(_) => Ok(()),
+            None if self.challenges.contains_key(&challenge_id) => Err(AiBattleError::NotChallengeOwner),
+            None => Err(AiBattleError::ChallengeNotFound { challenge_id }),
+        }
+    }
+
+    /// 期限切れの募集を破棄して募集者に通知し、破棄した件数を返す
+    pub fn purge_expired(&self) -> usize {
+        let now = Utc::now();
+        let mut expired = Vec::new();
+        self.challenges.retain(|_, open| {
+            if open.is_expired(now) {
+                expired.push(open.owner);
+                return false;
+            }
+            true
+        });
+
+        for owner in &expired {
+            self.notifications.notify(*owner, NotificationKind::ChallengeExpired, None, "参加者が現れないまま募集が期限切れになりました");
+        }
+        expired.len()
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/lobby",
+    tag = "lobby",
+    responses((status = 200, description = "参加者を募集中の対人戦（古い順）", body = LobbyResponse))
+)]
+pub async fn list_challenges(State(state): State<AppState>) -> Json<LobbyResponse> {
+    Json(LobbyResponse { challenges: state.ai_battle_service.lobby().list() })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/lobby",
+    tag = "lobby",
+    params(("X-Player-Id" = Uuid, Header, description = "プレイヤーID")),
+    request_body = CreateChallengeRequest,
+    responses(
+        (status = 201, description = "募集を掲示（レーティング対象の対局は、ログイン中のアカウントの席だけレーティングを更新する）", body = ChallengeCreatedResponse),
+        (status = 400, description = "有効期間または持ち時間が不正", body = ErrorResponse),
+        (status = 401, description = "プレイヤーIDが指定されていない、またはアクセストークンが無効", body = ErrorResponse),
+    )
+)]
+pub async fn create_challenge(
+    State(state): State<AppState>,
+    PlayerIdentity(player_id): PlayerIdentity,
+    account: CurrentAccount,
+    JsonBody(request): JsonBody<CreateChallengeRequest>,
+) -> AiBattleResult<(StatusCode, Json<ChallengeCreatedResponse>)> {
+    let owner_account = account.as_ref().map(|account| account.account_id);
+    let response = state.ai_battle_service.lobby().post(player_id, owner_account, request)?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/lobby/{challenge_id}/accept",
+    tag = "lobby",
+    params(
+        ("challenge_id" = Uuid, Path, description = "募集ID"),
+        ("X-Player-Id" = Uuid, Header, description = "プレイヤーID"),
+    ),
+    responses(
+        (status = 200, description = "対局が成立し、参加者の席を返す", body = PvpSeatResponse),
+        (status = 401, description = "プレイヤーIDが指定されていない、またはアクセストークンが無効", body = ErrorResponse),
+        (status = 404, description = "募集が存在しない、期限切れ、または成立済み", body = ErrorResponse),
+        (status = 409, description = "自分の募集には参加できない", body = ErrorResponse),
+        (status = 429, description = "セッション数の上限に到達", body = ErrorResponse),
+    )
+)]
+pub async fn accept_challenge(
+    State(state): State<AppState>,
+    PlayerIdentity(player_id): PlayerIdentity,
+    Path(challenge_id): Path<Uuid>,
+    api_key: Option<ApiKeyIdentity>,
+    account: CurrentAccount,
+) -> AiBattleResult<Json<PvpSeatResponse>> {
+    let response = state.ai_battle_service.accept_challenge(challenge_id, player_id)?;
+    
+    state.ai_battle_service.attribute_to_api_key(response.game_state.game_id, api_key.as_ref())?;
+    state.ai_battle_service.set_seat_account(response.game_state.game_id, response.color, account.as_ref())?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/lobby/{challenge_id}",
+    tag = "lobby",
+    params(
+        ("challenge_id" = Uuid, Path, description = "募集ID"),
+        ("X-Player-Id" = Uuid, Header, description = "プレイヤーID"),
+    ),
+    responses(
+        (status = 204, description = "募集を取り消した"),
+        (status = 401, description = "プレイヤーIDが指定されていない", body = ErrorResponse),
+        (status = 403, description = "募集者本人ではない", body = ErrorResponse),
+        (status = 404, description = "募集が存在しない", body = ErrorResponse),
+    )
+)]
+pub async fn cancel_challenge(
+    State(state): State<AppState>,
+    PlayerIdentity(player_id): PlayerIdentity,
+    Path(challenge_id): Path<Uuid>,
+) -> AiBattleResult<StatusCode> {
+    state.ai_battle_service.lobby().cancel(challenge_id, player_id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/lobby/invites/{invite_token}",
+    tag = "lobby",
+    params(("invite_token" = Uuid, Path, description = "招待トークン")),
+    responses(
+        (status = 200, description = "招待された募集の内容", body = Challenge),
+        (status = 404, description = "招待が存在しない、期限切れ、または成立済み", body = ErrorResponse),
+    )
+)]
+pub async fn get_invitation(
+    State(state): State<AppState>,
+    Path(invite_token): Path<Uuid>,
+) -> AiBattleResult<Json<Challenge>> {
+    Ok(Json(state.ai_battle_service.lobby().invitation(invite_token)?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/lobby/invites/{invite_token}/accept",
+    tag = "lobby",
+    params(
+        ("invite_token" = Uuid, Path, description = "招待トークン"),
+        ("X-Player-Id" = Uuid, Header, description = "プレイヤーID"),
+    ),
+    responses(
+        (status = 200, description = "対局が成立し、参加者の席を返す", body = PvpSeatResponse),
+        (status = 401, description = "プレイヤーIDが指定されていない、またはアクセストークンが無効", body = ErrorResponse),
+        (status = 403, description = "別のプレイヤー宛ての招待", body = ErrorResponse),
+        (status = 404, description = "招待が存在しない、期限切れ、または成立済み", body = ErrorResponse),
+        (status = 409, description = "自分の募集には参加できない", body = ErrorResponse),
+        (status = 429, description = "セッション数の上限に到達", body = ErrorResponse),
+    )
+)]
+pub async fn accept_invitation(
+    State(state): State<AppState>,
+    PlayerIdentity(player_id): PlayerIdentity,
+    Path(invite_token): Path<Uuid>,
+    api_key: Option<ApiKeyIdentity>,
+    account: CurrentAccount,
+) -> AiBattleResult<Json<PvpSeatResponse>> {
+    let response = state.ai_battle_service.accept_invitation(invite_token, player_id)?;
+    
+    state.ai_battle_service.attribute_to_api_key(response.game_state.game_id, api_key.as_ref())?;
+    state.ai_battle_service.set_seat_account(response.game_state.game_id, response.color, account.as_ref())?;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/lobby/invites/{invite_token}/decline",
+    tag = "lobby",
+    params(
+        ("invite_token" = Uuid, Path, description = "招待トークン"),
+        ("X-Player-Id" = Uuid, Header, description = "プレイヤーID"),
+    ),
+    responses(
+        (status = 204, description = "招待を辞退した（募集者に通知される）"),
+        (status = 401, description = "プレイヤーIDが指定されていない", body = ErrorResponse),
+        (status = 403, description = "別のプレイヤー宛ての招待", body = ErrorResponse),
+        (status = 404, description = "招待が存在しない、期限切れ、または成立済み", body = ErrorResponse),
+        (status = 409, description = "自分の募集は辞退できない（取り消しを使う）", body = ErrorResponse),
+    )
+)]
+pub async fn decline_invitation(
+    State(state): State<AppState>,
+    PlayerIdentity(player_id): PlayerIdentity,
+    Path(invite_token): Path<Uuid>,
+) -> AiBattleResult<StatusCode> {
+    state.ai_battle_service.lobby().decline(invite_token, player_id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/players/me/challenges",
+    tag = "players",
+    params(("X-Player-Id" = Uuid, Header, description = "プレイヤーID")),
+    responses(
+        (status = 200, description = "自分宛ての対局の申し込み", body = IncomingChallengesResponse),
+        (status = 401, description = "プレイヤーIDが指定されていない", body = ErrorResponse),
+    )
+)]
+pub async fn get_incoming_challenges(
+    State(state): State<AppState>,
+    PlayerIdentity(player_id): PlayerIdentity,
+) -> Json<IncomingChallengesResponse> {
+    Json(IncomingChallengesResponse { challenges: state.ai_battle_service.lobby().incoming(player_id) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lobby() -> Lobby {
+        Lobby::new(Arc::new(NotificationHub::default()))
+    }
+
+    #[test]
+    fn test_challenge_can_be_taken_once() {
+        let lobby = lobby();
+        let owner = Uuid::new_v4();
+        let created = lobby.post(owner, None, CreateChallengeRequest::default()).unwrap();
+        let challenge_id = created.challenge.id;
+        assert_eq!(lobby.list().len(), 1);
+
+        assert!(matches!(lobby.take(challenge_id, owner), Err(AiBattleError::OwnChallenge)));
+
+        let open = lobby.take(challenge_id, Uuid::new_v4()).unwrap();
+        assert_eq!(open.owner, owner);
+        assert_eq!(open.player_token, created.player_token);
+        assert!(lobby.list().is_empty());
+        assert!(matches!(
+            lobby.take(challenge_id, Uuid::new_v4()),
+            Err(AiBattleError::ChallengeNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_expired_challenges_are_purged() {
+        let lobby = lobby();
+        let owner = Uuid::new_v4();
+        let created = lobby.post(owner, None, CreateChallengeRequest::default()).unwrap();
+
+        lobby.challenges.get_mut(&created.challenge.id).unwrap().challenge.expires_at = Utc::now();
+        assert!(matches!(
+            lobby.take(created.challenge.id, Uuid::new_v4()),
+            Err(AiBattleError::ChallengeNotFound { .. })
+        ));
+        assert_eq!(lobby.purge_expired(), 0);
+        assert!(lobby.list().is_empty());
+
+        let inbox = lobby.notifications.list(owner, true);
+        assert_eq!(inbox.notifications[0].kind, NotificationKind::ChallengeExpired);
+    }
+
+    #[test]
+    fn test_direct_challenge_flow() {
+        let lobby = lobby();
+        let owner = Uuid::new_v4();
+        let opponent = Uuid::new_v4();
+        let request = CreateChallengeRequest { opponent: Some(opponent), ..Default::default() };
+        let created = lobby.post(owner, None, request).unwrap();
+        let invite_token = created.invite_token.unwrap();
+
+        assert!(created.challenge.private);
+        assert_eq!(created.invite_url, Some(format!("/api/lobby/invites/{}", invite_token)));
+        assert!(lobby.list().is_empty());
+        assert!(matches!(lobby.take(created.challenge.id, opponent), Err(AiBattleError::ChallengeNotFound { .. })));
+        assert_eq!(lobby.incoming(opponent).len(), 1);
+        assert_eq!(lobby.notifications.list(opponent, true).notifications[0].kind, NotificationKind::ChallengeReceived);
+
+        // 宛先以外のプレイヤーは招待トークンを知っていても参加・辞退できない
+        let stranger = Uuid::new_v4();
+        assert!(matches!(lobby.take_invite(invite_token, stranger), Err(AiBattleError::NotChallengeRecipient)));
+        assert!(matches!(lobby.decline(invite_token, stranger), Err(AiBattleError::NotChallengeRecipient)));
+
+        lobby.decline(invite_token, opponent).unwrap();
+        assert!(lobby.incoming(opponent).is_empty());
+        assert_eq!(lobby.notifications.list(owner, true).notifications[0].kind, NotificationKind::ChallengeDeclined);
+    }
+
+    #[test]
+    fn test_invite_link_can_be_used_by_anyone_once() {
+        let lobby = lobby();
+        let owner = Uuid::new_v4();
+        let created = lobby.post(owner, None, CreateChallengeRequest { private: true, ..Default::default() }).unwrap();
+        let invite_token = created.invite_token.unwrap();
+
+        assert!(lobby.list().is_empty());
+        assert_eq!(lobby.invitation(invite_token).unwrap().id, created.challenge.id);
+        assert!(matches!(lobby.take_invite(invite_token, owner), Err(AiBattleError::OwnChallenge)));
+
+        let open = lobby.take_invite(invite_token, Uuid::new_v4()).unwrap();
+        assert_eq!(open.player_token, created.player_token);
+        assert!(matches!(lobby.invitation(invite_token), Err(AiBattleError::ChallengeNotFound { .. })));
+    }
+
+    #[test]
+    fn test_post_validates_request() {
+        let lobby = lobby();
+        let owner = Uuid::new_v4();
+
+        let too_long = CreateChallengeRequest { ttl_seconds: Some(MAX_TTL.as_secs() + 1), ..Default::default() };
+        assert!(matches!(lobby.post(owner, None, too_long), Err(AiBattleError::BadRequest { .. })));
+
+        let no_time = CreateChallengeRequest {
+            time_control: Some(TimeControlSetting::Custom(TimeControl { initial_seconds: 0, increment_seconds: 5, per_move_seconds: None })),
+            ..Default::default()
+        };
+        assert!(matches!(lobby.post(owner, None, no_time), Err(AiBattleError::BadRequest { .. })));
+
+        let created = lobby.post(owner, None, CreateChallengeRequest::default()).unwrap();
+        assert!(matches!(lobby.cancel(created.challenge.id, Uuid::new_v4()), Err(AiBattleError::NotChallengeOwner)));
+        assert!(lobby.cancel(created.challenge.id, owner).is_ok());
+    }
+}
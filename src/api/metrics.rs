@@ -0,0 +1,164 @@
+//! メトリクスAPIモジュール
+//! AIバックエンドごとの着手計算の応答時間と計算待ちキューの状態を、Prometheusのテキスト形式で `/metrics` に公開する。
+
+use axum::{
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use std::fmt::Write;
+
+use crate::ai::queued_service::AiQueueStats;
+use crate::ai::timed_service::AiLatencyStats;
+
+use super::handlers::AppState;
+use super::health::AiBackendRole;
+
+/// Prometheusのテキスト形式のContent-Type
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// AIバックエンド1件分の統計
+#[derive(Debug, Clone)]
+pub struct BackendMetrics {
+    pub role: AiBackendRole,
+    pub name: &'static str,
+    /// 応答時間を計測していなければNone
+    pub latency: Option<AiLatencyStats>,
+    /// 計算待ちキューを使っていなければNone
+    pub queue: Option<AiQueueStats>,
+}
+
+/// 書き出すメトリクス1種類（値がNoneのバックエンドは出力しない）
+struct Metric {
+    name: &'static str,
+    kind: &'static str,
+    help: &'static str,
+    value: fn(&BackendMetrics) -> Option<f64>,
+}
+
+const METRICS: [Metric; 8] = [
+    Metric {
+        name: "reversi_ai_moves_total",
+        kind: "counter",
+        help: "計測した着手計算の総数",
+        value: |backend| backend.latency.map(|stats| stats.count as f64),
+    },
+    Metric {
+        name: "reversi_ai_move_latency_window",
+        kind: "gauge",
+        help: "応答時間の集計に使った直近の計測数",
+        value: |backend| backend.latency.map(|stats| stats.window as f64),
+    },
+    Metric {
+        name: "reversi_ai_move_latency_mean_ms",
+        kind: "gauge",
+        help: "直近の着手計算の応答時間の平均（ミリ秒）",
+        value: |backend| backend.latency.map(|stats| stats.mean_ms),
+    },
+    Metric {
+        name: "reversi_ai_move_latency_p95_ms",
+        kind: "gauge",
+        help: "直近の着手計算の応答時間の95パーセンタイル（ミリ秒）",
+        value: |backend| backend.latency.map(|stats| stats.p95_ms),
+    },
+    Metric {
+        name: "reversi_ai_queue_depth",
+        kind: "gauge",
+        help: "ワーカーが取り出すのを待っている計算の数",
+        value: |backend| backend.queue.map(|stats| stats.depth as f64),
+    },
+    Metric {
+        name: "reversi_ai_queue_capacity",
+        kind: "gauge",
+        help: "計算待ちキューの上限",
+        value: |backend| backend.queue.map(|stats| stats.capacity as f64),
+    },
+    Metric {
+        name: "reversi_ai_queue_active_workers",
+        kind: "gauge",
+        help: "計算中のワーカーの数",
+        value: |backend| backend.queue.map(|stats| stats.active as f64),
+    },
+    Metric {
+        name: "reversi_ai_queue_rejected_total",
+        kind: "counter",
+        help: "計算待ちキューが満杯で断った計算の総数",
+        value: |backend| backend.queue.map(|stats| stats.rejected as f64),
+    },
+];
+
+/// AIバックエンドのメトリクスを書き出す
+pub fn render_metrics(backends: &[BackendMetrics]) -> String {
+    let mut output = String::new();
+    for metric in &METRICS {
+        let _ = writeln!(output, "# HELP {} {}", metric.name, metric.help);
+        let _ = writeln!(output, "# TYPE {} {}", metric.name, metric.kind);
+        for backend in backends {
+            if let Some(value) = (metric.value)(backend) {
+                let _ = writeln!(
+                    output,
+                    "{}{{backend=\"{}\",role=\"{}\"}} {}",
+                    metric.name,
+                    backend.name,
+                    backend.role.as_str(),
+                    value,
+                );
+            }
+        }
+    }
+    output
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "system",
+    responses((
+        status = 200,
+        description = "AIバックエンドごとの着手計算の件数・応答時間と計算待ちキューの状態（Prometheusのテキスト形式）",
+        content_type = "text/plain",
+        body = String
+    ))
+)]
+pub async fn get_metrics(State(state): State<AppState>) -> Response {
+    let backends: Vec<_> = state.health
+        .ai_backends()
+        .into_iter()
+        .map(|(role, service)| BackendMetrics {
+            role,
+            name: service.get_name(),
+            latency: service.latency_stats(),
+            queue: service.queue_stats(),
+        })
+        .collect();
+
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], render_metrics(&backends)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_metrics_labels_each_backend() {
+        let primary = BackendMetrics {
+            role: AiBackendRole::Primary,
+            name: "LocalAIService",
+            latency: Some(AiLatencyStats { count: 12, window: 10, mean_ms: 3.5, p95_ms: 8.0 }),
+            queue: Some(AiQueueStats { depth: 2, capacity: 64, workers: 4, active: 4, rejected: 1 }),
+        };
+        let fallback = BackendMetrics { role: AiBackendRole::Fallback, name: "MockAIService", latency: None, queue: None };
+        let output = render_metrics(&[primary, fallback]);
+
+        assert!(output.contains("# TYPE reversi_ai_moves_total counter\n"));
+        assert!(output.contains("reversi_ai_moves_total{backend=\"LocalAIService\",role=\"primary\"} 12\n"));
+        assert!(output.contains("reversi_ai_move_latency_mean_ms{backend=\"LocalAIService\",role=\"primary\"} 3.5\n"));
+        assert!(output.contains("reversi_ai_move_latency_p95_ms{backend=\"LocalAIService\",role=\"primary\"} 8\n"));
+        assert!(output.contains("reversi_ai_queue_depth{backend=\"LocalAIService\",role=\"primary\"} 2\n"));
+        assert!(output.contains("reversi_ai_queue_rejected_total{backend=\"LocalAIService\",role=\"primary\"} 1\n"));
+        // 計測していないバックエンドの値は出力しない
+        assert!(!output.contains("role=\"fallback\""));
+        // 計測対象がなくてもメトリクスの定義は出力する
+        assert_eq!(render_metrics(&[]).lines().count(), METRICS.len() * 2);
+    }
+}
@@ -1,11 +1,18 @@
 use axum::{
     body::Body,
-    http::Request,
-    middleware::Next,
-    response::Response,
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, Method, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Json, Response},
+    Router,
 };
+use std::sync::Arc;
 use std::time::Instant;
 
+use crate::config::ServerConfig;
+
+use super::ai_battle::dto::ErrorResponse;
+
 pub async fn logging(
     request: Request<Body>,
     next: Next,
@@ -27,18 +34,110 @@ pub async fn logging(
     response
 }
 
+/// CORSで許可するメソッド
+const CORS_ALLOW_METHODS: &str = "GET, POST, PUT, DELETE, OPTIONS";
+
+/// CORSで許可するリクエストヘッダー（APIキーとアクセストークンを含む）
+const CORS_ALLOW_HEADERS: &str = "Content-Type, Authorization, X-API-Key";
+
+/// プリフライトの結果をブラウザがキャッシュする秒数
+const CORS_MAX_AGE_SECS: &str = "600";
+
+/// 設定（`ServerConfig.allowed_origins`）から作るCORSの許可設定
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorsPolicy {
+    /// 空または `*` を含む場合は全てのオリジンを許可する
+    allowed_origins: Vec<String>,
+}
+
+impl CorsPolicy {
+    /// CORSが無効（`enable_cors: false`）の場合はNone
+    pub fn from_config(config: &ServerConfig) -> Option<Self> {
+        config.enable_cors.then(|| Self { allowed_origins: config.allowed_origins.clone() })
+    }
+
+    pub fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.is_empty() || self.allowed_origins.iter().any(|origin| origin == "*")
+    }
+
+    /// `Access-Control-Allow-Origin` に返す値（許可されていないオリジンはNone）
+    fn allow_origin(&self, origin: Option<&HeaderValue>) -> Option<HeaderValue> {
+        if self.allows_any_origin() {
+            return Some(HeaderValue::from_static("*"));
+        }
+        let origin = origin?;
+        let allowed = origin
+            .to_str()
+            .is_ok_and(|origin| self.allowed_origins.iter().any(|allowed| allowed.eq_ignore_ascii_case(origin)));
+        allowed.then(|| origin.clone())
+    }
+
+    fn apply_headers(&self, allow_origin: HeaderValue, headers: &mut HeaderMap) {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+        if !self.allows_any_origin() {
+            headers.append(header::VARY, HeaderValue::from_static("Origin"));
+        }
+    }
+}
+
+/// 設定でCORSが有効な場合のみ、ルーター全体にCORSを適用する
+/// APIキー認証より外側に適用し、プリフライトや401応答にもCORSヘッダーを付ける
+pub fn apply_cors(router: Router, config: &ServerConfig) -> Router {
+    match CorsPolicy::from_config(config) {
+        Some(policy) => router.layer(middleware::from_fn_with_state(Arc::new(policy), cors)),
+        None => router,
+    }
+}
+
+/// 許可されたオリジンからのリクエストにCORSヘッダーを付与し、プリフライトには直接応答する
 pub async fn cors(
+    State(policy): State<Arc<CorsPolicy>>,
     request: Request<Body>,
     next: Next,
 ) -> Response {
-    let response = next.run(request).await;
+    let allow_origin = policy.allow_origin(request.headers().get(header::ORIGIN));
+
+    if is_preflight(&request) {
+        let Some(allow_origin) = allow_origin else {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(ErrorResponse::with_code("CORS_ORIGIN_NOT_ALLOWED", "許可されていないオリジンです", "CORS_ORIGIN_NOT_ALLOWED")),
+            ).into_response();
+        };
+        let mut response = StatusCode::NO_CONTENT.into_response();
+        let headers = response.headers_mut();
+        policy.apply_headers(allow_origin, headers);
+        headers.insert(header::ACCESS_CONTROL_ALLOW_METHODS, HeaderValue::from_static(CORS_ALLOW_METHODS));
+        headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, HeaderValue::from_static(CORS_ALLOW_HEADERS));
+        headers.insert(header::ACCESS_CONTROL_MAX_AGE, HeaderValue::from_static(CORS_MAX_AGE_SECS));
+        return response;
+    }
 
-    let mut response = response;
+    let mut response = next.run(request).await;
+    if let Some(allow_origin) = allow_origin {
+        policy.apply_headers(allow_origin, response.headers_mut());
+    }
+    response
+}
+
+/// `Origin` と `Access-Control-Request-Method` を伴うOPTIONSリクエスト
+fn is_preflight(request: &Request<Body>) -> bool {
+    request.method() == Method::OPTIONS
+        && request.headers().contains_key(header::ORIGIN)
+        && request.headers().contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+}
+
+/// 旧API（/api/games）のレスポンスに非推奨ヘッダーを付与する
+/// 数値盤面表現は廃止予定であり、後継のAI対戦APIを案内する
+pub async fn legacy_deprecation(
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
     let headers = response.headers_mut();
-    
-    headers.insert("Access-Control-Allow-Origin", "*".parse().unwrap());
-    headers.insert("Access-Control-Allow-Methods", "GET, POST, PUT, DELETE, OPTIONS".parse().unwrap());
-    headers.insert("Access-Control-Allow-Headers", "Content-Type, Authorization".parse().unwrap());
+
+    headers.insert("Deprecation", "true".parse().unwrap());
+    headers.insert("Link", "</api/ai-battle>; rel=\"successor-version\"".parse().unwrap());
 
     response
 }
@@ -47,8 +146,66 @@ pub async fn cors(
 mod tests {
     use super::*;
 
+    use axum::routing::get;
+    use tower::ServiceExt;
+
     #[test]
     fn test_middleware_functions_exist() {
         assert!(true);
     }
+
+    fn app(enable_cors: bool, origins: &[&str]) -> Router {
+        let config = ServerConfig {
+            enable_cors,
+            allowed_origins: origins.iter().map(|origin| origin.to_string()).collect(),
+            ..ServerConfig::default()
+        };
+        apply_cors(Router::new().route("/api/ai-battle/sessions", get(|| async { "ok" })), &config)
+    }
+
+    async fn call(app: Router, method: Method, headers: &[(header::HeaderName, &str)]) -> Response {
+        let mut builder = Request::builder().method(method).uri("/api/ai-battle/sessions");
+        for (name, value) in headers {
+            builder = builder.header(name, *value);
+        }
+        app.oneshot(builder.body(Body::empty()).unwrap()).await.unwrap()
+    }
+
+    fn allow_origin(response: &Response) -> Option<&str> {
+        response.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).and_then(|value| value.to_str().ok())
+    }
+
+    #[tokio::test]
+    async fn test_cors_allows_only_configured_origins() {
+        let origins = ["https://reversi.example.com"];
+        let allowed = [(header::ORIGIN, "https://reversi.example.com")];
+        let response = call(app(true, &origins), Method::GET, &allowed).await;
+        assert_eq!(allow_origin(&response), Some("https://reversi.example.com"));
+        assert_eq!(response.headers().get(header::VARY).unwrap(), "Origin");
+
+        let response = call(app(true, &origins), Method::GET, &[(header::ORIGIN, "https://evil.example.com")]).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(allow_origin(&response), None);
+
+        // 許可リストが空なら全てのオリジンを許可する
+        assert_eq!(allow_origin(&call(app(true, &[]), Method::GET, &allowed).await), Some("*"));
+        // CORSが無効ならヘッダーを付けない
+        assert_eq!(allow_origin(&call(app(false, &[]), Method::GET, &allowed).await), None);
+    }
+
+    #[tokio::test]
+    async fn test_cors_answers_preflight_requests() {
+        let origins = ["https://reversi.example.com"];
+        let preflight = |origin| [(header::ORIGIN, origin), (header::ACCESS_CONTROL_REQUEST_METHOD, "POST")];
+
+        let response = call(app(true, &origins), Method::OPTIONS, &preflight("https://reversi.example.com")).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(allow_origin(&response), Some("https://reversi.example.com"));
+        assert!(response.headers().get(header::ACCESS_CONTROL_ALLOW_METHODS).unwrap().to_str().unwrap().contains("POST"));
+        assert!(response.headers().get(header::ACCESS_CONTROL_ALLOW_HEADERS).unwrap().to_str().unwrap().contains("X-API-Key"));
+
+        let response = call(app(true, &origins), Method::OPTIONS, &preflight("https://evil.example.com")).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(allow_origin(&response), None);
+    }
 }
\ No newline at end of file
@@ -1,4 +1,32 @@
 pub mod handlers;
 pub mod middleware;
 pub mod routes;
-pub mod ai_battle;
\ No newline at end of file
+pub mod ai_battle;
+pub mod encoding;
+pub mod json;
+pub mod negotiation;
+pub mod leaderboard;
+pub mod openapi;
+pub mod health;
+pub mod metrics;
+pub mod admin;
+pub mod auth;
+pub mod accounts;
+pub mod archive;
+pub mod identity;
+pub mod players;
+pub mod positions;
+pub mod prefer;
+pub mod puzzles;
+pub mod stats;
+pub mod timeout;
+pub mod tournaments;
+pub mod notifications;
+pub mod webhook;
+pub mod lobby;
+#[cfg(feature = "debug-api")]
+pub mod debug;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "graphql")]
+pub mod graphql;
@@ -0,0 +1,164 @@
+//! レスポンス形式のネゴシエーションモジュール
+//! `Accept` ヘッダーで MessagePack（`application/msgpack`）または CBOR（`application/cbor`）を指定したクライアントに、
+//! JSONと同じ構造のDTOをバイナリ形式で返す。頻繁にポーリングするクライアントの転送量を減らすためで、エラーは常にJSONで返す。
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{
+        header::{ACCEPT, CONTENT_TYPE, VARY},
+        request::Parts,
+        HeaderMap, HeaderValue,
+    },
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+use std::convert::Infallible;
+
+use super::ai_battle::dto::AiBattleError;
+
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+pub const CBOR_CONTENT_TYPE: &str = "application/cbor";
+
+/// レスポンスの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResponseFormat {
+    #[default]
+    Json,
+    MessagePack,
+    Cbor,
+}
+
+impl ResponseFormat {
+    fn from_media_type(media_type: &str) -> Option<Self> {
+        match media_type.to_ascii_lowercase().as_str() {
+            "application/json" | "application/*" | "*/*" => Some(ResponseFormat::Json),
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Some(ResponseFormat::MessagePack)
+            }
+            "application/cbor" => Some(ResponseFormat::Cbor),
+            _ => None,
+        }
+    }
+
+    /// `Accept` ヘッダーのうちq値が最も高い対応形式（同じq値なら先に書かれたもの、対応形式がなければJSON）
+    pub fn from_accept(headers: &HeaderMap) -> Self {
+        let mut best: Option<(f32, ResponseFormat)> = None;
+        let ranges = headers
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','));
+        for range in ranges {
+            let mut parts = range.split(';');
+            let Some(format) = parts.next().and_then(|media_type| Self::from_media_type(media_type.trim())) else {
+                continue;
+            };
+            let quality = parts
+                .filter_map(|parameter| parameter.split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+                .map_or(1.0, |(_, value)| value.trim().parse().unwrap_or(0.0));
+            if quality > 0.0 && best.is_none_or(|(best_quality, _)| quality > best_quality) {
+                best = Some((quality, format));
+            }
+        }
+        best.map_or(ResponseFormat::Json, |(_, format)| format)
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ResponseFormat::Json => "application/json",
+            ResponseFormat::MessagePack => MSGPACK_CONTENT_TYPE,
+            ResponseFormat::Cbor => CBOR_CONTENT_TYPE,
+        }
+    }
+
+    fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, String> {
+        // バイナリ形式ではUUIDがバイト列になるため、一度JSONの値にしてJSONと同じ構造で書き出す
+        let value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+        match self {
+            ResponseFormat::Json => serde_json::to_vec(&value).map_err(|e| e.to_string()),
+            ResponseFormat::MessagePack => rmp_serde::to_vec(&value).map_err(|e| e.to_string()),
+            ResponseFormat::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(&value, &mut bytes).map_err(|e| e.to_string())?;
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ResponseFormat {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(ResponseFormat::from_accept(&parts.headers))
+    }
+}
+
+/// クライアントが指定した形式で返すレスポンス
+#[derive(Debug, Clone)]
+pub struct Negotiated<T>(pub ResponseFormat, pub T);
+
+impl<T: Serialize> IntoResponse for Negotiated<T> {
+    fn into_response(self) -> Response {
+        let Negotiated(format, value) = self;
+        let vary = [(VARY, HeaderValue::from_static("accept"))];
+        if format == ResponseFormat::Json {
+            return (vary, Json(value)).into_response();
+        }
+        match format.encode(&value) {
+            Ok(bytes) => (vary, [(CONTENT_TYPE, HeaderValue::from_static(format.content_type()))], bytes).into_response(),
+            Err(details) => AiBattleError::InternalError { details }.into_response(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn format(accept: &str) -> ResponseFormat {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_str(accept).unwrap());
+        ResponseFormat::from_accept(&headers)
+    }
+
+    #[test]
+    fn test_accept_header_selects_format() {
+        assert_eq!(ResponseFormat::from_accept(&HeaderMap::new()), ResponseFormat::Json);
+        assert_eq!(format("application/msgpack"), ResponseFormat::MessagePack);
+        assert_eq!(format("application/x-msgpack"), ResponseFormat::MessagePack);
+        assert_eq!(format("application/cbor"), ResponseFormat::Cbor);
+        assert_eq!(format("text/html, application/cbor;q=0.5, application/json;q=0.9"), ResponseFormat::Json);
+        assert_eq!(format("application/json;q=0.5, application/msgpack"), ResponseFormat::MessagePack);
+        assert_eq!(format("application/msgpack;q=0, */*"), ResponseFormat::Json);
+        assert_eq!(format("text/html"), ResponseFormat::Json);
+    }
+
+    #[derive(Debug, PartialEq, Serialize)]
+    struct Counts {
+        game_id: Uuid,
+        black_count: u8,
+        winner: Option<String>,
+    }
+
+    #[tokio::test]
+    async fn test_binary_formats_round_trip() {
+        let counts = Counts { game_id: Uuid::new_v4(), black_count: 3, winner: None };
+        for format in [ResponseFormat::MessagePack, ResponseFormat::Cbor] {
+            let response = Negotiated(format, &counts).into_response();
+            assert_eq!(response.headers()[CONTENT_TYPE], format.content_type());
+            assert_eq!(response.headers()[VARY], "accept");
+
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let decoded: serde_json::Value = match format {
+                ResponseFormat::MessagePack => rmp_serde::from_slice(&bytes).unwrap(),
+                _ => ciborium::from_reader(bytes.as_ref()).unwrap(),
+            };
+            assert_eq!(decoded, serde_json::to_value(&counts).unwrap());
+        }
+    }
+}
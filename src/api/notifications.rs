@@ -0,0 +1,433 @@
+//! プレイヤー向け通知モジュール
+//! 対局終了・相手の着手・大会ラウンド開始などの通知をプレイヤーごとにメモリ上で集約し、
+//! `/api/players/me/notifications` での取得とWebSocketでのプッシュを提供する。
+//! 同じ対局・種類の未読通知は1件にまとめ、プッシュは一定間隔に間引く。
+//! Webhookなどの外部配信先を登録すると、通知のたびに配信する。
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::ai_battle::dto::ErrorResponse;
+use super::handlers::AppState;
+use super::json::JsonBody;
+use super::identity::PlayerIdentity;
+
+/// 1プレイヤーあたりに保持する通知の上限
+const DEFAULT_CAPACITY: usize = 100;
+/// まとめられた通知を再プッシュするまでの最短間隔
+const DEFAULT_PUSH_INTERVAL: Duration = Duration::from_secs(5);
+/// WebSocket購読者ごとの送信バッファ
+const CHANNEL_CAPACITY: usize = 32;
+
+/// 通知の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// 参加している対局が終了した
+    GameFinished,
+    /// 対戦相手が着手し、自分の手番になった
+    OpponentMoved,
+    /// 参加している大会の次のラウンドが始まった
+    TournamentRoundStarted,
+    /// ロビーに出した募集に対戦相手が参加し、対局が始まった
+    ChallengeAccepted,
+    /// 自分宛ての対局の申し込みが届いた
+    ChallengeReceived,
+    /// 相手を指定した募集が辞退された
+    ChallengeDeclined,
+    /// 参加者のいないまま募集が期限切れになった
+    ChallengeExpired,
+    /// 通信対局で持ち時間が残りわずかになった
+    TurnReminder,
+}
+
+/// 通知1件
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Notification {
+    pub id: Uuid,
+    pub kind: NotificationKind,
+    pub game_id: Option<Uuid>,
+    /// 最新のイベントの内容
+    pub message: String,
+    /// この通知にまとめられたイベントの数
+    pub count: u32,
+    pub read: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 通知一覧のレスポンス（新しい順）
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationsResponse {
+    pub unread_count: usize,
+    pub notifications: Vec<Notification>,
+}
+
+/// 通知一覧のクエリパラメータ
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct NotificationsQuery {
+    /// trueの場合は未読のみ返す
+    #[serde(default)]
+    pub unread_only: bool,
+}
+
+/// 既読化リクエスト
+/// `ids` を省略した場合は全ての通知を既読にする
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct MarkReadRequest {
+    #[serde(default)]
+    pub ids: Option<Vec<Uuid>>,
+}
+
+/// 既読化の結果
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MarkReadResponse {
+    /// 新たに既読になった通知の数
+    pub marked: usize,
+    pub unread_count: usize,
+}
+
+struct InboxEntry {
+    notification: Notification,
+    last_pushed: DateTime<Utc>,
+}
+
+struct Inbox {
+    entries: VecDeque<InboxEntry>,
+    sender: broadcast::Sender<Notification>,
+}
+
+impl Inbox {
+    fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { entries: VecDeque::new(), sender }
+    }
+
+    fn unread_count(&self) -> usize {
+        self.entries.iter().filter(|entry| !entry.notification.read).count()
+    }
+}
+
+/// 通知の外部配信先
+/// 受信箱へのまとめや間引きとは独立に、通知のたびに呼ばれる
+pub trait NotificationSink: Send + Sync {
+    fn deliver(&self, player_id: Uuid, notification: &Notification);
+}
+
+/// プレイヤーごとの通知箱
+pub struct NotificationHub {
+    inboxes: DashMap<Uuid, Inbox>,
+    capacity: usize,
+    push_interval: Duration,
+    sinks: RwLock<Vec<Arc<dyn NotificationSink>>>,
+}
+
+impl std::fmt::Debug for NotificationHub {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NotificationHub")
+            .field("players", &self.inboxes.len())
+            .field("capacity", &self.capacity)
+            .field("push_interval", &self.push_interval)
+            .field("sinks", &self.sinks.read().map_or(0, |sinks| sinks.len()))
+            .finish()
+    }
+}
+
+impl Default for NotificationHub {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY, DEFAULT_PUSH_INTERVAL)
+    }
+}
+
+impl NotificationHub {
+    pub fn new(capacity: usize, push_interval: Duration) -> Self {
+        Self { inboxes: DashMap::new(), capacity, push_interval, sinks: RwLock::new(Vec::new()) }
+    }
+
+    /// 外部配信先を登録する
+    pub fn add_sink(&self, sink: Arc<dyn NotificationSink>) {
+        if let Ok(mut sinks) = self.sinks.write() {
+            sinks.push(sink);
+        }
+    }
+
+    /// プレイヤーに通知を追加する
+    /// 同じ種類・同じ対局の未読通知があればそちらにまとめ、前回のプッシュから
+    /// `push_interval` が経過している場合のみ再プッシュする
+    pub fn notify(&self, player_id: Uuid, kind: NotificationKind, game_id: Option<Uuid>, message: impl Into<String>) {
+        let notification = self.record(player_id, kind, game_id, message.into());
+
+        let Ok(sinks) = self.sinks.read() else { return };
+        for sink in sinks.iter() {
+            sink.deliver(player_id, &notification);
+        }
+    }
+
+    /// 受信箱に通知を追加し、追加（またはまとめた）後の通知を返す
+    fn record(&self, player_id: Uuid, kind: NotificationKind, game_id: Option<Uuid>, message: String) -> Notification {
+        let mut inbox = self.inboxes.entry(player_id).or_insert_with(Inbox::new);
+        let Inbox { entries, sender } = &mut *inbox;
+        let now = Utc::now();
+
+        let pending = entries
+            .iter_mut()
+            .find(|entry| !entry.notification.read && entry.notification.kind == kind && entry.notification.game_id == game_id);
+        if let Some(entry) = pending {
+            entry.notification.count += 1;
+            entry.notification.message = message;
+            entry.notification.updated_at = now;
+
+            let interval = chrono::Duration::from_std(self.push_interval).unwrap_or(chrono::Duration::MAX);
+            if now - entry.last_pushed >= interval {
+                entry.last_pushed = now;
+                let _ = sender.send(entry.notification.clone());
+            }
+            return entry.notification.clone();
+        }
+
+        let notification = Notification {
+            id: Uuid::new_v4(),
+            kind,
+            game_id,
+            message,
+            count: 1,
+            read: false,
+            created_at: now,
+            updated_at: now,
+        };
+        let _ = sender.send(notification.clone());
+        entries.push_back(InboxEntry { notification: notification.clone(), last_pushed: now });
+
+        while entries.len() > self.capacity {
+            entries.pop_front();
+        }
+        notification
+    }
+
+    /// 通知一覧を新しい順に取得する
+    pub fn list(&self, player_id: Uuid, unread_only: bool) -> NotificationsResponse {
+        let Some(inbox) = self.inboxes.get(&player_id) else {
+            return NotificationsResponse { unread_count: 0, notifications: Vec::new() };
+        };
+
+        let notifications = inbox.entries
+            .iter()
+            .rev()
+            .filter(|entry| !unread_only || !entry.notification.read)
+            .map(|entry| entry.notification.clone())
+            .collect();
+
+        NotificationsResponse { unread_count: inbox.unread_count(), notifications }
+    }
+
+    /// 通知を既読にする（`ids` がNoneなら全件）
+    pub fn mark_read(&self, player_id: Uuid, ids: Option<&[Uuid]>) -> MarkReadResponse {
+        let Some(mut inbox) = self.inboxes.get_mut(&player_id) else {
+            return MarkReadResponse { marked: 0, unread_count: 0 };
+        };
+
+        let mut marked = 0;
+        for entry in inbox.entries.iter_mut() {
+            let selected = ids.is_none_or(|ids| ids.contains(&entry.notification.id));
+            if selected && !entry.notification.read {
+                entry.notification.read = true;
+                marked += 1;
+            }
+        }
+
+        MarkReadResponse { marked, unread_count: inbox.unread_count() }
+    }
+
+    /// プレイヤー宛ての通知のプッシュを購読する
+    pub fn subscribe(&self, player_id: Uuid) -> broadcast::Receiver<Notification> {
+        self.inboxes.entry(player_id).or_insert_with(Inbox::new).sender.subscribe()
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/players/me/notifications",
+    tag = "players",
+    params(
+        NotificationsQuery,
+        ("X-Player-Id" = Uuid, Header, description = "プレイヤーID"),
+    ),
+    responses(
+        (status = 200, description = "通知一覧（新しい順）", body = NotificationsResponse),
+        (status = 401, description = "プレイヤーIDが指定されていない", body = ErrorResponse),
+    )
+)]
+pub async fn get_notifications(
+    State(state): State<AppState>,
+    PlayerIdentity(player_id): PlayerIdentity,
+    Query(query): Query<NotificationsQuery>,
+) -> Json<NotificationsResponse> {
+    Json(state.ai_battle_service.notifications().list(player_id, query.unread_only))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/players/me/notifications/read",
+    tag = "players",
+    params(("X-Player-Id" = Uuid, Header, description = "プレイヤーID")),
+    request_body = MarkReadRequest,
+    responses(
+        (status = 200, description = "既読化の結果", body = MarkReadResponse),
+        (status = 401, description = "プレイヤーIDが指定されていない", body = ErrorResponse),
+    )
+)]
+pub async fn mark_notifications_read(
+    State(state): State<AppState>,
+    PlayerIdentity(player_id): PlayerIdentity,
+    JsonBody(request): JsonBody<MarkReadRequest>,
+) -> Json<MarkReadResponse> {
+    Json(state.ai_battle_service.notifications().mark_read(player_id, request.ids.as_deref()))
+}
+
+/// WebSocket接続時のクエリパラメータ
+/// ブラウザのWebSocketはヘッダーを付けられないため、クエリでも指定できる
+#[derive(Debug, Deserialize)]
+pub struct NotificationSocketQuery {
+    pub player_id: Option<Uuid>,
+}
+
+/// 通知をWebSocketでプッシュする（1メッセージにつき `Notification` 1件のJSON）
+pub async fn notifications_socket(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    identity: Option<PlayerIdentity>,
+    Query(query): Query<NotificationSocketQuery>,
+) -> Response {
+    let Some(player_id) = identity.map(|PlayerIdentity(id)| id).or(query.player_id) else {
+        let error = ErrorResponse::with_code("MISSING_PLAYER_ID", "プレイヤーIDを指定してください", "MISSING_PLAYER_ID");
+        return (StatusCode::UNAUTHORIZED, Json(error)).into_response();
+    };
+
+    let receiver = state.ai_battle_service.notifications().subscribe(player_id);
+    ws.on_upgrade(move |socket| push_notifications(socket, receiver))
+}
+
+async fn push_notifications(mut socket: WebSocket, mut receiver: broadcast::Receiver<Notification>) {
+    loop {
+        tokio::select! {
+            received = receiver.recv() => match received {
+                Ok(notification) => {
+                    let Ok(text) = serde_json::to_string(&notification) else { continue };
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                // 取りこぼした分は一覧APIで取得できる
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unread_notifications_are_digested() {
+        let hub = NotificationHub::new(10, Duration::from_secs(60));
+        let player = Uuid::new_v4();
+        let game = Some(Uuid::new_v4());
+        let mut receiver = hub.subscribe(player);
+
+        hub.notify(player, NotificationKind::OpponentMoved, game, "first");
+        hub.notify(player, NotificationKind::OpponentMoved, game, "second");
+        hub.notify(player, NotificationKind::GameFinished, game, "finished");
+
+        let list = hub.list(player, false);
+        assert_eq!(list.unread_count, 2);
+        assert_eq!(list.notifications[0].kind, NotificationKind::GameFinished);
+        assert_eq!(list.notifications[1].count, 2);
+        assert_eq!(list.notifications[1].message, "second");
+
+        // まとめられた2件目は間隔内なのでプッシュされない
+        assert_eq!(receiver.try_recv().unwrap().message, "first");
+        assert_eq!(receiver.try_recv().unwrap().message, "finished");
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_digested_notification_is_repushed_after_interval() {
+        let hub = NotificationHub::new(10, Duration::ZERO);
+        let player = Uuid::new_v4();
+        let mut receiver = hub.subscribe(player);
+
+        hub.notify(player, NotificationKind::OpponentMoved, None, "first");
+        hub.notify(player, NotificationKind::OpponentMoved, None, "second");
+
+        assert_eq!(receiver.try_recv().unwrap().count, 1);
+        assert_eq!(receiver.try_recv().unwrap().count, 2);
+    }
+
+    #[test]
+    fn test_mark_read_and_capacity() {
+        let hub = NotificationHub::new(2, Duration::from_secs(60));
+        let player = Uuid::new_v4();
+
+        for _ in 0..3 {
+            hub.notify(player, NotificationKind::GameFinished, Some(Uuid::new_v4()), "finished");
+        }
+        let list = hub.list(player, false);
+        assert_eq!(list.notifications.len(), 2);
+
+        let first = list.notifications[0].id;
+        let result = hub.mark_read(player, Some(&[first]));
+        assert_eq!(result.marked, 1);
+        assert_eq!(result.unread_count, 1);
+        assert_eq!(hub.list(player, true).notifications.len(), 1);
+
+        assert_eq!(hub.mark_read(player, None).marked, 1);
+        assert_eq!(hub.list(Uuid::new_v4(), false).unread_count, 0);
+
+        // 既読になった通知には新しいイベントをまとめない
+        hub.notify(player, NotificationKind::GameFinished, list.notifications[0].game_id, "again");
+        assert_eq!(hub.list(player, true).notifications[0].count, 1);
+    }
+
+    #[test]
+    fn test_sinks_receive_every_notification() {
+        #[derive(Default)]
+        struct Recorder(std::sync::Mutex<Vec<(Uuid, u32)>>);
+
+        impl NotificationSink for Recorder {
+            fn deliver(&self, player_id: Uuid, notification: &Notification) {
+                self.0.lock().unwrap().push((player_id, notification.count));
+            }
+        }
+
+        let hub = NotificationHub::new(10, Duration::from_secs(60));
+        let recorder = Arc::new(Recorder::default());
+        hub.add_sink(recorder.clone());
+        let player = Uuid::new_v4();
+
+        // まとめられてプッシュが間引かれる場合も外部配信は行う
+        hub.notify(player, NotificationKind::TurnReminder, None, "first");
+        hub.notify(player, NotificationKind::TurnReminder, None, "second");
+        assert_eq!(*recorder.0.lock().unwrap(), vec![(player, 1), (player, 2)]);
+    }
+}
@@ -0,0 +1,266 @@
+//! OpenAPIドキュメント生成モジュール
+//! ハンドラーとDTOの定義からOpenAPI仕様を生成し、
+//! `/api/openapi.json` で公開する。
+
+use axum::response::Json;
+use utoipa::OpenApi;
+
+use super::{accounts, admin, ai_battle, archive, handlers, health, leaderboard, lobby, metrics, notifications, players, positions, puzzles, routes, stats, tournaments};
+
+/// API全体のOpenAPI定義
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Reversi API", description = "リバーシ対戦API"),
+    paths(
+        ai_battle::handlers::create_ai_battle,
+        ai_battle::handlers::get_difficulties,
+        ai_battle::handlers::get_time_control_presets,
+        ai_battle::handlers::stream_events,
+        ai_battle::handlers::wait_for_change,
+        ai_battle::handlers::get_sessions,
+        ai_battle::handlers::get_game_state,
+        ai_battle::handlers::delete_game,
+        ai_battle::handlers::execute_move,
+        ai_battle::handlers::pass_turn,
+        ai_battle::handlers::change_difficulty,
+        ai_battle::handlers::get_history,
+        ai_battle::handlers::get_transcript,
+        ai_battle::handlers::get_replay,
+        ai_battle::handlers::export_game,
+        ai_battle::handlers::import_game,
+        ai_battle::handlers::get_hint,
+        ai_battle::handlers::analyze_position,
+        ai_battle::handlers::create_ai_vs_ai,
+        ai_battle::handlers::step_game,
+        ai_battle::handlers::create_pvp,
+        ai_battle::handlers::join_pvp,
+        ai_battle::handlers::undo_move,
+        ai_battle::handlers::share_game,
+        ai_battle::handlers::spectate_game,
+        ai_battle::handlers::stream_spectator_events,
+        handlers::create_game,
+        handlers::import_game,
+        handlers::get_game,
+        handlers::make_move,
+        handlers::delete_game,
+        routes::health_check,
+        health::full_health,
+        health::liveness,
+        health::readiness,
+        metrics::get_metrics,
+        admin::pin_session,
+        admin::get_service_status,
+        archive::get_archive,
+        archive::get_archive_wthor,
+        positions::get_position,
+        puzzles::get_daily_puzzle,
+        puzzles::answer_daily_puzzle,
+        puzzles::get_puzzle_streak,
+        players::get_player_rating,
+        leaderboard::get_leaderboard,
+        stats::get_difficulty_stats,
+        tournaments::create_tournament,
+        tournaments::list_tournaments,
+        tournaments::get_tournament,
+        accounts::register,
+        accounts::login,
+        accounts::get_me,
+        notifications::get_notifications,
+        notifications::mark_notifications_read,
+        lobby::list_challenges,
+        lobby::create_challenge,
+        lobby::accept_challenge,
+        lobby::cancel_challenge,
+        lobby::get_invitation,
+        lobby::accept_invitation,
+        lobby::decline_invitation,
+        lobby::get_incoming_challenges,
+    ),
+    components(schemas(
+        super::encoding::ApiPlayer,
+        crate::game::Position,
+        crate::ai::Difficulty,
+        crate::ai::service::MoveAnalysis,
+        ai_battle::dto::AiDifficulty,
+        ai_battle::dto::GameStatus,
+        ai_battle::dto::MoveRecord,
+        ai_battle::dto::PlayerController,
+        ai_battle::dto::CreateAiVsAiRequest,
+        ai_battle::dto::StepResponse,
+        ai_battle::dto::SessionKind,
+        ai_battle::dto::JoinPvpRequest,
+        ai_battle::dto::PvpSeatResponse,
+        ai_battle::dto::TranscriptMove,
+        ai_battle::dto::UndoResponse,
+        ai_battle::dto::ShareResponse,
+        ai_battle::dto::CreateAiBattleRequest,
+        ai_battle::dto::PlayerMoveRequest,
+        ai_battle::dto::ChangeDifficultyRequest,
+        ai_battle::dto::AiBattleResponse,
+        ai_battle::dto::AiBattleDelta,
+        ai_battle::dto::CellChange,
+        ai_battle::dto::GameStateView,
+        ai_battle::dto::WaitResponse,
+        ai_battle::dto::PositionEvaluation,
+        crate::ai::explain::MoveExplanation,
+        crate::ai::adaptive::AdaptiveState,
+        crate::ai::explain::ConsideredMove,
+        crate::ai::explain::EvalComponents,
+        crate::ai::strategies::ScoredMove,
+        ai_battle::dto::MoveResponse,
+        ai_battle::dto::PassRequest,
+        ai_battle::dto::PassResponse,
+        ai_battle::dto::HintResponse,
+        ai_battle::dto::AnalyzeRequest,
+        ai_battle::dto::AnalyzeResponse,
+        ai_battle::dto::SessionListResponse,
+        ai_battle::dto::SessionSortKey,
+        ai_battle::dto::SortOrder,
+        ai_battle::dto::SessionSummary,
+        ai_battle::dto::PinSessionRequest,
+        ai_battle::dto::DeletionReceipt,
+        archive::ArchiveResponse,
+        crate::archive::ArchivedGame,
+        crate::archive::GameResult,
+        crate::positions::PositionReport,
+        crate::positions::KnownEvaluation,
+        crate::positions::ExplorerStats,
+        crate::positions::Continuation,
+        crate::positions::PuzzleReference,
+        crate::puzzles::DailyPuzzleResponse,
+        crate::puzzles::PuzzleAnswerRequest,
+        crate::puzzles::PuzzleAnswerResponse,
+        crate::puzzles::PuzzleStreak,
+        crate::ratings::PlayerRating,
+        crate::ratings::RatingChange,
+        crate::leaderboard::LeaderboardPeriod,
+        crate::leaderboard::DifficultyRecord,
+        crate::leaderboard::LeaderboardEntry,
+        crate::leaderboard::LeaderboardResponse,
+        crate::stats::DifficultyStats,
+        crate::stats::DifficultyStatsResponse,
+        crate::tournament::EngineSpec,
+        crate::tournament::CreateTournamentRequest,
+        crate::tournament::TournamentStatus,
+        crate::tournament::TournamentGame,
+        crate::tournament::Standing,
+        crate::tournament::TournamentResponse,
+        crate::tournament::TournamentListResponse,
+        crate::accounts::CredentialsRequest,
+        crate::accounts::AccessTokenResponse,
+        crate::accounts::AccountProfile,
+        ai_battle::dto::MoveHistoryResponse,
+        ai_battle::dto::TranscriptResponse,
+        ai_battle::dto::SessionExport,
+        ai_battle::dto::SessionExportConfig,
+        ai_battle::dto::ReplayResponse,
+        ai_battle::dto::ReplaySnapshot,
+        ai_battle::dto::DifficultyInfo,
+        ai_battle::dto::LevelInfo,
+        crate::ai::levels::AiConfigOverrides,
+        crate::ai::evaluation::EvalPreset,
+        crate::ai::personality::AiPersonality,
+        crate::game::GameVariant,
+        crate::game::InvalidMoveReason,
+        ai_battle::dto::Handicap,
+        ai_battle::dto::StartPosition,
+        ai_battle::dto::PersonalityInfo,
+        crate::ai::levels::LevelParams,
+        crate::ai::levels::Heuristic,
+        ai_battle::dto::DifficultiesResponse,
+        ai_battle::dto::ErrorResponse,
+        handlers::GameResponse,
+        handlers::MoveResponse,
+        handlers::ErrorResponse,
+        handlers::CreateGameRequest,
+        handlers::PlayerTypeRequest,
+        handlers::MakeMoveRequest,
+        handlers::ImportGameRequest,
+        handlers::ImportGameResponse,
+        health::HealthStatus,
+        health::AiBackendRole,
+        health::BreakerState,
+        health::AiBackendHealth,
+        crate::ai::cached_service::AiCacheStats,
+        crate::ai::timed_service::AiLatencyStats,
+        ai_battle::config_service::ServiceStatus,
+        ai_battle::service::ServiceStats,
+        health::SessionStoreHealth,
+        health::BackgroundTaskHealth,
+        health::QueueHealth,
+        health::FullHealthResponse,
+        health::LivenessResponse,
+        health::ReadinessResponse,
+        notifications::NotificationKind,
+        notifications::Notification,
+        notifications::NotificationsResponse,
+        notifications::MarkReadRequest,
+        notifications::MarkReadResponse,
+        ai_battle::clock::TimeControl,
+        ai_battle::clock::TimeControlPreset,
+        ai_battle::clock::TimeControlSetting,
+        ai_battle::clock::TimeControlPresetInfo,
+        ai_battle::clock::TimeControlPresetsResponse,
+        ai_battle::clock::ClockView,
+        ai_battle::events::SessionEvent,
+        lobby::ColorPreference,
+        lobby::Challenge,
+        lobby::CreateChallengeRequest,
+        lobby::ChallengeCreatedResponse,
+        lobby::LobbyResponse,
+        lobby::IncomingChallenge,
+        lobby::IncomingChallengesResponse,
+    )),
+    tags(
+        (name = "ai-battle", description = "AI対戦API"),
+        (name = "legacy", description = "旧ゲームAPI（非推奨）"),
+        (name = "system", description = "システム情報"),
+        (name = "players", description = "プレイヤー向けAPI"),
+        (name = "lobby", description = "対人戦の募集ロビー"),
+        (name = "archive", description = "終局した対局の記録"),
+        (name = "positions", description = "局面ハッシュによる局面の参照"),
+        (name = "puzzles", description = "アーカイブの対局から作った毎日の問題"),
+        (name = "accounts", description = "プレイヤーアカウントとアクセストークンの発行"),
+        (name = "tournaments", description = "AIエンジン同士の総当たり戦"),
+    )
+)]
+pub struct ApiDoc;
+
+/// 有効なフィーチャーに応じたOpenAPI仕様を組み立てる
+pub fn api_doc() -> utoipa::openapi::OpenApi {
+    #[allow(unused_mut)]
+    let mut doc = ApiDoc::openapi();
+    #[cfg(feature = "debug-api")]
+    doc.merge(super::debug::DebugApiDoc::openapi());
+    doc
+}
+
+/// 生成済みのOpenAPI仕様を返す
+pub async fn openapi_spec() -> Json<utoipa::openapi::OpenApi> {
+    Json(api_doc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_document_contains_all_paths() {
+        let doc = ApiDoc::openapi();
+
+        for path in ["/api/ai-battle", "/api/ai-battle/{game_id}", "/api/ai-battle/{game_id}/hint", "/api/games/{id}", "/health"] {
+            assert!(doc.paths.paths.contains_key(path), "missing path: {}", path);
+        }
+    }
+
+    #[test]
+    fn test_openapi_schema_names_are_unique() {
+        let doc = ApiDoc::openapi();
+        let schemas = &doc.components.as_ref().unwrap().schemas;
+
+        assert!(schemas.contains_key("MoveResponse"));
+        assert!(schemas.contains_key("LegacyMoveResponse"));
+        assert!(schemas.contains_key("ErrorResponse"));
+        assert!(schemas.contains_key("LegacyErrorResponse"));
+    }
+}
@@ -0,0 +1,27 @@
+//! プレイヤーAPIモジュール
+//! プレイヤーのEloレーティングを引く `/api/players/:player_id/rating` を提供する。
+//! プレイヤーIDは `X-Player-Id` ヘッダーで指定したID、またはアカウントIDで、未対局のプレイヤーは初期レーティングを返す。
+
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use uuid::Uuid;
+
+use crate::ratings::PlayerRating;
+
+use super::handlers::AppState;
+
+#[utoipa::path(
+    get,
+    path = "/api/players/{player_id}/rating",
+    tag = "players",
+    params(("player_id" = Uuid, Path, description = "プレイヤーID（`X-Player-Id` またはアカウントID）")),
+    responses((status = 200, description = "プレイヤーのレーティングと成績", body = PlayerRating))
+)]
+pub async fn get_player_rating(
+    State(state): State<AppState>,
+    Path(player_id): Path<Uuid>,
+) -> Json<PlayerRating> {
+    Json(state.ai_battle_service.ratings().get(player_id))
+}
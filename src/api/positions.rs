@@ -0,0 +1,31 @@
+//! 局面APIモジュール
+//! 正規化済みの局面ハッシュから盤面と既知の情報を引く `/api/positions/:hash` を提供する。
+//! ハッシュは盤の向きに依存しない公開識別子で、対局状態のレスポンスの `position_hash` から得られる。
+
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+
+use crate::positions::PositionReport;
+
+use super::ai_battle::dto::AiBattleResult;
+use super::handlers::AppState;
+
+#[utoipa::path(
+    get,
+    path = "/api/positions/{hash}",
+    tag = "positions",
+    params(("hash" = String, Path, description = "16桁の16進数の局面ハッシュ")),
+    responses(
+        (status = 200, description = "局面の盤面と既知の評価・統計・問題", body = PositionReport),
+        (status = 400, description = "ハッシュの形式が不正", body = ErrorResponse),
+        (status = 404, description = "登録されていない局面", body = ErrorResponse),
+    )
+)]
+pub async fn get_position(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+) -> AiBattleResult<Json<PositionReport>> {
+    Ok(Json(state.ai_battle_service.lookup_position(&hash)?))
+}
@@ -0,0 +1,71 @@
+//! `Prefer` ヘッダー（RFC 7240）の解釈モジュール
+//! 既定では本文を返さない操作について、`Prefer: return=representation` を
+//! 指定したクライアントにだけ結果のドキュメントを返すために使う。
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header::HeaderName, request::Parts, HeaderValue},
+};
+use std::convert::Infallible;
+
+/// 要求を受け入れたことを示すレスポンスヘッダー
+pub const PREFERENCE_APPLIED: HeaderName = HeaderName::from_static("preference-applied");
+
+/// クライアントが `return=representation` を要求したか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PreferRepresentation(pub bool);
+
+impl PreferRepresentation {
+    /// `Preference-Applied` に返す値
+    pub fn applied() -> HeaderValue {
+        HeaderValue::from_static("return=representation")
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for PreferRepresentation {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let requested = parts
+            .headers
+            .get_all("prefer")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|preference| preference.split(';').next().unwrap_or("").trim())
+            .any(|preference| {
+                preference
+                    .split_once('=')
+                    .is_some_and(|(name, value)| {
+                        name.trim().eq_ignore_ascii_case("return")
+                            && value.trim().trim_matches('"').eq_ignore_ascii_case("representation")
+                    })
+            });
+        Ok(PreferRepresentation(requested))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+
+    async fn extract(header: Option<&str>) -> bool {
+        let mut builder = Request::builder();
+        if let Some(value) = header {
+            builder = builder.header("Prefer", value);
+        }
+        let (mut parts, _) = builder.body(()).unwrap().into_parts();
+        PreferRepresentation::from_request_parts(&mut parts, &()).await.unwrap().0
+    }
+
+    #[tokio::test]
+    async fn test_prefer_return_representation() {
+        assert!(extract(Some("return=representation")).await);
+        assert!(extract(Some("respond-async, Return = \"representation\"; foo=bar")).await);
+        assert!(!extract(Some("return=minimal")).await);
+        assert!(!extract(None).await);
+    }
+}
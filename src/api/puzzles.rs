@@ -0,0 +1,63 @@
+//! 毎日の問題APIモジュール
+//! 今日の問題を返す `/api/puzzles/today` と、解答を検証して連続正解日数を更新する
+//! `/api/puzzles/today/answer` を提供する。解答者は `X-Player-Id` ヘッダーで識別する。
+
+use axum::{extract::State, response::Json};
+
+use crate::puzzles::{DailyPuzzleResponse, PuzzleAnswerRequest, PuzzleAnswerResponse, PuzzleStreak};
+
+use super::ai_battle::dto::AiBattleResult;
+use super::handlers::AppState;
+use super::identity::PlayerIdentity;
+use super::json::JsonBody;
+
+#[utoipa::path(
+    get,
+    path = "/api/puzzles/today",
+    tag = "puzzles",
+    responses(
+        (status = 200, description = "今日の問題（勝ちになるただ1つの手を見つける）", body = DailyPuzzleResponse),
+        (status = 404, description = "アーカイブの対局からまだ問題を作れていない", body = ErrorResponse),
+    )
+)]
+pub async fn get_daily_puzzle(State(state): State<AppState>) -> AiBattleResult<Json<DailyPuzzleResponse>> {
+    Ok(Json(state.ai_battle_service.daily_puzzle()?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/puzzles/today/answer",
+    tag = "puzzles",
+    params(("X-Player-Id" = Uuid, Header, description = "プレイヤーID")),
+    request_body = PuzzleAnswerRequest,
+    responses(
+        (status = 200, description = "解答の正誤と正解手・連続正解日数（成績に数えるのはその日の最初の解答のみ）", body = PuzzleAnswerResponse),
+        (status = 400, description = "座標が範囲外、または合法手ではない", body = ErrorResponse),
+        (status = 401, description = "プレイヤーIDが指定されていない", body = ErrorResponse),
+        (status = 404, description = "アーカイブの対局からまだ問題を作れていない", body = ErrorResponse),
+    )
+)]
+pub async fn answer_daily_puzzle(
+    State(state): State<AppState>,
+    PlayerIdentity(player_id): PlayerIdentity,
+    JsonBody(request): JsonBody<PuzzleAnswerRequest>,
+) -> AiBattleResult<Json<PuzzleAnswerResponse>> {
+    Ok(Json(state.ai_battle_service.answer_puzzle(player_id, request)?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/puzzles/streak",
+    tag = "puzzles",
+    params(("X-Player-Id" = Uuid, Header, description = "プレイヤーID")),
+    responses(
+        (status = 200, description = "プレイヤーの連続正解日数と成績", body = PuzzleStreak),
+        (status = 401, description = "プレイヤーIDが指定されていない", body = ErrorResponse),
+    )
+)]
+pub async fn get_puzzle_streak(
+    State(state): State<AppState>,
+    PlayerIdentity(player_id): PlayerIdentity,
+) -> Json<PuzzleStreak> {
+    Json(state.ai_battle_service.puzzle_streak(player_id))
+}
@@ -5,33 +5,106 @@ use axum::{
 };
 use tower::util::ServiceExt;
 
+use crate::config::{RouteClass, RouteTimeouts};
+
 use super::{
-    handlers::{create_game, delete_game, get_game, make_move, AppState},
-    middleware::{cors, logging},
+    handlers::{create_game, delete_game, get_game, import_game, make_move, AppState},
+    middleware::{legacy_deprecation, logging},
     ai_battle::routes::create_ai_battle_routes,
+    openapi::openapi_spec,
+    timeout::WithTimeout,
+    health::{full_health, liveness, readiness},
+    metrics::get_metrics,
+    admin::{get_service_status, pin_session},
+    archive::{get_archive, get_archive_wthor},
+    positions::get_position,
+    puzzles::{answer_daily_puzzle, get_daily_puzzle, get_puzzle_streak},
+    players::get_player_rating,
+    leaderboard::get_leaderboard,
+    stats::get_difficulty_stats,
+    tournaments::{create_tournament, get_tournament, list_tournaments},
+    accounts::{get_me, login, register},
+    notifications::{get_notifications, mark_notifications_read, notifications_socket},
+    lobby::{
+        accept_challenge, accept_invitation, cancel_challenge, create_challenge, decline_invitation,
+        get_incoming_challenges, get_invitation, list_challenges,
+    },
 };
 
 pub fn create_router() -> Router<AppState> {
+    create_router_with_timeouts(&RouteTimeouts::default())
+}
+
+/// 設定されたルート別の処理時間の上限を適用してルーターを作成する
+/// WebSocketの常時接続には上限を設定しない
+pub fn create_router_with_timeouts(timeouts: &RouteTimeouts) -> Router<AppState> {
+    let read = timeouts.budget(RouteClass::StateRead);
+    let default = timeouts.budget(RouteClass::Default);
+    
+    // 旧APIは数値盤面表現を返すため非推奨として扱う
+    let legacy_routes = Router::new()
+        .route("/api/games", post(create_game).with_timeout(default))
+        .route("/api/games/import", post(import_game).with_timeout(default))
+        .route("/api/games/:id", get(get_game).with_timeout(read))
+        .route("/api/games/:id/move", put(make_move).with_timeout(default))
+        .route("/api/games/:id", delete(delete_game).with_timeout(default))
+        .layer(middleware::from_fn(legacy_deprecation));
+    
     let base_routes = Router::new()
-        .route("/api/games", post(create_game))
-        .route("/api/games/:id", get(get_game))
-        .route("/api/games/:id/move", put(make_move))
-        .route("/api/games/:id", delete(delete_game))
-        
-        .route("/health", get(health_check));
+        .merge(legacy_routes)
+        .route("/health", get(health_check).with_timeout(read))
+        .route("/health/live", get(liveness).with_timeout(read))
+        .route("/health/ready", get(readiness).with_timeout(default))
+        .route("/metrics", get(get_metrics).with_timeout(read))
+        .route("/api/openapi.json", get(openapi_spec).with_timeout(read))
+        .route("/api/admin/health/full", get(full_health).with_timeout(default))
+        .route("/api/admin/status", get(get_service_status).with_timeout(default))
+        .route("/api/admin/sessions/:game_id/pin", put(pin_session).with_timeout(default))
+        .route("/api/archive", get(get_archive).with_timeout(default))
+        .route("/api/archive/wthor", get(get_archive_wthor).with_timeout(default))
+        .route("/api/positions/:hash", get(get_position).with_timeout(read))
+        .route("/api/puzzles/today", get(get_daily_puzzle).with_timeout(read))
+        .route("/api/puzzles/today/answer", post(answer_daily_puzzle).with_timeout(default))
+        .route("/api/puzzles/streak", get(get_puzzle_streak).with_timeout(read))
+        .route("/api/accounts/register", post(register).with_timeout(default))
+        .route("/api/accounts/login", post(login).with_timeout(default))
+        .route("/api/accounts/me", get(get_me).with_timeout(read))
+        .route("/api/players/me/notifications", get(get_notifications).with_timeout(read))
+        .route("/api/players/me/notifications/read", post(mark_notifications_read).with_timeout(default))
+        .route("/api/players/me/notifications/ws", get(notifications_socket))
+        .route("/api/lobby", get(list_challenges).with_timeout(read).post(create_challenge).with_timeout(default))
+        .route("/api/lobby/:challenge_id", delete(cancel_challenge).with_timeout(default))
+        .route("/api/lobby/:challenge_id/accept", post(accept_challenge).with_timeout(default))
+        .route("/api/lobby/invites/:invite_token", get(get_invitation).with_timeout(read))
+        .route("/api/lobby/invites/:invite_token/accept", post(accept_invitation).with_timeout(default))
+        .route("/api/lobby/invites/:invite_token/decline", post(decline_invitation).with_timeout(default))
+        .route("/api/players/me/challenges", get(get_incoming_challenges).with_timeout(read))
+        .route("/api/players/:player_id/rating", get(get_player_rating).with_timeout(read))
+        .route("/api/leaderboard", get(get_leaderboard).with_timeout(default))
+        .route("/api/stats/difficulties", get(get_difficulty_stats).with_timeout(read))
+        .route("/api/tournaments", get(list_tournaments).with_timeout(read).post(create_tournament).with_timeout(default))
+        .route("/api/tournaments/:tournament_id", get(get_tournament).with_timeout(read));
     
     base_routes
-        .layer(middleware::from_fn(cors))
         .layer(middleware::from_fn(logging))
 }
 
 pub fn create_ai_battle_router(app_state: AppState) -> Router {
-    create_ai_battle_routes(app_state.ai_battle_service)
-        .layer(middleware::from_fn(cors))
+    create_ai_battle_router_with_timeouts(app_state, &RouteTimeouts::default())
+}
+
+pub fn create_ai_battle_router_with_timeouts(app_state: AppState, timeouts: &RouteTimeouts) -> Router {
+    create_ai_battle_routes(app_state.ai_battle_service, timeouts)
         .layer(middleware::from_fn(logging))
 }
 
-async fn health_check() -> &'static str {
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "system",
+    responses((status = 200, description = "稼働状態", body = String, content_type = "text/plain"))
+)]
+pub async fn health_check() -> &'static str {
     "Reversi API Server is running"
 }
 
@@ -0,0 +1,18 @@
+//! 統計APIモジュール
+//! 終局した人間対AIの対局をAIの難易度ごとに集計した `/api/stats/difficulties` を提供する。
+
+use axum::{extract::State, response::Json};
+
+use crate::stats::DifficultyStatsResponse;
+
+use super::handlers::AppState;
+
+#[utoipa::path(
+    get,
+    path = "/api/stats/difficulties",
+    tag = "ai-battle",
+    responses((status = 200, description = "難易度ごとの人間の勝率・平均手数・AIの平均思考時間（サーバー起動後に終局した対局が対象）", body = DifficultyStatsResponse))
+)]
+pub async fn get_difficulty_stats(State(state): State<AppState>) -> Json<DifficultyStatsResponse> {
+    Json(state.ai_battle_service.difficulty_stats())
+}
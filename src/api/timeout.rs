@@ -0,0 +1,85 @@
+//! ルート別タイムアウトモジュール
+//! towerの `TimeoutLayer` をルートごとに重ね、設定された上限を超えたリクエストを打ち切る。
+//! 打ち切ったリクエストには `REQUEST_TIMEOUT` のエラーコードを持つ504を返す。
+
+use std::time::Duration;
+
+use axum::{
+    error_handling::HandleErrorLayer,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::MethodRouter,
+    BoxError, Json,
+};
+use tower::{
+    timeout::{error::Elapsed, TimeoutLayer},
+    ServiceBuilder,
+};
+
+use super::ai_battle::dto::{AiBattleError, ErrorResponse};
+
+/// ルートにタイムアウトを設定する拡張
+pub trait WithTimeout {
+    /// 処理が `budget` を超えた場合に504を返すようにする
+    fn with_timeout(self, budget: Duration) -> Self;
+}
+
+impl<S> WithTimeout for MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn with_timeout(self, budget: Duration) -> Self {
+        self.layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(move |err: BoxError| async move { timeout_error(err, budget) }))
+                .layer(TimeoutLayer::new(budget)),
+        )
+    }
+}
+
+fn timeout_error(err: BoxError, budget: Duration) -> Response {
+    let err = if err.is::<Elapsed>() {
+        AiBattleError::RequestTimeout { budget_ms: budget.as_millis() as u64 }
+    } else {
+        AiBattleError::InternalError { details: err.to_string() }
+    };
+    let (status, body): (StatusCode, Json<ErrorResponse>) = err.into();
+    (status, body).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn router() -> Router {
+        Router::new()
+            .route("/fast", get(|| async { "ok" }).with_timeout(Duration::from_millis(200)))
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "late"
+                })
+                .with_timeout(Duration::from_millis(20)),
+            )
+    }
+
+    #[tokio::test]
+    async fn test_requests_within_budget_pass_through() {
+        let response = router().oneshot(Request::get("/fast").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_slow_requests_get_structured_504() {
+        let response = router().oneshot(Request::get("/slow").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error["error_code"], "REQUEST_TIMEOUT");
+        assert!(error["message"].as_str().unwrap().contains("20ms"));
+    }
+}
@@ -0,0 +1,61 @@
+//! 大会APIモジュール
+//! AIエンジン同士の総当たり戦を始める `POST /api/tournaments` と、
+//! その進行状況と順位表を返す `/api/tournaments`・`/api/tournaments/:tournament_id` を提供する。
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use uuid::Uuid;
+
+use crate::tournament::{CreateTournamentRequest, TournamentListResponse, TournamentResponse};
+
+use super::ai_battle::dto::AiBattleResult;
+use super::handlers::AppState;
+use super::json::JsonBody;
+
+#[utoipa::path(
+    post,
+    path = "/api/tournaments",
+    tag = "tournaments",
+    request_body = CreateTournamentRequest,
+    responses(
+        (status = 202, description = "大会を登録し、バックグラウンドで対局を開始", body = TournamentResponse),
+        (status = 400, description = "参加エンジンや対局数の指定が不正、またはエンジンを作成できない", body = ErrorResponse),
+    )
+)]
+pub async fn create_tournament(
+    State(state): State<AppState>,
+    JsonBody(request): JsonBody<CreateTournamentRequest>,
+) -> AiBattleResult<(StatusCode, Json<TournamentResponse>)> {
+    let response = state.ai_battle_service.tournaments().start(request)?;
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tournaments",
+    tag = "tournaments",
+    responses((status = 200, description = "大会の一覧（作成の新しい順）", body = TournamentListResponse))
+)]
+pub async fn list_tournaments(State(state): State<AppState>) -> Json<TournamentListResponse> {
+    Json(TournamentListResponse { tournaments: state.ai_battle_service.tournaments().list() })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tournaments/{tournament_id}",
+    tag = "tournaments",
+    params(("tournament_id" = Uuid, Path, description = "大会ID")),
+    responses(
+        (status = 200, description = "大会の進行状況・順位表・対局結果", body = TournamentResponse),
+        (status = 404, description = "大会が存在しない", body = ErrorResponse),
+    )
+)]
+pub async fn get_tournament(
+    State(state): State<AppState>,
+    Path(tournament_id): Path<Uuid>,
+) -> AiBattleResult<Json<TournamentResponse>> {
+    Ok(Json(state.ai_battle_service.tournaments().get(tournament_id)?))
+}
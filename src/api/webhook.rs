@@ -0,0 +1,185 @@
+//! Webhook配信モジュール
+//! 通知を外部のHTTPエンドポイントへJSONでPOSTする。
+//! 通信対局の催促をメールやチャットへ中継する用途を想定しており、メールの送信は受信側で行う。
+//! HTTPクライアントへの依存を増やさないため、平文のHTTP/1.1のみに対応する。
+
+use serde::Serialize;
+use std::fmt;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use uuid::Uuid;
+
+use super::notifications::{Notification, NotificationKind, NotificationSink};
+
+/// 1回の配信にかける時間の上限
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 配信先のURL（`http://host[:port][/path]`）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookUrl {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl WebhookUrl {
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("http:// で始まるURLを指定してください: {}", url))?;
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = port.parse().map_err(|_| format!("ポート番号が不正です: {}", url))?;
+                (host, port)
+            }
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(format!("ホスト名がありません: {}", url));
+        }
+
+        Ok(Self { host: host.to_string(), port, path: path.to_string() })
+    }
+}
+
+impl fmt::Display for WebhookUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "http://{}:{}{}", self.host, self.port, self.path)
+    }
+}
+
+/// 配信するJSON
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    player_id: Uuid,
+    notification: &'a Notification,
+}
+
+/// 指定した種類の通知をWebhookで配信する
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    url: WebhookUrl,
+    kinds: Vec<NotificationKind>,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str, kinds: Vec<NotificationKind>) -> Result<Self, String> {
+        Ok(Self { url: WebhookUrl::parse(url)?, kinds })
+    }
+}
+
+impl NotificationSink for WebhookSink {
+    /// 配信はバックグラウンドで行い、失敗してもリトライしない
+    fn deliver(&self, player_id: Uuid, notification: &Notification) {
+        if !self.kinds.contains(&notification.kind) {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let Ok(body) = serde_json::to_vec(&WebhookPayload { player_id, notification }) else {
+            return;
+        };
+
+        let url = self.url.clone();
+        runtime.spawn(async move {
+            match tokio::time::timeout(REQUEST_TIMEOUT, post_json(&url, &body)).await {
+                Ok(Ok(status)) if (200..300).contains(&status) => {}
+                Ok(Ok(status)) => eprintln!("Webhook配信失敗 {}: HTTP {}", url, status),
+                Ok(Err(e)) => eprintln!("Webhook配信失敗 {}: {}", url, e),
+                Err(_) => eprintln!("Webhook配信失敗 {}: タイムアウト", url),
+            }
+        });
+    }
+}
+
+/// JSONをPOSTし、レスポンスのステータスコードを返す
+async fn post_json(url: &WebhookUrl, body: &[u8]) -> std::io::Result<u16> {
+    let mut stream = TcpStream::connect((url.host.as_str(), url.port)).await?;
+    let head = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        url.path,
+        url.host,
+        body.len(),
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body).await?;
+
+    // ステータス行だけを読む
+    let mut response = Vec::new();
+    let mut buffer = [0u8; 256];
+    while !response.windows(2).any(|window| window == b"\r\n") {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        response.extend_from_slice(&buffer[..read]);
+    }
+
+    let status_line = String::from_utf8_lossy(&response);
+    status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "不正なHTTPレスポンス"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_parse_url() {
+        let url = WebhookUrl::parse("http://hooks.example:8080/reversi/remind").unwrap();
+        assert_eq!(url.to_string(), "http://hooks.example:8080/reversi/remind");
+        assert_eq!(WebhookUrl::parse("http://hooks.example").unwrap().to_string(), "http://hooks.example:80/");
+
+        assert!(WebhookUrl::parse("https://hooks.example").is_err());
+        assert!(WebhookUrl::parse("http://hooks.example:port").is_err());
+        assert!(WebhookUrl::parse("http://:80/").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_delivers_selected_kinds() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        let sink = WebhookSink::new(&url, vec![NotificationKind::TurnReminder]).unwrap();
+        let player = Uuid::new_v4();
+
+        let notification = |kind| Notification {
+            id: Uuid::new_v4(),
+            kind,
+            game_id: None,
+            message: "message".to_string(),
+            count: 1,
+            read: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        sink.deliver(player, &notification(NotificationKind::OpponentMoved));
+        sink.deliver(player, &notification(NotificationKind::TurnReminder));
+
+        let (mut socket, _) = tokio::time::timeout(REQUEST_TIMEOUT, listener.accept()).await.unwrap().unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 1024];
+        while !request.ends_with(b"}}") {
+            let read = socket.read(&mut buffer).await.unwrap();
+            assert!(read > 0, "connection closed before the body was sent");
+            request.extend_from_slice(&buffer[..read]);
+        }
+        socket.write_all(b"HTTP/1.1 204 No Content\r\n\r\n").await.unwrap();
+
+        let request = String::from_utf8(request).unwrap();
+        assert!(request.starts_with("POST /hooks HTTP/1.1\r\n"));
+        assert!(request.contains(&player.to_string()));
+        assert!(request.contains("\"kind\":\"turn_reminder\""));
+        assert!(!request.contains("opponent_moved"));
+    }
+}
@@ -0,0 +1,714 @@
+//! 対局アーカイブモジュール
+//! 終局した対局の記録（棋譜、難易度、結果、日時）を保存し、条件を指定して検索できるようにする。
+//! セッションは非アクティブになるとクリーンアップで削除されるため、終局時点の記録をここに残す。
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use sqlx::{QueryBuilder, Row, Sqlite};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::api::ai_battle::{
+    initial_game_state, AiBattleError, AiBattleSession, AiDifficulty, GameStatus, Handicap, MoveRecord, SessionKind,
+    StartPosition,
+};
+use crate::config::DatabaseConfig;
+use crate::error::PersistenceError;
+use crate::game::{GameState, GameVariant, Player, DEFAULT_BOARD_SIZE};
+use crate::persistence::{database_error, SqliteSessionStore};
+use crate::replay::ENGINE_VERSION;
+use crate::session::SessionStoreBackend;
+use crate::wthor::WthorGame;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS archived_games (
+    game_id TEXT PRIMARY KEY,
+    result TEXT NOT NULL,
+    difficulty TEXT NOT NULL,
+    finished_at TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS archived_games_finished_at ON archived_games (finished_at);
+CREATE TABLE IF NOT EXISTS archived_game_players (
+    game_id TEXT NOT NULL,
+    player_id TEXT NOT NULL,
+    opponent TEXT,
+    outcome TEXT NOT NULL,
+    finished_at TEXT NOT NULL,
+    PRIMARY KEY (game_id, player_id)
+);
+CREATE INDEX IF NOT EXISTS archived_game_players_finished_at ON archived_game_players (finished_at);
+"#;
+
+/// 対局の結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GameResult {
+    BlackWin,
+    WhiteWin,
+    Draw,
+}
+
+impl GameResult {
+    pub fn from_winner(winner: Option<Player>) -> Self {
+        match winner {
+            Some(Player::Black) => GameResult::BlackWin,
+            Some(Player::White) => GameResult::WhiteWin,
+            None => GameResult::Draw,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GameResult::BlackWin => "black_win",
+            GameResult::WhiteWin => "white_win",
+            GameResult::Draw => "draw",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "black_win" | "black" => Some(GameResult::BlackWin),
+            "white_win" | "white" => Some(GameResult::WhiteWin),
+            "draw" => Some(GameResult::Draw),
+            _ => None,
+        }
+    }
+
+    /// 指定した色から見た結果
+    pub fn outcome_for(&self, player: Player) -> Outcome {
+        match (self, player) {
+            (GameResult::Draw, _) => Outcome::Draw,
+            (GameResult::BlackWin, Player::Black) | (GameResult::WhiteWin, Player::White) => Outcome::Win,
+            _ => Outcome::Loss,
+        }
+    }
+}
+
+/// 対局者から見た結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Win => "win",
+            Outcome::Loss => "loss",
+            Outcome::Draw => "draw",
+        }
+    }
+}
+
+/// 対局者の1局分の参加記録
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Participation {
+    pub player_id: Uuid,
+    /// 相手のAIの難易度（対人戦はNone）
+    pub opponent: Option<AiDifficulty>,
+    pub outcome: Outcome,
+}
+
+/// プレイヤーと相手の組ごとの成績の集計
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerTally {
+    pub player_id: Uuid,
+    /// 相手のAIの難易度（対人戦はNone）
+    pub opponent: Option<AiDifficulty>,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+impl PlayerTally {
+    fn new(player_id: Uuid, opponent: Option<AiDifficulty>) -> Self {
+        Self { player_id, opponent, wins: 0, losses: 0, draws: 0 }
+    }
+
+    pub fn games(&self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+
+    fn record(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Win => self.wins += 1,
+            Outcome::Loss => self.losses += 1,
+            Outcome::Draw => self.draws += 1,
+        }
+    }
+}
+
+/// アーカイブされた対局の記録
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArchivedGame {
+    pub game_id: Uuid,
+    pub kind: SessionKind,
+    pub difficulty: AiDifficulty,
+    pub result: GameResult,
+    pub black_count: u8,
+    pub white_count: u8,
+    /// 盤面の一辺のマス数（記録のない古い対局は8）
+    #[serde(default = "default_board_size")]
+    pub board_size: usize,
+    /// 対局のルール（記録のない古い対局は通常のオセロ）
+    #[serde(default)]
+    pub variant: GameVariant,
+    /// 置き石のハンデ（なければnull）
+    #[serde(default)]
+    pub handicap: Option<Handicap>,
+    /// 任意の局面から始めた対局の開始局面（初期配置から始めた場合はnull）
+    #[serde(default)]
+    pub start_position: Option<StartPosition>,
+    /// 時間切れで決着した対局か
+    pub time_forfeit: bool,
+    pub rated: bool,
+    /// 黒番のプレイヤーID（AI、または分からない場合はnull）
+    #[serde(default)]
+    pub black_player: Option<Uuid>,
+    /// 白番のプレイヤーID（AI、または分からない場合はnull）
+    #[serde(default)]
+    pub white_player: Option<Uuid>,
+    /// パスを含む着手順の全記録
+    pub moves: Vec<MoveRecord>,
+    /// AIの乱択・揺らぎのシード（記録のない古い対局ではnull）
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// 対局したエンジンのバージョン（記録のない古い対局ではnull）
+    #[serde(default)]
+    pub engine_version: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+}
+
+fn default_board_size() -> usize {
+    DEFAULT_BOARD_SIZE
+}
+
+impl ArchivedGame {
+    /// 終局したセッションの記録を作る（終局していなければNone）
+    pub fn from_session(session: &AiBattleSession) -> Option<Self> {
+        let GameStatus::Finished { winner } = session.status else {
+            return None;
+        };
+        let (black_count, white_count) = session.game_state.get_score();
+
+        Some(Self {
+            game_id: session.id,
+            kind: session.kind(),
+            difficulty: session.ai_difficulty,
+            result: GameResult::from_winner(winner),
+            black_count,
+            white_count,
+            board_size: session.game_state.board_size(),
+            variant: session.variant(),
+            handicap: session.handicap,
+            start_position: session.start_position.clone(),
+            time_forfeit: session.lost_on_time().is_some(),
+            rated: session.rated,
+            black_player: session.player_id(Player::Black),
+            white_player: session.player_id(Player::White),
+            moves: session.transcript_records(),
+            seed: session.seed,
+            engine_version: Some(ENGINE_VERSION.to_string()),
+            created_at: session.created_at,
+            finished_at: session.last_move_at,
+        })
+    }
+
+    /// 対局開始時の状態（対応していない盤面の大きさや不正な開始局面の場合はNone）
+    pub fn initial_state(&self) -> Option<GameState> {
+        initial_game_state(
+            self.board_size,
+            self.variant,
+            self.handicap,
+            self.start_position.as_ref(),
+            self.seed.unwrap_or_default(),
+        )
+    }
+
+    /// WTHOR形式の対局レコードにする（空きマスは勝った側に数える）
+    /// 8x8の盤で初期配置から始めた通常のオセロの対局以外はNone
+    pub fn to_wthor(&self) -> Option<WthorGame> {
+        if self.board_size != DEFAULT_BOARD_SIZE
+            || self.variant != GameVariant::Standard
+            || self.handicap.is_some()
+            || self.start_position.is_some()
+        {
+            return None;
+        }
+        let empties = 64u8.saturating_sub(self.black_count + self.white_count);
+        let black_discs = match self.result {
+            GameResult::BlackWin => self.black_count + empties,
+            GameResult::WhiteWin => self.black_count,
+            GameResult::Draw => self.black_count + empties / 2,
+        };
+        Some(WthorGame {
+            tournament: 0,
+            black_player: 0,
+            white_player: 0,
+            black_discs,
+            theoretical_black_discs: black_discs,
+            moves: self.moves.iter().filter_map(|record| record.position).collect(),
+        })
+    }
+
+    /// プレイヤーIDの分かる対局者の参加記録（同じプレイヤー同士の対局は集計しない）
+    pub fn participants(&self) -> Vec<Participation> {
+        if self.black_player.is_some() && self.black_player == self.white_player {
+            return Vec::new();
+        }
+        let opponent = (self.kind == SessionKind::HumanVsAi).then_some(self.difficulty);
+        [(Player::Black, self.black_player), (Player::White, self.white_player)]
+            .into_iter()
+            .filter_map(|(color, player_id)| {
+                Some(Participation { player_id: player_id?, opponent, outcome: self.result.outcome_for(color) })
+            })
+            .collect()
+    }
+}
+
+/// 参加記録をプレイヤーと相手の組ごとに集計する
+pub fn tally<'a>(games: impl IntoIterator<Item = &'a ArchivedGame>) -> Vec<PlayerTally> {
+    let mut tallies: HashMap<(Uuid, Option<AiDifficulty>), PlayerTally> = HashMap::new();
+    for participation in games.into_iter().flat_map(ArchivedGame::participants) {
+        tallies
+            .entry((participation.player_id, participation.opponent))
+            .or_insert_with(|| PlayerTally::new(participation.player_id, participation.opponent))
+            .record(participation.outcome);
+    }
+    tallies.into_values().collect()
+}
+
+/// アーカイブ検索のクエリパラメータ
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct ArchiveQuery {
+    /// `black_win` / `white_win` / `draw`
+    pub result: Option<String>,
+    /// AIの難易度
+    pub difficulty: Option<String>,
+    /// この日時以降に終局した対局（RFC 3339 または YYYY-MM-DD）
+    pub from: Option<String>,
+    /// この日時より前に終局した対局（YYYY-MM-DD の場合はその日を含む）
+    pub to: Option<String>,
+}
+
+/// 解釈済みの検索条件
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArchiveFilter {
+    pub result: Option<GameResult>,
+    pub difficulty: Option<AiDifficulty>,
+    pub from: Option<DateTime<Utc>>,
+    /// この日時を含まない
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl ArchiveQuery {
+    pub fn parse(&self) -> Result<ArchiveFilter, AiBattleError> {
+        let bad_request = |details: String| AiBattleError::BadRequest { details };
+
+        let result = self.result
+            .as_deref()
+            .map(|value| GameResult::parse(value).ok_or_else(|| bad_request(format!("不明な結果です: {}", value))))
+            .transpose()?;
+        let difficulty = self.difficulty
+            .as_deref()
+            .map(|value| value.parse::<AiDifficulty>().map_err(|details| AiBattleError::InvalidDifficulty { difficulty: details }))
+            .transpose()?;
+        let from = self.from
+            .as_deref()
+            .map(|value| parse_date(value, false).ok_or_else(|| bad_request(format!("日時を解釈できません: {}", value))))
+            .transpose()?;
+        let until = self.to
+            .as_deref()
+            .map(|value| parse_date(value, true).ok_or_else(|| bad_request(format!("日時を解釈できません: {}", value))))
+            .transpose()?;
+
+        if let (Some(from), Some(until)) = (from, until) {
+            if from >= until {
+                return Err(bad_request("from は to より前の日時を指定してください".to_string()));
+            }
+        }
+
+        Ok(ArchiveFilter { result, difficulty, from, until })
+    }
+}
+
+/// 日付のみの指定は、開始はその日の0時、終了は翌日の0時として扱う
+fn parse_date(value: &str, end_of_range: bool) -> Option<DateTime<Utc>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    let start = date.and_hms_opt(0, 0, 0)?.and_utc();
+    Some(if end_of_range { start + Duration::days(1) } else { start })
+}
+
+impl ArchiveFilter {
+    pub fn matches(&self, game: &ArchivedGame) -> bool {
+        self.result.is_none_or(|result| game.result == result)
+            && self.difficulty.is_none_or(|difficulty| game.difficulty == difficulty)
+            && self.from.is_none_or(|from| game.finished_at >= from)
+            && self.until.is_none_or(|until| game.finished_at < until)
+    }
+}
+
+/// 対局アーカイブの統一インターフェース
+#[async_trait]
+pub trait GameArchive: Send + Sync + fmt::Debug {
+    fn backend_name(&self) -> &'static str;
+
+    /// 対局を記録する（同じ対局は置き換える）
+    /// 終局を処理する同期的な経路から呼ばれるため、書き込みは後から反映してよい
+    fn record(&self, game: ArchivedGame);
+
+    /// それまでに受け付けた記録が反映されるまで待つ
+    async fn flush(&self);
+
+    /// 条件に合う対局を終局の新しい順に返す
+    async fn query(&self, filter: &ArchiveFilter) -> Result<Vec<ArchivedGame>, PersistenceError>;
+
+    /// 対局IDで1局を取り出す
+    async fn get(&self, game_id: Uuid) -> Result<Option<ArchivedGame>, PersistenceError>;
+
+    /// 指定した日時以降に終局した対局の成績をプレイヤーと相手の組ごとに集計する
+    async fn tally_players(&self, since: Option<DateTime<Utc>>) -> Result<Vec<PlayerTally>, PersistenceError>;
+}
+
+/// メモリ上のアーカイブ（再起動で失われる）
+#[derive(Debug, Default)]
+pub struct MemoryGameArchive {
+    games: DashMap<Uuid, ArchivedGame>,
+}
+
+#[async_trait]
+impl GameArchive for MemoryGameArchive {
+    fn backend_name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn record(&self, game: ArchivedGame) {
+        self.games.insert(game.game_id, game);
+    }
+
+    async fn flush(&self) {}
+
+    async fn query(&self, filter: &ArchiveFilter) -> Result<Vec<ArchivedGame>, PersistenceError> {
+        let mut games: Vec<ArchivedGame> = self.games
+            .iter()
+            .filter(|entry| filter.matches(entry.value()))
+            .map(|entry| entry.value().clone())
+            .collect();
+        games.sort_by_key(|game| std::cmp::Reverse(game.finished_at));
+        Ok(games)
+    }
+
+    async fn get(&self, game_id: Uuid) -> Result<Option<ArchivedGame>, PersistenceError> {
+        Ok(self.games.get(&game_id).map(|entry| entry.value().clone()))
+    }
+
+    async fn tally_players(&self, since: Option<DateTime<Utc>>) -> Result<Vec<PlayerTally>, PersistenceError> {
+        let games: Vec<ArchivedGame> = self.games
+            .iter()
+            .filter(|entry| since.is_none_or(|since| entry.value().finished_at >= since))
+            .map(|entry| entry.value().clone())
+            .collect();
+        Ok(tally(&games))
+    }
+}
+
+enum ArchiveCommand {
+    Record(Box<ArchivedGame>),
+    Flush(oneshot::Sender<()>),
+}
+
+/// SQLiteのアーカイブ
+/// 記録は専用タスクが受け付け順に書き込む
+#[derive(Debug, Clone)]
+pub struct SqliteGameArchive {
+    pool: SqlitePool,
+    sender: mpsc::UnboundedSender<ArchiveCommand>,
+}
+
+impl SqliteGameArchive {
+    /// セッションストアと同じデータベースにアーカイブのテーブルを作成する
+    pub async fn open(store: &SqliteSessionStore) -> Result<Self, PersistenceError> {
+        let pool = store.pool().clone();
+        sqlx::raw_sql(SCHEMA).execute(&pool).await.map_err(database_error)?;
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let writer = pool.clone();
+        tokio::spawn(async move {
+            while let Some(command) = receiver.recv().await {
+                match command {
+                    ArchiveCommand::Record(game) => {
+                        if let Err(e) = Self::insert(&writer, &game).await {
+                            eprintln!("対局のアーカイブに失敗: {}", e);
+                        }
+                    }
+                    ArchiveCommand::Flush(done) => {
+                        let _ = done.send(());
+                    }
+                }
+            }
+        });
+
+        Ok(Self { pool, sender })
+    }
+
+    async fn insert(pool: &SqlitePool, game: &ArchivedGame) -> Result<(), PersistenceError> {
+        let data = serde_json::to_string(game)
+            .map_err(|e| PersistenceError::SerializationError { message: e.to_string() })?;
+        let mut tx = pool.begin().await.map_err(database_error)?;
+        sqlx::query(
+            "INSERT INTO archived_games (game_id, result, difficulty, finished_at, data) VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(game_id) DO UPDATE SET result = excluded.result, difficulty = excluded.difficulty,
+             finished_at = excluded.finished_at, data = excluded.data",
+        )
+        .bind(game.game_id.to_string())
+        .bind(game.result.as_str())
+        .bind(game.difficulty.name())
+        .bind(game.finished_at)
+        .bind(data)
+        .execute(&mut *tx)
+        .await.map_err(database_error)?;
+
+        // 成績の集計用に対局者ごとの結果を持つ
+        sqlx::query("DELETE FROM archived_game_players WHERE game_id = ?")
+            .bind(game.game_id.to_string())
+            .execute(&mut *tx)
+            .await.map_err(database_error)?;
+        for participation in game.participants() {
+            sqlx::query(
+                "INSERT INTO archived_game_players (game_id, player_id, opponent, outcome, finished_at) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(game.game_id.to_string())
+            .bind(participation.player_id.to_string())
+            .bind(participation.opponent.map(|difficulty| difficulty.name()))
+            .bind(participation.outcome.as_str())
+            .bind(game.finished_at)
+            .execute(&mut *tx)
+            .await.map_err(database_error)?;
+        }
+        tx.commit().await.map_err(database_error)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl GameArchive for SqliteGameArchive {
+    fn backend_name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn record(&self, game: ArchivedGame) {
+        let _ = self.sender.send(ArchiveCommand::Record(Box::new(game)));
+    }
+
+    async fn flush(&self) {
+        let (done, wait) = oneshot::channel();
+        if self.sender.send(ArchiveCommand::Flush(done)).is_ok() {
+            let _ = wait.await;
+        }
+    }
+
+    async fn query(&self, filter: &ArchiveFilter) -> Result<Vec<ArchivedGame>, PersistenceError> {
+        let mut query: QueryBuilder<Sqlite> = QueryBuilder::new("SELECT data FROM archived_games WHERE 1 = 1");
+        if let Some(result) = filter.result {
+            query.push(" AND result = ").push_bind(result.as_str());
+        }
+        if let Some(difficulty) = filter.difficulty {
+            query.push(" AND difficulty = ").push_bind(difficulty.name());
+        }
+        if let Some(from) = filter.from {
+            query.push(" AND finished_at >= ").push_bind(from);
+        }
+        if let Some(until) = filter.until {
+            query.push(" AND finished_at < ").push_bind(until);
+        }
+        query.push(" ORDER BY finished_at DESC");
+
+        let rows = query.build().fetch_all(&self.pool).await.map_err(database_error)?;
+        rows.into_iter()
+            .map(|row| {
+                serde_json::from_str(row.get("data"))
+                    .map_err(|e| PersistenceError::SerializationError { message: e.to_string() })
+            })
+            .collect()
+    }
+
+    async fn get(&self, game_id: Uuid) -> Result<Option<ArchivedGame>, PersistenceError> {
+        let row = sqlx::query("SELECT data FROM archived_games WHERE game_id = ?")
+            .bind(game_id.to_string())
+            .fetch_optional(&self.pool)
+            .await.map_err(database_error)?;
+        row.map(|row| {
+            serde_json::from_str(row.get("data"))
+                .map_err(|e| PersistenceError::SerializationError { message: e.to_string() })
+        })
+        .transpose()
+    }
+
+    async fn tally_players(&self, since: Option<DateTime<Utc>>) -> Result<Vec<PlayerTally>, PersistenceError> {
+        let mut query: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "SELECT player_id, opponent, SUM(outcome = 'win') AS wins, SUM(outcome = 'loss') AS losses,
+             SUM(outcome = 'draw') AS draws FROM archived_game_players",
+        );
+        if let Some(since) = since {
+            query.push(" WHERE finished_at >= ").push_bind(since);
+        }
+        query.push(" GROUP BY player_id, opponent");
+
+        let rows = query.build().fetch_all(&self.pool).await.map_err(database_error)?;
+        let invalid = |message: String| PersistenceError::SerializationError { message };
+        rows.into_iter()
+            .map(|row| {
+                let player_id: String = row.get("player_id");
+                let opponent: Option<String> = row.get("opponent");
+                let count = |column: &str| row.get::<i64, _>(column) as u32;
+                Ok(PlayerTally {
+                    player_id: Uuid::parse_str(&player_id).map_err(|e| invalid(e.to_string()))?,
+                    opponent: opponent.map(|name| name.parse::<AiDifficulty>()).transpose().map_err(invalid)?,
+                    wins: count("wins"),
+                    losses: count("losses"),
+                    draws: count("draws"),
+                })
+            })
+            .collect()
+    }
+}
+
+/// 設定で選択された保存先のアーカイブを開く
+pub async fn open_game_archive(config: &DatabaseConfig) -> Result<Arc<dyn GameArchive>, PersistenceError> {
+    match config.session_store {
+        SessionStoreBackend::Memory => Ok(Arc::new(MemoryGameArchive::default())),
+        SessionStoreBackend::Sqlite => {
+            let store = SqliteSessionStore::connect(config).await?;
+            Ok(Arc::new(SqliteGameArchive::open(&store).await?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ReversiRules;
+
+    fn finished_game(winner: Option<Player>, difficulty: AiDifficulty, finished_at: DateTime<Utc>) -> ArchivedGame {
+        let mut session = AiBattleSession::new(difficulty);
+        let position = ReversiRules::get_valid_moves(&session.game_state.board, Player::Black)[0];
+        ReversiRules::apply_move(&mut session.game_state, position).unwrap();
+        session.record_placement();
+        session.status = GameStatus::Finished { winner };
+        session.last_move_at = finished_at;
+        ArchivedGame::from_session(&session).unwrap()
+    }
+
+    fn query(result: Option<&str>, difficulty: Option<&str>, from: Option<&str>, to: Option<&str>) -> ArchiveQuery {
+        ArchiveQuery {
+            result: result.map(str::to_string),
+            difficulty: difficulty.map(str::to_string),
+            from: from.map(str::to_string),
+            to: to.map(str::to_string),
+        }
+    }
+
+    async fn assert_filters(archive: &dyn GameArchive) {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2026, 5, d).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc();
+        let won = finished_game(Some(Player::Black), AiDifficulty::Hard, day(1));
+        let lost = finished_game(Some(Player::White), AiDifficulty::Easy, day(2));
+        let drawn = finished_game(None, AiDifficulty::Hard, day(3));
+        for game in [&won, &lost, &drawn] {
+            archive.record(game.clone());
+        }
+        archive.flush().await;
+
+        let ids = |games: Vec<ArchivedGame>| games.into_iter().map(|game| game.game_id).collect::<Vec<_>>();
+        let all = archive.query(&ArchiveFilter::default()).await.unwrap();
+        assert_eq!(ids(all.clone()), vec![drawn.game_id, lost.game_id, won.game_id]);
+        assert_eq!(all[2].moves.len(), 1);
+        assert_eq!(all[2].moves[0].seq, 1);
+
+        let filter = query(Some("black_win"), None, None, None).parse().unwrap();
+        assert_eq!(ids(archive.query(&filter).await.unwrap()), vec![won.game_id]);
+
+        let filter = query(None, Some("hard"), Some("2026-05-02"), None).parse().unwrap();
+        assert_eq!(ids(archive.query(&filter).await.unwrap()), vec![drawn.game_id]);
+
+        let filter = query(None, None, Some("2026-05-01T13:00:00Z"), Some("2026-05-02")).parse().unwrap();
+        assert_eq!(ids(archive.query(&filter).await.unwrap()), vec![lost.game_id]);
+    }
+
+    async fn assert_tally(archive: &dyn GameArchive) {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2026, 5, d).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc();
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let with_players = |mut game: ArchivedGame, kind: SessionKind, black: Option<Uuid>, white: Option<Uuid>| {
+            game.kind = kind;
+            game.black_player = black;
+            game.white_player = white;
+            game
+        };
+        for game in [
+            with_players(finished_game(Some(Player::Black), AiDifficulty::Hard, day(1)), SessionKind::HumanVsAi, Some(alice), None),
+            with_players(finished_game(None, AiDifficulty::Hard, day(2)), SessionKind::HumanVsAi, Some(alice), None),
+            with_players(finished_game(Some(Player::White), AiDifficulty::Easy, day(3)), SessionKind::HumanVsHuman, Some(alice), Some(bob)),
+        ] {
+            archive.record(game);
+        }
+        archive.flush().await;
+
+        let mut tallies = archive.tally_players(None).await.unwrap();
+        tallies.sort_by_key(|tally| (tally.player_id == bob, tally.opponent));
+        assert_eq!(tallies, vec![
+            PlayerTally { player_id: alice, opponent: None, wins: 0, losses: 1, draws: 0 },
+            PlayerTally { player_id: alice, opponent: Some(AiDifficulty::Hard), wins: 1, losses: 0, draws: 1 },
+            PlayerTally { player_id: bob, opponent: None, wins: 1, losses: 0, draws: 0 },
+        ]);
+
+        let recent = archive.tally_players(Some(day(2))).await.unwrap();
+        assert_eq!(recent.iter().map(PlayerTally::games).sum::<u32>(), 3);
+    }
+
+    #[test]
+    fn test_unfinished_sessions_are_not_archived() {
+        assert!(ArchivedGame::from_session(&AiBattleSession::new(AiDifficulty::Easy)).is_none());
+    }
+
+    #[test]
+    fn test_invalid_query_is_rejected() {
+        assert!(matches!(query(Some("win"), None, None, None).parse(), Err(AiBattleError::BadRequest { .. })));
+        assert!(matches!(query(None, Some("expert"), None, None).parse(), Err(AiBattleError::InvalidDifficulty { .. })));
+        assert!(matches!(query(None, None, Some("yesterday"), None).parse(), Err(AiBattleError::BadRequest { .. })));
+        assert!(matches!(
+            query(None, None, Some("2026-05-02"), Some("2026-05-01")).parse(),
+            Err(AiBattleError::BadRequest { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_memory_archive_filters() {
+        assert_filters(&MemoryGameArchive::default()).await;
+        assert_tally(&MemoryGameArchive::default()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_archive_filters() {
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 1,
+            ..DatabaseConfig::default()
+        };
+        let store = SqliteSessionStore::connect(&config).await.unwrap();
+        assert_filters(&SqliteGameArchive::open(&store).await.unwrap()).await;
+        assert_tally(&SqliteGameArchive::open(&store).await.unwrap()).await;
+    }
+}
@@ -0,0 +1,585 @@
+//! ターミナルでAIと対局するクライアント
+//! 既定ではAPIサーバー（`/api/ai-battle`）に接続して対局し、`--offline` ではゲームエンジンとローカルAIを直接使う。
+//! カーソルキー（またはhjkl）でマスを選び、Enter/Spaceで着手する。
+//!
+//! 使い方: `cargo run --bin tui -- [--offline] [--url http://127.0.0.1:3000] [--difficulty hard] [--color white]`
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Print, Stylize},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+use Reversi::ai::service::{AIService, AIServiceFactory};
+use Reversi::api::ai_battle::AiDifficulty;
+use Reversi::api::encoding::{self, api_player, CanonicalBoard};
+use Reversi::game::{Board, Cell, GameState, GameStatus, Player, Position, ReversiRules};
+
+const DEFAULT_URL: &str = "http://127.0.0.1:3000";
+
+/// APIのAI応手を待つ間の状態確認の間隔と回数
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+const POLL_ATTEMPTS: u32 = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Options {
+    offline: bool,
+    url: String,
+    difficulty: AiDifficulty,
+    color: Player,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            offline: false,
+            url: DEFAULT_URL.to_string(),
+            difficulty: AiDifficulty::Medium,
+            color: Player::Black,
+        }
+    }
+}
+
+const USAGE: &str = "使い方: tui [--offline] [--url http://host:port] [--difficulty easy|medium|hard] [--color black|white]";
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} の値がありません", name));
+        match arg.as_str() {
+            "--offline" => options.offline = true,
+            "--url" => options.url = value("--url")?,
+            "--difficulty" => options.difficulty = value("--difficulty")?.parse()?,
+            "--color" => {
+                options.color = match value("--color")?.to_ascii_lowercase().as_str() {
+                    "black" => Player::Black,
+                    "white" => Player::White,
+                    other => return Err(format!("色は black か white で指定してください: {}", other)),
+                }
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            other => return Err(format!("不明な引数です: {}\n{}", other, USAGE)),
+        }
+    }
+    Ok(options)
+}
+
+/// 画面に表示する対局の状態
+#[derive(Debug, Clone)]
+struct View {
+    board: Board,
+    to_move: Player,
+    valid_moves: Vec<Position>,
+    /// 手番側に合法手がなく、パスが必要な場合にtrue
+    must_pass: bool,
+    /// 終局していれば勝者（引き分けはNone）
+    finished: Option<Option<Player>>,
+}
+
+impl View {
+    fn from_state(state: &GameState) -> Self {
+        let finished = match state.game_status {
+            GameStatus::Finished { winner, .. } => Some(winner),
+            _ => None,
+        };
+        let valid_moves = if finished.is_some() {
+            Vec::new()
+        } else {
+            ReversiRules::get_valid_moves(&state.board, state.current_player)
+        };
+        Self {
+            board: state.board.clone(),
+            to_move: state.current_player,
+            must_pass: finished.is_none() && valid_moves.is_empty(),
+            valid_moves,
+            finished,
+        }
+    }
+}
+
+/// APIの対局状態（`AiBattleResponse`）のうち表示に使う部分
+#[derive(Debug, Deserialize)]
+struct RemoteState {
+    game_id: Uuid,
+    #[serde(with = "api_player::board")]
+    board: CanonicalBoard,
+    #[serde(with = "api_player")]
+    current_player: Player,
+    status: Value,
+    valid_moves: Vec<Position>,
+    must_pass: bool,
+    #[serde(default)]
+    ai_thinking: bool,
+}
+
+impl RemoteState {
+    fn view(&self) -> Result<View, String> {
+        let board = encoding::decode_board(&self.board).map_err(|e| e.to_string())?;
+        // 終局は `{"Finished": {"winner": "black" | "white" | null}}` で表される
+        let finished = self.status.get("Finished").map(|finished| match finished["winner"].as_str() {
+            Some("black") => Some(Player::Black),
+            Some("white") => Some(Player::White),
+            _ => None,
+        });
+        Ok(View {
+            board,
+            to_move: self.current_player,
+            valid_moves: self.valid_moves.clone(),
+            must_pass: self.must_pass,
+            finished,
+        })
+    }
+}
+
+/// 平文のHTTP/1.1でJSONを送受信するだけの最小限のクライアント
+#[derive(Debug, Clone)]
+struct ApiClient {
+    host: String,
+    port: u16,
+}
+
+impl ApiClient {
+    fn new(url: &str) -> Result<Self, String> {
+        let authority = url
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("http:// で始まるURLを指定してください: {}", url))?
+            .trim_end_matches('/');
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| format!("ポート番号が不正です: {}", url))?),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(format!("ホスト名がありません: {}", url));
+        }
+        Ok(Self { host: host.to_string(), port })
+    }
+
+    async fn request(&self, method: &str, path: &str, body: Option<Value>) -> Result<Value, String> {
+        let body = body.map(|body| body.to_string()).unwrap_or_default();
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port))
+            .await
+            .map_err(|e| format!("{}:{} に接続できません: {}", self.host, self.port, e))?;
+        let head = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            method,
+            path,
+            self.host,
+            body.len(),
+        );
+        stream.write_all(head.as_bytes()).await.map_err(|e| e.to_string())?;
+        stream.write_all(body.as_bytes()).await.map_err(|e| e.to_string())?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.map_err(|e| e.to_string())?;
+        let (status, body) = parse_response(&response)?;
+        let json: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+        if (200..300).contains(&status) {
+            Ok(json)
+        } else {
+            let message = json["message"].as_str().unwrap_or("エラーレスポンスを解釈できません");
+            Err(format!("HTTP {}: {}", status, message))
+        }
+    }
+}
+
+/// レスポンスをステータスコードと本文に分ける（chunked転送にも対応する）
+fn parse_response(response: &[u8]) -> Result<(u16, Vec<u8>), String> {
+    let invalid = || "不正なHTTPレスポンスです".to_string();
+    let split = response.windows(4).position(|window| window == b"\r\n\r\n").ok_or_else(invalid)?;
+    let head = String::from_utf8_lossy(&response[..split]);
+    let body = &response[split + 4..];
+
+    let status = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(invalid)?;
+    let chunked = head.lines().any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.starts_with("transfer-encoding:") && line.contains("chunked")
+    });
+    if !chunked {
+        return Ok((status, body.to_vec()));
+    }
+
+    let mut decoded = Vec::new();
+    let mut rest = body;
+    loop {
+        let line_end = rest.windows(2).position(|window| window == b"\r\n").ok_or_else(invalid)?;
+        let size_line = String::from_utf8_lossy(&rest[..line_end]);
+        let size = usize::from_str_radix(size_line.split(';').next().unwrap_or("").trim(), 16).map_err(|_| invalid())?;
+        rest = &rest[line_end + 2..];
+        if size == 0 {
+            return Ok((status, decoded));
+        }
+        let chunk = rest.get(..size).ok_or_else(invalid)?;
+        decoded.extend_from_slice(chunk);
+        rest = rest.get(size + 2..).ok_or_else(invalid)?;
+    }
+}
+
+/// 対局の進行先（APIサーバー、またはローカルのエンジン）
+enum Backend {
+    Api {
+        client: ApiClient,
+        game_id: Uuid,
+        human: Player,
+    },
+    Offline {
+        state: GameState,
+        ai: Box<dyn AIService>,
+        difficulty: AiDifficulty,
+        human: Player,
+    },
+}
+
+impl Backend {
+    async fn start(options: &Options) -> Result<(Self, View), String> {
+        if options.offline {
+            let ai = AIServiceFactory::create_fast_local().map_err(|e| e.to_string())?;
+            let mut backend = Backend::Offline {
+                state: GameState::new(),
+                ai,
+                difficulty: options.difficulty,
+                human: options.color,
+            };
+            let view = backend.play_ai_turns().await?;
+            return Ok((backend, view));
+        }
+
+        let client = ApiClient::new(&options.url)?;
+        let color = match options.color {
+            Player::Black => "black",
+            Player::White => "white",
+        };
+        let created = client
+            .request("POST", "/api/ai-battle", Some(json!({"difficulty": options.difficulty.name(), "player_color": color})))
+            .await?;
+        let remote: RemoteState = serde_json::from_value(created).map_err(|e| e.to_string())?;
+        let backend = Backend::Api { client, game_id: remote.game_id, human: options.color };
+        let view = backend.settle(remote).await?;
+        Ok((backend, view))
+    }
+
+    fn human(&self) -> Player {
+        match self {
+            Backend::Api { human, .. } | Backend::Offline { human, .. } => *human,
+        }
+    }
+
+    /// 着手し、AIの応手まで進めた状態を返す
+    async fn play(&mut self, position: Position) -> Result<View, String> {
+        match self {
+            Backend::Api { client, game_id, .. } => {
+                let path = format!("/api/ai-battle/{}/move", game_id);
+                let response = client
+                    .request("POST", &path, Some(json!({"row": position.row, "col": position.col})))
+                    .await?;
+                let remote: RemoteState = serde_json::from_value(response["game_state"].clone()).map_err(|e| e.to_string())?;
+                self.settle(remote).await
+            }
+            Backend::Offline { state, .. } => {
+                ReversiRules::apply_move(state, position).map_err(|e| e.to_string())?;
+                state.switch_player();
+                ReversiRules::handle_turn(state);
+                self.play_ai_turns().await
+            }
+        }
+    }
+
+    /// 合法手がない場合にパスする
+    async fn pass(&mut self) -> Result<View, String> {
+        match self {
+            Backend::Api { client, game_id, .. } => {
+                let path = format!("/api/ai-battle/{}/pass", game_id);
+                let response = client.request("POST", &path, Some(json!({}))).await?;
+                let remote: RemoteState = serde_json::from_value(response["game_state"].clone()).map_err(|e| e.to_string())?;
+                self.settle(remote).await
+            }
+            Backend::Offline { state, .. } => {
+                ReversiRules::handle_turn(state);
+                self.play_ai_turns().await
+            }
+        }
+    }
+
+    /// AIの手番が続く間、ローカルのAIに指させる
+    async fn play_ai_turns(&mut self) -> Result<View, String> {
+        let Backend::Offline { state, ai, difficulty, human } = self else {
+            unreachable!("オフライン対局でのみ呼ばれる");
+        };
+        while !state.is_finished() && state.current_player != *human {
+            if !ReversiRules::has_valid_moves(&state.board, state.current_player) {
+                ReversiRules::handle_turn(state);
+                continue;
+            }
+            let result = ai.calculate_move(state, *difficulty).await.map_err(|e| e.to_string())?;
+            ReversiRules::apply_move(state, result.position).map_err(|e| e.to_string())?;
+            state.switch_player();
+            ReversiRules::handle_turn(state);
+        }
+        Ok(View::from_state(state))
+    }
+
+    /// AIが思考中であれば、人間の手番か終局になるまで状態を取り直す
+    async fn settle(&self, mut remote: RemoteState) -> Result<View, String> {
+        let Backend::Api { client, game_id, human } = self else {
+            unreachable!("API対局でのみ呼ばれる");
+        };
+        let path = format!("/api/ai-battle/{}", game_id);
+        for _ in 0..POLL_ATTEMPTS {
+            let view = remote.view()?;
+            if view.finished.is_some() || (view.to_move == *human && !remote.ai_thinking) {
+                return Ok(view);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+            remote = serde_json::from_value(client.request("GET", &path, None).await?).map_err(|e| e.to_string())?;
+        }
+        remote.view()
+    }
+}
+
+/// 盤面上のカーソル
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cursor {
+    row: usize,
+    col: usize,
+}
+
+impl Cursor {
+    fn moved(self, key: KeyCode) -> Self {
+        let (row, col) = match key {
+            KeyCode::Up | KeyCode::Char('k') => (self.row.saturating_sub(1), self.col),
+            KeyCode::Down | KeyCode::Char('j') => ((self.row + 1).min(7), self.col),
+            KeyCode::Left | KeyCode::Char('h') => (self.row, self.col.saturating_sub(1)),
+            KeyCode::Right | KeyCode::Char('l') => (self.row, (self.col + 1).min(7)),
+            _ => (self.row, self.col),
+        };
+        Self { row, col }
+    }
+
+    fn position(self) -> Position {
+        Position::new(self.row, self.col).expect("カーソルは盤面内に限られる")
+    }
+}
+
+fn player_name(player: Player) -> &'static str {
+    match player {
+        Player::Black => "黒 ●",
+        Player::White => "白 ○",
+    }
+}
+
+fn result_line(view: &View, human: Player) -> String {
+    let (black, white) = view.board.count_pieces();
+    let outcome = match view.finished {
+        Some(Some(winner)) if winner == human => "あなたの勝ちです",
+        Some(Some(_)) => "AIの勝ちです",
+        Some(None) => "引き分けです",
+        None => "対局中です",
+    };
+    format!("黒 {} - {} 白  {}", black, white, outcome)
+}
+
+/// `Board::display` と同じ並びで盤面を描き、カーソルと合法手を重ねる
+fn draw(out: &mut impl Write, view: &View, cursor: Cursor, human: Player, message: &str) -> io::Result<()> {
+    queue!(out, Clear(ClearType::All), MoveTo(0, 0), Print(format!("リバーシ  あなた: {}", player_name(human))))?;
+    queue!(out, MoveTo(0, 2), Print("  0 1 2 3 4 5 6 7"))?;
+    let show_moves = view.finished.is_none() && view.to_move == human;
+    for row in 0..8 {
+        queue!(out, MoveTo(0, 3 + row as u16), Print(format!("{} ", row)))?;
+        for col in 0..8 {
+            let position = Position::new(row, col).expect("盤面内の座標");
+            let symbol = match view.board.get_cell(position) {
+                Some(Cell::Black) => "●",
+                Some(Cell::White) => "○",
+                _ if show_moves && view.valid_moves.contains(&position) => "*",
+                _ => ".",
+            };
+            if (Cursor { row, col }) == cursor {
+                queue!(out, Print(symbol.reverse()), Print(" "))?;
+            } else {
+                queue!(out, Print(format!("{} ", symbol)))?;
+            }
+        }
+    }
+
+    let (black, white) = view.board.count_pieces();
+    let status = match view.finished {
+        Some(_) => result_line(view, human),
+        None if view.to_move == human && view.must_pass => "合法手がありません。pでパスしてください".to_string(),
+        None if view.to_move == human => format!("あなたの手番です（{}）", player_name(human)),
+        None => "AIの手番です".to_string(),
+    };
+    queue!(
+        out,
+        MoveTo(0, 12),
+        Print(format!("黒 {}  白 {}", black, white)),
+        MoveTo(0, 13),
+        Print(status),
+        MoveTo(0, 14),
+        Print(message),
+        MoveTo(0, 16),
+        Print("←↑↓→/hjkl: 移動  Enter/Space: 着手  p: パス  q: 終了"),
+    )?;
+    out.flush()
+}
+
+/// 端末を対局画面に切り替えて対局し、最後の状態を返す
+fn run(runtime: &Runtime, mut backend: Backend, mut view: View) -> io::Result<View> {
+    let human = backend.human();
+    let mut cursor = Cursor { row: 2, col: 3 };
+    let mut message = String::new();
+    let mut out = io::stdout();
+
+    loop {
+        draw(&mut out, &view, cursor, human, &message)?;
+        let Event::Key(KeyEvent { code, modifiers, kind: KeyEventKind::Press, .. }) = event::read()? else {
+            continue;
+        };
+        message.clear();
+        let result = match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(view),
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return Ok(view),
+            KeyCode::Enter | KeyCode::Char(' ') if view.finished.is_none() && view.to_move == human => {
+                if view.valid_moves.contains(&cursor.position()) {
+                    message = "AIが考えています...".to_string();
+                    draw(&mut out, &view, cursor, human, &message)?;
+                    Some(runtime.block_on(backend.play(cursor.position())))
+                } else {
+                    message = format!("({}, {}) には打てません", cursor.row, cursor.col);
+                    None
+                }
+            }
+            KeyCode::Char('p') if view.finished.is_none() && view.to_move == human => {
+                if view.must_pass {
+                    Some(runtime.block_on(backend.pass()))
+                } else {
+                    message = "合法手があるためパスできません".to_string();
+                    None
+                }
+            }
+            key => {
+                cursor = cursor.moved(key);
+                None
+            }
+        };
+        match result {
+            Some(Ok(next)) => {
+                view = next;
+                message.clear();
+            }
+            Some(Err(e)) => message = format!("エラー: {}", e),
+            None => {}
+        }
+    }
+}
+
+fn main() {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    let runtime = Runtime::new().expect("tokioランタイムを作成できません");
+    let (backend, view) = match runtime.block_on(Backend::start(&options)) {
+        Ok(started) => started,
+        Err(e) => {
+            eprintln!("対局を開始できません: {}", e);
+            if !options.offline {
+                eprintln!("サーバーを起動するか、--offline でローカルのエンジンと対局してください");
+            }
+            std::process::exit(1);
+        }
+    };
+    let human = backend.human();
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode().expect("端末をrawモードにできません");
+    let _ = crossterm::execute!(stdout, EnterAlternateScreen, Hide);
+    let result = run(&runtime, backend, view);
+    let _ = crossterm::execute!(stdout, Show, LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+
+    match result {
+        Ok(view) => {
+            print!("{}", view.board.display());
+            println!("{}", result_line(&view, human));
+        }
+        Err(e) => {
+            eprintln!("端末の操作に失敗しました: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(args(&[])).unwrap(), Options::default());
+        let options = parse_args(args(&["--offline", "--difficulty", "hard", "--color", "White"])).unwrap();
+        assert!(options.offline);
+        assert_eq!((options.difficulty, options.color), (AiDifficulty::Hard, Player::White));
+        assert!(parse_args(args(&["--color", "red"])).is_err());
+        assert!(parse_args(args(&["--url"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_chunked_response() {
+        let response = b"HTTP/1.1 201 Created\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n{\"a\"\r\n3\r\n:1}\r\n0\r\n\r\n";
+        assert_eq!(parse_response(response).unwrap(), (201, b"{\"a\":1}".to_vec()));
+        let response = b"HTTP/1.1 404 Not Found\r\ncontent-length: 2\r\n\r\n{}";
+        assert_eq!(parse_response(response).unwrap(), (404, b"{}".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_api_game_plays_against_the_server() {
+        use Reversi::api::{handlers::AppState, routes::create_ai_battle_router};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let router = create_ai_battle_router(AppState::new());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let options = Options { url, difficulty: AiDifficulty::Easy, ..Options::default() };
+        let (mut backend, view) = Backend::start(&options).await.unwrap();
+        assert_eq!((view.to_move, view.valid_moves.len()), (Player::Black, 4));
+
+        let reply = backend.play(view.valid_moves[0]).await.unwrap();
+        assert_eq!(reply.to_move, Player::Black);
+        assert!(backend.play(Position::new(0, 0).unwrap()).await.unwrap_err().starts_with("HTTP 400"));
+    }
+
+    #[tokio::test]
+    async fn test_offline_game_lets_the_ai_open_when_human_is_white() {
+        let options = Options { offline: true, color: Player::White, difficulty: AiDifficulty::Easy, ..Options::default() };
+        let (mut backend, view) = Backend::start(&options).await.unwrap();
+        assert_eq!(view.to_move, Player::White);
+        assert_eq!(view.board.count_pieces(), (4, 1));
+
+        let reply = backend.play(view.valid_moves[0]).await.unwrap();
+        assert!(reply.finished.is_some() || reply.to_move == Player::White);
+    }
+}
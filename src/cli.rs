@@ -0,0 +1,587 @@
+//! コマンドライン引数の定義と、サーバー起動以外のサブコマンドの実装
+//! サブコマンドを省略した場合は `serve` として扱う。
+
+use std::path::PathBuf;
+use std::time::Instant;
+
+use clap::{Args, Parser, Subcommand};
+
+use uuid::Uuid;
+
+use Reversi::{
+    ai::service::AIServiceFactory,
+    api::ai_battle::{config_utils, AiDifficulty},
+    archive::{open_game_archive, ArchivedGame},
+    config::{Config, ConfigOverrides},
+    game::{self, Board, GameState, Player, ReversiRules},
+    replay,
+    selfplay::{self as selfplay_data, SelfplayFormat, SelfplaySummary, SelfplayWriter},
+    simulation,
+};
+
+/// 既定の設定ファイルの出力先（`Config::load` が最初に探すパス）
+const DEFAULT_CONFIG_PATH: &str = "config.json";
+
+#[derive(Debug, Parser)]
+#[command(name = "reversi", version, about = "Reversi APIサーバー")]
+pub struct Cli {
+    /// ポートをバインドせずにエンジンを検証して終了する
+    #[arg(long)]
+    pub self_test: bool,
+
+    #[command(flatten)]
+    pub config: ConfigArgs,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// 設定ファイルの指定と、環境変数より優先する設定の上書き
+#[derive(Debug, Clone, Default, Args)]
+pub struct ConfigArgs {
+    /// 設定ファイル（省略時は config.json, config/app.json, /etc/reversi/config.json の順に探す）
+    #[arg(long = "config", global = true)]
+    pub file: Option<PathBuf>,
+    /// 待ち受けるポート
+    #[arg(long, global = true)]
+    pub port: Option<u16>,
+    /// 待ち受けるホスト
+    #[arg(long, global = true)]
+    pub host: Option<String>,
+    /// データベースの接続文字列
+    #[arg(long, global = true)]
+    pub database_url: Option<String>,
+    /// AI対戦の最大セッション数
+    #[arg(long, global = true)]
+    pub max_sessions: Option<usize>,
+}
+
+impl ConfigArgs {
+    pub fn overrides(&self) -> ConfigOverrides {
+        ConfigOverrides {
+            port: self.port,
+            host: self.host.clone(),
+            database_url: self.database_url.clone(),
+            max_sessions: self.max_sessions,
+        }
+    }
+
+    /// 既定値・設定ファイル・環境変数・コマンドライン引数を順に重ねて設定を読み込む
+    pub fn load(&self) -> Result<Config, String> {
+        Config::load_layered(self.file.as_deref(), &self.overrides()).map_err(|e| format!("設定を読み込めません: {}", e))
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// APIサーバーを起動する（サブコマンド省略時の既定）
+    Serve,
+    /// 既定の設定を config.json に書き出す
+    GenerateConfig {
+        /// 既存の config.json を上書きする
+        #[arg(long)]
+        force: bool,
+    },
+    /// 設定ファイルと環境変数を読み込み、設定値を検証する
+    ValidateConfig {
+        /// 検証する設定ファイル（省略時はサーバー起動時と同じ探索順で読み込む）
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// 設定を表示する
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// AI同士で対局し、結果を集計する
+    Selfplay(SelfplayArgs),
+    /// 同じ難易度のAI同士で大量に対局し、勝率・平均手数・平均分岐数・所要時間を集計する
+    Simulate(SimulateArgs),
+    /// 難易度ごとにAIの思考時間を計測する（`bench perft` で着手生成を検証する）
+    Bench(BenchArgs),
+    /// アーカイブした対局を初期局面から再生する
+    Replay(ReplayArgs),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// 設定をJSONで表示する（APIキーとJWTの署名鍵は伏せる）
+    Show {
+        /// 環境変数とコマンドライン引数を反映した最終的な設定を表示する（省略時は既定値と設定ファイルのみ）
+        #[arg(long)]
+        effective: bool,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct SelfplayArgs {
+    /// 対局数
+    #[arg(long, default_value_t = 10)]
+    pub games: u32,
+    /// 黒番のAIの難易度
+    #[arg(long, default_value = "medium")]
+    pub black: AiDifficulty,
+    /// 白番のAIの難易度
+    #[arg(long, default_value = "medium")]
+    pub white: AiDifficulty,
+    /// 乱択のシード（指定すると対局ごとに1ずつずらして再現可能にする）
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// 対局データの出力先（省略時は書き出さない）
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+    /// 出力形式（jsonl / wthor、省略時は出力先の拡張子 .wtb ならWTHOR、それ以外はJSONL）
+    #[arg(long)]
+    pub format: Option<SelfplayFormat>,
+}
+
+#[derive(Debug, Args)]
+pub struct SimulateArgs {
+    /// 対局数
+    #[arg(long, default_value_t = 1000)]
+    pub games: u32,
+    /// 両者のAIの難易度
+    #[arg(long, default_value = "easy")]
+    pub ai: AiDifficulty,
+    /// 乱択のシード（指定すると対局ごとに1ずつずらして再現可能にする）
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct BenchArgs {
+    #[command(subcommand)]
+    pub command: Option<BenchCommand>,
+    /// 計測する難易度（省略時は全難易度）
+    #[arg(long)]
+    pub difficulty: Option<AiDifficulty>,
+    /// 計測に使う局面の数
+    #[arg(long, default_value_t = 20)]
+    pub positions: usize,
+    /// 計測局面を作る対局のシード
+    #[arg(long, default_value_t = 1)]
+    pub seed: u64,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BenchCommand {
+    /// 初期局面からのゲーム木の末端の数（perft）を数え、既知の値と比べる
+    Perft(PerftArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct PerftArgs {
+    /// 探索する手数（パスも1手と数える）
+    #[arg(long, default_value_t = 8)]
+    pub depth: u32,
+    /// 盤面の一辺のマス数（既知の値と比べるのは8のときのみ）
+    #[arg(long, default_value_t = 8)]
+    pub size: usize,
+    /// 最初の1手ごとの末端の数も表示する
+    #[arg(long)]
+    pub divide: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ReplayArgs {
+    /// 再生する対局のID（設定のデータベースのアーカイブから読み込む）
+    #[arg(required_unless_present = "file", conflicts_with = "file")]
+    pub game_id: Option<Uuid>,
+    /// 対局の記録のJSONファイル（`GET /api/archive` の要素と同じ形式）
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+    /// 棋譜がルールと記録したシード・AIの設定に矛盾しないかを検証する（矛盾があれば終了コード1）
+    #[arg(long)]
+    pub verify: bool,
+}
+
+/// 既定の設定ファイルを生成する
+pub fn generate_config(force: bool) -> i32 {
+    if !force && std::path::Path::new(DEFAULT_CONFIG_PATH).exists() {
+        eprintln!("{} は既に存在します。上書きする場合は --force を指定してください", DEFAULT_CONFIG_PATH);
+        return 1;
+    }
+    match config_utils::generate_default_config_file(DEFAULT_CONFIG_PATH) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("設定ファイルの生成に失敗: {}", e);
+            1
+        }
+    }
+}
+
+/// 設定を読み込んで検証し、結果を表示する
+pub fn validate_config(file: Option<PathBuf>, config_args: &ConfigArgs) -> i32 {
+    let config_args = ConfigArgs { file: file.or_else(|| config_args.file.clone()), ..config_args.clone() };
+    let config = match config_args.load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+    let violations = config.violations();
+    if violations.is_empty() {
+        println!("設定は有効です");
+        return 0;
+    }
+    eprintln!("設定エラー（{}件）:", violations.len());
+    for violation in violations {
+        eprintln!("  {}", violation);
+    }
+    1
+}
+
+/// 設定をJSONで表示する
+/// `effective` でなければ既定値に設定ファイルを重ねた値、そうでなければ環境変数・コマンドライン引数まで重ねた値
+pub fn show_config(effective: bool, config_args: &ConfigArgs) -> i32 {
+    let path = Config::locate_file(config_args.file.as_deref());
+    match &path {
+        Some(path) => eprintln!("設定ファイル: {}", path.display()),
+        None => eprintln!("設定ファイル: なし（既定値を使用）"),
+    }
+    let config = if effective {
+        config_args.load()
+    } else {
+        path.map_or_else(|| Ok(Config::default()), Config::from_file)
+            .map_err(|e| format!("設定を読み込めません: {}", e))
+    };
+    let config = match config {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+    match serde_json::to_string_pretty(&config.redacted()) {
+        Ok(json) => {
+            println!("{}", json);
+            0
+        }
+        Err(e) => {
+            eprintln!("設定を出力できません: {}", e);
+            1
+        }
+    }
+}
+
+/// AI同士の対局を繰り返し、対局データを書き出して勝敗と石数を集計する
+pub async fn selfplay(args: SelfplayArgs) -> i32 {
+    let engine = match AIServiceFactory::create_fast_local() {
+        Ok(engine) => engine,
+        Err(e) => {
+            eprintln!("AIサービスを作成できません: {}", e);
+            return 1;
+        }
+    };
+
+    let mut writer = match &args.output {
+        Some(path) => {
+            let format = args.format.unwrap_or_else(|| SelfplayFormat::from_path(path));
+            match std::fs::File::create(path) {
+                Ok(file) => Some(SelfplayWriter::new(std::io::BufWriter::new(file), format)),
+                Err(e) => {
+                    eprintln!("{} を作成できません: {}", path.display(), e);
+                    return 1;
+                }
+            }
+        }
+        None => None,
+    };
+
+    let started = Instant::now();
+    let mut summary = SelfplaySummary::default();
+    for game in 1..=args.games {
+        let played = selfplay_data::play_game(&*engine, game, args.black, args.white, args.seed).await;
+        summary.record(&played);
+
+        let (black, white) = played.state.get_score();
+        let result = match played.winner() {
+            Some(Player::Black) => "黒の勝ち",
+            Some(Player::White) => "白の勝ち",
+            None => "引き分け",
+        };
+        match &played.forfeit {
+            Some(reason) => println!("#{:<4} 黒 {:>2} - {:<2} 白  {}（反則負け: {}）", game, black, white, result, reason),
+            None => println!("#{:<4} 黒 {:>2} - {:<2} 白  {}", game, black, white, result),
+        }
+
+        if let Some(writer) = writer.as_mut() {
+            if let Err(e) = writer.write_game(&played) {
+                eprintln!("対局データの書き込みに失敗: {}", e);
+                return 1;
+            }
+        }
+    }
+
+    if let Some(writer) = writer {
+        if let Err(e) = writer.finish() {
+            eprintln!("対局データの書き込みに失敗: {}", e);
+            return 1;
+        }
+    }
+    if args.games > 0 {
+        println!(
+            "黒 {:?} / 白 {:?}  {}  所要時間 {:.1}秒",
+            args.black,
+            args.white,
+            summary,
+            started.elapsed().as_secs_f64(),
+        );
+    }
+    0
+}
+
+/// HTTPを介さずにエンジン同士の対局を繰り返し、集計結果を表示する
+pub async fn simulate(args: SimulateArgs) -> i32 {
+    let engine = match AIServiceFactory::create_fast_local() {
+        Ok(engine) => engine,
+        Err(e) => {
+            eprintln!("AIサービスを作成できません: {}", e);
+            return 1;
+        }
+    };
+    let report = simulation::simulate(&*engine, args.games, args.ai, args.seed).await;
+    println!("{:?} 同士", args.ai);
+    println!("{}", report);
+    if report.forfeits > 0 { 1 } else { 0 }
+}
+
+async fn load_archived_game(args: &ReplayArgs, config_args: &ConfigArgs) -> Result<ArchivedGame, String> {
+    if let Some(path) = &args.file {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("{} を読み込めません: {}", path.display(), e))?;
+        return serde_json::from_str(&json).map_err(|e| format!("{} を解釈できません: {}", path.display(), e));
+    }
+    let game_id = args.game_id.ok_or("対局のIDか --file を指定してください")?;
+    let config = config_args.load()?;
+    let archive = open_game_archive(&config.database)
+        .await
+        .map_err(|e| format!("アーカイブを開けません: {}", e))?;
+    archive
+        .get(game_id)
+        .await
+        .map_err(|e| format!("アーカイブを読み込めません: {}", e))?
+        .ok_or_else(|| format!("対局 {} はアーカイブにありません", game_id))
+}
+
+/// アーカイブした対局の棋譜を表示し、`--verify` の場合は再生して検証する
+pub async fn replay(args: ReplayArgs, config_args: &ConfigArgs) -> i32 {
+    let game = match load_archived_game(&args, config_args).await {
+        Ok(game) => game,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+
+    println!("対局 {}  {:?}  黒 {} - {} 白", game.game_id, game.kind, game.black_count, game.white_count);
+    for (index, record) in game.moves.iter().enumerate() {
+        let color = if record.player == Player::Black { "黒" } else { "白" };
+        let actor = if record.ai_params.is_some() { "AI" } else { "" };
+        match record.position {
+            Some(_) => println!("{:>3}. {}{} {}", index + 1, color, actor, record.notation),
+            None => println!("{:>3}. {}{} パス", index + 1, color, actor),
+        }
+    }
+    if !args.verify {
+        return 0;
+    }
+
+    let report = replay::verify(&game);
+    println!(
+        "{}手を再生、AIの着手 {}手を確認（シードの記録がなく未確認 {}手）",
+        report.plies, report.ai_moves_verified, report.ai_moves_unverifiable
+    );
+    if !report.same_engine() {
+        println!(
+            "注意: 対局時のエンジン {} と現在のエンジン {} が異なります",
+            report.engine_version.as_deref().unwrap_or("不明"),
+            replay::ENGINE_VERSION
+        );
+    }
+    for warning in &report.warnings {
+        println!("警告: {}（思考時間の上限による差の可能性があります）", warning);
+    }
+    for error in &report.errors {
+        eprintln!("不一致: {}", error);
+    }
+    if report.is_consistent() {
+        println!("記録は一貫しています");
+        0
+    } else {
+        1
+    }
+}
+
+/// シード付きの乱択の対局から、計測に使う局面を集める
+fn bench_positions(count: usize, seed: u64) -> Vec<GameState> {
+    let mut positions = Vec::new();
+    let mut game_seed = seed;
+    while positions.len() < count {
+        let mut state = GameState::new();
+        while !state.is_finished() && positions.len() < count {
+            let moves = ReversiRules::get_valid_moves(&state.board, state.current_player);
+            if moves.is_empty() {
+                ReversiRules::handle_turn(&mut state);
+                continue;
+            }
+            positions.push(state.clone());
+            // 線形合同法で合法手を選ぶ（計測局面の再現性のみが目的）
+            game_seed = game_seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let position = moves[(game_seed >> 33) as usize % moves.len()];
+            if ReversiRules::apply_move(&mut state, position).is_err() {
+                break;
+            }
+            state.switch_player();
+            ReversiRules::handle_turn(&mut state);
+        }
+    }
+    positions
+}
+
+/// 同じ局面の集合に対する各難易度の思考時間を計測する
+pub async fn bench(args: BenchArgs) -> i32 {
+    let engine = match AIServiceFactory::create_fast_local() {
+        Ok(engine) => engine,
+        Err(e) => {
+            eprintln!("AIサービスを作成できません: {}", e);
+            return 1;
+        }
+    };
+    let difficulties = match args.difficulty {
+        Some(difficulty) => vec![difficulty],
+        None => AiDifficulty::all(),
+    };
+    let positions = bench_positions(args.positions, args.seed);
+
+    println!("{}局面で計測", positions.len());
+    let mut failed = false;
+    'difficulties: for difficulty in difficulties {
+        let mut timings = Vec::with_capacity(positions.len());
+        let (mut nodes, mut cutoffs, mut first_move_cutoffs) = (0, 0, 0);
+        for state in &positions {
+            let started = Instant::now();
+            let result = match engine.calculate_move(state, difficulty).await {
+                Ok(result) => result,
+                Err(e) => {
+                    // 未実装の難易度があっても残りの難易度は計測する
+                    eprintln!("{:<6} 着手の計算に失敗: {}", difficulty.name(), e);
+                    failed = true;
+                    continue 'difficulties;
+                }
+            };
+            timings.push(started.elapsed().as_secs_f64() * 1000.0);
+            nodes += result.nodes_evaluated.unwrap_or(0);
+            cutoffs += result.cutoffs.unwrap_or(0);
+            first_move_cutoffs += result.first_move_cutoffs.unwrap_or(0);
+        }
+        let total: f64 = timings.iter().sum();
+        let max = timings.iter().copied().fold(0.0, f64::max);
+        print!(
+            "{:<6} 平均 {:>8.2}ms  最大 {:>8.2}ms  合計 {:>9.1}ms",
+            difficulty.name(),
+            total / timings.len().max(1) as f64,
+            max,
+            total,
+        );
+        // 探索するレベルのみ、局面あたりのノード数と最初の手でのβカットの割合を表示する
+        if nodes > 0 {
+            print!(
+                "  ノード {:>10.0}  初手カット率 {:>5.1}%",
+                nodes as f64 / timings.len().max(1) as f64,
+                first_move_cutoffs as f64 * 100.0 / cutoffs.max(1) as f64,
+            );
+        }
+        println!();
+    }
+    if failed { 1 } else { 0 }
+}
+
+/// 初期局面からperftを数え、標準の盤面では既知の値と一致するかを確かめる（不一致なら終了コード1）
+pub fn perft(args: PerftArgs) -> i32 {
+    let Some(board) = Board::with_size(args.size) else {
+        eprintln!("対応していない盤面の大きさです: {}", args.size);
+        return 1;
+    };
+
+    let started = Instant::now();
+    let nodes = if args.divide {
+        let divided = game::perft_divide(&board, Player::Black, args.depth);
+        for (position, nodes) in &divided {
+            match position {
+                Some(position) => println!("{:<4} {}", position.to_string(), nodes),
+                None => println!("{:<4} {}", "パス", nodes),
+            }
+        }
+        divided.iter().map(|(_, nodes)| nodes).sum()
+    } else {
+        game::perft(&board, Player::Black, args.depth)
+    };
+    let elapsed = started.elapsed().as_secs_f64();
+    println!(
+        "perft({}) = {}  所要時間 {:.2}秒  {:.0}局面/秒",
+        args.depth,
+        nodes,
+        elapsed,
+        nodes as f64 / elapsed.max(f64::EPSILON),
+    );
+
+    let expected = if args.size == 8 { game::perft::standard_perft(args.depth) } else { None };
+    match expected {
+        Some(expected) if expected != nodes => {
+            eprintln!("既知の値 {} と一致しません", expected);
+            1
+        }
+        Some(_) => {
+            println!("既知の値と一致しました");
+            0
+        }
+        None => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition_and_defaults() {
+        Cli::command().debug_assert();
+        assert!(Cli::parse_from(["reversi"]).command.is_none());
+
+        let cli = Cli::parse_from(["reversi", "selfplay", "--games", "3", "--black", "hard"]);
+        let Some(Command::Selfplay(args)) = cli.command else { panic!("selfplay として解釈されるべき") };
+        assert_eq!((args.games, args.black, args.white, args.seed), (3, AiDifficulty::Hard, AiDifficulty::Medium, None));
+        assert!(Cli::try_parse_from(["reversi", "bench", "--difficulty", "expert"]).is_err());
+
+        let cli = Cli::parse_from(["reversi", "simulate", "--games", "10000", "--ai", "easy"]);
+        let Some(Command::Simulate(args)) = cli.command else { panic!("simulate として解釈されるべき") };
+        assert_eq!((args.games, args.ai, args.seed), (10000, AiDifficulty::Easy, None));
+
+        let cli = Cli::parse_from(["reversi", "bench", "perft", "--depth", "5", "--divide"]);
+        let Some(Command::Bench(BenchArgs { command: Some(BenchCommand::Perft(args)), .. })) = cli.command else {
+            panic!("bench perft として解釈されるべき")
+        };
+        assert_eq!((args.depth, args.size, args.divide), (5, 8, true));
+        let cli = Cli::parse_from(["reversi", "bench", "--positions", "3"]);
+        let Some(Command::Bench(args)) = cli.command else { panic!("bench として解釈されるべき") };
+        assert!(args.command.is_none() && args.positions == 3);
+
+        let cli = Cli::parse_from(["reversi", "selfplay", "--output", "games.dat", "--format", "wthor"]);
+        let Some(Command::Selfplay(args)) = cli.command else { panic!("selfplay として解釈されるべき") };
+        assert_eq!(args.format, Some(SelfplayFormat::Wthor));
+
+        let cli = Cli::parse_from(["reversi", "replay", "--file", "game.json", "--verify"]);
+        let Some(Command::Replay(args)) = cli.command else { panic!("replay として解釈されるべき") };
+        assert!(args.verify && args.game_id.is_none());
+        assert!(Cli::try_parse_from(["reversi", "replay"]).is_err());
+    }
+
+    #[test]
+    fn test_bench_positions_are_reproducible() {
+        let positions = bench_positions(70, 3);
+        assert_eq!(positions.len(), 70);
+        let again = bench_positions(70, 3);
+        assert!(positions.iter().zip(&again).all(|(a, b)| a.board == b.board && a.current_player == b.current_player));
+    }
+}
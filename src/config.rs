@@ -1,12 +1,16 @@
 //! アプリケーション設定管理モジュール
 //! サーバー、データベース、AIサービスなどの設定を
 //! 設定ファイルと環境変数から読み込んで管理する。
+//! 既定値 < 設定ファイル < 環境変数 < コマンドライン引数 の順に、項目ごとに上書きする
+//! （設定ファイルや環境変数で指定しなかった項目は、下の層の値のまま残る）。
 
 use serde::{Deserialize, Serialize};
-use std::{env, fs, path::Path, time::Duration};
+use std::{env, fs, path::{Path, PathBuf}, time::Duration};
 
 use crate::ai::service::{AIServiceConfig, AIServiceType};
-use crate::api::ai_battle::dto::AiDifficulty;
+use crate::session::SessionStoreBackend;
+use crate::ai::AiDifficulty;
+use crate::api::webhook::WebhookUrl;
 
 /// Duration型をJSONでシリアライズするためのモジュール
 mod duration_serde {
@@ -68,7 +72,20 @@ pub struct ServerConfig {
     pub port: u16,
     pub host: String,
     pub enable_cors: bool,
+    /// CORSで許可するオリジン（`https://example.com` の形式）
+    /// 空の場合は全てのオリジンを許可する
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
     pub enable_logging: bool,
+    /// ルートごとのリクエスト処理時間の上限
+    #[serde(default)]
+    pub timeouts: RouteTimeouts,
+    /// 指定した場合はリバースプロキシを介さずにHTTPSで待ち受ける
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+    /// 指定した場合はこのポートでgRPC APIを待ち受ける（`grpc` フィーチャーが必要）
+    #[serde(default)]
+    pub grpc_port: Option<u16>,
 }
 
 impl Default for ServerConfig {
@@ -77,7 +94,69 @@ impl Default for ServerConfig {
             port: 3000,
             host: "0.0.0.0".to_string(),
             enable_cors: true,
+            allowed_origins: Vec::new(),
             enable_logging: true,
+            timeouts: RouteTimeouts::default(),
+            tls: None,
+            grpc_port: None,
+        }
+    }
+}
+
+/// HTTPSの設定（証明書チェーンと秘密鍵はPEM形式のファイル）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub cert_path: String,
+    pub key_path: String,
+    /// 指定した場合、このポートで受けたHTTPリクエストをHTTPSへリダイレクトする
+    #[serde(default)]
+    pub redirect_http_port: Option<u16>,
+}
+
+/// ルートの種類ごとのリクエスト処理時間の上限（ミリ秒）
+/// 上限を超えたリクエストは504で打ち切られる。イベントストリームなどの常時接続は対象外
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RouteTimeouts {
+    /// 対局状態や一覧の取得
+    pub state_read_ms: u64,
+    /// 着手・パス・AIの手番の進行（上級AIの思考時間を含む）
+    pub move_ms: u64,
+    /// 局面解析
+    pub analysis_ms: u64,
+    /// 上記以外のルート
+    pub default_ms: u64,
+}
+
+/// タイムアウトの上限を決めるルートの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteClass {
+    StateRead,
+    Move,
+    Analysis,
+    Default,
+}
+
+impl RouteTimeouts {
+    /// ルートの種類に対応する上限
+    pub fn budget(&self, class: RouteClass) -> Duration {
+        let millis = match class {
+            RouteClass::StateRead => self.state_read_ms,
+            RouteClass::Move => self.move_ms,
+            RouteClass::Analysis => self.analysis_ms,
+            RouteClass::Default => self.default_ms,
+        };
+        Duration::from_millis(millis)
+    }
+}
+
+impl Default for RouteTimeouts {
+    fn default() -> Self {
+        Self {
+            state_read_ms: 2_000,
+            move_ms: 30_000,
+            analysis_ms: 300_000,
+            default_ms: 10_000,
         }
     }
 }
@@ -90,6 +169,9 @@ pub struct DatabaseConfig {
     pub max_connections: u32,
     #[serde(with = "duration_serde")]
     pub connection_timeout: Duration,
+    /// セッションの保存先
+    #[serde(default)]
+    pub session_store: SessionStoreBackend,
 }
 
 impl Default for DatabaseConfig {
@@ -98,6 +180,30 @@ impl Default for DatabaseConfig {
             url: "sqlite:reversi.db".to_string(),
             max_connections: 5,
             connection_timeout: Duration::from_secs(30),
+            session_store: SessionStoreBackend::default(),
+        }
+    }
+}
+
+/// 非アクティブなセッションのクリーンアップから除外する対象
+/// いずれも進行中の対局のみが対象で、終局したセッションは通常どおり削除される（ピン留めを除く）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CleanupPolicy {
+    /// 通信対局を除外する
+    pub exempt_correspondence: bool,
+    /// 大会の対局を除外する
+    pub exempt_tournament_games: bool,
+    /// 管理者がピン留めしたセッションを除外する（終局後も残す）
+    pub exempt_pinned: bool,
+}
+
+impl Default for CleanupPolicy {
+    fn default() -> Self {
+        Self {
+            exempt_correspondence: true,
+            exempt_tournament_games: true,
+            exempt_pinned: true,
         }
     }
 }
@@ -111,6 +217,8 @@ pub struct AiBattleConfig {
     pub default_difficulty: AiDifficulty,
     pub enable_session_cleanup: bool,
     pub cleanup_interval_minutes: u64,
+    #[serde(default)]
+    pub cleanup_policy: CleanupPolicy,
 }
 
 impl Default for AiBattleConfig {
@@ -121,6 +229,7 @@ impl Default for AiBattleConfig {
             default_difficulty: AiDifficulty::Easy,
             enable_session_cleanup: true,
             cleanup_interval_minutes: 5,
+            cleanup_policy: CleanupPolicy::default(),
         }
     }
 }
@@ -133,6 +242,14 @@ pub struct FallbackConfig {
     pub fallback_ai_service: AIServiceType,
     pub max_retry_attempts: u32,
     pub retry_delay_ms: u64,
+    /// プライマリAIの死活を確認する間隔（ミリ秒、0は監視しない）
+    /// 利用できない間は新しい着手をフォールバックAIで計算し、復旧したら戻す
+    #[serde(default = "default_health_check_interval_ms")]
+    pub health_check_interval_ms: u64,
+}
+
+fn default_health_check_interval_ms() -> u64 {
+    5000
 }
 
 impl Default for FallbackConfig {
@@ -142,6 +259,56 @@ impl Default for FallbackConfig {
             fallback_ai_service: AIServiceType::Local,
             max_retry_attempts: 3,
             retry_delay_ms: 1000,
+            health_check_interval_ms: default_health_check_interval_ms(),
+        }
+    }
+}
+
+/// 通信対局の設定
+/// 手番の催促の時期と、催促・時間切れの結果を外部へ届けるWebhookを指定する
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CorrespondenceConfig {
+    /// 手番になってから催促を送るまでの時間（分）
+    /// 未指定の場合は1手の持ち時間の3/4が経過した時点で催促する
+    #[serde(default)]
+    pub reminder_after_minutes: Option<u64>,
+    /// 催促と終局を通知するWebhookのURL（http://のみ、未指定なら配信しない）
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+}
+
+/// APIキー1件分の設定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKeyEntry {
+    /// キーの利用者名（セッションの作成元として記録される）
+    pub name: String,
+    pub key: String,
+}
+
+/// APIキー認証とプレイヤーアカウントの設定
+/// APIキーが1件も登録されていない場合はAPIキーの認証を行わない
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthConfig {
+    #[serde(default)]
+    pub api_keys: Vec<ApiKeyEntry>,
+    /// アカウントのJWTの署名鍵（32バイト以上、未設定なら起動ごとにランダムに生成する）
+    #[serde(default)]
+    pub jwt_secret: Option<String>,
+    /// 発行したJWTの有効期間（分）
+    #[serde(default = "default_token_ttl_minutes")]
+    pub token_ttl_minutes: u64,
+}
+
+fn default_token_ttl_minutes() -> u64 {
+    24 * 60
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            api_keys: Vec::new(),
+            jwt_secret: None,
+            token_ttl_minutes: default_token_ttl_minutes(),
         }
     }
 }
@@ -156,6 +323,10 @@ pub struct Config {
     pub ai_battle: AiBattleConfig,
     pub ai_service: AIServiceConfig,
     pub fallback: FallbackConfig,
+    #[serde(default)]
+    pub correspondence: CorrespondenceConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
 }
 
 impl Default for Config {
@@ -167,6 +338,8 @@ impl Default for Config {
             ai_battle: AiBattleConfig::default(),
             ai_service: AIServiceConfig::default(),
             fallback: FallbackConfig::default(),
+            correspondence: CorrespondenceConfig::default(),
+            auth: AuthConfig::default(),
         }
     }
 }
@@ -184,22 +357,76 @@ pub enum ConfigError {
     #[error("環境変数エラー: {name} = {value}")]
     EnvVarError { name: String, value: String },
     
-    #[error("設定値が無効です: {field} = {value}")]
-    InvalidValue { field: String, value: String },
+    #[error("設定値が無効です（{}件）: {}", .0.len(), .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    Invalid(Vec<ConfigViolation>),
+}
+
+/// 設定値の違反1件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigViolation {
+    /// 違反した項目（`server.port` の形式）
+    pub field: String,
+    pub value: String,
+    /// 違反の理由と指定できる値
+    pub reason: String,
+}
+
+impl std::fmt::Display for ConfigViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} = {}（{}）", self.field, self.value, self.reason)
+    }
+}
+
+/// 設定ファイルを指定しない場合に探すパス（先に見つかったものを使う）
+pub const CONFIG_SEARCH_PATHS: &[&str] = &["config.json", "config/app.json", "/etc/reversi/config.json"];
+
+/// コマンドライン引数による設定の上書き（指定した項目のみ、環境変数より優先する）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigOverrides {
+    pub port: Option<u16>,
+    pub host: Option<String>,
+    pub database_url: Option<String>,
+    pub max_sessions: Option<usize>,
+}
+
+/// `overlay` に含まれる項目だけで `base` を上書きする（オブジェクトは項目ごとに再帰する）
+fn merge_json(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
 }
 
 impl Config {
     /// 指定したファイルパスから設定を読み込む
+    /// ファイルにない項目は既定値のまま残る
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
         let content = fs::read_to_string(path)?;
-        let config: Config = serde_json::from_str(&content)?;
-        Ok(config)
+        let mut config = serde_json::to_value(Config::default())?;
+        merge_json(&mut config, serde_json::from_str(&content)?);
+        Ok(serde_json::from_value(config)?)
     }
     
     /// 環境変数から設定を読み込む
     /// デフォルト値をベースに環境変数で上書きする
     pub fn from_env() -> Result<Self, ConfigError> {
         let mut config = Config::default();
+        config.apply_env()?;
+        Ok(config)
+    }
+    
+    /// 設定されている環境変数の項目だけを上書きする
+    pub fn apply_env(&mut self) -> Result<(), ConfigError> {
+        let config = self;
         
         if let Ok(port) = env::var("SERVER_PORT") {
             config.server.port = port.parse().map_err(|_| ConfigError::EnvVarError {
@@ -212,10 +439,72 @@ impl Config {
             config.server.host = host;
         }
         
+        // カンマ区切りで指定する
+        if let Ok(origins) = env::var("CORS_ALLOWED_ORIGINS") {
+            config.server.allowed_origins = origins
+                .split(',')
+                .map(str::trim)
+                .filter(|origin| !origin.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        
+        // 証明書と秘密鍵は両方指定する（設定ファイルのリダイレクト先のポートは引き継ぐ）
+        match (env::var("TLS_CERT_PATH"), env::var("TLS_KEY_PATH")) {
+            (Ok(cert_path), Ok(key_path)) => match config.server.tls.as_mut() {
+                Some(tls) => {
+                    tls.cert_path = cert_path;
+                    tls.key_path = key_path;
+                }
+                None => config.server.tls = Some(TlsConfig { cert_path, key_path, redirect_http_port: None }),
+            },
+            (Ok(_), Err(_)) => return Err(ConfigError::EnvVarError {
+                name: "TLS_KEY_PATH".to_string(),
+                value: "<unset>".to_string(),
+            }),
+            (Err(_), Ok(_)) => return Err(ConfigError::EnvVarError {
+                name: "TLS_CERT_PATH".to_string(),
+                value: "<unset>".to_string(),
+            }),
+            (Err(_), Err(_)) => {}
+        }
+        
+        if let Ok(redirect_port) = env::var("TLS_REDIRECT_HTTP_PORT") {
+            let port = redirect_port.parse().map_err(|_| ConfigError::EnvVarError {
+                name: "TLS_REDIRECT_HTTP_PORT".to_string(),
+                value: redirect_port.clone(),
+            })?;
+            match config.server.tls.as_mut() {
+                Some(tls) => tls.redirect_http_port = Some(port),
+                None => return Err(ConfigError::EnvVarError {
+                    name: "TLS_REDIRECT_HTTP_PORT".to_string(),
+                    value: redirect_port,
+                }),
+            }
+        }
+        
+        if let Ok(grpc_port) = env::var("GRPC_PORT") {
+            config.server.grpc_port = Some(grpc_port.parse().map_err(|_| ConfigError::EnvVarError {
+                name: "GRPC_PORT".to_string(),
+                value: grpc_port,
+            })?);
+        }
+        
         if let Ok(database_url) = env::var("DATABASE_URL") {
             config.database.url = database_url;
         }
         
+        if let Ok(backend) = env::var("SESSION_STORE_BACKEND") {
+            config.database.session_store = match backend.to_lowercase().as_str() {
+                "memory" => SessionStoreBackend::Memory,
+                "sqlite" => SessionStoreBackend::Sqlite,
+                _ => return Err(ConfigError::EnvVarError {
+                    name: "SESSION_STORE_BACKEND".to_string(),
+                    value: backend,
+                }),
+            };
+        }
+        
         if let Ok(max_sessions) = env::var("AI_BATTLE_MAX_SESSIONS") {
             config.ai_battle.max_sessions = max_sessions.parse().map_err(|_| ConfigError::EnvVarError {
                 name: "AI_BATTLE_MAX_SESSIONS".to_string(),
@@ -260,6 +549,45 @@ impl Config {
             })?;
         }
         
+        if let Ok(threads) = env::var("AI_SEARCH_THREADS") {
+            config.ai_service.search_threads = threads.parse().map_err(|_| ConfigError::EnvVarError {
+                name: "AI_SEARCH_THREADS".to_string(),
+                value: threads,
+            })?;
+        }
+        
+        if let Ok(opening_book) = env::var("AI_OPENING_BOOK") {
+            config.ai_service.opening_book = Some(opening_book.into());
+        }
+        
+        if let Ok(enable_caching) = env::var("AI_ENABLE_CACHING") {
+            config.ai_service.enable_caching = enable_caching.parse().map_err(|_| ConfigError::EnvVarError {
+                name: "AI_ENABLE_CACHING".to_string(),
+                value: enable_caching,
+            })?;
+        }
+        
+        if let Ok(capacity) = env::var("AI_CACHE_CAPACITY") {
+            config.ai_service.cache_capacity = capacity.parse().map_err(|_| ConfigError::EnvVarError {
+                name: "AI_CACHE_CAPACITY".to_string(),
+                value: capacity,
+            })?;
+        }
+        
+        if let Ok(workers) = env::var("AI_QUEUE_WORKERS") {
+            config.ai_service.queue_workers = workers.parse().map_err(|_| ConfigError::EnvVarError {
+                name: "AI_QUEUE_WORKERS".to_string(),
+                value: workers,
+            })?;
+        }
+        
+        if let Ok(capacity) = env::var("AI_QUEUE_CAPACITY") {
+            config.ai_service.queue_capacity = capacity.parse().map_err(|_| ConfigError::EnvVarError {
+                name: "AI_QUEUE_CAPACITY".to_string(),
+                value: capacity,
+            })?;
+        }
+        
         if let Ok(enable_fallback) = env::var("ENABLE_AI_FALLBACK") {
             config.fallback.enable_fallback = enable_fallback.parse().map_err(|_| ConfigError::EnvVarError {
                 name: "ENABLE_AI_FALLBACK".to_string(),
@@ -267,33 +595,99 @@ impl Config {
             })?;
         }
         
+        if let Ok(interval) = env::var("AI_HEALTH_CHECK_INTERVAL_MS") {
+            config.fallback.health_check_interval_ms = interval.parse().map_err(|_| ConfigError::EnvVarError {
+                name: "AI_HEALTH_CHECK_INTERVAL_MS".to_string(),
+                value: interval,
+            })?;
+        }
+        
+        if let Ok(reminder_after) = env::var("CORRESPONDENCE_REMINDER_AFTER_MINUTES") {
+            config.correspondence.reminder_after_minutes = Some(reminder_after.parse().map_err(|_| ConfigError::EnvVarError {
+                name: "CORRESPONDENCE_REMINDER_AFTER_MINUTES".to_string(),
+                value: reminder_after,
+            })?);
+        }
+        
+        if let Ok(webhook_url) = env::var("CORRESPONDENCE_WEBHOOK_URL") {
+            config.correspondence.webhook_url = Some(webhook_url);
+        }
+        
+        // "名前:キー" をカンマ区切りで指定する
+        if let Ok(api_keys) = env::var("API_KEYS") {
+            config.auth.api_keys = api_keys
+                .split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(|entry| match entry.split_once(':') {
+                    Some((name, key)) => Ok(ApiKeyEntry { name: name.trim().to_string(), key: key.trim().to_string() }),
+                    // キーがログに残らないよう値は伏せる
+                    None => Err(ConfigError::EnvVarError { name: "API_KEYS".to_string(), value: "<redacted>".to_string() }),
+                })
+                .collect::<Result<_, _>>()?;
+        }
+        
+        if let Ok(jwt_secret) = env::var("JWT_SECRET") {
+            config.auth.jwt_secret = Some(jwt_secret);
+        }
+        
+        Ok(())
+    }
+    
+    /// コマンドライン引数で指定された項目だけを上書きする
+    pub fn apply_overrides(&mut self, overrides: &ConfigOverrides) {
+        if let Some(port) = overrides.port {
+            self.server.port = port;
+        }
+        if let Some(host) = &overrides.host {
+            self.server.host = host.clone();
+        }
+        if let Some(database_url) = &overrides.database_url {
+            self.database.url = database_url.clone();
+        }
+        if let Some(max_sessions) = overrides.max_sessions {
+            self.ai_battle.max_sessions = max_sessions;
+        }
+    }
+    
+    /// 読み込む設定ファイル（指定がなければ探索パスのうち最初に存在するもの、どれもなければNone）
+    pub fn locate_file(file: Option<&Path>) -> Option<PathBuf> {
+        match file {
+            Some(path) => Some(path.to_path_buf()),
+            None => CONFIG_SEARCH_PATHS.iter().map(PathBuf::from).find(|path| path.exists()),
+        }
+    }
+    
+    /// 既定値 < 設定ファイル < 環境変数 < コマンドライン引数 の順に項目ごとに上書きして設定を読み込む
+    /// `file` を省略した場合は探索パスから設定ファイルを探し、見つからなければ既定値から始める
+    pub fn load_layered(file: Option<&Path>, overrides: &ConfigOverrides) -> Result<Self, ConfigError> {
+        let mut config = match Self::locate_file(file) {
+            Some(path) => Self::from_file(path)?,
+            None => Config::default(),
+        };
+        config.apply_env()?;
+        config.apply_overrides(overrides);
         Ok(config)
     }
     
     /// 設定ファイルと環境変数を結合して設定を読み込む
-    /// 設定ファイルがなくてもデフォルト値で動作する
+    /// 設定ファイルがなくてもデフォルト値で動作する（読み込めない場合は警告して既定値を使う）
     pub fn load() -> Self {
-        let mut config = Config::default();
-        
-        if let Ok(file_config) = Self::from_file("config.json") {
-            config = file_config;
-        } else if let Ok(file_config) = Self::from_file("config/app.json") {
-            config = file_config;
-        } else if let Ok(file_config) = Self::from_file("/etc/reversi/config.json") {
-            config = file_config;
+        Self::load_layered(None, &ConfigOverrides::default()).unwrap_or_else(|e| {
+            eprintln!("警告: 設定を読み込めないため既定値を使用します: {}", e);
+            Config::default()
+        })
+    }
+    
+    /// 表示用に、APIキーとJWTの署名鍵を伏せた設定
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        for entry in &mut config.auth.api_keys {
+            entry.key = "<redacted>".to_string();
         }
-        
-        // 環境変数で設定を上書き
-        if let Ok(env_config) = Self::from_env() {
-            config.server.port = env_config.server.port;
-            config.server.host = env_config.server.host;
-            config.database.url = env_config.database.url;
-            config.ai_battle.max_sessions = env_config.ai_battle.max_sessions;
-            config.ai_battle.session_timeout_minutes = env_config.ai_battle.session_timeout_minutes;
-            config.ai_service = env_config.ai_service;
-            config.fallback = env_config.fallback;
+        if config.auth.jwt_secret.is_some() {
+            config.auth.jwt_secret = Some("<redacted>".to_string());
         }
-        
         config
     }
     
@@ -305,31 +699,202 @@ impl Config {
     }
     
     /// 設定値の妥当性をチェックする
-    /// 不正な値がある場合はConfigErrorを返す
+    /// 不正な値がある場合は、見つかった全ての違反を含むConfigErrorを返す
     pub fn validate(&self) -> Result<(), ConfigError> {
+        let violations = self.violations();
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(violations))
+        }
+    }
+    
+    /// 設定値の違反を全て列挙する（違反がなければ空）
+    pub fn violations(&self) -> Vec<ConfigViolation> {
+        let mut violations = Vec::new();
+        let mut violation = |field: &str, value: String, reason: &str| {
+            violations.push(ConfigViolation { field: field.to_string(), value, reason: reason.to_string() });
+        };
+        
         if self.server.port == 0 {
-            return Err(ConfigError::InvalidValue {
-                field: "server.port".to_string(),
-                value: self.server.port.to_string(),
-            });
+            violation("server.port", self.server.port.to_string(), "1以上を指定してください");
+        }
+        
+        if !is_valid_host(&self.server.host) {
+            violation("server.host", self.server.host.clone(), "IPアドレスかホスト名を指定してください");
         }
         
         if self.ai_battle.max_sessions == 0 {
-            return Err(ConfigError::InvalidValue {
-                field: "ai_battle.max_sessions".to_string(),
-                value: self.ai_battle.max_sessions.to_string(),
-            });
+            violation("ai_battle.max_sessions", self.ai_battle.max_sessions.to_string(), "1以上を指定してください");
+        }
+        
+        if self.system_limits.max_move_history == 0 {
+            violation("system_limits.max_move_history", "0".to_string(), "1以上を指定してください");
+        }
+        
+        for (index, origin) in self.server.allowed_origins.iter().enumerate() {
+            if !is_valid_origin(origin) {
+                violation(
+                    &format!("server.allowed_origins[{}]", index),
+                    origin.clone(),
+                    "* または http(s)://ホスト[:ポート] を指定してください",
+                );
+            }
+        }
+        
+        if let Some(tls) = &self.server.tls {
+            for (field, path) in [("server.tls.cert_path", &tls.cert_path), ("server.tls.key_path", &tls.key_path)] {
+                if path.trim().is_empty() {
+                    violation(field, path.clone(), "ファイルのパスを指定してください");
+                }
+            }
+            if let Some(redirect_port) = tls.redirect_http_port {
+                if redirect_port == 0 || redirect_port == self.server.port {
+                    violation(
+                        "server.tls.redirect_http_port",
+                        redirect_port.to_string(),
+                        "1以上で、server.port と異なるポートを指定してください",
+                    );
+                }
+            }
+        }
+        
+        if let Some(grpc_port) = self.server.grpc_port {
+            let redirect_port = self.server.tls.as_ref().and_then(|tls| tls.redirect_http_port);
+            if grpc_port == 0 || grpc_port == self.server.port || Some(grpc_port) == redirect_port {
+                violation("server.grpc_port", grpc_port.to_string(), "1以上で、HTTPのポートと異なるポートを指定してください");
+            }
+        }
+        
+        let timeouts = &self.server.timeouts;
+        for (field, value) in [
+            ("server.timeouts.state_read_ms", timeouts.state_read_ms),
+            ("server.timeouts.move_ms", timeouts.move_ms),
+            ("server.timeouts.analysis_ms", timeouts.analysis_ms),
+            ("server.timeouts.default_ms", timeouts.default_ms),
+        ] {
+            if value == 0 {
+                violation(field, value.to_string(), "1以上を指定してください");
+            }
+        }
+        
+        if self.ai_battle.session_timeout_minutes <= 0 {
+            violation(
+                "ai_battle.session_timeout_minutes",
+                self.ai_battle.session_timeout_minutes.to_string(),
+                "1以上を指定してください",
+            );
+        }
+        
+        if self.ai_battle.enable_session_cleanup {
+            let interval = self.ai_battle.cleanup_interval_minutes;
+            if interval == 0 {
+                violation("ai_battle.cleanup_interval_minutes", "0".to_string(), "1以上を指定してください");
+            } else if self.ai_battle.session_timeout_minutes > 0
+                && interval > self.ai_battle.session_timeout_minutes as u64
+            {
+                // 間隔がタイムアウトより長いと、期限切れのセッションがタイムアウトの2倍近く残る
+                violation(
+                    "ai_battle.cleanup_interval_minutes",
+                    interval.to_string(),
+                    "ai_battle.session_timeout_minutes 以下を指定してください",
+                );
+            }
         }
         
         if self.ai_service.timeout_ms == 0 {
-            return Err(ConfigError::InvalidValue {
-                field: "ai_service.timeout_ms".to_string(),
-                value: self.ai_service.timeout_ms.to_string(),
-            });
+            violation("ai_service.timeout_ms", "0".to_string(), "1以上を指定してください");
         }
         
-        Ok(())
+        if self.ai_service.service_type == AIServiceType::Http {
+            let endpoint_url = self.ai_service.endpoint_url.as_deref().unwrap_or_default();
+            if !(endpoint_url.starts_with("http://") || endpoint_url.starts_with("https://")) {
+                violation(
+                    "ai_service.endpoint_url",
+                    endpoint_url.to_string(),
+                    "service_type が Http の場合は http(s):// で始まるURLを指定してください",
+                );
+            }
+        }
+        
+        if self.ai_service.enable_caching && self.ai_service.cache_capacity == 0 {
+            violation("ai_service.cache_capacity", "0".to_string(), "キャッシュが有効な場合は1以上を指定してください");
+        }
+        
+        if self.ai_service.queue_workers > 0 && self.ai_service.queue_capacity == 0 {
+            violation("ai_service.queue_capacity", "0".to_string(), "計算待ちキューを使う場合は1以上を指定してください");
+        }
+        
+        if self.fallback.enable_fallback && self.fallback.fallback_ai_service == AIServiceType::Http {
+            // フォールバックのAIサービスには接続先を設定できない
+            violation(
+                "fallback.fallback_ai_service",
+                "Http".to_string(),
+                "フォールバックには Local か Mock を指定してください",
+            );
+        }
+        
+        if self.correspondence.reminder_after_minutes == Some(0) {
+            violation("correspondence.reminder_after_minutes", "0".to_string(), "1以上を指定してください");
+        }
+        
+        if let Some(webhook_url) = &self.correspondence.webhook_url {
+            if WebhookUrl::parse(webhook_url).is_err() {
+                violation("correspondence.webhook_url", webhook_url.clone(), "http:// で始まるURLを指定してください");
+            }
+        }
+        
+        for (index, entry) in self.auth.api_keys.iter().enumerate() {
+            let duplicate = self.auth.api_keys[..index]
+                .iter()
+                .any(|other| other.key == entry.key || other.name == entry.name);
+            if entry.name.is_empty() || entry.key.is_empty() || duplicate {
+                violation(
+                    &format!("auth.api_keys[{}]", index),
+                    entry.name.clone(),
+                    "名前とキーを指定し、他のキーと重複しないようにしてください",
+                );
+            }
+        }
+        
+        if self.auth.jwt_secret.as_ref().is_some_and(|secret| secret.len() < 32) {
+            // 署名鍵がログに残らないよう値は伏せる
+            violation("auth.jwt_secret", "<redacted>".to_string(), "32バイト以上を指定してください");
+        }
+        
+        if self.auth.token_ttl_minutes == 0 {
+            violation("auth.token_ttl_minutes", "0".to_string(), "1以上を指定してください");
+        }
+        
+        violations
+    }
+}
+
+/// 待ち受けるホストとして有効か（IPアドレス、またはラベルが英数字とハイフンからなるホスト名）
+fn is_valid_host(host: &str) -> bool {
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return true;
+    }
+    !host.is_empty()
+        && host.len() <= 253
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// CORSのオリジンとして有効か（`*` またはパスを含まない `http(s)://ホスト[:ポート]`）
+fn is_valid_origin(origin: &str) -> bool {
+    if origin == "*" {
+        return true;
     }
+    let host = origin
+        .strip_prefix("https://")
+        .or_else(|| origin.strip_prefix("http://"));
+    host.is_some_and(|host| !host.is_empty() && !host.contains(['/', '?', '#', ' ']))
 }
 
 /// 後方互換性を保つためのメソッド群
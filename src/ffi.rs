@@ -0,0 +1,248 @@
+//! C言語向けのFFI
+//! HTTP層を使わずにエンジンをモバイルアプリなどへ組み込むための `extern "C"` API。
+//! ヘッダーは `ffi` フィーチャーでのビルド時に cbindgen が `include/reversi.h` へ生成する。
+//! マスは `行 * 8 + 列` の番号（0〜63）、石の色は 0=空き・1=黒・2=白 で表す。
+//! 静的ライブラリが必要な場合は `cargo rustc --lib --release --no-default-features --features ffi --crate-type staticlib` でビルドする。
+
+use std::ptr;
+
+use crate::ai::strategies::{create_seeded_ai_strategy, Difficulty};
+use crate::error::GameError;
+use crate::game::{Cell, GameState, Player, Position, ReversiRules};
+
+/// FFIのバージョン（互換性のない変更をしたときに上げる）
+pub const REVERSI_FFI_VERSION: u32 = 1;
+
+/// 関数の結果
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReversiStatus {
+    Ok = 0,
+    /// NULLポインタが渡された
+    NullPointer = -1,
+    /// マス番号・難易度が範囲外
+    InvalidArgument = -2,
+    /// 合法手ではない
+    InvalidMove = -3,
+    /// 終局している
+    GameFinished = -4,
+    /// AIが着手を計算できなかった
+    AiFailed = -5,
+}
+
+/// 対局（中身はC側からは見えない）
+pub struct ReversiGame {
+    state: GameState,
+}
+
+fn cell_code(cell: Cell) -> u8 {
+    match cell {
+        Cell::Empty => 0,
+        Cell::Black => 1,
+        Cell::White => 2,
+    }
+}
+
+fn player_code(player: Player) -> u8 {
+    cell_code(player.to_cell())
+}
+
+fn position_index(position: Position) -> u8 {
+    (position.row * 8 + position.col) as u8
+}
+
+/// FFIのバージョン
+#[no_mangle]
+pub extern "C" fn reversi_ffi_version() -> u32 {
+    REVERSI_FFI_VERSION
+}
+
+/// 新しい対局（初期配置・黒番）を作る。使い終わったら `reversi_free_game` で解放する
+#[no_mangle]
+pub extern "C" fn reversi_new_game() -> *mut ReversiGame {
+    Box::into_raw(Box::new(ReversiGame { state: GameState::new() }))
+}
+
+/// 対局を解放する（NULLの場合は何もしない）
+///
+/// # Safety
+/// `game` は `reversi_new_game` が返したポインタで、まだ解放していないこと
+#[no_mangle]
+pub unsafe extern "C" fn reversi_free_game(game: *mut ReversiGame) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// 64マス分の石の色を `out` に書き込む（a1からh8へ行ごと）
+///
+/// # Safety
+/// `game` は有効な対局、`out` は64バイト以上書き込めること
+#[no_mangle]
+pub unsafe extern "C" fn reversi_board(game: *const ReversiGame, out: *mut u8) -> ReversiStatus {
+    let (Some(game), false) = (game.as_ref(), out.is_null()) else {
+        return ReversiStatus::NullPointer;
+    };
+    for index in 0..64 {
+        let cell = Position::new(index / 8, index % 8).and_then(|position| game.state.board.get_cell(position));
+        *out.add(index) = cell.map_or(0, cell_code);
+    }
+    ReversiStatus::Ok
+}
+
+/// 手番の色（NULLの場合は0）
+///
+/// # Safety
+/// `game` は有効な対局かNULLであること
+#[no_mangle]
+pub unsafe extern "C" fn reversi_current_player(game: *const ReversiGame) -> u8 {
+    game.as_ref().map_or(0, |game| player_code(game.state.current_player))
+}
+
+/// 手番の合法手を最大 `capacity` 個 `out` に書き込み、合法手の総数を返す
+/// `out` にNULL、`capacity` に0を渡すと数だけを返す
+///
+/// # Safety
+/// `game` は有効な対局かNULL、`out` は `capacity` バイト以上書き込めるかNULLであること
+#[no_mangle]
+pub unsafe extern "C" fn reversi_valid_moves(game: *const ReversiGame, out: *mut u8, capacity: usize) -> usize {
+    let Some(game) = game.as_ref() else {
+        return 0;
+    };
+    let moves = ReversiRules::get_valid_moves(&game.state.board, game.state.current_player);
+    if !out.is_null() {
+        for (index, position) in moves.iter().take(capacity).enumerate() {
+            *out.add(index) = position_index(*position);
+        }
+    }
+    moves.len()
+}
+
+/// 手番の色で着手し、パス判定・終局判定まで行う
+///
+/// # Safety
+/// `game` は有効な対局かNULLであること
+#[no_mangle]
+pub unsafe extern "C" fn reversi_apply_move(game: *mut ReversiGame, index: u8) -> ReversiStatus {
+    let Some(game) = game.as_mut() else {
+        return ReversiStatus::NullPointer;
+    };
+    let Some(position) = Position::new(index as usize / 8, index as usize % 8) else {
+        return ReversiStatus::InvalidArgument;
+    };
+    match ReversiRules::apply_move(&mut game.state, position) {
+        Ok(_) => {
+            game.state.switch_player();
+            ReversiRules::handle_turn(&mut game.state);
+            ReversiStatus::Ok
+        }
+        Err(GameError::GameFinished) => ReversiStatus::GameFinished,
+        Err(_) => ReversiStatus::InvalidMove,
+    }
+}
+
+/// 手番のAIが選ぶ手のマス番号を `out_index` に書き込む
+/// `difficulty` は 0=Easy・1=Medium・2=Hard、`seed` は乱択のシード
+///
+/// # Safety
+/// `game` は有効な対局かNULL、`out_index` は書き込めるかNULLであること
+#[no_mangle]
+pub unsafe extern "C" fn reversi_ai_move(
+    game: *const ReversiGame,
+    difficulty: i32,
+    seed: u64,
+    out_index: *mut u8,
+) -> ReversiStatus {
+    let (Some(game), false) = (game.as_ref(), out_index.is_null()) else {
+        return ReversiStatus::NullPointer;
+    };
+    let difficulty = match difficulty {
+        0 => Difficulty::Beginner,
+        1 => Difficulty::Intermediate,
+        2 => Difficulty::Advanced,
+        _ => return ReversiStatus::InvalidArgument,
+    };
+    if game.state.is_finished() {
+        return ReversiStatus::GameFinished;
+    }
+    match create_seeded_ai_strategy(difficulty, Some(seed)).calculate_move(&game.state) {
+        Ok(position) => {
+            ptr::write(out_index, position_index(position));
+            ReversiStatus::Ok
+        }
+        Err(_) => ReversiStatus::AiFailed,
+    }
+}
+
+/// 終局しているか（NULLの場合はfalse）
+///
+/// # Safety
+/// `game` は有効な対局かNULLであること
+#[no_mangle]
+pub unsafe extern "C" fn reversi_is_finished(game: *const ReversiGame) -> bool {
+    game.as_ref().is_some_and(|game| game.state.is_finished())
+}
+
+/// 黒・白の石数を書き込む
+///
+/// # Safety
+/// `game` は有効な対局、`black`・`white` は書き込めること
+#[no_mangle]
+pub unsafe extern "C" fn reversi_score(game: *const ReversiGame, black: *mut u8, white: *mut u8) -> ReversiStatus {
+    let (Some(game), false, false) = (game.as_ref(), black.is_null(), white.is_null()) else {
+        return ReversiStatus::NullPointer;
+    };
+    let (black_count, white_count) = game.state.get_score();
+    ptr::write(black, black_count);
+    ptr::write(white, white_count);
+    ReversiStatus::Ok
+}
+
+/// 勝者の色（進行中・引き分け・NULLの場合は0）
+///
+/// # Safety
+/// `game` は有効な対局かNULLであること
+#[no_mangle]
+pub unsafe extern "C" fn reversi_winner(game: *const ReversiGame) -> u8 {
+    match game.as_ref() {
+        Some(game) if game.state.is_finished() => {
+            ReversiRules::determine_winner(&game.state.board, game.state.variant).map_or(0, player_code)
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_play_through_c_api() {
+        unsafe {
+            assert_eq!(reversi_ffi_version(), REVERSI_FFI_VERSION);
+            let game = reversi_new_game();
+            let mut moves = [0u8; 64];
+            assert_eq!(reversi_valid_moves(game, moves.as_mut_ptr(), moves.len()), 4);
+            assert_eq!(&moves[..4], &[19, 26, 37, 44]);
+            assert_eq!(reversi_valid_moves(game, ptr::null_mut(), 0), 4);
+
+            assert_eq!(reversi_apply_move(game, 0), ReversiStatus::InvalidMove);
+            assert_eq!(reversi_apply_move(game, 64), ReversiStatus::InvalidArgument);
+            assert_eq!(reversi_apply_move(game, 37), ReversiStatus::Ok);
+            assert_eq!(reversi_current_player(game), 2);
+
+            let (mut black, mut white) = (0u8, 0u8);
+            assert_eq!(reversi_score(game, &mut black, &mut white), ReversiStatus::Ok);
+            assert_eq!((black, white), (4, 1));
+
+            let mut index = 0u8;
+            assert_eq!(reversi_ai_move(game, 0, 11, &mut index), ReversiStatus::Ok);
+            let count = reversi_valid_moves(game, moves.as_mut_ptr(), moves.len());
+            assert!(moves[..count].contains(&index));
+            assert_eq!(reversi_ai_move(game, 3, 11, &mut index), ReversiStatus::InvalidArgument);
+            assert_eq!(reversi_apply_move(ptr::null_mut(), 19), ReversiStatus::NullPointer);
+
+            reversi_free_game(game);
+        }
+    }
+}
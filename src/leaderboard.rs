@@ -0,0 +1,235 @@
+//! リーダーボードモジュール
+//! アーカイブの対局をプレイヤーごとに集計し、勝敗数・AIの難易度ごとの勝率とEloレーティングの順位表を作る。
+//! 集計期間は直近24時間（daily）・直近7日間（weekly）・全期間（all_time）から選ぶ。
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::api::ai_battle::{AiBattleError, AiDifficulty};
+use crate::archive::PlayerTally;
+use crate::ratings::Ratings;
+
+/// 既定の表示件数
+pub const DEFAULT_LEADERBOARD_LIMIT: usize = 20;
+
+/// 表示件数の上限
+pub const MAX_LEADERBOARD_LIMIT: usize = 100;
+
+/// 集計期間
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardPeriod {
+    /// 直近24時間
+    Daily,
+    /// 直近7日間
+    Weekly,
+    #[default]
+    AllTime,
+}
+
+impl LeaderboardPeriod {
+    /// 集計の対象となる終局日時の下限（全期間ならNone）
+    pub fn since(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            LeaderboardPeriod::Daily => Some(now - Duration::days(1)),
+            LeaderboardPeriod::Weekly => Some(now - Duration::days(7)),
+            LeaderboardPeriod::AllTime => None,
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "daily" | "day" => Some(LeaderboardPeriod::Daily),
+            "weekly" | "week" => Some(LeaderboardPeriod::Weekly),
+            "all_time" | "all-time" | "all" => Some(LeaderboardPeriod::AllTime),
+            _ => None,
+        }
+    }
+}
+
+/// リーダーボードのクエリパラメータ
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct LeaderboardQuery {
+    /// `daily` / `weekly` / `all_time`（既定）
+    pub period: Option<String>,
+    /// 表示件数（1〜100、既定は20）
+    pub limit: Option<String>,
+}
+
+impl LeaderboardQuery {
+    pub fn parse(&self) -> Result<(LeaderboardPeriod, usize), AiBattleError> {
+        let period = match self.period.as_deref() {
+            Some(value) => LeaderboardPeriod::parse(value)
+                .ok_or_else(|| AiBattleError::BadRequest { details: format!("不明な集計期間です: {}", value) })?,
+            None => LeaderboardPeriod::default(),
+        };
+        let limit = match self.limit.as_deref() {
+            Some(value) => value
+                .parse::<usize>()
+                .ok()
+                .filter(|limit| (1..=MAX_LEADERBOARD_LIMIT).contains(limit))
+                .ok_or_else(|| AiBattleError::BadRequest {
+                    details: format!("limit は1〜{}で指定してください: {}", MAX_LEADERBOARD_LIMIT, value),
+                })?,
+            None => DEFAULT_LEADERBOARD_LIMIT,
+        };
+        Ok((period, limit))
+    }
+}
+
+/// AIの難易度ごとの成績
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct DifficultyRecord {
+    pub difficulty: AiDifficulty,
+    pub games: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// 勝率（引き分けは0.5勝として数える）
+    pub win_rate: f64,
+}
+
+/// 順位表の1行
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct LeaderboardEntry {
+    pub rank: u32,
+    pub player_id: Uuid,
+    /// 現在のEloレーティング（期間によらない）
+    pub rating: i32,
+    /// 期間内の対局数（AI戦と対人戦の合計）
+    pub games: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// 勝率（引き分けは0.5勝として数える）
+    pub win_rate: f64,
+    /// AI戦の難易度ごとの成績（難易度の低い順、対局のない難易度は含まない）
+    pub by_difficulty: Vec<DifficultyRecord>,
+}
+
+/// リーダーボード（レーティングの高い順）
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LeaderboardResponse {
+    pub period: LeaderboardPeriod,
+    /// 集計の対象とした終局日時の下限（全期間ならnull）
+    pub since: Option<DateTime<Utc>>,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+fn win_rate(wins: u32, draws: u32, games: u32) -> f64 {
+    if games == 0 {
+        return 0.0;
+    }
+    (wins as f64 + draws as f64 * 0.5) / games as f64
+}
+
+/// 期間内に対局したプレイヤーを、レーティング・勝ち数・勝率の順に並べる
+pub fn build_leaderboard(
+    tallies: Vec<PlayerTally>,
+    ratings: &Ratings,
+    period: LeaderboardPeriod,
+    since: Option<DateTime<Utc>>,
+    limit: usize,
+) -> LeaderboardResponse {
+    let mut players: HashMap<Uuid, Vec<PlayerTally>> = HashMap::new();
+    for tally in tallies {
+        players.entry(tally.player_id).or_default().push(tally);
+    }
+
+    let mut entries: Vec<LeaderboardEntry> = players
+        .into_iter()
+        .map(|(player_id, tallies)| {
+            let wins = tallies.iter().map(|tally| tally.wins).sum();
+            let losses = tallies.iter().map(|tally| tally.losses).sum();
+            let draws = tallies.iter().map(|tally| tally.draws).sum();
+            let games = wins + losses + draws;
+
+            let mut by_difficulty: Vec<DifficultyRecord> = tallies
+                .iter()
+                .filter_map(|tally| {
+                    let difficulty = tally.opponent?;
+                    Some(DifficultyRecord {
+                        difficulty,
+                        games: tally.games(),
+                        wins: tally.wins,
+                        losses: tally.losses,
+                        draws: tally.draws,
+                        win_rate: win_rate(tally.wins, tally.draws, tally.games()),
+                    })
+                })
+                .collect();
+            by_difficulty.sort_by_key(|record| record.difficulty);
+
+            LeaderboardEntry {
+                rank: 0,
+                player_id,
+                rating: ratings.get(player_id).rating,
+                games,
+                wins,
+                losses,
+                draws,
+                win_rate: win_rate(wins, draws, games),
+                by_difficulty,
+            }
+        })
+        .collect();
+
+    entries.sort_by(|a, b| {
+        b.rating
+            .cmp(&a.rating)
+            .then(b.wins.cmp(&a.wins))
+            .then(b.win_rate.total_cmp(&a.win_rate))
+            .then(a.player_id.cmp(&b.player_id))
+    });
+    entries.truncate(limit);
+    for (index, entry) in entries.iter_mut().enumerate() {
+        entry.rank = index as u32 + 1;
+    }
+
+    LeaderboardResponse { period, since, entries }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tally(player_id: Uuid, opponent: Option<AiDifficulty>, wins: u32, losses: u32, draws: u32) -> PlayerTally {
+        PlayerTally { player_id, opponent, wins, losses, draws }
+    }
+
+    #[test]
+    fn test_query_parsing() {
+        let query = |period: Option<&str>, limit: Option<&str>| LeaderboardQuery {
+            period: period.map(str::to_string),
+            limit: limit.map(str::to_string),
+        };
+        assert_eq!(query(None, None).parse().unwrap(), (LeaderboardPeriod::AllTime, DEFAULT_LEADERBOARD_LIMIT));
+        assert_eq!(query(Some("Weekly"), Some("5")).parse().unwrap(), (LeaderboardPeriod::Weekly, 5));
+        assert!(matches!(query(Some("monthly"), None).parse(), Err(AiBattleError::BadRequest { .. })));
+        assert!(matches!(query(None, Some("0")).parse(), Err(AiBattleError::BadRequest { .. })));
+    }
+
+    #[test]
+    fn test_entries_are_ranked_by_rating_then_wins() {
+        let (alice, bob, carol) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let tallies = vec![
+            tally(alice, Some(AiDifficulty::Hard), 1, 1, 0),
+            tally(alice, Some(AiDifficulty::Easy), 2, 0, 1),
+            tally(alice, None, 0, 1, 0),
+            tally(bob, Some(AiDifficulty::Medium), 1, 0, 0),
+            tally(carol, Some(AiDifficulty::Easy), 0, 2, 0),
+        ];
+        let board = build_leaderboard(tallies, &Ratings::default(), LeaderboardPeriod::AllTime, None, 2);
+
+        // 全員が初期レーティングのため勝ち数の多い順になり、3人目は件数の上限で除かれる
+        assert_eq!(board.entries.iter().map(|entry| (entry.rank, entry.player_id)).collect::<Vec<_>>(), vec![(1, alice), (2, bob)]);
+        let alice = &board.entries[0];
+        assert_eq!((alice.games, alice.wins, alice.losses, alice.draws), (6, 3, 2, 1));
+        assert!((alice.win_rate - 3.5 / 6.0).abs() < 1e-9);
+        let difficulties: Vec<_> = alice.by_difficulty.iter().map(|record| (record.difficulty, record.win_rate)).collect();
+        assert_eq!(difficulties, vec![(AiDifficulty::Easy, 2.5 / 3.0), (AiDifficulty::Hard, 0.5)]);
+    }
+}
@@ -1,9 +1,50 @@
-pub mod game;
+pub use reversi_core::{error, game, serde_util, wthor};
 pub mod ai;
+
+#[cfg(feature = "server")]
 pub mod api;
+#[cfg(feature = "server")]
 pub mod session;
-pub mod error;
+#[cfg(feature = "server")]
 pub mod config;
+#[cfg(feature = "server")]
+pub mod self_test;
+#[cfg(feature = "server")]
+pub mod persistence;
+#[cfg(feature = "server")]
+pub mod archive;
+#[cfg(feature = "server")]
+pub mod positions;
+#[cfg(feature = "server")]
+pub mod maintenance;
+#[cfg(feature = "server")]
+pub mod accounts;
+#[cfg(feature = "server")]
+pub mod ratings;
+#[cfg(feature = "server")]
+pub mod leaderboard;
+#[cfg(feature = "server")]
+pub mod tournament;
+#[cfg(feature = "server")]
+pub mod stats;
+#[cfg(feature = "server")]
+pub mod tls;
+#[cfg(feature = "server")]
+pub mod selfplay;
+#[cfg(feature = "server")]
+pub mod replay;
+#[cfg(feature = "server")]
+pub mod simulation;
+#[cfg(feature = "server")]
+pub mod puzzles;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 
 pub use error::{GameError, AIError, PersistenceError, Result};
-pub use config::{Config, SystemLimits};
\ No newline at end of file
+#[cfg(feature = "server")]
+pub use config::{Config, SystemLimits};
@@ -1,29 +1,84 @@
 //! Reversi APIサーバーのエントリポイント
-//! 設定読み込み、AIサービス初期化、HTTPサーバー起動を行う。
+//! コマンドライン引数を解釈し、既定ではサーバーを起動する（設定読み込み、AIサービス初期化、HTTPサーバー起動）。
+
+mod cli;
 
 use std::sync::Arc;
 
+use clap::Parser;
+
 use Reversi::{
-    api::{routes::{create_router, create_ai_battle_router}, handlers::AppState},
-    api::ai_battle::{ConfigurableAiBattleService, config_utils},
+    api::{routes::{create_router_with_timeouts, create_ai_battle_router_with_timeouts}, handlers::AppState},
+    api::auth::{self, ApiKeyRegistry},
+    api::middleware::apply_cors,
+    api::ai_battle::{ConfigurableAiBattleService, spawn_clock_sweeper},
     config::Config,
+    session::store::open_session_store,
+    archive::open_game_archive,
+    accounts::open_account_store,
+    ratings::open_rating_store,
+    maintenance::Maintenance,
+    tls,
+    self_test::{self, CheckStatus},
 };
 use tokio::net::TcpListener;
 
-/// メイン関数 - サーバーの初期化と起動を担当
+use cli::{BenchArgs, BenchCommand, Cli, Command, ConfigCommand};
+
+/// メイン関数 - サブコマンドを振り分ける
 #[tokio::main]
 async fn main() {
-    // 設定ファイルと環境変数から統合設定を読み込み
-    let config = Config::load();
-    if let Err(e) = config.validate() {
-        eprintln!("設定エラー: {}", e);
-        eprintln!("デフォルト設定を生成: cargo run -- --generate-config");
+    let cli = Cli::parse();
+    
+    let code = match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => {
+            // 既定値・設定ファイル・環境変数・コマンドライン引数を重ねて統合設定を読み込み
+            let config = match cli.config.load() {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            };
+            
+            // ポートをバインドせずにエンジンを検証して終了する
+            if cli.self_test {
+                run_self_test(&config).await;
+            }
+            serve(config).await;
+            0
+        }
+        Command::GenerateConfig { force } => cli::generate_config(force),
+        Command::ValidateConfig { file } => cli::validate_config(file, &cli.config),
+        Command::Config(ConfigCommand::Show { effective }) => cli::show_config(effective, &cli.config),
+        Command::Selfplay(args) => cli::selfplay(args).await,
+        Command::Simulate(args) => cli::simulate(args).await,
+        Command::Bench(BenchArgs { command: Some(BenchCommand::Perft(args)), .. }) => cli::perft(args),
+        Command::Bench(args) => cli::bench(args).await,
+        Command::Replay(args) => cli::replay(args, &cli.config).await,
+    };
+    std::process::exit(code);
+}
+
+/// サーバーの初期化と起動を担当
+async fn serve(config: Config) {
+    let violations = config.violations();
+    if !violations.is_empty() {
+        eprintln!("設定エラー（{}件）:", violations.len());
+        for violation in &violations {
+            eprintln!("  {}", violation);
+        }
+        eprintln!("デフォルト設定を生成: cargo run -- generate-config");
         std::process::exit(1);
     }
     
     println!("設定読み込み完了:");
     println!("  サーバー: {}:{}", config.server.host, config.server.port);
     println!("  データベース: {}", config.database.url);
+    println!("  セッションストア: {:?}", config.database.session_store);
+    println!("  タイムアウト: 取得 {}ms / 着手 {}ms / 解析 {}ms / その他 {}ms",
+        config.server.timeouts.state_read_ms, config.server.timeouts.move_ms,
+        config.server.timeouts.analysis_ms, config.server.timeouts.default_ms);
     println!("  AIサービス: {:?}", config.ai_service.service_type);
     println!("  フォールバック: {}", config.fallback.enable_fallback);
     println!("  最大セッション数: {}", config.ai_battle.max_sessions);
@@ -37,11 +92,126 @@ async fn main() {
         }
     };
     
+    // 進行中の対局を復元し、以降の変更をデータベースに書き込む
+    match open_session_store(&config.database).await {
+        Ok(Some(store)) => match configurable_service.session_manager().attach_store(store).await {
+            Ok(summary) => {
+                println!("  進行中の対局: {}件を復元 (終局済み{}件は読み込まず)", summary.restored, summary.finished);
+                if summary.over_capacity > 0 {
+                    eprintln!("警告: 最大セッション数を超えたため、進行中の対局{}件を復元できませんでした", summary.over_capacity);
+                }
+                
+                // AIの思考中に停止した対局の応手をバックグラウンドで指し直す
+                let service = Arc::clone(configurable_service.get_service());
+                tokio::spawn(async move {
+                    let resumed = service.resume_pending_ai_turns().await;
+                    if resumed > 0 {
+                        println!("AIの応手を{}局で再開しました", resumed);
+                    }
+                });
+            }
+            Err(e) => eprintln!("警告: セッションの復元に失敗: {}", e),
+        },
+        Ok(None) => {}
+        Err(e) => eprintln!("警告: データベースに接続できないため、セッションはメモリのみに保存します: {}", e),
+    }
+    
+    match open_game_archive(&config.database).await {
+        Ok(archive) => configurable_service.get_service().attach_archive(archive),
+        Err(e) => eprintln!("警告: データベースに接続できないため、対局のアーカイブはメモリのみに保存します: {}", e),
+    }
+    
+    match open_account_store(&config.database).await {
+        Ok(store) => configurable_service.get_service().accounts().attach_store(store),
+        Err(e) => eprintln!("警告: データベースに接続できないため、アカウントはメモリのみに保存します: {}", e),
+    }
+    match open_rating_store(&config.database).await {
+        Ok(store) => match configurable_service.get_service().ratings().attach_store(store).await {
+            Ok(players) => println!("  レーティング: {}人分を読み込み", players),
+            Err(e) => eprintln!("警告: レーティングの読み込みに失敗: {}", e),
+        },
+        Err(e) => eprintln!("警告: データベースに接続できないため、レーティングはメモリのみに保存します: {}", e),
+    }
+    if config.auth.jwt_secret.is_none() {
+        eprintln!("警告: JWTの署名鍵が未設定のため、再起動すると発行済みのアクセストークンは無効になります");
+    }
+    
+    // アーカイブの棋譜から局面ハッシュの統計と毎日の問題を復元する
+    match configurable_service.get_service().rebuild_position_index().await {
+        Ok(games) => println!(
+            "  局面インデックス: {}局から{}局面・{}問を登録",
+            games,
+            configurable_service.get_service().positions().len(),
+            configurable_service.get_service().puzzles().len(),
+        ),
+        Err(e) => eprintln!("警告: 局面インデックスの構築に失敗: {}", e),
+    }
+    
     let state = AppState::new_with_configurable_service(Arc::clone(&configurable_service));
     
-    let app = create_router()
+    // 持ち時間のある対局の時間切れ判定と通信対局の催促
+    spawn_clock_sweeper(Arc::clone(&state.ai_battle_service), &state.health);
+    
+    // プライマリAIの死活監視（利用できない間はフォールバックAIで着手を計算する）
+    if configurable_service.spawn_health_monitor(&state.health) {
+        println!("  AI死活監視: {}msごと", config.fallback.health_check_interval_ms);
+    }
+    
+    // 非アクティブなセッションの定期削除（サーバー停止まで保持する）
+    let maintenance = Maintenance::start(&config.ai_battle, Arc::clone(configurable_service.session_manager()), &state.health);
+    if maintenance.task_names().is_empty() {
+        println!("  セッションクリーンアップ: 無効");
+    } else {
+        println!("  セッションクリーンアップ: {}分ごと", config.ai_battle.cleanup_interval_minutes);
+    }
+    
+    let timeouts = &config.server.timeouts;
+    let app = create_router_with_timeouts(timeouts)
         .with_state(state.clone())
-        .merge(create_ai_battle_router(state));
+        .merge(create_ai_battle_router_with_timeouts(state, timeouts));
+    
+    // APIキーが登録されている場合のみ /api 以下を保護する
+    let api_keys = ApiKeyRegistry::from_config(&config.auth);
+    if api_keys.is_enabled() {
+        println!("  APIキー認証: 有効 ({}件)", api_keys.len());
+    } else {
+        println!("  APIキー認証: 無効");
+    }
+    
+    // gRPC APIはHTTPと別のポートで待ち受け、同じAPIキーで認証する
+    match config.server.grpc_port {
+        #[cfg(feature = "grpc")]
+        Some(grpc_port) => {
+            let address = format!("{}:{}", config.server.host, grpc_port)
+                .parse()
+                .unwrap_or_else(|e| {
+                    eprintln!("gRPCのアドレスが不正です {}:{}: {}", config.server.host, grpc_port, e);
+                    std::process::exit(1);
+                });
+            let service = Arc::clone(configurable_service.get_service());
+            let grpc_api_keys = api_keys.clone();
+            tokio::spawn(async move {
+                if let Err(e) = Reversi::api::grpc::serve(service, grpc_api_keys, address).await {
+                    eprintln!("gRPCサーバーが停止しました: {}", e);
+                }
+            });
+            println!("  gRPC: ポート{}", grpc_port);
+        }
+        #[cfg(not(feature = "grpc"))]
+        Some(_) => eprintln!("警告: grpc フィーチャーを有効にせずにビルドしたため、server.grpc_port は無視します"),
+        None => {}
+    }
+    let app = auth::protect(app, api_keys);
+    
+    // CORSは認証より外側に適用し、プリフライトを認証前に応答する
+    if !config.server.enable_cors {
+        println!("  CORS: 無効");
+    } else if config.server.allowed_origins.is_empty() {
+        println!("  CORS: 全てのオリジンを許可");
+    } else {
+        println!("  CORS: {}", config.server.allowed_origins.join(", "));
+    }
+    let app = apply_cors(app, &config.server);
     
     let bind_address = format!("{}:{}", config.server.host, config.server.port);
     let listener = TcpListener::bind(&bind_address)
@@ -51,7 +221,31 @@ async fn main() {
             std::process::exit(1);
         });
     
-    println!("Reversi APIサーバー開始: {}", bind_address);
+    // TLSが設定されていればHTTPSで待ち受ける（リダイレクト用のHTTPポートはサーバー停止まで保持する）
+    let mut tls_setup = None;
+    if let Some(tls) = &config.server.tls {
+        let rustls_config = tls::load_rustls_config(tls).await.unwrap_or_else(|e| {
+            eprintln!("TLS証明書の読み込み失敗 ({}, {}): {}", tls.cert_path, tls.key_path, e);
+            std::process::exit(1);
+        });
+        let redirect = match tls.redirect_http_port {
+            Some(http_port) => {
+                let task = tls::spawn_https_redirect(&config.server.host, http_port, config.server.port)
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("HTTPリダイレクト用ポートのバインド失敗 {}: {}", http_port, e);
+                        std::process::exit(1);
+                    });
+                println!("  HTTPSリダイレクト: ポート{} → {}", http_port, config.server.port);
+                Some(task)
+            }
+            None => None,
+        };
+        tls_setup = Some((rustls_config, redirect));
+    }
+    
+    let scheme = if tls_setup.is_some() { "https" } else { "http" };
+    println!("Reversi APIサーバー開始: {}://{}", scheme, bind_address);
     
     if !configurable_service.check_primary_service_health().await {
         eprintln!("警告: プライマリAIサービスが不健全");
@@ -65,7 +259,37 @@ async fn main() {
     println!("サーバー稼働中 (Ctrl+C で停止)");
     
     // Axumサーバーを開始し、リクエストの処理を開始
-    axum::serve(listener, app)
-        .await
-        .expect("Failed to start server");
-}
\ No newline at end of file
+    match tls_setup {
+        Some((rustls_config, _redirect)) => {
+            let listener = listener.into_std().expect("Failed to start server");
+            axum_server::from_tcp_rustls(listener, rustls_config)
+                .serve(app.into_make_service())
+                .await
+                .expect("Failed to start server");
+        }
+        None => axum::serve(listener, app)
+            .await
+            .expect("Failed to start server"),
+    }
+}
+
+/// セルフテストを実行し、結果に応じた終了コードでプロセスを終了する
+async fn run_self_test(config: &Config) -> ! {
+    let report = self_test::run(config).await;
+    
+    for check in &report.checks {
+        let label = match check.status {
+            CheckStatus::Passed => "OK",
+            CheckStatus::Skipped => "SKIP",
+            CheckStatus::Failed => "FAIL",
+        };
+        println!("[{:>4}] {}: {}", label, check.name, check.detail);
+    }
+    
+    if report.is_success() {
+        println!("セルフテスト成功");
+        std::process::exit(0);
+    }
+    eprintln!("セルフテスト失敗");
+    std::process::exit(1);
+}
@@ -0,0 +1,101 @@
+//! 保守タスクモジュール
+//! サーバーの稼働中に定期実行する保守処理をバックグラウンドタスクとして起動し、所有する。
+//! 現在は非アクティブなセッションのクリーンアップ（`AiBattleConfig.enable_session_cleanup`）を扱う。
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::api::health::HealthRegistry;
+use crate::config::AiBattleConfig;
+use crate::session::AiBattleSessionManager;
+
+/// セッションクリーンアップのタスク名（ヘルスチェックに表示される）
+pub const SESSION_CLEANUP_TASK: &str = "session-cleanup";
+
+/// 起動した保守タスクの一覧
+/// 破棄するとタスクも停止する
+#[derive(Debug, Default)]
+pub struct Maintenance {
+    tasks: Vec<(&'static str, JoinHandle<()>)>,
+}
+
+impl Maintenance {
+    /// 設定で有効になっている保守タスクを起動する
+    pub fn start(config: &AiBattleConfig, sessions: Arc<AiBattleSessionManager>, health: &HealthRegistry) -> Self {
+        let mut maintenance = Self::default();
+        if config.enable_session_cleanup && config.cleanup_interval_minutes > 0 {
+            let interval = Duration::from_secs(config.cleanup_interval_minutes * 60);
+            maintenance.tasks.push((SESSION_CLEANUP_TASK, spawn_session_cleanup(sessions, interval, health)));
+        }
+        maintenance
+    }
+
+    /// 起動中のタスク名
+    pub fn task_names(&self) -> Vec<&'static str> {
+        self.tasks.iter().map(|(name, _)| *name).collect()
+    }
+}
+
+impl Drop for Maintenance {
+    fn drop(&mut self) {
+        for (_, task) in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// 一定間隔で非アクティブなセッションを削除するタスクを起動する
+/// 起動直後は復元したばかりのセッションを対象にしないよう、最初の間隔が経過してから実行する
+pub fn spawn_session_cleanup(
+    sessions: Arc<AiBattleSessionManager>,
+    interval: Duration,
+    health: &HealthRegistry,
+) -> JoinHandle<()> {
+    let heartbeat = health.register_task(SESSION_CLEANUP_TASK, interval);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let removed = sessions.cleanup_inactive_sessions().await;
+            if removed > 0 {
+                println!("セッションクリーンアップ: 非アクティブなセッションを{}件削除 (残り{}件)", removed, sessions.session_count());
+            }
+            heartbeat.beat();
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ai_battle::AiDifficulty;
+
+    #[tokio::test]
+    async fn test_cleanup_task_removes_inactive_sessions() {
+        // タイムアウト0分のため、作成済みのセッションは全て非アクティブ扱いになる
+        let sessions = Arc::new(AiBattleSessionManager::with_timeout(10, 0));
+        let session_id = sessions.create_session(AiDifficulty::Easy).await.unwrap();
+        let health = HealthRegistry::new(Arc::clone(&sessions));
+
+        let task = spawn_session_cleanup(Arc::clone(&sessions), Duration::from_millis(10), &health);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        task.abort();
+
+        assert!(!sessions.session_exists(&session_id));
+        assert!(health.check().await.background_tasks.iter().any(|task| task.name == SESSION_CLEANUP_TASK && task.alive));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_cleanup_starts_no_tasks() {
+        let sessions = Arc::new(AiBattleSessionManager::new(10));
+        let health = HealthRegistry::new(Arc::clone(&sessions));
+
+        let config = AiBattleConfig { enable_session_cleanup: false, ..AiBattleConfig::default() };
+        assert!(Maintenance::start(&config, Arc::clone(&sessions), &health).task_names().is_empty());
+
+        let config = AiBattleConfig::default();
+        assert_eq!(Maintenance::start(&config, sessions, &health).task_names(), vec![SESSION_CLEANUP_TASK]);
+    }
+}
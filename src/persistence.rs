@@ -0,0 +1,342 @@
+//! 永続化モジュール
+//! `DatabaseConfig` の接続先（SQLite）にセッションと着手履歴を保存し、
+//! サーバーの再起動後にセッションを復元できるようにする。
+//! セッションマネージャーからの書き込みは専用タスクが受け付け順に反映する（ライトビハインド）。
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::Row;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+use uuid::Uuid;
+
+use crate::api::ai_battle::{move_notation, AiBattleSession, MoveRecord};
+use crate::config::DatabaseConfig;
+use crate::error::PersistenceError;
+use crate::game::{Player, Position};
+use crate::session::SessionStore;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS sessions (
+    id TEXT PRIMARY KEY,
+    data TEXT NOT NULL,
+    finished INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS moves (
+    session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    seq INTEGER NOT NULL,
+    player TEXT NOT NULL,
+    row INTEGER,
+    col INTEGER,
+    played_at TEXT NOT NULL,
+    thinking_time_ms INTEGER,
+    PRIMARY KEY (session_id, seq)
+);
+CREATE INDEX IF NOT EXISTS sessions_updated_at ON sessions (updated_at);
+"#;
+
+pub(crate) fn database_error(error: sqlx::Error) -> PersistenceError {
+    PersistenceError::DatabaseError { message: error.to_string() }
+}
+
+fn serialization_error(error: serde_json::Error) -> PersistenceError {
+    PersistenceError::SerializationError { message: error.to_string() }
+}
+
+fn player_name(player: Player) -> &'static str {
+    match player {
+        Player::Black => "black",
+        Player::White => "white",
+    }
+}
+
+/// SQLiteにセッションを保存するストア
+/// セッション本体はJSONで、着手履歴は1手1行で保存する
+#[derive(Debug, Clone)]
+pub struct SqliteSessionStore {
+    pool: SqlitePool,
+}
+
+impl SqliteSessionStore {
+    /// 接続してスキーマを作成する（データベースファイルがなければ作成する）
+    pub async fn connect(config: &DatabaseConfig) -> Result<Self, PersistenceError> {
+        let options = SqliteConnectOptions::from_str(&config.url).map_err(database_error)?.create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(config.max_connections)
+            .acquire_timeout(config.connection_timeout)
+            .connect_with(options)
+            .await.map_err(database_error)?;
+        sqlx::raw_sql(SCHEMA).execute(&pool).await.map_err(database_error)?;
+
+        Ok(Self { pool })
+    }
+
+    /// 同じデータベースに保存する他のストアと接続を共有するためのプール
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    /// セッションと着手履歴を保存する（既存の場合は置き換える）
+    pub async fn save_session(&self, session: &AiBattleSession) -> Result<(), PersistenceError> {
+        let id = session.id.to_string();
+        let data = serde_json::to_string(session).map_err(serialization_error)?;
+
+        let mut tx = self.pool.begin().await.map_err(database_error)?;
+        sqlx::query(
+            "INSERT INTO sessions (id, data, finished, created_at, updated_at) VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET data = excluded.data, finished = excluded.finished, updated_at = excluded.updated_at",
+        )
+        .bind(&id)
+        .bind(data)
+        .bind(session.is_finished())
+        .bind(session.created_at)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await.map_err(database_error)?;
+
+        // 待ったで履歴が短くなる場合があるため、履歴は毎回書き直す
+        // 人間の着手を含む全記録を、着手の通し番号をキーにして保存する
+        sqlx::query("DELETE FROM moves WHERE session_id = ?").bind(&id).execute(&mut *tx).await.map_err(database_error)?;
+        for record in session.transcript_records() {
+            sqlx::query(
+                "INSERT INTO moves (session_id, seq, player, row, col, played_at, thinking_time_ms) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&id)
+            .bind(record.seq as i64)
+            .bind(player_name(record.player))
+            .bind(record.position.map(|position| position.row as i64))
+            .bind(record.position.map(|position| position.col as i64))
+            .bind(record.timestamp)
+            .bind(record.thinking_time_ms.map(|ms| ms as i64))
+            .execute(&mut *tx)
+            .await.map_err(database_error)?;
+        }
+        tx.commit().await.map_err(database_error)?;
+
+        Ok(())
+    }
+
+    pub async fn load_session(&self, session_id: Uuid) -> Result<Option<AiBattleSession>, PersistenceError> {
+        let row = sqlx::query("SELECT data FROM sessions WHERE id = ?")
+            .bind(session_id.to_string())
+            .fetch_optional(&self.pool)
+            .await.map_err(database_error)?;
+
+        row.map(|row| Self::decode_session(row.get("data"))).transpose()
+    }
+
+    /// 保存されている全セッションを更新の新しい順に読み込む
+    pub async fn load_sessions(&self) -> Result<Vec<AiBattleSession>, PersistenceError> {
+        let rows = sqlx::query("SELECT data FROM sessions ORDER BY updated_at DESC")
+            .fetch_all(&self.pool)
+            .await.map_err(database_error)?;
+
+        rows.into_iter().map(|row| Self::decode_session(row.get("data"))).collect()
+    }
+
+    /// 着手履歴を着手順に読み込む
+    pub async fn load_moves(&self, session_id: Uuid) -> Result<Vec<MoveRecord>, PersistenceError> {
+        let rows = sqlx::query(
+            "SELECT seq, player, row, col, played_at, thinking_time_ms FROM moves WHERE session_id = ? ORDER BY seq",
+        )
+        .bind(session_id.to_string())
+        .fetch_all(&self.pool)
+        .await.map_err(database_error)?;
+
+        rows.into_iter()
+            .map(|row| {
+                let player = match row.get::<String, _>("player").as_str() {
+                    "black" => Player::Black,
+                    "white" => Player::White,
+                    other => {
+                        return Err(PersistenceError::SerializationError { message: format!("不明なプレイヤー: {}", other) })
+                    }
+                };
+                let position = match (row.get::<Option<i64>, _>("row"), row.get::<Option<i64>, _>("col")) {
+                    (Some(row), Some(col)) => Some(Position { row: row as usize, col: col as usize }),
+                    _ => None,
+                };
+                Ok(MoveRecord {
+                    seq: row.get::<i64, _>("seq") as u64,
+                    player,
+                    position,
+                    notation: move_notation(position),
+                    timestamp: row.get::<DateTime<Utc>, _>("played_at"),
+                    thinking_time_ms: row.get::<Option<i64>, _>("thinking_time_ms").map(|ms| ms as u64),
+                    ai_params: None,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn delete_session(&self, session_id: Uuid) -> Result<(), PersistenceError> {
+        sqlx::query("DELETE FROM sessions WHERE id = ?")
+            .bind(session_id.to_string())
+            .execute(&self.pool)
+            .await.map_err(database_error)?;
+        Ok(())
+    }
+
+    /// 保存時点でAIが思考中だったセッションは、再開後に思考中のまま残らないようにする
+    /// 通し番号の導入前に保存されたセッションには番号を振り直す
+    fn decode_session(data: String) -> Result<AiBattleSession, PersistenceError> {
+        let mut session: AiBattleSession = serde_json::from_str(&data).map_err(serialization_error)?;
+        session.ai_thinking = false;
+        session.ensure_move_seqs();
+        Ok(session)
+    }
+}
+
+#[async_trait]
+impl SessionStore for SqliteSessionStore {
+    fn backend_name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn get(&self, session_id: Uuid) -> Result<Option<AiBattleSession>, PersistenceError> {
+        self.load_session(session_id).await
+    }
+
+    async fn put(&self, session: &AiBattleSession) -> Result<(), PersistenceError> {
+        self.save_session(session).await
+    }
+
+    async fn remove(&self, session_id: Uuid) -> Result<(), PersistenceError> {
+        self.delete_session(session_id).await
+    }
+
+    async fn list(&self) -> Result<Vec<AiBattleSession>, PersistenceError> {
+        self.load_sessions().await
+    }
+
+    async fn ping(&self) -> Result<(), PersistenceError> {
+        sqlx::query("SELECT 1").execute(&self.pool).await.map_err(database_error)?;
+        Ok(())
+    }
+}
+
+enum PersistCommand {
+    Save(Box<AiBattleSession>),
+    Delete(Uuid),
+    Flush(oneshot::Sender<()>),
+}
+
+/// セッションの変更をバックグラウンドでストアに書き込むハンドル
+/// 書き込みは受け付け順に1つずつ行うため、同じセッションへの更新が前後しない
+#[derive(Debug, Clone)]
+pub struct SessionPersister {
+    store: Arc<dyn SessionStore>,
+    sender: mpsc::UnboundedSender<PersistCommand>,
+}
+
+impl SessionPersister {
+    pub fn spawn(store: Arc<dyn SessionStore>) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let writer = Arc::clone(&store);
+        tokio::spawn(async move {
+            while let Some(command) = receiver.recv().await {
+                let result = match command {
+                    PersistCommand::Save(session) => writer.put(&session).await,
+                    PersistCommand::Delete(session_id) => writer.remove(session_id).await,
+                    PersistCommand::Flush(done) => {
+                        let _ = done.send(());
+                        Ok(())
+                    }
+                };
+                if let Err(e) = result {
+                    eprintln!("セッションの永続化に失敗: {}", e);
+                }
+            }
+        });
+
+        Self { store, sender }
+    }
+
+    pub fn store(&self) -> &Arc<dyn SessionStore> {
+        &self.store
+    }
+
+    pub fn save(&self, session: &AiBattleSession) {
+        let _ = self.sender.send(PersistCommand::Save(Box::new(session.clone())));
+    }
+
+    pub fn delete(&self, session_id: Uuid) {
+        let _ = self.sender.send(PersistCommand::Delete(session_id));
+    }
+
+    /// それまでに受け付けた書き込みが反映されるまで待つ
+    pub async fn flush(&self) {
+        let (done, wait) = oneshot::channel();
+        if self.sender.send(PersistCommand::Flush(done)).is_ok() {
+            let _ = wait.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ai_battle::AiDifficulty;
+    use crate::game::ReversiRules;
+    use std::time::Duration;
+
+    /// インメモリのデータベースは接続ごとに別物になるため、接続を1本に限定する
+    async fn memory_store() -> SqliteSessionStore {
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 1,
+            connection_timeout: Duration::from_secs(5),
+            ..DatabaseConfig::default()
+        };
+        SqliteSessionStore::connect(&config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_session_and_moves_round_trip() {
+        let store = memory_store().await;
+        let mut session = AiBattleSession::new(AiDifficulty::Easy);
+        let position = ReversiRules::get_valid_moves(&session.game_state.board, Player::Black)[0];
+        ReversiRules::apply_move(&mut session.game_state, position).unwrap();
+        session.add_move_record(MoveRecord::new(Player::Black, position, None));
+        session.add_move_record(MoveRecord::pass(Player::White));
+        session.ai_thinking = true;
+
+        store.save_session(&session).await.unwrap();
+        store.save_session(&session).await.unwrap();
+
+        let restored = store.load_session(session.id).await.unwrap().unwrap();
+        assert_eq!(restored.game_state.board, session.game_state.board);
+        assert!(!restored.ai_thinking);
+
+        let moves = store.load_moves(session.id).await.unwrap();
+        assert_eq!(moves.len(), 2);
+        assert_eq!(moves[0].position, Some(position));
+        assert!(moves[1].is_pass());
+
+        store.delete_session(session.id).await.unwrap();
+        assert!(store.load_session(session.id).await.unwrap().is_none());
+        assert!(store.load_moves(session.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_persister_applies_writes_in_order() {
+        let persister = SessionPersister::spawn(Arc::new(memory_store().await));
+        let mut session = AiBattleSession::new(AiDifficulty::Hard);
+
+        persister.save(&session);
+        session.ai_difficulty = AiDifficulty::Medium;
+        persister.save(&session);
+        let removed = AiBattleSession::new(AiDifficulty::Easy);
+        persister.save(&removed);
+        persister.delete(removed.id);
+        persister.flush().await;
+
+        let sessions = persister.store().list().await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].ai_difficulty, AiDifficulty::Medium);
+    }
+}
@@ -0,0 +1,292 @@
+//! 局面インデックスモジュール
+//! 正規化した局面ハッシュ（`PositionHash`）から、盤面とその局面について分かっている情報
+//! （解析で得た評価値、アーカイブの棋譜から集計した統計、局面を参照する問題）を引けるようにする。
+//! 対局中に現れた局面とアーカイブの棋譜に含まれる局面を登録する。
+//! 盤面と評価値は正規化後の向きで保持するため、同じ局面なら盤の向きによらず同じ結果を返す。
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::ai::service::MoveAnalysis;
+use crate::api::ai_battle::{AiDifficulty, MoveRecord};
+use crate::api::encoding::{self, api_player};
+use crate::archive::{ArchivedGame, GameResult};
+use crate::game::{Board, GameState, Player, Position, PositionHash, ReversiRules};
+
+/// 解析で得た局面の評価（難易度ごとに最新の1件を保持する）
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct KnownEvaluation {
+    pub difficulty: AiDifficulty,
+    /// 正規化後の盤面での最善手
+    pub best_move: Position,
+    /// 手番側から見た最善手の評価値
+    pub score: f64,
+    pub depth: u32,
+    pub evaluated_at: DateTime<Utc>,
+}
+
+/// この局面から指された手と、その手を選んだ対局数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct Continuation {
+    /// 正規化後の盤面での位置（パスの場合はnull）
+    pub position: Option<Position>,
+    pub games: u32,
+}
+
+/// アーカイブの対局のうち、この局面を通った対局の集計
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ExplorerStats {
+    pub games: u32,
+    pub black_wins: u32,
+    pub white_wins: u32,
+    pub draws: u32,
+    /// 対局数の多い順
+    pub continuations: Vec<Continuation>,
+}
+
+impl ExplorerStats {
+    fn record(&mut self, result: GameResult, next: Option<Option<Position>>) {
+        self.games += 1;
+        match result {
+            GameResult::BlackWin => self.black_wins += 1,
+            GameResult::WhiteWin => self.white_wins += 1,
+            GameResult::Draw => self.draws += 1,
+        }
+        let Some(position) = next else {
+            return;
+        };
+        match self.continuations.iter_mut().find(|continuation| continuation.position == position) {
+            Some(continuation) => continuation.games += 1,
+            None => self.continuations.push(Continuation { position, games: 1 }),
+        }
+        self.continuations.sort_by_key(|continuation| std::cmp::Reverse(continuation.games));
+    }
+}
+
+/// 局面を参照する問題
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct PuzzleReference {
+    pub puzzle_id: Uuid,
+    pub title: String,
+}
+
+/// ハッシュから引いた局面の情報
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PositionReport {
+    /// 16桁の16進数の局面ハッシュ
+    pub hash: String,
+    /// 正規化後の向きの盤面
+    #[serde(with = "api_player::board")]
+    #[schema(value_type = Vec<Vec<Option<ApiPlayer>>>)]
+    pub board: encoding::CanonicalBoard,
+    #[serde(with = "api_player")]
+    #[schema(value_type = ApiPlayer)]
+    pub to_move: Player,
+    pub black_count: u8,
+    pub white_count: u8,
+    pub valid_moves: Vec<Position>,
+    pub evaluations: Vec<KnownEvaluation>,
+    pub explorer: ExplorerStats,
+    pub puzzles: Vec<PuzzleReference>,
+}
+
+#[derive(Debug, Clone)]
+struct PositionEntry {
+    board: Board,
+    to_move: Player,
+    evaluations: Vec<KnownEvaluation>,
+    explorer: ExplorerStats,
+    puzzles: Vec<PuzzleReference>,
+}
+
+/// 局面ハッシュから局面の情報を引く索引
+#[derive(Debug, Default)]
+pub struct PositionIndex {
+    entries: DashMap<PositionHash, PositionEntry>,
+}
+
+impl PositionIndex {
+    /// 初期局面を登録した索引を作成する
+    pub fn new() -> Self {
+        let index = Self::default();
+        index.observe(&Board::new(), Player::Black);
+        index
+    }
+
+    /// 局面を登録し、そのハッシュを返す
+    pub fn observe(&self, board: &Board, to_move: Player) -> PositionHash {
+        let canonical = PositionHash::canonicalize(board, to_move);
+        self.entries.entry(canonical.hash).or_insert_with(|| PositionEntry {
+            board: canonical.board,
+            to_move,
+            evaluations: Vec::new(),
+            explorer: ExplorerStats::default(),
+            puzzles: Vec::new(),
+        });
+        canonical.hash
+    }
+
+    /// 合法手の解析結果から最善手を評価として記録する
+    pub fn record_evaluation(&self, board: &Board, to_move: Player, difficulty: AiDifficulty, moves: &[MoveAnalysis]) {
+        let Some(best) = moves.iter().max_by(|a, b| a.score.total_cmp(&b.score)) else {
+            return;
+        };
+        let canonical = PositionHash::canonicalize(board, to_move);
+        let evaluation = KnownEvaluation {
+            difficulty,
+            best_move: canonical.to_canonical(best.position),
+            score: best.score,
+            depth: best.depth,
+            evaluated_at: Utc::now(),
+        };
+
+        self.observe(board, to_move);
+        if let Some(mut entry) = self.entries.get_mut(&canonical.hash) {
+            entry.evaluations.retain(|known| known.difficulty != difficulty);
+            entry.evaluations.push(evaluation);
+        }
+    }
+
+    /// 終局した対局の棋譜を再生し、通った局面ごとに結果と次の手を集計する
+    pub fn record_game(&self, game: &ArchivedGame) {
+        // 石の少ない方が勝つ対局の結果は、通常の対局の勝率に混ぜない
+        if game.variant.fewer_discs_win() {
+            return;
+        }
+        let Some(mut state) = game.initial_state() else {
+            return;
+        };
+        for record in &game.moves {
+            self.record_visit(&state.board, record.player, game.result, Some(record.position));
+            if !Self::replay_move(&mut state, record) {
+                // 棋譜が途中から再生できない場合はそこまでの局面のみ集計する
+                return;
+            }
+        }
+        let to_move = game.moves.last().map_or(state.current_player, |record| record.player.opposite());
+        self.record_visit(&state.board, to_move, game.result, None);
+    }
+
+    fn record_visit(&self, board: &Board, to_move: Player, result: GameResult, next: Option<Option<Position>>) {
+        let canonical = PositionHash::canonicalize(board, to_move);
+        let next = next.map(|position| position.map(|position| canonical.to_canonical(position)));
+        self.observe(board, to_move);
+        if let Some(mut entry) = self.entries.get_mut(&canonical.hash) {
+            entry.explorer.record(result, next);
+        }
+    }
+
+    fn replay_move(state: &mut GameState, record: &MoveRecord) -> bool {
+        let Some(position) = record.position else {
+            return true;
+        };
+        state.current_player = record.player;
+        ReversiRules::apply_move(state, position).is_ok()
+    }
+
+    /// 局面を参照する問題を登録する
+    pub fn add_puzzle(&self, board: &Board, to_move: Player, puzzle: PuzzleReference) -> PositionHash {
+        let hash = self.observe(board, to_move);
+        if let Some(mut entry) = self.entries.get_mut(&hash) {
+            if !entry.puzzles.iter().any(|known| known.puzzle_id == puzzle.puzzle_id) {
+                entry.puzzles.push(puzzle);
+            }
+        }
+        hash
+    }
+
+    pub fn lookup(&self, hash: PositionHash) -> Option<PositionReport> {
+        let entry = self.entries.get(&hash)?;
+        let (black_count, white_count) = entry.board.count_pieces();
+        Some(PositionReport {
+            hash: hash.to_string(),
+            board: encoding::encode_board(&entry.board),
+            to_move: entry.to_move,
+            black_count,
+            white_count,
+            valid_moves: ReversiRules::get_valid_moves(&entry.board, entry.to_move),
+            evaluations: entry.evaluations.clone(),
+            explorer: entry.explorer.clone(),
+            puzzles: entry.puzzles.clone(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ai_battle::AiBattleSession;
+
+    fn finished_game(moves: &[(usize, usize)]) -> ArchivedGame {
+        let mut session = AiBattleSession::new_pvp();
+        for &(row, col) in moves {
+            let player = session.current_player;
+            ReversiRules::apply_move(&mut session.game_state, Position::new(row, col).unwrap()).unwrap();
+            session.add_move_record(MoveRecord::new(player, Position::new(row, col).unwrap(), None));
+            session.advance_turn();
+        }
+        ArchivedGame::from_session(&session).expect("終局している")
+    }
+
+    #[test]
+    fn test_start_position_is_always_known() {
+        let index = PositionIndex::new();
+        let report = index.lookup(PositionHash::of(&Board::new(), Player::Black)).unwrap();
+        assert_eq!((report.black_count, report.white_count), (2, 2));
+        assert_eq!(report.valid_moves.len(), 4);
+        assert!(index.lookup(PositionHash::of(&Board::new(), Player::White)).is_none());
+    }
+
+    #[test]
+    fn test_archived_games_feed_explorer_stats() {
+        let index = PositionIndex::new();
+        // 9手で黒が白を全滅させる対局
+        let wipeout = [(2, 3), (2, 2), (2, 1), (1, 3), (0, 4), (5, 3), (6, 3), (2, 4), (3, 5)];
+        index.record_game(&finished_game(&wipeout));
+        index.record_game(&finished_game(&wipeout));
+
+        let start = index.lookup(PositionHash::of(&Board::new(), Player::Black)).unwrap();
+        assert_eq!(start.explorer.games, 2);
+        assert_eq!(start.explorer.black_wins, 2);
+        assert_eq!(start.explorer.continuations.len(), 1);
+        assert_eq!(start.explorer.continuations[0].games, 2);
+        assert!(start.valid_moves.contains(&start.explorer.continuations[0].position.unwrap()));
+
+        // 初手の4通りは同じ局面として集計される
+        let mut state = GameState::new();
+        ReversiRules::apply_move(&mut state, Position::new(5, 4).unwrap()).unwrap();
+        let after_first = index.lookup(PositionHash::of(&state.board, Player::White)).unwrap();
+        assert_eq!(after_first.explorer.games, 2);
+    }
+
+    #[test]
+    fn test_evaluations_and_puzzles_are_attached_to_the_position() {
+        let index = PositionIndex::new();
+        let board = Board::new();
+        let moves = vec![
+            MoveAnalysis { position: Position::new(2, 3).unwrap(), score: 1.0, depth: 3 },
+            MoveAnalysis { position: Position::new(3, 2).unwrap(), score: 2.0, depth: 3 },
+        ];
+        index.record_evaluation(&board, Player::Black, AiDifficulty::Hard, &moves);
+        index.record_evaluation(&board, Player::Black, AiDifficulty::Hard, &moves[..1]);
+        let puzzle = PuzzleReference { puzzle_id: Uuid::new_v4(), title: "初手".to_string() };
+        let hash = index.add_puzzle(&board, Player::Black, puzzle.clone());
+        index.add_puzzle(&board, Player::Black, puzzle.clone());
+
+        let report = index.lookup(hash).unwrap();
+        assert_eq!(report.evaluations.len(), 1);
+        assert_eq!(report.evaluations[0].score, 1.0);
+        assert_eq!(report.puzzles, vec![puzzle]);
+    }
+}
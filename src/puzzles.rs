@@ -0,0 +1,379 @@
+//! 毎日の問題モジュール
+//! アーカイブの対局から、完全読みで勝ちになる手がただ1つしかない終盤の局面を問題として集め、
+//! 日付ごとに1問を「最善手を見つける」問題として出題する。
+//! 解答は完全読みで検証し、プレイヤー（`X-Player-Id` のプレイヤーID）ごとに連続正解日数を記録する。
+//! 問題と記録はメモリに保持し、起動時にアーカイブから問題を集め直す。
+
+use chrono::{Datelike, NaiveDate};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::ai::solver::{self, SolvedMove};
+use crate::api::ai_battle::{dto::validate_position, AiBattleError};
+use crate::api::encoding::{self, api_player};
+use crate::archive::ArchivedGame;
+use crate::game::{Board, GameVariant, Player, Position, PositionHash, ReversiRules};
+use crate::positions::{PositionIndex, PuzzleReference};
+
+/// 問題にする局面の空きマス数の上限（完全読みの時間を抑える）
+pub const PUZZLE_MAX_EMPTIES: usize = 10;
+
+/// 終盤の局面から作った「最善手を見つける」問題
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    /// 元の対局IDと出題局面の手数から決める（集め直しても同じIDになる）
+    pub puzzle_id: Uuid,
+    pub title: String,
+    pub board: Board,
+    pub to_move: Player,
+    pub variant: GameVariant,
+    /// 勝ちになるただ1つの手
+    pub solution: Position,
+    /// 正解手を打った場合の最終的な石差（手番側から見た値）
+    pub margin: i32,
+    /// 問題を取り出した対局
+    pub source_game_id: Uuid,
+    /// 出題局面までの手数（パスを含む）
+    pub ply: usize,
+}
+
+impl Puzzle {
+    /// 局面で勝ちになる手がただ1つなら、その局面を問題にする
+    pub fn from_position(board: &Board, to_move: Player, variant: GameVariant, source_game_id: Uuid, ply: usize) -> Option<Self> {
+        if solver::empties(board) > PUZZLE_MAX_EMPTIES {
+            return None;
+        }
+        let moves = solver::solve_moves(board, to_move, variant)?;
+        // 合法手が1つしかない局面は問題にならない
+        if moves.len() < 2 {
+            return None;
+        }
+        let [best]: [&SolvedMove; 1] = moves.iter().filter(|solved| solved.wins()).collect::<Vec<_>>().try_into().ok()?;
+
+        let (high, low) = source_game_id.as_u64_pair();
+        Some(Self {
+            puzzle_id: Uuid::from_u64_pair(high ^ ply as u64, low),
+            title: format!("{}番・残り{}マスの勝ち筋", color(to_move), solver::empties(board)),
+            board: board.clone(),
+            to_move,
+            variant,
+            solution: best.position,
+            margin: best.margin,
+            source_game_id,
+            ply,
+        })
+    }
+
+    /// 対局を再生し、勝ちになる手がただ1つの局面のうち最も早いもの（最も空きマスの多いもの）を問題にする
+    pub fn mine(game: &ArchivedGame) -> Option<Self> {
+        let mut state = game.initial_state()?;
+        for (ply, record) in game.moves.iter().enumerate() {
+            let Some(position) = record.position else {
+                continue;
+            };
+            if let Some(puzzle) = Self::from_position(&state.board, record.player, game.variant, game.game_id, ply) {
+                return Some(puzzle);
+            }
+            state.current_player = record.player;
+            ReversiRules::apply_move(&mut state, position).ok()?;
+        }
+        None
+    }
+
+    /// 解答を完全読みで検証する（勝ちになる手なら正解）
+    /// 合法手でない場合はNone
+    pub fn check(&self, answer: Position) -> Option<bool> {
+        solver::solve_move(&self.board, self.to_move, answer, self.variant).map(|margin| margin > 0)
+    }
+
+    pub fn reference(&self) -> PuzzleReference {
+        PuzzleReference { puzzle_id: self.puzzle_id, title: self.title.clone() }
+    }
+}
+
+fn color(player: Player) -> &'static str {
+    match player {
+        Player::Black => "黒",
+        Player::White => "白",
+    }
+}
+
+/// プレイヤーの毎日の問題の成績
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct PuzzleStreak {
+    pub player_id: Uuid,
+    /// 今日までの連続正解日数（昨日までの連続正解は、今日まだ解答していなければ途切れていない）
+    pub current: u32,
+    /// 最長の連続正解日数
+    pub best: u32,
+    /// 正解した日数
+    pub solved: u32,
+    /// 解答した日数
+    pub attempted: u32,
+    /// 最後に正解した日（未正解ならnull）
+    pub last_solved: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct StreakRecord {
+    current: u32,
+    best: u32,
+    solved: u32,
+    attempted: u32,
+    last_solved: Option<NaiveDate>,
+    last_attempted: Option<NaiveDate>,
+}
+
+impl StreakRecord {
+    /// その日の最初の解答を記録する（同じ日の2回目以降の解答は数えない）
+    fn record(&mut self, date: NaiveDate, correct: bool) -> bool {
+        if self.last_attempted == Some(date) {
+            return false;
+        }
+        self.last_attempted = Some(date);
+        self.attempted += 1;
+        if correct {
+            self.current = if self.last_solved == date.pred_opt() { self.current + 1 } else { 1 };
+            self.best = self.best.max(self.current);
+            self.solved += 1;
+            self.last_solved = Some(date);
+        } else {
+            self.current = 0;
+        }
+        true
+    }
+
+    fn on(&self, player_id: Uuid, date: NaiveDate) -> PuzzleStreak {
+        let alive = self.last_solved == Some(date) || self.last_solved == date.pred_opt();
+        PuzzleStreak {
+            player_id,
+            current: if alive { self.current } else { 0 },
+            best: self.best,
+            solved: self.solved,
+            attempted: self.attempted,
+            last_solved: self.last_solved,
+        }
+    }
+}
+
+/// 出題中の問題
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DailyPuzzleResponse {
+    pub date: NaiveDate,
+    pub puzzle_id: Uuid,
+    pub title: String,
+    /// 問題の目的
+    pub objective: String,
+    #[serde(with = "api_player::board")]
+    #[schema(value_type = Vec<Vec<Option<ApiPlayer>>>)]
+    pub board: encoding::CanonicalBoard,
+    pub board_size: usize,
+    #[serde(with = "api_player")]
+    #[schema(value_type = ApiPlayer)]
+    pub to_move: Player,
+    pub variant: GameVariant,
+    pub valid_moves: Vec<Position>,
+    pub black_count: u8,
+    pub white_count: u8,
+    /// 局面ハッシュ（`/api/positions/{hash}` で局面の情報を引ける）
+    pub position_hash: String,
+}
+
+impl DailyPuzzleResponse {
+    fn new(date: NaiveDate, puzzle: &Puzzle) -> Self {
+        let (black_count, white_count) = puzzle.board.count_pieces();
+        let objective = if puzzle.variant.fewer_discs_win() {
+            "石の少ない方が勝つルールで、勝ちになるただ1つの手を見つけてください"
+        } else {
+            "勝ちになるただ1つの手を見つけてください"
+        };
+        Self {
+            date,
+            puzzle_id: puzzle.puzzle_id,
+            title: puzzle.title.clone(),
+            objective: objective.to_string(),
+            board: encoding::encode_board(&puzzle.board),
+            board_size: puzzle.board.size(),
+            to_move: puzzle.to_move,
+            variant: puzzle.variant,
+            valid_moves: ReversiRules::get_valid_moves(&puzzle.board, puzzle.to_move),
+            black_count,
+            white_count,
+            position_hash: PositionHash::of(&puzzle.board, puzzle.to_move).to_string(),
+        }
+    }
+}
+
+/// 今日の問題への解答
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema)]
+pub struct PuzzleAnswerRequest {
+    pub row: u8,
+    pub col: u8,
+}
+
+/// 解答の結果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PuzzleAnswerResponse {
+    pub date: NaiveDate,
+    pub puzzle_id: Uuid,
+    pub correct: bool,
+    /// その日の最初の解答として成績に数えたか
+    pub counted: bool,
+    pub solution: Position,
+    /// 正解手を打った場合の最終的な石差（手番側から見た値）
+    pub margin: i32,
+    pub streak: PuzzleStreak,
+}
+
+/// 毎日の問題の出題と解答の記録
+#[derive(Debug, Default)]
+pub struct Puzzles {
+    pool: RwLock<Vec<Puzzle>>,
+    /// 日付ごとに出題した問題（その日のうちに問題が増えても出題は変えない）
+    daily: DashMap<NaiveDate, Uuid>,
+    streaks: DashMap<Uuid, StreakRecord>,
+}
+
+impl Puzzles {
+    /// 問題を加える（同じ局面の問題が既にあれば加えない）
+    pub fn add(&self, puzzle: Puzzle) -> bool {
+        let mut pool = self.pool.write().unwrap();
+        let hash = PositionHash::of(&puzzle.board, puzzle.to_move);
+        if pool.iter().any(|known| {
+            known.puzzle_id == puzzle.puzzle_id
+                || (known.variant == puzzle.variant && PositionHash::of(&known.board, known.to_move) == hash)
+        }) {
+            return false;
+        }
+        pool.push(puzzle);
+        true
+    }
+
+    /// 対局から問題を取り出して加え、局面インデックスにも問題として登録する
+    pub fn mine(&self, game: &ArchivedGame, positions: &PositionIndex) -> bool {
+        let Some(puzzle) = Puzzle::mine(game) else {
+            return false;
+        };
+        let (board, to_move, reference) = (puzzle.board.clone(), puzzle.to_move, puzzle.reference());
+        if !self.add(puzzle) {
+            return false;
+        }
+        positions.add_puzzle(&board, to_move, reference);
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.pool.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// その日の問題（問題が1つもなければNone）
+    /// まだ出題していない日は、IDの順に並べた問題から日付で1つを選ぶ
+    pub fn daily(&self, date: NaiveDate) -> Option<Puzzle> {
+        let pool = self.pool.read().unwrap();
+        if let Some(puzzle_id) = self.daily.get(&date) {
+            return pool.iter().find(|puzzle| puzzle.puzzle_id == *puzzle_id).cloned();
+        }
+        let mut ids: Vec<Uuid> = pool.iter().map(|puzzle| puzzle.puzzle_id).collect();
+        if ids.is_empty() {
+            return None;
+        }
+        ids.sort();
+        let chosen = *self.daily.entry(date).or_insert(ids[date.num_days_from_ce() as usize % ids.len()]);
+        pool.iter().find(|puzzle| puzzle.puzzle_id == chosen).cloned()
+    }
+
+    /// その日の問題を出題する
+    pub fn today(&self, date: NaiveDate) -> Result<DailyPuzzleResponse, AiBattleError> {
+        let puzzle = self.daily(date).ok_or(AiBattleError::NoPuzzleAvailable)?;
+        Ok(DailyPuzzleResponse::new(date, &puzzle))
+    }
+
+    /// その日の問題への解答を検証し、プレイヤーの成績を更新する
+    pub fn answer(&self, player_id: Uuid, date: NaiveDate, request: PuzzleAnswerRequest) -> Result<PuzzleAnswerResponse, AiBattleError> {
+        let puzzle = self.daily(date).ok_or(AiBattleError::NoPuzzleAvailable)?;
+        let answer = validate_position(request.row, request.col, puzzle.board.size())
+            .map_err(|reason| AiBattleError::InvalidPosition { reason })?;
+        let correct = puzzle.check(answer).ok_or_else(|| AiBattleError::InvalidMove {
+            reason: format!("({}, {})は合法手ではありません", answer.row, answer.col),
+            rejection: None,
+        })?;
+
+        let mut record = self.streaks.entry(player_id).or_default();
+        let counted = record.record(date, correct);
+        Ok(PuzzleAnswerResponse {
+            date,
+            puzzle_id: puzzle.puzzle_id,
+            correct,
+            counted,
+            solution: puzzle.solution,
+            margin: puzzle.margin,
+            streak: record.on(player_id, date),
+        })
+    }
+
+    /// プレイヤーのその日時点の成績（未解答のプレイヤーは0件）
+    pub fn streak(&self, player_id: Uuid, date: NaiveDate) -> PuzzleStreak {
+        self.streaks
+            .get(&player_id)
+            .map(|record| record.on(player_id, date))
+            .unwrap_or(PuzzleStreak { player_id, ..PuzzleStreak::default() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::setup::parse_board;
+
+    #[test]
+    fn test_daily_puzzle_answers_and_streaks() {
+        // 黒番: 右下の隅(7,7)だけが2石差の勝ちで、(2,1)は8石差の負けになる
+        let board = parse_board(
+            "-XXXXXXO/-XOXXXOO/X-OXXXOO/XOXXXXOO/OOOOOOOX/XOXXOOOX/XXOXOOOX/XXXXOOO-",
+        )
+        .unwrap();
+        let puzzle = Puzzle::from_position(&board, Player::Black, GameVariant::Standard, Uuid::new_v4(), 56).unwrap();
+        assert_eq!((puzzle.solution, puzzle.margin), (Position::new(7, 7).unwrap(), 2));
+        assert_eq!(puzzle.check(Position::new(2, 1).unwrap()), Some(false));
+        assert_eq!(puzzle.check(Position::new(0, 0).unwrap()), None);
+        // 2手とも勝ちになる局面は問題にしない
+        let both_win = parse_board("XXXXXXXX/XXXXXXXX/XXXXXXXX/XXXXXXXX/XXXXXXXX/XXXXXXXX/XXXXXXO-/XXXXXXO-").unwrap();
+        assert_eq!(Puzzle::from_position(&both_win, Player::Black, GameVariant::Standard, Uuid::new_v4(), 60), None);
+
+        let puzzles = Puzzles::default();
+        let day = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        assert!(matches!(puzzles.today(day), Err(AiBattleError::NoPuzzleAvailable)));
+        assert!(puzzles.add(puzzle.clone()));
+        assert!(!puzzles.add(Puzzle { puzzle_id: Uuid::new_v4(), ..puzzle.clone() }));
+        assert_eq!(puzzles.today(day).unwrap().puzzle_id, puzzle.puzzle_id);
+
+        // 成績に数えるのはその日の最初の解答だけ
+        let player = Uuid::new_v4();
+        let answer = |row, col| PuzzleAnswerRequest { row, col };
+        let wrong = puzzles.answer(player, day, answer(2, 1)).unwrap();
+        assert!(!wrong.correct && wrong.counted);
+        let retry = puzzles.answer(player, day, answer(7, 7)).unwrap();
+        assert!(retry.correct && !retry.counted);
+        assert_eq!(retry.streak.current, 0);
+        assert!(matches!(
+            puzzles.answer(player, day, answer(0, 0)),
+            Err(AiBattleError::InvalidMove { .. })
+        ));
+        assert!(matches!(puzzles.answer(player, day, answer(8, 0)), Err(AiBattleError::InvalidPosition { .. })));
+
+        let next = day.succ_opt().unwrap();
+        puzzles.answer(player, next, answer(7, 7)).unwrap();
+        let streak = puzzles.answer(player, next.succ_opt().unwrap(), answer(7, 7)).unwrap().streak;
+        assert_eq!((streak.current, streak.best, streak.solved, streak.attempted), (2, 2, 2, 3));
+        // 1日空けると連続正解は途切れる
+        let later = puzzles.streak(player, NaiveDate::from_ymd_opt(2024, 5, 5).unwrap());
+        assert_eq!((later.current, later.best), (0, 2));
+    }
+}
@@ -0,0 +1,227 @@
+//! Python向けのpyo3バインディング
+//! 盤面・ルール・対局状態とAIの着手計算をPythonから呼び出せるようにする。
+//! 座標は `(行, 列)`（0始まり）、石の色は `"black"` / `"white"` の文字列でやり取りする。
+//! `maturin develop` でビルドすると `import reversi` で読み込める。
+
+// pyo3 0.22 のマクロが展開する `PyResult` の変換に clippy が反応するため
+#![allow(clippy::useless_conversion)]
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::ai::strategies::{create_seeded_ai_strategy, Difficulty};
+use crate::game::{Board, Cell, GameState, GameStatus, GameVariant, Player, Position, ReversiRules};
+
+fn player_name(player: Player) -> &'static str {
+    match player {
+        Player::Black => "black",
+        Player::White => "white",
+    }
+}
+
+fn parse_player(player: &str) -> PyResult<Player> {
+    player.parse().map_err(PyValueError::new_err)
+}
+
+fn position(row: usize, col: usize) -> PyResult<Position> {
+    Position::new(row, col).ok_or_else(|| PyValueError::new_err(format!("座標は0〜7で指定してください: ({}, {})", row, col)))
+}
+
+fn coordinates(positions: Vec<Position>) -> Vec<(usize, usize)> {
+    positions.into_iter().map(|position| (position.row, position.col)).collect()
+}
+
+/// 盤面
+#[pyclass(name = "Board", module = "reversi")]
+#[derive(Debug, Clone)]
+pub struct PyBoard {
+    inner: Board,
+}
+
+#[pymethods]
+impl PyBoard {
+    /// 初期配置の盤面を作る
+    #[new]
+    fn new() -> Self {
+        Self { inner: Board::new() }
+    }
+
+    /// マスの石の色（空きはNone）
+    fn get_cell(&self, row: usize, col: usize) -> PyResult<Option<&'static str>> {
+        Ok(match self.inner.get_cell(position(row, col)?) {
+            Some(Cell::Black) => Some("black"),
+            Some(Cell::White) => Some("white"),
+            _ => None,
+        })
+    }
+
+    /// 黒・白の石数
+    fn count_pieces(&self) -> (u8, u8) {
+        self.inner.count_pieces()
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.display()
+    }
+}
+
+/// 対局状態
+#[pyclass(name = "GameState", module = "reversi")]
+#[derive(Debug, Clone)]
+pub struct PyGameState {
+    inner: GameState,
+}
+
+#[pymethods]
+impl PyGameState {
+    /// 初期配置・黒番の対局を作る
+    #[new]
+    fn new() -> Self {
+        Self { inner: GameState::new() }
+    }
+
+    /// 盤面のコピー
+    #[getter]
+    fn board(&self) -> PyBoard {
+        PyBoard { inner: self.inner.board.clone() }
+    }
+
+    #[getter]
+    fn current_player(&self) -> &'static str {
+        player_name(self.inner.current_player)
+    }
+
+    /// 勝者（進行中・引き分けの場合はNone）
+    #[getter]
+    fn winner(&self) -> Option<&'static str> {
+        match self.inner.game_status {
+            GameStatus::Finished { winner, .. } => winner.map(player_name),
+            _ => None,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+
+    /// 黒・白の石数
+    fn score(&self) -> (u8, u8) {
+        self.inner.get_score()
+    }
+
+    /// パスを除く着手数
+    fn move_count(&self) -> usize {
+        self.inner.get_move_count()
+    }
+
+    /// 手番の合法手
+    fn valid_moves(&self) -> Vec<(usize, usize)> {
+        coordinates(ReversiRules::get_valid_moves(&self.inner.board, self.inner.current_player))
+    }
+
+    /// 手番の色で着手し、パス判定・終局判定まで行う（裏返したマスを返す）
+    fn play(&mut self, row: usize, col: usize) -> PyResult<Vec<(usize, usize)>> {
+        let flipped = ReversiRules::apply_move(&mut self.inner, position(row, col)?)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        self.inner.switch_player();
+        ReversiRules::handle_turn(&mut self.inner);
+        Ok(coordinates(flipped))
+    }
+
+    fn copy(&self) -> Self {
+        self.clone()
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.board.display()
+    }
+}
+
+/// ルール判定（Rust側の `ReversiRules` と同じく、`apply_move` は手番の交代を行わない）
+#[pyclass(name = "ReversiRules", module = "reversi")]
+pub struct PyReversiRules;
+
+#[pymethods]
+impl PyReversiRules {
+    #[staticmethod]
+    fn is_valid_move(board: &PyBoard, row: usize, col: usize, player: &str) -> PyResult<bool> {
+        Ok(ReversiRules::is_valid_move(&board.inner, position(row, col)?, parse_player(player)?))
+    }
+
+    #[staticmethod]
+    fn get_valid_moves(board: &PyBoard, player: &str) -> PyResult<Vec<(usize, usize)>> {
+        Ok(coordinates(ReversiRules::get_valid_moves(&board.inner, parse_player(player)?)))
+    }
+
+    #[staticmethod]
+    fn get_flipped_positions(board: &PyBoard, row: usize, col: usize, player: &str) -> PyResult<Vec<(usize, usize)>> {
+        Ok(coordinates(ReversiRules::get_flipped_positions(&board.inner, position(row, col)?, parse_player(player)?)))
+    }
+
+    /// 手番の色で着手する（裏返したマスを返す）
+    #[staticmethod]
+    fn apply_move(state: &mut PyGameState, row: usize, col: usize) -> PyResult<Vec<(usize, usize)>> {
+        ReversiRules::apply_move(&mut state.inner, position(row, col)?)
+            .map(coordinates)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// 手番の合法手がなければパスし、双方になければ終局させる
+    #[staticmethod]
+    fn handle_turn(state: &mut PyGameState) -> bool {
+        ReversiRules::handle_turn(&mut state.inner)
+    }
+
+    #[staticmethod]
+    fn is_game_over(board: &PyBoard) -> bool {
+        ReversiRules::is_game_over(&board.inner)
+    }
+
+    #[staticmethod]
+    fn determine_winner(board: &PyBoard) -> Option<&'static str> {
+        ReversiRules::determine_winner(&board.inner, GameVariant::Standard).map(player_name)
+    }
+}
+
+/// 手番のAIが選ぶ手を同期的に計算する
+/// `difficulty` は easy / medium / hard（beginner / intermediate / advanced）
+#[pyfunction]
+#[pyo3(signature = (state, difficulty = "easy", seed = None))]
+fn calculate_move(state: &PyGameState, difficulty: &str, seed: Option<u64>) -> PyResult<(usize, usize)> {
+    let difficulty: Difficulty = difficulty.parse().map_err(PyValueError::new_err)?;
+    create_seeded_ai_strategy(difficulty, seed)
+        .calculate_move(&state.inner)
+        .map(|position| (position.row, position.col))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+#[pymodule]
+fn reversi(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyBoard>()?;
+    module.add_class::<PyGameState>()?;
+    module.add_class::<PyReversiRules>()?;
+    module.add_function(wrap_pyfunction!(calculate_move, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_state_plays_and_ai_picks_valid_move() {
+        let mut state = PyGameState::new();
+        assert_eq!(state.valid_moves(), vec![(2, 3), (3, 2), (4, 5), (5, 4)]);
+        assert_eq!(state.play(4, 5).unwrap(), vec![(4, 4)]);
+        assert_eq!((state.current_player(), state.score()), ("white", (4, 1)));
+        assert!(state.play(0, 0).is_err());
+
+        let board = state.board();
+        assert_eq!(board.get_cell(4, 4).unwrap(), Some("black"));
+        assert!(PyReversiRules::is_valid_move(&board, 5, 3, "white").unwrap());
+        assert!(PyReversiRules::get_valid_moves(&board, "purple").is_err());
+
+        let (row, col) = calculate_move(&state, "easy", Some(3)).unwrap();
+        assert!(state.valid_moves().contains(&(row, col)));
+    }
+}
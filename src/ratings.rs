@@ -0,0 +1,495 @@
+//! レーティングモジュール
+//! ログイン中のアカウントごとにEloレーティングを管理し、
+//! レーティング対象（`rated`）の対局が終局するたびに更新する。
+//! 認証されていない `X-Player-Id` だけで担当している席はなりすませるため、レーティングの対象にしない。
+//! AIの各難易度は固定のアンカーレーティングを持ち、対局しても変動しない。
+//! 保存先はセッションストアと同じく `DatabaseConfig.session_store` で選択する。
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::{mpsc, oneshot};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::ai_battle::{AiBattleSession, AiDifficulty, GameStatus, PlayerController};
+use crate::api::encoding::api_player;
+use crate::config::DatabaseConfig;
+use crate::error::PersistenceError;
+use crate::game::Player;
+use crate::persistence::{database_error, SqliteSessionStore};
+use crate::session::SessionStoreBackend;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS player_ratings (
+    player_id TEXT PRIMARY KEY,
+    rating INTEGER NOT NULL,
+    games INTEGER NOT NULL,
+    wins INTEGER NOT NULL,
+    losses INTEGER NOT NULL,
+    draws INTEGER NOT NULL,
+    updated_at TEXT
+);
+"#;
+
+/// 未対局のプレイヤーのレーティング
+pub const INITIAL_RATING: i32 = 1200;
+
+/// 1局あたりの変動の大きさ（Kファクター）
+pub const K_FACTOR: f64 = 32.0;
+
+/// AIの難易度ごとの固定レーティング
+pub fn anchor_rating(difficulty: AiDifficulty) -> i32 {
+    match difficulty {
+        AiDifficulty::Easy => 1000,
+        AiDifficulty::Medium => 1400,
+        AiDifficulty::Hard => 1800,
+    }
+}
+
+/// `rating` のプレイヤーが `opponent` の相手に対して得る期待スコア（0.0〜1.0）
+pub fn expected_score(rating: i32, opponent: i32) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent - rating) as f64 / 400.0))
+}
+
+/// 実際のスコア（勝ち1.0・引き分け0.5・負け0.0）に対するレーティングの変動
+pub fn rating_delta(rating: i32, opponent: i32, score: f64) -> i32 {
+    (K_FACTOR * (score - expected_score(rating, opponent))).round() as i32
+}
+
+/// プレイヤーのレーティングと対局成績
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PlayerRating {
+    pub player_id: Uuid,
+    pub rating: i32,
+    /// レーティング対象の対局数
+    pub games: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// 最後に更新された日時（未対局ならnull）
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl PlayerRating {
+    pub fn initial(player_id: Uuid) -> Self {
+        Self {
+            player_id,
+            rating: INITIAL_RATING,
+            games: 0,
+            wins: 0,
+            losses: 0,
+            draws: 0,
+            updated_at: None,
+        }
+    }
+
+    fn apply(&mut self, score: f64, delta: i32, now: DateTime<Utc>) {
+        self.rating += delta;
+        self.games += 1;
+        if score > 0.5 {
+            self.wins += 1;
+        } else if score < 0.5 {
+            self.losses += 1;
+        } else {
+            self.draws += 1;
+        }
+        self.updated_at = Some(now);
+    }
+}
+
+/// 終局によるプレイヤーのレーティングの変動
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RatingChange {
+    pub player_id: Uuid,
+    /// プレイヤーが担当した色
+    #[serde(with = "api_player")]
+    #[schema(value_type = crate::api::encoding::ApiPlayer)]
+    pub color: Player,
+    pub before: i32,
+    pub after: i32,
+    pub delta: i32,
+}
+
+/// レーティングの計算で見た各色の担当
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Seat {
+    Player(Uuid),
+    Ai(AiDifficulty),
+}
+
+impl Seat {
+    fn of(session: &AiBattleSession, color: Player) -> Option<Self> {
+        match session.controller(color) {
+            PlayerController::Ai { difficulty } => Some(Seat::Ai(difficulty)),
+            PlayerController::Human => session.account_id(color).map(Seat::Player),
+        }
+    }
+}
+
+/// レーティングの保存先の統一インターフェース
+#[async_trait]
+pub trait RatingStore: Send + Sync + fmt::Debug {
+    fn backend_name(&self) -> &'static str;
+
+    /// プレイヤーのレーティングを保存する（同じプレイヤーは置き換える）
+    /// 終局を処理する同期的な経路から呼ばれるため、書き込みは後から反映してよい
+    fn save(&self, rating: PlayerRating);
+
+    /// それまでに受け付けた保存が反映されるまで待つ
+    async fn flush(&self);
+
+    async fn load_all(&self) -> Result<Vec<PlayerRating>, PersistenceError>;
+}
+
+/// メモリ上のレーティング（再起動で失われる）
+#[derive(Debug, Default)]
+pub struct MemoryRatingStore {
+    ratings: DashMap<Uuid, PlayerRating>,
+}
+
+#[async_trait]
+impl RatingStore for MemoryRatingStore {
+    fn backend_name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn save(&self, rating: PlayerRating) {
+        self.ratings.insert(rating.player_id, rating);
+    }
+
+    async fn flush(&self) {}
+
+    async fn load_all(&self) -> Result<Vec<PlayerRating>, PersistenceError> {
+        Ok(self.ratings.iter().map(|entry| entry.value().clone()).collect())
+    }
+}
+
+enum RatingCommand {
+    Save(PlayerRating),
+    Flush(oneshot::Sender<()>),
+}
+
+/// SQLiteのレーティング
+/// 保存は専用タスクが受け付け順に書き込む
+#[derive(Debug, Clone)]
+pub struct SqliteRatingStore {
+    pool: SqlitePool,
+    sender: mpsc::UnboundedSender<RatingCommand>,
+}
+
+impl SqliteRatingStore {
+    /// セッションストアと同じデータベースにレーティングのテーブルを作成する
+    pub async fn open(store: &SqliteSessionStore) -> Result<Self, PersistenceError> {
+        let pool = store.pool().clone();
+        sqlx::raw_sql(SCHEMA).execute(&pool).await.map_err(database_error)?;
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let writer = pool.clone();
+        tokio::spawn(async move {
+            while let Some(command) = receiver.recv().await {
+                match command {
+                    RatingCommand::Save(rating) => {
+                        if let Err(e) = Self::upsert(&writer, &rating).await {
+                            eprintln!("レーティングの保存に失敗: {}", e);
+                        }
+                    }
+                    RatingCommand::Flush(done) => {
+                        let _ = done.send(());
+                    }
+                }
+            }
+        });
+
+        Ok(Self { pool, sender })
+    }
+
+    async fn upsert(pool: &SqlitePool, rating: &PlayerRating) -> Result<(), PersistenceError> {
+        sqlx::query(
+            "INSERT INTO player_ratings (player_id, rating, games, wins, losses, draws, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(player_id) DO UPDATE SET rating = excluded.rating, games = excluded.games, wins = excluded.wins,
+             losses = excluded.losses, draws = excluded.draws, updated_at = excluded.updated_at",
+        )
+        .bind(rating.player_id.to_string())
+        .bind(rating.rating)
+        .bind(rating.games)
+        .bind(rating.wins)
+        .bind(rating.losses)
+        .bind(rating.draws)
+        .bind(rating.updated_at)
+        .execute(pool)
+        .await.map_err(database_error)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RatingStore for SqliteRatingStore {
+    fn backend_name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn save(&self, rating: PlayerRating) {
+        let _ = self.sender.send(RatingCommand::Save(rating));
+    }
+
+    async fn flush(&self) {
+        let (done, wait) = oneshot::channel();
+        if self.sender.send(RatingCommand::Flush(done)).is_ok() {
+            let _ = wait.await;
+        }
+    }
+
+    async fn load_all(&self) -> Result<Vec<PlayerRating>, PersistenceError> {
+        let rows = sqlx::query("SELECT player_id, rating, games, wins, losses, draws, updated_at FROM player_ratings")
+            .fetch_all(&self.pool)
+            .await.map_err(database_error)?;
+        rows.into_iter()
+            .map(|row| {
+                let player_id: String = row.get("player_id");
+                Ok(PlayerRating {
+                    player_id: Uuid::parse_str(&player_id)
+                        .map_err(|e| PersistenceError::SerializationError { message: e.to_string() })?,
+                    rating: row.get("rating"),
+                    games: row.get("games"),
+                    wins: row.get("wins"),
+                    losses: row.get("losses"),
+                    draws: row.get("draws"),
+                    updated_at: row.get("updated_at"),
+                })
+            })
+            .collect()
+    }
+}
+
+/// 設定で選択された保存先のレーティングストアを開く
+pub async fn open_rating_store(config: &DatabaseConfig) -> Result<Arc<dyn RatingStore>, PersistenceError> {
+    match config.session_store {
+        SessionStoreBackend::Memory => Ok(Arc::new(MemoryRatingStore::default())),
+        SessionStoreBackend::Sqlite => {
+            let store = SqliteSessionStore::connect(config).await?;
+            Ok(Arc::new(SqliteRatingStore::open(&store).await?))
+        }
+    }
+}
+
+/// プレイヤーのレーティングを保持し、終局ごとに更新する
+/// 終局の処理は同期的に行うため、全プレイヤーのレーティングをメモリに持ち、保存先へは後から書き込む
+pub struct Ratings {
+    ratings: Mutex<HashMap<Uuid, PlayerRating>>,
+    store: RwLock<Arc<dyn RatingStore>>,
+}
+
+impl fmt::Debug for Ratings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ratings")
+            .field("players", &self.ratings.lock().unwrap().len())
+            .field("store", &self.store().backend_name())
+            .finish()
+    }
+}
+
+impl Default for Ratings {
+    fn default() -> Self {
+        Self {
+            ratings: Mutex::new(HashMap::new()),
+            store: RwLock::new(Arc::new(MemoryRatingStore::default())),
+        }
+    }
+}
+
+impl Ratings {
+    /// 保存先を差し替え、保存済みのレーティングを読み込む（読み込んだ人数を返す）
+    pub async fn attach_store(&self, store: Arc<dyn RatingStore>) -> Result<usize, PersistenceError> {
+        let loaded = store.load_all().await?;
+        let count = loaded.len();
+        {
+            let mut ratings = self.ratings.lock().unwrap();
+            for rating in loaded {
+                ratings.insert(rating.player_id, rating);
+            }
+        }
+        *self.store.write().unwrap() = store;
+        Ok(count)
+    }
+
+    pub fn store(&self) -> Arc<dyn RatingStore> {
+        Arc::clone(&self.store.read().unwrap())
+    }
+
+    /// プレイヤーのレーティング（未対局なら初期値）
+    pub fn get(&self, player_id: Uuid) -> PlayerRating {
+        self.ratings
+            .lock()
+            .unwrap()
+            .get(&player_id)
+            .cloned()
+            .unwrap_or_else(|| PlayerRating::initial(player_id))
+    }
+
+    /// 終局したレーティング対象の対局の結果で両者のレーティングを更新し、変動を返す
+    /// AI同士の対局、担当プレイヤーの分からない色がある対局、同じプレイヤー同士の対局は対象外
+    pub fn record_game(&self, session: &AiBattleSession) -> Vec<RatingChange> {
+        let GameStatus::Finished { winner } = session.status else {
+            return Vec::new();
+        };
+        if !session.rated {
+            return Vec::new();
+        }
+        let (Some(black), Some(white)) = (Seat::of(session, Player::Black), Seat::of(session, Player::White)) else {
+            return Vec::new();
+        };
+        if black == white || matches!((black, white), (Seat::Ai(_), Seat::Ai(_))) {
+            return Vec::new();
+        }
+
+        let mut ratings = self.ratings.lock().unwrap();
+        let current = |seat: Seat| match seat {
+            Seat::Player(player_id) => ratings.get(&player_id).map_or(INITIAL_RATING, |rating| rating.rating),
+            Seat::Ai(difficulty) => anchor_rating(difficulty),
+        };
+        let before = [current(black), current(white)];
+
+        let now = Utc::now();
+        let store = self.store();
+        let mut changes = Vec::new();
+        for (index, (color, seat)) in [(Player::Black, black), (Player::White, white)].into_iter().enumerate() {
+            let Seat::Player(player_id) = seat else {
+                continue;
+            };
+            let score = match winner {
+                Some(winner) if winner == color => 1.0,
+                Some(_) => 0.0,
+                None => 0.5,
+            };
+            let delta = rating_delta(before[index], before[1 - index], score);
+
+            let rating = ratings.entry(player_id).or_insert_with(|| PlayerRating::initial(player_id));
+            rating.apply(score, delta, now);
+            store.save(rating.clone());
+            changes.push(RatingChange {
+                player_id,
+                color,
+                before: before[index],
+                after: rating.rating,
+                delta,
+            });
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rated_game(black: PlayerController, white: PlayerController, winner: Option<Player>) -> AiBattleSession {
+        let mut session = AiBattleSession::new(AiDifficulty::Medium);
+        session.black = black;
+        session.white = white;
+        session.rated = true;
+        session.status = GameStatus::Finished { winner };
+        session
+    }
+
+    #[test]
+    fn test_elo_expectation_and_delta() {
+        assert!((expected_score(1200, 1200) - 0.5).abs() < 1e-9);
+        assert_eq!(rating_delta(1200, 1200, 1.0), 16);
+        assert_eq!(rating_delta(1200, 1200, 0.5), 0);
+        // 格上に勝つと大きく上がり、格下に負けると大きく下がる
+        assert_eq!(rating_delta(1200, 1800, 1.0), 31);
+        assert_eq!(rating_delta(1200, 1000, 0.0), -24);
+    }
+
+    #[test]
+    fn test_ai_games_rate_only_the_human_against_the_anchor() {
+        let ratings = Ratings::default();
+        let player = Uuid::new_v4();
+        let mut session = rated_game(PlayerController::Human, PlayerController::Ai { difficulty: AiDifficulty::Hard }, Some(Player::Black));
+        session.owner_id = Some(player);
+
+        let changes = ratings.record_game(&session);
+        assert_eq!(changes, vec![RatingChange { player_id: player, color: Player::Black, before: 1200, after: 1231, delta: 31 }]);
+        let rating = ratings.get(player);
+        assert_eq!((rating.rating, rating.games, rating.wins), (1231, 1, 1));
+
+        // レーティング対象外・AI同士の対局は更新しない
+        session.rated = false;
+        assert!(ratings.record_game(&session).is_empty());
+        let exhibition = rated_game(
+            PlayerController::Ai { difficulty: AiDifficulty::Easy },
+            PlayerController::Ai { difficulty: AiDifficulty::Hard },
+            None,
+        );
+        assert!(ratings.record_game(&exhibition).is_empty());
+        assert_eq!(ratings.get(player).games, 1);
+    }
+
+    #[test]
+    fn test_pvp_games_rate_both_players() {
+        let ratings = Ratings::default();
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut session = rated_game(PlayerController::Human, PlayerController::Human, Some(Player::White));
+        session.accounts.set(Player::Black, alice);
+        session.accounts.set(Player::White, bob);
+
+        let changes = ratings.record_game(&session);
+        assert_eq!(changes.iter().map(|change| change.delta).collect::<Vec<_>>(), vec![-16, 16]);
+        assert_eq!((ratings.get(alice).rating, ratings.get(bob).rating), (1184, 1216));
+        assert_eq!((ratings.get(alice).losses, ratings.get(bob).wins), (1, 1));
+
+        // 相手の分からない対人戦は対象外
+        session.accounts = Default::default();
+        session.accounts.set(Player::Black, alice);
+        assert!(ratings.record_game(&session).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_header_only_seats_are_not_rated() {
+        let ratings = Ratings::default();
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+
+        // `X-Player-Id` は誰でも名乗れるため、アカウントに紐付かない席ではレーティングを更新しない
+        let mut pvp = rated_game(PlayerController::Human, PlayerController::Human, Some(Player::Black));
+        pvp.owners.set(Player::Black, alice);
+        pvp.owners.set(Player::White, bob);
+        assert!(ratings.record_game(&pvp).is_empty());
+
+        let mut against_ai = rated_game(PlayerController::Human, PlayerController::Ai { difficulty: AiDifficulty::Easy }, Some(Player::Black));
+        against_ai.owners.set(Player::Black, alice);
+        assert!(ratings.record_game(&against_ai).is_empty());
+
+        assert_eq!((ratings.get(alice), ratings.get(bob)), (PlayerRating::initial(alice), PlayerRating::initial(bob)));
+        assert!(ratings.store().load_all().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_ratings_survive_a_restart() {
+        let config = DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            max_connections: 1,
+            ..DatabaseConfig::default()
+        };
+        let store: Arc<dyn RatingStore> = Arc::new(SqliteRatingStore::open(&SqliteSessionStore::connect(&config).await.unwrap()).await.unwrap());
+        let ratings = Ratings::default();
+        ratings.attach_store(Arc::clone(&store)).await.unwrap();
+
+        let player = Uuid::new_v4();
+        let mut session = rated_game(PlayerController::Ai { difficulty: AiDifficulty::Easy }, PlayerController::Human, None);
+        session.accounts.set(Player::White, player);
+        ratings.record_game(&session);
+        store.flush().await;
+
+        let restarted = Ratings::default();
+        assert_eq!(restarted.attach_store(store).await.unwrap(), 1);
+        assert_eq!(restarted.get(player), ratings.get(player));
+        assert_eq!(restarted.get(player).draws, 1);
+    }
+}
@@ -0,0 +1,230 @@
+//! 対局の再生と検証
+//! アーカイブした対局を初期局面から1手ずつ再生し、棋譜がルールに沿っているか、
+//! AIの着手が記録したシードと探索の設定から同じ手になるかを確かめる。
+//! AIの着手の再計算は組み込みのエンジンで行うため、外部のAIサービスで対局した記録は一致しない。
+
+use serde::Serialize;
+use std::fmt;
+use uuid::Uuid;
+
+use crate::ai::levels::LevelParams;
+use crate::archive::ArchivedGame;
+use crate::game::{GameState, Player, Position, ReversiRules};
+
+/// 対局を記録したエンジンのバージョン
+pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 再生で見つかった食い違い（`ply` はパスを含む1始まりの手数）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReplayIssue {
+    /// 手番ではない色の着手が記録されている
+    WrongPlayer { ply: usize, expected: Player, recorded: Player },
+    /// 合法手ではない
+    IllegalMove { ply: usize, player: Player, position: Position },
+    /// 合法手があるのにパスしている
+    IllegalPass { ply: usize, player: Player },
+    /// 終局後にも着手が記録されている
+    MoveAfterGameOver { ply: usize },
+    /// AIの着手が、記録したシードと探索の設定で再計算した手と異なる
+    AiMoveMismatch { ply: usize, player: Player, recorded: Position, replayed: Position },
+    /// 再生後の石数が記録と異なる
+    ScoreMismatch { recorded: (u8, u8), replayed: (u8, u8) },
+    /// 時間切れ以外で終局したのに、盤面が終局していない
+    NotFinished,
+    /// 対応していない盤面の大きさ
+    UnsupportedBoardSize { size: usize },
+}
+
+fn color(player: &Player) -> &'static str {
+    match player {
+        Player::Black => "黒",
+        Player::White => "白",
+    }
+}
+
+impl fmt::Display for ReplayIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayIssue::WrongPlayer { ply, expected, recorded } => {
+                write!(f, "{}手目: {}の手番に{}の着手が記録されています", ply, color(expected), color(recorded))
+            }
+            ReplayIssue::IllegalMove { ply, player, position } => {
+                write!(f, "{}手目: {}の({}, {})は合法手ではありません", ply, color(player), position.row, position.col)
+            }
+            ReplayIssue::IllegalPass { ply, player } => write!(f, "{}手目: {}は合法手があるのにパスしています", ply, color(player)),
+            ReplayIssue::MoveAfterGameOver { ply } => write!(f, "{}手目: 終局後の着手が記録されています", ply),
+            ReplayIssue::AiMoveMismatch { ply, player, recorded, replayed } => write!(
+                f,
+                "{}手目: {}のAIの着手({}, {})が再計算した手({}, {})と異なります",
+                ply, color(player), recorded.row, recorded.col, replayed.row, replayed.col
+            ),
+            ReplayIssue::ScoreMismatch { recorded, replayed } => write!(
+                f,
+                "石数が記録（黒{} 白{}）と再生後（黒{} 白{}）で異なります",
+                recorded.0, recorded.1, replayed.0, replayed.1
+            ),
+            ReplayIssue::NotFinished => write!(f, "記録の最後まで再生しても終局しません"),
+            ReplayIssue::UnsupportedBoardSize { size } => write!(f, "{}x{}の盤面には対応していません", size, size),
+        }
+    }
+}
+
+/// 再生の結果
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayReport {
+    pub game_id: Uuid,
+    /// 再生できた手数（パスを含む）
+    pub plies: usize,
+    /// 再計算して一致を確かめたAIの着手の数
+    pub ai_moves_verified: usize,
+    /// シードの記録がなく確かめられなかったAIの着手の数
+    pub ai_moves_unverifiable: usize,
+    /// 対局したエンジンのバージョン（記録がなければnull）
+    pub engine_version: Option<String>,
+    /// 時間の上限のある探索で再計算した手と異なった着手
+    /// 対局時に探索を打ち切った深さの違いで起こりうるため、エラーにはしない
+    pub warnings: Vec<ReplayIssue>,
+    pub errors: Vec<ReplayIssue>,
+}
+
+impl ReplayReport {
+    /// 記録がルールとエンジンに矛盾していないか
+    pub fn is_consistent(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// 現在のエンジンと同じバージョンで対局した記録か
+    pub fn same_engine(&self) -> bool {
+        self.engine_version.as_deref() == Some(ENGINE_VERSION)
+    }
+}
+
+/// 記録したシードと探索の設定でAIの着手を再計算する
+/// 時間の上限は外して最後の深さまで探索する（対局時に時間内に探索を終えていれば同じ手になる）
+fn replay_ai_move(state: &GameState, params: LevelParams, seed: u64) -> Option<Position> {
+    let params = LevelParams { time_limit_ms: 0, ..params };
+    params.create_strategy(Some(seed), 1).calculate_move(state).ok()
+}
+
+/// アーカイブした対局を初期局面から再生して検証する（最初に見つかったルール違反で再生をやめる）
+pub fn verify(game: &ArchivedGame) -> ReplayReport {
+    let mut report = ReplayReport {
+        game_id: game.game_id,
+        plies: 0,
+        ai_moves_verified: 0,
+        ai_moves_unverifiable: 0,
+        engine_version: game.engine_version.clone(),
+        warnings: Vec::new(),
+        errors: Vec::new(),
+    };
+    let Some(mut state) = game.initial_state() else {
+        report.errors.push(ReplayIssue::UnsupportedBoardSize { size: game.board_size });
+        return report;
+    };
+
+    for (index, record) in game.moves.iter().enumerate() {
+        let ply = index + 1;
+        let player = state.current_player;
+        if ReversiRules::is_game_over(&state.board) {
+            report.errors.push(ReplayIssue::MoveAfterGameOver { ply });
+            return report;
+        }
+        if record.player != player {
+            report.errors.push(ReplayIssue::WrongPlayer { ply, expected: player, recorded: record.player });
+            return report;
+        }
+
+        let Some(position) = record.position else {
+            if ReversiRules::has_valid_moves(&state.board, player) {
+                report.errors.push(ReplayIssue::IllegalPass { ply, player });
+                return report;
+            }
+            state.switch_player();
+            report.plies = ply;
+            continue;
+        };
+        if !ReversiRules::is_valid_move(&state.board, position, player) {
+            report.errors.push(ReplayIssue::IllegalMove { ply, player, position });
+            return report;
+        }
+
+        match (record.ai_params, game.seed) {
+            (Some(params), Some(seed)) => {
+                let replayed = replay_ai_move(&state, params, seed);
+                match replayed {
+                    Some(replayed) if replayed == position => report.ai_moves_verified += 1,
+                    _ => {
+                        let issue = ReplayIssue::AiMoveMismatch {
+                            ply,
+                            player,
+                            recorded: position,
+                            replayed: replayed.unwrap_or(position),
+                        };
+                        if params.time_limit_ms > 0 {
+                            report.warnings.push(issue);
+                        } else {
+                            report.errors.push(issue);
+                        }
+                    }
+                }
+            }
+            (Some(_), None) => report.ai_moves_unverifiable += 1,
+            (None, _) => {}
+        }
+
+        // 検証済みの合法手なので失敗しない
+        let _ = ReversiRules::apply_move(&mut state, position);
+        state.switch_player();
+        report.plies = ply;
+    }
+
+    if !game.time_forfeit && !ReversiRules::is_game_over(&state.board) {
+        report.errors.push(ReplayIssue::NotFinished);
+    }
+    let replayed = state.board.count_pieces();
+    let recorded = (game.black_count, game.white_count);
+    if replayed != recorded {
+        report.errors.push(ReplayIssue::ScoreMismatch { recorded, replayed });
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::local_service::LocalAIService;
+    use crate::api::ai_battle::{AiBattleService, AiDifficulty};
+    use crate::archive::ArchiveFilter;
+    use crate::session::AiBattleSessionManager;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_archived_ai_game_replays_move_for_move() {
+        let service = AiBattleService::new_with_ai_service(
+            Arc::new(AiBattleSessionManager::new(10)),
+            Arc::new(LocalAIService::new_fast()),
+        );
+        service.create_ai_vs_ai(AiDifficulty::Easy, AiDifficulty::Medium, true).await.unwrap();
+        let game = service.query_archive(&ArchiveFilter::default()).await.unwrap().remove(0);
+
+        let report = verify(&game);
+        assert!(report.is_consistent(), "{:?}", report.errors);
+        assert!(report.same_engine());
+        assert_eq!(report.plies, game.moves.len());
+        let ai_moves = game.moves.iter().filter(|record| record.position.is_some()).count();
+        assert_eq!(report.ai_moves_verified + report.warnings.len(), ai_moves);
+
+        // 乱択のAIの着手を別の合法手に差し替えると、以降の記録が食い違う
+        let mut tampered = game.clone();
+        let state = GameState::new();
+        let first = &mut tampered.moves[0];
+        let other = ReversiRules::get_valid_moves(&state.board, Player::Black)
+            .into_iter()
+            .find(|position| Some(*position) != first.position)
+            .unwrap();
+        first.position = Some(other);
+        let report = verify(&tampered);
+        assert!(matches!(report.errors[0], ReplayIssue::AiMoveMismatch { ply: 1, .. }));
+    }
+}
@@ -0,0 +1,199 @@
+//! 起動時セルフテストモジュール
+//! ポートをバインドせずにエンジンを起動し、設定・AI対局・セッション永続化を検証する。
+//! `reversi --self-test` から呼び出され、コンテナのロールアウト前のヘルスゲートとして使う。
+
+use std::fs;
+
+use crate::api::ai_battle::{AiBattleService, AiBattleSession, AiDifficulty, ConfigurableAiBattleService};
+use crate::config::Config;
+use crate::error::PersistenceError;
+use crate::session::AiBattleSessionManager;
+
+/// 各難易度の対局で人間側が打つ手数
+const SCRIPTED_MOVES: usize = 4;
+
+/// チェック結果の種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Passed,
+    /// 対象外（AIサービスが未対応の難易度など）
+    Skipped,
+    Failed,
+}
+
+/// チェック1件分の結果
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+/// セルフテスト全体の結果
+#[derive(Debug, Default)]
+pub struct SelfTestReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl SelfTestReport {
+    /// 失敗したチェックが1件もなければtrue
+    pub fn is_success(&self) -> bool {
+        self.checks.iter().all(|check| check.status != CheckStatus::Failed)
+    }
+
+    fn record(&mut self, name: impl Into<String>, result: Result<String, String>) {
+        let (status, detail) = match result {
+            Ok(detail) => (CheckStatus::Passed, detail),
+            Err(detail) => (CheckStatus::Failed, detail),
+        };
+        self.checks.push(CheckResult { name: name.into(), status, detail });
+    }
+
+    fn skip(&mut self, name: impl Into<String>, detail: impl Into<String>) {
+        self.checks.push(CheckResult { name: name.into(), status: CheckStatus::Skipped, detail: detail.into() });
+    }
+}
+
+/// 設定を検証し、エンジンを起動して各チェックを実行する
+pub async fn run(config: &Config) -> SelfTestReport {
+    let mut report = SelfTestReport::default();
+
+    report.record("config", config.validate().map(|_| "設定値は有効".to_string()).map_err(|e| e.to_string()));
+
+    match &config.server.tls {
+        Some(tls) => report.record(
+            "tls",
+            crate::tls::load_rustls_config(tls)
+                .await
+                .map(|_| format!("証明書を読み込みました: {}", tls.cert_path))
+                .map_err(|e| format!("証明書または秘密鍵を読み込めません: {}", e)),
+        ),
+        None => report.skip("tls", "TLSは未設定"),
+    }
+
+    let engine = match ConfigurableAiBattleService::new(config) {
+        Ok(engine) => engine,
+        Err(e) => {
+            report.record("engine", Err(format!("AI対戦サービスを起動できません: {}", e)));
+            return report;
+        }
+    };
+    let primary = engine.primary_ai_service();
+    report.record("engine", Ok(format!("プライマリAI: {}", primary.get_name())));
+
+    let supported = primary.get_supported_difficulties();
+    for difficulty in AiDifficulty::all() {
+        let name = format!("game:{}", difficulty.name());
+        if supported.contains(&difficulty) {
+            report.record(name, play_scripted_game(engine.get_service(), difficulty).await);
+        } else {
+            report.skip(name, format!("{}は{}に未対応", primary.get_name(), difficulty.name()));
+        }
+    }
+
+    report.record(
+        "persistence",
+        check_persistence(engine.session_manager()).await.map_err(|e| e.to_string()),
+    );
+
+    report
+}
+
+/// 人間側は常に最初の合法手を打ち、AIの応手と盤面の整合性を確認する
+async fn play_scripted_game(service: &AiBattleService, difficulty: AiDifficulty) -> Result<String, String> {
+    let created = service.create_ai_battle(difficulty).await.map_err(|e| e.to_string())?;
+    let game_id = created.game_id;
+
+    let result = async {
+        let mut state = created;
+        let mut ai_moves = 0;
+
+        for _ in 0..SCRIPTED_MOVES {
+            let Some(&position) = state.valid_moves.first() else { break };
+            let response = service.make_player_move(game_id, position).await.map_err(|e| e.to_string())?;
+            ai_moves += usize::from(response.ai_move.is_some());
+            state = response.game_state;
+
+            let discs = u32::from(state.black_count) + u32::from(state.white_count);
+            if discs != 4 + state.move_count {
+                return Err(format!("石数 {} が手数 {} と一致しません", discs, state.move_count));
+            }
+        }
+
+        if ai_moves == 0 {
+            return Err("AIが一度も着手しませんでした".to_string());
+        }
+        Ok(format!("{}手進行（AIの着手 {}回）", state.move_count, ai_moves))
+    }
+    .await;
+
+    let _ = service.delete_session(game_id);
+    result
+}
+
+/// セッションストアへの書き込み・読み出しと、スナップショットのファイル往復を確認する
+async fn check_persistence(session_manager: &AiBattleSessionManager) -> Result<String, PersistenceError> {
+    let session_id = session_manager
+        .create_session(AiDifficulty::Easy)
+        .await
+        .map_err(|e| PersistenceError::DatabaseError { message: e.to_string() })?;
+
+    let result = (|| {
+        let session = session_manager
+            .get_session(&session_id)
+            .map_err(|e| PersistenceError::DatabaseError { message: e.to_string() })?;
+
+        let path = std::env::temp_dir().join(format!("reversi-self-test-{}.json", session_id));
+        let snapshot = serde_json::to_string(&session)
+            .map_err(|e| PersistenceError::SerializationError { message: e.to_string() })?;
+        fs::write(&path, &snapshot)?;
+        let restored = fs::read_to_string(&path);
+        fs::remove_file(&path)?;
+
+        let restored: AiBattleSession = serde_json::from_str(&restored?)
+            .map_err(|e| PersistenceError::SerializationError { message: e.to_string() })?;
+        if restored.id != session.id || restored.game_state.board != session.game_state.board {
+            return Err(PersistenceError::SerializationError {
+                message: "復元したセッションが保存前と一致しません".to_string(),
+            });
+        }
+
+        Ok("セッションの書き込み・読み出しに成功".to_string())
+    })();
+
+    let _ = session_manager.remove_session(&session_id);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::service::AIServiceType;
+
+    fn mock_config() -> Config {
+        let mut config = Config::default();
+        config.ai_service.service_type = AIServiceType::Mock;
+        config.fallback.enable_fallback = false;
+        config
+    }
+
+    #[tokio::test]
+    async fn test_self_test_passes_with_mock_ai() {
+        let report = run(&mock_config()).await;
+
+        assert!(report.is_success(), "{:?}", report.checks);
+        let games = report.checks.iter().filter(|check| check.name.starts_with("game:")).count();
+        assert_eq!(games, AiDifficulty::all().len());
+        assert!(report.checks.iter().any(|check| check.name == "persistence" && check.status == CheckStatus::Passed));
+    }
+
+    #[tokio::test]
+    async fn test_self_test_fails_on_invalid_config() {
+        let mut config = mock_config();
+        config.ai_service.timeout_ms = 0;
+
+        let report = run(&config).await;
+        assert!(!report.is_success());
+        assert_eq!(report.checks[0].status, CheckStatus::Failed);
+    }
+}
@@ -0,0 +1,287 @@
+//! 自己対局によるデータ生成モジュール
+//! AI同士の対局を繰り返し、局面・着手・結果をJSONL（1局1行）またはWTHOR形式で書き出す。
+//! 評価関数の調整や学習用データの作成に使う。
+
+use chrono::{Datelike, Utc};
+use serde::Serialize;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::ai::service::AIService;
+use crate::api::ai_battle::AiDifficulty;
+use crate::game::{Board, Cell, GameState, GameStatus, Player, Position};
+use crate::tournament::play_engine_game;
+use crate::wthor::{self, WthorGame};
+
+/// 出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfplayFormat {
+    /// 1局を1行のJSONとして書き出す（局面・着手・結果を含む）
+    Jsonl,
+    /// WTHOR形式（.wtb、着手と結果のみ）
+    Wthor,
+}
+
+impl SelfplayFormat {
+    /// 出力先の拡張子から形式を決める（.wtb 以外はJSONL）
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("wtb") => SelfplayFormat::Wthor,
+            _ => SelfplayFormat::Jsonl,
+        }
+    }
+}
+
+impl FromStr for SelfplayFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "jsonl" | "json" => Ok(SelfplayFormat::Jsonl),
+            "wthor" | "wtb" => Ok(SelfplayFormat::Wthor),
+            _ => Err(format!("出力形式は jsonl か wthor で指定してください: {}", s)),
+        }
+    }
+}
+
+/// 盤面を64文字（黒は `X`、白は `O`、空きは `-`、a1からh8へ行ごと）で表す
+pub fn board_string(board: &Board) -> String {
+    (0..8)
+        .flat_map(|row| (0..8).map(move |col| (row, col)))
+        .map(|(row, col)| match Position::new(row, col).and_then(|position| board.get_cell(position)) {
+            Some(Cell::Black) => 'X',
+            Some(Cell::White) => 'O',
+            _ => '-',
+        })
+        .collect()
+}
+
+fn player_name(player: Player) -> &'static str {
+    match player {
+        Player::Black => "black",
+        Player::White => "white",
+    }
+}
+
+/// JSONLに書き出す1手分の記録
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfplayMove {
+    /// 着手前の盤面（`board_string` の形式）
+    pub board: String,
+    pub player: &'static str,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// JSONLに書き出す1局分の記録
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfplayRecord {
+    pub game: u32,
+    pub black: AiDifficulty,
+    pub white: AiDifficulty,
+    pub seed: Option<u64>,
+    /// `black` / `white` / `draw`
+    pub result: &'static str,
+    pub black_count: u8,
+    pub white_count: u8,
+    /// エンジンの異常で反則負けになった場合の理由
+    pub forfeit: Option<String>,
+    /// パスを除く着手順
+    pub moves: Vec<SelfplayMove>,
+}
+
+/// 1局分の結果
+#[derive(Debug, Clone)]
+pub struct SelfplayGame {
+    /// 1始まりの対局番号
+    pub game: u32,
+    pub black: AiDifficulty,
+    pub white: AiDifficulty,
+    pub seed: Option<u64>,
+    pub state: GameState,
+    pub forfeit: Option<String>,
+}
+
+impl SelfplayGame {
+    pub fn winner(&self) -> Option<Player> {
+        match self.state.game_status {
+            GameStatus::Finished { winner, .. } => winner,
+            _ => None,
+        }
+    }
+
+    /// 着手前の局面を再生しながらJSONLの記録を作る
+    pub fn record(&self) -> SelfplayRecord {
+        let mut board = Board::new();
+        let moves = self.state.move_history
+            .iter()
+            .map(|game_move| {
+                let entry = SelfplayMove {
+                    board: board_string(&board),
+                    player: player_name(game_move.player),
+                    row: game_move.position.row,
+                    col: game_move.position.col,
+                };
+                for position in std::iter::once(&game_move.position).chain(&game_move.flipped) {
+                    board.set_cell(*position, game_move.player.to_cell());
+                }
+                entry
+            })
+            .collect();
+        let (black_count, white_count) = self.state.get_score();
+
+        SelfplayRecord {
+            game: self.game,
+            black: self.black,
+            white: self.white,
+            seed: self.seed,
+            result: self.winner().map_or("draw", player_name),
+            black_count,
+            white_count,
+            forfeit: self.forfeit.clone(),
+            moves,
+        }
+    }
+}
+
+/// 対局結果の集計
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SelfplaySummary {
+    pub games: u32,
+    pub black_wins: u32,
+    pub white_wins: u32,
+    pub draws: u32,
+    pub forfeits: u32,
+    total_moves: u64,
+    total_margin: i64,
+}
+
+impl SelfplaySummary {
+    pub fn record(&mut self, game: &SelfplayGame) {
+        self.games += 1;
+        match game.winner() {
+            Some(Player::Black) => self.black_wins += 1,
+            Some(Player::White) => self.white_wins += 1,
+            None => self.draws += 1,
+        }
+        if game.forfeit.is_some() {
+            self.forfeits += 1;
+        }
+        let (black, white) = game.state.get_score();
+        self.total_moves += game.state.move_history.len() as u64;
+        self.total_margin += black as i64 - white as i64;
+    }
+
+    /// 1局あたりの平均着手数（パスを除く）
+    pub fn average_moves(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.total_moves as f64 / self.games as f64
+    }
+
+    /// 1局あたりの平均石差（黒 - 白）
+    pub fn average_margin(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.total_margin as f64 / self.games as f64
+    }
+}
+
+impl fmt::Display for SelfplaySummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}局: 黒 {}勝 / 白 {}勝 / 引き分け {} (反則負け {})  平均手数 {:.1}  平均石差(黒-白) {:+.1}",
+            self.games,
+            self.black_wins,
+            self.white_wins,
+            self.draws,
+            self.forfeits,
+            self.average_moves(),
+            self.average_margin(),
+        )
+    }
+}
+
+/// 対局を指定の形式で書き出す
+/// JSONLは1局ごとに書き、WTHORはヘッダーに対局数が要るため `finish` でまとめて書く
+pub struct SelfplayWriter<W: Write> {
+    writer: W,
+    format: SelfplayFormat,
+    wthor_games: Vec<WthorGame>,
+}
+
+impl<W: Write> SelfplayWriter<W> {
+    pub fn new(writer: W, format: SelfplayFormat) -> Self {
+        Self { writer, format, wthor_games: Vec::new() }
+    }
+
+    pub fn write_game(&mut self, game: &SelfplayGame) -> io::Result<()> {
+        match self.format {
+            SelfplayFormat::Jsonl => {
+                serde_json::to_writer(&mut self.writer, &game.record())?;
+                self.writer.write_all(b"\n")
+            }
+            SelfplayFormat::Wthor => {
+                self.wthor_games.push(WthorGame::from_state(&game.state));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        match self.format {
+            SelfplayFormat::Jsonl => self.writer.flush(),
+            SelfplayFormat::Wthor => {
+                let now = Utc::now();
+                wthor::write_database(self.writer, &self.wthor_games, now.year() as u16, now)
+            }
+        }
+    }
+}
+
+/// AI同士で1局指す（シードを指定すると `seed + 対局番号 - 1` で乱択する）
+pub async fn play_game(engine: &dyn AIService, game: u32, black: AiDifficulty, white: AiDifficulty, seed: Option<u64>) -> SelfplayGame {
+    let seed = seed.map(|seed| seed.wrapping_add(game as u64 - 1));
+    let (state, forfeit) = play_engine_game((engine, black), (engine, white), seed).await;
+    SelfplayGame { game, black, white, seed, state, forfeit }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::mock_service::MockAIService;
+
+    #[tokio::test]
+    async fn test_jsonl_records_replay_positions_before_each_move() {
+        let engine = MockAIService::new_fast();
+        let game = play_game(&engine, 1, AiDifficulty::Easy, AiDifficulty::Easy, Some(5)).await;
+        let mut summary = SelfplaySummary::default();
+        summary.record(&game);
+        assert_eq!(summary.games, 1);
+        assert_eq!(summary.average_moves(), game.state.move_history.len() as f64);
+
+        let mut output = Vec::new();
+        let mut writer = SelfplayWriter::new(&mut output, SelfplayFormat::Jsonl);
+        writer.write_game(&game).unwrap();
+        writer.finish().unwrap();
+
+        let record: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(record["moves"][0]["board"], board_string(&Board::new()));
+        assert_eq!(record["moves"][0]["player"], "black");
+        assert_eq!(record["moves"].as_array().unwrap().len(), game.state.move_history.len());
+        let (black, white) = game.state.get_score();
+        assert_eq!((record["black_count"].as_u64(), record["white_count"].as_u64()), (Some(black as u64), Some(white as u64)));
+    }
+
+    #[test]
+    fn test_format_from_path_and_name() {
+        assert_eq!(SelfplayFormat::from_path("games.WTB".as_ref()), SelfplayFormat::Wthor);
+        assert_eq!(SelfplayFormat::from_path("games.jsonl".as_ref()), SelfplayFormat::Jsonl);
+        assert_eq!("wthor".parse::<SelfplayFormat>(), Ok(SelfplayFormat::Wthor));
+        assert!("csv".parse::<SelfplayFormat>().is_err());
+    }
+}
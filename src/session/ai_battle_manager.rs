@@ -1,56 +1,214 @@
 //! AI対戦セッション管理モジュール
 //! 同時にAI対戦を行うユーザーのセッションを管理し、
 //! セッション数制限、タイムアウト処理、クリーンアップを担当する。
+//! 対局中のセッションは `MemorySessionStore` に置き、
+//! 永続化先の `SessionStore` を接続した場合は、セッションの変更をそちらにも反映する。
 
-use dashmap::DashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
 use chrono::{DateTime, Utc, Duration};
+use dashmap::DashMap;
 use uuid::Uuid;
 
-use crate::api::ai_battle::{AiBattleSession, AiBattleError, AiBattleResult, AiDifficulty};
+use crate::game::Player;
+use crate::api::ai_battle::{AiBattleSession, AiBattleError, AiBattleResult, AiDifficulty, SessionFilter};
+use crate::config::CleanupPolicy;
+use crate::error::PersistenceError;
+use crate::persistence::SessionPersister;
+// トレイトのメソッドはDashMapのメソッドと名前が重なるため、型はモジュール経由で参照する
+use super::store::{self, MemorySessionStore};
+
+/// 起動時にストアからセッションを復元した結果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RestoreSummary {
+    /// メモリに復元した進行中のセッション数
+    pub restored: usize,
+    /// 終局済みのため復元しなかったセッション数
+    pub finished: usize,
+    /// 最大セッション数を超えたため復元しなかった進行中のセッション数
+    pub over_capacity: usize,
+}
 
 /// AI対戦セッションの管理を行うメイン構造体
 /// スレッドセーフなDashMapで同時アクセスを効率的に処理
 #[derive(Debug, Clone)]
 pub struct AiBattleSessionManager {
     /// アクティブセッションのコレクション
-    sessions: Arc<DashMap<Uuid, AiBattleSession>>,
+    sessions: MemorySessionStore,
     /// 同時存在可能な最大セッション数
     max_sessions: usize,
     /// セッションのタイムアウト時間（分）
     session_timeout_minutes: i64,
+    /// 接続済みのストアへの書き込みハンドル（未接続ならメモリのみ）
+    persister: Arc<OnceLock<SessionPersister>>,
+    /// クリーンアップから除外するセッションの条件
+    cleanup_policy: CleanupPolicy,
+    /// 着手を処理中のセッション
+    moves_in_progress: Arc<DashMap<Uuid, ()>>,
+    /// セッション数の確認と登録をまとめて行うためのロック
+    admission: Arc<Mutex<()>>,
+}
+
+/// セッションの着手の処理中を表すガード（破棄すると同じセッションの次の着手を受け付ける）
+#[derive(Debug)]
+pub struct MoveGuard {
+    moves_in_progress: Arc<DashMap<Uuid, ()>>,
+    session_id: Uuid,
+}
+
+impl Drop for MoveGuard {
+    fn drop(&mut self) {
+        self.moves_in_progress.remove(&self.session_id);
+    }
 }
 
 impl AiBattleSessionManager {
     /// デフォルトタイムアウト（30分）でセッションマネージャーを作成
     pub fn new(max_sessions: usize) -> Self {
         Self {
-            sessions: Arc::new(DashMap::new()),
+            sessions: MemorySessionStore::new(),
             max_sessions,
             session_timeout_minutes: 30,
+            persister: Arc::new(OnceLock::new()),
+            cleanup_policy: CleanupPolicy::default(),
+            moves_in_progress: Arc::new(DashMap::new()),
+            admission: Arc::new(Mutex::new(())),
         }
     }
     
     /// カスタムタイムアウトでセッションマネージャーを作成
     pub fn with_timeout(max_sessions: usize, timeout_minutes: i64) -> Self {
         Self {
-            sessions: Arc::new(DashMap::new()),
+            sessions: MemorySessionStore::new(),
             max_sessions,
             session_timeout_minutes: timeout_minutes,
+            persister: Arc::new(OnceLock::new()),
+            cleanup_policy: CleanupPolicy::default(),
+            moves_in_progress: Arc::new(DashMap::new()),
+            admission: Arc::new(Mutex::new(())),
+        }
+    }
+    
+    /// クリーンアップから除外する条件を設定する
+    pub fn with_cleanup_policy(mut self, cleanup_policy: CleanupPolicy) -> Self {
+        self.cleanup_policy = cleanup_policy;
+        self
+    }
+    
+    /// クリーンアップの対象外のセッションか
+    pub fn is_cleanup_exempt(&self, session: &AiBattleSession) -> bool {
+        let policy = &self.cleanup_policy;
+        if policy.exempt_pinned && session.pinned {
+            return true;
+        }
+        if session.is_finished() {
+            return false;
+        }
+        (policy.exempt_correspondence && session.is_correspondence())
+            || (policy.exempt_tournament_games && session.tournament_id.is_some())
+    }
+    
+    /// ストアを接続し、進行中のセッションを更新の新しい順に上限数まで復元する
+    /// 終局済みのセッションはストアに残したまま読み込まない（一覧はストアから参照できる）。
+    /// 以降のセッションの変更はストアにも書き込まれる
+    pub async fn attach_store(&self, store: Arc<dyn store::SessionStore>) -> Result<RestoreSummary, PersistenceError> {
+        let stored = store.list().await?;
+        let persister = SessionPersister::spawn(store);
+        if self.persister.set(persister).is_err() {
+            return Err(PersistenceError::DatabaseError { message: "ストアは接続済みです".to_string() });
+        }
+        
+        let mut summary = RestoreSummary::default();
+        for session in stored {
+            let _admission = self.admission.lock().unwrap();
+            if session.is_finished() {
+                summary.finished += 1;
+            } else if self.sessions.len() >= self.max_sessions {
+                summary.over_capacity += 1;
+            } else {
+                self.sessions.entry(session.id).or_insert(session);
+                summary.restored += 1;
+            }
+        }
+        Ok(summary)
+    }
+    
+    /// 接続済みのストア
+    pub fn store(&self) -> Option<&Arc<dyn store::SessionStore>> {
+        self.persister.get().map(SessionPersister::store)
+    }
+    
+    /// ストアへの書き込み待ちをすべて反映する
+    pub async fn flush_store(&self) {
+        if let Some(persister) = self.persister.get() {
+            persister.flush().await;
+        }
+    }
+    
+    fn persist(&self, session: &AiBattleSession) {
+        if let Some(persister) = self.persister.get() {
+            persister.save(session);
+        }
+    }
+    
+    fn unpersist(&self, session_id: Uuid) {
+        if let Some(persister) = self.persister.get() {
+            persister.delete(session_id);
         }
     }
     
     /// 新しいAI対戦セッションを作成する
     /// 最大セッション数に達している場合はエラーを返す
     pub async fn create_session(&self, difficulty: AiDifficulty) -> AiBattleResult<Uuid> {
-        // セッション数制限をチェック
+        self.insert_session(AiBattleSession::new(difficulty))
+    }
+    
+    /// 人間の担当色を指定してセッションを作成する
+    pub async fn create_session_with_color(
+        &self,
+        difficulty: AiDifficulty,
+        player_color: Player,
+    ) -> AiBattleResult<Uuid> {
+        self.insert_session(AiBattleSession::new_with_color(difficulty, player_color))
+    }
+    
+    /// 両色ともAIが操作するセッションを作成する
+    pub async fn create_ai_vs_ai_session(
+        &self,
+        black_difficulty: AiDifficulty,
+        white_difficulty: AiDifficulty,
+    ) -> AiBattleResult<Uuid> {
+        self.insert_session(AiBattleSession::new_ai_vs_ai(black_difficulty, white_difficulty))
+    }
+    
+    /// AIの乱択にシードを用いるAI同士のセッションを作成する
+    pub async fn create_seeded_ai_vs_ai_session(
+        &self,
+        black_difficulty: AiDifficulty,
+        white_difficulty: AiDifficulty,
+        seed: u64,
+    ) -> AiBattleResult<Uuid> {
+        self.insert_session(AiBattleSession {
+            seed: Some(seed),
+            ..AiBattleSession::new_ai_vs_ai(black_difficulty, white_difficulty)
+        })
+    }
+    
+    /// 対人戦のセッションを作成する
+    pub async fn create_pvp_session(&self) -> AiBattleResult<Uuid> {
+        self.insert_session(AiBattleSession::new_pvp())
+    }
+    
+    /// 呼び出し側で組み立てたセッションを登録する
+    pub fn insert_session(&self, session: AiBattleSession) -> AiBattleResult<Uuid> {
+        // 同時に登録されても上限を超えないよう、数の確認から登録までロックを保持する
+        let _admission = self.admission.lock().unwrap();
         if self.sessions.len() >= self.max_sessions {
             return Err(AiBattleError::MaxSessionsReached { max: self.max_sessions });
         }
         
-        let session = AiBattleSession::new(difficulty);
         let session_id = session.id;
         
+        self.persist(&session);
         self.sessions.insert(session_id, session);
         
         Ok(session_id)
@@ -69,6 +227,7 @@ impl AiBattleSessionManager {
         
         match self.sessions.get_mut(&session_id) {
             Some(mut existing_session) => {
+                self.persist(&session);
                 *existing_session = session;
                 Ok(())
             }
@@ -76,9 +235,70 @@ impl AiBattleSessionManager {
         }
     }
     
+    /// セッションをロックしたまま変更する
+    /// クロージャがエラーを返した場合、変更は破棄され保存済みのセッションはそのまま残る
+    pub fn modify_session<T>(
+        &self,
+        session_id: &Uuid,
+        f: impl FnOnce(&mut AiBattleSession) -> AiBattleResult<T>,
+    ) -> AiBattleResult<T> {
+        let mut entry = self.sessions
+            .get_mut(session_id)
+            .ok_or(AiBattleError::GameNotFound { game_id: *session_id })?;
+        
+        let mut draft = entry.clone();
+        let result = f(&mut draft)?;
+        self.persist(&draft);
+        *entry = draft;
+        Ok(result)
+    }
+    
+    /// セッションの着手の処理を始める
+    /// 返したガードを保持しているあいだ、同じセッションの着手は待たずに `MoveInProgress` で失敗する
+    pub fn begin_move(&self, session_id: &Uuid) -> AiBattleResult<MoveGuard> {
+        if !self.sessions.contains_key(session_id) {
+            return Err(AiBattleError::GameNotFound { game_id: *session_id });
+        }
+        match self.moves_in_progress.entry(*session_id) {
+            dashmap::Entry::Occupied(_) => Err(AiBattleError::MoveInProgress),
+            dashmap::Entry::Vacant(entry) => {
+                entry.insert(());
+                Ok(MoveGuard { moves_in_progress: Arc::clone(&self.moves_in_progress), session_id: *session_id })
+            }
+        }
+    }
+    
+    /// セッションを複製せずに参照する
+    pub fn with_session<T>(&self, session_id: &Uuid, f: impl FnOnce(&AiBattleSession) -> T) -> AiBattleResult<T> {
+        let entry = self.sessions
+            .get(session_id)
+            .ok_or(AiBattleError::GameNotFound { game_id: *session_id })?;
+        Ok(f(&entry))
+    }
+    
+    /// セッションをロックしたまま、複製せずにその場で変更する
+    /// `modify_session` と異なり、クロージャがエラーを返してもそれまでの変更は残り、ストアにも反映する。
+    /// ロック中は同じセッションをセッションマネージャー経由で参照しないこと（デッドロックする）
+    pub fn with_session_mut<T>(
+        &self,
+        session_id: &Uuid,
+        f: impl FnOnce(&mut AiBattleSession) -> AiBattleResult<T>,
+    ) -> AiBattleResult<T> {
+        let mut entry = self.sessions
+            .get_mut(session_id)
+            .ok_or(AiBattleError::GameNotFound { game_id: *session_id })?;
+    
+        let result = f(&mut entry);
+        self.persist(&entry);
+        result
+    }
+    
     pub fn remove_session(&self, session_id: &Uuid) -> AiBattleResult<AiBattleSession> {
         match self.sessions.remove(session_id) {
-            Some((_, session)) => Ok(session),
+            Some((_, session)) => {
+                self.unpersist(*session_id);
+                Ok(session)
+            }
             None => Err(AiBattleError::GameNotFound { game_id: *session_id }),
         }
     }
@@ -87,10 +307,56 @@ impl AiBattleSessionManager {
         self.sessions.iter().map(|entry| entry.value().clone()).collect()
     }
     
+    /// 条件に合う最初のセッションを複製して返す
+    pub fn find_session(&self, predicate: impl Fn(&AiBattleSession) -> bool) -> Option<AiBattleSession> {
+        self.sessions
+            .iter()
+            .find(|entry| predicate(entry.value()))
+            .map(|entry| entry.value().clone())
+    }
+    
+    /// 条件に合うセッションのIDを返す（セッションは複製しない）
+    pub fn session_ids(&self, predicate: impl Fn(&AiBattleSession) -> bool) -> Vec<Uuid> {
+        self.sessions
+            .iter()
+            .filter(|entry| predicate(entry.value()))
+            .map(|entry| *entry.key())
+            .collect()
+    }
+    
+    /// 条件に合うセッションのみを複製して返す
+    pub fn list_sessions_matching(&self, filter: &SessionFilter) -> Vec<AiBattleSession> {
+        self.sessions
+            .iter()
+            .filter(|entry| filter.matches(entry.value()))
+            .map(|entry| entry.value().clone())
+            .collect()
+    }
+    
+    /// 条件に合うセッションの一覧を返す
+    /// メモリ上にセッションがなくストアが接続されている場合は、ストアから読み込んで返す
+    pub async fn list_sessions_or_stored(&self, filter: &SessionFilter) -> Vec<AiBattleSession> {
+        if !self.sessions.is_empty() {
+            return self.list_sessions_matching(filter);
+        }
+        match self.store() {
+            Some(store) => match store.list().await {
+                Ok(sessions) => sessions.into_iter().filter(|session| filter.matches(session)).collect(),
+                Err(e) => {
+                    eprintln!("保存済みセッションの読み込みに失敗: {}", e);
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        }
+    }
+    
     pub fn session_count(&self) -> usize {
         self.sessions.len()
     }
     
+    /// 最終着手から一定時間経過したセッションを削除する
+    /// `CleanupPolicy` で除外されたセッション（進行中の通信対局・大会の対局、ピン留め）は残す
     pub async fn cleanup_inactive_sessions(&self) -> usize {
         let cutoff_time = Utc::now() - Duration::minutes(self.session_timeout_minutes);
         let mut removed_count = 0;
@@ -98,11 +364,13 @@ impl AiBattleSessionManager {
         let expired_ids: Vec<Uuid> = self.sessions
             .iter()
             .filter(|entry| entry.value().last_move_at < cutoff_time)
+            .filter(|entry| !self.is_cleanup_exempt(entry.value()))
             .map(|entry| *entry.key())
             .collect();
         
         for session_id in expired_ids {
             if self.sessions.remove(&session_id).is_some() {
+                self.unpersist(session_id);
                 removed_count += 1;
             }
         }
@@ -191,6 +459,23 @@ mod tests {
         assert!(matches!(result, Err(AiBattleError::MaxSessionsReached { max: 2 })));
     }
     
+    #[test]
+    fn test_concurrent_inserts_do_not_exceed_the_limit() {
+        let manager = AiBattleSessionManager::new(4);
+        let barrier = std::sync::Barrier::new(16);
+        
+        let inserted = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..16).map(|_| scope.spawn(|| {
+                barrier.wait();
+                manager.insert_session(AiBattleSession::new(AiDifficulty::Easy))
+            })).collect();
+            handles.into_iter().filter_map(|handle| handle.join().unwrap().ok()).count()
+        });
+        // 同時に登録しても、受け付けるのは上限数までに限られる
+        assert_eq!(inserted, 4);
+        assert_eq!(manager.session_count(), 4);
+    }
+    
     #[tokio::test]
     async fn test_get_session() {
         let manager = AiBattleSessionManager::new(10);
@@ -224,6 +509,63 @@ mod tests {
         assert!(updated_session.ai_thinking);
     }
     
+    #[tokio::test]
+    async fn test_modify_session_discards_failed_changes() {
+        let manager = AiBattleSessionManager::new(10);
+        let session_id = manager.create_session(AiDifficulty::Easy).await.unwrap();
+        
+        let result: AiBattleResult<()> = manager.modify_session(&session_id, |session| {
+            session.ai_thinking = true;
+            Err(AiBattleError::NotPlayerTurn)
+        });
+        assert!(result.is_err());
+        assert!(!manager.get_session(&session_id).unwrap().ai_thinking);
+        
+        manager.modify_session(&session_id, |session| {
+            session.ai_thinking = true;
+            Ok(())
+        }).unwrap();
+        assert!(manager.get_session(&session_id).unwrap().ai_thinking);
+    }
+
+    #[tokio::test]
+    async fn test_with_session_mut_changes_in_place() {
+        let manager = AiBattleSessionManager::new(10);
+        let session_id = manager.create_session(AiDifficulty::Easy).await.unwrap();
+        let other = manager.create_session(AiDifficulty::Hard).await.unwrap();
+    
+        // エラーを返してもそれまでの変更は残る
+        let result: AiBattleResult<()> = manager.with_session_mut(&session_id, |session| {
+            session.ai_thinking = true;
+            Err(AiBattleError::NotPlayerTurn)
+        });
+        assert!(result.is_err());
+        assert!(manager.with_session(&session_id, |session| session.ai_thinking).unwrap());
+        assert_eq!(manager.session_ids(|session| session.ai_thinking), vec![session_id]);
+        assert!(!manager.get_session(&other).unwrap().ai_thinking);
+    
+        let missing = Uuid::new_v4();
+        assert!(matches!(
+            manager.with_session_mut(&missing, |_| Ok(())),
+            Err(AiBattleError::GameNotFound { .. })
+        ));
+    }
+    
+    #[tokio::test]
+    async fn test_begin_move_rejects_concurrent_moves() {
+        let manager = AiBattleSessionManager::new(10);
+        let session_id = manager.create_session(AiDifficulty::Easy).await.unwrap();
+        let other = manager.create_session(AiDifficulty::Easy).await.unwrap();
+        
+        let guard = manager.begin_move(&session_id).unwrap();
+        assert!(matches!(manager.begin_move(&session_id), Err(AiBattleError::MoveInProgress)));
+        assert!(manager.begin_move(&other).is_ok());
+        
+        drop(guard);
+        assert!(manager.begin_move(&session_id).is_ok());
+        assert!(matches!(manager.begin_move(&Uuid::new_v4()), Err(AiBattleError::GameNotFound { .. })));
+    }
+    
     #[tokio::test]
     async fn test_remove_session() {
         let manager = AiBattleSessionManager::new(10);
@@ -247,6 +589,31 @@ mod tests {
         assert_eq!(sessions.len(), 2);
     }
     
+    #[tokio::test]
+    async fn test_list_sessions_matching_filter() {
+        use crate::api::ai_battle::{GameStatus, SessionStatusFilter};
+        
+        let manager = AiBattleSessionManager::new(10);
+        let easy = manager.create_session(AiDifficulty::Easy).await.unwrap();
+        let hard = manager.create_session(AiDifficulty::Hard).await.unwrap();
+        let _pvp = manager.create_pvp_session().await.unwrap();
+        manager.modify_session(&hard, |session| {
+            session.status = GameStatus::Finished { winner: None };
+            Ok(())
+        }).unwrap();
+        
+        let ids = |filter: SessionFilter| -> Vec<Uuid> {
+            manager.list_sessions_matching(&filter).into_iter().map(|session| session.id).collect()
+        };
+        assert_eq!(ids(SessionFilter::default()).len(), 3);
+        assert_eq!(ids(SessionFilter { difficulty: Some(AiDifficulty::Easy), ..SessionFilter::default() }), vec![easy]);
+        assert_eq!(ids(SessionFilter { status: Some(SessionStatusFilter::Finished), ..SessionFilter::default() }), vec![hard]);
+        assert!(ids(SessionFilter {
+            status: Some(SessionStatusFilter::InProgress),
+            difficulty: Some(AiDifficulty::Hard),
+        }).is_empty());
+    }
+    
     #[tokio::test]
     async fn test_ai_thinking_flag() {
         let manager = AiBattleSessionManager::new(10);
@@ -275,6 +642,93 @@ mod tests {
         assert_eq!(manager.session_count(), 0);
     }
     
+    #[tokio::test]
+    async fn test_cleanup_keeps_active_correspondence_games() {
+        use crate::api::ai_battle::{GameClock, TimeControlPreset};
+        
+        let manager = AiBattleSessionManager::with_timeout(10, 0);
+        let mut session = AiBattleSession::new(AiDifficulty::Easy);
+        session.clock = Some(GameClock::new(TimeControlPreset::Correspondence3Days.time_control(), Utc::now()));
+        let correspondence_id = manager.insert_session(session).unwrap();
+        manager.create_session(AiDifficulty::Easy).await.unwrap();
+        
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        assert_eq!(manager.cleanup_inactive_sessions().await, 1);
+        assert!(manager.session_exists(&correspondence_id));
+    }
+    
+    #[tokio::test]
+    async fn test_cleanup_policy_exemptions() {
+        let manager = AiBattleSessionManager::with_timeout(10, 0);
+        let pinned = manager.insert_session(AiBattleSession { pinned: true, ..AiBattleSession::new(AiDifficulty::Easy) }).unwrap();
+        let tournament = manager
+            .insert_session(AiBattleSession { tournament_id: Some(Uuid::new_v4()), ..AiBattleSession::new(AiDifficulty::Easy) })
+            .unwrap();
+        
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        assert_eq!(manager.cleanup_inactive_sessions().await, 0);
+        
+        // ポリシーで除外を無効にするとピン留めも削除される
+        let strict = AiBattleSessionManager::with_timeout(10, 0).with_cleanup_policy(CleanupPolicy {
+            exempt_pinned: false,
+            ..CleanupPolicy::default()
+        });
+        strict.insert_session(manager.get_session(&pinned).unwrap()).unwrap();
+        strict.insert_session(manager.get_session(&tournament).unwrap()).unwrap();
+        assert_eq!(strict.cleanup_inactive_sessions().await, 1);
+        assert!(strict.session_exists(&tournament));
+    }
+    
+    #[tokio::test]
+    async fn test_sessions_survive_restart_with_store() {
+        use crate::config::DatabaseConfig;
+        use crate::persistence::SqliteSessionStore;
+        
+        let dir = tempfile::tempdir().unwrap();
+        let config = DatabaseConfig {
+            url: format!("sqlite:{}", dir.path().join("sessions.db").display()),
+            ..DatabaseConfig::default()
+        };
+        
+        let manager = AiBattleSessionManager::new(10);
+        manager.attach_store(Arc::new(SqliteSessionStore::connect(&config).await.unwrap())).await.unwrap();
+        let kept = manager.create_session(AiDifficulty::Hard).await.unwrap();
+        let removed = manager.create_session(AiDifficulty::Easy).await.unwrap();
+        let finished = manager.create_session(AiDifficulty::Easy).await.unwrap();
+        manager.modify_session(&kept, |session| {
+            session.ai_difficulty = AiDifficulty::Medium;
+            Ok(())
+        }).unwrap();
+        manager.modify_session(&finished, |session| {
+            session.status = crate::api::ai_battle::GameStatus::Finished { winner: None };
+            Ok(())
+        }).unwrap();
+        manager.remove_session(&removed).unwrap();
+        manager.flush_store().await;
+        
+        // 再起動を想定し、新しいマネージャーに同じデータベースから復元する
+        let restarted = AiBattleSessionManager::new(10);
+        let store: Arc<dyn store::SessionStore> = Arc::new(SqliteSessionStore::connect(&config).await.unwrap());
+        let summary = restarted.attach_store(Arc::clone(&store)).await.unwrap();
+        assert_eq!(summary, RestoreSummary { restored: 1, finished: 1, over_capacity: 0 });
+        assert_eq!(restarted.get_session(&kept).unwrap().ai_difficulty, AiDifficulty::Medium);
+        assert!(!restarted.session_exists(&removed));
+        // 終局済みの対局はメモリに読み込まないが、ストアからは消さない
+        assert!(!restarted.session_exists(&finished));
+        assert!(store.get(finished).await.unwrap().is_some());
+        
+        // メモリが空の場合は一覧をストアから返す
+        let cold = AiBattleSessionManager::new(10);
+        cold.persister.set(SessionPersister::spawn(store)).unwrap();
+        assert_eq!(cold.list_sessions_or_stored(&SessionFilter::default()).await.len(), 2);
+        
+        // 上限を超える進行中のセッションは復元しない
+        let small = AiBattleSessionManager::new(0);
+        let store: Arc<dyn store::SessionStore> = Arc::new(SqliteSessionStore::connect(&config).await.unwrap());
+        let summary = small.attach_store(store).await.unwrap();
+        assert_eq!(summary, RestoreSummary { restored: 0, finished: 1, over_capacity: 1 });
+    }
+    
     #[test]
     fn test_session_stats() {
         let manager = AiBattleSessionManager::new(10);
@@ -1,3 +1,5 @@
 pub mod ai_battle_manager;
+pub mod store;
 
-pub use ai_battle_manager::*;
\ No newline at end of file
+pub use ai_battle_manager::*;
+pub use store::{MemorySessionStore, SessionStore, SessionStoreBackend};
\ No newline at end of file
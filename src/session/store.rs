@@ -0,0 +1,140 @@
+//! セッションストアモジュール
+//! セッションの保存先を抽象化する `SessionStore` と、その実装を提供する。
+//! `MemorySessionStore` はプロセス内のDashMapに保存する既定の実装で、
+//! 設定で `sqlite` を選択した場合は `SqliteSessionStore` に永続化する。
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::api::ai_battle::AiBattleSession;
+use crate::config::DatabaseConfig;
+use crate::error::PersistenceError;
+use crate::persistence::SqliteSessionStore;
+
+/// セッションの保存先の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SessionStoreBackend {
+    /// プロセス内のメモリのみ（再起動で失われる）
+    Memory,
+    /// `DatabaseConfig.url` のSQLiteデータベース
+    #[default]
+    Sqlite,
+}
+
+/// セッションの保存先の統一インターフェース
+#[async_trait]
+pub trait SessionStore: Send + Sync + fmt::Debug {
+    /// ヘルスチェックなどに表示する実装名
+    fn backend_name(&self) -> &'static str;
+
+    async fn get(&self, session_id: Uuid) -> Result<Option<AiBattleSession>, PersistenceError>;
+
+    /// セッションを保存する（既存の場合は置き換える）
+    async fn put(&self, session: &AiBattleSession) -> Result<(), PersistenceError>;
+
+    async fn remove(&self, session_id: Uuid) -> Result<(), PersistenceError>;
+
+    /// 保存されている全セッションを更新の新しい順に返す
+    async fn list(&self) -> Result<Vec<AiBattleSession>, PersistenceError>;
+
+    /// 保存先に接続できるかを確かめる（ヘルスチェック用）
+    async fn ping(&self) -> Result<(), PersistenceError> {
+        Ok(())
+    }
+}
+
+/// DashMapにセッションを保存するストア
+/// セッションマネージャーの作業領域としても使われ、マネージャーからは同期的に参照できる
+#[derive(Debug, Clone, Default)]
+pub struct MemorySessionStore {
+    sessions: Arc<DashMap<Uuid, AiBattleSession>>,
+}
+
+impl MemorySessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Deref for MemorySessionStore {
+    type Target = DashMap<Uuid, AiBattleSession>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sessions
+    }
+}
+
+#[async_trait]
+impl SessionStore for MemorySessionStore {
+    fn backend_name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn get(&self, session_id: Uuid) -> Result<Option<AiBattleSession>, PersistenceError> {
+        Ok(self.sessions.get(&session_id).map(|session| session.clone()))
+    }
+
+    async fn put(&self, session: &AiBattleSession) -> Result<(), PersistenceError> {
+        self.sessions.insert(session.id, session.clone());
+        Ok(())
+    }
+
+    async fn remove(&self, session_id: Uuid) -> Result<(), PersistenceError> {
+        self.sessions.remove(&session_id);
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<AiBattleSession>, PersistenceError> {
+        let mut sessions: Vec<AiBattleSession> = self.sessions.iter().map(|entry| entry.value().clone()).collect();
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.last_move_at));
+        Ok(sessions)
+    }
+}
+
+/// 設定で選択された永続化先のストアを開く
+/// `Memory` の場合は永続化しないためNoneを返す
+pub async fn open_session_store(config: &DatabaseConfig) -> Result<Option<Arc<dyn SessionStore>>, PersistenceError> {
+    match config.session_store {
+        SessionStoreBackend::Memory => Ok(None),
+        SessionStoreBackend::Sqlite => {
+            let store: Arc<dyn SessionStore> = Arc::new(SqliteSessionStore::connect(config).await?);
+            Ok(Some(store))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ai_battle::AiDifficulty;
+
+    #[tokio::test]
+    async fn test_memory_store_round_trip() {
+        let store = MemorySessionStore::new();
+        let older = AiBattleSession::new(AiDifficulty::Easy);
+        let mut newer = AiBattleSession::new(AiDifficulty::Hard);
+        newer.update_last_move();
+
+        store.put(&older).await.unwrap();
+        store.put(&newer).await.unwrap();
+        assert_eq!(store.get(older.id).await.unwrap().unwrap().ai_difficulty, AiDifficulty::Easy);
+
+        let listed: Vec<Uuid> = store.list().await.unwrap().iter().map(|session| session.id).collect();
+        assert_eq!(listed, vec![newer.id, older.id]);
+
+        store.remove(older.id).await.unwrap();
+        assert!(store.get(older.id).await.unwrap().is_none());
+        assert_eq!(store.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_memory_backend_does_not_open_a_store() {
+        let config = DatabaseConfig { session_store: SessionStoreBackend::Memory, ..DatabaseConfig::default() };
+        assert!(open_session_store(&config).await.unwrap().is_none());
+    }
+}
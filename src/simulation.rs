@@ -0,0 +1,145 @@
+//! 一括シミュレーションモジュール
+//! HTTPを介さずに同じ難易度のAI同士の対局を大量に繰り返し、勝率・平均手数・平均分岐数・所要時間を集計する。
+//! 評価関数や探索を変更したときの回帰確認に使う。
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::ai::service::AIService;
+use crate::api::ai_battle::AiDifficulty;
+use crate::game::{Board, GameState, GameStatus, Player, ReversiRules};
+use crate::tournament::play_engine_game;
+
+/// シミュレーションの集計結果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulationReport {
+    pub games: u32,
+    pub black_wins: u32,
+    pub white_wins: u32,
+    pub draws: u32,
+    /// エンジンの異常で反則負けになった対局の数
+    pub forfeits: u32,
+    /// パスを除く着手の総数
+    pub total_moves: u64,
+    /// 各着手の直前の局面での合法手の数の合計
+    pub total_legal_moves: u64,
+    pub elapsed: Duration,
+}
+
+impl SimulationReport {
+    /// 終局した1局を集計に加える
+    pub fn record(&mut self, state: &GameState, forfeit: bool) {
+        self.games += 1;
+        match state.game_status {
+            GameStatus::Finished { winner: Some(Player::Black), .. } => self.black_wins += 1,
+            GameStatus::Finished { winner: Some(Player::White), .. } => self.white_wins += 1,
+            _ => self.draws += 1,
+        }
+        if forfeit {
+            self.forfeits += 1;
+        }
+
+        // 棋譜を初期局面から再生し、着手ごとの合法手の数を数える
+        let mut board = Board::new();
+        for game_move in &state.move_history {
+            self.total_legal_moves += ReversiRules::get_valid_moves(&board, game_move.player).len() as u64;
+            for position in std::iter::once(&game_move.position).chain(&game_move.flipped) {
+                board.set_cell(*position, game_move.player.to_cell());
+            }
+        }
+        self.total_moves += state.move_history.len() as u64;
+    }
+
+    fn rate(&self, count: u32) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        count as f64 * 100.0 / self.games as f64
+    }
+
+    /// 1局あたりの平均着手数（パスを除く）
+    pub fn average_moves(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.total_moves as f64 / self.games as f64
+    }
+
+    /// 平均分岐数（着手した局面での合法手の数の平均）
+    pub fn branching_factor(&self) -> f64 {
+        if self.total_moves == 0 {
+            return 0.0;
+        }
+        self.total_legal_moves as f64 / self.total_moves as f64
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.elapsed.as_secs_f64();
+        writeln!(
+            f,
+            "{}局: 黒勝率 {:.1}% / 白勝率 {:.1}% / 引き分け {:.1}% (反則負け {})",
+            self.games,
+            self.rate(self.black_wins),
+            self.rate(self.white_wins),
+            self.rate(self.draws),
+            self.forfeits,
+        )?;
+        writeln!(f, "平均手数 {:.1}  平均分岐数 {:.2}", self.average_moves(), self.branching_factor())?;
+        write!(
+            f,
+            "所要時間 {:.2}秒  1局あたり {:.2}ms  1手あたり {:.3}ms",
+            seconds,
+            seconds * 1000.0 / self.games.max(1) as f64,
+            seconds * 1000.0 / self.total_moves.max(1) as f64,
+        )
+    }
+}
+
+/// 同じ難易度のAI同士で `games` 局指し、結果を集計する
+/// シードを指定すると対局ごとに `seed + 対局番号 - 1` で乱択し、同じ結果を再現できる
+pub async fn simulate(engine: &dyn AIService, games: u32, difficulty: AiDifficulty, seed: Option<u64>) -> SimulationReport {
+    let started = Instant::now();
+    let mut report = SimulationReport::default();
+    for game in 0..games {
+        let seed = seed.map(|seed| seed.wrapping_add(game as u64));
+        let (state, forfeit) = play_engine_game((engine, difficulty), (engine, difficulty), seed).await;
+        report.record(&state, forfeit.is_some());
+    }
+    report.elapsed = started.elapsed();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::mock_service::MockAIService;
+
+    #[tokio::test]
+    async fn test_simulation_aggregates_games() {
+        let engine = MockAIService::new_fast();
+        let report = simulate(&engine, 3, AiDifficulty::Easy, Some(7)).await;
+        assert_eq!(report.games, 3);
+        assert_eq!(report.black_wins + report.white_wins + report.draws, 3);
+        assert!(report.average_moves() > 0.0);
+        // 初期局面の合法手は4つで、以降も着手した局面には必ず1つ以上ある
+        assert!(report.branching_factor() >= 1.0);
+        assert!(report.to_string().starts_with("3局: "));
+    }
+
+    #[test]
+    fn test_branching_factor_counts_legal_moves_before_each_move() {
+        let mut state = GameState::new();
+        let position = ReversiRules::get_valid_moves(&state.board, Player::Black)[0];
+        ReversiRules::apply_move(&mut state, position).unwrap();
+        state.switch_player();
+        state.finish(None);
+
+        let mut report = SimulationReport::default();
+        report.record(&state, false);
+        assert_eq!((report.draws, report.total_moves, report.total_legal_moves), (1, 1, 4));
+        assert_eq!(report.branching_factor(), 4.0);
+        assert_eq!(SimulationReport::default().branching_factor(), 0.0);
+    }
+}
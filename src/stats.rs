@@ -0,0 +1,190 @@
+//! 難易度別の対局統計モジュール
+//! 終局した人間対AIの対局をAIの難易度ごとに集計し、人間の勝率・平均手数・AIと人間の平均思考時間を求める。
+//! 集計はサーバーの起動後に終局した対局が対象で、再起動すると初期化される。
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use utoipa::ToSchema;
+
+use crate::api::ai_battle::{AiBattleSession, AiDifficulty, GameStatus};
+use crate::game::Player;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct DifficultyTally {
+    games: u32,
+    human_wins: u32,
+    ai_wins: u32,
+    draws: u32,
+    /// パスを除く着手数の合計
+    total_moves: u64,
+    total_duration_ms: u64,
+    ai_moves: u64,
+    total_ai_thinking_ms: u64,
+    human_moves: u64,
+    total_human_thinking_ms: u64,
+}
+
+/// AIの難易度ごとの対局統計
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct DifficultyStats {
+    pub difficulty: AiDifficulty,
+    /// 終局した対局数
+    pub games: u32,
+    pub human_wins: u32,
+    pub ai_wins: u32,
+    pub draws: u32,
+    /// 人間の勝率（引き分けは0.5勝として数える、対局がなければnull）
+    pub human_win_rate: Option<f64>,
+    /// 1局あたりの平均着手数（パスを除く）
+    pub average_moves: Option<f64>,
+    /// 対局開始から終局までの平均秒数
+    pub average_duration_seconds: Option<f64>,
+    /// AIの1手あたりの平均思考時間（ミリ秒）
+    pub average_ai_thinking_ms: Option<f64>,
+    /// 人間の1手あたりの平均思考時間（ミリ秒、局面を取得してから着手するまで）
+    pub average_human_thinking_ms: Option<f64>,
+}
+
+/// 難易度別の対局統計（難易度の低い順、対局のない難易度も含む）
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DifficultyStatsResponse {
+    pub difficulties: Vec<DifficultyStats>,
+}
+
+fn average(total: u64, count: u64) -> Option<f64> {
+    (count > 0).then(|| total as f64 / count as f64)
+}
+
+/// 思考時間が記録された `player` の着手の数と思考時間の合計
+fn thinking_totals(session: &AiBattleSession, player: Player) -> (u64, u64) {
+    session
+        .move_history
+        .iter()
+        .filter(|record| record.player == player && !record.is_pass())
+        .filter_map(|record| record.thinking_time_ms)
+        .fold((0, 0), |(count, total), thinking_ms| (count + 1, total + thinking_ms))
+}
+
+/// 終局した対局を難易度ごとに集計する
+#[derive(Debug, Default)]
+pub struct DifficultyStatsAggregator {
+    tallies: Mutex<HashMap<AiDifficulty, DifficultyTally>>,
+}
+
+impl DifficultyStatsAggregator {
+    /// 終局した人間対AIの対局を集計に加える（それ以外の対局は無視する）
+    pub fn record_game(&self, session: &AiBattleSession) {
+        let GameStatus::Finished { winner } = session.status else {
+            return;
+        };
+        let Some(human) = session.player_color() else {
+            return;
+        };
+        let ai = human.opposite();
+        let Some(difficulty) = session.controller(ai).difficulty() else {
+            return;
+        };
+
+        let (ai_moves, ai_thinking_ms) = thinking_totals(session, ai);
+        let (human_moves, human_thinking_ms) = thinking_totals(session, human);
+        let duration_ms = (session.last_move_at - session.created_at).num_milliseconds().max(0) as u64;
+
+        let mut tallies = self.tallies.lock().unwrap();
+        let tally = tallies.entry(difficulty).or_default();
+        tally.games += 1;
+        match winner {
+            Some(player) if player == human => tally.human_wins += 1,
+            Some(_) => tally.ai_wins += 1,
+            None => tally.draws += 1,
+        }
+        tally.total_moves += session.game_state.move_history.len() as u64;
+        tally.total_duration_ms += duration_ms;
+        tally.ai_moves += ai_moves;
+        tally.total_ai_thinking_ms += ai_thinking_ms;
+        tally.human_moves += human_moves;
+        tally.total_human_thinking_ms += human_thinking_ms;
+    }
+
+    pub fn snapshot(&self) -> DifficultyStatsResponse {
+        let tallies = self.tallies.lock().unwrap();
+        let difficulties = AiDifficulty::all()
+            .into_iter()
+            .map(|difficulty| {
+                let tally = tallies.get(&difficulty).copied().unwrap_or_default();
+                let games = tally.games as u64;
+                DifficultyStats {
+                    difficulty,
+                    games: tally.games,
+                    human_wins: tally.human_wins,
+                    ai_wins: tally.ai_wins,
+                    draws: tally.draws,
+                    human_win_rate: (games > 0)
+                        .then(|| (tally.human_wins as f64 + tally.draws as f64 * 0.5) / games as f64),
+                    average_moves: average(tally.total_moves, games),
+                    average_duration_seconds: average(tally.total_duration_ms, games).map(|ms| ms / 1000.0),
+                    average_ai_thinking_ms: average(tally.total_ai_thinking_ms, tally.ai_moves),
+                    average_human_thinking_ms: average(tally.total_human_thinking_ms, tally.human_moves),
+                }
+            })
+            .collect();
+        DifficultyStatsResponse { difficulties }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ai_battle::{MoveRecord, PlayerController};
+    use crate::game::Position;
+
+    fn finished_game(difficulty: AiDifficulty, human: Player, winner: Option<Player>, ai_thinking_ms: &[u64]) -> AiBattleSession {
+        let mut session = AiBattleSession::new_with_color(difficulty, human);
+        for (index, &thinking_ms) in ai_thinking_ms.iter().enumerate() {
+            session.move_history.push(MoveRecord::new(human.opposite(), Position::new(index, 0).unwrap(), Some(thinking_ms)));
+        }
+        session.status = GameStatus::Finished { winner };
+        session
+    }
+
+    #[test]
+    fn test_outcomes_are_grouped_by_ai_difficulty() {
+        let stats = DifficultyStatsAggregator::default();
+        stats.record_game(&finished_game(AiDifficulty::Hard, Player::Black, Some(Player::White), &[100, 300]));
+        stats.record_game(&finished_game(AiDifficulty::Hard, Player::White, Some(Player::White), &[200]));
+        stats.record_game(&finished_game(AiDifficulty::Hard, Player::Black, None, &[]));
+        stats.record_game(&finished_game(AiDifficulty::Easy, Player::Black, Some(Player::Black), &[]));
+
+        // 進行中の対局・AI同士の対局は集計しない
+        stats.record_game(&AiBattleSession::new(AiDifficulty::Medium));
+        let mut exhibition = finished_game(AiDifficulty::Medium, Player::Black, None, &[]);
+        exhibition.black = PlayerController::Ai { difficulty: AiDifficulty::Medium };
+        stats.record_game(&exhibition);
+
+        let report = stats.snapshot();
+        let summary: Vec<_> = report.difficulties.iter().map(|entry| (entry.difficulty, entry.games)).collect();
+        assert_eq!(summary, vec![(AiDifficulty::Easy, 1), (AiDifficulty::Medium, 0), (AiDifficulty::Hard, 3)]);
+
+        let hard = &report.difficulties[2];
+        assert_eq!((hard.human_wins, hard.ai_wins, hard.draws), (1, 1, 1));
+        assert_eq!(hard.human_win_rate, Some(0.5));
+        assert_eq!(hard.average_ai_thinking_ms, Some(200.0));
+        assert_eq!(hard.average_human_thinking_ms, None);
+        assert_eq!(report.difficulties[0].human_win_rate, Some(1.0));
+        assert_eq!(report.difficulties[1].human_win_rate, None);
+    }
+
+    #[test]
+    fn test_human_thinking_time_is_averaged_separately() {
+        let stats = DifficultyStatsAggregator::default();
+        let mut game = finished_game(AiDifficulty::Easy, Player::Black, None, &[50]);
+        for (index, thinking_ms) in [1000, 3000].into_iter().enumerate() {
+            game.move_history.push(MoveRecord::new(Player::Black, Position::new(index, 1).unwrap(), Some(thinking_ms)));
+        }
+        stats.record_game(&game);
+
+        let easy = &stats.snapshot().difficulties[0];
+        assert_eq!(easy.average_ai_thinking_ms, Some(50.0));
+        assert_eq!(easy.average_human_thinking_ms, Some(2000.0));
+    }
+}
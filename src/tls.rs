@@ -0,0 +1,115 @@
+//! TLSモジュール
+//! 設定（`ServerConfig.tls`）の証明書と秘密鍵を読み込み、サーバー自身でHTTPSを終端する。
+//! `redirect_http_port` を指定した場合は、そのポートで受けたHTTPリクエストをHTTPSのURLへ恒久リダイレクトする。
+
+use std::io;
+
+use axum::{
+    http::{header, HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Redirect, Response},
+    Router,
+};
+use axum_server::tls_rustls::RustlsConfig;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use crate::config::TlsConfig;
+
+/// 証明書チェーンと秘密鍵（PEM形式）からTLSの設定を作る
+pub async fn load_rustls_config(config: &TlsConfig) -> io::Result<RustlsConfig> {
+    // 暗号プロバイダーにはringを使う（設定済みの場合はそのまま）
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    RustlsConfig::from_pem_file(&config.cert_path, &config.key_path).await
+}
+
+/// 全てのHTTPリクエストを同じホストのHTTPSへリダイレクトするルーター
+pub fn https_redirect_router(https_port: u16) -> Router {
+    Router::new().fallback(move |headers: HeaderMap, uri: Uri| async move {
+        redirect_to_https(&headers, &uri, https_port)
+    })
+}
+
+/// HTTPからHTTPSへのリダイレクトを指定したポートで待ち受ける
+pub async fn spawn_https_redirect(host: &str, http_port: u16, https_port: u16) -> io::Result<JoinHandle<()>> {
+    let listener = TcpListener::bind((host, http_port)).await?;
+    Ok(tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, https_redirect_router(https_port)).await {
+            eprintln!("HTTPSリダイレクトの待ち受けが停止しました: {}", e);
+        }
+    }))
+}
+
+fn redirect_to_https(headers: &HeaderMap, uri: &Uri, https_port: u16) -> Response {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .map(strip_port)
+        .filter(|host| !host.is_empty());
+    let Some(host) = host else {
+        return (StatusCode::BAD_REQUEST, "Hostヘッダーがありません").into_response();
+    };
+
+    let path = uri.path_and_query().map(|path| path.as_str()).unwrap_or("/");
+    let location = if https_port == 443 {
+        format!("https://{}{}", host, path)
+    } else {
+        format!("https://{}:{}{}", host, https_port, path)
+    };
+    Redirect::permanent(&location).into_response()
+}
+
+/// `Host` ヘッダーからポート番号を除く（IPv6アドレスの角括弧は残す）
+fn strip_port(host: &str) -> &str {
+    if host.starts_with('[') {
+        return host.find(']').map_or(host, |end| &host[..=end]);
+    }
+    host.split_once(':').map_or(host, |(name, _)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn location(https_port: u16, uri: &str, host: Option<&str>) -> (StatusCode, Option<String>) {
+        let mut builder = Request::get(uri);
+        if let Some(host) = host {
+            builder = builder.header(header::HOST, host);
+        }
+        let response = https_redirect_router(https_port)
+            .oneshot(builder.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let location = response
+            .headers()
+            .get(header::LOCATION)
+            .map(|value| value.to_str().unwrap().to_string());
+        (response.status(), location)
+    }
+
+    #[tokio::test]
+    async fn test_http_requests_redirect_to_https() {
+        let expected = Some("https://reversi.example.com/api/ai-battle/sessions?page=2".to_string());
+        assert_eq!(
+            location(443, "/api/ai-battle/sessions?page=2", Some("reversi.example.com:80")).await,
+            (StatusCode::PERMANENT_REDIRECT, expected),
+        );
+        assert_eq!(
+            location(8443, "/health", Some("[::1]:8080")).await.1,
+            Some("https://[::1]:8443/health".to_string()),
+        );
+        assert_eq!(location(443, "/health", None).await.0, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_missing_certificate_fails_to_load() {
+        let config = TlsConfig {
+            cert_path: "/nonexistent/cert.pem".to_string(),
+            key_path: "/nonexistent/key.pem".to_string(),
+            redirect_http_port: None,
+        };
+        assert!(load_rustls_config(&config).await.is_err());
+    }
+}
@@ -0,0 +1,461 @@
+//! 大会モジュール
+//! 設定したAIエンジン（AIサービスの種類と難易度の組）同士で総当たり戦を行い、対局結果と順位表を保持する。
+//! 評価関数などの変更で実際にAIが強くなったかを確かめるために使う。
+//! 対局はセッションを作らずエンジン同士で直接指し進め、大会はバックグラウンドで進行する。
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::ai::mock_service::MockAIService;
+use crate::ai::service::{AIService, AIServiceConfig, AIServiceFactory, AIServiceType};
+use crate::api::ai_battle::{AiBattleError, AiBattleResult, AiDifficulty};
+use crate::archive::{GameResult, Outcome};
+use crate::error::AIError;
+use crate::game::{GameState, GameStatus, Player, ReversiRules};
+
+/// 大会の参加者数の上限
+pub const MAX_ENTRANTS: usize = 8;
+
+/// 1組あたりの対局数の上限
+pub const MAX_GAMES_PER_PAIRING: u32 = 10;
+
+/// 大会に参加するAIエンジン
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct EngineSpec {
+    /// 順位表に表示する名前（大会内で一意）
+    pub name: String,
+    #[schema(value_type = String, example = "Local")]
+    pub service: AIServiceType,
+    /// HTTPエンジンの接続先
+    #[serde(default)]
+    pub endpoint_url: Option<String>,
+    pub difficulty: AiDifficulty,
+}
+
+impl EngineSpec {
+    /// 大会用のエンジンを作成する（思考時間の演出は行わない）
+    fn build(&self) -> Result<Arc<dyn AIService>, AIError> {
+        let service: Box<dyn AIService> = match self.service {
+            AIServiceType::Local => AIServiceFactory::create_fast_local()?,
+            AIServiceType::Mock => Box::new(MockAIService::new_fast()),
+            AIServiceType::Http => AIServiceFactory::create_service(&AIServiceConfig {
+                service_type: AIServiceType::Http,
+                endpoint_url: self.endpoint_url.clone(),
+                queue_workers: 0,
+                ..AIServiceConfig::default()
+            })?,
+        };
+        Ok(service.into())
+    }
+}
+
+fn default_games_per_pairing() -> u32 {
+    2
+}
+
+/// 大会の作成リクエスト
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CreateTournamentRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 2〜8のエンジン
+    pub entrants: Vec<EngineSpec>,
+    /// 各組の対局数（1〜10、既定は2）。先後は1局ごとに入れ替える
+    #[serde(default = "default_games_per_pairing")]
+    pub games_per_pairing: u32,
+    /// 設定した場合、各対局のAIの乱択をこのシードから決定的に行う
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl CreateTournamentRequest {
+    fn validate(&self) -> Result<(), AiBattleError> {
+        let bad_request = |details: String| AiBattleError::BadRequest { details };
+        if !(2..=MAX_ENTRANTS).contains(&self.entrants.len()) {
+            return Err(bad_request(format!("参加エンジンは2〜{}個で指定してください", MAX_ENTRANTS)));
+        }
+        if !(1..=MAX_GAMES_PER_PAIRING).contains(&self.games_per_pairing) {
+            return Err(bad_request(format!("games_per_pairing は1〜{}で指定してください", MAX_GAMES_PER_PAIRING)));
+        }
+        let mut names = HashSet::new();
+        for entrant in &self.entrants {
+            if entrant.name.trim().is_empty() {
+                return Err(bad_request("エンジン名を指定してください".to_string()));
+            }
+            if !names.insert(entrant.name.as_str()) {
+                return Err(bad_request(format!("エンジン名が重複しています: {}", entrant.name)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 大会の進行状況
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TournamentStatus {
+    Running,
+    Finished,
+}
+
+/// 大会の1局の結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TournamentGame {
+    /// 1から始まる対局番号
+    pub number: u32,
+    /// 黒番のエンジン名
+    pub black: String,
+    /// 白番のエンジン名
+    pub white: String,
+    pub result: GameResult,
+    pub black_count: u8,
+    pub white_count: u8,
+    /// パスを除く着手数
+    pub moves: u32,
+    /// エンジンのエラーや不正な着手で決着した場合、その理由（負けたのは手番側）
+    pub forfeit: Option<String>,
+}
+
+/// 順位表の1行
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct Standing {
+    pub rank: u32,
+    pub name: String,
+    pub games: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// 勝ち1点・引き分け0.5点
+    pub points: f64,
+    /// 自分の石数から相手の石数を引いた値の合計
+    pub disc_difference: i32,
+}
+
+/// 大会の状態と順位表
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TournamentResponse {
+    pub tournament_id: Uuid,
+    pub name: String,
+    pub status: TournamentStatus,
+    pub entrants: Vec<EngineSpec>,
+    pub games_per_pairing: u32,
+    /// 全対局数
+    pub total_games: u32,
+    /// 勝ち点・石数差の順
+    pub standings: Vec<Standing>,
+    /// 終局した対局（対局番号順）
+    pub games: Vec<TournamentGame>,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// 大会の一覧
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TournamentListResponse {
+    /// 作成の新しい順
+    pub tournaments: Vec<TournamentResponse>,
+}
+
+#[derive(Debug, Clone)]
+struct Tournament {
+    id: Uuid,
+    name: String,
+    status: TournamentStatus,
+    entrants: Vec<EngineSpec>,
+    games_per_pairing: u32,
+    seed: Option<u64>,
+    games: Vec<TournamentGame>,
+    created_at: DateTime<Utc>,
+    finished_at: Option<DateTime<Utc>>,
+}
+
+impl Tournament {
+    /// 総当たりの組み合わせ（各組の中で先後を入れ替える）
+    fn schedule(&self) -> Vec<(usize, usize)> {
+        let count = self.entrants.len();
+        let mut schedule = Vec::new();
+        for a in 0..count {
+            for b in a + 1..count {
+                for game in 0..self.games_per_pairing {
+                    schedule.push(if game % 2 == 0 { (a, b) } else { (b, a) });
+                }
+            }
+        }
+        schedule
+    }
+
+    fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self.entrants
+            .iter()
+            .map(|entrant| Standing {
+                rank: 0,
+                name: entrant.name.clone(),
+                games: 0,
+                wins: 0,
+                losses: 0,
+                draws: 0,
+                points: 0.0,
+                disc_difference: 0,
+            })
+            .collect();
+
+        for game in &self.games {
+            for (name, color) in [(&game.black, Player::Black), (&game.white, Player::White)] {
+                let Some(standing) = standings.iter_mut().find(|standing| &standing.name == name) else {
+                    continue;
+                };
+                let (own, other) = match color {
+                    Player::Black => (game.black_count, game.white_count),
+                    Player::White => (game.white_count, game.black_count),
+                };
+                standing.games += 1;
+                standing.disc_difference += own as i32 - other as i32;
+                match game.result.outcome_for(color) {
+                    Outcome::Win => {
+                        standing.wins += 1;
+                        standing.points += 1.0;
+                    }
+                    Outcome::Loss => standing.losses += 1,
+                    Outcome::Draw => {
+                        standing.draws += 1;
+                        standing.points += 0.5;
+                    }
+                }
+            }
+        }
+
+        standings.sort_by(|a, b| {
+            b.points
+                .total_cmp(&a.points)
+                .then(b.disc_difference.cmp(&a.disc_difference))
+                .then(a.name.cmp(&b.name))
+        });
+        for (index, standing) in standings.iter_mut().enumerate() {
+            standing.rank = index as u32 + 1;
+        }
+        standings
+    }
+
+    fn to_response(&self) -> TournamentResponse {
+        TournamentResponse {
+            tournament_id: self.id,
+            name: self.name.clone(),
+            status: self.status,
+            entrants: self.entrants.clone(),
+            games_per_pairing: self.games_per_pairing,
+            total_games: self.schedule().len() as u32,
+            standings: self.standings(),
+            games: self.games.clone(),
+            created_at: self.created_at,
+            finished_at: self.finished_at,
+        }
+    }
+}
+
+/// エンジン同士で1局を指し切る
+/// エンジンがエラーを返すか不正な手を返した場合は、その手番側の負けとする
+pub async fn play_engine_game(
+    black: (&dyn AIService, AiDifficulty),
+    white: (&dyn AIService, AiDifficulty),
+    seed: Option<u64>,
+) -> (GameState, Option<String>) {
+    let mut state = GameState::new();
+    while !state.is_finished() {
+        let player = state.current_player;
+        if !ReversiRules::has_valid_moves(&state.board, player) {
+            ReversiRules::handle_turn(&mut state);
+            continue;
+        }
+
+        let (engine, difficulty) = match player {
+            Player::Black => black,
+            Player::White => white,
+        };
+        let result = match seed {
+            Some(seed) => engine.calculate_move_seeded(&state, difficulty, seed.wrapping_add(state.move_history.len() as u64)).await,
+            None => engine.calculate_move(&state, difficulty).await,
+        };
+        let applied = result
+            .map_err(|e| e.to_string())
+            .and_then(|result| ReversiRules::apply_move(&mut state, result.position).map_err(|e| e.to_string()));
+        if let Err(reason) = applied {
+            state.finish(Some(player.opposite()));
+            return (state, Some(format!("{:?}: {}", player, reason)));
+        }
+
+        state.switch_player();
+        ReversiRules::handle_turn(&mut state);
+    }
+    (state, None)
+}
+
+/// 大会の作成・進行と結果の参照
+#[derive(Debug, Default)]
+pub struct Tournaments {
+    tournaments: DashMap<Uuid, Tournament>,
+}
+
+impl Tournaments {
+    /// 大会を登録し、バックグラウンドで総当たり戦を始める
+    pub fn start(self: &Arc<Self>, request: CreateTournamentRequest) -> AiBattleResult<TournamentResponse> {
+        request.validate()?;
+        let engines = request.entrants
+            .iter()
+            .map(|entrant| {
+                entrant.build().map_err(|e| AiBattleError::BadRequest {
+                    details: format!("エンジン {} を作成できません: {}", entrant.name, e),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        let tournament = Tournament {
+            id,
+            name: request.name.unwrap_or_else(|| format!("Tournament {}", now.format("%Y-%m-%d %H:%M"))),
+            status: TournamentStatus::Running,
+            entrants: request.entrants,
+            games_per_pairing: request.games_per_pairing,
+            seed: request.seed,
+            games: Vec::new(),
+            created_at: now,
+            finished_at: None,
+        };
+        let response = tournament.to_response();
+        self.tournaments.insert(id, tournament);
+
+        let tournaments = Arc::clone(self);
+        tokio::spawn(async move { tournaments.run(id, engines).await });
+        Ok(response)
+    }
+
+    async fn run(&self, id: Uuid, engines: Vec<Arc<dyn AIService>>) {
+        let Some((schedule, entrants, seed)) = self.tournaments
+            .get(&id)
+            .map(|tournament| (tournament.schedule(), tournament.entrants.clone(), tournament.seed))
+        else {
+            return;
+        };
+
+        for (index, (black, white)) in schedule.into_iter().enumerate() {
+            let number = index as u32 + 1;
+            let (state, forfeit) = play_engine_game(
+                (engines[black].as_ref(), entrants[black].difficulty),
+                (engines[white].as_ref(), entrants[white].difficulty),
+                seed.map(|seed| seed.wrapping_add(number as u64 * 1000)),
+            )
+            .await;
+
+            let (black_count, white_count) = state.get_score();
+            let winner = match state.game_status {
+                GameStatus::Finished { winner, .. } => winner,
+                _ => None,
+            };
+            let game = TournamentGame {
+                number,
+                black: entrants[black].name.clone(),
+                white: entrants[white].name.clone(),
+                result: GameResult::from_winner(winner),
+                black_count,
+                white_count,
+                moves: state.move_history.len() as u32,
+                forfeit,
+            };
+            let Some(mut tournament) = self.tournaments.get_mut(&id) else {
+                return;
+            };
+            tournament.games.push(game);
+        }
+
+        if let Some(mut tournament) = self.tournaments.get_mut(&id) {
+            tournament.status = TournamentStatus::Finished;
+            tournament.finished_at = Some(Utc::now());
+        }
+    }
+
+    pub fn get(&self, id: Uuid) -> AiBattleResult<TournamentResponse> {
+        self.tournaments
+            .get(&id)
+            .map(|tournament| tournament.to_response())
+            .ok_or(AiBattleError::TournamentNotFound { tournament_id: id })
+    }
+
+    pub fn list(&self) -> Vec<TournamentResponse> {
+        let mut tournaments: Vec<TournamentResponse> = self.tournaments
+            .iter()
+            .map(|entry| entry.value().to_response())
+            .collect();
+        tournaments.sort_by_key(|tournament| std::cmp::Reverse(tournament.created_at));
+        tournaments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine(name: &str, service: AIServiceType, difficulty: AiDifficulty) -> EngineSpec {
+        EngineSpec { name: name.to_string(), service, endpoint_url: None, difficulty }
+    }
+
+    fn request(entrants: Vec<EngineSpec>, games_per_pairing: u32) -> CreateTournamentRequest {
+        CreateTournamentRequest { name: None, entrants, games_per_pairing, seed: Some(7) }
+    }
+
+    #[test]
+    fn test_invalid_tournaments_are_rejected() {
+        let tournaments = Arc::new(Tournaments::default());
+        let mock = engine("mock", AIServiceType::Mock, AiDifficulty::Easy);
+        let http = engine("http", AIServiceType::Http, AiDifficulty::Easy);
+
+        for request in [
+            request(vec![mock.clone()], 2),
+            request(vec![mock.clone(), mock.clone()], 2),
+            request(vec![mock.clone(), engine("other", AIServiceType::Mock, AiDifficulty::Hard)], 0),
+            request(vec![mock.clone(), http], 2),
+        ] {
+            assert!(matches!(tournaments.start(request), Err(AiBattleError::BadRequest { .. })));
+        }
+        assert!(tournaments.list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_produces_standings() {
+        let tournaments = Arc::new(Tournaments::default());
+        let entrants = vec![
+            engine("mock-a", AIServiceType::Mock, AiDifficulty::Easy),
+            engine("mock-b", AIServiceType::Mock, AiDifficulty::Easy),
+            engine("local-easy", AIServiceType::Local, AiDifficulty::Easy),
+        ];
+        let started = tournaments.start(request(entrants, 2)).unwrap();
+        assert_eq!(started.status, TournamentStatus::Running);
+        assert_eq!(started.total_games, 6);
+
+        let mut finished = started;
+        for _ in 0..200 {
+            finished = tournaments.get(finished.tournament_id).unwrap();
+            if finished.status == TournamentStatus::Finished {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(finished.status, TournamentStatus::Finished);
+        assert_eq!(finished.games.len(), 6);
+        assert!(finished.games.iter().all(|game| game.forfeit.is_none()));
+
+        // 各エンジンは4局ずつ指し、勝ち点の合計は対局数に等しい
+        assert!(finished.standings.iter().all(|standing| standing.games == 4));
+        assert_eq!(finished.standings.iter().map(|standing| standing.points).sum::<f64>(), 6.0);
+        assert_eq!(finished.standings.iter().map(|standing| standing.disc_difference).sum::<i32>(), 0);
+        assert_eq!(finished.standings[0].rank, 1);
+
+        // 先後を入れ替えている
+        let first_pairing: Vec<_> = finished.games[..2].iter().map(|game| game.black.as_str()).collect();
+        assert_eq!(first_pairing, vec!["mock-a", "mock-b"]);
+    }
+}
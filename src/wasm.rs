@@ -0,0 +1,154 @@
+//! ブラウザ向けのwasm-bindgenラッパー
+//! ゲームロジック（盤面・合法手・着手）とAI戦略をJavaScriptから使えるようにする。
+//! マスは `行 * 8 + 列` の番号（0〜63）、石の色は 0=空き・1=黒・2=白 の数値でやり取りする。
+
+use wasm_bindgen::prelude::*;
+
+use crate::ai::evaluation::{BoardEvaluator, EvalWeights};
+use crate::ai::strategies::{create_seeded_ai_strategy, Difficulty};
+use crate::game::{Cell, GameState, Player, Position, ReversiRules};
+
+fn cell_code(cell: Cell) -> u8 {
+    match cell {
+        Cell::Empty => 0,
+        Cell::Black => 1,
+        Cell::White => 2,
+    }
+}
+
+fn player_code(player: Player) -> u8 {
+    cell_code(player.to_cell())
+}
+
+fn position_index(position: Position) -> u8 {
+    (position.row * 8 + position.col) as u8
+}
+
+fn index_position(index: u8) -> Option<Position> {
+    Position::new(index as usize / 8, index as usize % 8)
+}
+
+/// JavaScriptから操作する対局
+/// 着手後のパス判定・終局判定まで行い、常に次に打つ側の手番にしておく
+#[wasm_bindgen(js_name = Board)]
+#[derive(Debug, Clone)]
+pub struct WasmBoard {
+    state: GameState,
+}
+
+impl Default for WasmBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen(js_class = Board)]
+impl WasmBoard {
+    /// 初期配置の対局を作る
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self { state: GameState::new() }
+    }
+
+    /// 64マス分の石の色（a1からh8へ行ごと）
+    pub fn cells(&self) -> Vec<u8> {
+        (0..64)
+            .map(|index| index_position(index).and_then(|position| self.state.board.get_cell(position)).map_or(0, cell_code))
+            .collect()
+    }
+
+    /// 手番の色
+    #[wasm_bindgen(js_name = currentPlayer)]
+    pub fn current_player(&self) -> u8 {
+        player_code(self.state.current_player)
+    }
+
+    /// 手番の合法手のマス番号
+    #[wasm_bindgen(js_name = validMoves)]
+    pub fn valid_moves(&self) -> Vec<u8> {
+        ReversiRules::get_valid_moves(&self.state.board, self.state.current_player)
+            .into_iter()
+            .map(position_index)
+            .collect()
+    }
+
+    /// 手番の色で着手し、裏返したマス番号を返す
+    #[wasm_bindgen(js_name = applyMove)]
+    pub fn apply_move(&mut self, index: u8) -> Result<Vec<u8>, JsError> {
+        self.play(index).map_err(|message| JsError::new(&message))
+    }
+
+    #[wasm_bindgen(js_name = isFinished)]
+    pub fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+
+    /// 黒・白の石数
+    pub fn score(&self) -> Vec<u8> {
+        let (black, white) = self.state.get_score();
+        vec![black, white]
+    }
+
+    /// 勝者の色（進行中・引き分けの場合は undefined）
+    pub fn winner(&self) -> Option<u8> {
+        if !self.state.is_finished() {
+            return None;
+        }
+        ReversiRules::determine_winner(&self.state.board, self.state.variant).map(player_code)
+    }
+
+    /// 手番から見た盤面の評価値（正なら手番が有利）
+    pub fn evaluate(&self) -> f32 {
+        BoardEvaluator::evaluate_position(&self.state.board, self.state.current_player, &EvalWeights::default())
+    }
+
+    /// 指定した難易度（easy / medium / hard など）のAIが選ぶ手のマス番号
+    #[wasm_bindgen(js_name = aiMove)]
+    pub fn ai_move(&self, difficulty: &str, seed: Option<u64>) -> Result<u8, JsError> {
+        self.choose_move(difficulty, seed).map_err(|message| JsError::new(&message))
+    }
+}
+
+impl WasmBoard {
+    /// `JsError` はwasm以外のターゲットでは作れないため、エラーは文字列で返してバインディング側で変換する
+    fn play(&mut self, index: u8) -> Result<Vec<u8>, String> {
+        let position = index_position(index).ok_or_else(|| format!("マス番号は0〜63で指定してください: {}", index))?;
+        let flipped = ReversiRules::apply_move(&mut self.state, position).map_err(|e| e.to_string())?;
+        self.state.switch_player();
+        ReversiRules::handle_turn(&mut self.state);
+        Ok(flipped.into_iter().map(position_index).collect())
+    }
+
+    fn choose_move(&self, difficulty: &str, seed: Option<u64>) -> Result<u8, String> {
+        let difficulty: Difficulty = difficulty.parse()?;
+        create_seeded_ai_strategy(difficulty, seed)
+            .calculate_move(&self.state)
+            .map(position_index)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board_plays_through_index_api() {
+        let mut board = WasmBoard::new();
+        assert_eq!(board.current_player(), 1);
+        assert_eq!(board.valid_moves(), vec![19, 26, 37, 44]);
+
+        // f5（4行5列）に黒が打つと e5 が裏返る
+        assert_eq!(board.play(37).unwrap(), vec![36]);
+        assert_eq!(board.current_player(), 2);
+        assert_eq!(board.score(), vec![4, 1]);
+        assert_eq!(board.cells()[36], 1);
+        assert!(board.play(0).is_err());
+        assert!(board.play(64).is_err());
+
+        let index = board.choose_move("easy", Some(7)).unwrap();
+        assert!(board.valid_moves().contains(&index));
+        assert!(board.choose_move("expert", None).is_err());
+        assert_eq!(board.winner(), None);
+    }
+}
@@ -15,7 +15,8 @@ use uuid::Uuid;
 
 use Reversi::{
     api::{handlers::AppState, routes::{create_router, create_ai_battle_router}},
-    config::Config,
+    api::auth::{self, ApiKeyRegistry},
+    config::{ApiKeyEntry, AuthConfig, Config},
 };
 
 async fn create_test_app() -> axum::Router {
@@ -76,7 +77,7 @@ async fn test_ai_battle_full_workflow() {
     assert_eq!(get_response.status(), StatusCode::OK);
     let game_state = parse_response_json(get_response).await;
     assert_eq!(game_state["game_id"], game_id);
-    assert_eq!(game_state["current_player"], "Black");
+    assert_eq!(game_state["current_player"], "black");
     assert!(game_state["valid_moves"].is_array());
     
     let valid_moves = game_state["valid_moves"].as_array().unwrap();
@@ -88,8 +89,8 @@ async fn test_ai_battle_full_workflow() {
         Method::POST,
         &format!("/api/ai-battle/{}/move", game_id),
         Some(json!({
-            "row": first_move[0],
-            "col": first_move[1]
+            "row": first_move["row"],
+            "col": first_move["col"]
         }))
     ).await;
     
@@ -205,7 +206,9 @@ async fn test_error_responses() {
         Some(json!({"difficulty": "invalid"}))
     ).await;
     
-    assert_eq!(invalid_difficulty_response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(invalid_difficulty_response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let error_data = parse_response_json(invalid_difficulty_response).await;
+    assert_eq!(error_data["error_code"], "INVALID_REQUEST_BODY");
     
     // 無効な座標での着手
     let create_response = send_request(
@@ -274,7 +277,7 @@ async fn test_concurrent_session_creation() {
     let results: Vec<_> = futures::future::join_all(handles).await;
     
     // 全てのセッション作成が成功することを確認
-    for (i, result) in results {
+    for result in results {
         let (thread_id, status) = result.unwrap();
         println!("Thread {}: {:?}", thread_id, status);
         assert_eq!(status, StatusCode::CREATED);
@@ -367,7 +370,7 @@ async fn test_game_state_consistency() {
     let game_id = game_data["game_id"].as_str().unwrap();
     
     // 初期状態確認
-    assert_eq!(game_data["current_player"], "Black");
+    assert_eq!(game_data["current_player"], "black");
     assert_eq!(game_data["black_count"], 2);
     assert_eq!(game_data["white_count"], 2);
     assert_eq!(game_data["move_count"], 0);
@@ -433,7 +436,7 @@ async fn test_all_http_methods_and_endpoints() {
     ];
     
     for (method, endpoint, body) in endpoints {
-        let response = send_request(&mut app, method, endpoint, body).await;
+        let response = send_request(&mut app, method.clone(), endpoint, body).await;
         assert!(
             response.status().is_success() || response.status() == StatusCode::CREATED,
             "Endpoint {} {} failed with status: {:?}",
@@ -463,7 +466,7 @@ async fn test_all_http_methods_and_endpoints() {
     ];
     
     for (method, endpoint, body) in game_endpoints {
-        let response = send_request(&mut app, method, &endpoint, body).await;
+        let response = send_request(&mut app, method.clone(), &endpoint, body).await;
         assert!(
             response.status().is_success() || response.status().is_client_error(),
             "Endpoint {} {} failed with status: {:?}",
@@ -472,4 +475,298 @@ async fn test_all_http_methods_and_endpoints() {
             response.status()
         );
     }
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn test_legacy_api_is_marked_deprecated() {
+    let mut app = create_test_app().await;
+    
+    let response = send_request(
+        &mut app,
+        Method::POST,
+        "/api/games",
+        Some(json!({
+            "player1_type": {"Human": {"name": "player"}},
+            "player2_type": {"AI": {"difficulty": "Beginner"}}
+        }))
+    ).await;
+    
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["Deprecation"], "true");
+    assert!(response.headers()["Link"].to_str().unwrap().contains("/api/ai-battle"));
+    
+    let game = parse_response_json(response).await;
+    assert_eq!(game["board"][3][3], 2);
+    assert_eq!(game["board"][3][4], 1);
+    
+    // 新APIには非推奨ヘッダーが付与されない
+    let health_response = send_request(&mut app, Method::GET, "/health", None).await;
+    assert!(health_response.headers().get("Deprecation").is_none());
+}
+
+#[tokio::test]
+async fn test_hint_endpoint() {
+    let mut app = create_test_app().await;
+    
+    let create_response = send_request(
+        &mut app,
+        Method::POST,
+        "/api/ai-battle",
+        Some(json!({"difficulty": "Easy"}))
+    ).await;
+    let game_data = parse_response_json(create_response).await;
+    let game_id = game_data["game_id"].as_str().unwrap();
+    
+    let hint_response = send_request(
+        &mut app,
+        Method::GET,
+        &format!("/api/ai-battle/{}/hint?difficulty=easy", game_id),
+        None
+    ).await;
+    
+    assert_eq!(hint_response.status(), StatusCode::OK);
+    let hint = parse_response_json(hint_response).await;
+    assert!(hint["suggested_move"].is_object());
+    assert_eq!(hint["difficulty"], "Easy");
+    
+    // ヒント取得後も盤面は変化しない
+    let state_response = send_request(
+        &mut app,
+        Method::GET,
+        &format!("/api/ai-battle/{}", game_id),
+        None
+    ).await;
+    let state = parse_response_json(state_response).await;
+    assert_eq!(state["move_count"], 0);
+    
+    let invalid_response = send_request(
+        &mut app,
+        Method::GET,
+        &format!("/api/ai-battle/{}/hint?difficulty=impossible", game_id),
+        None
+    ).await;
+    assert_eq!(invalid_response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_analyze_endpoint() {
+    let mut app = create_test_app().await;
+    
+    let create_response = send_request(
+        &mut app,
+        Method::POST,
+        "/api/ai-battle",
+        Some(json!({"difficulty": "easy"}))
+    ).await;
+    let game_data = parse_response_json(create_response).await;
+    let game_id = game_data["game_id"].as_str().unwrap();
+    
+    let analyze_response = send_request(
+        &mut app,
+        Method::POST,
+        &format!("/api/ai-battle/{}/analyze", game_id),
+        Some(json!({}))
+    ).await;
+    assert_eq!(analyze_response.status(), StatusCode::OK);
+    
+    let analysis = parse_response_json(analyze_response).await;
+    let moves = analysis["moves"].as_array().unwrap();
+    assert_eq!(moves.len(), game_data["valid_moves"].as_array().unwrap().len());
+    
+    let scores: Vec<f64> = moves.iter().map(|entry| entry["score"].as_f64().unwrap()).collect();
+    assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]));
+    assert!(moves.iter().all(|entry| entry["depth"].as_u64().unwrap() >= 1));
+}
+
+#[tokio::test]
+async fn test_create_ai_battle_as_white() {
+    let mut app = create_test_app().await;
+    
+    let create_response = send_request(
+        &mut app,
+        Method::POST,
+        "/api/ai-battle",
+        Some(json!({"difficulty": "easy", "player_color": "white"}))
+    ).await;
+    assert_eq!(create_response.status(), StatusCode::CREATED);
+    
+    let game_data = parse_response_json(create_response).await;
+    assert_eq!(game_data["current_player"], "white");
+    assert_eq!(game_data["move_count"], 1);
+    assert_eq!(game_data["white"], json!("Human"));
+}
+
+#[tokio::test]
+async fn test_pvp_notifications() {
+    let app = create_test_app().await;
+    let black_id = Uuid::new_v4().to_string();
+    let white_id = Uuid::new_v4().to_string();
+    
+    let send = |method: Method, uri: String, player_id: &str, body: Value| {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Content-Type", "application/json")
+            .header("X-Player-Id", player_id)
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+        app.clone().oneshot(request)
+    };
+    
+    let created = parse_response_json(
+        send(Method::POST, "/api/ai-battle/pvp".to_string(), &black_id, json!({})).await.unwrap()
+    ).await;
+    let game_id = created["game_state"]["game_id"].as_str().unwrap().to_string();
+    
+    let joined = send(
+        Method::POST,
+        format!("/api/ai-battle/{}/join", game_id),
+        &white_id,
+        json!({"join_token": created["join_token"]}),
+    ).await.unwrap();
+    assert_eq!(joined.status(), StatusCode::OK);
+    
+    let first_move = &created["game_state"]["valid_moves"][0];
+    let moved = send(
+        Method::POST,
+        format!("/api/ai-battle/{}/move", game_id),
+        &black_id,
+        json!({"row": first_move["row"], "col": first_move["col"], "player_token": created["player_token"]}),
+    ).await.unwrap();
+    assert_eq!(moved.status(), StatusCode::OK);
+    
+    let inbox = parse_response_json(
+        send(Method::GET, "/api/players/me/notifications".to_string(), &white_id, Value::Null).await.unwrap()
+    ).await;
+    assert_eq!(inbox["unread_count"], 1);
+    assert_eq!(inbox["notifications"][0]["kind"], "opponent_moved");
+    assert_eq!(inbox["notifications"][0]["game_id"], game_id.as_str());
+    
+    let black_inbox = parse_response_json(
+        send(Method::GET, "/api/players/me/notifications".to_string(), &black_id, Value::Null).await.unwrap()
+    ).await;
+    assert_eq!(black_inbox["unread_count"], 0);
+    
+    let marked = parse_response_json(
+        send(Method::POST, "/api/players/me/notifications/read".to_string(), &white_id, json!({})).await.unwrap()
+    ).await;
+    assert_eq!(marked["marked"], 1);
+    assert_eq!(marked["unread_count"], 0);
+}
+
+#[tokio::test]
+async fn test_session_event_stream() {
+    use futures::StreamExt;
+    
+    let mut app = create_test_app().await;
+    let created = parse_response_json(
+        send_request(&mut app, Method::POST, "/api/ai-battle", Some(json!({"difficulty": "Easy"}))).await
+    ).await;
+    let game_id = created["game_id"].as_str().unwrap().to_string();
+    
+    let response = send_request(&mut app, Method::GET, &format!("/api/ai-battle/{}/events", game_id), None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/event-stream"));
+    let mut body = response.into_body().into_data_stream();
+    
+    let first_move = &created["valid_moves"][0];
+    let moved = send_request(
+        &mut app,
+        Method::POST,
+        &format!("/api/ai-battle/{}/move", game_id),
+        Some(json!({"row": first_move["row"], "col": first_move["col"]})),
+    ).await;
+    assert_eq!(moved.status(), StatusCode::OK);
+    
+    // 人間の着手、AIの思考開始・終了、AIの着手の順に届く
+    let mut text = String::new();
+    while text.matches("\n\n").count() < 4 {
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), body.next())
+            .await
+            .expect("event stream timed out")
+            .expect("event stream closed")
+            .unwrap();
+        text.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+    let names: Vec<&str> = text.lines().filter_map(|line| line.strip_prefix("event: ")).collect();
+    assert_eq!(names, ["move_made", "ai_thinking", "ai_thinking", "move_made"]);
+    
+    let data: Vec<Value> = text
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .map(|data| serde_json::from_str(data).unwrap())
+        .collect();
+    assert_eq!(data[0]["player"], "black");
+    assert_eq!(data[1]["thinking"], true);
+    assert_eq!(data[2]["thinking"], false);
+    assert_eq!(data[3]["player"], "white");
+    assert_eq!(data[3]["move_count"], 2);
+    
+    // セッションを削除するとストリームが終了する
+    let deleted = send_request(&mut app, Method::DELETE, &format!("/api/ai-battle/{}", game_id), None).await;
+    assert_eq!(deleted.status(), StatusCode::NO_CONTENT);
+    let end = tokio::time::timeout(std::time::Duration::from_secs(5), body.next()).await.unwrap();
+    assert!(end.is_none());
+}
+
+#[tokio::test]
+async fn test_full_health_endpoint() {
+    let mut app = create_test_app().await;
+    
+    let create_response = send_request(
+        &mut app,
+        Method::POST,
+        "/api/ai-battle",
+        Some(json!({"difficulty": "easy"}))
+    ).await;
+    assert_eq!(create_response.status(), StatusCode::CREATED);
+    
+    let response = send_request(&mut app, Method::GET, "/api/admin/health/full", None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    
+    let health = parse_response_json(response).await;
+    assert_eq!(health["status"], "ok");
+    assert_eq!(health["ai_backends"][0]["role"], "primary");
+    assert_eq!(health["ai_backends"][0]["available"], true);
+    assert_eq!(health["session_store"]["active_sessions"], 1);
+    assert!(health["background_tasks"].is_array());
+    assert!(health["queues"].is_array());
+}
+
+#[tokio::test]
+async fn test_api_keys_protect_routes_and_attribute_sessions() {
+    let auth = AuthConfig {
+        api_keys: vec![ApiKeyEntry { name: "frontend".to_string(), key: "frontend-secret".to_string() }],
+        ..AuthConfig::default()
+    };
+    let app = auth::protect(create_test_app().await, ApiKeyRegistry::from_config(&auth));
+    let keyed = |method: Method, uri: &str, body: Option<Value>| {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Content-Type", "application/json")
+            .header("X-API-Key", "frontend-secret");
+        match body {
+            Some(body) => request.body(Body::from(serde_json::to_vec(&body).unwrap())).unwrap(),
+            None => request.body(Body::empty()).unwrap(),
+        }
+    };
+    
+    let mut open_app = app.clone();
+    let denied = send_request(&mut open_app, Method::POST, "/api/ai-battle", Some(json!({"difficulty": "easy"}))).await;
+    assert_eq!(denied.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(parse_response_json(denied).await["error_code"], "MISSING_API_KEY");
+    let public = send_request(&mut open_app, Method::GET, "/api/ai-battle/difficulties", None).await;
+    assert_eq!(public.status(), StatusCode::OK);
+    let health = send_request(&mut open_app, Method::GET, "/health", None).await;
+    assert_eq!(health.status(), StatusCode::OK);
+    
+    let created = app.clone().oneshot(keyed(Method::POST, "/api/ai-battle/pvp", None)).await.unwrap();
+    assert_eq!(created.status(), StatusCode::CREATED);
+    let game_id = parse_response_json(created).await["game_state"]["game_id"].as_str().unwrap().to_string();
+    
+    let sessions = app.clone().oneshot(keyed(Method::GET, "/api/ai-battle/sessions", None)).await.unwrap();
+    assert_eq!(sessions.status(), StatusCode::OK);
+    let sessions = parse_response_json(sessions).await;
+    let session = sessions["sessions"].as_array().unwrap().iter().find(|s| s["game_id"] == game_id.as_str()).unwrap();
+    assert_eq!(session["api_key_name"], "frontend");
+}
@@ -4,7 +4,7 @@ use std::{env, fs};
 use tempfile::TempDir;
 
 use Reversi::{
-    config::{Config, ConfigError, ServerConfig, AiBattleConfig},
+    config::{Config, ConfigError, ConfigOverrides, ServerConfig, AiBattleConfig, ApiKeyEntry, RouteClass, RouteTimeouts, TlsConfig},
     api::ai_battle::{ConfigurableAiBattleService, config_utils},
     ai::service::{AIServiceConfig, AIServiceType},
     api::ai_battle::dto::AiDifficulty,
@@ -17,6 +17,7 @@ fn create_test_config() -> Config {
             host: "127.0.0.1".to_string(),
             enable_cors: false,
             enable_logging: false,
+            ..Default::default()
         },
         ai_battle: AiBattleConfig {
             max_sessions: 50,
@@ -24,6 +25,7 @@ fn create_test_config() -> Config {
             default_difficulty: AiDifficulty::Medium,
             enable_session_cleanup: false,
             cleanup_interval_minutes: 10,
+            ..Default::default()
         },
         ai_service: AIServiceConfig {
             service_type: AIServiceType::Mock,
@@ -89,6 +91,120 @@ fn test_config_validation() {
     config.ai_battle.max_sessions = 10;
     config.ai_service.timeout_ms = 0;
     assert!(config.validate().is_err());
+    
+    // 無効なルート別タイムアウト
+    config.ai_service.timeout_ms = 5000;
+    config.server.timeouts.move_ms = 0;
+    assert!(config.validate().is_err());
+    
+    // クリーンアップ有効時の間隔0分
+    config.server.timeouts.move_ms = 30_000;
+    config.ai_battle.cleanup_interval_minutes = 0;
+    assert!(config.validate().is_err());
+    config.ai_battle.enable_session_cleanup = false;
+    assert!(config.validate().is_ok());
+    
+    // APIキーの名前・キーの重複
+    let entry = ApiKeyEntry { name: "frontend".to_string(), key: "secret".to_string() };
+    config.auth.api_keys = vec![entry.clone()];
+    assert!(config.validate().is_ok());
+    config.auth.api_keys.push(ApiKeyEntry { name: "batch".to_string(), ..entry });
+    assert!(config.validate().is_err());
+    config.auth.api_keys.clear();
+    
+    // JWTの署名鍵は32バイト以上
+    config.auth.jwt_secret = Some("too-short".to_string());
+    assert!(config.validate().is_err());
+    config.auth.jwt_secret = Some("x".repeat(32));
+    assert!(config.validate().is_ok());
+    
+    // CORSのオリジンはパスを含まないURL
+    config.server.allowed_origins = vec!["https://reversi.example.com".to_string(), "http://localhost:5173".to_string()];
+    assert!(config.validate().is_ok());
+    config.server.allowed_origins.push("https://reversi.example.com/".to_string());
+    assert!(config.validate().is_err());
+    config.server.allowed_origins = vec!["reversi.example.com".to_string()];
+    assert!(config.validate().is_err());
+    config.server.allowed_origins.clear();
+    
+    // HTTPリダイレクトのポートはHTTPSのポートと別にする
+    config.server.tls = Some(TlsConfig {
+        cert_path: "certs/fullchain.pem".to_string(),
+        key_path: "certs/privkey.pem".to_string(),
+        redirect_http_port: Some(8080),
+    });
+    assert!(config.validate().is_ok());
+    config.server.tls.as_mut().unwrap().redirect_http_port = Some(config.server.port);
+    assert!(config.validate().is_err());
+    config.server.tls.as_mut().unwrap().redirect_http_port = Some(8080);
+    
+    // gRPCのポートはHTTPのポートと別にする
+    config.server.grpc_port = Some(50051);
+    assert!(config.validate().is_ok());
+    config.server.grpc_port = Some(8080);
+    assert!(config.validate().is_err());
+    config.server.grpc_port = None;
+    config.server.tls.as_mut().unwrap().redirect_http_port = None;
+    config.server.tls.as_mut().unwrap().key_path = String::new();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_config_validation_reports_every_violation() {
+    let mut config = Config::default();
+    config.server.host = "not a host".to_string();
+    config.ai_service.service_type = AIServiceType::Http;
+    config.ai_battle.cleanup_interval_minutes = config.ai_battle.session_timeout_minutes as u64 + 1;
+    config.system_limits.max_move_history = 0;
+    
+    let fields: Vec<_> = config.violations().into_iter().map(|violation| violation.field).collect();
+    assert_eq!(
+        fields,
+        [
+            "server.host",
+            "system_limits.max_move_history",
+            "ai_battle.cleanup_interval_minutes",
+            "ai_service.endpoint_url",
+        ]
+    );
+    match config.validate() {
+        Err(ConfigError::Invalid(violations)) => assert_eq!(violations.len(), 4),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    
+    // ホスト名・IPv6アドレスと、HTTPのAIサービスの接続先
+    config.server.host = "reversi-api.internal".to_string();
+    config.ai_service.endpoint_url = Some("http://ai.internal:8080".to_string());
+    config.ai_battle.cleanup_interval_minutes = 5;
+    config.system_limits.max_move_history = 1000;
+    assert!(config.validate().is_ok());
+    config.server.host = "::1".to_string();
+    assert!(config.validate().is_ok());
+    
+    // 計算待ちキューを使う場合は上限が必要
+    config.ai_service.queue_capacity = 0;
+    assert_eq!(config.violations()[0].field, "ai_service.queue_capacity");
+    config.ai_service.queue_workers = 0;
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_route_timeouts_default_when_missing_from_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.json");
+    
+    let mut config = Config::default();
+    config.server.timeouts.analysis_ms = 60_000;
+    config.save_to_file(&config_path).unwrap();
+    assert_eq!(Config::from_file(&config_path).unwrap().server.timeouts.analysis_ms, 60_000);
+    
+    // timeoutsを持たない既存の設定ファイルは既定値で読み込む
+    let mut value: serde_json::Value = serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    value["server"].as_object_mut().unwrap().remove("timeouts");
+    fs::write(&config_path, value.to_string()).unwrap();
+    let loaded = Config::from_file(&config_path).unwrap();
+    assert_eq!(loaded.server.timeouts, RouteTimeouts::default());
+    assert_eq!(loaded.server.timeouts.budget(RouteClass::StateRead), std::time::Duration::from_secs(2));
 }
 
 #[test]
@@ -160,6 +276,37 @@ fn test_config_load_precedence() {
     env::remove_var("SERVER_HOST");
 }
 
+#[test]
+fn test_layered_config_merges_field_by_field() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.json");
+    
+    // 一部の項目だけを書いた設定ファイル
+    fs::write(&config_path, r#"{"server": {"port": 6000}, "ai_service": {"timeout_ms": 1234}}"#).unwrap();
+    env::set_var("AI_SERVICE_MAX_RETRIES", "7");
+    
+    let overrides = ConfigOverrides { host: Some("cli_host".to_string()), ..Default::default() };
+    let config = Config::load_layered(Some(&config_path), &overrides).unwrap();
+    env::remove_var("AI_SERVICE_MAX_RETRIES");
+    
+    // 環境変数はai_serviceの他の項目を既定値に戻さない
+    assert_eq!(config.ai_service.timeout_ms, 1234);
+    assert_eq!(config.ai_service.max_retries, 7);
+    assert_eq!(config.ai_service.service_type, AIServiceConfig::default().service_type);
+    assert_eq!(config.server.port, 6000);
+    assert_eq!(config.server.host, "cli_host");
+    assert_eq!(config.ai_battle.max_sessions, AiBattleConfig::default().max_sessions);
+    
+    // コマンドライン引数は設定ファイルより優先する
+    let overrides = ConfigOverrides { port: Some(8000), ..Default::default() };
+    assert_eq!(Config::load_layered(Some(&config_path), &overrides).unwrap().server.port, 8000);
+    
+    // 表示用の設定では署名鍵を伏せる
+    let mut secret = config.clone();
+    secret.auth.jwt_secret = Some("x".repeat(32));
+    assert_eq!(secret.redacted().auth.jwt_secret.as_deref(), Some("<redacted>"));
+}
+
 #[tokio::test]
 async fn test_configurable_service_creation() {
     let config = Config::default();
@@ -173,6 +320,24 @@ async fn test_configurable_service_creation() {
     assert!(!status.primary_service_name.is_empty());
     assert!(status.primary_service_available);
     assert_eq!(status.total_sessions, 0);
+    assert!(status.primary_service_cache.is_none());
+}
+
+#[tokio::test]
+async fn test_enable_caching_reports_cache_stats() {
+    let mut config = Config::default();
+    config.ai_service.service_type = AIServiceType::Mock;
+    config.ai_service.enable_caching = true;
+    config.ai_service.cache_capacity = 16;
+    
+    let service = ConfigurableAiBattleService::new(&config).unwrap();
+    let game_state = Reversi::game::GameState::new();
+    for _ in 0..2 {
+        service.primary_ai_service().calculate_move(&game_state, AiDifficulty::Hard).await.unwrap();
+    }
+    
+    let cache = service.get_service_status().await.primary_service_cache.unwrap();
+    assert_eq!((cache.hits, cache.misses, cache.entries, cache.capacity), (1, 1, 1, 16));
 }
 
 #[tokio::test]
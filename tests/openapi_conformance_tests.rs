@@ -0,0 +1,995 @@
+//! OpenAPIスキーマ適合テストモジュール
+//! 実際のルーターの全エンドポイントを呼び出し、レスポンスが
+//! 生成されたOpenAPIスキーマに適合するかを検証する。
+//! ドキュメント上のDTOとserdeの出力の乖離（列挙値の大文字小文字など）を検出する。
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+    response::Response,
+};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use Reversi::api::{handlers::AppState, routes::{create_router, create_ai_battle_router}};
+
+fn create_test_app() -> axum::Router {
+    let state = AppState::new();
+
+    create_router()
+        .with_state(state.clone())
+        .merge(create_ai_battle_router(state))
+}
+
+async fn send_request(app: &axum::Router, method: Method, uri: &str, body: Option<Value>) -> Response<Body> {
+    send_request_with_headers(app, method, uri, &[], body).await
+}
+
+async fn send_request_with_headers(
+    app: &axum::Router,
+    method: Method,
+    uri: &str,
+    headers: &[(&str, &str)],
+    body: Option<Value>,
+) -> Response<Body> {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+
+    let request = match body {
+        Some(body) => request.body(Body::from(serde_json::to_vec(&body).unwrap())).unwrap(),
+        None => request.body(Body::empty()).unwrap(),
+    };
+
+    app.clone().oneshot(request).await.unwrap()
+}
+
+async fn read_body(response: Response<Body>) -> Vec<u8> {
+    axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec()
+}
+
+/// `#/components/schemas/...` 形式の参照を解決する
+fn resolve_ref<'a>(doc: &'a Value, reference: &str) -> &'a Value {
+    let name = reference
+        .strip_prefix("#/components/schemas/")
+        .unwrap_or_else(|| panic!("unsupported $ref: {}", reference));
+    doc.pointer(&format!("/components/schemas/{}", name))
+        .unwrap_or_else(|| panic!("dangling $ref: {}", reference))
+}
+
+/// OpenAPI 3.0スキーマのサブセットで値を検証する
+/// 違反内容をパス付きで `errors` に追加する
+fn validate(doc: &Value, schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return validate(doc, resolve_ref(doc, reference), value, path, errors);
+    }
+
+    if value.is_null() {
+        if schema.get("nullable").and_then(Value::as_bool) != Some(true) {
+            errors.push(format!("{}: null is not allowed", path));
+        }
+        return;
+    }
+
+    if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+        for sub_schema in all_of {
+            validate(doc, sub_schema, value, path, errors);
+        }
+    }
+
+    if let Some(one_of) = schema.get("oneOf").and_then(Value::as_array) {
+        let matches = one_of
+            .iter()
+            .filter(|sub_schema| {
+                let mut sub_errors = Vec::new();
+                validate(doc, sub_schema, value, path, &mut sub_errors);
+                sub_errors.is_empty()
+            })
+            .count();
+        if matches != 1 {
+            errors.push(format!("{}: {} matched {} oneOf variants", path, value, matches));
+        }
+    }
+
+    if let Some(expected) = schema.get("enum").and_then(Value::as_array) {
+        if !expected.contains(value) {
+            errors.push(format!("{}: {} is not one of {:?}", path, value, expected));
+        }
+    }
+
+    match schema.get("type").and_then(Value::as_str) {
+        Some("object") => {
+            let Some(object) = value.as_object() else {
+                errors.push(format!("{}: expected object, got {}", path, value));
+                return;
+            };
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for required in schema.get("required").and_then(Value::as_array).into_iter().flatten() {
+                let key = required.as_str().unwrap();
+                if !object.contains_key(key) {
+                    errors.push(format!("{}: missing required field '{}'", path, key));
+                }
+            }
+            if let Some(properties) = properties {
+                for (key, field_value) in object {
+                    match properties.get(key) {
+                        Some(field_schema) => validate(doc, field_schema, field_value, &format!("{}.{}", path, key), errors),
+                        None => errors.push(format!("{}: undocumented field '{}'", path, key)),
+                    }
+                }
+            }
+        }
+        Some("array") => {
+            let Some(items) = value.as_array() else {
+                errors.push(format!("{}: expected array, got {}", path, value));
+                return;
+            };
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    errors.push(format!("{}: expected at least {} items", path, min));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if (items.len() as u64) > max {
+                    errors.push(format!("{}: expected at most {} items", path, max));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate(doc, item_schema, item, &format!("{}[{}]", path, index), errors);
+                }
+            }
+        }
+        Some("string") => {
+            let Some(text) = value.as_str() else {
+                errors.push(format!("{}: expected string, got {}", path, value));
+                return;
+            };
+            match schema.get("format").and_then(Value::as_str) {
+                Some("uuid") if Uuid::parse_str(text).is_err() => {
+                    errors.push(format!("{}: '{}' is not a uuid", path, text));
+                }
+                Some("date-time") if chrono::DateTime::parse_from_rfc3339(text).is_err() => {
+                    errors.push(format!("{}: '{}' is not a date-time", path, text));
+                }
+                _ => {}
+            }
+        }
+        Some("integer") | Some("number") => {
+            let Some(number) = value.as_f64() else {
+                errors.push(format!("{}: expected number, got {}", path, value));
+                return;
+            };
+            if schema.get("type").and_then(Value::as_str) == Some("integer") && !(value.is_i64() || value.is_u64()) {
+                errors.push(format!("{}: expected integer, got {}", path, value));
+            }
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if number < min {
+                    errors.push(format!("{}: {} is below minimum {}", path, number, min));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if number > max {
+                    errors.push(format!("{}: {} is above maximum {}", path, number, max));
+                }
+            }
+        }
+        Some("boolean") if !value.is_boolean() => {
+            errors.push(format!("{}: expected boolean, got {}", path, value));
+        }
+        _ => {}
+    }
+}
+
+/// ドキュメント化された操作に対してレスポンスを検証する
+struct ConformanceChecker {
+    app: axum::Router,
+    doc: Value,
+    exercised: HashSet<(String, String)>,
+}
+
+impl ConformanceChecker {
+    async fn new() -> Self {
+        let app = create_test_app();
+        let response = send_request(&app, Method::GET, "/api/openapi.json", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let doc = serde_json::from_slice(&read_body(response).await).unwrap();
+
+        Self { app, doc, exercised: HashSet::new() }
+    }
+
+    /// 実際のリクエストを送信し、レスポンスをスキーマで検証する
+    /// `template` はOpenAPI上のパス、`uri` は実際に送信するURI
+    async fn check(
+        &mut self,
+        method: Method,
+        template: &str,
+        uri: &str,
+        body: Option<Value>,
+        expected_status: StatusCode,
+    ) -> Value {
+        self.check_with_headers(method, template, uri, &[], body, expected_status).await
+    }
+
+    /// 追加のリクエストヘッダー付きで `check` を行う
+    async fn check_with_headers(
+        &mut self,
+        method: Method,
+        template: &str,
+        uri: &str,
+        headers: &[(&str, &str)],
+        body: Option<Value>,
+        expected_status: StatusCode,
+    ) -> Value {
+        let method_key = method.as_str().to_lowercase();
+        let response = send_request_with_headers(&self.app, method.clone(), uri, headers, body).await;
+        let status = response.status();
+        assert_eq!(status, expected_status, "{} {} returned unexpected status", method, uri);
+
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .map(|value| value.to_str().unwrap().to_string());
+        let bytes = read_body(response).await;
+
+        let operation = self.doc
+            .pointer(&format!("/paths/{}/{}", template.replace('/', "~1"), method_key))
+            .unwrap_or_else(|| panic!("{} {} is not documented", method, template))
+            .clone();
+        let documented = operation
+            .pointer(&format!("/responses/{}", status.as_u16()))
+            .unwrap_or_else(|| panic!("{} {} does not document status {}", method, template, status));
+
+        self.exercised.insert((method_key, template.to_string()));
+
+        let Some(content) = documented.get("content").and_then(Value::as_object) else {
+            assert!(bytes.is_empty(), "{} {} returned an undocumented body", method, uri);
+            return Value::Null;
+        };
+
+        let content_type = content_type.expect("documented response without content-type");
+        let (media_type, media) = content
+            .iter()
+            .find(|(media_type, _)| content_type.starts_with(media_type.as_str()))
+            .unwrap_or_else(|| panic!("{} {} returned undocumented content-type {}", method, uri, content_type));
+
+        let value = if media_type == "application/json" {
+            serde_json::from_slice(&bytes).unwrap()
+        } else if media_type == "application/msgpack" {
+            rmp_serde::from_slice(&bytes).unwrap()
+        } else if media_type == "application/cbor" {
+            ciborium::from_reader(bytes.as_slice()).unwrap()
+        } else {
+            // バイナリ形式のレスポンスは文字列として扱う
+            Value::String(String::from_utf8_lossy(&bytes).into_owned())
+        };
+
+        let mut errors = Vec::new();
+        validate(&self.doc, &media["schema"], &value, "$", &mut errors);
+        assert!(errors.is_empty(), "{} {} does not conform to schema:\n{}", method, uri, errors.join("\n"));
+
+        value
+    }
+
+    /// ドキュメント化された全操作が実行されたか確認する
+    fn assert_all_operations_exercised(&self) {
+        let mut missing = Vec::new();
+        for (path, item) in self.doc["paths"].as_object().unwrap() {
+            for method in item.as_object().unwrap().keys() {
+                if !self.exercised.contains(&(method.clone(), path.clone())) {
+                    missing.push(format!("{} {}", method.to_uppercase(), path));
+                }
+            }
+        }
+        assert!(missing.is_empty(), "operations not exercised: {:?}", missing);
+    }
+}
+
+#[test]
+fn test_validator_detects_drift() {
+    let doc = json!({
+        "components": {"schemas": {
+            "Difficulty": {"type": "string", "enum": ["Easy", "Medium", "Hard"]},
+            "Info": {
+                "type": "object",
+                "required": ["id"],
+                "properties": {"id": {"$ref": "#/components/schemas/Difficulty"}}
+            }
+        }}
+    });
+    let schema = json!({"$ref": "#/components/schemas/Info"});
+
+    let mut errors = Vec::new();
+    validate(&doc, &schema, &json!({"id": "Medium"}), "$", &mut errors);
+    assert!(errors.is_empty());
+
+    validate(&doc, &schema, &json!({"id": "medium"}), "$", &mut errors);
+    assert_eq!(errors.len(), 1);
+
+    errors.clear();
+    validate(&doc, &schema, &json!({"id": "Easy", "extra": 1}), "$", &mut errors);
+    assert!(errors[0].contains("undocumented field"));
+}
+
+#[tokio::test]
+async fn test_all_routes_conform_to_openapi_schema() {
+    let mut checker = ConformanceChecker::new().await;
+    let missing_id = Uuid::new_v4();
+
+    // システム
+    checker.check(Method::GET, "/health", "/health", None, StatusCode::OK).await;
+    checker.check(Method::GET, "/api/admin/health/full", "/api/admin/health/full", None, StatusCode::OK).await;
+    checker.check(Method::GET, "/api/admin/status", "/api/admin/status", None, StatusCode::OK).await;
+    checker.check(Method::GET, "/health/live", "/health/live", None, StatusCode::OK).await;
+    checker.check(Method::GET, "/health/ready", "/health/ready", None, StatusCode::OK).await;
+    let metrics = checker.check(Method::GET, "/metrics", "/metrics", None, StatusCode::OK).await;
+    assert!(metrics.as_str().unwrap().contains("# TYPE reversi_ai_move_latency_p95_ms gauge"));
+
+    // AI対戦API
+    checker.check(Method::GET, "/api/ai-battle/difficulties", "/api/ai-battle/difficulties", None, StatusCode::OK).await;
+    checker.check(Method::GET, "/api/ai-battle/time-controls", "/api/ai-battle/time-controls", None, StatusCode::OK).await;
+
+    let clocked = checker.check(
+        Method::POST, "/api/ai-battle", "/api/ai-battle",
+        Some(json!({"difficulty": "Easy", "time_control": "blitz_5_3"})), StatusCode::CREATED,
+    ).await;
+    assert_eq!(clocked["clock"]["time_control"]["increment_seconds"], 3);
+    assert_eq!(clocked["evaluation"], Value::Null);
+    checker.check(
+        Method::POST, "/api/ai-battle", "/api/ai-battle",
+        Some(json!({"difficulty": "Easy", "time_control": {"initial_seconds": 0}})), StatusCode::BAD_REQUEST,
+    ).await;
+
+    let created = checker.check(
+        Method::POST, "/api/ai-battle", "/api/ai-battle",
+        Some(json!({"difficulty": "Easy"})), StatusCode::CREATED,
+    ).await;
+    let game_id = created["game_id"].as_str().unwrap().to_string();
+    let first_move = created["valid_moves"][0].clone();
+
+    checker.check(Method::GET, "/api/ai-battle/sessions", "/api/ai-battle/sessions", None, StatusCode::OK).await;
+    let paged = checker.check(
+        Method::GET, "/api/ai-battle/sessions", "/api/ai-battle/sessions?page=2&per_page=1&sort_by=last_move_at&order=asc",
+        None, StatusCode::OK,
+    ).await;
+    assert_eq!(paged["sessions"].as_array().unwrap().len(), 1);
+    assert_eq!(paged["total_pages"], 2);
+    checker.check(
+        Method::GET, "/api/ai-battle/sessions", "/api/ai-battle/sessions?sort_by=status", None, StatusCode::BAD_REQUEST,
+    ).await;
+    let filtered = checker.check(
+        Method::GET, "/api/ai-battle/sessions", "/api/ai-battle/sessions?status=in_progress&difficulty=easy",
+        None, StatusCode::OK,
+    ).await;
+    assert!(filtered["sessions"].as_array().unwrap().iter().any(|session| session["game_id"] == game_id.as_str()));
+    checker.check(
+        Method::GET, "/api/ai-battle/sessions", "/api/ai-battle/sessions?status=paused", None, StatusCode::BAD_REQUEST,
+    ).await;
+    let pinned = checker.check(
+        Method::PUT, "/api/admin/sessions/{game_id}/pin", &format!("/api/admin/sessions/{}/pin", game_id),
+        Some(json!({"pinned": true})), StatusCode::OK,
+    ).await;
+    assert_eq!(pinned["pinned"], true);
+    checker.check(
+        Method::PUT, "/api/admin/sessions/{game_id}/pin", &format!("/api/admin/sessions/{}/pin", missing_id),
+        Some(json!({"pinned": true})), StatusCode::NOT_FOUND,
+    ).await;
+    checker.check(
+        Method::GET, "/api/ai-battle/{game_id}", &format!("/api/ai-battle/{}", game_id),
+        None, StatusCode::OK,
+    ).await;
+    checker.check(
+        Method::GET, "/api/ai-battle/{game_id}", &format!("/api/ai-battle/{}", missing_id),
+        None, StatusCode::NOT_FOUND,
+    ).await;
+    checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/hint", &format!("/api/ai-battle/{}/hint", game_id),
+        None, StatusCode::OK,
+    ).await;
+    checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/hint", &format!("/api/ai-battle/{}/hint?difficulty=unknown", game_id),
+        None, StatusCode::BAD_REQUEST,
+    ).await;
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/analyze", &format!("/api/ai-battle/{}/analyze", game_id),
+        Some(json!({})), StatusCode::OK,
+    ).await;
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/analyze", &format!("/api/ai-battle/{}/analyze", missing_id),
+        Some(json!({"difficulty": "hard"})), StatusCode::NOT_FOUND,
+    ).await;
+    let rejected = checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/move", &format!("/api/ai-battle/{}/move", game_id),
+        Some(json!({"row": 0, "col": 0})), StatusCode::BAD_REQUEST,
+    ).await;
+    assert_eq!(rejected["reason_code"], "no_bracketing_line");
+    assert_eq!(rejected["nearest_valid_moves"].as_array().unwrap().len(), 3);
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/move", &format!("/api/ai-battle/{}/move", game_id),
+        Some(json!({"row": first_move["row"], "col": first_move["col"]})), StatusCode::OK,
+    ).await;
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/pass", &format!("/api/ai-battle/{}/pass", game_id),
+        None, StatusCode::BAD_REQUEST,
+    ).await;
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/pass", &format!("/api/ai-battle/{}/pass", missing_id),
+        Some(json!({})), StatusCode::NOT_FOUND,
+    ).await;
+    checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/history", &format!("/api/ai-battle/{}/history", game_id),
+        None, StatusCode::OK,
+    ).await;
+    let transcript = checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/transcript", &format!("/api/ai-battle/{}/transcript", game_id),
+        None, StatusCode::OK,
+    ).await;
+    assert_eq!(transcript["transcript"].as_str().unwrap().len() % 2, 0);
+    checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/transcript", &format!("/api/ai-battle/{}/transcript", missing_id),
+        None, StatusCode::NOT_FOUND,
+    ).await;
+    let exported = checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/export", &format!("/api/ai-battle/{}/export", game_id),
+        None, StatusCode::OK,
+    ).await;
+    assert_eq!(exported["source_game_id"], game_id.as_str());
+    checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/export", &format!("/api/ai-battle/{}/export", missing_id),
+        None, StatusCode::NOT_FOUND,
+    ).await;
+    let imported = checker.check(
+        Method::POST, "/api/ai-battle/import", "/api/ai-battle/import",
+        Some(exported.clone()), StatusCode::CREATED,
+    ).await;
+    assert_ne!(imported["game_id"], game_id.as_str());
+    let mut tampered = exported;
+    tampered["board"] = json!("--------/--------/--------/---OX---/---XO---/--------/--------/--------");
+    checker.check(
+        Method::POST, "/api/ai-battle/import", "/api/ai-battle/import",
+        Some(tampered), StatusCode::BAD_REQUEST,
+    ).await;
+    let replay = checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/replay", &format!("/api/ai-battle/{}/replay", game_id),
+        None, StatusCode::OK,
+    ).await;
+    let total_moves = replay["total_moves"].as_u64().unwrap();
+    assert_eq!(replay["snapshots"].as_array().unwrap().len() as u64, total_moves + 1);
+    let snapshot = checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/replay", &format!("/api/ai-battle/{}/replay?move=0", game_id),
+        None, StatusCode::OK,
+    ).await;
+    assert_eq!(snapshot["snapshots"][0]["last_move"], Value::Null);
+    checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/replay", &format!("/api/ai-battle/{}/replay?move={}", game_id, total_moves + 1),
+        None, StatusCode::BAD_REQUEST,
+    ).await;
+    checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/replay", &format!("/api/ai-battle/{}/replay", missing_id),
+        None, StatusCode::NOT_FOUND,
+    ).await;
+    checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/events", &format!("/api/ai-battle/{}/events", missing_id),
+        None, StatusCode::NOT_FOUND,
+    ).await;
+    checker.check(
+        Method::PUT, "/api/ai-battle/{game_id}/difficulty", &format!("/api/ai-battle/{}/difficulty", game_id),
+        Some(json!({"difficulty": "Medium"})), StatusCode::OK,
+    ).await;
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/step", &format!("/api/ai-battle/{}/step", game_id),
+        None, StatusCode::FORBIDDEN,
+    ).await;
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/undo", &format!("/api/ai-battle/{}/undo", game_id),
+        None, StatusCode::OK,
+    ).await;
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/undo", &format!("/api/ai-battle/{}/undo", game_id),
+        None, StatusCode::CONFLICT,
+    ).await;
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/undo", &format!("/api/ai-battle/{}/undo", missing_id),
+        None, StatusCode::NOT_FOUND,
+    ).await;
+    checker.check(
+        Method::DELETE, "/api/ai-battle/{game_id}", &format!("/api/ai-battle/{}", game_id),
+        None, StatusCode::NO_CONTENT,
+    ).await;
+    let receipt_id = clocked["game_id"].as_str().unwrap().to_string();
+    let receipt = checker.check_with_headers(
+        Method::DELETE, "/api/ai-battle/{game_id}", &format!("/api/ai-battle/{}", receipt_id),
+        &[("Prefer", "return=representation")], None, StatusCode::OK,
+    ).await;
+    assert_eq!(receipt["game_id"], receipt_id.as_str());
+    checker.check(
+        Method::DELETE, "/api/ai-battle/{game_id}", &format!("/api/ai-battle/{}", game_id),
+        None, StatusCode::NOT_FOUND,
+    ).await;
+
+    // AI同士の対戦
+    let exhibition = checker.check(
+        Method::POST, "/api/ai-battle/ai-vs-ai", "/api/ai-battle/ai-vs-ai",
+        Some(json!({"black_difficulty": "easy", "white_difficulty": "Easy"})), StatusCode::CREATED,
+    ).await;
+    let exhibition_id = exhibition["game_id"].as_str().unwrap().to_string();
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/step", &format!("/api/ai-battle/{}/step", exhibition_id),
+        None, StatusCode::OK,
+    ).await;
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/step", &format!("/api/ai-battle/{}/step", missing_id),
+        None, StatusCode::NOT_FOUND,
+    ).await;
+
+    // 対人戦
+    let pvp = checker.check(
+        Method::POST, "/api/ai-battle/pvp", "/api/ai-battle/pvp", None, StatusCode::CREATED,
+    ).await;
+    let pvp_id = pvp["game_state"]["game_id"].as_str().unwrap().to_string();
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/join", &format!("/api/ai-battle/{}/join", pvp_id),
+        Some(json!({"join_token": Uuid::new_v4()})), StatusCode::FORBIDDEN,
+    ).await;
+    let joined = checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/join", &format!("/api/ai-battle/{}/join", pvp_id),
+        Some(json!({"join_token": pvp["join_token"]})), StatusCode::OK,
+    ).await;
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/join", &format!("/api/ai-battle/{}/join", pvp_id),
+        Some(json!({"join_token": pvp["join_token"]})), StatusCode::CONFLICT,
+    ).await;
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/join", &format!("/api/ai-battle/{}/join", missing_id),
+        Some(json!({"join_token": Uuid::new_v4()})), StatusCode::NOT_FOUND,
+    ).await;
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/move", &format!("/api/ai-battle/{}/move", pvp_id),
+        Some(json!({"row": 2, "col": 3})), StatusCode::FORBIDDEN,
+    ).await;
+
+    // 対局アーカイブ（9手で白の石がなくなる対局を終局させる）
+    let wipeout = [(2, 3), (2, 2), (2, 1), (1, 3), (0, 4), (5, 3), (6, 3), (2, 4), (3, 5)];
+    let mut position_hashes = Vec::new();
+    for (ply, (row, col)) in wipeout.into_iter().enumerate() {
+        let token = if ply % 2 == 0 { &pvp["player_token"] } else { &joined["player_token"] };
+        let moved = checker.check(
+            Method::POST, "/api/ai-battle/{game_id}/move", &format!("/api/ai-battle/{}/move", pvp_id),
+            Some(json!({"row": row, "col": col, "player_token": token})), StatusCode::OK,
+        ).await;
+        position_hashes.push(moved["game_state"]["position_hash"].as_str().unwrap().to_string());
+    }
+    let archive = checker.check(
+        Method::GET, "/api/archive", "/api/archive?result=black_win&from=2000-01-01", None, StatusCode::OK,
+    ).await;
+    let archived = &archive["games"][0];
+    assert_eq!(archived["game_id"], pvp_id.as_str());
+    assert_eq!(archived["moves"].as_array().unwrap().len(), 9);
+    checker.check(Method::GET, "/api/archive", "/api/archive?result=win", None, StatusCode::BAD_REQUEST).await;
+    checker.check(
+        Method::GET, "/api/archive/wthor", "/api/archive/wthor?result=black_win", None, StatusCode::OK,
+    ).await;
+    checker.check(Method::GET, "/api/archive/wthor", "/api/archive/wthor?result=win", None, StatusCode::BAD_REQUEST).await;
+
+    // 局面ハッシュ（対局中に現れた局面は終局後も引ける）
+    let position = checker.check(
+        Method::GET, "/api/positions/{hash}", &format!("/api/positions/{}", position_hashes[0]), None, StatusCode::OK,
+    ).await;
+    assert_eq!(position["to_move"], "white");
+    assert_eq!(position["explorer"]["games"], 1);
+    assert_eq!(position["explorer"]["black_wins"], 1);
+    checker.check(
+        Method::GET, "/api/positions/{hash}", "/api/positions/0000000000000000", None, StatusCode::NOT_FOUND,
+    ).await;
+    checker.check(Method::GET, "/api/positions/{hash}", "/api/positions/not-a-hash", None, StatusCode::BAD_REQUEST).await;
+
+    // 毎日の問題（9手で終わる対局には終盤の局面がなく、まだ問題がない）
+    checker.check(Method::GET, "/api/puzzles/today", "/api/puzzles/today", None, StatusCode::NOT_FOUND).await;
+    let solver_header = [("X-Player-Id", "5b0e6a57-8f61-4b8e-9d3c-0f1f2a3b4c5d")];
+    checker.check_with_headers(
+        Method::POST, "/api/puzzles/today/answer", "/api/puzzles/today/answer",
+        &solver_header, Some(json!({"row": 7, "col": 7})), StatusCode::NOT_FOUND,
+    ).await;
+    checker.check(
+        Method::POST, "/api/puzzles/today/answer", "/api/puzzles/today/answer",
+        Some(json!({"row": 7, "col": 7})), StatusCode::UNAUTHORIZED,
+    ).await;
+    let streak = checker.check_with_headers(
+        Method::GET, "/api/puzzles/streak", "/api/puzzles/streak", &solver_header, None, StatusCode::OK,
+    ).await;
+    assert_eq!(streak["current"], 0);
+
+    // アカウントとセッションの所有者
+    let registered = checker.check(
+        Method::POST, "/api/accounts/register", "/api/accounts/register",
+        Some(json!({"username": "alice", "password": "correct horse"})), StatusCode::CREATED,
+    ).await;
+    checker.check(
+        Method::POST, "/api/accounts/register", "/api/accounts/register",
+        Some(json!({"username": "Alice", "password": "another password"})), StatusCode::CONFLICT,
+    ).await;
+    checker.check(
+        Method::POST, "/api/accounts/register", "/api/accounts/register",
+        Some(json!({"username": "bob", "password": "short"})), StatusCode::BAD_REQUEST,
+    ).await;
+    let logged_in = checker.check(
+        Method::POST, "/api/accounts/login", "/api/accounts/login",
+        Some(json!({"username": "alice", "password": "correct horse"})), StatusCode::OK,
+    ).await;
+    assert_eq!(logged_in["account_id"], registered["account_id"]);
+    checker.check(
+        Method::POST, "/api/accounts/login", "/api/accounts/login",
+        Some(json!({"username": "alice", "password": "wrong password"})), StatusCode::UNAUTHORIZED,
+    ).await;
+    let bearer = format!("Bearer {}", logged_in["access_token"].as_str().unwrap());
+    let owner_header = [("Authorization", bearer.as_str())];
+    let me = checker.check_with_headers(
+        Method::GET, "/api/accounts/me", "/api/accounts/me", &owner_header, None, StatusCode::OK,
+    ).await;
+    assert_eq!(me["username"], "alice");
+    checker.check(Method::GET, "/api/accounts/me", "/api/accounts/me", None, StatusCode::UNAUTHORIZED).await;
+    checker.check_with_headers(
+        Method::POST, "/api/ai-battle", "/api/ai-battle",
+        &[("Authorization", "Bearer a.b.c")], Some(json!({"difficulty": "Easy"})), StatusCode::UNAUTHORIZED,
+    ).await;
+    checker.check_with_headers(
+        Method::POST, "/api/ai-battle/ai-vs-ai", "/api/ai-battle/ai-vs-ai",
+        &[("Authorization", "Bearer a.b.c")], Some(json!({"black_difficulty": "Easy", "white_difficulty": "Easy"})),
+        StatusCode::UNAUTHORIZED,
+    ).await;
+    checker.check_with_headers(
+        Method::POST, "/api/ai-battle/pvp", "/api/ai-battle/pvp",
+        &[("Authorization", "Bearer a.b.c")], None, StatusCode::UNAUTHORIZED,
+    ).await;
+    
+    let owned = checker.check_with_headers(
+        Method::POST, "/api/ai-battle", "/api/ai-battle",
+        &owner_header, Some(json!({"difficulty": "Easy"})), StatusCode::CREATED,
+    ).await;
+    let owned_uri = format!("/api/ai-battle/{}", owned["game_id"].as_str().unwrap());
+    let owned_move = json!({"row": owned["valid_moves"][0]["row"], "col": owned["valid_moves"][0]["col"]});
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/move", &format!("{}/move", owned_uri), Some(owned_move.clone()), StatusCode::UNAUTHORIZED,
+    ).await;
+    checker.check(Method::POST, "/api/ai-battle/{game_id}/pass", &format!("{}/pass", owned_uri), None, StatusCode::UNAUTHORIZED).await;
+    checker.check(Method::POST, "/api/ai-battle/{game_id}/undo", &format!("{}/undo", owned_uri), None, StatusCode::UNAUTHORIZED).await;
+    checker.check(Method::POST, "/api/ai-battle/{game_id}/step", &format!("{}/step", owned_uri), None, StatusCode::UNAUTHORIZED).await;
+    checker.check(
+        Method::PUT, "/api/ai-battle/{game_id}/difficulty", &format!("{}/difficulty", owned_uri),
+        Some(json!({"difficulty": "Hard"})), StatusCode::UNAUTHORIZED,
+    ).await;
+    checker.check(Method::DELETE, "/api/ai-battle/{game_id}", &owned_uri, None, StatusCode::UNAUTHORIZED).await;
+    
+    let intruder = checker.check(
+        Method::POST, "/api/accounts/register", "/api/accounts/register",
+        Some(json!({"username": "mallory", "password": "correct horse"})), StatusCode::CREATED,
+    ).await;
+    let intruder_bearer = format!("Bearer {}", intruder["access_token"].as_str().unwrap());
+    let intruder_header = [("Authorization", intruder_bearer.as_str())];
+    checker.check_with_headers(
+        Method::POST, "/api/ai-battle/{game_id}/undo", &format!("{}/undo", owned_uri), &intruder_header, None, StatusCode::FORBIDDEN,
+    ).await;
+    checker.check_with_headers(
+        Method::PUT, "/api/ai-battle/{game_id}/difficulty", &format!("{}/difficulty", owned_uri),
+        &intruder_header, Some(json!({"difficulty": "Hard"})), StatusCode::FORBIDDEN,
+    ).await;
+    checker.check_with_headers(
+        Method::DELETE, "/api/ai-battle/{game_id}", &owned_uri, &intruder_header, None, StatusCode::FORBIDDEN,
+    ).await;
+    checker.check(Method::POST, "/api/ai-battle/{game_id}/share", &format!("{}/share", owned_uri), None, StatusCode::UNAUTHORIZED).await;
+    checker.check_with_headers(
+        Method::POST, "/api/ai-battle/{game_id}/share", &format!("{}/share", owned_uri), &intruder_header, None, StatusCode::FORBIDDEN,
+    ).await;
+    checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/share", &format!("/api/ai-battle/{}/share", Uuid::new_v4()), None, StatusCode::NOT_FOUND,
+    ).await;
+    let share = checker.check_with_headers(
+        Method::POST, "/api/ai-battle/{game_id}/share", &format!("{}/share", owned_uri), &owner_header, None, StatusCode::OK,
+    ).await;
+    let spectate_url = share["spectate_url"].as_str().unwrap().to_string();
+    let spectated = checker.check(Method::GET, "/api/spectate/{share_token}", &spectate_url, None, StatusCode::OK).await;
+    assert_eq!(spectated["game_id"], owned["game_id"]);
+    checker.check(
+        Method::GET, "/api/spectate/{share_token}", &format!("/api/spectate/{}", Uuid::new_v4()), None, StatusCode::NOT_FOUND,
+    ).await;
+    checker.check(
+        Method::GET, "/api/spectate/{share_token}/events", &format!("/api/spectate/{}/events", Uuid::new_v4()),
+        None, StatusCode::NOT_FOUND,
+    ).await;
+    checker.check_with_headers(
+        Method::POST, "/api/ai-battle/{game_id}/move", &format!("{}/move", owned_uri), &owner_header, Some(owned_move), StatusCode::OK,
+    ).await;
+    checker.check_with_headers(
+        Method::DELETE, "/api/ai-battle/{game_id}", &owned_uri, &owner_header, None, StatusCode::NO_CONTENT,
+    ).await;
+
+    // プレイヤー通知
+    let player_id = Uuid::new_v4().to_string();
+    let player_header = [("X-Player-Id", player_id.as_str())];
+    checker.check(
+        Method::GET, "/api/players/me/notifications", "/api/players/me/notifications", None, StatusCode::UNAUTHORIZED,
+    ).await;
+    checker.check_with_headers(
+        Method::GET, "/api/players/me/notifications", "/api/players/me/notifications?unread_only=true",
+        &player_header, None, StatusCode::OK,
+    ).await;
+    checker.check_with_headers(
+        Method::POST, "/api/players/me/notifications/read", "/api/players/me/notifications/read",
+        &player_header, Some(json!({})), StatusCode::OK,
+    ).await;
+    checker.check(
+        Method::POST, "/api/players/me/notifications/read", "/api/players/me/notifications/read",
+        Some(json!({"ids": []})), StatusCode::UNAUTHORIZED,
+    ).await;
+    let rating = checker.check(
+        Method::GET, "/api/players/{player_id}/rating", &format!("/api/players/{}/rating", player_id), None, StatusCode::OK,
+    ).await;
+    assert_eq!(rating["rating"], 1200);
+    checker.check(Method::GET, "/api/leaderboard", "/api/leaderboard?period=weekly&limit=10", None, StatusCode::OK).await;
+    checker.check(Method::GET, "/api/leaderboard", "/api/leaderboard?period=monthly", None, StatusCode::BAD_REQUEST).await;
+    checker.check(Method::GET, "/api/stats/difficulties", "/api/stats/difficulties", None, StatusCode::OK).await;
+
+    // 大会
+    let tournament = checker.check(
+        Method::POST, "/api/tournaments", "/api/tournaments",
+        Some(json!({"name": "mock cup", "entrants": [
+            {"name": "mock-a", "service": "Mock", "difficulty": "Easy"}, {"name": "mock-b", "service": "Mock", "difficulty": "Easy"},
+        ], "games_per_pairing": 1})),
+        StatusCode::ACCEPTED,
+    ).await;
+    checker.check(
+        Method::POST, "/api/tournaments", "/api/tournaments",
+        Some(json!({"name": "solo", "entrants": [{"name": "mock-a", "service": "Mock", "difficulty": "Easy"}]})),
+        StatusCode::BAD_REQUEST,
+    ).await;
+    checker.check(Method::GET, "/api/tournaments", "/api/tournaments", None, StatusCode::OK).await;
+    checker.check(
+        Method::GET, "/api/tournaments/{tournament_id}",
+        &format!("/api/tournaments/{}", tournament["tournament_id"].as_str().unwrap()), None, StatusCode::OK,
+    ).await;
+    checker.check(
+        Method::GET, "/api/tournaments/{tournament_id}", &format!("/api/tournaments/{}", Uuid::new_v4()),
+        None, StatusCode::NOT_FOUND,
+    ).await;
+
+    // ロビー
+    let host_id = Uuid::new_v4().to_string();
+    let host_header = [("X-Player-Id", host_id.as_str())];
+    let created = checker.check_with_headers(
+        Method::POST, "/api/lobby", "/api/lobby",
+        &host_header, Some(json!({"time_control": {"initial_seconds": 300, "increment_seconds": 5}, "rated": true, "color": "black"})),
+        StatusCode::CREATED,
+    ).await;
+    let challenge_id = created["challenge"]["id"].as_str().unwrap().to_string();
+    checker.check_with_headers(
+        Method::POST, "/api/lobby", "/api/lobby",
+        &host_header, Some(json!({"ttl_seconds": 0})), StatusCode::BAD_REQUEST,
+    ).await;
+    checker.check(Method::POST, "/api/lobby", "/api/lobby", Some(json!({})), StatusCode::UNAUTHORIZED).await;
+    checker.check(Method::GET, "/api/lobby", "/api/lobby", None, StatusCode::OK).await;
+    checker.check_with_headers(
+        Method::POST, "/api/lobby/{challenge_id}/accept", &format!("/api/lobby/{}/accept", challenge_id),
+        &host_header, None, StatusCode::CONFLICT,
+    ).await;
+    checker.check_with_headers(
+        Method::POST, "/api/lobby/{challenge_id}/accept", &format!("/api/lobby/{}/accept", challenge_id),
+        &player_header, None, StatusCode::OK,
+    ).await;
+    checker.check_with_headers(
+        Method::POST, "/api/lobby/{challenge_id}/accept", &format!("/api/lobby/{}/accept", challenge_id),
+        &player_header, None, StatusCode::NOT_FOUND,
+    ).await;
+    checker.check(
+        Method::POST, "/api/lobby/{challenge_id}/accept", &format!("/api/lobby/{}/accept", challenge_id),
+        None, StatusCode::UNAUTHORIZED,
+    ).await;
+    let cancelled = checker.check_with_headers(
+        Method::POST, "/api/lobby", "/api/lobby", &host_header, Some(json!({})), StatusCode::CREATED,
+    ).await;
+    let cancel_uri = format!("/api/lobby/{}", cancelled["challenge"]["id"].as_str().unwrap());
+    checker.check_with_headers(
+        Method::DELETE, "/api/lobby/{challenge_id}", &cancel_uri, &player_header, None, StatusCode::FORBIDDEN,
+    ).await;
+    checker.check(Method::DELETE, "/api/lobby/{challenge_id}", &cancel_uri, None, StatusCode::UNAUTHORIZED).await;
+    checker.check_with_headers(
+        Method::DELETE, "/api/lobby/{challenge_id}", &cancel_uri, &host_header, None, StatusCode::NO_CONTENT,
+    ).await;
+    checker.check_with_headers(
+        Method::DELETE, "/api/lobby/{challenge_id}", &cancel_uri, &host_header, None, StatusCode::NOT_FOUND,
+    ).await;
+
+    // 相手指定の募集と招待リンク
+    let direct = checker.check_with_headers(
+        Method::POST, "/api/lobby", "/api/lobby",
+        &host_header, Some(json!({"opponent": player_id})), StatusCode::CREATED,
+    ).await;
+    let direct_token = direct["invite_token"].as_str().unwrap().to_string();
+    checker.check_with_headers(
+        Method::GET, "/api/players/me/challenges", "/api/players/me/challenges",
+        &player_header, None, StatusCode::OK,
+    ).await;
+    checker.check(
+        Method::GET, "/api/players/me/challenges", "/api/players/me/challenges", None, StatusCode::UNAUTHORIZED,
+    ).await;
+    checker.check(
+        Method::GET, "/api/lobby/invites/{invite_token}", &format!("/api/lobby/invites/{}", direct_token),
+        None, StatusCode::OK,
+    ).await;
+    let stranger_id = Uuid::new_v4().to_string();
+    let stranger_header = [("X-Player-Id", stranger_id.as_str())];
+    checker.check_with_headers(
+        Method::POST, "/api/lobby/invites/{invite_token}/accept", &format!("/api/lobby/invites/{}/accept", direct_token),
+        &stranger_header, None, StatusCode::FORBIDDEN,
+    ).await;
+    checker.check_with_headers(
+        Method::POST, "/api/lobby/invites/{invite_token}/decline", &format!("/api/lobby/invites/{}/decline", direct_token),
+        &stranger_header, None, StatusCode::FORBIDDEN,
+    ).await;
+    checker.check(
+        Method::POST, "/api/lobby/invites/{invite_token}/decline", &format!("/api/lobby/invites/{}/decline", direct_token),
+        None, StatusCode::UNAUTHORIZED,
+    ).await;
+    checker.check_with_headers(
+        Method::POST, "/api/lobby/invites/{invite_token}/decline", &format!("/api/lobby/invites/{}/decline", direct_token),
+        &player_header, None, StatusCode::NO_CONTENT,
+    ).await;
+    checker.check_with_headers(
+        Method::POST, "/api/lobby/invites/{invite_token}/decline", &format!("/api/lobby/invites/{}/decline", direct_token),
+        &player_header, None, StatusCode::NOT_FOUND,
+    ).await;
+    checker.check(
+        Method::GET, "/api/lobby/invites/{invite_token}", &format!("/api/lobby/invites/{}", direct_token),
+        None, StatusCode::NOT_FOUND,
+    ).await;
+    let link = checker.check_with_headers(
+        Method::POST, "/api/lobby", "/api/lobby", &host_header, Some(json!({"private": true})), StatusCode::CREATED,
+    ).await;
+    let link_token = link["invite_token"].as_str().unwrap().to_string();
+    checker.check_with_headers(
+        Method::POST, "/api/lobby/invites/{invite_token}/accept", &format!("/api/lobby/invites/{}/accept", link_token),
+        &host_header, None, StatusCode::CONFLICT,
+    ).await;
+    checker.check_with_headers(
+        Method::POST, "/api/lobby/invites/{invite_token}/decline", &format!("/api/lobby/invites/{}/decline", link_token),
+        &host_header, None, StatusCode::CONFLICT,
+    ).await;
+    checker.check(
+        Method::POST, "/api/lobby/invites/{invite_token}/accept", &format!("/api/lobby/invites/{}/accept", link_token),
+        None, StatusCode::UNAUTHORIZED,
+    ).await;
+    checker.check_with_headers(
+        Method::POST, "/api/lobby/invites/{invite_token}/accept", &format!("/api/lobby/invites/{}/accept", link_token),
+        &stranger_header, None, StatusCode::OK,
+    ).await;
+    checker.check_with_headers(
+        Method::POST, "/api/lobby/invites/{invite_token}/accept", &format!("/api/lobby/invites/{}/accept", link_token),
+        &stranger_header, None, StatusCode::NOT_FOUND,
+    ).await;
+
+    // デバッグAPI
+    #[cfg(feature = "debug-api")]
+    checker.check(
+        Method::POST, "/api/debug/simulate-game", "/api/debug/simulate-game",
+        Some(json!({"black_difficulty": "Easy", "white_difficulty": "Easy", "seed": 1})), StatusCode::OK,
+    ).await;
+
+    // 旧API
+    let legacy = checker.check(
+        Method::POST, "/api/games", "/api/games",
+        Some(json!({
+            "player1_type": {"Human": {"name": "player"}},
+            "player2_type": {"AI": {"difficulty": "Beginner"}}
+        })),
+        StatusCode::OK,
+    ).await;
+    let legacy_id = legacy["id"].as_str().unwrap().to_string();
+    let legacy_move = legacy["valid_moves"][0].clone();
+
+    checker.check(
+        Method::GET, "/api/games/{id}", &format!("/api/games/{}", legacy_id),
+        None, StatusCode::OK,
+    ).await;
+    checker.check(
+        Method::GET, "/api/games/{id}", &format!("/api/games/{}", missing_id),
+        None, StatusCode::NOT_FOUND,
+    ).await;
+    checker.check(
+        Method::PUT, "/api/games/{id}/move", &format!("/api/games/{}/move", legacy_id),
+        Some(json!({"row": 0, "col": 0})), StatusCode::BAD_REQUEST,
+    ).await;
+    checker.check(
+        Method::PUT, "/api/games/{id}/move", &format!("/api/games/{}/move", legacy_id),
+        Some(json!({"row": legacy_move[0], "col": legacy_move[1]})), StatusCode::OK,
+    ).await;
+    checker.check(
+        Method::DELETE, "/api/games/{id}", &format!("/api/games/{}", legacy_id),
+        None, StatusCode::NO_CONTENT,
+    ).await;
+    let imported = checker.check(
+        Method::POST, "/api/games/import", "/api/games/import",
+        Some(json!({"transcript": "f5 d6 c3"})), StatusCode::OK,
+    ).await;
+    assert_eq!(imported["transcript"], "F5D6C3");
+    assert_eq!(imported["game_state"]["move_count"], 3);
+    checker.check(
+        Method::POST, "/api/games/import", "/api/games/import",
+        Some(json!({"transcript": "F5F5"})), StatusCode::BAD_REQUEST,
+    ).await;
+
+    let evaluated = checker.check(
+        Method::POST, "/api/ai-battle", "/api/ai-battle",
+        Some(json!({"difficulty": "Easy", "eval_bar": true})), StatusCode::CREATED,
+    ).await;
+    assert!(evaluated["evaluation"]["score"].is_number());
+    let teaching = checker.check(
+        Method::POST, "/api/ai-battle", "/api/ai-battle",
+        Some(json!({"difficulty": "Easy", "teaching": true})), StatusCode::CREATED,
+    ).await;
+    let taught = checker.check(
+        Method::POST, "/api/ai-battle/{game_id}/move", &format!("/api/ai-battle/{}/move", teaching["game_id"].as_str().unwrap()),
+        Some(json!({"row": teaching["valid_moves"][0]["row"], "col": teaching["valid_moves"][0]["col"]})), StatusCode::OK,
+    ).await;
+    assert_eq!(taught["explanation"]["position"], taught["ai_move"]);
+    let teaching_uri = format!("/api/ai-battle/{}", teaching["game_id"].as_str().unwrap());
+    let delta = checker.check(
+        Method::GET, "/api/ai-battle/{game_id}", &format!("{}?since={}", teaching_uri, teaching["version"]),
+        None, StatusCode::OK,
+    ).await;
+    assert_eq!(delta["moves"].as_array().unwrap().len(), 2);
+    assert!(delta.get("board").is_none());
+    let unchanged = checker.check(
+        Method::GET, "/api/ai-battle/{game_id}", &format!("{}?since={}", teaching_uri, delta["version"]),
+        None, StatusCode::OK,
+    ).await;
+    assert_eq!(unchanged["changes"], json!([]));
+    let waited = checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/wait", &format!("{}/wait?timeout=1&version=0", teaching_uri),
+        None, StatusCode::OK,
+    ).await;
+    assert_eq!(waited["changed"], true);
+    checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/wait", &format!("{}/wait?timeout=0", teaching_uri),
+        None, StatusCode::BAD_REQUEST,
+    ).await;
+    checker.check(
+        Method::GET, "/api/ai-battle/{game_id}/wait", &format!("/api/ai-battle/{}/wait?timeout=1", Uuid::new_v4()),
+        None, StatusCode::NOT_FOUND,
+    ).await;
+    let adaptive = checker.check(
+        Method::POST, "/api/ai-battle", "/api/ai-battle",
+        Some(json!({"difficulty": "Medium", "adaptive": true})), StatusCode::CREATED,
+    ).await;
+    assert_eq!(adaptive["adaptive"]["level"], 5);
+    checker.check(
+        Method::POST, "/api/ai-battle", "/api/ai-battle",
+        Some(json!({"difficulty": "Medium", "adaptive": true, "ai_config": {"depth": 2}})), StatusCode::BAD_REQUEST,
+    ).await;
+    let packed_uri = format!("/api/ai-battle/{}", adaptive["game_id"].as_str().unwrap());
+    let packed = checker.check_with_headers(
+        Method::GET, "/api/ai-battle/{game_id}", &packed_uri,
+        &[("Accept", "application/msgpack")], None, StatusCode::OK,
+    ).await;
+    assert_eq!(packed["board"], adaptive["board"]);
+    let packed_move = checker.check_with_headers(
+        Method::POST, "/api/ai-battle/{game_id}/move", &format!("{}/move", packed_uri),
+        &[("Accept", "application/cbor")],
+        Some(json!({"row": adaptive["valid_moves"][0]["row"], "col": adaptive["valid_moves"][0]["col"]})), StatusCode::OK,
+    ).await;
+    assert_eq!(packed_move["player_move"], adaptive["valid_moves"][0]);
+    checker.check_with_headers(
+        Method::GET, "/api/ai-battle/{game_id}", &format!("{}?since={}", packed_uri, adaptive["version"]),
+        &[("Accept", "application/cbor")], None, StatusCode::OK,
+    ).await;
+    checker.check_with_headers(
+        Method::POST, "/api/ai-battle/{game_id}/pass", &format!("{}/pass", packed_uri),
+        &[("Accept", "application/msgpack")], None, StatusCode::BAD_REQUEST,
+    ).await;
+
+    checker.assert_all_operations_exercised();
+}
@@ -90,7 +90,7 @@ proptest! {
                 let current_state = current_state.unwrap();
                 
                 // ゲーム終了していたら終了
-                if let crate::api::ai_battle::dto::GameStatus::Finished { .. } = current_state.status {
+                if let Reversi::api::ai_battle::dto::GameStatus::Finished { .. } = current_state.status {
                     break;
                 }
                 
@@ -117,7 +117,7 @@ proptest! {
                         prop_assert!(game_state.move_count >= 0);
                         
                         // 不変条件4: 有効手は現在のプレイヤーで計算されている
-                        if let crate::api::ai_battle::dto::GameStatus::InProgress = game_state.status {
+                        if let Reversi::api::ai_battle::dto::GameStatus::InProgress = game_state.status {
                             // ゲーム続行中は有効手が存在するか、パスである
                             prop_assert!(game_state.valid_moves.is_empty() || !game_state.valid_moves.is_empty());
                         }
@@ -136,7 +136,8 @@ proptest! {
             
             // 少なくとも1回は有効な着手があることを期待（大抵の場合）
             prop_assume!(valid_move_count > 0 || invalid_move_count > 0);
-        });
+            Ok::<(), TestCaseError>(())
+        })?;
     }
     
     /// プロパティ: セッション管理の一貫性
@@ -193,7 +194,8 @@ proptest! {
             for session_id in session_ids {
                 prop_assert!(sessions.iter().any(|s| s.id == session_id));
             }
-        });
+            Ok::<(), TestCaseError>(())
+        })?;
     }
     
     /// プロパティ: AI戦略の一貫性
@@ -243,7 +245,8 @@ proptest! {
                     }
                 }
             }
-        });
+            Ok::<(), TestCaseError>(())
+        })?;
     }
     
     /// プロパティ: 着手履歴の一貫性
@@ -298,7 +301,8 @@ proptest! {
                     prop_assert!(matches!(move_record.player, Player::Black | Player::White));
                 }
             }
-        });
+            Ok::<(), TestCaseError>(())
+        })?;
     }
     
     /// プロパティ: エラー処理の堅牢性
@@ -336,7 +340,8 @@ proptest! {
             
             // 何らかの結果（成功かエラー）が得られている
             prop_assert!(error_count + success_count > 0);
-        });
+            Ok::<(), TestCaseError>(())
+        })?;
     }
     
     /// プロパティ: 並行アクセスの安全性
@@ -408,7 +413,8 @@ proptest! {
             let final_stats = service.get_service_stats();
             
             prop_assert_eq!(final_sessions.len(), final_stats.total_sessions);
-        });
+            Ok::<(), TestCaseError>(())
+        })?;
     }
 }
 
@@ -416,6 +422,7 @@ proptest! {
 #[cfg(test)]
 mod runtime_tests {
     use super::*;
+    use proptest::strategy::ValueTree;
     
     #[tokio::test]
     async fn test_property_tests_can_run() {